@@ -44,6 +44,7 @@ export default defineConfig({
           text: 'API 文档',
           items: [
             { text: 'API 总览', link: '/api/overview' },
+            { text: '错误码', link: '/api/errors' },
             { text: '认证 API', link: '/api/auth' },
             { text: '学习 API', link: '/api/learning' },
             { text: '单词管理 API', link: '/api/words' },
@@ -2,9 +2,11 @@ import { api } from './client';
 import type {
   AdminAuthResponse, AdminStats,
   AdminUsersPage, AdminUsersQuery,
-  EngagementAnalytics, LearningAnalytics,
+  EngagementAnalytics, LearningAnalytics, LlmUsageReport, RetentionReport, OnboardingFunnel,
+  WordDifficultyReport,
   SystemHealth, DatabaseInfo, SystemSettings,
   UpdateCheck,
+  BroadcastRequest, BroadcastResult, ScheduledBroadcast, BroadcastStats,
 } from '@/types/admin';
 import type { AmasConfig } from '@/types/amas';
 import type { BrowseItem, WordbookPreview, ImportResult, UpdateInfo, SyncResult } from '@/types/wordbookCenter';
@@ -31,6 +33,11 @@ export const adminApi = {
   // Analytics
   getEngagement: () => api.get<EngagementAnalytics>('/api/admin/analytics/engagement', undefined, { useAdminToken: true }),
   getLearningAnalytics: () => api.get<LearningAnalytics>('/api/admin/analytics/learning', undefined, { useAdminToken: true }),
+  getLlmUsage: (days?: number) => api.get<LlmUsageReport>('/api/admin/analytics/llm-usage', { days }, { useAdminToken: true }),
+  getRetention: (weeks?: number) => api.get<RetentionReport>('/api/admin/analytics/retention', { weeks }, { useAdminToken: true }),
+  getFunnel: (params?: { from?: string; to?: string }) => api.get<OnboardingFunnel>('/api/admin/analytics/funnel', params, { useAdminToken: true }),
+  getWordDifficulty: (params?: { limit?: number; minSamples?: number }) =>
+    api.get<WordDifficultyReport>('/api/admin/analytics/word-difficulty', params, { useAdminToken: true }),
 
   // Monitoring
   getHealth: () => api.get<SystemHealth>('/api/admin/monitoring/health', undefined, { useAdminToken: true }),
@@ -38,7 +45,11 @@ export const adminApi = {
   checkUpdate: () => api.get<UpdateCheck>('/api/admin/monitoring/check-update', undefined, { useAdminToken: true }),
 
   // Broadcast & Settings
-  broadcast: (data: { title: string; message: string }) => api.post<{ sent: number }>('/api/admin/broadcast', data, { useAdminToken: true }),
+  broadcast: (data: BroadcastRequest) => api.post<BroadcastResult>('/api/admin/broadcast', data, { useAdminToken: true }),
+  scheduleBroadcast: (data: BroadcastRequest & { scheduledAt: string }) => api.post<ScheduledBroadcast>('/api/admin/broadcast', data, { useAdminToken: true }),
+  getScheduledBroadcasts: (limit?: number) => api.get<ScheduledBroadcast[]>('/api/admin/broadcast/scheduled', { limit }, { useAdminToken: true }),
+  cancelScheduledBroadcast: (id: string) => api.delete<ScheduledBroadcast>(`/api/admin/broadcast/scheduled/${id}`, { useAdminToken: true }),
+  getBroadcastStats: (id: string) => api.get<BroadcastStats>(`/api/admin/broadcast/${id}/stats`, undefined, { useAdminToken: true }),
   getSettings: () => api.get<SystemSettings>('/api/admin/settings', undefined, { useAdminToken: true }),
   updateSettings: (data: Partial<SystemSettings>) => api.put<SystemSettings>('/api/admin/settings', data, { useAdminToken: true }),
   reloadAmas: (data: AmasConfig) => api.post<AmasConfig>('/api/admin/settings/reload-amas', data, { useAdminToken: true }),
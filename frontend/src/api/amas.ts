@@ -13,6 +13,8 @@ import type {
   AmasConfig,
   AmasMetrics,
   MonitoringEvent,
+  LlmAdvisory,
+  ApplyAdvisoryResult,
 } from '@/types/amas';
 import { AMAS_MONITORING_DEFAULT_LIMIT } from '@/lib/constants';
 
@@ -88,6 +90,19 @@ export const amasApi = {
     return api.get<MonitoringEvent[]>('/api/admin/amas/monitoring', { limit }, { useAdminToken: true });
   },
 
+  getAdvisories(limit?: number) {
+    return api.get<LlmAdvisory[]>('/api/admin/amas/advisories', { limit }, { useAdminToken: true });
+  },
+
+  /** 应用一条顾问建议；须由管理员显式确认后传入 `confirm: true` */
+  applyAdvisorySuggestion(advisoryId: string, index: number) {
+    return api.post<ApplyAdvisoryResult>(
+      `/api/admin/amas/advisories/${advisoryId}/suggestions/${index}/apply`,
+      { confirm: true },
+      { useAdminToken: true },
+    );
+  },
+
   subscribeStateEvents(onState: (event: AmasStateStreamEvent) => void) {
     return connectAmasStateStream(onState);
   },
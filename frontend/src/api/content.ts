@@ -1,18 +1,29 @@
 import { api } from './client';
-import type { Etymology, Morpheme, WordContexts, SemanticSearchResult, ConfusionPairsResult } from '@/types/content';
+import type { Etymology, Morpheme, WordContexts, ContentKind, ContentInvalidation, SemanticSearchResult, ConfusionPairsResult, RelatedWordsResult, ServerLimits } from '@/types/content';
 import { SEMANTIC_SEARCH_DEFAULT_LIMIT } from '@/lib/constants';
 
 export const contentApi = {
+  getLimits: () =>
+    api.get<ServerLimits>('/api/content/limits'),
   getEtymology: (wordId: string) =>
     api.get<Etymology>(`/api/content/etymology/${wordId}`),
   semanticSearch: (query: string, limit = SEMANTIC_SEARCH_DEFAULT_LIMIT) =>
     api.get<SemanticSearchResult>('/api/content/semantic/search', { query, limit }),
   getWordContexts: (wordId: string) =>
     api.get<WordContexts>(`/api/content/word-contexts/${wordId}`),
+  regenerateWordContexts: (wordId: string) =>
+    api.post<WordContexts>(`/api/content/word-contexts/${wordId}/regenerate`),
+  invalidateCache: (
+    scope: { wordId: string } | { tag: string } | { all: true },
+    kinds?: ContentKind[],
+  ) =>
+    api.post<ContentInvalidation>('/api/content/cache/invalidate', { ...scope, kinds }),
   getMorphemes: (wordId: string) =>
     api.get<{ wordId: string; morphemes: Morpheme[] }>(`/api/content/morphemes/${wordId}`),
   setMorphemes: (wordId: string, morphemes: Morpheme[]) =>
     api.post<{ wordId: string; morphemes: Morpheme[] }>(`/api/content/morphemes/${wordId}`, { morphemes }),
   getConfusionPairs: (wordId: string) =>
     api.get<ConfusionPairsResult>(`/api/content/confusion-pairs/${wordId}`),
+  getRelatedWords: (wordId: string, limit?: number) =>
+    api.get<RelatedWordsResult>(`/api/content/related/${wordId}`, { limit }),
 };
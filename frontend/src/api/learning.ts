@@ -9,6 +9,9 @@ import type {
   SyncProgressRequest,
   LearningSession,
   CompleteSessionRequest,
+  PlacementAnswer,
+  PlacementResponse,
+  PlacementStatus,
 } from '@/types/learning';
 import type { AmasStrategy } from '@/types/amas';
 
@@ -43,4 +46,12 @@ export const learningApi = {
     if (sanitized.contextShifts != null) sanitized.contextShifts = Math.round(sanitized.contextShifts);
     return api.post<LearningSession>('/api/learning/sync-progress', sanitized, { keepalive: options?.keepalive ?? false });
   },
+
+  getPlacement() {
+    return api.get<PlacementStatus>('/api/learning/placement');
+  },
+
+  submitPlacement(answers: PlacementAnswer[]) {
+    return api.post<PlacementResponse>('/api/learning/placement', { answers });
+  },
 };
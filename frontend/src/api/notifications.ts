@@ -1,10 +1,11 @@
 import { api } from './client';
 import type { Notification, Badge } from '@/types/notification';
 import type { UserPreferences } from '@/types/user';
+import type { PaginatedResponse } from '@/types/api';
 
 export const notificationsApi = {
-  list: (params?: { limit?: number; unreadOnly?: boolean }) =>
-    api.get<Notification[]>('/api/notifications', params),
+  list: (params?: { page?: number; perPage?: number; unreadOnly?: boolean }) =>
+    api.get<PaginatedResponse<Notification>>('/api/notifications', params).then((res) => res.data),
   getUnreadCount: () =>
     api.get<{ unreadCount: number }>('/api/notifications/unread-count'),
   markRead: (id: string) =>
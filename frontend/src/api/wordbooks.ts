@@ -3,13 +3,20 @@ import type { Wordbook, CreateWordbookRequest } from '@/types/wordbook';
 import type { Word } from '@/types/word';
 import type { PaginatedResponse } from '@/types/api';
 
+/** 服务端单页上限 */
+const MAX_PAGE_SIZE = 100;
+
 export const wordbooksApi = {
   getSystem() {
-    return api.get<Wordbook[]>('/api/wordbooks/system');
+    return api
+      .get<PaginatedResponse<Wordbook>>('/api/wordbooks/system', { perPage: MAX_PAGE_SIZE })
+      .then((res) => res.data);
   },
 
   getUser() {
-    return api.get<Wordbook[]>('/api/wordbooks/user');
+    return api
+      .get<PaginatedResponse<Wordbook>>('/api/wordbooks/user', { perPage: MAX_PAGE_SIZE })
+      .then((res) => res.data);
   },
 
   create(data: CreateWordbookRequest) {
@@ -21,7 +28,7 @@ export const wordbooksApi = {
   },
 
   addWords(id: string, wordIds: string[]) {
-    return api.post<{ added: number }>(`/api/wordbooks/${id}/words`, { wordIds });
+    return api.post<{ added: number; conflicts: string[] }>(`/api/wordbooks/${id}/words`, { wordIds });
   },
 
   removeWord(bookId: string, wordId: string) {
@@ -1,9 +1,9 @@
 import { api } from './client';
-import type { Word, CreateWordRequest, BatchCreateResponse, ImportUrlResponse } from '@/types/word';
+import type { Word, CreateWordRequest, BatchCreateResponse, BulkTagsResponse, DuplicateGroup, MergeSummary, ImportUrlResponse } from '@/types/word';
 import type { PaginatedResponse } from '@/types/api';
 
 export const wordsApi = {
-  list: (params?: { page?: number; perPage?: number; search?: string }) =>
+  list: (params?: { page?: number; perPage?: number; search?: string; tag?: string; tagMatch?: 'all' | 'any'; pos?: string }) =>
     api.get<PaginatedResponse<Word>>('/api/words', params),
   get: (id: string) => api.get<Word>(`/api/words/${id}`),
   create: (data: CreateWordRequest) => api.post<Word>('/api/words', data),
@@ -12,5 +12,11 @@ export const wordsApi = {
   batchCreate: (words: CreateWordRequest[]) => api.post<BatchCreateResponse>('/api/words/batch', { words }),
   batchGet: (ids: string[]) => api.post<Word[]>('/api/words/batch-get', { ids }),
   count: () => api.get<{ total: number }>('/api/words/count'),
+  tags: () => api.get<Array<{ tag: string; count: number }>>('/api/words/tags'),
+  bulkTags: (data: { wordIds: string[]; addTags?: string[]; removeTags?: string[] }) =>
+    api.post<BulkTagsResponse>('/api/words/tags/bulk', data),
+  duplicates: () => api.get<DuplicateGroup[]>('/api/words/duplicates'),
+  mergeDuplicates: (data: { canonicalId: string; duplicateIds: string[]; dryRun?: boolean }) =>
+    api.post<MergeSummary>('/api/words/duplicates/merge', data),
   importUrl: (url: string) => api.post<ImportUrlResponse>('/api/words/import-url', { url }),
 };
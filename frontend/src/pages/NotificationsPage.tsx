@@ -24,7 +24,7 @@ export default function NotificationsPage() {
     setLoading(true);
     try {
       const [res, unread] = await Promise.all([
-        notificationsApi.list({ limit: 50 }),
+        notificationsApi.list({ perPage: 50 }),
         notificationsApi.getUnreadCount().catch(() => null),
       ]);
       setItems(res ?? []);
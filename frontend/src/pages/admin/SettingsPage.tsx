@@ -3,19 +3,22 @@ import { Card } from '@/components/ui/Card';
 import { Input } from '@/components/ui/Input';
 import { Button } from '@/components/ui/Button';
 import { Switch } from '@/components/ui/Switch';
+import { Select } from '@/components/ui/Select';
 import { Spinner } from '@/components/ui/Spinner';
 import { uiStore } from '@/stores/ui';
 import { adminApi } from '@/api/admin';
 import { SETTINGS_MAX_USERS, SETTINGS_MAX_DAILY_WORDS } from '@/lib/constants';
+import type { SystemSettings } from '@/types/admin';
+
+const STUDY_MODE_OPTIONS = [
+  { value: 'normal', label: '常规' },
+  { value: 'intensive', label: '强化' },
+  { value: 'review', label: '复习' },
+  { value: 'casual', label: '轻松' },
+];
 
 export default function SettingsPage() {
-  const [settings, setSettings] = createSignal<{
-    maxUsers: number;
-    registrationEnabled: boolean;
-    maintenanceMode: boolean;
-    defaultDailyWords: number;
-    wordbookCenterUrl?: string;
-  } | null>(null);
+  const [settings, setSettings] = createSignal<SystemSettings | null>(null);
   const [loading, setLoading] = createSignal(true);
   const [saving, setSaving] = createSignal(false);
   const [broadcastTitle, setBroadcastTitle] = createSignal('');
@@ -47,6 +50,10 @@ export default function SettingsPage() {
       uiStore.toast.warning(`默认每日单词数应在 1 ~ ${SETTINGS_MAX_DAILY_WORDS} 之间`);
       return;
     }
+    if (s.defaultMasteryTarget < 1 || s.defaultMasteryTarget > 100) {
+      uiStore.toast.warning('默认每日掌握目标应在 1 ~ 100 之间');
+      return;
+    }
     setSaving(true);
     try {
       await adminApi.updateSettings(s);
@@ -155,6 +162,20 @@ export default function SettingsPage() {
                   value={String(s().defaultDailyWords)}
                   onInput={(e) => updateField('defaultDailyWords', parseInt(e.currentTarget.value) || 20)}
                 />
+                <Input
+                  label="默认每日掌握目标"
+                  type="number"
+                  min={1}
+                  max={100}
+                  value={String(s().defaultMasteryTarget)}
+                  onInput={(e) => updateField('defaultMasteryTarget', parseInt(e.currentTarget.value) || 10)}
+                />
+                <Select
+                  label="默认学习模式"
+                  options={STUDY_MODE_OPTIONS}
+                  value={s().defaultStudyMode}
+                  onChange={(e) => updateField('defaultStudyMode', e.currentTarget.value)}
+                />
                 <Switch
                   checked={s().registrationEnabled}
                   onChange={(v) => updateField('registrationEnabled', v)}
@@ -1,4 +1,5 @@
 import type { PaginatedResponse } from './api';
+import type { StudyMode } from './studyConfig';
 
 export interface AdminUser {
   id: string;
@@ -44,6 +45,79 @@ export interface LearningAnalytics {
   overallAccuracy: number;
 }
 
+export interface LlmUsageCounts {
+  calls: number;
+  promptTokens: number;
+  completionTokens: number;
+  estimatedCost: number;
+}
+
+export interface LlmUsageDaily extends LlmUsageCounts {
+  date: string;
+  byFeature: Record<string, LlmUsageCounts>;
+  updatedAt: string;
+}
+
+export interface LlmUsageReport {
+  budget: {
+    dailyTokenLimit: number | null;
+    usedToday: number;
+    remainingToday: number | null;
+    exhausted: boolean;
+  };
+  days: LlmUsageDaily[];
+}
+
+export interface RetentionCohort {
+  /** 注册周的周一（UTC），YYYY-MM-DD */
+  weekStart: string;
+  size: number;
+  /** 第 k 项为注册后第 k 周提交过学习记录的人数 */
+  active: number[];
+  retention: number[];
+}
+
+export interface RetentionReport {
+  weeks: number;
+  generatedAt: string;
+  cohorts: RetentionCohort[];
+}
+
+export type FunnelStep = 'registered' | 'firstWord' | 'firstSession' | 'streak';
+
+export interface FunnelStage {
+  step: FunnelStep;
+  users: number;
+  conversionRate: number;
+  overallRate: number;
+}
+
+export interface OnboardingFunnel {
+  from: string;
+  to: string;
+  steps: FunnelStage[];
+}
+
+export interface WordDifficulty {
+  wordId: string;
+  text: string;
+  labeledDifficulty: number;
+  attempts: number;
+  correct: number;
+  errorRate: number;
+  confidenceLow: number;
+  confidenceHigh: number;
+  difficultyGap: number;
+  eloRating: number;
+  eloGames: number;
+}
+
+export interface WordDifficultyReport {
+  generatedAt: string;
+  sampledRecords: number;
+  words: WordDifficulty[];
+}
+
 export interface SystemHealth {
   status: 'healthy' | 'degraded' | 'down';
   dbSizeBytes: number;
@@ -70,5 +144,57 @@ export interface SystemSettings {
   registrationEnabled: boolean;
   maintenanceMode: boolean;
   defaultDailyWords: number;
+  /** 新用户注册时的每日掌握目标 */
+  defaultMasteryTarget: number;
+  /** 新用户注册时的学习模式 */
+  defaultStudyMode: StudyMode;
   wordbookCenterUrl?: string;
 }
+
+export type BroadcastTarget =
+  | { type: 'all' }
+  | { type: 'excludeBanned' }
+  | { type: 'activeWithin'; days: number }
+  | { type: 'wordbookLearners'; wordbookId: string };
+
+export interface BroadcastRequest {
+  title: string;
+  message: string;
+  target?: BroadcastTarget;
+  /** ISO 时间，缺省立即发送 */
+  scheduledAt?: string;
+}
+
+export interface BroadcastResult {
+  sent: number;
+  skippedDuplicates: number;
+  recipients: number;
+  broadcastId: string;
+}
+
+export interface ScheduledBroadcast {
+  id: string;
+  title: string;
+  message: string;
+  target: BroadcastTarget;
+  scheduledAt: string;
+  status: 'pending' | 'sent' | 'cancelled';
+  createdBy: string;
+  createdAt: string;
+  recipients: number;
+  sentAt: string | null;
+  sent: number;
+  skippedDuplicates: number;
+}
+
+export interface BroadcastStats {
+  broadcastId: string;
+  delivered: number;
+  read: number;
+  unread: number;
+  /** 用户已删除、无法判断是否读过的通知数 */
+  dismissed: number;
+  readRate: number;
+  firstDeliveredAt: string | null;
+  lastDeliveredAt: string | null;
+}
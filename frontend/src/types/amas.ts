@@ -215,3 +215,36 @@ export interface AmasStateStreamEvent {
   sessionEventCount: number;
   totalEventCount: number;
 }
+
+export interface AdvisorySuggestion {
+  /** AMAS 配置字段路径，如 `ensemble.warmupSamples` */
+  path: string;
+  current: unknown;
+  proposed: unknown;
+  rationale: string;
+  appliedAt: string | null;
+  appliedBy: string | null;
+}
+
+export interface AdvisoryAnomaly {
+  metric: string;
+  severity: 'info' | 'warning' | 'critical';
+  detail: string;
+  date: string | null;
+}
+
+export interface LlmAdvisory {
+  id: string;
+  model: string;
+  summary: string;
+  suggestions: AdvisorySuggestion[];
+  anomalies: AdvisoryAnomaly[];
+  createdAt: string;
+}
+
+export interface ApplyAdvisoryResult {
+  path: string;
+  previous: unknown;
+  applied: unknown;
+  advisory: LlmAdvisory;
+}
@@ -19,4 +19,5 @@ export interface PaginatedResponse<T> {
   page: number;
   perPage: number;
   totalPages: number;
+  hasNext: boolean;
 }
@@ -24,8 +24,17 @@ export interface WordContexts {
   contexts: Array<{
     id: string;
     sentence: string;
-    source: string;
+    source: 'word_examples' | 'generated';
+    model?: string;
   }>;
+  generated: { model: string; promptVersion: number; generatedAt: string } | null;
+}
+
+export type ContentKind = 'etymology' | 'examples' | 'morphemes';
+
+export interface ContentInvalidation {
+  invalidated: Record<ContentKind, number>;
+  total: number;
 }
 
 // SemanticSearchItem 与 Word 字段完全相同，直接复用
@@ -49,3 +58,28 @@ export interface ConfusionPairsResult {
   wordId: string;
   confusionPairs: ConfusionPair[];
 }
+
+export interface WordRelation {
+  type: 'semantic' | 'morpheme' | 'confusion';
+  score: number;
+  sharedMorphemes?: string[];
+}
+
+export interface RelatedWord extends Word {
+  score: number;
+  relations: WordRelation[];
+}
+
+export interface RelatedWordsResult {
+  wordId: string;
+  word: string;
+  total: number;
+  related: RelatedWord[];
+  degraded: boolean;
+}
+
+export interface ServerLimits {
+  pagination: { defaultPageSize: number; recordsDefaultPageSize: number; maxPageSize: number };
+  batch: { maxBatchSize: number; maxExcludeWordIds: number; maxImportWords: number };
+  body: { jsonBytes: number; bulkBytes: number; uploadBytes: number };
+}
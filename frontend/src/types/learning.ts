@@ -1,4 +1,5 @@
 import type { Word } from './word';
+import type { AmasCognitiveProfile } from './amas';
 
 export interface LearningSession {
   id: string;
@@ -88,3 +89,31 @@ export interface CompleteSessionRequest {
   errorProneWordIds: string[];
   avgResponseTimeMs: number;
 }
+
+export interface PlacementAnswer {
+  wordId: string;
+  isCorrect: boolean;
+  responseTimeMs: number;
+}
+
+export interface PlacementResult {
+  elo: number;
+  answered: number;
+  correct: number;
+  cognitiveProfile: AmasCognitiveProfile;
+  completedAt: string;
+}
+
+export interface PlacementStatus {
+  available: boolean;
+  result: PlacementResult | null;
+}
+
+export interface PlacementResponse {
+  completed: boolean;
+  answered: number;
+  total: number;
+  estimatedElo: number;
+  nextWord: Word | null;
+  result: PlacementResult | null;
+}
@@ -6,6 +6,7 @@ export interface Notification {
   type: 'system' | 'achievement' | 'reminder' | 'info' | 'broadcast';
   read: boolean;
   createdAt: string;
+  broadcastId?: string | null;
 }
 
 export interface Badge {
@@ -31,6 +31,35 @@ export interface BatchCreateResponse {
   items: Word[];
 }
 
+export interface BulkTagsResponse {
+  updated: number;
+  failed: number;
+  results: Array<{ wordId: string; success: boolean; tags?: string[]; error?: string }>;
+}
+
+export interface DuplicateGroup {
+  normalizedText: string;
+  canonicalId: string;
+  words: Array<{
+    id: string;
+    text: string;
+    meaning: string;
+    createdAt: string;
+    references: { records: number; learningStates: number; wordbookEntries: number };
+  }>;
+}
+
+export interface MergeSummary {
+  canonicalId: string;
+  mergedIds: string[];
+  recordsMoved: number;
+  learningStatesMoved: number;
+  learningStatesDropped: number;
+  wordbookEntriesMoved: number;
+  wordbookEntriesDropped: number;
+  applied: boolean;
+}
+
 export interface ImportUrlRequest {
   url: string;
 }
@@ -40,6 +40,7 @@ export interface WordbookPreview {
     page: number;
     perPage: number;
     totalPages: number;
+    hasNext: boolean;
   };
 }
 
@@ -25,6 +25,20 @@ vi.mock('@/api/auth', () => ({ authApi: { refresh: vi.fn() } }));
 import { contentApi } from '@/api/content';
 
 describe('contentApi', () => {
+  it('getLimits returns server-enforced caps', async () => {
+    const limits = {
+      pagination: { defaultPageSize: 20, recordsDefaultPageSize: 50, maxPageSize: 100 },
+      batch: { maxBatchSize: 500, maxExcludeWordIds: 1000, maxImportWords: 5000 },
+      body: { jsonBytes: 65536, bulkBytes: 2097152, uploadBytes: 8388608 },
+    };
+    server.use(
+      http.get(`${BASE}/api/content/limits`, () =>
+        HttpResponse.json({ success: true, data: limits })),
+    );
+    const result = await contentApi.getLimits();
+    expect(result).toEqual(limits);
+  });
+
   it('getEtymology returns etymology for a word', async () => {
     const etymology = { wordId: 'w1', origin: 'Latin', history: 'From "testare"' };
     server.use(
@@ -31,7 +31,10 @@ describe('notificationsApi', () => {
     ];
     server.use(
       http.get(`${BASE}/api/notifications`, () =>
-        HttpResponse.json({ success: true, data: notifications })),
+        HttpResponse.json({
+          success: true,
+          data: { data: notifications, total: 1, page: 1, perPage: 20, totalPages: 1, hasNext: false },
+        })),
     );
     const result = await notificationsApi.list();
     expect(result).toEqual(notifications);
@@ -41,12 +44,15 @@ describe('notificationsApi', () => {
     server.use(
       http.get(`${BASE}/api/notifications`, ({ request }) => {
         const url = new URL(request.url);
-        expect(url.searchParams.get('limit')).toBe('10');
+        expect(url.searchParams.get('perPage')).toBe('10');
         expect(url.searchParams.get('unreadOnly')).toBe('true');
-        return HttpResponse.json({ success: true, data: [] });
+        return HttpResponse.json({
+          success: true,
+          data: { data: [], total: 0, page: 1, perPage: 10, totalPages: 0, hasNext: false },
+        });
       }),
     );
-    const result = await notificationsApi.list({ limit: 10, unreadOnly: true });
+    const result = await notificationsApi.list({ perPage: 10, unreadOnly: true });
     expect(result).toEqual([]);
   });
 
@@ -28,8 +28,13 @@ describe('wordbooksApi', () => {
   it('getSystem returns system wordbooks', async () => {
     const books = [{ id: 'b1', name: 'CET-4', type: 'system', wordCount: 4000 }];
     server.use(
-      http.get(`${BASE}/api/wordbooks/system`, () =>
-        HttpResponse.json({ success: true, data: books })),
+      http.get(`${BASE}/api/wordbooks/system`, ({ request }) => {
+        expect(new URL(request.url).searchParams.get('perPage')).toBe('100');
+        return HttpResponse.json({
+          success: true,
+          data: { data: books, total: 1, page: 1, perPage: 100, totalPages: 1, hasNext: false },
+        });
+      }),
     );
     const result = await wordbooksApi.getSystem();
     expect(result).toEqual(books);
@@ -38,8 +43,13 @@ describe('wordbooksApi', () => {
   it('getUser returns user wordbooks', async () => {
     const books = [{ id: 'b2', name: 'My Words', type: 'user', wordCount: 50 }];
     server.use(
-      http.get(`${BASE}/api/wordbooks/user`, () =>
-        HttpResponse.json({ success: true, data: books })),
+      http.get(`${BASE}/api/wordbooks/user`, ({ request }) => {
+        expect(new URL(request.url).searchParams.get('perPage')).toBe('100');
+        return HttpResponse.json({
+          success: true,
+          data: { data: books, total: 1, page: 1, perPage: 100, totalPages: 1, hasNext: false },
+        });
+      }),
     );
     const result = await wordbooksApi.getUser();
     expect(result).toEqual(books);
@@ -30,9 +30,70 @@ impl Default for FeatureFlags {
     }
 }
 
+/// 单个用户的算法开关覆盖，用于灰度与排查；为空的字段沿用全局 `FeatureFlags`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagOverrides {
+    #[serde(default)]
+    pub ige_enabled: Option<bool>,
+    #[serde(default)]
+    pub swd_enabled: Option<bool>,
+    #[serde(default)]
+    pub iad_enabled: Option<bool>,
+    #[serde(default)]
+    pub mtp_enabled: Option<bool>,
+}
+
+impl FeatureFlagOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, flags: &mut FeatureFlags) {
+        for (flag, value) in [
+            (&mut flags.ige_enabled, self.ige_enabled),
+            (&mut flags.swd_enabled, self.swd_enabled),
+            (&mut flags.iad_enabled, self.iad_enabled),
+            (&mut flags.mtp_enabled, self.mtp_enabled),
+        ] {
+            if let Some(value) = value {
+                *flag = value;
+            }
+        }
+    }
+}
+
+/// 多算法候选的组合策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnsembleStrategy {
+    /// 按信任度与置信度加权融合
+    #[default]
+    Weighted,
+    /// 直接采用置信度最高的候选
+    ArgmaxConfidence,
+    /// 以信任分作为各算法的学习缩放系数做线性组合
+    Stacked,
+}
+
+/// 预热期启发式加成随样本数衰减的形状，均在 `warmup_samples` 处降到 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarmupBoostDecay {
+    /// 预热期内保持满额，到阈值时直接归零
+    Step,
+    /// 线性递减
+    #[default]
+    Linear,
+    /// 前期下降快、后期平缓的指数递减
+    Exponential,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnsembleConfig {
+    #[serde(default)]
+    pub strategy: EnsembleStrategy,
     pub base_weight_heuristic: f64,
     pub base_weight_ige: f64,
     pub base_weight_swd: f64,
@@ -42,15 +103,30 @@ pub struct EnsembleConfig {
     pub min_weight: f64,
     #[serde(default = "default_warmup_heuristic_boost")]
     pub warmup_heuristic_boost: f64,
+    /// 新配置默认线性递减；已保存的旧配置缺少该字段时保持原来的阶跃行为
+    #[serde(default = "default_legacy_warmup_boost_decay")]
+    pub warmup_boost_decay: WarmupBoostDecay,
+    /// 信任分数向基准权重回归的半衰期（小时），按距上次更新的时间衰减；0 表示不衰减
+    #[serde(default = "default_trust_decay_half_life_hours")]
+    pub trust_decay_half_life_hours: f64,
 }
 
 fn default_warmup_heuristic_boost() -> f64 {
     0.20
 }
 
+fn default_legacy_warmup_boost_decay() -> WarmupBoostDecay {
+    WarmupBoostDecay::Step
+}
+
+fn default_trust_decay_half_life_hours() -> f64 {
+    168.0
+}
+
 impl Default for EnsembleConfig {
     fn default() -> Self {
         Self {
+            strategy: EnsembleStrategy::Weighted,
             base_weight_heuristic: 0.40,
             base_weight_ige: 0.30,
             base_weight_swd: 0.30,
@@ -59,10 +135,25 @@ impl Default for EnsembleConfig {
             blend_max: 0.50,
             min_weight: 0.15,
             warmup_heuristic_boost: 0.20,
+            warmup_boost_decay: WarmupBoostDecay::Linear,
+            trust_decay_half_life_hours: default_trust_decay_half_life_hours(),
         }
     }
 }
 
+/// 视觉疲劳与行为疲劳的混合方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FatigueBlendMode {
+    /// 按 visual_fatigue_weight 线性加权
+    #[default]
+    Linear,
+    /// 取两者较大值，强烈的视觉困倦信号不被低行为估计稀释
+    Max,
+    /// 按信号新鲜度加权：行为估计越久未更新，视觉信号权重越高
+    DecayWeighted,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelingConfig {
@@ -105,6 +196,20 @@ pub struct ModelingConfig {
     /// 趋势状态平滑系数（EMA alpha）
     #[serde(default = "default_trend_alpha")]
     pub trend_alpha: f64,
+    /// 趋势值单次事件允许的最大变化量（1.0 即不限制）
+    #[serde(default = "default_trend_max_delta")]
+    pub trend_max_delta: f64,
+    /// 单次视觉疲劳上报允许的最大疲劳变化量（1.0 即不限制）
+    #[serde(default = "default_visual_fatigue_max_delta")]
+    pub visual_fatigue_max_delta: f64,
+    /// 两次视觉疲劳上报被采纳的最小间隔（毫秒，0 即不限制）
+    #[serde(default)]
+    pub visual_fatigue_min_interval_ms: u64,
+    #[serde(default)]
+    pub visual_fatigue_blend_mode: FatigueBlendMode,
+    /// decay-weighted 模式下行为疲劳估计的新鲜度半衰期（秒）
+    #[serde(default = "default_visual_fatigue_decay_half_life_secs")]
+    pub visual_fatigue_decay_half_life_secs: f64,
 }
 
 fn default_response_speed_max_ms() -> f64 {
@@ -137,6 +242,15 @@ fn default_cognitive_profile_alpha() -> f64 {
 fn default_trend_alpha() -> f64 {
     0.05
 }
+fn default_trend_max_delta() -> f64 {
+    1.0
+}
+fn default_visual_fatigue_max_delta() -> f64 {
+    1.0
+}
+fn default_visual_fatigue_decay_half_life_secs() -> f64 {
+    300.0
+}
 
 impl Default for ModelingConfig {
     fn default() -> Self {
@@ -158,6 +272,11 @@ impl Default for ModelingConfig {
             engagement_focus_loss_penalty_max: 0.3,
             cognitive_profile_alpha: 0.1,
             trend_alpha: 0.05,
+            trend_max_delta: 1.0,
+            visual_fatigue_max_delta: 1.0,
+            visual_fatigue_min_interval_ms: 0,
+            visual_fatigue_blend_mode: FatigueBlendMode::Linear,
+            visual_fatigue_decay_half_life_secs: 300.0,
         }
     }
 }
@@ -210,6 +329,30 @@ impl Default for ConstraintConfig {
 pub struct MonitoringConfig {
     pub sample_rate: f64,
     pub metrics_flush_interval_secs: u64,
+    /// 算法状态反序列化失败时写入修复日志（保留原始数据）
+    #[serde(default = "default_record_state_repairs")]
+    pub record_state_repairs: bool,
+    /// 强制全量采样的用户，用于排查个别用户的问题
+    #[serde(default)]
+    pub full_sample_user_ids: Vec<String>,
+    /// 强制全量采样的哈希分桶区间，按 user_id 哈希分到 0-99 号桶
+    #[serde(default)]
+    pub full_sample_buckets: Option<SampleBucketRange>,
+}
+
+/// 左闭右开的分桶区间 `[start, end)`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SampleBucketRange {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl MonitoringConfig {
+    pub const SAMPLE_BUCKETS: u8 = 100;
+}
+
+fn default_record_state_repairs() -> bool {
+    true
 }
 
 impl Default for MonitoringConfig {
@@ -217,6 +360,9 @@ impl Default for MonitoringConfig {
         Self {
             sample_rate: 0.05,
             metrics_flush_interval_secs: 300,
+            record_state_repairs: true,
+            full_sample_user_ids: Vec::new(),
+            full_sample_buckets: None,
         }
     }
 }
@@ -324,6 +470,9 @@ pub struct FeatureConfig {
     pub trust_weight_blend: f64,
     #[serde(default = "default_incorrect_quality_scale")]
     pub incorrect_quality_scale: f64,
+    /// 时段画像批量落盘间隔（秒）；0 表示每次更新立即写入
+    #[serde(default)]
+    pub temporal_profile_flush_interval_secs: u64,
 }
 
 impl Default for FeatureConfig {
@@ -344,6 +493,7 @@ impl Default for FeatureConfig {
             trust_base_learning_rate: 0.05,
             trust_weight_blend: 0.5,
             incorrect_quality_scale: 0.1,
+            temporal_profile_flush_interval_secs: 0,
         }
     }
 }
@@ -499,9 +649,22 @@ impl Default for SwdConfig {
     }
 }
 
+/// 复习间隔调度算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SchedulerKind {
+    /// 多时间尺度记忆强度 + 半衰期（MDM）
+    #[default]
+    Composite,
+    /// 经典 SM-2：易度因子 × 上次间隔
+    Sm2,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MemoryModelConfig {
+    #[serde(default)]
+    pub scheduler: SchedulerKind,
     pub short_term_learning_rate: f64,
     pub medium_term_learning_rate: f64,
     pub long_term_learning_rate: f64,
@@ -544,6 +707,7 @@ fn default_mastery_window_size() -> u32 {
 impl Default for MemoryModelConfig {
     fn default() -> Self {
         Self {
+            scheduler: SchedulerKind::Composite,
             short_term_learning_rate: 0.50,
             medium_term_learning_rate: 0.20,
             long_term_learning_rate: 0.05,
@@ -658,6 +822,19 @@ pub struct InterventionConfig {
     pub fatigue_alert_threshold: f64,
     pub motivation_alert_threshold: f64,
     pub attention_alert_threshold: f64,
+    /// 疲劳超过该值时视为严重，除建议外还会发送通知
+    #[serde(default = "default_severe_fatigue_threshold")]
+    pub severe_fatigue_threshold: f64,
+    /// 两次干预之间的冷却时间（秒），期间仅严重度升级时再次触发
+    #[serde(default = "default_intervention_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_severe_fatigue_threshold() -> f64 {
+    0.85
+}
+fn default_intervention_cooldown_secs() -> u64 {
+    600
 }
 
 impl Default for InterventionConfig {
@@ -666,6 +843,8 @@ impl Default for InterventionConfig {
             fatigue_alert_threshold: 0.7,
             motivation_alert_threshold: -0.3,
             attention_alert_threshold: 0.3,
+            severe_fatigue_threshold: 0.85,
+            cooldown_secs: 600,
         }
     }
 }
@@ -722,6 +901,59 @@ impl Default for LearningStrategyConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyConfig {
+    /// 等待用户级锁的超时时间（毫秒），超时返回 429
+    #[serde(default = "default_user_lock_timeout_ms")]
+    pub user_lock_timeout_ms: u64,
+    /// 单个用户同时排队或处理中的事件上限，超出直接返回 429
+    #[serde(default = "default_max_in_flight_per_user")]
+    pub max_in_flight_per_user: u32,
+}
+
+fn default_user_lock_timeout_ms() -> u64 {
+    5000
+}
+fn default_max_in_flight_per_user() -> u32 {
+    8
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            user_lock_timeout_ms: 5000,
+            max_in_flight_per_user: 8,
+        }
+    }
+}
+
+/// 考前突击（cram）模式覆盖参数：以长期保持为代价换取短期覆盖面，
+/// 仅在用户设置的截止时间前生效
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CramModeConfig {
+    /// 新词比例下限，用于前置新词
+    pub min_new_ratio: f64,
+    /// 复习间隔缩放系数，(0,1] 表示缩短间隔
+    pub interval_scale: f64,
+    /// 疲劳或注意力不足时仍允许的新词比例上限（替代强制复习模式）
+    pub max_new_ratio_when_fatigued: f64,
+    /// 单次开启的最长天数
+    pub max_duration_days: i64,
+}
+
+impl Default for CramModeConfig {
+    fn default() -> Self {
+        Self {
+            min_new_ratio: 0.6,
+            interval_scale: 0.5,
+            max_new_ratio_when_fatigued: 0.3,
+            max_duration_days: 30,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AMASConfig {
@@ -760,6 +992,10 @@ pub struct AMASConfig {
     pub learning_strategy: LearningStrategyConfig,
     #[serde(default)]
     pub classifier: ClassifierConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub cram_mode: CramModeConfig,
 }
 
 impl AMASConfig {
@@ -805,6 +1041,11 @@ impl AMASConfig {
         if !(0.0..=1.0).contains(&self.monitoring.sample_rate) {
             return Err("monitoring.sample_rate must be in [0,1]".to_string());
         }
+        if let Some(range) = self.monitoring.full_sample_buckets {
+            if range.start >= range.end || range.end > MonitoringConfig::SAMPLE_BUCKETS {
+                return Err("invalid monitoring.full_sample_buckets range".to_string());
+            }
+        }
 
         if !(0.0..=1.0).contains(&self.constraints.high_fatigue_threshold)
             || !(0.0..=1.0).contains(&self.constraints.low_attention_threshold)
@@ -829,10 +1070,54 @@ impl AMASConfig {
             ));
         }
 
+        if !self.ensemble.trust_decay_half_life_hours.is_finite()
+            || self.ensemble.trust_decay_half_life_hours < 0.0
+        {
+            return Err("ensemble.trust_decay_half_life_hours must be >= 0".to_string());
+        }
+
         if !(0.0..=1.0).contains(&self.modeling.visual_fatigue_weight) {
             return Err("modeling.visual_fatigue_weight must be in [0,1]".to_string());
         }
 
+        if self.concurrency.user_lock_timeout_ms == 0 {
+            return Err("concurrency.user_lock_timeout_ms must be > 0".to_string());
+        }
+        if self.concurrency.max_in_flight_per_user == 0 {
+            return Err("concurrency.max_in_flight_per_user must be > 0".to_string());
+        }
+
+        if !(0.0..=1.0).contains(&self.cram_mode.min_new_ratio)
+            || !(0.0..=1.0).contains(&self.cram_mode.max_new_ratio_when_fatigued)
+        {
+            return Err("cram_mode new ratios must be in [0,1]".to_string());
+        }
+        if self.cram_mode.interval_scale <= 0.0 || self.cram_mode.interval_scale > 1.0 {
+            return Err("cram_mode.interval_scale must be in (0,1]".to_string());
+        }
+        if self.cram_mode.max_duration_days <= 0 {
+            return Err("cram_mode.max_duration_days must be > 0".to_string());
+        }
+
+        if self.modeling.trend_max_delta <= 0.0 || self.modeling.trend_max_delta > 1.0 {
+            return Err("modeling.trend_max_delta must be in (0,1]".to_string());
+        }
+
+        if self.modeling.visual_fatigue_max_delta <= 0.0
+            || self.modeling.visual_fatigue_max_delta > 1.0
+        {
+            return Err("modeling.visual_fatigue_max_delta must be in (0,1]".to_string());
+        }
+        let half_life = self.modeling.visual_fatigue_decay_half_life_secs;
+        if self.modeling.visual_fatigue_blend_mode == FatigueBlendMode::DecayWeighted
+            && !(half_life.is_finite() && half_life > 0.0)
+        {
+            return Err(
+                "modeling.visual_fatigue_decay_half_life_secs must be > 0 for decay-weighted blend"
+                    .to_string(),
+            );
+        }
+
         if self.ensemble.min_weight <= 0.0 || self.ensemble.min_weight > 1.0 {
             return Err("ensemble.min_weight must be in (0,1]".to_string());
         }
@@ -1037,6 +1322,14 @@ impl AMASConfig {
         if !(0.0..=1.0).contains(&self.intervention.attention_alert_threshold) {
             return Err("intervention.attention_alert_threshold must be in [0,1]".to_string());
         }
+        if !(self.intervention.fatigue_alert_threshold..=1.0)
+            .contains(&self.intervention.severe_fatigue_threshold)
+        {
+            return Err(
+                "intervention.severe_fatigue_threshold must be in [fatigue_alert_threshold,1]"
+                    .to_string(),
+            );
+        }
 
         // LearningStrategyConfig
         if !(0.0..=1.0).contains(&self.learning_strategy.cross_session_high_accuracy) {
@@ -1073,4 +1366,16 @@ mod tests {
         cfg.monitoring.sample_rate = 2.0;
         assert!(cfg.validate().is_err());
     }
+
+    #[test]
+    fn saved_ensemble_config_without_decay_keeps_step() {
+        let mut saved = serde_json::to_value(EnsembleConfig::default()).unwrap();
+        saved.as_object_mut().unwrap().remove("warmupBoostDecay");
+        let cfg: EnsembleConfig = serde_json::from_value(saved).unwrap();
+        assert_eq!(cfg.warmup_boost_decay, WarmupBoostDecay::Step);
+        assert_eq!(
+            EnsembleConfig::default().warmup_boost_decay,
+            WarmupBoostDecay::Linear
+        );
+    }
 }
@@ -5,16 +5,23 @@
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::amas::config::EnsembleConfig;
+use crate::amas::config::{EnsembleConfig, EnsembleStrategy, WarmupBoostDecay};
 use crate::amas::types::{AlgorithmId, DecisionCandidate, StrategyParams};
 
+/// 指数衰减的速率，越大前期下降越快
+const EXPONENTIAL_WARMUP_DECAY_RATE: f64 = 3.0;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustScores {
     pub heuristic: f64,
     pub ige: f64,
     pub swd: f64,
+    /// 上次衰减的时间，旧数据为空时从首次加载开始计算
+    #[serde(default)]
+    pub decayed_at: Option<DateTime<Utc>>,
 }
 
 impl Default for TrustScores {
@@ -23,10 +30,54 @@ impl Default for TrustScores {
             heuristic: 0.5,
             ige: 0.5,
             swd: 0.5,
+            decayed_at: None,
         }
     }
 }
 
+/// 按距上次衰减的时间，把各信任分数与基准权重的差值按半衰期指数缩小。
+/// 指数衰减无记忆，分多次应用与一次应用结果相同，因此每次加载都可以直接前移时间戳；
+/// 持续被评估的算法会被奖励更新拉回，长期闲置的算法则逐渐回到基准。
+pub fn decay_trust(trust_scores: &mut TrustScores, now: DateTime<Utc>, config: &EnsembleConfig) {
+    let last = trust_scores.decayed_at.replace(now);
+    let half_life = config.trust_decay_half_life_hours;
+    let Some(last) = last else {
+        return;
+    };
+    if half_life <= 0.0 || now <= last {
+        return;
+    }
+
+    let idle_hours = (now - last).num_milliseconds() as f64 / 3_600_000.0;
+    let keep = 0.5_f64.powf(idle_hours / half_life);
+    for (score, base) in [
+        (&mut trust_scores.heuristic, config.base_weight_heuristic),
+        (&mut trust_scores.ige, config.base_weight_ige),
+        (&mut trust_scores.swd, config.base_weight_swd),
+    ] {
+        *score = base + (*score - base) * keep;
+    }
+}
+
+/// 预热期内启发式算法的额外权重，按 `warmup_boost_decay` 从满额平滑降到 0，
+/// 避免跨过 `warmup_samples` 时策略突变
+pub fn warmup_boost(total_samples: u64, config: &EnsembleConfig) -> f64 {
+    if total_samples >= config.warmup_samples {
+        return 0.0;
+    }
+    let remaining = 1.0 - total_samples as f64 / config.warmup_samples as f64;
+    let scale = match config.warmup_boost_decay {
+        WarmupBoostDecay::Step => 1.0,
+        WarmupBoostDecay::Linear => remaining,
+        WarmupBoostDecay::Exponential => {
+            // 归一化使 0 样本时为 1、阈值处为 0
+            let floor = (-EXPONENTIAL_WARMUP_DECAY_RATE).exp();
+            (((remaining - 1.0) * EXPONENTIAL_WARMUP_DECAY_RATE).exp() - floor) / (1.0 - floor)
+        }
+    };
+    config.warmup_heuristic_boost * scale
+}
+
 pub fn get_weights(
     total_samples: u64,
     trust_scores: &TrustScores,
@@ -51,9 +102,7 @@ pub fn get_weights(
         ((1.0 - blend) * config.base_weight_swd + blend * trust_scores.swd)
             .max(config.min_weight);
 
-    if in_warmup {
-        w_h += config.warmup_heuristic_boost;
-    }
+    w_h += warmup_boost(total_samples, config);
 
     let total = w_h + w_i + w_s;
 
@@ -97,6 +146,70 @@ pub fn get_weights_for_candidates(
     filtered
 }
 
+/// 按 config.strategy 组合候选，返回最终策略及各算法权重
+pub fn combine(
+    candidates: &[DecisionCandidate],
+    total_samples: u64,
+    trust_scores: &TrustScores,
+    config: &EnsembleConfig,
+) -> (StrategyParams, HashMap<AlgorithmId, f64>) {
+    match config.strategy {
+        EnsembleStrategy::Weighted => {
+            let weights =
+                get_weights_for_candidates(candidates, total_samples, trust_scores, config);
+            (merge(candidates, &weights), weights)
+        }
+        EnsembleStrategy::ArgmaxConfidence => match argmax_confidence(candidates) {
+            Some(chosen) => (
+                chosen.strategy.clone(),
+                HashMap::from([(chosen.algorithm_id, 1.0)]),
+            ),
+            None => (StrategyParams::default(), HashMap::new()),
+        },
+        EnsembleStrategy::Stacked => {
+            let weights = get_stacked_weights(candidates, trust_scores, config);
+            (merge(candidates, &weights), weights)
+        }
+    }
+}
+
+pub fn argmax_confidence(candidates: &[DecisionCandidate]) -> Option<&DecisionCandidate> {
+    candidates.iter().max_by(|a, b| {
+        a.confidence
+            .partial_cmp(&b.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+/// 线性组合：各算法缩放系数即其信任分（由 update_trust 在线学习），不掺入基础权重
+pub fn get_stacked_weights(
+    candidates: &[DecisionCandidate],
+    trust_scores: &TrustScores,
+    config: &EnsembleConfig,
+) -> HashMap<AlgorithmId, f64> {
+    let mut weights: HashMap<AlgorithmId, f64> = candidates
+        .iter()
+        .map(|c| {
+            let scale = match c.algorithm_id {
+                AlgorithmId::Heuristic => trust_scores.heuristic,
+                AlgorithmId::Ige => trust_scores.ige,
+                AlgorithmId::Swd => trust_scores.swd,
+                _ => 0.0,
+            }
+            .max(config.min_weight);
+            (c.algorithm_id, scale * c.confidence.clamp(0.0, 1.0))
+        })
+        .collect();
+
+    let total: f64 = weights.values().sum();
+    if total > 0.0 {
+        for v in weights.values_mut() {
+            *v /= total;
+        }
+    }
+    weights
+}
+
 pub fn merge(
     candidates: &[DecisionCandidate],
     weights: &HashMap<AlgorithmId, f64>,
@@ -166,4 +279,139 @@ mod tests {
         let sum: f64 = w.values().sum();
         assert!((sum - 1.0).abs() < 1e-9);
     }
+
+    fn candidate(algorithm_id: AlgorithmId, difficulty: f64, confidence: f64) -> DecisionCandidate {
+        DecisionCandidate {
+            algorithm_id,
+            strategy: StrategyParams {
+                difficulty,
+                ..Default::default()
+            },
+            confidence,
+            explanation: String::new(),
+        }
+    }
+
+    #[test]
+    fn warmup_boost_decays_monotonically_to_zero() {
+        for shape in [WarmupBoostDecay::Linear, WarmupBoostDecay::Exponential] {
+            let cfg = EnsembleConfig {
+                warmup_boost_decay: shape,
+                ..Default::default()
+            };
+            let boosts: Vec<f64> = (0..=cfg.warmup_samples)
+                .map(|n| warmup_boost(n, &cfg))
+                .collect();
+            assert!((boosts[0] - cfg.warmup_heuristic_boost).abs() < 1e-9);
+            assert!(boosts.windows(2).all(|w| w[1] < w[0]), "{shape:?}");
+            assert_eq!(boosts[cfg.warmup_samples as usize], 0.0);
+            // 最后一步的降幅不超过满额的一半，不再出现断崖
+            let last_step = boosts[boosts.len() - 2];
+            assert!(last_step < cfg.warmup_heuristic_boost / 2.0, "{shape:?}");
+        }
+
+        let step = EnsembleConfig {
+            warmup_boost_decay: WarmupBoostDecay::Step,
+            ..Default::default()
+        };
+        assert_eq!(
+            warmup_boost(step.warmup_samples - 1, &step),
+            step.warmup_heuristic_boost
+        );
+    }
+
+    #[test]
+    fn strategy_defaults_to_weighted_when_omitted() {
+        let mut value = serde_json::to_value(EnsembleConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("strategy");
+        let cfg: EnsembleConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(cfg.strategy, EnsembleStrategy::Weighted);
+    }
+
+    #[test]
+    fn argmax_confidence_picks_most_confident_candidate() {
+        let cfg = EnsembleConfig {
+            strategy: EnsembleStrategy::ArgmaxConfidence,
+            ..Default::default()
+        };
+        let candidates = vec![
+            candidate(AlgorithmId::Heuristic, 0.2, 0.4),
+            candidate(AlgorithmId::Ige, 0.8, 0.9),
+        ];
+        let (strategy, weights) = combine(&candidates, 100, &TrustScores::default(), &cfg);
+        assert_eq!(strategy.difficulty, 0.8);
+        assert_eq!(weights.get(&AlgorithmId::Ige), Some(&1.0));
+    }
+
+    #[test]
+    fn stacked_follows_learned_trust() {
+        let cfg = EnsembleConfig {
+            strategy: EnsembleStrategy::Stacked,
+            ..Default::default()
+        };
+        let trust = TrustScores {
+            heuristic: 0.9,
+            ige: 0.1,
+            swd: 0.5,
+            ..Default::default()
+        };
+        let candidates = vec![
+            candidate(AlgorithmId::Heuristic, 0.2, 1.0),
+            candidate(AlgorithmId::Ige, 0.8, 1.0),
+        ];
+        let (strategy, weights) = combine(&candidates, 100, &trust, &cfg);
+        let sum: f64 = weights.values().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+        assert!(weights[&AlgorithmId::Heuristic] > weights[&AlgorithmId::Ige]);
+        assert!(strategy.difficulty < 0.5);
+    }
+
+    #[test]
+    fn idle_trust_decays_toward_base_weights() {
+        let cfg = EnsembleConfig::default();
+        let start = Utc::now();
+        let mut trust = TrustScores {
+            heuristic: 0.95,
+            ige: 0.05,
+            swd: 0.5,
+            decayed_at: Some(start),
+        };
+        let gap = |t: &TrustScores| {
+            (t.heuristic - cfg.base_weight_heuristic).abs()
+                + (t.ige - cfg.base_weight_ige).abs()
+                + (t.swd - cfg.base_weight_swd).abs()
+        };
+        let initial_gap = gap(&trust);
+
+        // 一个半衰期后差值减半，分段衰减与一次性衰减一致
+        let half_life = chrono::Duration::hours(cfg.trust_decay_half_life_hours as i64);
+        decay_trust(&mut trust, start + half_life / 2, &cfg);
+        decay_trust(&mut trust, start + half_life, &cfg);
+        assert!((gap(&trust) - initial_gap / 2.0).abs() < 1e-9);
+
+        let mut idle = start + half_life;
+        for _ in 0..20 {
+            idle += half_life;
+            decay_trust(&mut trust, idle, &cfg);
+        }
+        assert!(gap(&trust) < 1e-5);
+        // 信任分数回到基准后，集成权重也回到基准权重
+        let weights = get_weights(1_000, &trust, &cfg);
+        assert!((weights[&AlgorithmId::Heuristic] - cfg.base_weight_heuristic).abs() < 1e-5);
+        assert!((weights[&AlgorithmId::Ige] - cfg.base_weight_ige).abs() < 1e-5);
+
+        // 关闭衰减时只前移时间戳
+        let disabled = EnsembleConfig {
+            trust_decay_half_life_hours: 0.0,
+            ..Default::default()
+        };
+        let mut fresh = TrustScores {
+            heuristic: 0.9,
+            ..Default::default()
+        };
+        decay_trust(&mut fresh, start, &disabled);
+        decay_trust(&mut fresh, start + half_life * 10, &disabled);
+        assert_eq!(fresh.heuristic, 0.9);
+        assert_eq!(fresh.decayed_at, Some(start + half_life * 10));
+    }
 }
@@ -1,20 +1,31 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
 
-use crate::amas::config::AMASConfig;
+use crate::amas::config::{AMASConfig, CramModeConfig, FatigueBlendMode, ModelingConfig};
 use crate::amas::decision::{ensemble, heuristic, ige, swd};
+use crate::amas::elo::EloRating;
+use crate::amas::intervention;
 use crate::amas::memory::{evm, iad, mastery, mdm, mtp};
 use crate::amas::metrics;
 use crate::amas::monitoring;
+use crate::amas::placement::PlacementResult;
 use crate::amas::types::*;
-use crate::response::AppError;
-use crate::store::Store;
+use crate::response::{AppError, ErrorCode};
+use crate::store::operations::engine::AlgoStateRepair;
+use crate::store::operations::notifications::{Notification, NotificationType};
+use crate::store::operations::visual_fatigue::VisualFatigueSample;
+use crate::store::{Store, StoreError};
 
 const USER_LOCK_CLEANUP_THRESHOLD: usize = 500;
 const SIGNAL_THRESHOLD: f64 = 0.5;
 const TREND_BASELINE: f64 = 0.5;
+const EXPLANATION_STATE_KEY: &str = "explanation";
+const MASTERY_STATE_PREFIX: &str = "mastery:";
+const AMAS_EXPORT_VERSION: u32 = 1;
+const DEFAULT_TEMPORAL_FLUSH_TICK_SECS: u64 = 60;
+const INTERVENTION_NOTIFICATION_GROUP: &str = "fatigue_intervention";
 
 /// 清理浮点数，将 NaN 和 Infinity 替换为安全默认值
 fn sanitize_float(value: f64, default: f64) -> f64 {
@@ -25,12 +36,99 @@ fn sanitize_float(value: f64, default: f64) -> f64 {
     }
 }
 
+/// tracing span 中代替 user_id 的摘要，可关联同一用户的事件而不暴露原始 ID
+fn span_user_hash(user_id: &str) -> String {
+    let mut hash = crate::auth::hash_token(user_id);
+    hash.truncate(16);
+    hash
+}
+
+/// 在保存前清理浮点字段，防止 NaN 传播
+fn sanitize_user_state(user_state: &mut UserState) {
+    user_state.attention = sanitize_float(user_state.attention, 0.5).clamp(0.0, 1.0);
+    user_state.fatigue = sanitize_float(user_state.fatigue, 0.0).clamp(0.0, 1.0);
+    user_state.motivation = sanitize_float(user_state.motivation, 0.0).clamp(-1.0, 1.0);
+    user_state.confidence = sanitize_float(user_state.confidence, 0.5).clamp(0.0, 1.0);
+    user_state.cognitive_profile.memory_capacity =
+        sanitize_float(user_state.cognitive_profile.memory_capacity, 0.5).clamp(0.0, 1.0);
+    user_state.cognitive_profile.processing_speed =
+        sanitize_float(user_state.cognitive_profile.processing_speed, 0.5).clamp(0.0, 1.0);
+    user_state.cognitive_profile.stability =
+        sanitize_float(user_state.cognitive_profile.stability, 0.5).clamp(0.0, 1.0);
+}
+
+/// cram 模式：前置新词、缩短复习间隔，并取消集成决策给出的复习模式
+fn apply_cram_override(mut strategy: StrategyParams, cram: &CramModeConfig) -> StrategyParams {
+    strategy.review_mode = false;
+    strategy.new_ratio = strategy.new_ratio.max(cram.min_new_ratio);
+    strategy.interval_scale *= cram.interval_scale;
+    strategy
+}
+
+/// 混合行为疲劳与视觉疲劳。`behavioral_age_secs` 为行为估计距上次答题事件的时长，
+/// 仅 decay-weighted 模式使用，未知时按完全新鲜处理
+fn blend_visual_fatigue(
+    behavioral: f64,
+    visual: f64,
+    behavioral_age_secs: Option<f64>,
+    m: &ModelingConfig,
+) -> f64 {
+    let w = m.visual_fatigue_weight;
+    match m.visual_fatigue_blend_mode {
+        FatigueBlendMode::Linear => (1.0 - w) * behavioral + w * visual,
+        FatigueBlendMode::Max => behavioral.max(visual),
+        FatigueBlendMode::DecayWeighted => {
+            let age = behavioral_age_secs.unwrap_or(0.0);
+            let freshness = 0.5_f64.powf(age / m.visual_fatigue_decay_half_life_secs);
+            let behavioral_weight = (1.0 - w) * freshness;
+            let total = behavioral_weight + w;
+            if total <= f64::EPSILON {
+                return visual;
+            }
+            (behavioral_weight * behavioral + w * visual) / total
+        }
+    }
+}
+
 pub struct AMASEngine {
     config: Arc<RwLock<Arc<AMASConfig>>>,
+    /// 同步调用方使用的配置快照：reload 时在持有异步写锁期间原子替换指针，
+    /// 读方只需克隆 Arc，不会在重载期间回退到默认配置
+    config_snapshot: Arc<std::sync::RwLock<Arc<AMASConfig>>>,
     config_hash: Arc<RwLock<String>>,
     store: Arc<Store>,
     user_locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
     metrics_registry: Arc<metrics::MetricsRegistry>,
+    /// 待落盘的时段画像样本（按到达顺序），仅在启用批量落盘时使用
+    pending_temporal: Arc<Mutex<HashMap<String, Vec<TemporalSample>>>>,
+}
+
+/// 在途事件计数，随请求结束（含等待超时）自动扣减
+struct InFlight(Arc<metrics::MetricsRegistry>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.leave_in_flight();
+    }
+}
+
+struct UserLockGuard {
+    _guard: OwnedMutexGuard<()>,
+    _in_flight: InFlight,
+}
+
+impl std::fmt::Debug for UserLockGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserLockGuard").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TemporalSample {
+    hour: u8,
+    accuracy: f64,
+    avg_response_time_ms: f64,
+    mastery_efficiency: f64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -40,23 +138,78 @@ pub struct AlgoStates {
     pub trust_scores: ensemble::TrustScores,
 }
 
+/// 一次事件处理读写的单用户状态；`memory` 决定记忆模型状态是落库还是只留在内存
+struct EventScope<'a, M> {
+    user_id: &'a str,
+    user_state: UserState,
+    algo_states: AlgoStates,
+    memory: M,
+}
+
+struct EventOutcome {
+    result: ProcessResult,
+    /// 约束层之前的策略，供监控判断约束是否生效
+    pre_constraint_strategy: StrategyParams,
+}
+
+/// update_memory 使用的按 key 存取的算法状态（mastery/iad/mtp/evm）
+trait MemoryStateAccess {
+    fn get(&mut self, key: &str) -> Result<Option<serde_json::Value>, StoreError>;
+    fn set(&mut self, key: &str, value: serde_json::Value) -> Result<(), StoreError>;
+}
+
+struct StoredMemoryStates<'a> {
+    store: &'a Store,
+    user_id: &'a str,
+}
+
+impl MemoryStateAccess for StoredMemoryStates<'_> {
+    fn get(&mut self, key: &str) -> Result<Option<serde_json::Value>, StoreError> {
+        self.store.get_engine_algo_state(self.user_id, key)
+    }
+
+    fn set(&mut self, key: &str, value: serde_json::Value) -> Result<(), StoreError> {
+        self.store.set_engine_algo_state(self.user_id, key, &value)
+    }
+}
+
+/// 回放用：状态只保存在内存中
+impl MemoryStateAccess for HashMap<String, serde_json::Value> {
+    fn get(&mut self, key: &str) -> Result<Option<serde_json::Value>, StoreError> {
+        Ok(HashMap::get(self, key).cloned())
+    }
+
+    fn set(&mut self, key: &str, value: serde_json::Value) -> Result<(), StoreError> {
+        self.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
 impl AMASEngine {
     pub fn new(config: AMASConfig, store: Arc<Store>) -> Self {
         let hash = monitoring::compute_config_hash(&config);
+        let config = Arc::new(config);
         Self {
-            config: Arc::new(RwLock::new(Arc::new(config))),
+            config: Arc::new(RwLock::new(Arc::clone(&config))),
+            config_snapshot: Arc::new(std::sync::RwLock::new(config)),
             config_hash: Arc::new(RwLock::new(hash)),
             store,
             user_locks: Arc::new(Mutex::new(HashMap::new())),
             metrics_registry: Arc::new(metrics::MetricsRegistry::new()),
+            pending_temporal: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub async fn reload_config(&self, new_config: AMASConfig) -> Result<(), String> {
         new_config.validate()?;
         let hash = monitoring::compute_config_hash(&new_config);
+        let new_config = Arc::new(new_config);
         let mut cfg = self.config.write().await;
-        *cfg = Arc::new(new_config);
+        *cfg = Arc::clone(&new_config);
+        *self
+            .config_snapshot
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = new_config;
         let mut h = self.config_hash.write().await;
         *h = hash;
         tracing::info!("AMAS config reloaded");
@@ -67,11 +220,25 @@ impl AMASEngine {
         self.config.read().await.as_ref().clone()
     }
 
+    /// 同步读取当前配置快照
+    fn config_snapshot(&self) -> Arc<AMASConfig> {
+        Arc::clone(
+            &self
+                .config_snapshot
+                .read()
+                .unwrap_or_else(|e| e.into_inner()),
+        )
+    }
+
     pub fn metrics_registry(&self) -> &Arc<metrics::MetricsRegistry> {
         &self.metrics_registry
     }
 
-    async fn acquire_user_lock(&self, user_id: &str) -> Arc<Mutex<()>> {
+    async fn acquire_user_lock(
+        &self,
+        user_id: &str,
+        max_in_flight: u32,
+    ) -> Result<Arc<Mutex<()>>, AppError> {
         let mut locks = self.user_locks.lock().await;
 
         // 定期清理不再持有的用户锁。
@@ -90,10 +257,49 @@ impl AMASEngine {
             }
         }
 
-        locks
+        let user_lock = locks
             .entry(user_id.to_string())
             .or_insert_with(|| Arc::new(Mutex::new(())))
-            .clone()
+            .clone();
+        // 除 HashMap 与本次克隆外，其余引用均来自排队中或持有锁的请求
+        let queued = Arc::strong_count(&user_lock) - 2;
+        if queued >= max_in_flight as usize {
+            self.metrics_registry.record_queue_rejected();
+            tracing::warn!(user_id, queued, max_in_flight, "用户在途事件过多");
+            return Err(AppError::too_many_requests(
+                "该用户排队中的请求过多，请稍后重试",
+            ));
+        }
+        self.metrics_registry.enter_in_flight(queued as u64 + 1);
+        Ok(user_lock)
+    }
+
+    /// 获取用户级锁：在途事件超过 concurrency.max_in_flight_per_user 时立即返回 429，
+    /// 超过 concurrency.user_lock_timeout_ms 仍未获取同样返回 429
+    async fn lock_user(&self, user_id: &str) -> Result<UserLockGuard, AppError> {
+        let concurrency = self.config_snapshot().concurrency.clone();
+        let timeout_ms = concurrency.user_lock_timeout_ms;
+        let user_lock = self
+            .acquire_user_lock(user_id, concurrency.max_in_flight_per_user)
+            .await?;
+        let in_flight = InFlight(Arc::clone(&self.metrics_registry));
+        let start = std::time::Instant::now();
+        let result = tokio::time::timeout(
+            std::time::Duration::from_millis(timeout_ms),
+            user_lock.lock_owned(),
+        )
+        .await;
+        let wait_us = start.elapsed().as_micros() as u64;
+        self.metrics_registry.record_lock_wait(wait_us, result.is_err());
+        result
+            .map(|guard| UserLockGuard {
+                _guard: guard,
+                _in_flight: in_flight,
+            })
+            .map_err(|_| {
+                tracing::warn!(user_id, timeout_ms, "等待用户锁超时");
+                AppError::too_many_requests("该用户有请求正在处理，请稍后重试")
+            })
     }
 
     pub async fn process_event(
@@ -103,45 +309,206 @@ impl AMASEngine {
     ) -> Result<ProcessResult, AppError> {
         let start = std::time::Instant::now();
 
-        let user_lock = self.acquire_user_lock(user_id).await;
-        let _guard = user_lock.lock().await;
+        let _guard = self.lock_user(user_id).await?;
 
-        let config = Arc::clone(&*self.config.read().await);
+        let config = self.with_user_flags(user_id, Arc::clone(&*self.config.read().await))?;
         let now = chrono::Utc::now();
 
-        let mut user_state = self.load_or_init_state(user_id)?;
-        let mut algo_states = self.load_algo_states(user_id)?;
+        let mut scope = EventScope {
+            user_id,
+            user_state: self.load_or_init_state(user_id)?,
+            algo_states: self.load_algo_states(user_id)?,
+            memory: StoredMemoryStates {
+                store: &self.store,
+                user_id,
+            },
+        };
 
-        let feature = self.build_feature_vector(&raw_event, &user_state, &config, now);
-        self.update_modeling(&mut user_state, &feature, &config);
+        // 阶段 span 挂在同为 info 级的父 span 下；user_id 属于敏感字段，只记录摘要
+        let event_span = tracing::info_span!(
+            "amas.process_event",
+            user = %span_user_hash(user_id),
+            word_id = %raw_event.word_id,
+        );
+        let cram_active = self.is_cram_active(user_id, now);
+        let outcome = self.evaluate_event(
+            &mut scope,
+            &raw_event,
+            now,
+            &config,
+            cram_active,
+            &event_span,
+        )?;
+
+        let explanation_record = ExplanationRecord {
+            explanation: outcome.result.explanation.clone(),
+            session_id: outcome.result.session_id.clone(),
+            generated_at: now,
+        };
+        tracing::info_span!(
+            parent: &event_span,
+            "amas.persist_state",
+            word_id = %raw_event.word_id,
+        )
+        .in_scope(|| {
+            self.persist_state(
+                user_id,
+                &scope.user_state,
+                &scope.algo_states,
+                &explanation_record,
+            )
+        })?;
 
-        let cold_start_phase = self.determine_cold_start_phase(&user_state, &config);
+        let result = outcome.result;
+        if let Some(ref triggered) = result.intervention {
+            self.notify_severe_intervention(user_id, triggered, &config);
+        }
 
-        let candidates = self.generate_candidates(&user_state, &feature, &mut algo_states, &config);
-        let (final_strategy, weights) =
-            self.ensemble_or_fallback(&candidates, &user_state, &algo_states, &config);
+        let latency_ms = start.elapsed().as_millis() as i64;
+        let config_version = self.config_hash.read().await.clone();
+        drop(_guard);
+        self.emit_monitoring(
+            user_id,
+            &result.session_id,
+            &result,
+            latency_ms,
+            &config,
+            &outcome.pre_constraint_strategy,
+            &config_version,
+        );
 
-        let reward = self.compute_reward(&feature, &user_state, &config);
-        let word_mastery =
-            self.update_memory(user_id, &raw_event, &feature, &final_strategy, &user_state, &config)?;
+        Ok(result)
+    }
+
+    /// 合并用户级算法开关覆盖；没有覆盖时直接复用全局配置
+    fn with_user_flags(
+        &self,
+        user_id: &str,
+        config: Arc<AMASConfig>,
+    ) -> Result<Arc<AMASConfig>, AppError> {
+        let overrides = self
+            .store
+            .get_feature_flag_overrides(user_id)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        match overrides {
+            Some(overrides) if !overrides.is_empty() => {
+                let mut merged = (*config).clone();
+                overrides.apply(&mut merged.feature_flags);
+                Ok(Arc::new(merged))
+            }
+            _ => Ok(config),
+        }
+    }
+
+    /// 在全新状态上按时间顺序重放事件：状态只在内存中推进，不落库、不发通知和监控，
+    /// 用于在真实数据上回归比对算法改动
+    pub fn replay_events(
+        &self,
+        user_id: &str,
+        events: Vec<(RawEvent, chrono::DateTime<chrono::Utc>)>,
+    ) -> Result<Vec<ProcessResult>, AppError> {
+        let config = self.with_user_flags(user_id, self.config_snapshot())?;
+        let mut scope = EventScope {
+            user_id,
+            user_state: UserState::default(),
+            algo_states: AlgoStates::default(),
+            memory: HashMap::new(),
+        };
+        let replay_span = tracing::info_span!("amas.replay", user = %span_user_hash(user_id));
+        events
+            .into_iter()
+            .map(|(raw_event, at)| {
+                self.evaluate_event(&mut scope, &raw_event, at, &config, false, &replay_span)
+                    .map(|outcome| outcome.result)
+            })
+            .collect()
+    }
+
+    /// 事件处理的计算部分，只推进 `scope` 中的状态；落库、通知与监控由调用方决定
+    fn evaluate_event<M: MemoryStateAccess>(
+        &self,
+        scope: &mut EventScope<'_, M>,
+        raw_event: &RawEvent,
+        now: chrono::DateTime<chrono::Utc>,
+        config: &AMASConfig,
+        cram_active: bool,
+        event_span: &tracing::Span,
+    ) -> Result<EventOutcome, AppError> {
+        let user_state = &mut scope.user_state;
+        let algo_states = &mut scope.algo_states;
+        ensemble::decay_trust(&mut algo_states.trust_scores, now, &config.ensemble);
+
+        let feature = self.build_feature_vector(raw_event, user_state, config, now);
+        self.update_modeling(user_state, &feature, config);
+
+        let cold_start_phase = self.determine_cold_start_phase(user_state, config);
+
+        let candidates = tracing::info_span!(
+            parent: event_span,
+            "amas.generate_candidates",
+            word_id = %raw_event.word_id,
+            candidates = tracing::field::Empty,
+        )
+        .in_scope(|| {
+            let candidates = self.generate_candidates(user_state, &feature, algo_states, config);
+            tracing::Span::current().record("candidates", candidates.len());
+            candidates
+        });
+        let (final_strategy, weights) = tracing::info_span!(
+            parent: event_span,
+            "amas.ensemble_or_fallback",
+            word_id = %raw_event.word_id,
+            algorithm = tracing::field::Empty,
+        )
+        .in_scope(|| {
+            let (strategy, weights) =
+                self.ensemble_or_fallback(&candidates, user_state, algo_states, config);
+            if let Some(algo) = dominant_algorithm(&weights) {
+                tracing::Span::current().record("algorithm", algo.as_str());
+            }
+            (strategy, weights)
+        });
+        let final_strategy = if cram_active {
+            apply_cram_override(final_strategy, &config.cram_mode)
+        } else {
+            final_strategy
+        };
+
+        let reward = self.compute_reward(&feature, user_state, config);
+        let word_mastery = tracing::info_span!(
+            parent: event_span,
+            "amas.update_memory",
+            word_id = %raw_event.word_id,
+            algorithm = ?config.memory_model.scheduler,
+        )
+        .in_scope(|| {
+            self.update_memory(
+                &mut scope.memory,
+                raw_event,
+                &feature,
+                &final_strategy,
+                user_state,
+                config,
+            )
+        })?;
 
         let retention_signal = word_mastery
             .as_ref()
             .map(|wm| wm.recall_probability)
             .unwrap_or(0.0);
-        let objective = self.evaluate_objective(&reward, retention_signal, &config);
+        let objective = self.evaluate_objective(&reward, retention_signal, config);
 
         let constrained_strategy =
-            self.apply_constraints(final_strategy.clone(), &user_state, &config);
+            self.apply_constraints(final_strategy.clone(), user_state, config, cram_active);
 
         self.update_trust_scores(
-            &mut algo_states,
+            algo_states,
             &candidates,
             reward.value,
             objective.score,
-            &user_state,
+            user_state,
             &weights,
-            &config,
+            config,
         );
 
         user_state.session_event_count += 1;
@@ -164,39 +531,42 @@ impl AMASEngine {
             }
         }
 
-        self.persist_state(user_id, &mut user_state, &algo_states)?;
+        let candidate = intervention::evaluate(user_state, &config.intervention, now);
+        let intervention = candidate.filter(|c| {
+            intervention::should_trigger(
+                user_state.last_intervention.as_ref(),
+                c,
+                &config.intervention,
+                now,
+            )
+        });
+        if let Some(ref triggered) = intervention {
+            user_state.last_intervention = Some(triggered.clone());
+        }
 
-        let explanation = self.build_explanation(&constrained_strategy, &user_state, &weights);
+        let explanation =
+            self.build_explanation(&constrained_strategy, user_state, &weights, config);
+        sanitize_user_state(user_state);
 
         let session_id = raw_event
             .session_id
             .clone()
-            .unwrap_or_else(|| format!("{user_id}-session"));
-
-        let result = ProcessResult {
-            session_id: session_id.clone(),
-            strategy: constrained_strategy,
-            explanation,
-            state: user_state.clone(),
-            word_mastery,
-            reward: reward.clone(),
-            cold_start_phase,
-        };
-
-        let latency_ms = start.elapsed().as_millis() as i64;
-        let config_version = self.config_hash.read().await.clone();
-        drop(_guard);
-        self.emit_monitoring(
-            user_id,
-            &session_id,
-            &result,
-            latency_ms,
-            &config,
-            &final_strategy,
-            &config_version,
-        );
-
-        Ok(result)
+            .unwrap_or_else(|| format!("{}-session", scope.user_id));
+
+        Ok(EventOutcome {
+            result: ProcessResult {
+                session_id,
+                strategy: constrained_strategy,
+                explanation,
+                state: user_state.clone(),
+                word_mastery,
+                reward,
+                cold_start_phase,
+                intervention,
+                algorithm_weights: weights,
+            },
+            pre_constraint_strategy: final_strategy,
+        })
     }
 
     pub async fn update_visual_fatigue(
@@ -204,17 +574,36 @@ impl AMASEngine {
         user_id: &str,
         visual_score: f64,
     ) -> Result<UserState, AppError> {
-        let user_lock = self.acquire_user_lock(user_id).await;
-        let _guard = user_lock.lock().await;
+        let _guard = self.lock_user(user_id).await?;
 
         let config = Arc::clone(&*self.config.read().await);
         let mut user_state = self.load_or_init_state(user_id)?;
 
-        let visual_fatigue = (visual_score / 100.0).clamp(0.0, 1.0);
+        let now = chrono::Utc::now();
 
-        // 混合公式：behavioral_weight * 行为疲劳 + visual_weight * 视觉疲劳
-        let w = config.modeling.visual_fatigue_weight;
-        user_state.fatigue = ((1.0 - w) * user_state.fatigue + w * visual_fatigue).clamp(0.0, 1.0);
+        // 间隔过短的上报直接忽略，避免噪声高频输入
+        let min_interval_ms = config.modeling.visual_fatigue_min_interval_ms;
+        if let Some(last) = user_state.last_visual_fatigue_at {
+            if min_interval_ms > 0 && (now - last).num_milliseconds() < min_interval_ms as i64 {
+                return Ok(user_state);
+            }
+        }
+
+        let visual_fatigue = (visual_score / 100.0).clamp(0.0, 1.0);
+        let behavioral_age_secs = user_state
+            .last_active_at
+            .map(|t| (now - t).num_milliseconds().max(0) as f64 / 1000.0);
+        let blended = blend_visual_fatigue(
+            user_state.fatigue,
+            visual_fatigue,
+            behavioral_age_secs,
+            &config.modeling,
+        );
+        // 限制单次变化幅度，抑制交替极值造成的振荡
+        let max_delta = config.modeling.visual_fatigue_max_delta;
+        let delta = (blended - user_state.fatigue).clamp(-max_delta, max_delta);
+        user_state.fatigue = (user_state.fatigue + delta).clamp(0.0, 1.0);
+        user_state.last_visual_fatigue_at = Some(now);
 
         // 持久化前清理浮点数值
         user_state.fatigue = sanitize_float(user_state.fatigue, 0.0).clamp(0.0, 1.0);
@@ -226,21 +615,143 @@ impl AMASEngine {
             .set_engine_user_state(user_id, &user_state_json)
             .map_err(|e| AppError::internal(&e.to_string()))?;
 
+        let sample = VisualFatigueSample {
+            visual_score,
+            blended_fatigue: user_state.fatigue,
+            recorded_at: now,
+        };
+        if let Err(e) = self.store.append_visual_fatigue_sample(user_id, &sample) {
+            tracing::warn!(user_id, error = %e, "failed to record visual fatigue sample");
+        }
+
         Ok(user_state)
     }
 
+    /// 完成定级测试：在用户锁内复核尚无学习记录，再写入结果、初始 ELO 与认知画像，
+    /// 作答题数计入事件数以缩短冷启动。已完成定级或已有学习记录时返回 false
+    pub async fn complete_placement(
+        &self,
+        user_id: &str,
+        result: &PlacementResult,
+        elo: &EloRating,
+    ) -> Result<bool, AppError> {
+        let _guard = self.lock_user(user_id).await?;
+        let mut user_state = self.load_or_init_state(user_id)?;
+        let store_err = |e: StoreError| AppError::internal(&e.to_string());
+        if user_state.total_event_count > 0
+            || self.store.get_user_elo(user_id).map_err(store_err)?.games > 0
+            || !self
+                .store
+                .record_placement_result(user_id, result)
+                .map_err(store_err)?
+        {
+            return Ok(false);
+        }
+        self.store.set_user_elo(user_id, elo).map_err(store_err)?;
+
+        user_state.cognitive_profile = result.cognitive_profile.clone();
+        user_state.total_event_count += result.answered as u64;
+        sanitize_user_state(&mut user_state);
+
+        let user_state_json =
+            serde_json::to_value(&user_state).map_err(|e| AppError::internal(&e.to_string()))?;
+        self.store
+            .set_engine_user_state(user_id, &user_state_json)
+            .map_err(store_err)?;
+        Ok(true)
+    }
+
     pub fn get_user_state(&self, user_id: &str) -> Result<UserState, AppError> {
         self.load_or_init_state(user_id)
     }
 
+    pub fn export_user_state(&self, user_id: &str) -> Result<AmasUserExport, AppError> {
+        let user_state = self.load_or_init_state(user_id)?;
+        let mut algo_states = std::collections::BTreeMap::new();
+        let mut mastery_states = std::collections::BTreeMap::new();
+        for (algo_id, value) in self
+            .store
+            .list_engine_algo_states(user_id)
+            .map_err(|e| AppError::internal(&e.to_string()))?
+        {
+            match algo_id.strip_prefix(MASTERY_STATE_PREFIX) {
+                Some(word_id) => mastery_states.insert(word_id.to_string(), value),
+                None => algo_states.insert(algo_id, value),
+            };
+        }
+        let elo = self
+            .store
+            .get_user_elo(user_id)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+
+        Ok(AmasUserExport {
+            version: AMAS_EXPORT_VERSION,
+            user_id: user_id.to_string(),
+            exported_at: chrono::Utc::now(),
+            user_state,
+            algo_states,
+            mastery_states,
+            elo,
+        })
+    }
+
+    /// 用导出文档整体覆盖用户的 AMAS 状态
+    pub async fn import_user_state(
+        &self,
+        user_id: &str,
+        export: &AmasUserExport,
+    ) -> Result<(), AppError> {
+        if export.version != AMAS_EXPORT_VERSION {
+            return Err(AppError::bad_request(
+                ErrorCode::UnsupportedExportVersion,
+                &format!("不支持的导出版本: {}", export.version),
+            ));
+        }
+
+        let _guard = self.lock_user(user_id).await?;
+
+        let user_state_json = serde_json::to_value(&export.user_state)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        let algo_entries: Vec<(String, serde_json::Value)> = export
+            .algo_states
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .chain(
+                export
+                    .mastery_states
+                    .iter()
+                    .map(|(word_id, v)| (format!("{MASTERY_STATE_PREFIX}{word_id}"), v.clone())),
+            )
+            .collect();
+
+        self.store
+            .replace_engine_state_atomic(user_id, &user_state_json, &algo_entries)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        self.store
+            .set_user_elo(user_id, &export.elo)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// 读取最近一次 process_event 生成的策略解释
+    pub fn get_latest_explanation(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<ExplanationRecord>, AppError> {
+        match self
+            .store
+            .get_engine_algo_state(user_id, EXPLANATION_STATE_KEY)
+            .map_err(|e| AppError::internal(&e.to_string()))?
+        {
+            Some(v) => serde_json::from_value(v)
+                .map(Some)
+                .map_err(|e| AppError::internal(&format!("Explanation deserialize: {e}"))),
+            None => Ok(None),
+        }
+    }
+
     pub fn compute_strategy_from_state(&self, user_state: &UserState) -> StrategyParams {
-        // 注意：使用 try_read 可能在写锁期间回退默认值。
-        // 对于精确结果，调用方应使用 compute_strategy_from_state_with_config 并传入已获取的 config。
-        let config = self
-            .config
-            .try_read()
-            .map(|c| Arc::clone(&c))
-            .unwrap_or_else(|_| Arc::new(AMASConfig::default()));
+        let config = self.config_snapshot();
         self.compute_strategy_from_state_with_config(user_state, &config)
     }
 
@@ -274,6 +785,49 @@ impl AMASEngine {
         Ok(self.determine_cold_start_phase(&state, &config))
     }
 
+    /// 把用户移到指定冷启动阶段的起点，只改写事件计数，返回调整前的阶段与事件数。
+    /// 目标阶段在当前阈值配置下不可达时拒绝修改
+    pub async fn set_cold_start_phase(
+        &self,
+        user_id: &str,
+        phase: &ColdStartPhase,
+    ) -> Result<(Option<ColdStartPhase>, u64), AppError> {
+        let _guard = self.lock_user(user_id).await?;
+        let config = Arc::clone(&*self.config.read().await);
+        let mut user_state = self.load_or_init_state(user_id)?;
+        let previous = (
+            self.determine_cold_start_phase(&user_state, &config),
+            user_state.total_event_count,
+        );
+
+        let cs = &config.cold_start;
+        user_state.total_event_count = match phase {
+            ColdStartPhase::Classify => 0,
+            ColdStartPhase::Explore => cs.classify_to_explore_events,
+            ColdStartPhase::Exploit => cs.explore_to_exploit_events,
+        };
+        let reached = self
+            .determine_cold_start_phase(&user_state, &config)
+            .unwrap_or(ColdStartPhase::Exploit);
+        if &reached != phase {
+            return Err(AppError::bad_request(
+                ErrorCode::AmasInvalidColdStartPhase,
+                "当前冷启动阈值配置下无法进入该阶段",
+            ));
+        }
+        // 会话内事件数不能超过总事件数
+        user_state.session_event_count = user_state
+            .session_event_count
+            .min(u32::try_from(user_state.total_event_count).unwrap_or(u32::MAX));
+
+        let user_state_json =
+            serde_json::to_value(&user_state).map_err(|e| AppError::internal(&e.to_string()))?;
+        self.store
+            .set_engine_user_state(user_id, &user_state_json)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        Ok(previous)
+    }
+
     pub fn reset_user_state(&self, user_id: &str) -> Result<(), AppError> {
         self.store
             .set_engine_user_state(
@@ -284,7 +838,7 @@ impl AMASEngine {
             .map_err(|e| AppError::internal(&e.to_string()))?;
 
         // 通过 Store 封装方法清除算法状态
-        for algo in &["ige", "swd", "trust"] {
+        for algo in &["ige", "swd", "trust", EXPLANATION_STATE_KEY] {
             self.store
                 .delete_engine_algo_state(user_id, algo)
                 .map_err(|e| AppError::internal(&e.to_string()))?;
@@ -293,6 +847,19 @@ impl AMASEngine {
         Ok(())
     }
 
+    /// 只把集成信任分数恢复为默认值，用户状态与其他算法状态保持不变；返回重置前的分数
+    pub async fn reset_trust_scores(
+        &self,
+        user_id: &str,
+    ) -> Result<ensemble::TrustScores, AppError> {
+        let _guard = self.lock_user(user_id).await?;
+        let previous = self.load_algo_state(user_id, "trust")?;
+        self.store
+            .delete_engine_algo_state(user_id, "trust")
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        Ok(previous)
+    }
+
     pub async fn update_temporal_profile(
         &self,
         user_id: &str,
@@ -301,30 +868,106 @@ impl AMASEngine {
         avg_response_time_ms: f64,
         mastery_efficiency: f64,
     ) -> Result<(), AppError> {
-        let user_lock = self.acquire_user_lock(user_id).await;
-        let _guard = user_lock.lock().await;
+        let sample = TemporalSample {
+            hour,
+            accuracy,
+            avg_response_time_ms,
+            mastery_efficiency,
+        };
 
-        let config = Arc::clone(&*self.config.read().await);
+        let flush_interval = self
+            .config_snapshot()
+            .feature
+            .temporal_profile_flush_interval_secs;
+        if flush_interval > 0 {
+            self.pending_temporal
+                .lock()
+                .await
+                .entry(user_id.to_string())
+                .or_default()
+                .push(sample);
+            return Ok(());
+        }
+
+        self.apply_temporal_samples(user_id, &[sample]).await
+    }
+
+    /// 将缓冲的时段画像样本按到达顺序写入用户状态，返回落盘的样本数。
+    /// 失败的用户样本会放回缓冲区，等待下次落盘。
+    pub async fn flush_temporal_profiles(&self) -> usize {
+        let pending = std::mem::take(&mut *self.pending_temporal.lock().await);
+        let mut flushed = 0;
+        for (user_id, samples) in pending {
+            match self.apply_temporal_samples(&user_id, &samples).await {
+                Ok(()) => flushed += samples.len(),
+                Err(e) => {
+                    tracing::warn!(user_id = %user_id, error = %e.message, "时段画像落盘失败");
+                    let mut buffer = self.pending_temporal.lock().await;
+                    let entry = buffer.entry(user_id).or_default();
+                    entry.splice(0..0, samples);
+                }
+            }
+        }
+        flushed
+    }
+
+    /// 周期性落盘时段画像，收到关闭信号时执行最后一次落盘
+    pub async fn run_temporal_flush_loop(
+        self: Arc<Self>,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) {
+        let configured = self
+            .config_snapshot()
+            .feature
+            .temporal_profile_flush_interval_secs;
+        let secs = match configured {
+            0 => DEFAULT_TEMPORAL_FLUSH_TICK_SECS,
+            n => n,
+        };
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(secs));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.flush_temporal_profiles().await;
+                }
+                _ = shutdown_rx.recv() => {
+                    let flushed = self.flush_temporal_profiles().await;
+                    tracing::info!(flushed, "关闭前落盘时段画像");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn apply_temporal_samples(
+        &self,
+        user_id: &str,
+        samples: &[TemporalSample],
+    ) -> Result<(), AppError> {
+        let _guard = self.lock_user(user_id).await?;
+
+        let alpha = self.config_snapshot().feature.temporal_profile_alpha;
         let mut user_state = self.load_or_init_state(user_id)?;
         let stats = &mut user_state.habit_profile.temporal_performance;
-        let idx = (hour as usize).min(23);
-        let h = &mut stats.hourly_stats[idx];
-
-        // EMA 指数平滑
-        let alpha = config.feature.temporal_profile_alpha;
-        if h.session_count == 0 {
-            h.avg_accuracy = accuracy;
-            h.avg_response_time_ms = avg_response_time_ms;
-            h.mastery_efficiency = mastery_efficiency;
-        } else {
-            h.avg_accuracy = h.avg_accuracy * (1.0 - alpha) + accuracy * alpha;
-            h.avg_response_time_ms =
-                h.avg_response_time_ms * (1.0 - alpha) + avg_response_time_ms * alpha;
-            h.mastery_efficiency =
-                h.mastery_efficiency * (1.0 - alpha) + mastery_efficiency * alpha;
+        for sample in samples {
+            let idx = (sample.hour as usize).min(23);
+            let h = &mut stats.hourly_stats[idx];
+
+            // EMA 指数平滑
+            if h.session_count == 0 {
+                h.avg_accuracy = sample.accuracy;
+                h.avg_response_time_ms = sample.avg_response_time_ms;
+                h.mastery_efficiency = sample.mastery_efficiency;
+            } else {
+                h.avg_accuracy = h.avg_accuracy * (1.0 - alpha) + sample.accuracy * alpha;
+                h.avg_response_time_ms =
+                    h.avg_response_time_ms * (1.0 - alpha) + sample.avg_response_time_ms * alpha;
+                h.mastery_efficiency =
+                    h.mastery_efficiency * (1.0 - alpha) + sample.mastery_efficiency * alpha;
+            }
+            h.session_count += 1;
+            stats.total_sessions += 1;
         }
-        h.session_count += 1;
-        stats.total_sessions += 1;
 
         // 持久化
         let user_state_json =
@@ -336,11 +979,7 @@ impl AMASEngine {
     }
 
     pub fn get_temporal_boost(&self, user_id: &str, hour: u8) -> Result<f64, AppError> {
-        let config = self
-            .config
-            .try_read()
-            .map(|c| Arc::clone(&c))
-            .unwrap_or_else(|_| Arc::new(AMASConfig::default()));
+        let config = self.config_snapshot();
         let state = self.load_or_init_state(user_id)?;
         let stats = &state.habit_profile.temporal_performance;
         let idx = (hour as usize).min(23);
@@ -368,51 +1007,47 @@ impl AMASEngine {
     }
 
     fn load_algo_states(&self, user_id: &str) -> Result<AlgoStates, AppError> {
-        let mut states = AlgoStates::default();
-
-        if let Some(v) = self
-            .store
-            .get_engine_algo_state(user_id, "ige")
-            .map_err(|e| AppError::internal(&e.to_string()))?
-        {
-            states.ige = match serde_json::from_value(v) {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!(user_id, algo = "ige", error = %e, "Algo state deserialization failed, using default");
-                    ige::IgeState::default()
-                }
-            };
-        }
+        Ok(AlgoStates {
+            ige: self.load_algo_state(user_id, "ige")?,
+            swd: self.load_algo_state(user_id, "swd")?,
+            trust_scores: self.load_algo_state(user_id, "trust")?,
+        })
+    }
 
-        if let Some(v) = self
+    /// 读取单个算法状态；反序列化失败时回退默认值，并按配置写入修复日志
+    fn load_algo_state<T: serde::de::DeserializeOwned + Default>(
+        &self,
+        user_id: &str,
+        algo_id: &str,
+    ) -> Result<T, AppError> {
+        let Some(v) = self
             .store
-            .get_engine_algo_state(user_id, "swd")
+            .get_engine_algo_state(user_id, algo_id)
             .map_err(|e| AppError::internal(&e.to_string()))?
-        {
-            states.swd = match serde_json::from_value(v) {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!(user_id, algo = "swd", error = %e, "Algo state deserialization failed, using default");
-                    swd::SwdState::default()
-                }
-            };
-        }
+        else {
+            return Ok(T::default());
+        };
 
-        if let Some(v) = self
-            .store
-            .get_engine_algo_state(user_id, "trust")
-            .map_err(|e| AppError::internal(&e.to_string()))?
-        {
-            states.trust_scores = match serde_json::from_value(v) {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!(user_id, algo = "trust", error = %e, "Algo state deserialization failed, using default");
-                    ensemble::TrustScores::default()
+        match serde_json::from_value(v.clone()) {
+            Ok(s) => Ok(s),
+            Err(e) => {
+                tracing::warn!(user_id, algo = algo_id, error = %e, "Algo state deserialization failed, using default");
+                if self.config_snapshot().monitoring.record_state_repairs {
+                    let repair = AlgoStateRepair {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        user_id: user_id.to_string(),
+                        algo_id: algo_id.to_string(),
+                        error: e.to_string(),
+                        raw_state: v,
+                        detected_at: chrono::Utc::now(),
+                    };
+                    if let Err(err) = self.store.record_algo_state_repair(&repair) {
+                        tracing::warn!(user_id, algo = algo_id, error = %err, "Failed to record algo state repair");
+                    }
                 }
-            };
+                Ok(T::default())
+            }
         }
-
-        Ok(states)
     }
 
     fn build_feature_vector(
@@ -529,15 +1164,17 @@ impl AMASEngine {
         state.cognitive_profile.stability =
             state.cognitive_profile.stability * (1.0 - alpha) + feature.quality * alpha;
 
-        // 更新趋势状态
-        let trend_alpha = m.trend_alpha;
-        state.trend_state.accuracy_trend = state.trend_state.accuracy_trend * (1.0 - trend_alpha)
-            + (feature.accuracy - TREND_BASELINE) * trend_alpha;
-        state.trend_state.speed_trend = state.trend_state.speed_trend * (1.0 - trend_alpha)
-            + (feature.response_speed - TREND_BASELINE) * trend_alpha;
-        state.trend_state.engagement_trend = state.trend_state.engagement_trend
-            * (1.0 - trend_alpha)
-            + (feature.engagement - TREND_BASELINE) * trend_alpha;
+        // 更新趋势状态，单次变化量受 trend_max_delta 限制以抑制离群事件
+        let trend_step = |prev: f64, signal: f64| {
+            let next = prev * (1.0 - m.trend_alpha) + (signal - TREND_BASELINE) * m.trend_alpha;
+            prev + (next - prev).clamp(-m.trend_max_delta, m.trend_max_delta)
+        };
+        state.trend_state.accuracy_trend =
+            trend_step(state.trend_state.accuracy_trend, feature.accuracy);
+        state.trend_state.speed_trend =
+            trend_step(state.trend_state.speed_trend, feature.response_speed);
+        state.trend_state.engagement_trend =
+            trend_step(state.trend_state.engagement_trend, feature.engagement);
     }
 
     fn determine_cold_start_phase(
@@ -558,11 +1195,7 @@ impl AMASEngine {
 
     /// B28: Classify learner type based on performance profile
     pub fn classify_learner_type(&self, user_id: &str) -> Result<LearnerType, AppError> {
-        let config = self
-            .config
-            .try_read()
-            .map(|c| Arc::clone(&c))
-            .unwrap_or_else(|_| Arc::new(AMASConfig::default()));
+        let config = self.config_snapshot();
         let state = self.load_or_init_state(user_id)?;
         let cp = &state.cognitive_profile;
         let cl = &config.classifier;
@@ -633,24 +1266,15 @@ impl AMASEngine {
         }
 
         if config.feature_flags.ensemble_enabled && candidates.len() > 1 {
-            let weights = ensemble::get_weights_for_candidates(
+            return ensemble::combine(
                 candidates,
                 user_state.total_event_count,
                 &algo_states.trust_scores,
                 &config.ensemble,
             );
-            let strategy = ensemble::merge(candidates, &weights);
-            return (strategy, weights);
         }
 
-        let chosen = candidates
-            .iter()
-            .max_by(|a, b| {
-                a.confidence
-                    .partial_cmp(&b.confidence)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            })
-            .unwrap();
+        let chosen = ensemble::argmax_confidence(candidates).unwrap();
         let mut weights = HashMap::new();
         weights.insert(chosen.algorithm_id, 1.0);
         (chosen.strategy.clone(), weights)
@@ -714,7 +1338,7 @@ impl AMASEngine {
 
     fn update_memory(
         &self,
-        user_id: &str,
+        memory: &mut impl MemoryStateAccess,
         raw_event: &RawEvent,
         feature: &FeatureVector,
         strategy: &StrategyParams,
@@ -726,9 +1350,8 @@ impl AMASEngine {
         }
 
         let key = format!("mastery:{}", raw_event.word_id);
-        let mut state = match self
-            .store
-            .get_engine_algo_state(user_id, &key)
+        let mut state = match memory
+            .get(&key)
             .map_err(|e| AppError::internal(&e.to_string()))?
         {
             Some(value) => match serde_json::from_value(value) {
@@ -745,9 +1368,8 @@ impl AMASEngine {
         let mut adjusted_interval_scale = strategy.interval_scale;
         if config.feature_flags.iad_enabled {
             let iad_key = "iad";
-            let mut iad_state: iad::IadState = self
-                .store
-                .get_engine_algo_state(user_id, iad_key)
+            let mut iad_state: iad::IadState = memory
+                .get(iad_key)
                 .map_err(|e| AppError::internal(&e.to_string()))?
                 .and_then(|v| serde_json::from_value(v).ok())
                 .unwrap_or_default();
@@ -767,8 +1389,8 @@ impl AMASEngine {
                         &config.iad,
                     );
                     if let Ok(val) = serde_json::to_value(&iad_state) {
-                        if let Err(e) = self.store.set_engine_algo_state(user_id, iad_key, &val) {
-                            tracing::warn!(user_id, key = iad_key, error = %e, "failed to persist algo state");
+                        if let Err(e) = memory.set(iad_key, val) {
+                            tracing::warn!(key = iad_key, error = %e, "failed to persist algo state");
                         }
                     }
                 }
@@ -778,9 +1400,8 @@ impl AMASEngine {
         // B37: MTP - 计算词素迁移加成
         if config.feature_flags.mtp_enabled {
             let mtp_key = "mtp";
-            let mut mtp_state: mtp::MtpState = self
-                .store
-                .get_engine_algo_state(user_id, mtp_key)
+            let mut mtp_state: mtp::MtpState = memory
+                .get(mtp_key)
                 .map_err(|e| AppError::internal(&e.to_string()))?
                 .and_then(|v| serde_json::from_value(v).ok())
                 .unwrap_or_default();
@@ -830,8 +1451,8 @@ impl AMASEngine {
                         &config.mtp,
                     );
                     if let Ok(val) = serde_json::to_value(&mtp_state) {
-                        if let Err(e) = self.store.set_engine_algo_state(user_id, mtp_key, &val) {
-                            tracing::warn!(user_id, key = mtp_key, error = %e, "failed to persist algo state");
+                        if let Err(e) = memory.set(mtp_key, val) {
+                            tracing::warn!(key = mtp_key, error = %e, "failed to persist algo state");
                         }
                     }
                 }
@@ -841,9 +1462,8 @@ impl AMASEngine {
         // B39: EVM - Encoding Variability Model
         {
             let evm_key = format!("evm:{}", raw_event.word_id);
-            let mut evm_state: evm::EvmState = self
-                .store
-                .get_engine_algo_state(user_id, &evm_key)
+            let mut evm_state: evm::EvmState = memory
+                .get(&evm_key)
                 .map_err(|e| AppError::internal(&e.to_string()))?
                 .and_then(|v| serde_json::from_value(v).ok())
                 .unwrap_or_default();
@@ -856,8 +1476,8 @@ impl AMASEngine {
             adjusted_interval_scale *= evm::interval_modifier(&evm_state);
 
             if let Ok(val) = serde_json::to_value(&evm_state) {
-                if let Err(e) = self.store.set_engine_algo_state(user_id, &evm_key, &val) {
-                    tracing::warn!(user_id, key = %evm_key, error = %e, "failed to persist algo state");
+                if let Err(e) = memory.set(&evm_key, val) {
+                    tracing::warn!(key = %evm_key, error = %e, "failed to persist algo state");
                 }
             }
         }
@@ -879,34 +1499,86 @@ impl AMASEngine {
             &config.memory_model,
         );
 
-        self.store
-            .set_engine_algo_state(
-                user_id,
+        memory
+            .set(
                 &key,
-                &serde_json::to_value(&state).map_err(|e| AppError::internal(&e.to_string()))?,
+                serde_json::to_value(&state).map_err(|e| AppError::internal(&e.to_string()))?,
             )
             .map_err(|e| AppError::internal(&e.to_string()))?;
 
         Ok(Some(decision))
     }
 
+    /// 严重干预额外写入通知，冷却窗口内合并为一条
+    fn notify_severe_intervention(
+        &self,
+        user_id: &str,
+        triggered: &Intervention,
+        config: &AMASConfig,
+    ) {
+        if triggered.severity != InterventionSeverity::Critical {
+            return;
+        }
+        let notification = Notification {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            notification_type: NotificationType::Reminder,
+            title: "休息提醒".to_string(),
+            message: triggered.message.clone(),
+            read: false,
+            created_at: triggered.triggered_at,
+            group_key: Some(INTERVENTION_NOTIFICATION_GROUP.to_string()),
+            count: 1,
+            items: Vec::new(),
+            updated_at: None,
+            deferred_until: None,
+            broadcast_id: None,
+        };
+        let window = chrono::Duration::seconds(config.intervention.cooldown_secs as i64);
+        let message = triggered.message.clone();
+        if let Err(e) = self
+            .store
+            .create_grouped_notification(&notification, window, |_| message.clone())
+        {
+            tracing::warn!(user_id, error = %e, "failed to create intervention notification");
+        }
+    }
+
+    /// cram 模式读取失败时按未开启处理，不影响正常学习
+    fn is_cram_active(&self, user_id: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.store
+            .get_study_config(user_id)
+            .is_ok_and(|c| c.is_cram_active(now))
+    }
+
     fn apply_constraints(
         &self,
         mut strategy: StrategyParams,
         state: &UserState,
         config: &AMASConfig,
+        cram_active: bool,
     ) -> StrategyParams {
         let c = &config.constraints;
+        // cram 模式下疲劳/注意力约束不再强制复习，但新词比例仍受上限约束
+        let fatigued_new_ratio_cap = if cram_active {
+            config.cram_mode.max_new_ratio_when_fatigued
+        } else {
+            c.max_new_ratio_when_fatigued
+        };
 
         if state.fatigue > c.high_fatigue_threshold {
             strategy.batch_size = strategy.batch_size.min(c.max_batch_size_when_fatigued);
-            strategy.new_ratio = strategy.new_ratio.min(c.max_new_ratio_when_fatigued);
+            strategy.new_ratio = strategy.new_ratio.min(fatigued_new_ratio_cap);
             strategy.difficulty = strategy.difficulty.min(c.max_difficulty_when_fatigued);
         }
 
         if state.attention < c.low_attention_threshold {
-            strategy.review_mode = true;
-            strategy.new_ratio = 0.0;
+            if cram_active {
+                strategy.new_ratio = strategy.new_ratio.min(fatigued_new_ratio_cap);
+            } else {
+                strategy.review_mode = true;
+                strategy.new_ratio = 0.0;
+            }
         }
 
         if state.motivation < c.low_motivation_threshold {
@@ -969,23 +1641,12 @@ impl AMASEngine {
     fn persist_state(
         &self,
         user_id: &str,
-        user_state: &mut UserState,
+        user_state: &UserState,
         algo_states: &AlgoStates,
+        explanation: &ExplanationRecord,
     ) -> Result<(), AppError> {
-        // 在保存前清理浮点字段，防止 NaN 传播
-        user_state.attention = sanitize_float(user_state.attention, 0.5).clamp(0.0, 1.0);
-        user_state.fatigue = sanitize_float(user_state.fatigue, 0.0).clamp(0.0, 1.0);
-        user_state.motivation = sanitize_float(user_state.motivation, 0.0).clamp(-1.0, 1.0);
-        user_state.confidence = sanitize_float(user_state.confidence, 0.5).clamp(0.0, 1.0);
-        user_state.cognitive_profile.memory_capacity =
-            sanitize_float(user_state.cognitive_profile.memory_capacity, 0.5).clamp(0.0, 1.0);
-        user_state.cognitive_profile.processing_speed =
-            sanitize_float(user_state.cognitive_profile.processing_speed, 0.5).clamp(0.0, 1.0);
-        user_state.cognitive_profile.stability =
-            sanitize_float(user_state.cognitive_profile.stability, 0.5).clamp(0.0, 1.0);
-
         let user_state_json =
-            serde_json::to_value(&*user_state).map_err(|e| AppError::internal(&e.to_string()))?;
+            serde_json::to_value(user_state).map_err(|e| AppError::internal(&e.to_string()))?;
 
         let algo_entries: Vec<(String, serde_json::Value)> = vec![
             (
@@ -1003,6 +1664,11 @@ impl AMASEngine {
                 serde_json::to_value(&algo_states.trust_scores)
                     .map_err(|e| AppError::internal(&e.to_string()))?,
             ),
+            (
+                EXPLANATION_STATE_KEY.to_string(),
+                serde_json::to_value(explanation)
+                    .map_err(|e| AppError::internal(&e.to_string()))?,
+            ),
         ];
 
         self.store
@@ -1015,41 +1681,109 @@ impl AMASEngine {
         strategy: &StrategyParams,
         user_state: &UserState,
         weights: &HashMap<AlgorithmId, f64>,
+        config: &AMASConfig,
     ) -> Explanation {
-        let mut factors = Vec::new();
-        factors.push(ExplanationFactor {
-            name: "difficulty".to_string(),
-            value: strategy.difficulty,
-            impact: if strategy.difficulty > 0.5 {
-                "positive".to_string()
-            } else {
-                "neutral".to_string()
+        let mut factors = vec![
+            ExplanationFactor {
+                name: "difficulty".to_string(),
+                value: strategy.difficulty,
+                impact: if strategy.difficulty > 0.5 {
+                    "positive".to_string()
+                } else {
+                    "neutral".to_string()
+                },
+                description: "题目难度".to_string(),
             },
-        });
-        factors.push(ExplanationFactor {
-            name: "fatigue".to_string(),
-            value: user_state.fatigue,
-            impact: if user_state.fatigue > 0.7 {
-                "negative".to_string()
-            } else {
-                "neutral".to_string()
+            ExplanationFactor {
+                name: "fatigue".to_string(),
+                value: user_state.fatigue,
+                impact: if user_state.fatigue > config.constraints.high_fatigue_threshold {
+                    "negative".to_string()
+                } else {
+                    "neutral".to_string()
+                },
+                description: "疲劳程度".to_string(),
             },
-        });
+            ExplanationFactor {
+                name: "attention".to_string(),
+                value: user_state.attention,
+                impact: if user_state.attention < config.constraints.low_attention_threshold {
+                    "negative".to_string()
+                } else {
+                    "neutral".to_string()
+                },
+                description: "注意力水平".to_string(),
+            },
+            ExplanationFactor {
+                name: "confidence".to_string(),
+                value: user_state.confidence,
+                impact: "neutral".to_string(),
+                description: "模型置信度".to_string(),
+            },
+        ];
 
         for (algo, weight) in weights {
             factors.push(ExplanationFactor {
                 name: format!("weight_{}", algo.as_str()),
                 value: *weight,
                 impact: "neutral".to_string(),
+                description: format!("{} 算法权重", algo.as_str()),
             });
         }
 
+        let (reason_key, primary_reason) =
+            Self::primary_reason(strategy, user_state, weights, config);
         Explanation {
-            primary_reason: "Strategy generated by AMAS".to_string(),
+            primary_reason,
+            reason_key: reason_key.to_string(),
             factors,
         }
     }
 
+    /// 按约束优先级选出主导因素，与 apply_constraints 的判定条件保持一致。
+    /// 返回 (语言键, 中文文本)
+    fn primary_reason(
+        strategy: &StrategyParams,
+        user_state: &UserState,
+        weights: &HashMap<AlgorithmId, f64>,
+        config: &AMASConfig,
+    ) -> (&'static str, String) {
+        let c = &config.constraints;
+        if user_state.fatigue > c.high_fatigue_threshold {
+            return ("amas.reason.high_fatigue", "降低难度以缓解疲劳".to_string());
+        }
+        // cram 模式下注意力不足不会切换复习模式
+        if user_state.attention < c.low_attention_threshold && strategy.review_mode {
+            return ("amas.reason.low_attention", "切换到复习模式".to_string());
+        }
+        if user_state.motivation < c.low_motivation_threshold {
+            return (
+                "amas.reason.low_motivation",
+                "放缓节奏以恢复学习动力".to_string(),
+            );
+        }
+        if user_state.confidence > config.learning_strategy.confidence_boost_threshold
+            && strategy.difficulty > 0.5
+        {
+            return ("amas.reason.high_confidence", "提升难度挑战".to_string());
+        }
+
+        match weights
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            Some((algo, weight)) => (
+                "amas.reason.algorithm_led",
+                format!(
+                    "由 {} 算法主导（权重 {:.0}%）",
+                    algo.as_str(),
+                    weight * 100.0
+                ),
+            ),
+            None => ("amas.reason.default", "使用默认策略".to_string()),
+        }
+    }
+
     fn emit_monitoring(
         &self,
         user_id: &str,
@@ -1072,3 +1806,213 @@ impl AMASEngine {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extreme_feature() -> FeatureVector {
+        FeatureVector {
+            accuracy: 1.0,
+            response_speed: 1.0,
+            quality: 1.0,
+            engagement: 1.0,
+            hint_penalty: 0.0,
+            time_since_last_event_secs: 0.0,
+            session_event_count: 1,
+            is_quit: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn buffered_temporal_profile_matches_write_through() {
+        let samples = [
+            (9u8, 0.9, 1200.0, 0.8),
+            (9, 0.4, 2500.0, 0.2),
+            (21, 0.7, 1800.0, 0.5),
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let direct_store = Arc::new(Store::open(dir.path().join("a").to_str().unwrap()).unwrap());
+        let direct = AMASEngine::new(AMASConfig::default(), direct_store);
+
+        let mut config = AMASConfig::default();
+        config.feature.temporal_profile_flush_interval_secs = 60;
+        let buffered_store = Arc::new(Store::open(dir.path().join("b").to_str().unwrap()).unwrap());
+        let buffered = AMASEngine::new(config, buffered_store);
+
+        for (hour, acc, rt, eff) in samples {
+            direct
+                .update_temporal_profile("u1", hour, acc, rt, eff)
+                .await
+                .unwrap();
+            buffered
+                .update_temporal_profile("u1", hour, acc, rt, eff)
+                .await
+                .unwrap();
+        }
+
+        let before = buffered.get_user_state("u1").unwrap();
+        assert_eq!(before.habit_profile.temporal_performance.total_sessions, 0);
+
+        assert_eq!(buffered.flush_temporal_profiles().await, samples.len());
+        let expected = direct.get_user_state("u1").unwrap();
+        let actual = buffered.get_user_state("u1").unwrap();
+        assert_eq!(
+            serde_json::to_value(&expected.habit_profile.temporal_performance).unwrap(),
+            serde_json::to_value(&actual.habit_profile.temporal_performance).unwrap()
+        );
+    }
+
+    #[test]
+    fn cram_mode_overrides_review_mode_within_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("db").to_str().unwrap()).unwrap());
+        let config = AMASConfig::default();
+        let engine = AMASEngine::new(config.clone(), store);
+        let distracted = UserState {
+            attention: 0.1,
+            ..UserState::default()
+        };
+
+        let normal =
+            engine.apply_constraints(StrategyParams::default(), &distracted, &config, false);
+        assert!(normal.review_mode);
+        assert_eq!(normal.new_ratio, 0.0);
+
+        let crammed = apply_cram_override(StrategyParams::default(), &config.cram_mode);
+        assert_eq!(crammed.new_ratio, config.cram_mode.min_new_ratio);
+        assert_eq!(crammed.interval_scale, config.cram_mode.interval_scale);
+        let crammed = engine.apply_constraints(crammed, &distracted, &config, true);
+        assert!(!crammed.review_mode);
+        assert_eq!(
+            crammed.new_ratio,
+            config.cram_mode.max_new_ratio_when_fatigued
+        );
+    }
+
+    #[test]
+    fn visual_fatigue_blend_modes() {
+        let mut m = ModelingConfig {
+            visual_fatigue_weight: 0.4,
+            ..ModelingConfig::default()
+        };
+        let linear = blend_visual_fatigue(0.2, 0.9, Some(0.0), &m);
+        assert!((linear - 0.48).abs() < 1e-9);
+
+        m.visual_fatigue_blend_mode = FatigueBlendMode::Max;
+        assert_eq!(blend_visual_fatigue(0.2, 0.9, Some(0.0), &m), 0.9);
+
+        m.visual_fatigue_blend_mode = FatigueBlendMode::DecayWeighted;
+        let fresh = blend_visual_fatigue(0.2, 0.9, Some(0.0), &m);
+        assert!((fresh - linear).abs() < 1e-9);
+        let half_life = m.visual_fatigue_decay_half_life_secs;
+        let stale = blend_visual_fatigue(0.2, 0.9, Some(half_life), &m);
+        assert!((stale - (0.3 * 0.2 + 0.4 * 0.9) / 0.7).abs() < 1e-9);
+        let ancient = blend_visual_fatigue(0.2, 0.9, Some(half_life * 50.0), &m);
+        assert!((ancient - 0.9).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn user_lock_times_out_when_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("db").to_str().unwrap()).unwrap());
+        let mut config = AMASConfig::default();
+        config.concurrency.user_lock_timeout_ms = 50;
+        let engine = AMASEngine::new(config, store);
+
+        let held = engine.lock_user("u1").await.unwrap();
+        let err = engine.lock_user("u1").await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::TOO_MANY_REQUESTS);
+        drop(held);
+        assert!(engine.lock_user("u1").await.is_ok());
+
+        let snapshot = engine.metrics_registry().lock_wait_snapshot();
+        assert_eq!(snapshot.timeout_count, 1);
+        assert_eq!(snapshot.acquired_count, 2);
+        assert!(snapshot.max_wait_us >= 50_000);
+    }
+
+    #[tokio::test]
+    async fn user_lock_rejects_when_too_many_in_flight() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("db").to_str().unwrap()).unwrap());
+        let mut config = AMASConfig::default();
+        config.concurrency.max_in_flight_per_user = 2;
+        let engine = Arc::new(AMASEngine::new(config, store));
+
+        let held = engine.lock_user("u1").await.unwrap();
+        let waiter = {
+            let engine = Arc::clone(&engine);
+            tokio::spawn(async move { engine.lock_user("u1").await.map(|_| ()) })
+        };
+        while engine.metrics_registry().lock_wait_snapshot().in_flight < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = engine.lock_user("u1").await.unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert!(engine.lock_user("u2").await.is_ok());
+
+        drop(held);
+        waiter.await.unwrap().unwrap();
+        let snapshot = engine.metrics_registry().lock_wait_snapshot();
+        assert_eq!(snapshot.rejected_count, 1);
+        assert_eq!(snapshot.max_queue_depth, 2);
+        assert_eq!(snapshot.in_flight, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn sync_reads_never_see_default_config_during_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("db").to_str().unwrap()).unwrap());
+        let mut base = AMASConfig::default();
+        base.ensemble.warmup_samples = 101;
+        let engine = Arc::new(AMASEngine::new(base.clone(), store));
+
+        let writer = {
+            let engine = Arc::clone(&engine);
+            tokio::spawn(async move {
+                for i in 0..500u64 {
+                    let mut cfg = base.clone();
+                    cfg.ensemble.warmup_samples = 101 + i % 2;
+                    engine.reload_config(cfg).await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let state = UserState::default();
+        while !writer.is_finished() {
+            let warmup = engine.config_snapshot().ensemble.warmup_samples;
+            assert!(
+                warmup == 101 || warmup == 102,
+                "unexpected config: {warmup}"
+            );
+            engine.compute_strategy_from_state(&state);
+            engine.classify_learner_type("u1").unwrap();
+            tokio::task::yield_now().await;
+        }
+        writer.await.unwrap();
+    }
+
+    #[test]
+    fn trend_max_delta_limits_single_event_shift() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("db").to_str().unwrap()).unwrap());
+        let engine = AMASEngine::new(AMASConfig::default(), store);
+
+        let mut config = AMASConfig::default();
+        config.modeling.trend_alpha = 0.5;
+        let mut unclamped = UserState::default();
+        engine.update_modeling(&mut unclamped, &extreme_feature(), &config);
+
+        config.modeling.trend_max_delta = 0.05;
+        let mut clamped = UserState::default();
+        engine.update_modeling(&mut clamped, &extreme_feature(), &config);
+
+        assert!((unclamped.trend_state.accuracy_trend - 0.25).abs() < 1e-9);
+        assert!((clamped.trend_state.accuracy_trend - 0.05).abs() < 1e-9);
+        assert!(clamped.trend_state.engagement_trend < unclamped.trend_state.engagement_trend);
+    }
+}
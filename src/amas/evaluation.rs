@@ -0,0 +1,186 @@
+//! 离线算法评估：按记录上标注的主导算法分组，统计策略生效后的学习结果。
+//! - 后续正确率：同一用户的下一次作答是否正确，反映该策略下的即时表现；
+//! - 保持率：同一单词间隔至少 `RETENTION_MIN_GAP_HOURS` 后的下一次作答是否正确，反映记忆保持。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::amas::types::AlgorithmId;
+use crate::store::operations::records::LearningRecord;
+use crate::store::{Store, StoreError};
+
+pub const RETENTION_MIN_GAP_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgorithmOutcome {
+    pub algorithm: AlgorithmId,
+    /// 区间内标注为该算法的记录数
+    pub samples: u64,
+    pub downstream_samples: u64,
+    pub downstream_accuracy: Option<f64>,
+    pub retention_samples: u64,
+    pub retention_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgorithmEvaluationReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub retention_min_gap_hours: i64,
+    /// 区间内未标注算法的记录数（旧记录或未经 AMAS 处理）
+    pub untagged_records: u64,
+    /// 按样本数降序
+    pub algorithms: Vec<AlgorithmOutcome>,
+}
+
+#[derive(Debug, Default)]
+struct Tally {
+    samples: u64,
+    downstream: u64,
+    downstream_correct: u64,
+    retention: u64,
+    retention_correct: u64,
+}
+
+fn ratio(hits: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| hits as f64 / total as f64)
+}
+
+/// 评估 `[from, to)` 内的带标注记录；后续作答可以落在 `to` 之后
+pub fn evaluate(
+    store: &Store,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<AlgorithmEvaluationReport, StoreError> {
+    let mut tallies: HashMap<AlgorithmId, Tally> = HashMap::new();
+    let mut untagged_records = 0;
+    for user_id in store.list_active_user_ids_between(from, to)? {
+        let records = store.get_user_records_since(&user_id, from)?;
+        untagged_records += tally_user(&records, to, &mut tallies);
+    }
+
+    let mut algorithms: Vec<AlgorithmOutcome> = tallies
+        .into_iter()
+        .map(|(algorithm, t)| AlgorithmOutcome {
+            algorithm,
+            samples: t.samples,
+            downstream_samples: t.downstream,
+            downstream_accuracy: ratio(t.downstream_correct, t.downstream),
+            retention_samples: t.retention,
+            retention_rate: ratio(t.retention_correct, t.retention),
+        })
+        .collect();
+    algorithms.sort_by(|a, b| {
+        b.samples
+            .cmp(&a.samples)
+            .then_with(|| a.algorithm.as_str().cmp(b.algorithm.as_str()))
+    });
+
+    Ok(AlgorithmEvaluationReport {
+        from,
+        to,
+        retention_min_gap_hours: RETENTION_MIN_GAP_HOURS,
+        untagged_records,
+        algorithms,
+    })
+}
+
+/// 统计单个用户按时间升序排列的记录，返回区间内未标注的记录数
+fn tally_user(
+    records: &[LearningRecord],
+    to: DateTime<Utc>,
+    tallies: &mut HashMap<AlgorithmId, Tally>,
+) -> u64 {
+    let min_gap = Duration::hours(RETENTION_MIN_GAP_HOURS);
+    // 倒序遍历，维护每个单词此后的作答（时间降序），用二分找间隔足够的第一次复习
+    let mut later_attempts: HashMap<&str, Vec<(DateTime<Utc>, bool)>> = HashMap::new();
+    let mut untagged = 0;
+    for (i, record) in records.iter().enumerate().rev() {
+        if record.created_at < to {
+            match record.algorithm_id {
+                Some(algorithm) => {
+                    let tally = tallies.entry(algorithm).or_default();
+                    tally.samples += 1;
+                    if let Some(next) = records.get(i + 1) {
+                        tally.downstream += 1;
+                        tally.downstream_correct += u64::from(next.is_correct);
+                    }
+                    if let Some(attempts) = later_attempts.get(record.word_id.as_str()) {
+                        let due = record.created_at + min_gap;
+                        let pos = attempts.partition_point(|(at, _)| *at >= due);
+                        if let Some((_, correct)) = pos.checked_sub(1).map(|p| attempts[p]) {
+                            tally.retention += 1;
+                            tally.retention_correct += u64::from(correct);
+                        }
+                    }
+                }
+                None => untagged += 1,
+            }
+        }
+        later_attempts
+            .entry(record.word_id.as_str())
+            .or_default()
+            .push((record.created_at, record.is_correct));
+    }
+    untagged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        word_id: &str,
+        hours: i64,
+        correct: bool,
+        algo: Option<AlgorithmId>,
+    ) -> LearningRecord {
+        let base = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        LearningRecord {
+            id: format!("{word_id}-{hours}"),
+            user_id: "u1".to_string(),
+            word_id: word_id.to_string(),
+            is_correct: correct,
+            response_time_ms: 1000,
+            session_id: None,
+            created_at: base + Duration::hours(hours),
+            algorithm_id: algo,
+            algorithm_weights: Default::default(),
+        }
+    }
+
+    #[test]
+    fn tallies_downstream_and_retention_per_algorithm() {
+        let records = vec![
+            record("w1", 0, true, Some(AlgorithmId::Ige)),
+            record("w2", 1, false, Some(AlgorithmId::Swd)),
+            // 距 w1@0h 不足 24 小时，不作为其保持率样本
+            record("w1", 2, false, Some(AlgorithmId::Ige)),
+            record("w3", 3, true, None),
+            // 落在区间之外，只作为后续作答
+            record("w1", 30, true, Some(AlgorithmId::Ige)),
+            record("w2", 40, false, Some(AlgorithmId::Swd)),
+        ];
+        let to = records[4].created_at;
+        let mut tallies = HashMap::new();
+        let untagged = tally_user(&records, to, &mut tallies);
+        assert_eq!(untagged, 1);
+
+        let ige = &tallies[&AlgorithmId::Ige];
+        assert_eq!(ige.samples, 2);
+        assert_eq!((ige.downstream, ige.downstream_correct), (2, 1));
+        // w1@0h 与 w1@2h 的间隔复习都是 w1@30h
+        assert_eq!((ige.retention, ige.retention_correct), (2, 2));
+
+        let swd = &tallies[&AlgorithmId::Swd];
+        assert_eq!(swd.samples, 1);
+        assert_eq!((swd.downstream, swd.downstream_correct), (1, 0));
+        assert_eq!((swd.retention, swd.retention_correct), (1, 0));
+    }
+}
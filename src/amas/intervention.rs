@@ -0,0 +1,104 @@
+//! 干预建议：状态越过 InterventionConfig 阈值时给出休息、转复习或鼓励提示
+
+use chrono::{DateTime, Utc};
+
+use crate::amas::config::InterventionConfig;
+use crate::amas::types::{Intervention, InterventionKind, InterventionSeverity, UserState};
+
+/// 按疲劳 > 注意力 > 动机的优先级取最需要处理的一项，未越过任何阈值时返回 None
+pub fn evaluate(
+    state: &UserState,
+    cfg: &InterventionConfig,
+    now: DateTime<Utc>,
+) -> Option<Intervention> {
+    let (kind, severity, message) = if state.fatigue >= cfg.severe_fatigue_threshold {
+        (
+            InterventionKind::TakeBreak,
+            InterventionSeverity::Critical,
+            "疲劳程度较高，请先休息 10 分钟再继续",
+        )
+    } else if state.fatigue > cfg.fatigue_alert_threshold {
+        (
+            InterventionKind::TakeBreak,
+            InterventionSeverity::Warning,
+            "您似乎有些疲劳，建议休息一下",
+        )
+    } else if state.attention < cfg.attention_alert_threshold {
+        (
+            InterventionKind::SwitchToReview,
+            InterventionSeverity::Warning,
+            "注意力有所下降，先复习已学单词吧",
+        )
+    } else if state.motivation < cfg.motivation_alert_threshold {
+        (
+            InterventionKind::Encourage,
+            InterventionSeverity::Info,
+            "试试更简单的单词来重建信心",
+        )
+    } else {
+        return None;
+    };
+
+    Some(Intervention {
+        kind,
+        severity,
+        message: message.to_string(),
+        triggered_at: now,
+    })
+}
+
+/// 冷却期内不重复提示，除非严重程度升级
+pub fn should_trigger(
+    last: Option<&Intervention>,
+    candidate: &Intervention,
+    cfg: &InterventionConfig,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(last) = last else {
+        return true;
+    };
+    let elapsed_secs = (now - last.triggered_at).num_seconds();
+    elapsed_secs >= cfg.cooldown_secs as i64 || candidate.severity > last.severity
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn debounces_until_cooldown_or_escalation() {
+        let cfg = InterventionConfig::default();
+        let now = Utc::now();
+        let tired = UserState {
+            fatigue: 0.75,
+            ..UserState::default()
+        };
+        let warning = evaluate(&tired, &cfg, now).unwrap();
+        assert_eq!(warning.kind, InterventionKind::TakeBreak);
+        assert_eq!(warning.severity, InterventionSeverity::Warning);
+        assert!(should_trigger(None, &warning, &cfg, now));
+
+        let later = now + Duration::seconds(60);
+        let repeat = evaluate(&tired, &cfg, later).unwrap();
+        assert!(!should_trigger(Some(&warning), &repeat, &cfg, later));
+
+        let exhausted = UserState {
+            fatigue: 0.95,
+            ..UserState::default()
+        };
+        let critical = evaluate(&exhausted, &cfg, later).unwrap();
+        assert_eq!(critical.severity, InterventionSeverity::Critical);
+        assert!(should_trigger(Some(&warning), &critical, &cfg, later));
+
+        let after_cooldown = now + Duration::seconds(cfg.cooldown_secs as i64);
+        assert!(should_trigger(
+            Some(&warning),
+            &repeat,
+            &cfg,
+            after_cooldown
+        ));
+        assert!(evaluate(&UserState::default(), &cfg, now).is_none());
+    }
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::amas::config::MemoryModelConfig;
+use crate::amas::config::{MemoryModelConfig, SchedulerKind};
 use crate::amas::types::*;
 
 use super::mdm::MdmState;
+use super::sm2::Sm2State;
 
 const ALPHA_SCALE: f64 = 0.3;
 const ALPHA_MIN: f64 = 0.1;
@@ -20,6 +21,8 @@ pub struct WordMasteryState {
     pub total_correct: u32,
     #[serde(default)]
     pub recent_results: Vec<bool>,
+    #[serde(default)]
+    pub sm2: Sm2State,
 }
 
 impl WordMasteryState {
@@ -32,6 +35,7 @@ impl WordMasteryState {
             total_attempts: 0,
             total_correct: 0,
             recent_results: Vec::new(),
+            sm2: Sm2State::default(),
         }
     }
 }
@@ -47,6 +51,8 @@ pub fn update_mastery(
     let alpha = (interval_scale * ALPHA_SCALE).clamp(ALPHA_MIN, ALPHA_MAX);
     let effective_quality = if is_correct { quality } else { quality * 0.1 };
     super::mdm::update_strength(&mut state.mdm, effective_quality, alpha, config);
+    // SM-2 状态始终同步更新，切换调度器时无需重新积累
+    super::sm2::update(&mut state.sm2, super::sm2::grade(is_correct, quality));
 
     state.total_attempts += 1;
     if is_correct {
@@ -67,8 +73,12 @@ pub fn update_mastery(
 
     let now = chrono::Utc::now().timestamp_millis();
     let recall = super::mdm::recall_probability(&state.mdm, now, config);
-    let interval =
-        super::mdm::compute_interval(&state.mdm, desired_retention, interval_scale, config);
+    let interval = match config.scheduler {
+        SchedulerKind::Composite => {
+            super::mdm::compute_interval(&state.mdm, desired_retention, interval_scale, config)
+        }
+        SchedulerKind::Sm2 => super::sm2::interval_secs(&state.sm2, interval_scale),
+    };
 
     WordMasteryDecision {
         word_id: state.word_id.clone(),
@@ -124,4 +134,29 @@ mod tests {
             MasteryLevel::Reviewing | MasteryLevel::Mastered
         ));
     }
+
+    #[test]
+    fn scheduler_selects_interval_progression() {
+        let composite = MemoryModelConfig::default();
+        let sm2 = MemoryModelConfig {
+            scheduler: SchedulerKind::Sm2,
+            ..MemoryModelConfig::default()
+        };
+        let mut composite_state = WordMasteryState::new("w1");
+        let mut sm2_state = WordMasteryState::new("w1");
+        let mut composite_days = Vec::new();
+        let mut sm2_days = Vec::new();
+        for _ in 0..4 {
+            let a = update_mastery(&mut composite_state, true, 1.0, 1.0, 0.9, &composite);
+            let b = update_mastery(&mut sm2_state, true, 1.0, 1.0, 0.9, &sm2);
+            assert_eq!(a.mastery_level, b.mastery_level);
+            composite_days.push(a.next_review_interval_secs as f64 / 86400.0);
+            sm2_days.push(b.next_review_interval_secs as f64 / 86400.0);
+        }
+
+        // SM-2：1 天、6 天，之后按易度因子倍增（满分时 EF 每次 +0.1）
+        assert_eq!(sm2_days, vec![1.0, 6.0, 17.0, 49.0]);
+        assert!(composite_days.windows(2).all(|w| w[1] >= w[0]));
+        assert_ne!(composite_days, sm2_days);
+    }
 }
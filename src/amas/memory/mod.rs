@@ -3,3 +3,4 @@ pub mod iad;
 pub mod mastery;
 pub mod mdm;
 pub mod mtp;
+pub mod sm2;
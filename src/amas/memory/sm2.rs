@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+const INITIAL_EASINESS: f64 = 2.5;
+const MIN_EASINESS: f64 = 1.3;
+const FIRST_INTERVAL_DAYS: f64 = 1.0;
+const SECOND_INTERVAL_DAYS: f64 = 6.0;
+const PASSING_GRADE: u8 = 3;
+const MAX_INTERVAL_DAYS: f64 = 365.0;
+const MIN_INTERVAL_SECS: i64 = 60;
+
+/// 经典 SM-2 调度状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sm2State {
+    pub easiness: f64,
+    pub repetitions: u32,
+    /// 兼容改名前以 snake_case 保存的状态
+    #[serde(alias = "interval_days")]
+    pub interval_days: f64,
+}
+
+impl Default for Sm2State {
+    fn default() -> Self {
+        Self {
+            easiness: INITIAL_EASINESS,
+            repetitions: 0,
+            interval_days: 0.0,
+        }
+    }
+}
+
+/// 将 [0,1] 的作答质量映射为 SM-2 的 0-5 评分：答对至少 3 分，答错至多 2 分
+pub fn grade(is_correct: bool, quality: f64) -> u8 {
+    let bonus = (quality.clamp(0.0, 1.0) * 2.0).round() as u8;
+    if is_correct {
+        PASSING_GRADE + bonus
+    } else {
+        bonus
+    }
+}
+
+pub fn update(state: &mut Sm2State, grade: u8) {
+    let grade = grade.min(5);
+    let miss = (5 - grade) as f64;
+    state.easiness = (state.easiness + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASINESS);
+
+    if grade < PASSING_GRADE {
+        state.repetitions = 0;
+        state.interval_days = FIRST_INTERVAL_DAYS;
+        return;
+    }
+    state.interval_days = match state.repetitions {
+        0 => FIRST_INTERVAL_DAYS,
+        1 => SECOND_INTERVAL_DAYS,
+        _ => (state.interval_days * state.easiness).round(),
+    };
+    state.repetitions += 1;
+}
+
+pub fn interval_secs(state: &Sm2State, interval_scale: f64) -> i64 {
+    let days = (state.interval_days * interval_scale.max(0.1)).min(MAX_INTERVAL_DAYS);
+    ((days * 86400.0) as i64).max(MIN_INTERVAL_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_review_resets_repetitions() {
+        let mut state = Sm2State::default();
+        for _ in 0..3 {
+            update(&mut state, 5);
+        }
+        assert_eq!(state.repetitions, 3);
+        update(&mut state, grade(false, 0.9));
+        assert_eq!(state.repetitions, 0);
+        assert_eq!(state.interval_days, FIRST_INTERVAL_DAYS);
+        assert!(state.easiness >= MIN_EASINESS);
+    }
+
+    #[test]
+    fn serializes_camel_case_and_reads_legacy_keys() {
+        let json = serde_json::to_value(Sm2State::default()).unwrap();
+        assert!(json.get("intervalDays").is_some());
+
+        let legacy: Sm2State = serde_json::from_value(serde_json::json!({
+            "easiness": 2.5,
+            "repetitions": 2,
+            "interval_days": 6.0,
+        }))
+        .unwrap();
+        assert_eq!(legacy.interval_days, 6.0);
+    }
+}
@@ -77,8 +77,35 @@ impl AlgorithmMetrics {
     }
 }
 
+/// 用户级锁等待耗时与排队深度统计
+#[derive(Default)]
+pub struct LockWaitMetrics {
+    pub acquired_count: AtomicU64,
+    pub timeout_count: AtomicU64,
+    pub total_wait_us: AtomicU64,
+    pub max_wait_us: AtomicU64,
+    /// 当前排队或处理中的事件总数
+    pub in_flight: AtomicU64,
+    /// 观测到的单用户最大排队深度
+    pub max_queue_depth: AtomicU64,
+    pub rejected_count: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockWaitSnapshot {
+    pub acquired_count: u64,
+    pub timeout_count: u64,
+    pub avg_wait_us: f64,
+    pub max_wait_us: u64,
+    pub in_flight: u64,
+    pub max_queue_depth: u64,
+    pub rejected_count: u64,
+}
+
 pub struct MetricsRegistry {
     metrics: HashMap<AlgorithmId, AlgorithmMetrics>,
+    lock_wait: LockWaitMetrics,
 }
 
 impl MetricsRegistry {
@@ -94,7 +121,58 @@ impl MetricsRegistry {
         ] {
             metrics.insert(*id, AlgorithmMetrics::new());
         }
-        Self { metrics }
+        Self {
+            metrics,
+            lock_wait: LockWaitMetrics::default(),
+        }
+    }
+
+    pub fn record_lock_wait(&self, wait_us: u64, timed_out: bool) {
+        let lw = &self.lock_wait;
+        if timed_out {
+            lw.timeout_count.fetch_add(1, Ordering::Relaxed);
+        } else {
+            lw.acquired_count.fetch_add(1, Ordering::Relaxed);
+        }
+        lw.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        lw.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+    }
+
+    /// `depth` 为该用户含本次在内的排队深度
+    pub fn enter_in_flight(&self, depth: u64) {
+        let lw = &self.lock_wait;
+        lw.in_flight.fetch_add(1, Ordering::Relaxed);
+        lw.max_queue_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    pub fn leave_in_flight(&self) {
+        self.lock_wait.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn record_queue_rejected(&self) {
+        self.lock_wait
+            .rejected_count
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn lock_wait_snapshot(&self) -> LockWaitSnapshot {
+        let lw = &self.lock_wait;
+        let acquired = lw.acquired_count.load(Ordering::Relaxed);
+        let timeouts = lw.timeout_count.load(Ordering::Relaxed);
+        let total = acquired + timeouts;
+        LockWaitSnapshot {
+            acquired_count: acquired,
+            timeout_count: timeouts,
+            avg_wait_us: if total > 0 {
+                lw.total_wait_us.load(Ordering::Relaxed) as f64 / total as f64
+            } else {
+                0.0
+            },
+            max_wait_us: lw.max_wait_us.load(Ordering::Relaxed),
+            in_flight: lw.in_flight.load(Ordering::Relaxed),
+            max_queue_depth: lw.max_queue_depth.load(Ordering::Relaxed),
+            rejected_count: lw.rejected_count.load(Ordering::Relaxed),
+        }
     }
 
     pub fn record_call(&self, id: AlgorithmId, latency_us: u64, is_error: bool) {
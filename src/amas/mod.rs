@@ -2,9 +2,12 @@ pub mod config;
 pub mod decision;
 pub mod elo;
 pub mod engine;
+pub mod evaluation;
+pub mod intervention;
 pub mod memory;
 pub mod metrics;
 pub mod metrics_persistence;
 pub mod monitoring;
+pub mod placement;
 pub mod types;
 pub mod word_selector;
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::amas::config::AMASConfig;
+use crate::amas::config::{AMASConfig, MonitoringConfig};
 use crate::amas::types::*;
 use crate::store::Store;
 
@@ -122,6 +123,25 @@ pub fn should_sample(
     rand::random::<f64>() < sample_rate
 }
 
+/// 用户所在的采样分桶，基于 user_id 哈希，跨进程稳定
+pub fn sample_bucket(user_id: &str) -> u8 {
+    let digest = Sha256::digest(user_id.as_bytes());
+    (u16::from_be_bytes([digest[0], digest[1]]) % MonitoringConfig::SAMPLE_BUCKETS as u16) as u8
+}
+
+/// 命中白名单或分桶区间的用户全量采样，其余用户沿用全局采样率
+pub fn effective_sample_rate(config: &MonitoringConfig, user_id: &str) -> f64 {
+    if config.full_sample_user_ids.iter().any(|id| id == user_id) {
+        return 1.0;
+    }
+    if let Some(range) = config.full_sample_buckets {
+        if (range.start..range.end).contains(&sample_bucket(user_id)) {
+            return 1.0;
+        }
+    }
+    config.sample_rate
+}
+
 pub fn compute_config_hash(config: &AMASConfig) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -147,7 +167,7 @@ pub fn record_event(
     if !should_sample(
         is_anomaly,
         &result.cold_start_phase,
-        config.monitoring.sample_rate,
+        effective_sample_rate(&config.monitoring, user_id),
     ) {
         return;
     }
@@ -181,3 +201,27 @@ pub fn record_event(
         tracing::error!(error=%e, "Failed to persist monitoring event");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amas::config::SampleBucketRange;
+
+    #[test]
+    fn overrides_force_full_sampling() {
+        let mut cfg = MonitoringConfig {
+            full_sample_user_ids: vec!["u-debug".to_string()],
+            ..MonitoringConfig::default()
+        };
+        assert_eq!(effective_sample_rate(&cfg, "u-debug"), 1.0);
+        assert_eq!(effective_sample_rate(&cfg, "u-other"), cfg.sample_rate);
+
+        let bucket = sample_bucket("u-other");
+        assert_eq!(bucket, sample_bucket("u-other"));
+        cfg.full_sample_buckets = Some(SampleBucketRange {
+            start: bucket,
+            end: bucket + 1,
+        });
+        assert_eq!(effective_sample_rate(&cfg, "u-other"), 1.0);
+    }
+}
@@ -0,0 +1,130 @@
+//! 新用户定级测试：按当前估计挑选难度相近的单词，用新手 K 值的 ELO 更新估计能力，
+//! 结束后据此设定初始 ELO 与认知画像，缩短冷启动
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::amas::config::AMASConfig;
+use crate::amas::elo::{self, EloRating};
+use crate::amas::types::CognitiveProfile;
+
+/// 定级测试题数
+pub const PLACEMENT_QUESTIONS: usize = 8;
+/// 从最近的多少个单词中挑选题目
+pub const PLACEMENT_POOL_SIZE: usize = 500;
+/// 标注难度 0 与 1 的单词相对默认 ELO 的偏移
+const DIFFICULTY_ELO_SPAN: f64 = 400.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacementAnswer {
+    pub word_id: String,
+    pub is_correct: bool,
+    pub response_time_ms: i64,
+}
+
+/// 已完成的定级测试，每个用户只记录一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacementResult {
+    pub elo: f64,
+    pub answered: usize,
+    pub correct: usize,
+    pub cognitive_profile: CognitiveProfile,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// 题目难度：作答足够多的单词用其 ELO，否则按标注难度折算
+pub fn word_rating(labeled_difficulty: f64, word_elo: &EloRating, config: &AMASConfig) -> f64 {
+    if word_elo.games >= config.elo.novice_game_threshold {
+        word_elo.rating
+    } else {
+        config.elo.default_elo
+            + (labeled_difficulty.clamp(0.0, 1.0) - 0.5) * 2.0 * DIFFICULTY_ELO_SPAN
+    }
+}
+
+/// 从默认 ELO 开始依次回放作答，返回估计的用户 ELO；单词评分只用于计算，不回写
+pub fn estimate_elo(answers: &[(f64, bool)], config: &AMASConfig) -> EloRating {
+    let mut user = EloRating {
+        rating: config.elo.default_elo,
+        games: 0,
+    };
+    for &(word_rating, is_correct) in answers {
+        let mut word = EloRating {
+            rating: word_rating,
+            games: 0,
+        };
+        elo::update_elo(&mut user, &mut word, is_correct, &config.elo);
+    }
+    user
+}
+
+/// 与引擎的特征提取一致：正确率对应记忆容量，反应速度对应处理速度，作答质量对应稳定性
+pub fn seed_profile(answers: &[PlacementAnswer], config: &AMASConfig) -> CognitiveProfile {
+    if answers.is_empty() {
+        return CognitiveProfile::default();
+    }
+    let f = &config.feature;
+    let (mut accuracy, mut speed, mut quality) = (0.0, 0.0, 0.0);
+    for answer in answers {
+        let correct = if answer.is_correct { 1.0 } else { 0.0 };
+        let response_speed = (1.0
+            - answer.response_time_ms.max(0) as f64 / config.modeling.response_speed_max_ms)
+            .clamp(0.0, 1.0);
+        let q = (correct * f.quality_accuracy_weight + response_speed * f.quality_speed_weight)
+            .clamp(0.0, 1.0);
+        accuracy += correct;
+        speed += response_speed;
+        quality += if answer.is_correct {
+            q
+        } else {
+            q * f.incorrect_quality_scale
+        };
+    }
+    let n = answers.len() as f64;
+    CognitiveProfile {
+        memory_capacity: accuracy / n,
+        processing_speed: speed / n,
+        stability: quality / n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_moves_toward_performance() {
+        let config = AMASConfig::default();
+        let strong = estimate_elo(&[(1400.0, true); PLACEMENT_QUESTIONS], &config);
+        let weak = estimate_elo(&[(1000.0, false); PLACEMENT_QUESTIONS], &config);
+        assert!(strong.rating > config.elo.default_elo + 100.0);
+        assert!(weak.rating < config.elo.default_elo - 100.0);
+        assert_eq!(strong.games, PLACEMENT_QUESTIONS as u32);
+
+        let labeled_hard = word_rating(1.0, &EloRating::default(), &config);
+        assert_eq!(labeled_hard, config.elo.default_elo + DIFFICULTY_ELO_SPAN);
+    }
+
+    #[test]
+    fn profile_reflects_accuracy_and_speed() {
+        let config = AMASConfig::default();
+        let answers = [
+            PlacementAnswer {
+                word_id: "a".to_string(),
+                is_correct: true,
+                response_time_ms: 1000,
+            },
+            PlacementAnswer {
+                word_id: "b".to_string(),
+                is_correct: false,
+                response_time_ms: 9000,
+            },
+        ];
+        let profile = seed_profile(&answers, &config);
+        assert_eq!(profile.memory_capacity, 0.5);
+        assert!((profile.processing_speed - 0.5).abs() < 1e-9);
+        assert!(profile.stability > 0.0 && profile.stability < 1.0);
+    }
+}
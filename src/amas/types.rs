@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -88,6 +90,11 @@ pub struct UserState {
     pub habit_profile: HabitProfile,
     #[serde(default)]
     pub last_session_id: Option<String>,
+    #[serde(default)]
+    pub last_visual_fatigue_at: Option<DateTime<Utc>>,
+    /// 最近一次触发的干预，用于冷却去抖
+    #[serde(default)]
+    pub last_intervention: Option<Intervention>,
 }
 
 impl Default for UserState {
@@ -105,6 +112,8 @@ impl Default for UserState {
             trend_state: TrendState::default(),
             habit_profile: HabitProfile::default(),
             last_session_id: None,
+            last_visual_fatigue_at: None,
+            last_intervention: None,
         }
     }
 }
@@ -265,16 +274,46 @@ pub struct ObjectiveEvaluation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Explanation {
+    /// 中文展示文本
     pub primary_reason: String,
+    /// 供前端本地化的语言键
+    #[serde(default)]
+    pub reason_key: String,
     pub factors: Vec<ExplanationFactor>,
 }
 
+/// 用户 AMAS 状态的可移植导出文档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmasUserExport {
+    pub version: u32,
+    pub user_id: String,
+    pub exported_at: DateTime<Utc>,
+    pub user_state: UserState,
+    /// 除掌握度以外的算法状态，键为 algo_id
+    pub algo_states: std::collections::BTreeMap<String, serde_json::Value>,
+    /// 单词掌握度状态，键为 word_id
+    pub mastery_states: std::collections::BTreeMap<String, serde_json::Value>,
+    pub elo: crate::amas::elo::EloRating,
+}
+
+/// 持久化的最近一次策略解释
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplanationRecord {
+    pub explanation: Explanation,
+    pub session_id: String,
+    pub generated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExplanationFactor {
     pub name: String,
     pub value: f64,
     pub impact: String,
+    #[serde(default)]
+    pub description: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,9 +346,47 @@ pub struct ProcessResult {
     pub word_mastery: Option<WordMasteryDecision>,
     pub reward: Reward,
     pub cold_start_phase: Option<ColdStartPhase>,
+    /// 本次事件触发的干预建议（已按冷却时间去抖）
+    #[serde(default)]
+    pub intervention: Option<Intervention>,
+    /// 生成 `strategy` 时各算法的权重；回退路径下只有被选中的算法，权重为 1
+    #[serde(default)]
+    pub algorithm_weights: HashMap<AlgorithmId, f64>,
+}
+
+impl ProcessResult {
+    pub fn dominant_algorithm(&self) -> Option<AlgorithmId> {
+        dominant_algorithm(&self.algorithm_weights)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterventionKind {
+    TakeBreak,
+    SwitchToReview,
+    Encourage,
+}
+
+/// 按严重程度递增排序
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum InterventionSeverity {
+    Info,
+    Warning,
+    Critical,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Intervention {
+    pub kind: InterventionKind,
+    pub severity: InterventionSeverity,
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColdStartPhase {
     Classify,
     Explore,
@@ -349,6 +426,14 @@ impl AlgorithmId {
     }
 }
 
+/// 权重最高的算法
+pub fn dominant_algorithm(weights: &HashMap<AlgorithmId, f64>) -> Option<AlgorithmId> {
+    weights
+        .iter()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(algo, _)| *algo)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,6 @@
 //! 选词评分模块：综合 AMAS 算法输出，对候选词进行评分排序
+//!
+//! 评分与排序不含随机成分（同分按 word_id 排序），相同的存储状态与时间点必然得到相同结果
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -14,7 +16,10 @@ use crate::store::operations::words::Word;
 use crate::store::Store;
 
 fn score_desc(a: &ScoredWord, b: &ScoredWord) -> Ordering {
-    b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+    b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.word_id.cmp(&b.word_id))
 }
 
 fn retain_top_k_by_score(words: &mut Vec<ScoredWord>, k: usize) {
@@ -311,6 +316,21 @@ mod tests {
         assert_eq!(words[1].word_id, "w3");
     }
 
+    #[test]
+    fn retain_top_k_breaks_ties_by_word_id() {
+        let tied = |id: &str| ScoredWord {
+            word_id: id.to_string(),
+            score: 0.5,
+            is_new: false,
+        };
+        let mut words = vec![tied("w4"), tied("w2"), tied("w3"), tied("w1")];
+
+        retain_top_k_by_score(&mut words, 2);
+
+        let ids: Vec<&str> = words.iter().map(|w| w.word_id.as_str()).collect();
+        assert_eq!(ids, vec!["w1", "w2"]);
+    }
+
     #[test]
     fn score_new_word_prefetched_penalizes_far_difficulty() {
         let ws = WordSelectorConfig::default();
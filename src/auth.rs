@@ -1,13 +1,17 @@
+use std::net::IpAddr;
+
 use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
 use argon2::Argon2;
 use axum::extract::{FromRef, FromRequestParts};
 use axum::http::{request::Parts, HeaderMap};
 use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::constants::SESSION_TOUCH_INTERVAL_SECS;
 use crate::response::AppError;
 use crate::state::AppState;
 
@@ -41,6 +45,15 @@ pub fn hash_token(token: &str) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// 以密钥做 HMAC 的客户端 IP 摘要，避免明文 IP 落盘且无法通过枚举 IPv4 空间还原
+pub fn hash_client_ip(ip: &IpAddr, secret: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can accept any key length");
+    mac.update(b"client_ip:");
+    mac.update(ip.to_string().as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
@@ -167,6 +180,8 @@ pub fn extract_refresh_token_from_headers(headers: &HeaderMap) -> Result<String,
 #[derive(Debug, Clone)]
 pub struct AuthUser {
     pub user_id: String,
+    /// 当前访问令牌的哈希，即会话 ID
+    pub session_hash: String,
 }
 
 #[derive(Debug, Clone)]
@@ -210,8 +225,20 @@ where
             return Err(AppError::forbidden("用户已被封禁"));
         }
 
+        // 节流写入最近活跃时间，避免每个请求都落盘
+        let now = Utc::now();
+        let stale = session.last_seen_at.map_or(true, |seen| {
+            now - seen >= Duration::seconds(SESSION_TOUCH_INTERVAL_SECS)
+        });
+        if stale {
+            if let Err(e) = app_state.store().touch_session(&token_hash, now) {
+                tracing::warn!(error = %e, "更新会话活跃时间失败");
+            }
+        }
+
         Ok(AuthUser {
             user_id: claims.sub,
+            session_hash: token_hash,
         })
     }
 }
@@ -5,6 +5,8 @@ use std::str::FromStr;
 
 use std::fmt;
 
+use crate::constants::{LOCKOUT_DURATION_MINUTES, MAX_FAILED_LOGIN_ATTEMPTS};
+
 #[derive(Clone)]
 pub struct Config {
     pub host: IpAddr,
@@ -13,14 +15,19 @@ pub struct Config {
     pub enable_file_logs: bool,
     pub log_dir: String,
     pub sled_path: String,
+    pub store: StoreConfig,
     pub jwt_secret: String,
     pub refresh_jwt_secret: String,
     pub jwt_expires_in_hours: u64,
     pub refresh_token_expires_in_hours: u64,
     pub admin_jwt_secret: String,
     pub admin_jwt_expires_in_hours: u64,
+    /// 加密管理员 TOTP 密钥的专用密钥，与 JWT 密钥分离，轮换 JWT 密钥不影响已启用的两步验证
+    pub admin_totp_encryption_key: String,
     pub cors_origin: String,
     pub trust_proxy: bool,
+    /// 停机时等待在途 HTTP 请求完成的最长秒数，超时后强制关闭剩余连接
+    pub http_drain_timeout_secs: u64,
     pub rate_limit: RateLimitConfig,
     pub auth_rate_limit: AuthRateLimitConfig,
     pub worker: WorkerConfig,
@@ -28,6 +35,31 @@ pub struct Config {
     pub llm: LLMConfig,
     pub pagination: PaginationConfig,
     pub limits: LimitsConfig,
+    pub password_policy: PasswordPolicy,
+    pub lockout: LockoutPolicy,
+    pub csp: CspConfig,
+    pub static_assets: StaticAssetsConfig,
+    pub compression: CompressionConfig,
+    pub word_uniqueness: WordUniqueness,
+    pub moderation: ModerationConfig,
+}
+
+/// sled 调优参数。缓存越大读越快但占用内存越多；
+/// 后台落盘间隔越长写吞吐越高，但进程崩溃时最多丢失该间隔内的写入。
+#[derive(Debug, Clone)]
+pub struct StoreConfig {
+    pub cache_capacity_bytes: u64,
+    /// 后台周期落盘间隔（毫秒），0 表示关闭，仅在停机及显式 flush 时落盘
+    pub flush_every_ms: u64,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        Self {
+            cache_capacity_bytes: 512_000_000,
+            flush_every_ms: 1000,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -57,6 +89,10 @@ pub struct LimitsConfig {
     pub candidate_word_pool_size: usize,
     pub rate_limit_max_entries: usize,
     pub rate_limit_cleanup_interval_secs: u64,
+    /// 相同广播（标题+内容）在该窗口内对同一用户只投递一次，0 表示不去重
+    pub broadcast_dedup_window_secs: u64,
+    /// 语义搜索时探查的近邻聚类数（基于 word_clusters 的近似索引），0 表示始终暴力检索
+    pub semantic_search_probe_clusters: usize,
 }
 
 impl Default for LimitsConfig {
@@ -72,10 +108,230 @@ impl Default for LimitsConfig {
             candidate_word_pool_size: 500,
             rate_limit_max_entries: 100_000,
             rate_limit_cleanup_interval_secs: 300,
+            broadcast_dedup_window_secs: 3600,
+            semantic_search_probe_clusters: 3,
+        }
+    }
+}
+
+/// 密码策略，默认值与原固定规则一致
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// 通过 HaveIBeenPwned range API（k-匿名，仅上传 SHA-1 前 5 位）检查泄露密码
+    pub check_breached: bool,
+    pub breach_api_url: String,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 256,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+            check_breached: false,
+            breach_api_url: "https://api.pwnedpasswords.com/range/".to_string(),
+        }
+    }
+}
+
+/// 登录失败锁定策略；关闭递增时每次锁定固定 `base_minutes`
+#[derive(Debug, Clone)]
+pub struct LockoutPolicy {
+    pub max_failed_attempts: u32,
+    pub base_minutes: i64,
+    /// 开启后每个连续锁定周期时长翻倍，直到 `max_minutes`
+    pub escalation_enabled: bool,
+    pub max_minutes: i64,
+    /// 上次锁定结束后超过该时长未再被锁定，递增级别清零
+    pub reset_after_hours: i64,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: MAX_FAILED_LOGIN_ATTEMPTS,
+            base_minutes: LOCKOUT_DURATION_MINUTES,
+            escalation_enabled: false,
+            max_minutes: 24 * 60,
+            reset_after_hours: 24,
+        }
+    }
+}
+
+impl LockoutPolicy {
+    /// 第 `level` 次连续锁定（从 1 开始）的锁定时长
+    pub fn lock_duration(&self, level: u32) -> chrono::Duration {
+        if !self.escalation_enabled {
+            return chrono::Duration::minutes(self.base_minutes);
+        }
+        let factor = 1_i64 << level.saturating_sub(1).min(20);
+        chrono::Duration::minutes(
+            self.base_minutes
+                .saturating_mul(factor)
+                .min(self.max_minutes),
+        )
+    }
+}
+
+/// Content-Security-Policy 响应头。`raw_override` 非空时原样使用，否则由各指令拼装，
+/// `base-uri` 与 `form-action` 固定为 `'self'`
+#[derive(Debug, Clone)]
+pub struct CspConfig {
+    pub raw_override: Option<String>,
+    pub default_src: String,
+    pub script_src: String,
+    pub style_src: String,
+    pub font_src: String,
+    pub connect_src: String,
+    pub img_src: String,
+    pub worker_src: String,
+    pub frame_ancestors: String,
+}
+
+impl Default for CspConfig {
+    fn default() -> Self {
+        Self {
+            raw_override: None,
+            default_src: "'self'".to_string(),
+            script_src: "'self'".to_string(),
+            style_src: "'self' https://fonts.googleapis.com".to_string(),
+            font_src: "https://fonts.gstatic.com".to_string(),
+            connect_src: "'self'".to_string(),
+            img_src: "'self' data: blob:".to_string(),
+            worker_src: "'self' blob:".to_string(),
+            frame_ancestors: "'none'".to_string(),
+        }
+    }
+}
+
+impl CspConfig {
+    /// 生成响应头取值；指令为空、含 `;` 或不是合法的 header 值时返回错误
+    pub fn header_value(&self) -> Result<axum::http::HeaderValue, String> {
+        let policy = match &self.raw_override {
+            Some(raw) => raw.trim().to_string(),
+            None => {
+                let directives = [
+                    ("default-src", &self.default_src),
+                    ("script-src", &self.script_src),
+                    ("style-src", &self.style_src),
+                    ("font-src", &self.font_src),
+                    ("connect-src", &self.connect_src),
+                    ("img-src", &self.img_src),
+                    ("worker-src", &self.worker_src),
+                    ("frame-ancestors", &self.frame_ancestors),
+                ];
+                let mut parts = Vec::with_capacity(directives.len() + 2);
+                for (name, value) in directives {
+                    let value = value.trim();
+                    if value.is_empty() || value.contains(';') {
+                        return Err(format!("invalid {name} directive: '{value}'"));
+                    }
+                    parts.push(format!("{name} {value}"));
+                }
+                parts.push("base-uri 'self'".to_string());
+                parts.push("form-action 'self'".to_string());
+                parts.join("; ")
+            }
+        };
+        if policy.is_empty() {
+            return Err("policy is empty".to_string());
+        }
+        axum::http::HeaderValue::from_str(&policy).map_err(|e| e.to_string())
+    }
+}
+
+/// 前端静态资源目录与 Cache-Control 策略
+#[derive(Debug, Clone)]
+pub struct StaticAssetsConfig {
+    pub dir: PathBuf,
+    /// HTML 入口的缓存时长，0 表示每次都向服务器验证
+    pub html_max_age_secs: u64,
+    /// `/assets/` 下带哈希的构建产物，按 immutable 缓存
+    pub assets_max_age_secs: u64,
+    pub other_max_age_secs: u64,
+}
+
+impl Default for StaticAssetsConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("static"),
+            html_max_age_secs: 0,
+            assets_max_age_secs: 31_536_000,
+            other_max_age_secs: 3600,
+        }
+    }
+}
+
+impl StaticAssetsConfig {
+    pub fn index_file(&self) -> PathBuf {
+        self.dir.join("index.html")
+    }
+
+    /// 目录不存在时返回错误，启动阶段据此直接失败
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.dir.is_dir() {
+            return Err(format!(
+                "static directory '{}' does not exist",
+                self.dir.display()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn cache_control(&self, is_html: bool, is_asset: bool) -> String {
+        if is_html {
+            if self.html_max_age_secs == 0 {
+                "no-cache, must-revalidate".to_string()
+            } else {
+                format!("public, max-age={}", self.html_max_age_secs)
+            }
+        } else if is_asset {
+            format!("public, max-age={}, immutable", self.assets_max_age_secs)
+        } else {
+            format!("public, max-age={}", self.other_max_age_secs)
+        }
+    }
+}
+
+/// 响应压缩：仅压缩白名单内且不小于阈值的响应，已带 Content-Encoding 的响应不会被再次压缩
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    pub min_size_bytes: u16,
+    /// Content-Type 前缀白名单（不含参数部分）
+    pub content_types: Vec<String>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 1024,
+            content_types: DEFAULT_COMPRESSIBLE_TYPES
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
         }
     }
 }
 
+const DEFAULT_COMPRESSIBLE_TYPES: &[&str] = &[
+    "application/json",
+    "text/html",
+    "text/css",
+    "text/plain",
+    "text/javascript",
+    "application/javascript",
+    "image/svg+xml",
+];
+
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
     pub window_secs: u64,
@@ -102,6 +358,116 @@ pub struct WorkerConfig {
     pub is_leader: bool,
     pub enable_llm_advisor: bool,
     pub enable_monitoring: bool,
+    /// 存储落盘维护任务的 cron 表达式
+    pub store_compaction_cron: String,
+    /// 同类未读通知的合并窗口（秒），0 表示不合并
+    pub notification_group_window_secs: u64,
+    /// 监控事件保留天数，超期后由 cache_cleanup 删除
+    pub monitoring_retention_days: u64,
+    pub enable_backup: bool,
+    pub backup_cron: String,
+    /// 备份文件目录，手动备份与定时备份共用
+    pub backup_dir: String,
+    /// 定时备份后保留的最新备份份数
+    pub backup_retention: usize,
+    pub enable_inactive_user_purge: bool,
+    pub inactive_user_purge_cron: String,
+    /// 超过该天数未活跃的用户进入清理候选
+    pub inactive_user_retention_days: u64,
+    pub inactive_user_action: InactiveUserAction,
+    /// 仅输出候选报告，不修改数据
+    pub inactive_user_purge_dry_run: bool,
+}
+
+/// 对长期未活跃用户执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InactiveUserAction {
+    /// 发送站内提醒，不修改账户
+    Notify,
+    /// 抹去邮箱、用户名等个人信息并封禁账户，保留匿名学习记录
+    Anonymize,
+    /// 通过 `delete_user` 删除账户及全部关联数据
+    Delete,
+}
+
+impl InactiveUserAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Notify => "notify",
+            Self::Anonymize => "anonymize",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl FromStr for InactiveUserAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "notify" => Ok(Self::Notify),
+            "anonymize" => Ok(Self::Anonymize),
+            "delete" => Ok(Self::Delete),
+            other => Err(format!("unknown inactive user action: {other}")),
+        }
+    }
+}
+
+/// 创建、改名单词时的拼写唯一性约束，拼写按去首尾空白、折叠空白、不区分大小写比较。
+/// 默认关闭，以兼容有意保留同形异义词的部署
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WordUniqueness {
+    #[default]
+    Off,
+    /// 全库拼写唯一
+    Global,
+    /// 同一词书内拼写唯一
+    Wordbook,
+}
+
+impl FromStr for WordUniqueness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "global" => Ok(Self::Global),
+            "wordbook" => Ok(Self::Wordbook),
+            other => Err(format!("unknown word uniqueness mode: {other}")),
+        }
+    }
+}
+
+/// LLM 生成内容（例句、词源）缓存前的审核方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModerationMode {
+    /// 不审核
+    Off,
+    /// 仅按屏蔽词表检查
+    #[default]
+    Wordlist,
+    /// 屏蔽词表之外再调用 LLM provider 审核，provider 不可用时拒绝缓存
+    Provider,
+}
+
+impl FromStr for ModerationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "wordlist" => Ok(Self::Wordlist),
+            "provider" => Ok(Self::Provider),
+            other => Err(format!("unknown moderation mode: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModerationConfig {
+    pub mode: ModerationMode,
+    /// 屏蔽词，不区分大小写；单词按整词匹配，含空格的短语按子串匹配
+    pub blocked_terms: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -113,10 +479,98 @@ pub struct AMASEnvConfig {
 #[derive(Clone)]
 pub struct LLMConfig {
     pub enabled: bool,
-    pub mock: bool,
+    pub provider: LlmBackend,
+    /// 为空时使用所选后端的默认地址
     pub api_url: String,
     pub api_key: String,
     pub timeout_secs: u64,
+    pub models: LlmModels,
+    pub circuit: LlmCircuitConfig,
+    pub budget: LlmBudget,
+}
+
+/// LLM 调用熔断：连续失败 `failure_threshold` 次后暂停调用 `cooldown_secs` 秒
+#[derive(Debug, Clone, Copy)]
+pub struct LlmCircuitConfig {
+    pub failure_threshold: u32,
+    pub cooldown_secs: u64,
+}
+
+impl Default for LlmCircuitConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_secs: 60,
+        }
+    }
+}
+
+/// LLM 用量预算与单价（美元/千 token），单价只用于估算费用
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LlmBudget {
+    /// 每日（UTC）token 上限，达到后暂停 LLM worker；0 表示不限
+    pub daily_token_limit: u64,
+    pub input_price_per_1k: f64,
+    pub output_price_per_1k: f64,
+}
+
+/// LLM 后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmBackend {
+    /// 确定性假数据，不发起网络请求
+    #[default]
+    Mock,
+    /// OpenAI 兼容的 `/chat/completions` 与 `/embeddings` 接口，需要 API key
+    OpenAi,
+    /// 本地部署的 OpenAI 兼容服务（如 Ollama、llama.cpp），API key 可选
+    Local,
+}
+
+impl LlmBackend {
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Self::Mock => "",
+            Self::OpenAi => "https://api.openai.com/v1",
+            Self::Local => "http://localhost:11434/v1",
+        }
+    }
+}
+
+impl FromStr for LlmBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mock" => Ok(Self::Mock),
+            "openai" | "openai-compatible" => Ok(Self::OpenAi),
+            "local" => Ok(Self::Local),
+            other => Err(format!("unknown llm provider: {other}")),
+        }
+    }
+}
+
+/// 各功能使用的模型，未单独配置的文本生成功能使用 `default`
+#[derive(Debug, Clone)]
+pub struct LlmModels {
+    pub default: String,
+    pub embedding: String,
+    pub etymology: Option<String>,
+    pub examples: Option<String>,
+    pub moderation: Option<String>,
+    pub advisor: Option<String>,
+}
+
+impl Default for LlmModels {
+    fn default() -> Self {
+        Self {
+            default: "gpt-4o-mini".to_string(),
+            embedding: "text-embedding-3-small".to_string(),
+            etymology: None,
+            examples: None,
+            moderation: None,
+            advisor: None,
+        }
+    }
 }
 
 impl fmt::Debug for Config {
@@ -128,17 +582,20 @@ impl fmt::Debug for Config {
             .field("enable_file_logs", &self.enable_file_logs)
             .field("log_dir", &self.log_dir)
             .field("sled_path", &self.sled_path)
+            .field("store", &self.store)
             .field("jwt_secret", &"***REDACTED***")
             .field("refresh_jwt_secret", &"***REDACTED***")
             .field("jwt_expires_in_hours", &self.jwt_expires_in_hours)
             .field("refresh_token_expires_in_hours", &self.refresh_token_expires_in_hours)
             .field("admin_jwt_secret", &"***REDACTED***")
+            .field("admin_totp_encryption_key", &"***REDACTED***")
             .field(
                 "admin_jwt_expires_in_hours",
                 &self.admin_jwt_expires_in_hours,
             )
             .field("cors_origin", &self.cors_origin)
             .field("trust_proxy", &self.trust_proxy)
+            .field("http_drain_timeout_secs", &self.http_drain_timeout_secs)
             .field("rate_limit", &self.rate_limit)
             .field("auth_rate_limit", &self.auth_rate_limit)
             .field("worker", &self.worker)
@@ -146,6 +603,11 @@ impl fmt::Debug for Config {
             .field("llm", &self.llm)
             .field("pagination", &self.pagination)
             .field("limits", &self.limits)
+            .field("password_policy", &self.password_policy)
+            .field("lockout", &self.lockout)
+            .field("csp", &self.csp)
+            .field("static_assets", &self.static_assets)
+            .field("compression", &self.compression)
             .finish()
     }
 }
@@ -154,10 +616,13 @@ impl fmt::Debug for LLMConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LLMConfig")
             .field("enabled", &self.enabled)
-            .field("mock", &self.mock)
+            .field("provider", &self.provider)
             .field("api_url", &self.api_url)
             .field("api_key", &"***REDACTED***")
             .field("timeout_secs", &self.timeout_secs)
+            .field("models", &self.models)
+            .field("circuit", &self.circuit)
+            .field("budget", &self.budget)
             .finish()
     }
 }
@@ -186,6 +651,22 @@ impl Config {
             }
         };
 
+        let admin_jwt_secret = env_or("ADMIN_JWT_SECRET", DEFAULT_ADMIN_JWT_SECRET);
+        let admin_totp_encryption_key = match env::var("ADMIN_TOTP_ENCRYPTION_KEY") {
+            Ok(val) if !val.is_empty() => val,
+            _ => {
+                use hmac::{Hmac, Mac};
+                type HmacSha256 = Hmac<sha2::Sha256>;
+                let mut mac = HmacSha256::new_from_slice(admin_jwt_secret.as_bytes())
+                    .expect("HMAC can accept any key length");
+                mac.update(b"admin_totp_encryption_key_derivation");
+                tracing::warn!(
+                    "ADMIN_TOTP_ENCRYPTION_KEY 未设置，已从 ADMIN_JWT_SECRET 派生，轮换 ADMIN_JWT_SECRET 会使已启用的两步验证失效。生产环境请设置独立的 ADMIN_TOTP_ENCRYPTION_KEY"
+                );
+                hex::encode(mac.finalize().into_bytes())
+            }
+        };
+
         let config = Self {
             host: env_or_parse("HOST", IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
             port: env_or_parse("PORT", 3000_u16),
@@ -193,14 +674,20 @@ impl Config {
             enable_file_logs: env_or_bool("ENABLE_FILE_LOGS", false),
             log_dir: env_or("LOG_DIR", "./logs"),
             sled_path: normalized_sled_path(&env_or("SLED_PATH", "./data/learning.sled")),
+            store: StoreConfig {
+                cache_capacity_bytes: env_or_parse("SLED_CACHE_CAPACITY_BYTES", 512_000_000_u64),
+                flush_every_ms: env_or_parse("SLED_FLUSH_EVERY_MS", 1000_u64),
+            },
             jwt_secret,
             refresh_jwt_secret,
             jwt_expires_in_hours: env_or_parse("JWT_EXPIRES_IN_HOURS", 24_u64),
             refresh_token_expires_in_hours: env_or_parse("REFRESH_TOKEN_EXPIRES_IN_HOURS", 168_u64),
-            admin_jwt_secret: env_or("ADMIN_JWT_SECRET", DEFAULT_ADMIN_JWT_SECRET),
+            admin_jwt_secret,
             admin_jwt_expires_in_hours: env_or_parse("ADMIN_JWT_EXPIRES_IN_HOURS", 2_u64),
+            admin_totp_encryption_key,
             cors_origin: env_or("CORS_ORIGIN", "http://localhost:5173"),
             trust_proxy: env_or_bool("TRUST_PROXY", false),
+            http_drain_timeout_secs: env_or_parse("HTTP_DRAIN_TIMEOUT_SECS", 30_u64),
             rate_limit: RateLimitConfig {
                 window_secs: env_or_parse("RATE_LIMIT_WINDOW_SECS", 900_u64),
                 max_requests: env_or_parse("RATE_LIMIT_MAX", 500_u64),
@@ -213,6 +700,24 @@ impl Config {
                 is_leader: env_or_bool("WORKER_LEADER", true),
                 enable_llm_advisor: env_or_bool("ENABLE_LLM_ADVISOR_WORKER", false),
                 enable_monitoring: env_or_bool("ENABLE_ENGINE_MONITORING_WORKER", true),
+                store_compaction_cron: env_or("WORKER_STORE_COMPACTION_CRON", "0 45 * * * *"),
+                notification_group_window_secs: env_or_parse(
+                    "NOTIFICATION_GROUP_WINDOW_SECS",
+                    86_400_u64,
+                ),
+                monitoring_retention_days: env_or_parse("MONITORING_RETENTION_DAYS", 7_u64),
+                enable_backup: env_or_bool("ENABLE_BACKUP_WORKER", false),
+                backup_cron: env_or("WORKER_BACKUP_CRON", "0 15 3 * * *"),
+                backup_dir: env_or("BACKUP_DIR", "./data/backups"),
+                backup_retention: env_or_parse("BACKUP_RETENTION", 7_usize),
+                enable_inactive_user_purge: env_or_bool("ENABLE_INACTIVE_USER_PURGE_WORKER", false),
+                inactive_user_purge_cron: env_or("WORKER_INACTIVE_USER_PURGE_CRON", "0 0 4 * * *"),
+                inactive_user_retention_days: env_or_parse("INACTIVE_USER_RETENTION_DAYS", 730_u64),
+                inactive_user_action: env_or_parse(
+                    "INACTIVE_USER_ACTION",
+                    InactiveUserAction::Notify,
+                ),
+                inactive_user_purge_dry_run: env_or_bool("INACTIVE_USER_PURGE_DRY_RUN", true),
             },
             amas: AMASEnvConfig {
                 ensemble_enabled: env_or_bool("AMAS_ENSEMBLE_ENABLED", true),
@@ -220,10 +725,32 @@ impl Config {
             },
             llm: LLMConfig {
                 enabled: env_or_bool("LLM_ENABLED", false),
-                mock: env_or_bool("LLM_MOCK", true),
+                // 未设置 LLM_PROVIDER 时沿用旧的 LLM_MOCK 开关
+                provider: match env::var("LLM_PROVIDER") {
+                    Ok(_) => env_or_parse("LLM_PROVIDER", LlmBackend::Mock),
+                    Err(_) if env_or_bool("LLM_MOCK", true) => LlmBackend::Mock,
+                    Err(_) => LlmBackend::OpenAi,
+                },
                 api_url: env_or("LLM_API_URL", ""),
                 api_key: env_or("LLM_API_KEY", ""),
                 timeout_secs: env_or_parse("LLM_TIMEOUT_SECS", 30_u64),
+                models: LlmModels {
+                    default: env_or("LLM_MODEL", &LlmModels::default().default),
+                    embedding: env_or("LLM_MODEL_EMBEDDING", &LlmModels::default().embedding),
+                    etymology: env::var("LLM_MODEL_ETYMOLOGY").ok(),
+                    examples: env::var("LLM_MODEL_EXAMPLES").ok(),
+                    moderation: env::var("LLM_MODEL_MODERATION").ok(),
+                    advisor: env::var("LLM_MODEL_ADVISOR").ok(),
+                },
+                circuit: LlmCircuitConfig {
+                    failure_threshold: env_or_parse("LLM_CIRCUIT_FAILURE_THRESHOLD", 5_u32),
+                    cooldown_secs: env_or_parse("LLM_CIRCUIT_COOLDOWN_SECS", 60_u64),
+                },
+                budget: LlmBudget {
+                    daily_token_limit: env_or_parse("LLM_DAILY_TOKEN_BUDGET", 0_u64),
+                    input_price_per_1k: env_or_parse("LLM_PRICE_INPUT_PER_1K", 0.0_f64),
+                    output_price_per_1k: env_or_parse("LLM_PRICE_OUTPUT_PER_1K", 0.0_f64),
+                },
             },
             pagination: PaginationConfig {
                 default_page_size: env_or_parse("PAGINATION_DEFAULT_SIZE", 20_u64),
@@ -240,6 +767,76 @@ impl Config {
                 candidate_word_pool_size: env_or_parse("LIMITS_CANDIDATE_WORD_POOL_SIZE", 500_usize),
                 rate_limit_max_entries: env_or_parse("LIMITS_RATE_LIMIT_MAX_ENTRIES", 100_000_usize),
                 rate_limit_cleanup_interval_secs: env_or_parse("LIMITS_RATE_LIMIT_CLEANUP_INTERVAL_SECS", 300_u64),
+                broadcast_dedup_window_secs: env_or_parse("LIMITS_BROADCAST_DEDUP_WINDOW_SECS", 3600_u64),
+                semantic_search_probe_clusters: env_or_parse("LIMITS_SEMANTIC_SEARCH_PROBE_CLUSTERS", 3_usize),
+            },
+            password_policy: PasswordPolicy {
+                min_length: env_or_parse("PASSWORD_MIN_LENGTH", 8_usize),
+                max_length: env_or_parse("PASSWORD_MAX_LENGTH", 256_usize),
+                require_uppercase: env_or_bool("PASSWORD_REQUIRE_UPPERCASE", true),
+                require_lowercase: env_or_bool("PASSWORD_REQUIRE_LOWERCASE", true),
+                require_digit: env_or_bool("PASSWORD_REQUIRE_DIGIT", true),
+                require_symbol: env_or_bool("PASSWORD_REQUIRE_SYMBOL", false),
+                check_breached: env_or_bool("PASSWORD_CHECK_BREACHED", false),
+                breach_api_url: env_or(
+                    "PASSWORD_BREACH_API_URL",
+                    "https://api.pwnedpasswords.com/range/",
+                ),
+            },
+            lockout: LockoutPolicy {
+                max_failed_attempts: env_or_parse(
+                    "LOCKOUT_MAX_FAILED_ATTEMPTS",
+                    MAX_FAILED_LOGIN_ATTEMPTS,
+                ),
+                base_minutes: env_or_parse("LOCKOUT_BASE_MINUTES", LOCKOUT_DURATION_MINUTES),
+                escalation_enabled: env_or_bool("LOCKOUT_ESCALATION_ENABLED", false),
+                max_minutes: env_or_parse("LOCKOUT_MAX_MINUTES", 24 * 60_i64),
+                reset_after_hours: env_or_parse("LOCKOUT_RESET_AFTER_HOURS", 24_i64),
+            },
+            csp: {
+                let defaults = CspConfig::default();
+                CspConfig {
+                    raw_override: env::var("CSP_OVERRIDE")
+                        .ok()
+                        .filter(|raw| !raw.trim().is_empty()),
+                    default_src: env_or("CSP_DEFAULT_SRC", &defaults.default_src),
+                    script_src: env_or("CSP_SCRIPT_SRC", &defaults.script_src),
+                    style_src: env_or("CSP_STYLE_SRC", &defaults.style_src),
+                    font_src: env_or("CSP_FONT_SRC", &defaults.font_src),
+                    connect_src: env_or("CSP_CONNECT_SRC", &defaults.connect_src),
+                    img_src: env_or("CSP_IMG_SRC", &defaults.img_src),
+                    worker_src: env_or("CSP_WORKER_SRC", &defaults.worker_src),
+                    frame_ancestors: env_or("CSP_FRAME_ANCESTORS", &defaults.frame_ancestors),
+                }
+            },
+            static_assets: StaticAssetsConfig {
+                dir: PathBuf::from(env_or("STATIC_DIR", "static")),
+                html_max_age_secs: env_or_parse("STATIC_HTML_MAX_AGE_SECS", 0_u64),
+                assets_max_age_secs: env_or_parse("STATIC_ASSETS_MAX_AGE_SECS", 31_536_000_u64),
+                other_max_age_secs: env_or_parse("STATIC_OTHER_MAX_AGE_SECS", 3600_u64),
+            },
+            compression: CompressionConfig {
+                min_size_bytes: env_or_parse("COMPRESSION_MIN_SIZE_BYTES", 1024_u16),
+                content_types: match env::var("COMPRESSION_CONTENT_TYPES") {
+                    Ok(raw) => raw
+                        .split(',')
+                        .map(|t| t.trim().to_ascii_lowercase())
+                        .filter(|t| !t.is_empty())
+                        .collect(),
+                    Err(_) => CompressionConfig::default().content_types,
+                },
+            },
+            word_uniqueness: env_or_parse("WORD_UNIQUENESS", WordUniqueness::Off),
+            moderation: ModerationConfig {
+                mode: env_or_parse("MODERATION_MODE", ModerationMode::Wordlist),
+                blocked_terms: env::var("MODERATION_BLOCKED_TERMS")
+                    .map(|raw| {
+                        raw.split(',')
+                            .map(|t| t.trim().to_lowercase())
+                            .filter(|t| !t.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
             },
         };
 
@@ -279,6 +876,12 @@ impl Config {
                  Set a strong random secret via the ADMIN_JWT_SECRET environment variable."
             );
         }
+        if self.admin_totp_encryption_key.len() < 32 {
+            panic!(
+                "FATAL: ADMIN_TOTP_ENCRYPTION_KEY is too short (minimum 32 bytes). \
+                 Set a strong random key via the ADMIN_TOTP_ENCRYPTION_KEY environment variable."
+            );
+        }
         if self.refresh_jwt_secret.contains(Self::INSECURE_MARKER)
             || self.refresh_jwt_secret.contains(Self::MUST_CHANGE_MARKER)
         {
@@ -391,9 +994,21 @@ mod tests {
             "LLM_ENABLED",
             "LLM_TIMEOUT_SECS",
             "LLM_MOCK",
+            "LLM_PROVIDER",
+            "LLM_MODEL",
+            "LLM_MODEL_ETYMOLOGY",
             "JWT_SECRET",
             "ADMIN_JWT_SECRET",
             "REFRESH_JWT_SECRET",
+            "LOCKOUT_BASE_MINUTES",
+            "LOCKOUT_ESCALATION_ENABLED",
+            "LOCKOUT_MAX_MINUTES",
+            "CSP_OVERRIDE",
+            "CSP_FRAME_ANCESTORS",
+            "STATIC_DIR",
+            "STATIC_HTML_MAX_AGE_SECS",
+            "SLED_CACHE_CAPACITY_BYTES",
+            "SLED_FLUSH_EVERY_MS",
         ]
     }
 
@@ -465,6 +1080,122 @@ mod tests {
 
         let cfg = Config::from_env();
         assert!(cfg.llm.enabled);
-        assert!(!cfg.llm.mock);
+        assert_eq!(cfg.llm.provider, LlmBackend::OpenAi);
+    }
+
+    #[test]
+    fn llm_provider_and_model_overrides() {
+        let _guard = env_lock().lock().expect("env lock");
+        clear_keys(managed_keys());
+        set_test_secrets();
+
+        let cfg = Config::from_env();
+        assert_eq!(cfg.llm.provider, LlmBackend::Mock);
+        assert!(cfg.llm.models.etymology.is_none());
+
+        env::set_var("LLM_MOCK", "true");
+        env::set_var("LLM_PROVIDER", "local");
+        env::set_var("LLM_MODEL", "llama3");
+        env::set_var("LLM_MODEL_ETYMOLOGY", "qwen2.5:7b");
+
+        let cfg = Config::from_env();
+        assert_eq!(cfg.llm.provider, LlmBackend::Local);
+        assert_eq!(cfg.llm.models.default, "llama3");
+        assert_eq!(cfg.llm.models.etymology.as_deref(), Some("qwen2.5:7b"));
+    }
+
+    #[test]
+    fn lockout_escalation_doubles_until_cap() {
+        let _guard = env_lock().lock().expect("env lock");
+        clear_keys(managed_keys());
+        set_test_secrets();
+
+        let fixed = Config::from_env().lockout;
+        assert_eq!(fixed.lock_duration(3), chrono::Duration::minutes(15));
+
+        env::set_var("LOCKOUT_BASE_MINUTES", "10");
+        env::set_var("LOCKOUT_ESCALATION_ENABLED", "true");
+        env::set_var("LOCKOUT_MAX_MINUTES", "60");
+
+        let policy = Config::from_env().lockout;
+        assert_eq!(policy.lock_duration(1), chrono::Duration::minutes(10));
+        assert_eq!(policy.lock_duration(3), chrono::Duration::minutes(40));
+        assert_eq!(policy.lock_duration(8), chrono::Duration::minutes(60));
+    }
+
+    #[test]
+    fn csp_defaults_and_overrides() {
+        let _guard = env_lock().lock().expect("env lock");
+        clear_keys(managed_keys());
+        set_test_secrets();
+
+        let default_csp = Config::from_env().csp.header_value().unwrap();
+        assert_eq!(
+            default_csp,
+            "default-src 'self'; script-src 'self'; style-src 'self' https://fonts.googleapis.com; font-src https://fonts.gstatic.com; connect-src 'self'; img-src 'self' data: blob:; worker-src 'self' blob:; frame-ancestors 'none'; base-uri 'self'; form-action 'self'"
+        );
+
+        env::set_var("CSP_FRAME_ANCESTORS", "https://portal.example.com");
+        let csp = Config::from_env().csp.header_value().unwrap();
+        assert!(csp
+            .to_str()
+            .unwrap()
+            .contains("frame-ancestors https://portal.example.com;"));
+
+        env::set_var("CSP_FRAME_ANCESTORS", "'none'; script-src *");
+        assert!(Config::from_env().csp.header_value().is_err());
+
+        env::set_var("CSP_OVERRIDE", "default-src 'self'\nX-Injected: 1");
+        assert!(Config::from_env().csp.header_value().is_err());
+        env::set_var("CSP_OVERRIDE", "default-src *");
+        assert_eq!(
+            Config::from_env().csp.header_value().unwrap(),
+            "default-src *"
+        );
+    }
+
+    #[test]
+    fn static_assets_dir_and_cache_policy() {
+        let _guard = env_lock().lock().expect("env lock");
+        clear_keys(managed_keys());
+        set_test_secrets();
+
+        let defaults = Config::from_env().static_assets;
+        assert_eq!(defaults.index_file(), PathBuf::from("static/index.html"));
+        assert_eq!(
+            defaults.cache_control(true, false),
+            "no-cache, must-revalidate"
+        );
+        assert_eq!(
+            defaults.cache_control(false, true),
+            "public, max-age=31536000, immutable"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        env::set_var("STATIC_DIR", dir.path());
+        env::set_var("STATIC_HTML_MAX_AGE_SECS", "60");
+        let cfg = Config::from_env().static_assets;
+        assert!(cfg.validate().is_ok());
+        assert_eq!(cfg.cache_control(true, false), "public, max-age=60");
+
+        env::set_var("STATIC_DIR", dir.path().join("missing"));
+        assert!(Config::from_env().static_assets.validate().is_err());
+    }
+
+    #[test]
+    fn store_tuning_from_env() {
+        let _guard = env_lock().lock().expect("env lock");
+        clear_keys(managed_keys());
+        set_test_secrets();
+
+        let defaults = Config::from_env().store;
+        assert_eq!(defaults.cache_capacity_bytes, 512_000_000);
+        assert_eq!(defaults.flush_every_ms, 1000);
+
+        env::set_var("SLED_CACHE_CAPACITY_BYTES", "64000000");
+        env::set_var("SLED_FLUSH_EVERY_MS", "0");
+        let cfg = Config::from_env().store;
+        assert_eq!(cfg.cache_capacity_bytes, 64_000_000);
+        assert_eq!(cfg.flush_every_ms, 0);
     }
 }
@@ -19,12 +19,6 @@ pub const DEFAULT_MAX_USERS: u64 = 10_000;
 /// 默认分页大小（records / v1 routes）
 pub const DEFAULT_PAGE_SIZE_RECORDS: u64 = 50;
 
-/// 列表接口默认分页大小
-pub const DEFAULT_PAGE_SIZE: u64 = 20;
-
-/// 列表接口最大分页大小
-pub const MAX_PAGE_SIZE: u64 = 100;
-
 /// 新单词初始半衰期（小时）
 pub const DEFAULT_HALF_LIFE_HOURS: f64 = 24.0;
 
@@ -34,6 +28,21 @@ pub const DEFAULT_PREFERRED_HOURS: &[u8] = &[9, 14, 20];
 /// 混淆对列表最大返回数量
 pub const MAX_CONFUSION_PAIRS: usize = 100;
 
+/// 每个单词生成的例句数量
+pub const GENERATED_EXAMPLE_COUNT: usize = 3;
+
+/// 例句生成提示词版本，修改提示词时递增以淘汰旧缓存
+pub const EXAMPLE_PROMPT_VERSION: u32 = 1;
+
+/// 审核拒绝记录的保留天数，由 cache_cleanup worker 清理
+pub const MODERATION_REJECTION_RETENTION_DAYS: i64 = 90;
+
+/// 词源生成提示词版本，修改提示词时递增，etymology_generation worker 会重新生成旧条目
+pub const ETYMOLOGY_PROMPT_VERSION: u32 = 1;
+
+/// 词源生成被审核拒绝时写入缓存条目的退避标记，规则兜底覆盖占位条目时需保留
+pub const ETYMOLOGY_REJECTION_FIELDS: [&str; 2] = ["llmRejections", "llmRetryAfter"];
+
 /// 默认用户偏好主题
 pub const DEFAULT_THEME: &str = "light";
 
@@ -42,3 +51,12 @@ pub const DEFAULT_LANGUAGE: &str = "en";
 
 /// 每小时毫秒数
 pub const MILLIS_PER_HOUR: i64 = 3_600_000;
+
+/// 管理员 TOTP otpauth URI 中的签发方名称
+pub const ADMIN_TOTP_ISSUER: &str = "WordForge";
+
+/// 启用 TOTP 时生成的一次性备用码数量
+pub const ADMIN_TOTP_BACKUP_CODE_COUNT: usize = 10;
+
+/// 会话最近活跃时间的最小更新间隔（秒）
+pub const SESSION_TOUCH_INTERVAL_SECS: i64 = 60;
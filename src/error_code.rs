@@ -0,0 +1,236 @@
+//! 错误码注册表：所有 API 错误码在此集中登记，文档见 `docs/api/errors.md`。
+//! 编译期校验错误码唯一，且与变体名一一对应（`AuthEmailExists` ⇔ `AUTH_EMAIL_EXISTS`），
+//! 新增或改名都无法在不修改本表的情况下悄然复用或漂移。
+
+use serde::{Serialize, Serializer};
+
+macro_rules! error_codes {
+    ($($variant:ident => $code:literal,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $($variant,)*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+        }
+
+        const _: () = check_registry(&[$((stringify!($variant), $code),)*]);
+    };
+}
+
+error_codes! {
+    // 通用
+    BadRequest => "BAD_REQUEST",
+    ValidationError => "VALIDATION_ERROR",
+    InvalidRequestBody => "INVALID_REQUEST_BODY",
+    InvalidJsonSyntax => "INVALID_JSON_SYNTAX",
+    MissingContentType => "MISSING_CONTENT_TYPE",
+    Forbidden => "FORBIDDEN",
+    NotFound => "NOT_FOUND",
+    MethodNotAllowed => "METHOD_NOT_ALLOWED",
+    Conflict => "CONFLICT",
+    PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+    RateLimited => "RATE_LIMITED",
+    BatchTooLarge => "BATCH_TOO_LARGE",
+    InvalidDateRange => "INVALID_DATE_RANGE",
+    InvalidIdempotencyKey => "INVALID_IDEMPOTENCY_KEY",
+    IdempotencyKeyInProgress => "IDEMPOTENCY_KEY_IN_PROGRESS",
+    IdempotencyKeyReused => "IDEMPOTENCY_KEY_REUSED",
+    MaintenanceMode => "MAINTENANCE_MODE",
+    InternalError => "INTERNAL_ERROR",
+
+    // 认证
+    AuthUnauthorized => "AUTH_UNAUTHORIZED",
+    AuthRateLimited => "AUTH_RATE_LIMITED",
+    AuthAccountLocked => "AUTH_ACCOUNT_LOCKED",
+    AuthRefreshTokenReused => "AUTH_REFRESH_TOKEN_REUSED",
+    AuthEmailExists => "AUTH_EMAIL_EXISTS",
+    AuthInvalidEmail => "AUTH_INVALID_EMAIL",
+    AuthInvalidUsername => "AUTH_INVALID_USERNAME",
+    AuthInvalidResetToken => "AUTH_INVALID_RESET_TOKEN",
+    AuthExpiredResetToken => "AUTH_EXPIRED_RESET_TOKEN",
+
+    // 密码策略
+    PasswordTooShort => "PASSWORD_TOO_SHORT",
+    PasswordTooLong => "PASSWORD_TOO_LONG",
+    PasswordMissingUppercase => "PASSWORD_MISSING_UPPERCASE",
+    PasswordMissingLowercase => "PASSWORD_MISSING_LOWERCASE",
+    PasswordMissingDigit => "PASSWORD_MISSING_DIGIT",
+    PasswordMissingSymbol => "PASSWORD_MISSING_SYMBOL",
+    PasswordBreached => "PASSWORD_BREACHED",
+
+    // 用户与画像
+    UserInvalidEmail => "USER_INVALID_EMAIL",
+    UserInvalidUsername => "USER_INVALID_USERNAME",
+    UserEmailUnchanged => "USER_EMAIL_UNCHANGED",
+    UserInvalidEmailToken => "USER_INVALID_EMAIL_TOKEN",
+    UserExpiredEmailToken => "USER_EXPIRED_EMAIL_TOKEN",
+    InvalidWeek => "INVALID_WEEK",
+    AvatarEmpty => "AVATAR_EMPTY",
+    AvatarTooLarge => "AVATAR_TOO_LARGE",
+    AvatarInvalidType => "AVATAR_INVALID_TYPE",
+    AvatarInvalidImage => "AVATAR_INVALID_IMAGE",
+    InvalidRewardType => "INVALID_REWARD_TYPE",
+    InvalidPreferredHours => "INVALID_PREFERRED_HOURS",
+    InvalidSessionLength => "INVALID_SESSION_LENGTH",
+    InvalidSessionsPerDay => "INVALID_SESSIONS_PER_DAY",
+
+    // 通知
+    InvalidQuietHours => "INVALID_QUIET_HOURS",
+    InvalidTimezone => "INVALID_TIMEZONE",
+    InvalidLanguage => "INVALID_LANGUAGE",
+    InvalidTheme => "INVALID_THEME",
+    WebhookInvalidUrl => "WEBHOOK_INVALID_URL",
+
+    // 学习
+    InvalidCramUntil => "INVALID_CRAM_UNTIL",
+    LearningTooManyExcludes => "LEARNING_TOO_MANY_EXCLUDES",
+    LearningInvalidRecentPerformance => "LEARNING_INVALID_RECENT_PERFORMANCE",
+    LearningInvalidPlacementAnswers => "LEARNING_INVALID_PLACEMENT_ANSWERS",
+    LearningPlacementCompleted => "LEARNING_PLACEMENT_COMPLETED",
+
+    // 单词、词书与导入
+    WordNotFound => "WORD_NOT_FOUND",
+    WordsInvalidPayload => "WORDS_INVALID_PAYLOAD",
+    WordTextExists => "WORD_TEXT_EXISTS",
+    WordbookNotFound => "WORDBOOK_NOT_FOUND",
+    WordbookInvalidName => "WORDBOOK_INVALID_NAME",
+    WordbookTooManyWords => "WORDBOOK_TOO_MANY_WORDS",
+    ImportInvalidUrl => "IMPORT_INVALID_URL",
+    ImportBlockedUrl => "IMPORT_BLOCKED_URL",
+    ImportDnsFailed => "IMPORT_DNS_FAILED",
+    ImportFetchFailed => "IMPORT_FETCH_FAILED",
+    ImportReadFailed => "IMPORT_READ_FAILED",
+    ImportTooLarge => "IMPORT_TOO_LARGE",
+    WbCenterNotConfigured => "WB_CENTER_NOT_CONFIGURED",
+    WbCenterFetchFailed => "WB_CENTER_FETCH_FAILED",
+    WbCenterReadFailed => "WB_CENTER_READ_FAILED",
+    WbCenterParseFailed => "WB_CENTER_PARSE_FAILED",
+    WbCenterTooLarge => "WB_CENTER_TOO_LARGE",
+    WbCenterAlreadyImported => "WB_CENTER_ALREADY_IMPORTED",
+    InvalidQuery => "INVALID_QUERY",
+    LlmUnavailable => "LLM_UNAVAILABLE",
+    ContentRejected => "CONTENT_REJECTED",
+
+    // AMAS
+    AmasInvalidConfig => "AMAS_INVALID_CONFIG",
+    AmasInvalidColdStartPhase => "AMAS_INVALID_COLD_START_PHASE",
+    UnsupportedExportVersion => "UNSUPPORTED_EXPORT_VERSION",
+    InvalidScore => "INVALID_SCORE",
+    ConfirmationRequired => "CONFIRMATION_REQUIRED",
+    AdvisorySuggestionApplied => "ADVISORY_SUGGESTION_APPLIED",
+
+    // 管理后台
+    AdminAlreadyExists => "ADMIN_ALREADY_EXISTS",
+    AdminInvalidEmail => "ADMIN_INVALID_EMAIL",
+    AdminTotpRequired => "ADMIN_TOTP_REQUIRED",
+    AdminTotpInvalid => "ADMIN_TOTP_INVALID",
+    AdminTotpNotEnrolled => "ADMIN_TOTP_NOT_ENROLLED",
+    AdminTotpAlreadyEnabled => "ADMIN_TOTP_ALREADY_ENABLED",
+    InvalidDailyWords => "INVALID_DAILY_WORDS",
+    InvalidMaxUsers => "INVALID_MAX_USERS",
+    InvalidMasteryTarget => "INVALID_MASTERY_TARGET",
+    InvalidStudyMode => "INVALID_STUDY_MODE",
+    InvalidTitle => "INVALID_TITLE",
+    InvalidMessage => "INVALID_MESSAGE",
+    InvalidBroadcastTarget => "INVALID_BROADCAST_TARGET",
+    InvalidScheduledAt => "INVALID_SCHEDULED_AT",
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// 变体名按驼峰拆分转大写蛇形后必须等于错误码
+const fn matches_variant(variant: &str, code: &str) -> bool {
+    let (v, c) = (variant.as_bytes(), code.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    while i < v.len() {
+        let b = v[i];
+        if b.is_ascii_uppercase() && i > 0 {
+            if j >= c.len() || c[j] != b'_' {
+                return false;
+            }
+            j += 1;
+        }
+        if j >= c.len() || c[j] != b.to_ascii_uppercase() {
+            return false;
+        }
+        i += 1;
+        j += 1;
+    }
+    j == c.len()
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn check_registry(entries: &[(&str, &str)]) {
+    let mut i = 0;
+    while i < entries.len() {
+        assert!(
+            matches_variant(entries[i].0, entries[i].1),
+            "error code does not match its variant name"
+        );
+        let mut j = i + 1;
+        while j < entries.len() {
+            assert!(!str_eq(entries[i].1, entries[j].1), "duplicate error code");
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_code_is_documented() {
+        let docs = include_str!("../docs/api/errors.md");
+        for code in ErrorCode::ALL {
+            assert!(
+                docs.contains(&format!("`{code}`")),
+                "{code} missing from docs/api/errors.md"
+            );
+        }
+    }
+
+    #[test]
+    fn registry_checks_reject_drift() {
+        assert!(matches_variant("AuthEmailExists", "AUTH_EMAIL_EXISTS"));
+        assert!(!matches_variant("AuthEmailExists", "AUTH_EMAIL_EXIST"));
+        assert!(!matches_variant("AmasInvalidConfig", "INVALID_AMAS_CONFIG"));
+        assert_eq!(
+            serde_json::to_value(ErrorCode::BatchTooLarge).unwrap(),
+            "BATCH_TOO_LARGE"
+        );
+    }
+}
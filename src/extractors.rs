@@ -1,9 +1,18 @@
+use std::net::{IpAddr, SocketAddr};
+
 use axum::extract::rejection::JsonRejection;
-use axum::extract::{FromRequest, Request};
+use axum::extract::{ConnectInfo, FromRef, FromRequest, FromRequestParts, Request};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
 use axum::response::IntoResponse;
 use serde::de::DeserializeOwned;
 
-use crate::response::AppError;
+use crate::middleware::rate_limit::extract_client_ip;
+use crate::response::{AppError, ErrorCode};
+use crate::state::AppState;
+
+/// 保存到会话中的 User-Agent 最大长度
+const MAX_USER_AGENT_LEN: usize = 256;
 
 /// A wrapper around `axum::Json<T>` that returns `AppError` on deserialization failure
 /// instead of Axum's default plain-text rejection.
@@ -29,23 +38,31 @@ fn json_rejection_to_app_error(rejection: JsonRejection) -> AppError {
     match rejection {
         JsonRejection::JsonDataError(e) => {
             tracing::warn!(error = %e, "JSON data deserialization failed");
-            AppError::bad_request("INVALID_REQUEST_BODY", "请求体格式无效")
+            AppError::bad_request(ErrorCode::InvalidRequestBody, "请求体格式无效")
         }
         JsonRejection::JsonSyntaxError(e) => {
             tracing::warn!(error = %e, "JSON syntax parsing failed");
-            AppError::bad_request("INVALID_REQUEST_BODY", "请求体格式无效")
+            AppError::bad_request(ErrorCode::InvalidJsonSyntax, "请求体不是合法的 JSON")
         }
         JsonRejection::MissingJsonContentType(e) => {
             tracing::warn!(error = %e, "Missing or invalid JSON Content-Type");
-            AppError::bad_request("INVALID_REQUEST_BODY", "请求体格式无效")
+            AppError::bad_request(
+                ErrorCode::MissingContentType,
+                "请求头需包含 Content-Type: application/json",
+            )
+        }
+        JsonRejection::BytesRejection(e)
+            if e.status() == axum::http::StatusCode::PAYLOAD_TOO_LARGE =>
+        {
+            AppError::payload_too_large("请求体过大")
         }
         JsonRejection::BytesRejection(e) => {
             tracing::warn!(error = %e, "Failed to read request body bytes");
-            AppError::bad_request("INVALID_REQUEST_BODY", "请求体格式无效")
+            AppError::bad_request(ErrorCode::InvalidRequestBody, "请求体格式无效")
         }
         other => {
             tracing::warn!(error = %other, "Unexpected JSON body rejection");
-            AppError::bad_request("INVALID_REQUEST_BODY", "请求体格式无效")
+            AppError::bad_request(ErrorCode::InvalidRequestBody, "请求体格式无效")
         }
     }
 }
@@ -63,3 +80,36 @@ impl<T: serde::Serialize> IntoResponse for JsonBody<T> {
         axum::Json(self.0).into_response()
     }
 }
+
+/// 请求方的设备信息：User-Agent 与客户端 IP（按 `trust_proxy` 规则解析）
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: IpAddr,
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+    AppState: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+        let connect_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ci| ci.0.ip());
+        let ip = extract_client_ip(&parts.headers, app_state.config().trust_proxy, connect_ip);
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|ua| !ua.is_empty())
+            .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect());
+        Ok(Self { user_agent, ip })
+    }
+}
@@ -0,0 +1,352 @@
+//! 错误消息本地化：按请求的 `Accept-Language` 协商语言，以错误码为消息键查表渲染。
+//! 中文为默认语言，直接使用调用处的消息；其他语言缺少译文时回退到中文消息。
+//! 错误码本身与语言无关，客户端仍应以 `code` 判断错误类型。
+
+use std::future::Future;
+
+use crate::error_code::ErrorCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Zh,
+    En,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        if primary.eq_ignore_ascii_case("zh") {
+            Some(Locale::Zh)
+        } else if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    /// 取 q 值最高的受支持语言，q 值相同时按出现顺序；均不支持时为默认语言
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut best: Option<(f32, Locale)> = None;
+        for item in accept_language.split(',') {
+            let mut parts = item.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_tag) else {
+                continue;
+            };
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+            if q > 0.0 && best.map_or(true, |(best_q, _)| q > best_q) {
+                best = Some((q, locale));
+            }
+        }
+        best.map(|(_, locale)| locale).unwrap_or_default()
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// 在指定语言下执行请求处理，期间渲染的错误响应使用该语言
+pub async fn with_locale<F: Future>(locale: Locale, f: F) -> F::Output {
+    REQUEST_LOCALE.scope(locale, f).await
+}
+
+/// 当前请求的语言；不在请求作用域内（如后台任务、单元测试）时为默认语言
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// 按当前请求语言渲染错误消息，`default` 为调用处的中文消息
+pub fn localize(code: ErrorCode, default: &str) -> String {
+    translate(code, current_locale())
+        .unwrap_or(default)
+        .to_string()
+}
+
+pub fn translate(code: ErrorCode, locale: Locale) -> Option<&'static str> {
+    let table = match locale {
+        Locale::Zh => return None,
+        Locale::En => EN,
+    };
+    table
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, message)| *message)
+}
+
+/// `VALIDATION_ERROR` 的消息来自存储层校验，按原文返回
+const EN: &[(ErrorCode, &str)] = &[
+    (ErrorCode::BadRequest, "Bad request"),
+    (ErrorCode::InvalidRequestBody, "Invalid request body"),
+    (
+        ErrorCode::InvalidJsonSyntax,
+        "Request body is not valid JSON",
+    ),
+    (
+        ErrorCode::MissingContentType,
+        "Content-Type must be application/json",
+    ),
+    (ErrorCode::Forbidden, "Access denied"),
+    (ErrorCode::NotFound, "Resource not found"),
+    (ErrorCode::MethodNotAllowed, "Method not allowed"),
+    (ErrorCode::Conflict, "Resource conflict"),
+    (ErrorCode::PayloadTooLarge, "Request body too large"),
+    (ErrorCode::RateLimited, "Too many requests"),
+    (ErrorCode::BatchTooLarge, "Too many items in one batch"),
+    (ErrorCode::InvalidDateRange, "Invalid date range"),
+    (ErrorCode::InvalidIdempotencyKey, "Invalid idempotency key"),
+    (
+        ErrorCode::IdempotencyKeyInProgress,
+        "A request with this idempotency key is still in progress",
+    ),
+    (
+        ErrorCode::IdempotencyKeyReused,
+        "Idempotency key was already used with a different request body",
+    ),
+    (
+        ErrorCode::MaintenanceMode,
+        "The system is under maintenance, please try again later",
+    ),
+    (ErrorCode::InternalError, "Internal server error"),
+    (ErrorCode::AuthUnauthorized, "Authentication required"),
+    (
+        ErrorCode::AuthRateLimited,
+        "Too many authentication attempts, please try again later",
+    ),
+    (
+        ErrorCode::AuthAccountLocked,
+        "Account temporarily locked after too many failed logins",
+    ),
+    (
+        ErrorCode::AuthRefreshTokenReused,
+        "Refresh token has already been used",
+    ),
+    (ErrorCode::AuthEmailExists, "Email is already registered"),
+    (ErrorCode::AuthInvalidEmail, "Invalid email address"),
+    (ErrorCode::AuthInvalidUsername, "Invalid username"),
+    (
+        ErrorCode::AuthInvalidResetToken,
+        "Invalid password reset token",
+    ),
+    (
+        ErrorCode::AuthExpiredResetToken,
+        "Password reset token has expired",
+    ),
+    (ErrorCode::PasswordTooShort, "Password is too short"),
+    (ErrorCode::PasswordTooLong, "Password is too long"),
+    (
+        ErrorCode::PasswordMissingUppercase,
+        "Password must contain an uppercase letter",
+    ),
+    (
+        ErrorCode::PasswordMissingLowercase,
+        "Password must contain a lowercase letter",
+    ),
+    (
+        ErrorCode::PasswordMissingDigit,
+        "Password must contain a digit",
+    ),
+    (
+        ErrorCode::PasswordMissingSymbol,
+        "Password must contain a special character",
+    ),
+    (
+        ErrorCode::PasswordBreached,
+        "This password appears in a known data breach, please choose another",
+    ),
+    (ErrorCode::UserInvalidEmail, "Invalid email address"),
+    (ErrorCode::UserInvalidUsername, "Invalid username"),
+    (
+        ErrorCode::UserEmailUnchanged,
+        "New email is the same as the current one",
+    ),
+    (
+        ErrorCode::UserInvalidEmailToken,
+        "Invalid email verification token",
+    ),
+    (
+        ErrorCode::UserExpiredEmailToken,
+        "Email verification token has expired",
+    ),
+    (ErrorCode::InvalidWeek, "Week must be formatted as YYYY-Www"),
+    (ErrorCode::AvatarEmpty, "No avatar file uploaded"),
+    (ErrorCode::AvatarTooLarge, "Avatar file is too large"),
+    (ErrorCode::AvatarInvalidType, "Unsupported avatar format"),
+    (
+        ErrorCode::AvatarInvalidImage,
+        "Avatar image could not be decoded",
+    ),
+    (ErrorCode::InvalidRewardType, "Invalid reward type"),
+    (
+        ErrorCode::InvalidPreferredHours,
+        "Invalid preferred study hours",
+    ),
+    (ErrorCode::InvalidSessionLength, "Invalid session length"),
+    (ErrorCode::InvalidSessionsPerDay, "Invalid sessions per day"),
+    (ErrorCode::InvalidQuietHours, "Invalid quiet hours"),
+    (ErrorCode::InvalidTimezone, "Invalid timezone"),
+    (ErrorCode::InvalidLanguage, "Invalid language"),
+    (ErrorCode::InvalidTheme, "Invalid theme"),
+    (ErrorCode::WebhookInvalidUrl, "Invalid webhook URL"),
+    (ErrorCode::InvalidCramUntil, "Invalid cram deadline"),
+    (
+        ErrorCode::LearningTooManyExcludes,
+        "Too many excluded words",
+    ),
+    (
+        ErrorCode::LearningInvalidRecentPerformance,
+        "recentPerformance must be between 0 and 1",
+    ),
+    (
+        ErrorCode::LearningInvalidPlacementAnswers,
+        "Invalid placement test answers",
+    ),
+    (
+        ErrorCode::LearningPlacementCompleted,
+        "Placement test already completed",
+    ),
+    (ErrorCode::WordNotFound, "Word not found"),
+    (ErrorCode::WordsInvalidPayload, "Invalid word data"),
+    (
+        ErrorCode::WordTextExists,
+        "A word with the same spelling already exists",
+    ),
+    (ErrorCode::WordbookNotFound, "Wordbook not found"),
+    (ErrorCode::WordbookInvalidName, "Invalid wordbook name"),
+    (
+        ErrorCode::WordbookTooManyWords,
+        "Wordbook contains too many words",
+    ),
+    (ErrorCode::ImportInvalidUrl, "Invalid import URL"),
+    (ErrorCode::ImportBlockedUrl, "Import URL is not allowed"),
+    (ErrorCode::ImportDnsFailed, "Failed to resolve import URL"),
+    (ErrorCode::ImportFetchFailed, "Failed to fetch import URL"),
+    (ErrorCode::ImportReadFailed, "Failed to read import content"),
+    (ErrorCode::ImportTooLarge, "Import content is too large"),
+    (
+        ErrorCode::WbCenterNotConfigured,
+        "Wordbook center is not configured",
+    ),
+    (
+        ErrorCode::WbCenterFetchFailed,
+        "Failed to fetch from wordbook center",
+    ),
+    (
+        ErrorCode::WbCenterReadFailed,
+        "Failed to read wordbook center response",
+    ),
+    (
+        ErrorCode::WbCenterParseFailed,
+        "Failed to parse wordbook center response",
+    ),
+    (
+        ErrorCode::WbCenterTooLarge,
+        "Wordbook center response is too large",
+    ),
+    (
+        ErrorCode::WbCenterAlreadyImported,
+        "Wordbook has already been imported",
+    ),
+    (ErrorCode::InvalidQuery, "Invalid search query"),
+    (ErrorCode::LlmUnavailable, "LLM service is unavailable"),
+    (
+        ErrorCode::ContentRejected,
+        "Generated content was rejected by moderation",
+    ),
+    (ErrorCode::AmasInvalidConfig, "Invalid AMAS configuration"),
+    (
+        ErrorCode::AmasInvalidColdStartPhase,
+        "Cold-start phase is unreachable with the current configuration",
+    ),
+    (
+        ErrorCode::UnsupportedExportVersion,
+        "Unsupported export version",
+    ),
+    (ErrorCode::InvalidScore, "Score must be between 0 and 100"),
+    (
+        ErrorCode::ConfirmationRequired,
+        "Explicit confirmation is required",
+    ),
+    (
+        ErrorCode::AdvisorySuggestionApplied,
+        "This suggestion has already been applied",
+    ),
+    (ErrorCode::AdminAlreadyExists, "Admin already exists"),
+    (ErrorCode::AdminInvalidEmail, "Invalid admin email"),
+    (
+        ErrorCode::AdminTotpRequired,
+        "Two-factor authentication code required",
+    ),
+    (
+        ErrorCode::AdminTotpInvalid,
+        "Invalid two-factor authentication code",
+    ),
+    (
+        ErrorCode::AdminTotpNotEnrolled,
+        "Two-factor authentication is not enrolled",
+    ),
+    (
+        ErrorCode::AdminTotpAlreadyEnabled,
+        "Two-factor authentication is already enabled",
+    ),
+    (
+        ErrorCode::InvalidDailyWords,
+        "Invalid default daily word count",
+    ),
+    (ErrorCode::InvalidMaxUsers, "Invalid maximum user count"),
+    (
+        ErrorCode::InvalidMasteryTarget,
+        "Invalid default daily mastery target",
+    ),
+    (ErrorCode::InvalidStudyMode, "Invalid default study mode"),
+    (ErrorCode::InvalidTitle, "Invalid title"),
+    (ErrorCode::InvalidMessage, "Invalid message"),
+    (
+        ErrorCode::InvalidBroadcastTarget,
+        "Invalid broadcast target",
+    ),
+    (
+        ErrorCode::InvalidScheduledAt,
+        "Scheduled time must be within the next 365 days",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_by_quality_and_defaults_to_zh() {
+        assert_eq!(Locale::negotiate("en-US,en;q=0.9"), Locale::En);
+        assert_eq!(Locale::negotiate("zh-CN,zh;q=0.9,en;q=0.8"), Locale::Zh);
+        assert_eq!(
+            Locale::negotiate("fr;q=1.0, en;q=0.5, zh;q=0.3"),
+            Locale::En
+        );
+        assert_eq!(Locale::negotiate("en;q=0, zh-TW;q=0.2"), Locale::Zh);
+        assert_eq!(Locale::negotiate("fr-FR,de"), Locale::Zh);
+        assert_eq!(Locale::negotiate(""), Locale::Zh);
+    }
+
+    #[tokio::test]
+    async fn localize_uses_request_locale_with_fallback() {
+        assert_eq!(localize(ErrorCode::NotFound, "用户不存在"), "用户不存在");
+        with_locale(Locale::En, async {
+            assert_eq!(
+                localize(ErrorCode::NotFound, "用户不存在"),
+                "Resource not found"
+            );
+            // 无译文时回退到中文消息
+            assert_eq!(
+                localize(ErrorCode::ValidationError, "备份文件无效"),
+                "备份文件无效"
+            );
+        })
+        .await;
+    }
+}
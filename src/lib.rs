@@ -2,7 +2,9 @@ pub mod amas;
 pub mod auth;
 pub mod config;
 pub mod constants;
+pub mod error_code;
 pub mod extractors;
+pub mod i18n;
 pub mod logging;
 pub mod middleware;
 pub mod response;
@@ -1,5 +1,7 @@
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::http::{header, HeaderName, HeaderValue};
 use learning_backend::amas::config::AMASConfig;
@@ -14,12 +16,10 @@ use learning_backend::store::Store;
 use learning_backend::workers::WorkerManager;
 use tokio::sync::broadcast;
 use tower_http::catch_panic::CatchPanicLayer;
-use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::TraceLayer;
 
-const CSP_HEADER: &str = "default-src 'self'; script-src 'self'; style-src 'self' https://fonts.googleapis.com; font-src https://fonts.gstatic.com; connect-src 'self'; img-src 'self' data: blob:; worker-src 'self' blob:; frame-ancestors 'none'; base-uri 'self'; form-action 'self'";
 const HSTS_HEADER: &str = "max-age=31536000; includeSubDomains";
 
 #[tokio::main]
@@ -33,12 +33,28 @@ async fn main() {
         enable_file_logs: config.enable_file_logs,
         log_dir: config.log_dir.clone(),
     });
+
+    // 离线恢复：learning-backend restore <backup-file> [--force]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("restore") {
+        run_restore(&config, &args[1..]);
+        return;
+    }
+
     tracing::info!("Starting learning-backend");
 
-    // Validate LLM config at startup (panics if enabled=true, mock=false)
+    // Validate LLM config at startup (panics on invalid model names or missing API key)
     LlmProvider::validate_config(&config.llm);
 
-    let store = Arc::new(Store::open(&config.sled_path).expect("Failed to open sled database"));
+    let store = Arc::new(
+        Store::open_with_config(&config.sled_path, &config.store)
+            .expect("Failed to open sled database"),
+    );
+    tracing::info!(
+        cache_capacity_bytes = config.store.cache_capacity_bytes,
+        flush_every_ms = config.store.flush_every_ms,
+        "Store opened"
+    );
     store.run_migrations().expect("Failed to run migrations");
 
     let (shutdown_tx, _) = broadcast::channel::<()>(8);
@@ -64,13 +80,28 @@ async fn main() {
         shutdown_tx.subscribe(),
     ));
 
+    tokio::spawn(
+        amas_engine
+            .clone()
+            .run_temporal_flush_loop(shutdown_tx.subscribe()),
+    );
+
     let worker_handle = if config.worker.is_leader {
         let worker_manager = WorkerManager::new(
             store.clone(),
             amas_engine.clone(),
             shutdown_tx.subscribe(),
             &config.worker,
-        );
+        )
+        .with_readiness(state.readiness().clone())
+        .with_moderation(config.moderation.clone())
+        .with_broadcast_dedup_window(config.limits.broadcast_dedup_window_secs);
+        let worker_manager = if config.llm.enabled {
+            // 与请求路径共用同一实例，用量与熔断状态一致
+            worker_manager.with_llm_provider(state.llm().clone())
+        } else {
+            worker_manager
+        };
         Some(tokio::spawn(async move {
             if let Err(e) = worker_manager.start().await {
                 tracing::error!(error = %e, "Worker manager failed");
@@ -80,11 +111,18 @@ async fn main() {
         None
     };
 
+    if let Err(e) = config.static_assets.validate() {
+        panic!("FATAL: {e}. Set STATIC_DIR to the built frontend directory.");
+    }
+
     let cors_layer = build_cors_layer(&config);
+    let csp_header = config.csp.header_value().unwrap_or_else(|e| {
+        panic!("FATAL: Invalid Content-Security-Policy configuration: {e}. Fix the CSP_* environment variables.")
+    });
 
+    let in_flight = state.in_flight().clone();
     let app = build_router(state)
         .layer(cors_layer)
-        .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
         .layer(CatchPanicLayer::new())
         .layer(SetResponseHeaderLayer::overriding(
@@ -101,7 +139,7 @@ async fn main() {
         ))
         .layer(SetResponseHeaderLayer::overriding(
             HeaderName::from_static("content-security-policy"),
-            HeaderValue::from_static(CSP_HEADER),
+            csp_header,
         ))
         .layer(SetResponseHeaderLayer::overriding(
             HeaderName::from_static("strict-transport-security"),
@@ -114,11 +152,14 @@ async fn main() {
         .await
         .expect("Failed to bind TCP listener");
 
+    // 在启动前订阅，确保能收到停机信号并开始计算排空超时
+    let mut drain_rx = shutdown_tx.subscribe();
     let server_future = axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
     .with_graceful_shutdown(shutdown_signal(shutdown_tx.clone()));
+    let mut server_task = tokio::spawn(async move { server_future.await });
 
     if let Some(handle) = worker_handle {
         // Worker 作为独立后台任务运行，panic 仅记录错误，不终止 HTTP 服务器
@@ -132,10 +173,42 @@ async fn main() {
         });
     }
 
-    if let Err(e) = server_future.await {
-        tracing::error!(error = %e, "HTTP server crashed");
+    let server_result = tokio::select! {
+        result = &mut server_task => Some(result),
+        _ = drain_rx.recv() => {
+            let drain_timeout = Duration::from_secs(config.http_drain_timeout_secs);
+            tracing::info!(
+                in_flight = in_flight.count(),
+                timeout_secs = drain_timeout.as_secs(),
+                "Draining in-flight HTTP requests"
+            );
+            // serve 返回后已派生的连接任务仍可能在处理请求，需等计数归零再落盘
+            let drained = tokio::time::timeout(drain_timeout, async {
+                let result = (&mut server_task).await;
+                in_flight.wait_idle().await;
+                result
+            })
+            .await;
+            match drained {
+                Ok(result) => Some(result),
+                Err(_) => {
+                    tracing::warn!(
+                        in_flight = in_flight.count(),
+                        "HTTP drain timed out, flushing with requests still in flight"
+                    );
+                    None
+                }
+            }
+        }
+    };
+    match server_result {
+        Some(Ok(Err(e))) => tracing::error!(error = %e, "HTTP server crashed"),
+        Some(Err(e)) => tracing::error!(error = %e, "HTTP server task failed"),
+        _ => {}
     }
 
+    amas_engine.flush_temporal_profiles().await;
+
     tracing::info!("Flushing store before exit");
     if let Err(e) = store.flush() {
         tracing::error!(error = %e, "Failed to flush store before exit");
@@ -143,6 +216,35 @@ async fn main() {
     tracing::info!("Shutdown complete");
 }
 
+/// 服务器需处于停止状态（sled 数据库独占打开），恢复完成后执行迁移
+fn run_restore(config: &Config, args: &[String]) {
+    let force = args.iter().any(|a| a == "--force");
+    let Some(path) = args.iter().find(|a| !a.starts_with("--")) else {
+        eprintln!("Usage: learning-backend restore <backup-file> [--force]");
+        std::process::exit(2);
+    };
+
+    let store = Store::open_with_config(&config.sled_path, &config.store)
+        .expect("Failed to open sled database");
+    match store.restore_backup(Path::new(path), force) {
+        Ok(report) => tracing::info!(
+            path = %path,
+            trees = report.trees,
+            entries = report.entries,
+            "Backup restored"
+        ),
+        Err(e) => {
+            tracing::error!(error = %e, path = %path, "Restore failed");
+            std::process::exit(1);
+        }
+    }
+    store.run_migrations().expect("Failed to run migrations");
+    if let Err(e) = store.flush() {
+        tracing::error!(error = %e, "Failed to flush store after restore");
+        std::process::exit(1);
+    }
+}
+
 fn build_cors_layer(config: &Config) -> CorsLayer {
     if config.cors_origin.trim() == "*" {
         // 通配符模式仅用于开发环境，通配符与 credentials 互斥
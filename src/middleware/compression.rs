@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::http::{header, Extensions, HeaderMap, StatusCode, Version};
+use tower_http::compression::predicate::{Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+use crate::config::CompressionConfig;
+
+/// gzip/brotli 压缩层，按客户端 Accept-Encoding 协商编码
+pub fn compression_layer(cfg: &CompressionConfig) -> CompressionLayer<impl Predicate> {
+    let allowed: Arc<[String]> = cfg.content_types.clone().into();
+    let allowlist = move |_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| {
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| is_allowed(&allowed, ct))
+    };
+    CompressionLayer::new().compress_when(SizeAbove::new(cfg.min_size_bytes).and(allowlist))
+}
+
+fn is_allowed(allowed: &[String], content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    allowed.iter().any(|t| essence.starts_with(t.as_str()))
+}
@@ -0,0 +1,17 @@
+use axum::extract::Request;
+use axum::http::header::ACCEPT_LANGUAGE;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::i18n::{self, Locale};
+
+/// 按 `Accept-Language` 确定本次请求错误消息的语言，缺省为中文
+pub async fn locale_middleware(req: Request, next: Next) -> Response {
+    let locale = req
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(Locale::negotiate)
+        .unwrap_or_default();
+    i18n::with_locale(locale, next.run(req)).await
+}
@@ -0,0 +1,47 @@
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::response::{AppError, ErrorCode};
+use crate::state::AppState;
+
+/// 维护期间建议客户端的重试间隔（秒）
+const MAINTENANCE_RETRY_AFTER_SECS: u64 = 300;
+
+/// 维护模式下拒绝写请求，读请求照常处理。
+/// `/admin` 下的接口（含管理员登录与设置）不受限制，以便运维人员关闭维护模式。
+pub async fn maintenance_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !is_write_method(req.method()) || is_exempt_path(req.uri().path()) {
+        return Ok(next.run(req).await);
+    }
+    if !state.store().get_system_settings()?.maintenance_mode {
+        return Ok(next.run(req).await);
+    }
+
+    let mut response =
+        AppError::service_unavailable(ErrorCode::MaintenanceMode, "系统正在维护中，请稍后重试")
+            .into_response();
+    response.headers_mut().insert(
+        header::RETRY_AFTER,
+        HeaderValue::from(MAINTENANCE_RETRY_AFTER_SECS),
+    );
+    Ok(response)
+}
+
+fn is_write_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+fn is_exempt_path(path: &str) -> bool {
+    // 挂在 /api 下时看到的是去掉前缀后的路径
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    path == "/admin" || path.starts_with("/admin/")
+}
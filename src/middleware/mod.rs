@@ -1,2 +1,6 @@
+pub mod compression;
+pub mod in_flight;
+pub mod locale;
+pub mod maintenance;
 pub mod rate_limit;
 pub mod request_id;
@@ -11,7 +11,8 @@ use axum::Json;
 use std::net::SocketAddr;
 use tokio::sync::{broadcast, Mutex};
 
-use crate::response::{AppError, ErrorBody};
+use crate::i18n::localize;
+use crate::response::{AppError, ErrorBody, ErrorCode};
 use crate::state::AppState;
 
 const NUM_SHARDS: usize = 16;
@@ -198,9 +199,10 @@ pub async fn rate_limit_middleware(
             axum::http::StatusCode::TOO_MANY_REQUESTS,
             Json(ErrorBody {
                 success: false,
-                code: "RATE_LIMITED".to_string(),
-                message: "请求过于频繁".to_string(),
+                code: ErrorCode::RateLimited,
+                message: localize(ErrorCode::RateLimited, "请求过于频繁"),
                 trace_id: None,
+                details: None,
             }),
         )
             .into_response();
@@ -323,9 +325,10 @@ pub async fn auth_rate_limit_middleware(
             axum::http::StatusCode::TOO_MANY_REQUESTS,
             Json(ErrorBody {
                 success: false,
-                code: "AUTH_RATE_LIMITED".to_string(),
-                message: "认证尝试次数过多，请稍后再试".to_string(),
+                code: ErrorCode::AuthRateLimited,
+                message: localize(ErrorCode::AuthRateLimited, "认证尝试次数过多，请稍后再试"),
                 trace_id: None,
+                details: None,
             }),
         )
             .into_response();
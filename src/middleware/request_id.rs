@@ -5,7 +5,7 @@ use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use http_body_util::BodyExt;
 
-use crate::response::ErrorBody;
+use crate::response::{AppError, ErrorBody, ErrorCode};
 
 pub async fn request_id_middleware(req: Request, next: Next) -> Response {
     let request_id = req
@@ -42,6 +42,13 @@ pub async fn request_id_middleware(req: Request, next: Next) -> Response {
         response.headers_mut().insert("x-request-id", value);
     }
 
+    if let Some(error) = response.extensions_mut().remove::<AppError>() {
+        error.log(&request_id);
+        let body = serde_json::to_vec(&error.to_body(Some(request_id))).unwrap_or_default();
+        let (parts, _) = response.into_parts();
+        return Response::from_parts(parts, Body::from(body));
+    }
+
     if !response.status().is_success() {
         if is_json_content_type(&response) {
             // Existing JSON error: inject traceId
@@ -110,25 +117,26 @@ async fn wrap_plain_error_as_json(response: Response, request_id: &str) -> Respo
         status,
         axum::Json(ErrorBody {
             success: false,
-            code: code.to_string(),
+            code,
             message,
             trace_id: Some(request_id.to_string()),
+            details: None,
         }),
     )
         .into_response()
 }
 
-fn error_code_for_status(status: StatusCode) -> &'static str {
+fn error_code_for_status(status: StatusCode) -> ErrorCode {
     match status {
-        StatusCode::BAD_REQUEST => "BAD_REQUEST",
-        StatusCode::UNAUTHORIZED => "AUTH_UNAUTHORIZED",
-        StatusCode::FORBIDDEN => "FORBIDDEN",
-        StatusCode::NOT_FOUND => "NOT_FOUND",
-        StatusCode::METHOD_NOT_ALLOWED => "METHOD_NOT_ALLOWED",
-        StatusCode::CONFLICT => "CONFLICT",
-        StatusCode::PAYLOAD_TOO_LARGE => "PAYLOAD_TOO_LARGE",
-        StatusCode::TOO_MANY_REQUESTS => "RATE_LIMITED",
-        _ => "INTERNAL_ERROR",
+        StatusCode::BAD_REQUEST => ErrorCode::BadRequest,
+        StatusCode::UNAUTHORIZED => ErrorCode::AuthUnauthorized,
+        StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+        StatusCode::CONFLICT => ErrorCode::Conflict,
+        StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+        StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+        _ => ErrorCode::InternalError,
     }
 }
 
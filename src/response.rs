@@ -3,6 +3,8 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Serialize;
 
+pub use crate::error_code::ErrorCode;
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiResponse<T: Serialize> {
@@ -18,123 +20,187 @@ pub struct PaginatedResponse<T: Serialize> {
     pub page: u64,
     pub per_page: u64,
     pub total_pages: u64,
+    pub has_next: bool,
+}
+
+impl<T: Serialize> PaginatedResponse<T> {
+    pub fn new(data: Vec<T>, total: u64, page: u64, per_page: u64) -> Self {
+        let total_pages = if per_page > 0 {
+            total.div_ceil(per_page)
+        } else {
+            0
+        };
+        Self {
+            data,
+            total,
+            page,
+            per_page,
+            total_pages,
+            has_next: page < total_pages,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorBody {
     pub success: bool,
-    pub code: String,
+    pub code: ErrorCode,
     pub message: String,
     pub trace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone)]
 pub struct AppError {
     pub status: StatusCode,
-    pub code: String,
+    pub code: ErrorCode,
     pub message: String,
     pub is_operational: bool,
+    /// 供客户端展示的附加结构化信息（如锁定剩余时间）
+    pub details: Option<serde_json::Value>,
 }
 
 impl AppError {
-    pub fn bad_request(code: &str, message: &str) -> Self {
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn bad_request(code: ErrorCode, message: &str) -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
-            code: code.to_string(),
+            code,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn unauthorized(message: &str) -> Self {
         Self {
             status: StatusCode::UNAUTHORIZED,
-            code: "AUTH_UNAUTHORIZED".to_string(),
+            code: ErrorCode::AuthUnauthorized,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn forbidden(message: &str) -> Self {
         Self {
             status: StatusCode::FORBIDDEN,
-            code: "FORBIDDEN".to_string(),
+            code: ErrorCode::Forbidden,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn not_found(message: &str) -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
-            code: "NOT_FOUND".to_string(),
+            code: ErrorCode::NotFound,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
-    pub fn conflict(code: &str, message: &str) -> Self {
+    pub fn conflict(code: ErrorCode, message: &str) -> Self {
         Self {
             status: StatusCode::CONFLICT,
-            code: code.to_string(),
+            code,
+            message: message.to_string(),
+            is_operational: true,
+            details: None,
+        }
+    }
+
+    pub fn unprocessable(code: ErrorCode, message: &str) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            code,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn too_many_requests(message: &str) -> Self {
         Self {
             status: StatusCode::TOO_MANY_REQUESTS,
-            code: "RATE_LIMITED".to_string(),
+            code: ErrorCode::RateLimited,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn payload_too_large(message: &str) -> Self {
         Self {
             status: StatusCode::PAYLOAD_TOO_LARGE,
-            code: "PAYLOAD_TOO_LARGE".to_string(),
+            code: ErrorCode::PayloadTooLarge,
+            message: message.to_string(),
+            is_operational: true,
+            details: None,
+        }
+    }
+
+    pub fn service_unavailable(code: ErrorCode, message: &str) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            code,
             message: message.to_string(),
             is_operational: true,
+            details: None,
         }
     }
 
     pub fn internal(message: &str) -> Self {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
-            code: "INTERNAL_ERROR".to_string(),
+            code: ErrorCode::InternalError,
             message: message.to_string(),
             is_operational: false,
+            details: None,
         }
     }
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let exposed_message = if self.is_operational {
-            self.message.clone()
+impl AppError {
+    /// 渲染响应体：消息按当前请求语言本地化，非业务错误不暴露内部信息
+    pub fn to_body(&self, trace_id: Option<String>) -> ErrorBody {
+        let message = if self.is_operational {
+            crate::i18n::localize(self.code, &self.message)
         } else {
-            "服务器内部错误".to_string()
+            crate::i18n::localize(ErrorCode::InternalError, "服务器内部错误")
         };
+        ErrorBody {
+            success: false,
+            code: self.code,
+            message,
+            trace_id,
+            details: self.details.clone(),
+        }
+    }
 
+    pub fn log(&self, request_id: &str) {
         if self.is_operational {
-            tracing::warn!(status = %self.status, code = %self.code, error = %self.message, "API error");
+            tracing::warn!(request_id, status = %self.status, code = %self.code, error = %self.message, "API error");
         } else {
-            tracing::error!(status = %self.status, code = %self.code, error = %self.message, "Internal API error");
+            tracing::error!(request_id, status = %self.status, code = %self.code, error = %self.message, "Internal API error");
         }
+    }
+}
 
-        (
-            self.status,
-            Json(ErrorBody {
-                success: false,
-                code: self.code,
-                message: exposed_message,
-                trace_id: None,
-            }),
-        )
-            .into_response()
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Json(self.to_body(None))).into_response();
+        // 由 request_id 中间件回填 traceId 并记录带请求 ID 的日志
+        response.extensions_mut().insert(self);
+        response
     }
 }
 
@@ -145,7 +211,7 @@ impl From<crate::store::StoreError> for AppError {
     fn from(value: crate::store::StoreError) -> Self {
         match &value {
             crate::store::StoreError::Validation(msg) => {
-                AppError::bad_request("VALIDATION_ERROR", msg)
+                AppError::bad_request(ErrorCode::ValidationError, msg)
             }
             _ => AppError::internal(&value.to_string()),
         }
@@ -178,24 +244,7 @@ pub fn paginated<T: Serialize>(
     page: u64,
     per_page: u64,
 ) -> impl IntoResponse {
-    let total_pages = if per_page > 0 {
-        total.div_ceil(per_page)
-    } else {
-        0
-    };
-    (
-        StatusCode::OK,
-        Json(ApiResponse {
-            success: true,
-            data: PaginatedResponse {
-                data,
-                total,
-                page,
-                per_page,
-                total_pages,
-            },
-        }),
-    )
+    ok(PaginatedResponse::new(data, total, page, per_page))
 }
 
 #[cfg(test)]
@@ -216,19 +265,19 @@ mod tests {
 
     #[tokio::test]
     async fn bad_request_keeps_message() {
-        let resp = AppError::bad_request("BAD_INPUT", "invalid email").into_response();
+        let resp = AppError::bad_request(ErrorCode::BadRequest, "invalid email").into_response();
         let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
         let text = String::from_utf8(body.to_vec()).unwrap();
         assert!(text.contains("invalid email"));
-        assert!(text.contains("BAD_INPUT"));
+        assert!(text.contains("BAD_REQUEST"));
     }
 
     #[tokio::test]
     async fn error_field_is_code() {
-        let resp = AppError::bad_request("BAD_INPUT", "invalid email").into_response();
+        let resp = AppError::bad_request(ErrorCode::BadRequest, "invalid email").into_response();
         let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
         let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["code"], "BAD_INPUT");
+        assert_eq!(json["code"], "BAD_REQUEST");
         assert!(json.get("error").is_none());
     }
 
@@ -240,4 +289,14 @@ mod tests {
         assert_eq!(json["code"], "NOT_FOUND");
         assert!(json.get("error").is_none());
     }
+
+    #[test]
+    fn pagination_reports_total_pages_and_has_next() {
+        let page = PaginatedResponse::new(vec![1, 2], 5, 2, 2);
+        assert_eq!((page.total_pages, page.has_next), (3, true));
+        let last = PaginatedResponse::new(vec![5], 5, 3, 2);
+        assert_eq!((last.total_pages, last.has_next), (3, false));
+        let empty = PaginatedResponse::<u8>::new(vec![], 0, 1, 20);
+        assert_eq!((empty.total_pages, empty.has_next), (0, false));
+    }
 }
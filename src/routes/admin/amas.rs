@@ -1,19 +1,29 @@
-use axum::extract::{Query, State};
+use std::collections::BTreeMap;
+
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::routing::{get, post};
 use axum::Router;
 
 use crate::extractors::JsonBody;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::amas::types::RawEvent;
+use crate::amas::config::FeatureFlagOverrides;
+use crate::amas::memory::mastery::WordMasteryState;
+use crate::amas::types::{
+    AlgorithmId, ColdStartPhase, MasteryLevel, RawEvent, StrategyParams, WordMasteryDecision,
+};
 use crate::auth::{AdminAuthUser, AuthUser};
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/process-event", post(process_event))
-        .route("/batch-process", post(batch_process))
+        .route(
+            "/batch-process",
+            post(batch_process).layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
         // B18-B24: AMAS query endpoints
         .route("/state", get(get_amas_state))
         .route("/strategy", get(get_strategy))
@@ -31,6 +41,19 @@ pub fn admin_router() -> Router<AppState> {
         .route("/config", get(get_config).put(update_config))
         .route("/metrics", get(get_metrics))
         .route("/monitoring", get(get_monitoring_events))
+        .route("/state-repairs", get(get_state_repairs))
+        .route("/replay/:user_id", post(replay_user_records))
+        .route("/:user_id/reset-trust", post(reset_user_trust))
+        .route("/:user_id/cold-start-phase", post(set_cold_start_phase))
+        .route(
+            "/:user_id/feature-flags",
+            get(get_user_feature_flags).put(set_user_feature_flags),
+        )
+        .route("/advisories", get(list_advisories))
+        .route(
+            "/advisories/:id/suggestions/:index/apply",
+            post(apply_advisory_suggestion),
+        )
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -98,7 +121,7 @@ async fn batch_process(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.events.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量处理事件数量上限为{}",
                 state.config().limits.max_batch_size
@@ -133,13 +156,13 @@ async fn update_config(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     // 先进行配置验证
     cfg.validate()
-        .map_err(|e| AppError::bad_request("AMAS_INVALID_CONFIG", &e))?;
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
 
     state
         .amas()
         .reload_config(cfg)
         .await
-        .map_err(|e| AppError::bad_request("AMAS_INVALID_CONFIG", &e))?;
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
 
     tracing::info!(
         admin_id = %admin.admin_id,
@@ -173,6 +196,361 @@ async fn get_monitoring_events(
     Ok(ok(events))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateRepairsQuery {
+    user_id: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn get_state_repairs(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<StateRepairsQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let repairs = state
+        .store()
+        .list_algo_state_repairs(query.user_id.as_deref(), limit)?;
+    Ok(ok(repairs))
+}
+
+const REPLAY_DEFAULT_LIMIT: usize = 1000;
+const REPLAY_MAX_LIMIT: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+struct ReplayQuery {
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayStep {
+    record_id: String,
+    word_id: String,
+    is_correct: bool,
+    created_at: chrono::DateTime<chrono::Utc>,
+    strategy: StrategyParams,
+    word_mastery: Option<WordMasteryDecision>,
+    /// 作答时记录的主导算法，旧记录为空
+    stored_algorithm: Option<AlgorithmId>,
+    replayed_algorithm: Option<AlgorithmId>,
+    algorithm_changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MasteryComparison {
+    word_id: String,
+    stored: Option<MasteryLevel>,
+    replayed: MasteryLevel,
+}
+
+// 以全新状态从第一条记录起按时间顺序重放（不落库），返回 [offset, offset+limit) 区间的逐条结果
+// 及与作答时记录的主导算法的差异；重放到最后一条记录时再与已存储的策略和掌握度对比
+async fn replay_user_records(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<ReplayQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(REPLAY_DEFAULT_LIMIT)
+        .clamp(1, REPLAY_MAX_LIMIT);
+    let report =
+        tokio::task::spawn_blocking(move || replay_records(&state, &user_id, offset, limit))
+            .await
+            .map_err(|e| AppError::internal(&format!("Replay task failed: {e}")))??;
+    Ok(ok(report))
+}
+
+fn replay_records(
+    state: &AppState,
+    user_id: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<serde_json::Value, AppError> {
+    let end = offset.saturating_add(limit);
+    let mut records = state
+        .store()
+        .get_user_records_oldest_first(user_id, end.saturating_add(1))?;
+    let has_more = records.len() > end;
+    records.truncate(end);
+
+    let events = records
+        .iter()
+        .map(|r| {
+            let event = RawEvent {
+                word_id: r.word_id.clone(),
+                is_correct: r.is_correct,
+                response_time_ms: r.response_time_ms,
+                session_id: r.session_id.clone(),
+                ..RawEvent::default()
+            };
+            (event, r.created_at)
+        })
+        .collect();
+    let results = state.amas().replay_events(user_id, events)?;
+
+    let steps: Vec<ReplayStep> = records
+        .iter()
+        .zip(results.iter())
+        .skip(offset)
+        .map(|(record, result)| {
+            let replayed_algorithm = result.dominant_algorithm();
+            ReplayStep {
+                record_id: record.id.clone(),
+                word_id: record.word_id.clone(),
+                is_correct: record.is_correct,
+                created_at: record.created_at,
+                strategy: result.strategy.clone(),
+                word_mastery: result.word_mastery.clone(),
+                algorithm_changed: record.algorithm_id.is_some()
+                    && record.algorithm_id != replayed_algorithm,
+                stored_algorithm: record.algorithm_id,
+                replayed_algorithm,
+            }
+        })
+        .collect();
+
+    // 未重放到最后一条记录时，与当前存储状态的对比没有意义
+    let (stored_strategy, mastery) = if has_more {
+        (None, None)
+    } else {
+        let export = state.amas().export_user_state(user_id)?;
+        let stored_strategy = state.amas().compute_strategy_from_state(&export.user_state);
+        let mut replayed_levels = BTreeMap::new();
+        for decision in results.iter().filter_map(|r| r.word_mastery.as_ref()) {
+            replayed_levels.insert(decision.word_id.clone(), decision.mastery_level.clone());
+        }
+        let mastery: Vec<MasteryComparison> = replayed_levels
+            .into_iter()
+            .map(|(word_id, replayed)| MasteryComparison {
+                stored: export
+                    .mastery_states
+                    .get(&word_id)
+                    .and_then(|v| serde_json::from_value::<WordMasteryState>(v.clone()).ok())
+                    .map(|s| s.mastery_level),
+                word_id,
+                replayed,
+            })
+            .collect();
+        (Some(stored_strategy), Some(mastery))
+    };
+
+    Ok(serde_json::json!({
+        "userId": user_id,
+        "offset": offset,
+        "replayedCount": results.len(),
+        "hasMore": has_more,
+        "algorithmChanges": steps.iter().filter(|s| s.algorithm_changed).count(),
+        "steps": steps,
+        "storedStrategy": stored_strategy,
+        "replayedStrategy": results.last().map(|r| &r.strategy),
+        "mastery": mastery,
+    }))
+}
+
+// 只重置集成信任分数，让集成从中立状态重新收敛，不影响学习进度
+async fn reset_user_trust(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    let previous = state.amas().reset_trust_scores(&user_id).await?;
+
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "reset_trust",
+        user_id = %user_id,
+        "管理员重置用户信任分数"
+    );
+    Ok(ok(serde_json::json!({
+        "userId": user_id,
+        "previous": previous,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ColdStartPhaseRequest {
+    phase: ColdStartPhase,
+}
+
+// 直接切换冷启动阶段，便于复现卡在某一阶段的问题；只改写事件计数，不重放历史
+async fn set_cold_start_phase(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    JsonBody(req): JsonBody<ColdStartPhaseRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    let (previous_phase, previous_event_count) = state
+        .amas()
+        .set_cold_start_phase(&user_id, &req.phase)
+        .await?;
+
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "set_cold_start_phase",
+        user_id = %user_id,
+        from = ?previous_phase,
+        to = ?req.phase,
+        "管理员调整用户冷启动阶段"
+    );
+    Ok(ok(serde_json::json!({
+        "userId": user_id,
+        "previousPhase": previous_phase,
+        "previousEventCount": previous_event_count,
+        "phase": req.phase,
+        "totalEventCount": state.amas().get_user_state(&user_id)?.total_event_count,
+    })))
+}
+
+async fn user_feature_flags_response(
+    state: &AppState,
+    user_id: &str,
+) -> Result<serde_json::Value, AppError> {
+    let overrides = state
+        .store()
+        .get_feature_flag_overrides(user_id)?
+        .unwrap_or_default();
+    let mut effective = state.amas().get_config().await.feature_flags;
+    overrides.apply(&mut effective);
+    Ok(serde_json::json!({
+        "userId": user_id,
+        "overrides": overrides,
+        "effective": effective,
+    }))
+}
+
+async fn get_user_feature_flags(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    Ok(ok(user_feature_flags_response(&state, &user_id).await?))
+}
+
+// 整体替换用户的算法开关覆盖，字段为 null 时沿用全局开关，全部为 null 即清除
+async fn set_user_feature_flags(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    JsonBody(overrides): JsonBody<FeatureFlagOverrides>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    state
+        .store()
+        .set_feature_flag_overrides(&user_id, &overrides)?;
+
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "set_user_feature_flags",
+        user_id = %user_id,
+        overrides = ?overrides,
+        "管理员设置用户算法开关覆盖"
+    );
+    Ok(ok(user_feature_flags_response(&state, &user_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct AdvisoriesQuery {
+    limit: Option<usize>,
+}
+
+async fn list_advisories(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<AdvisoriesQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    Ok(ok(state.store().list_llm_advisories(limit)?))
+}
+
+#[derive(Debug, Deserialize)]
+struct ApplySuggestionRequest {
+    #[serde(default)]
+    confirm: bool,
+}
+
+/// 应用一条顾问建议：改写当前配置的对应字段后走与 `PUT /config` 相同的校验与重载
+async fn apply_advisory_suggestion(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path((id, index)): Path<(String, usize)>,
+    JsonBody(req): JsonBody<ApplySuggestionRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if !req.confirm {
+        return Err(AppError::bad_request(
+            ErrorCode::ConfirmationRequired,
+            "应用配置建议需显式确认（confirm: true）",
+        ));
+    }
+    let store = state.store();
+    let advisory = store
+        .get_llm_advisory(&id)?
+        .ok_or_else(|| AppError::not_found("建议不存在"))?;
+    let suggestion = advisory
+        .output
+        .suggestions
+        .get(index)
+        .ok_or_else(|| AppError::not_found("建议不存在"))?;
+    if suggestion.applied_at.is_some() {
+        return Err(AppError::conflict(
+            ErrorCode::AdvisorySuggestionApplied,
+            "该建议已应用",
+        ));
+    }
+
+    let mut value = serde_json::to_value(state.amas().get_config().await)
+        .map_err(|e| AppError::internal(&e.to_string()))?;
+    let field = value
+        .pointer_mut(&suggestion.json_pointer())
+        .ok_or_else(|| AppError::bad_request(ErrorCode::AmasInvalidConfig, "配置字段不存在"))?;
+    let previous = std::mem::replace(field, suggestion.proposed.clone());
+    let cfg: crate::amas::config::AMASConfig = serde_json::from_value(value)
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e.to_string()))?;
+    cfg.validate()
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
+    state
+        .amas()
+        .reload_config(cfg)
+        .await
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
+
+    let advisory = store.mark_llm_advisory_suggestion_applied(&id, index, &admin.admin_id)?;
+    let suggestion = &advisory.output.suggestions[index];
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "apply_advisory_suggestion",
+        advisory_id = %id,
+        path = %suggestion.path,
+        "管理员应用 LLM 顾问配置建议"
+    );
+    Ok(ok(serde_json::json!({
+        "path": suggestion.path,
+        "previous": previous,
+        "applied": suggestion.proposed,
+        "advisory": advisory,
+    })))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct VisualFatigueRequest {
@@ -186,7 +564,7 @@ async fn report_visual_fatigue(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if !(0.0..=100.0).contains(&req.score) {
         return Err(AppError::bad_request(
-            "INVALID_SCORE",
+            ErrorCode::InvalidScore,
             "分数必须在0到100之间",
         ));
     }
@@ -1,15 +1,32 @@
-use axum::extract::State;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderValue};
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::Router;
 
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::amas::evaluation;
 use crate::auth::AdminAuthUser;
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::state::AppState;
+use crate::store::operations::monitoring::MonitoringDailySummary;
+use crate::store::operations::retention::{RetentionCohort, RetentionReport};
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/engagement", get(user_engagement))
         .route("/learning", get(learning_metrics))
+        .route("/monitoring", get(monitoring_summaries))
+        .route("/algorithm-evaluation", get(algorithm_evaluation))
+        .route("/llm-usage", get(llm_usage))
+        .route("/retention", get(cohort_retention))
+        .route("/funnel", get(onboarding_funnel))
+        .route("/word-difficulty", get(word_difficulty))
 }
 
 // B61: User engagement analytics
@@ -49,3 +66,396 @@ async fn learning_metrics(
         "overallAccuracy": if total_records > 0 { total_correct as f64 / total_records as f64 } else { 0.0 },
     })))
 }
+
+#[derive(Debug, Deserialize)]
+struct MonitoringSummaryQuery {
+    days: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitoringSummaryView {
+    #[serde(flatten)]
+    summary: MonitoringDailySummary,
+    latency_p50: i64,
+    latency_p95: i64,
+    latency_p99: i64,
+}
+
+// 监控事件按天汇总（由 monitoring_aggregate worker 生成），按日期降序
+async fn monitoring_summaries(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<MonitoringSummaryQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let days = query.days.unwrap_or(7).clamp(1, 90);
+    let summaries: Vec<MonitoringSummaryView> = state
+        .store()
+        .list_monitoring_daily_summaries(days)?
+        .into_iter()
+        .map(|summary| MonitoringSummaryView {
+            latency_p50: summary.latency_percentile(50.0),
+            latency_p95: summary.latency_percentile(95.0),
+            latency_p99: summary.latency_percentile(99.0),
+            summary,
+        })
+        .collect();
+    Ok(ok(summaries))
+}
+
+/// 算法评估与引导漏斗最多覆盖的天数
+const MAX_EVALUATION_DAYS: i64 = 180;
+
+#[derive(Debug, Deserialize)]
+struct DateRangeQuery {
+    /// 起始日期（含），默认 `to` 前 30 天
+    from: Option<NaiveDate>,
+    /// 结束日期（含），默认今天
+    to: Option<NaiveDate>,
+}
+
+impl DateRangeQuery {
+    /// 转为 `[from, to)` 的 UTC 时间区间
+    fn resolve(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+        let to = self.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = self.from.unwrap_or(to - Duration::days(29));
+        let days = (to - from).num_days() + 1;
+        if !(1..=MAX_EVALUATION_DAYS).contains(&days) {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidDateRange,
+                &format!("日期区间须为 1-{MAX_EVALUATION_DAYS} 天，且 from 不晚于 to"),
+            ));
+        }
+        Ok((
+            from.and_time(NaiveTime::MIN).and_utc(),
+            (to + Duration::days(1)).and_time(NaiveTime::MIN).and_utc(),
+        ))
+    }
+}
+
+// 按记录上标注的主导算法统计后续正确率与保持率，作为调整集成权重的依据
+async fn algorithm_evaluation(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let (from, to) = query.resolve()?;
+    let report = tokio::task::spawn_blocking(move || evaluation::evaluate(state.store(), from, to))
+        .await
+        .map_err(|e| AppError::internal(&format!("Evaluation task failed: {e}")))??;
+    Ok(ok(report))
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmUsageQuery {
+    days: Option<usize>,
+}
+
+// LLM token 用量与估算费用，按日期降序；附当日预算使用情况
+async fn llm_usage(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<LlmUsageQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let days = query.days.unwrap_or(7).clamp(1, 90);
+    let budget = state.config().llm.budget;
+    let used_today = state
+        .store()
+        .get_llm_usage_daily(Utc::now().date_naive())?
+        .map_or(0, |daily| daily.total.total_tokens());
+    let limit = (budget.daily_token_limit > 0).then_some(budget.daily_token_limit);
+    Ok(ok(serde_json::json!({
+        "budget": {
+            "dailyTokenLimit": limit,
+            "usedToday": used_today,
+            "remainingToday": limit.map(|l| l.saturating_sub(used_today)),
+            "exhausted": state.llm().budget_exhausted(),
+        },
+        "days": state.store().list_llm_usage_daily(days)?,
+    })))
+}
+
+/// 留存矩阵最多回看的周数
+const MAX_RETENTION_WEEKS: usize = 26;
+/// 留存矩阵与单词难度统计的缓存时长
+const ANALYTICS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(600);
+
+#[derive(Debug, Deserialize)]
+struct RetentionQuery {
+    weeks: Option<usize>,
+}
+
+// 按注册周分组的留存矩阵：各周注册用户在之后每周提交过学习记录的比例，结果缓存 10 分钟
+async fn cohort_retention(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<RetentionQuery>,
+    Query(export): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let weeks = query.weeks.unwrap_or(12).clamp(1, MAX_RETENTION_WEEKS);
+    let report = cached_retention(&state, weeks).await?;
+    if export.format == ExportFormat::Json {
+        return Ok(ok(report).into_response());
+    }
+    let rows = report.cohorts.into_iter().flat_map(|cohort| {
+        let RetentionCohort {
+            week_start,
+            size,
+            active,
+            retention,
+        } = cohort;
+        active
+            .into_iter()
+            .zip(retention)
+            .enumerate()
+            .map(move |(offset, (active, rate))| {
+                vec![
+                    week_start.to_string(),
+                    size.to_string(),
+                    offset.to_string(),
+                    active.to_string(),
+                    rate.to_string(),
+                ]
+            })
+    });
+    Ok(csv_response(
+        "retention",
+        &[
+            "cohortWeek",
+            "cohortSize",
+            "weekOffset",
+            "active",
+            "retention",
+        ],
+        rows,
+    ))
+}
+
+async fn cached_retention(state: &AppState, weeks: usize) -> Result<RetentionReport, AppError> {
+    if let Some((cached_at, report)) = state.retention_cache().read().await.get(&weeks) {
+        if cached_at.elapsed() < ANALYTICS_CACHE_TTL {
+            return Ok(report.clone());
+        }
+    }
+
+    let store_state = state.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        store_state.store().cohort_retention(weeks, Utc::now())
+    })
+    .await
+    .map_err(|e| AppError::internal(&format!("Retention task failed: {e}")))??;
+    state
+        .retention_cache()
+        .write()
+        .await
+        .insert(weeks, (Instant::now(), report.clone()));
+    Ok(report)
+}
+
+// 引导漏斗：区间内注册的用户中，依次完成首次学习、首个学习会话、连续学习 3 天的人数与转化率
+async fn onboarding_funnel(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<DateRangeQuery>,
+    Query(export): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let (from, to) = query.resolve()?;
+    let steps = tokio::task::spawn_blocking(move || state.store().onboarding_funnel(from, to))
+        .await
+        .map_err(|e| AppError::internal(&format!("Funnel task failed: {e}")))??;
+    if export.format == ExportFormat::Json {
+        return Ok(ok(serde_json::json!({
+            "from": from,
+            "to": to,
+            "steps": steps,
+        }))
+        .into_response());
+    }
+    let rows = steps.into_iter().map(|stage| {
+        vec![
+            stage.step.as_str().to_string(),
+            stage.users.to_string(),
+            stage.conversion_rate.to_string(),
+            stage.overall_rate.to_string(),
+        ]
+    });
+    Ok(csv_response(
+        "funnel",
+        &["step", "users", "conversionRate", "overallRate"],
+        rows,
+    ))
+}
+
+/// 单词难度统计最多读取的最近学习记录数
+const MAX_DIFFICULTY_RECORDS: usize = 100_000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WordDifficultyQuery {
+    limit: Option<usize>,
+    /// 作答次数少于该值的单词不参与排名
+    min_samples: Option<u64>,
+}
+
+// 全体用户视角下的单词难度排名：最近学习记录的错误率及其置信区间、单词 ELO，结果缓存 10 分钟。
+// CSV 导出默认不限条数
+async fn word_difficulty(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<WordDifficultyQuery>,
+    Query(export): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let limit = match export.format {
+        ExportFormat::Json => query.limit.unwrap_or(50).clamp(1, 200),
+        ExportFormat::Csv => query.limit.unwrap_or(usize::MAX).max(1),
+    };
+    let min_samples = query.min_samples.unwrap_or(5).max(1);
+
+    let cached = match &*state.word_difficulty_cache().read().await {
+        Some((cached_at, report)) if cached_at.elapsed() < ANALYTICS_CACHE_TTL => {
+            Some(report.clone())
+        }
+        _ => None,
+    };
+    let report = match cached {
+        Some(report) => report,
+        None => {
+            let store_state = state.clone();
+            let report = tokio::task::spawn_blocking(move || {
+                store_state
+                    .store()
+                    .word_difficulty_report(MAX_DIFFICULTY_RECORDS)
+            })
+            .await
+            .map_err(|e| AppError::internal(&format!("Word difficulty task failed: {e}")))??;
+            *state.word_difficulty_cache().write().await = Some((Instant::now(), report.clone()));
+            report
+        }
+    };
+
+    let words = report
+        .words
+        .into_iter()
+        .filter(move |w| w.attempts >= min_samples)
+        .take(limit);
+    if export.format == ExportFormat::Csv {
+        let rows = words.map(|w| {
+            vec![
+                w.word_id,
+                w.text,
+                w.labeled_difficulty.to_string(),
+                w.attempts.to_string(),
+                w.correct.to_string(),
+                w.error_rate.to_string(),
+                w.confidence_low.to_string(),
+                w.confidence_high.to_string(),
+                w.difficulty_gap.to_string(),
+                w.elo_rating.to_string(),
+                w.elo_games.to_string(),
+            ]
+        });
+        return Ok(csv_response(
+            "word-difficulty",
+            &[
+                "wordId",
+                "text",
+                "labeledDifficulty",
+                "attempts",
+                "correct",
+                "errorRate",
+                "confidenceLow",
+                "confidenceHigh",
+                "difficultyGap",
+                "eloRating",
+                "eloGames",
+            ],
+            rows,
+        ));
+    }
+    Ok(ok(serde_json::json!({
+        "generatedAt": report.generated_at,
+        "sampledRecords": report.sampled_records,
+        "words": words.collect::<Vec<_>>(),
+    }))
+    .into_response())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+/// CSV 单元格：必要时加引号转义；以公式字符开头的文本前置单引号，防止在表格软件中被当作公式执行
+fn csv_field(value: &str) -> String {
+    let formula =
+        value.starts_with(['=', '+', '-', '@', '\t', '\r']) && value.parse::<f64>().is_err();
+    let value = if formula {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_line(fields: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|f| csv_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// 以 CSV 附件逐行流式返回，避免一次性拼出整个响应体
+fn csv_response<I>(name: &str, header_row: &[&str], rows: I) -> Response
+where
+    I: Iterator<Item = Vec<String>> + Send + 'static,
+{
+    let lines = std::iter::once(csv_line(header_row))
+        .chain(rows.map(csv_line))
+        .map(Ok::<_, std::convert::Infallible>);
+    let mut response = Response::new(Body::from_stream(futures::stream::iter(lines)));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/csv; charset=utf-8"),
+    );
+    let filename = format!(
+        "attachment; filename=\"wordforge-{name}-{}.csv\"",
+        Utc::now().format("%Y%m%d")
+    );
+    if let Ok(value) = HeaderValue::from_str(&filename) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_and_guarded_against_formulas() {
+        assert_eq!(
+            csv_line(["a", "b,c", "say \"hi\""]),
+            "a,\"b,c\",\"say \"\"hi\"\"\"\r\n"
+        );
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(csv_field("-0.25"), "-0.25");
+    }
+}
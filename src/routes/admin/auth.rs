@@ -10,11 +10,13 @@ use crate::auth::{
     extract_token_from_headers, generate_dummy_argon2_hash, hash_password, hash_token,
     sign_jwt_for_admin, verify_password, AdminAuthUser,
 };
-use crate::response::{created, ok, AppError};
+use crate::constants::{ADMIN_TOTP_BACKUP_CODE_COUNT, ADMIN_TOTP_ISSUER};
+use crate::response::{created, ok, AppError, ErrorCode};
+use crate::services::{password_breach, totp};
 use crate::state::AppState;
 use crate::store::operations::admins::Admin;
 use crate::store::operations::sessions::Session;
-use crate::validation::{is_valid_email, validate_password};
+use crate::validation::is_valid_email;
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -22,6 +24,10 @@ pub fn router() -> Router<AppState> {
         .route("/login", post(login))
         .route("/logout", post(logout))
         .route("/verify", get(verify))
+        .route("/totp/enroll", post(totp_enroll))
+        .route("/totp/enable", post(totp_enable))
+        .route("/totp/disable", post(totp_disable))
+        .route("/totp/backup-codes", post(totp_regenerate_backup_codes))
 }
 
 /// 不受 auth rate limit 约束的公开路由
@@ -47,6 +53,35 @@ struct SetupRequest {
 struct LoginRequest {
     email: String,
     password: String,
+    /// 启用两步验证后必填，可为 TOTP 验证码或备用码
+    #[serde(default)]
+    totp_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TotpCodeRequest {
+    code: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TotpEnrollResponse {
+    secret: String,
+    otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TotpEnableResponse {
+    enabled: bool,
+    backup_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TotpBackupCodesResponse {
+    backup_codes: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -76,13 +111,11 @@ async fn setup(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if !is_valid_email(&req.email) {
         return Err(AppError::bad_request(
-            "ADMIN_INVALID_EMAIL",
+            ErrorCode::AdminInvalidEmail,
             "邮箱格式无效",
         ));
     }
-    if let Err(msg) = validate_password(&req.password) {
-        return Err(AppError::bad_request("ADMIN_WEAK_PASSWORD", msg));
-    }
+    password_breach::enforce(&req.password, &state.config().password_policy).await?;
 
     let admin = Admin {
         id: uuid::Uuid::new_v4().to_string(),
@@ -92,12 +125,16 @@ async fn setup(
         updated_at: Utc::now(),
         failed_login_count: 0,
         locked_until: None,
+        totp_secret: None,
+        totp_enabled: false,
+        totp_backup_codes: Vec::new(),
+        totp_last_step: None,
     };
 
     // 使用 create_first_admin 在事务内部原子性检查是否已有 admin，防止 TOCTOU
     state.store().create_first_admin(&admin).map_err(|e| {
         if matches!(e, crate::store::StoreError::Conflict { .. }) {
-            AppError::conflict("ADMIN_ALREADY_EXISTS", "管理员账户已存在")
+            AppError::conflict(ErrorCode::AdminAlreadyExists, "管理员账户已存在")
         } else {
             AppError::from(e)
         }
@@ -117,6 +154,10 @@ async fn setup(
         created_at: Utc::now(),
         expires_at: Utc::now() + Duration::hours(state.config().admin_jwt_expires_in_hours as i64),
         revoked: false,
+        last_seen_at: None,
+        family_id: None,
+        user_agent: None,
+        ip_hash: None,
     })?;
 
     Ok(created(AdminAuthResponse {
@@ -166,6 +207,27 @@ async fn login(
 
     let admin = admin.unwrap();
 
+    if admin.totp_enabled {
+        let Some(code) = req
+            .totp_code
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+        else {
+            return Err(totp_error(ErrorCode::AdminTotpRequired, "请输入两步验证码"));
+        };
+        if !verify_second_factor(&state, &admin, code)? {
+            if let Err(e) = state.store().record_admin_failed_login(&admin.id) {
+                tracing::error!(
+                    admin_id = %admin.id,
+                    error = %e,
+                    "记录管理员登录失败次数时出错"
+                );
+            }
+            return Err(totp_error(ErrorCode::AdminTotpInvalid, "两步验证码无效"));
+        }
+    }
+
     // 登录成功，重置失败计数
     if let Err(e) = state.store().reset_admin_login_attempts(&admin.id) {
         tracing::error!(
@@ -189,6 +251,10 @@ async fn login(
         created_at: Utc::now(),
         expires_at: Utc::now() + Duration::hours(state.config().admin_jwt_expires_in_hours as i64),
         revoked: false,
+        last_seen_at: None,
+        family_id: None,
+        user_agent: None,
+        ip_hash: None,
     })?;
 
     Ok(ok(AdminAuthResponse {
@@ -215,6 +281,170 @@ async fn verify(
     }))
 }
 
+fn totp_error(code: ErrorCode, message: &str) -> AppError {
+    AppError {
+        code,
+        ..AppError::unauthorized(message)
+    }
+}
+
+fn decrypt_admin_totp_secret(state: &AppState, admin: &Admin) -> Result<Vec<u8>, AppError> {
+    let sealed = admin.totp_secret.as_deref().ok_or_else(|| {
+        AppError::bad_request(ErrorCode::AdminTotpNotEnrolled, "尚未登记两步验证")
+    })?;
+    totp::decrypt_secret(&state.config().admin_totp_encryption_key, sealed)
+        .ok_or_else(|| AppError::internal("两步验证密钥无法解密"))
+}
+
+/// 依次尝试 TOTP 验证码与备用码，成功时消费对应的时间步或备用码
+fn verify_second_factor(state: &AppState, admin: &Admin, code: &str) -> Result<bool, AppError> {
+    let secret = decrypt_admin_totp_secret(state, admin)?;
+    if let Some(step) = totp::verify_code(&secret, code, Utc::now().timestamp() as u64) {
+        return Ok(state.store().consume_admin_totp_step(&admin.id, step)?);
+    }
+    let code_hash = hash_token(&totp::normalize_backup_code(code));
+    Ok(state
+        .store()
+        .consume_admin_backup_code(&admin.id, &code_hash)?)
+}
+
+/// 生成新的 TOTP 密钥（未验证前不生效），重复调用会替换尚未启用的密钥
+async fn totp_enroll(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let admin_record = state
+        .store()
+        .get_admin_by_id(&admin.admin_id)?
+        .ok_or_else(|| AppError::unauthorized("管理员不存在"))?;
+
+    let secret = totp::generate_secret();
+    let sealed = totp::encrypt_secret(&state.config().admin_totp_encryption_key, &secret);
+    if !state
+        .store()
+        .set_admin_pending_totp(&admin_record.id, &sealed)?
+    {
+        return Err(AppError::conflict(
+            ErrorCode::AdminTotpAlreadyEnabled,
+            "两步验证已启用",
+        ));
+    }
+
+    Ok(ok(TotpEnrollResponse {
+        secret: totp::base32_encode(&secret),
+        otpauth_uri: totp::otpauth_uri(ADMIN_TOTP_ISSUER, &admin_record.email, &secret),
+    }))
+}
+
+/// 校验验证器生成的验证码后启用两步验证，并一次性返回备用码明文
+async fn totp_enable(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<TotpCodeRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let admin_record = state
+        .store()
+        .get_admin_by_id(&admin.admin_id)?
+        .ok_or_else(|| AppError::unauthorized("管理员不存在"))?;
+    if admin_record.totp_enabled {
+        return Err(AppError::conflict(
+            ErrorCode::AdminTotpAlreadyEnabled,
+            "两步验证已启用",
+        ));
+    }
+
+    let secret = decrypt_admin_totp_secret(&state, &admin_record)?;
+    let step = totp::verify_code(&secret, &req.code, Utc::now().timestamp() as u64)
+        .ok_or_else(|| AppError::bad_request(ErrorCode::AdminTotpInvalid, "两步验证码无效"))?;
+
+    let (backup_codes, hashes) = new_backup_codes();
+    if !state
+        .store()
+        .enable_admin_totp(&admin_record.id, hashes, step)?
+    {
+        return Err(AppError::conflict(
+            ErrorCode::AdminTotpAlreadyEnabled,
+            "两步验证已启用",
+        ));
+    }
+
+    Ok(ok(TotpEnableResponse {
+        enabled: true,
+        backup_codes,
+    }))
+}
+
+fn new_backup_codes() -> (Vec<String>, Vec<String>) {
+    let backup_codes = totp::generate_backup_codes(ADMIN_TOTP_BACKUP_CODE_COUNT);
+    let hashes = backup_codes
+        .iter()
+        .map(|code| hash_token(&totp::normalize_backup_code(code)))
+        .collect();
+    (backup_codes, hashes)
+}
+
+/// 已启用两步验证的管理员须用验证码或备用码确认身份
+fn require_second_factor(state: &AppState, admin_id: &str, code: &str) -> Result<(), AppError> {
+    let admin_record = state
+        .store()
+        .get_admin_by_id(admin_id)?
+        .ok_or_else(|| AppError::unauthorized("管理员不存在"))?;
+    if !admin_record.totp_enabled {
+        return Err(AppError::bad_request(
+            ErrorCode::AdminTotpNotEnrolled,
+            "尚未启用两步验证",
+        ));
+    }
+    if !verify_second_factor(state, &admin_record, code)? {
+        return Err(AppError::bad_request(
+            ErrorCode::AdminTotpInvalid,
+            "两步验证码无效",
+        ));
+    }
+    Ok(())
+}
+
+/// 校验验证码或备用码后关闭两步验证，清除密钥与剩余备用码
+async fn totp_disable(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<TotpCodeRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    require_second_factor(&state, &admin.admin_id, &req.code)?;
+    state.store().disable_admin_totp(&admin.admin_id)?;
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "totp_disable",
+        "管理员关闭两步验证"
+    );
+    Ok(ok(serde_json::json!({ "enabled": false })))
+}
+
+/// 校验验证码或备用码后重新生成备用码，旧备用码全部作废
+async fn totp_regenerate_backup_codes(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<TotpCodeRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    require_second_factor(&state, &admin.admin_id, &req.code)?;
+    let (backup_codes, hashes) = new_backup_codes();
+    if !state
+        .store()
+        .replace_admin_backup_codes(&admin.admin_id, hashes)?
+    {
+        return Err(AppError::bad_request(
+            ErrorCode::AdminTotpNotEnrolled,
+            "尚未启用两步验证",
+        ));
+    }
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "totp_regenerate_backup_codes",
+        "管理员重新生成两步验证备用码"
+    );
+    Ok(ok(TotpBackupCodesResponse { backup_codes }))
+}
+
 async fn logout(
     _admin: AdminAuthUser,
     State(state): State<AppState>,
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use axum::extract::State;
+use axum::routing::post;
+use axum::Router;
+
+use crate::auth::AdminAuthUser;
+use crate::response::{created, AppError};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(create_backup))
+}
+
+/// 在线备份：导出到 `BACKUP_DIR`，返回备份文件路径与大小
+async fn create_backup(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let dir = PathBuf::from(&state.config().worker.backup_dir);
+    let report = tokio::task::spawn_blocking(move || state.store().write_backup(&dir))
+        .await
+        .map_err(|e| AppError::internal(&format!("Backup task failed: {e}")))??;
+
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "create_backup",
+        path = %report.path.display(),
+        size_bytes = report.size_bytes,
+        "管理员创建数据库备份"
+    );
+    Ok(created(report))
+}
@@ -1,17 +1,27 @@
-use axum::extract::State;
-use axum::routing::post;
+use axum::extract::{Path, Query, State};
+use axum::routing::{delete, get, post};
 use axum::Router;
 
 use crate::extractors::JsonBody;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 
 use crate::auth::AdminAuthUser;
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::state::AppState;
+use crate::store::operations::broadcasts::{
+    broadcast_content_hash, BroadcastStatus, BroadcastTarget, ScheduledBroadcast,
+};
+
+/// 定时广播最远可提前的天数，也是活跃人群回溯天数的上限
+const MAX_SCHEDULE_DAYS: i64 = 365;
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", post(broadcast_message))
+    Router::new()
+        .route("/", post(broadcast_message))
+        .route("/scheduled", get(list_scheduled))
+        .route("/scheduled/:id", delete(cancel_scheduled))
+        .route("/:id/stats", get(broadcast_stats))
 }
 
 // B63: System-wide broadcast
@@ -20,22 +30,54 @@ pub fn router() -> Router<AppState> {
 struct BroadcastRequest {
     title: String,
     message: String,
+    #[serde(default)]
+    target: BroadcastTarget,
+    /// 为空时立即发送
+    scheduled_at: Option<DateTime<Utc>>,
 }
 
 impl BroadcastRequest {
-    fn validate(&self) -> Result<(), AppError> {
+    fn validate(&self, state: &AppState) -> Result<(), AppError> {
         if self.title.is_empty() || self.title.len() > 200 {
             return Err(AppError::bad_request(
-                "INVALID_TITLE",
+                ErrorCode::InvalidTitle,
                 "标题长度需在1到200个字符之间",
             ));
         }
         if self.message.is_empty() || self.message.len() > 10000 {
             return Err(AppError::bad_request(
-                "INVALID_MESSAGE",
+                ErrorCode::InvalidMessage,
                 "消息内容长度需在1到10000个字符之间",
             ));
         }
+        match &self.target {
+            BroadcastTarget::ActiveWithin { days }
+                if !(1..=MAX_SCHEDULE_DAYS).contains(&i64::from(*days)) =>
+            {
+                return Err(AppError::bad_request(
+                    ErrorCode::InvalidBroadcastTarget,
+                    "活跃天数需在1到365之间",
+                ));
+            }
+            BroadcastTarget::WordbookLearners { wordbook_id }
+                if state.store().get_wordbook(wordbook_id)?.is_none() =>
+            {
+                return Err(AppError::bad_request(
+                    ErrorCode::WordbookNotFound,
+                    "词书不存在",
+                ));
+            }
+            _ => {}
+        }
+        if let Some(at) = self.scheduled_at {
+            let now = Utc::now();
+            if at <= now || at > now + Duration::days(MAX_SCHEDULE_DAYS) {
+                return Err(AppError::bad_request(
+                    ErrorCode::InvalidScheduledAt,
+                    "计划发送时间需在未来365天以内",
+                ));
+            }
+        }
         Ok(())
     }
 }
@@ -45,58 +87,115 @@ async fn broadcast_message(
     State(state): State<AppState>,
     JsonBody(req): JsonBody<BroadcastRequest>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
-    req.validate()?;
+    req.validate(&state)?;
 
-    // 使用幂等 key 防止重复广播
     let broadcast_id = uuid::Uuid::new_v4().to_string();
+    let recipients = state.store().resolve_broadcast_recipients(&req.target)?;
 
-    // 分批加载用户，避免一次性加载所有用户导致内存溢出
-    let batch_size = 100;
-    let mut offset = 0;
-    let mut total_sent = 0usize;
-
-    loop {
-        let users = state.store().list_users(batch_size, offset)?;
-        if users.is_empty() {
-            break;
-        }
-
-        let entries: Vec<(String, String, serde_json::Value)> = users
-            .iter()
-            .map(|user| {
-                let notification_id = format!("{}_{}", broadcast_id, user.id);
-                let value = serde_json::json!({
-                    "id": notification_id,
-                    "userId": user.id,
-                    "type": "broadcast",
-                    "title": req.title,
-                    "message": req.message,
-                    "read": false,
-                    "createdAt": Utc::now().to_rfc3339(),
-                });
-                (user.id.clone(), notification_id, value)
-            })
-            .collect();
-
-        total_sent += entries.len();
-        state
-            .store()
-            .batch_create_notifications(&entries)
-            .map_err(|e| AppError::internal(&e.to_string()))?;
-
-        offset += users.len();
-        tracing::info!("广播进度: 已发送 {} 条通知", total_sent);
+    if let Some(scheduled_at) = req.scheduled_at {
+        // 投递时按当时的人群重新解析，此处的人数仅供确认
+        let scheduled = ScheduledBroadcast {
+            id: broadcast_id,
+            title: req.title,
+            message: req.message,
+            target: req.target,
+            scheduled_at,
+            status: BroadcastStatus::Pending,
+            created_by: admin.admin_id.clone(),
+            created_at: Utc::now(),
+            recipients: recipients.len(),
+            sent_at: None,
+            sent: 0,
+            skipped_duplicates: 0,
+        };
+        state.store().save_scheduled_broadcast(&scheduled)?;
+        tracing::info!(
+            admin_id = %admin.admin_id,
+            action = "schedule_broadcast",
+            broadcast_id = %scheduled.id,
+            scheduled_at = %scheduled.scheduled_at,
+            recipients = scheduled.recipients,
+            "管理员创建定时广播"
+        );
+        return Ok(ok(
+            serde_json::to_value(&scheduled).map_err(|e| AppError::internal(&e.to_string()))?
+        ));
     }
 
+    // 使用内容指纹防止重复广播
+    let dedup_window_ms = state.config().limits.broadcast_dedup_window_secs as i64 * 1000;
+    let outcome = state.store().send_broadcast(
+        &broadcast_id,
+        &req.title,
+        &req.message,
+        &broadcast_content_hash(&req.title, &req.message),
+        &recipients,
+        dedup_window_ms,
+    )?;
+
     tracing::info!(
         admin_id = %admin.admin_id,
         action = "broadcast",
         broadcast_id = %broadcast_id,
-        total_sent = total_sent,
+        total_sent = outcome.sent,
+        total_skipped = outcome.skipped_duplicates,
         "管理员发送系统广播"
     );
 
-    Ok(ok(
-        serde_json::json!({"sent": total_sent, "broadcastId": broadcast_id}),
-    ))
+    Ok(ok(serde_json::json!({
+        "sent": outcome.sent,
+        "skippedDuplicates": outcome.skipped_duplicates,
+        "recipients": recipients.len(),
+        "broadcastId": broadcast_id,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct ScheduledQuery {
+    limit: Option<usize>,
+}
+
+async fn list_scheduled(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<ScheduledQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    Ok(ok(state.store().list_scheduled_broadcasts(limit)?))
+}
+
+async fn cancel_scheduled(
+    admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let scheduled = state
+        .store()
+        .get_scheduled_broadcast(&id)?
+        .ok_or_else(|| AppError::not_found("定时广播不存在"))?;
+    let cancelled = state
+        .store()
+        .transition_scheduled_broadcast(&scheduled, BroadcastStatus::Cancelled)?
+        .ok_or_else(|| AppError::conflict(ErrorCode::Conflict, "广播已发送或已取消"))?;
+
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "cancel_broadcast",
+        broadcast_id = %id,
+        "管理员取消定时广播"
+    );
+    Ok(ok(cancelled))
+}
+
+async fn broadcast_stats(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let stats = state.store().broadcast_stats(&id)?;
+    // 尚未投递的定时广播返回全零统计
+    if stats.delivered == 0 && state.store().get_scheduled_broadcast(&id)?.is_none() {
+        return Err(AppError::not_found("广播不存在"));
+    }
+    Ok(ok(stats))
 }
@@ -1,18 +1,21 @@
 pub mod amas;
 pub mod analytics;
 pub mod auth;
+pub mod backup;
 pub mod broadcast;
 pub mod monitoring;
 pub mod settings;
 
-use axum::extract::{Path, Query, State};
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::routing::{get, post};
 use axum::Router;
 use serde::{Deserialize, Serialize};
 
+use crate::amas::types::AmasUserExport;
 use crate::auth::{hash_password, hash_token, AdminAuthUser};
 use crate::extractors::JsonBody;
 use crate::response::{ok, AppError};
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
 use crate::store::operations::users::User;
 
@@ -54,12 +57,18 @@ pub fn router() -> Router<AppState> {
         .nest("/settings", settings::router())
         .nest("/wordbook-center", super::wordbook_center::admin_router())
         .nest("/amas", amas::admin_router())
+        .nest("/backup", backup::router())
         .route("/users", get(list_users))
         .route("/users/:id/ban", post(ban_user))
         .route("/users/:id/unban", post(unban_user))
         .route("/stats", get(admin_stats))
         .route("/users/:id/reset-password", post(admin_reset_user_password))
         .route("/users/:id/set-password", post(admin_set_user_password))
+        .route("/users/:id/amas-export", get(export_user_amas))
+        .route(
+            "/users/:id/amas-import",
+            post(import_user_amas).layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
 }
 
 /// 导出 admin 认证路由（用于在外层添加专用速率限制）
@@ -182,6 +191,42 @@ async fn admin_stats(
     })))
 }
 
+async fn export_user_amas(
+    _admin: AdminAuthUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    Ok(ok(state.amas().export_user_state(&id)?))
+}
+
+async fn import_user_amas(
+    admin: AdminAuthUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    JsonBody(export): JsonBody<AmasUserExport>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if state.store().get_user_by_id(&id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+    state.amas().import_user_state(&id, &export).await?;
+    tracing::info!(
+        admin_id = %admin.admin_id,
+        action = "import_user_amas",
+        target_user_id = %id,
+        source_user_id = %export.user_id,
+        "管理员导入用户 AMAS 状态"
+    );
+    Ok(ok(serde_json::json!({
+        "imported": true,
+        "userId": id,
+        "algoStates": export.algo_states.len(),
+        "masteryStates": export.mastery_states.len(),
+    })))
+}
+
 async fn admin_reset_user_password(
     admin: AdminAuthUser,
     Path(id): Path<String>,
@@ -233,9 +278,8 @@ async fn admin_set_user_password(
     State(state): State<AppState>,
     JsonBody(req): JsonBody<AdminSetPasswordRequest>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
-    if let Err(msg) = crate::validation::validate_password(&req.new_password) {
-        return Err(AppError::bad_request("AUTH_WEAK_PASSWORD", msg));
-    }
+    crate::services::password_breach::enforce(&req.new_password, &state.config().password_policy)
+        .await?;
 
     let mut user = state
         .store()
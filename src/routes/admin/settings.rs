@@ -7,8 +7,9 @@ use serde::Deserialize;
 
 use crate::amas::config::AMASConfig;
 use crate::auth::AdminAuthUser;
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::state::AppState;
+use crate::store::operations::study_configs::StudyMode;
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -23,6 +24,8 @@ struct UpdateSystemSettings {
     registration_enabled: Option<bool>,
     maintenance_mode: Option<bool>,
     default_daily_words: Option<u32>,
+    default_mastery_target: Option<u32>,
+    default_study_mode: Option<StudyMode>,
     wordbook_center_url: Option<String>,
 }
 
@@ -31,7 +34,7 @@ impl UpdateSystemSettings {
         if let Some(v) = self.max_users {
             if !(1..=1_000_000).contains(&v) {
                 return Err(AppError::bad_request(
-                    "INVALID_MAX_USERS",
+                    ErrorCode::InvalidMaxUsers,
                     "最大用户数必须在1到1000000之间",
                 ));
             }
@@ -39,11 +42,26 @@ impl UpdateSystemSettings {
         if let Some(v) = self.default_daily_words {
             if !(1..=500).contains(&v) {
                 return Err(AppError::bad_request(
-                    "INVALID_DAILY_WORDS",
+                    ErrorCode::InvalidDailyWords,
                     "每日默认单词数必须在1到500之间",
                 ));
             }
         }
+        if let Some(v) = self.default_mastery_target {
+            if !(1..=100).contains(&v) {
+                return Err(AppError::bad_request(
+                    ErrorCode::InvalidMasteryTarget,
+                    "默认每日掌握目标必须在1到100之间",
+                ));
+            }
+        }
+        // cram 必须带截止时间，不能作为默认模式
+        if self.default_study_mode == Some(StudyMode::Cram) {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidStudyMode,
+                "默认学习模式不能为 cram",
+            ));
+        }
         Ok(())
     }
 }
@@ -77,6 +95,12 @@ async fn update_settings(
     if let Some(v) = req.default_daily_words {
         settings.default_daily_words = v;
     }
+    if let Some(v) = req.default_mastery_target {
+        settings.default_mastery_target = v;
+    }
+    if let Some(v) = req.default_study_mode {
+        settings.default_study_mode = v;
+    }
     if let Some(ref v) = req.wordbook_center_url {
         settings.wordbook_center_url = if v.is_empty() { None } else { Some(v.clone()) };
     }
@@ -100,12 +124,12 @@ async fn reload_amas_config(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     new_config
         .validate()
-        .map_err(|e| AppError::bad_request("INVALID_AMAS_CONFIG", &e))?;
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
     state
         .amas()
         .reload_config(new_config)
         .await
-        .map_err(|e| AppError::bad_request("INVALID_AMAS_CONFIG", &e))?;
+        .map_err(|e| AppError::bad_request(ErrorCode::AmasInvalidConfig, &e))?;
     let config = state.amas().get_config().await;
 
     tracing::info!(
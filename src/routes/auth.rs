@@ -4,20 +4,23 @@ use axum::response::{IntoResponse, Response};
 use axum::routing::post;
 use axum::Router;
 
-use crate::extractors::JsonBody;
-use chrono::{Duration, Utc};
+use crate::extractors::{ClientInfo, JsonBody};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::auth::{
-    extract_refresh_token_from_headers, generate_dummy_argon2_hash, hash_password, hash_token,
-    sign_jwt_for_user, sign_refresh_token_for_user, verify_jwt, verify_password, AuthUser,
+    extract_refresh_token_from_headers, generate_dummy_argon2_hash, hash_client_ip, hash_password,
+    hash_token, sign_jwt_for_user, sign_refresh_token_for_user, verify_jwt, verify_password,
+    AuthUser,
 };
-use crate::response::{created, ok, AppError};
+use crate::response::{created, ok, AppError, ErrorCode};
+use crate::services::password_breach;
 use crate::state::AppState;
 use crate::store::keys;
+use crate::store::operations::refresh_tokens::{RefreshRotation, RefreshToken};
 use crate::store::operations::sessions::Session;
 use crate::store::operations::users::User;
-use crate::validation::{is_valid_email, validate_password, validate_username};
+use crate::validation::{is_valid_email, validate_username};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -101,8 +104,13 @@ pub struct PasswordResetEntry {
 /// 每用户最大并发会话数
 const MAX_SESSIONS_PER_USER: usize = 10;
 
-/// Issue an access + refresh token pair and persist the access session.
-fn issue_token_pair(user_id: &str, state: &AppState) -> Result<(String, String), AppError> {
+/// Sign and persist an access token session bound to a refresh token family.
+fn issue_access_token(
+    user_id: &str,
+    family_id: &str,
+    client: &ClientInfo,
+    state: &AppState,
+) -> Result<String, AppError> {
     // 清理超出限制的旧会话
     if let Err(e) = state
         .store()
@@ -117,13 +125,6 @@ fn issue_token_pair(user_id: &str, state: &AppState) -> Result<(String, String),
         state.config().jwt_expires_in_hours,
     )?;
 
-    let refresh_token = sign_refresh_token_for_user(
-        user_id,
-        &state.config().refresh_jwt_secret,
-        state.config().refresh_token_expires_in_hours,
-    )?;
-
-    // Persist the access token session
     let token_hash = hash_token(&access_token);
     state.store().create_session(&Session {
         token_hash,
@@ -132,16 +133,45 @@ fn issue_token_pair(user_id: &str, state: &AppState) -> Result<(String, String),
         created_at: Utc::now(),
         expires_at: Utc::now() + Duration::hours(state.config().jwt_expires_in_hours as i64),
         revoked: false,
+        last_seen_at: Some(Utc::now()),
+        family_id: Some(family_id.to_string()),
+        user_agent: client.user_agent.clone(),
+        ip_hash: Some(hash_client_ip(&client.ip, &state.config().jwt_secret)),
     })?;
 
-    // Persist the refresh token session (longer expiry)
-    let refresh_hash = hash_token(&refresh_token);
-    state.store().create_session(&Session {
-        token_hash: refresh_hash,
+    Ok(access_token)
+}
+
+fn sign_refresh_token(
+    user_id: &str,
+    state: &AppState,
+) -> Result<(String, DateTime<Utc>), AppError> {
+    let refresh_token = sign_refresh_token_for_user(
+        user_id,
+        &state.config().refresh_jwt_secret,
+        state.config().refresh_token_expires_in_hours,
+    )?;
+    let expires_at =
+        Utc::now() + Duration::hours(state.config().refresh_token_expires_in_hours as i64);
+    Ok((refresh_token, expires_at))
+}
+
+/// Issue an access + refresh token pair; the refresh token starts a new rotation family.
+fn issue_token_pair(
+    user_id: &str,
+    client: &ClientInfo,
+    state: &AppState,
+) -> Result<(String, String), AppError> {
+    let family_id = uuid::Uuid::new_v4().to_string();
+    let access_token = issue_access_token(user_id, &family_id, client, state)?;
+    let (refresh_token, expires_at) = sign_refresh_token(user_id, state)?;
+    state.store().create_refresh_token(&RefreshToken {
+        token_hash: hash_token(&refresh_token),
         user_id: user_id.to_string(),
-        token_type: "refresh".to_string(),
+        family_id,
         created_at: Utc::now(),
-        expires_at: Utc::now() + Duration::hours(state.config().refresh_token_expires_in_hours as i64),
+        expires_at,
+        replaced_by: None,
         revoked: false,
     })?;
 
@@ -150,34 +180,30 @@ fn issue_token_pair(user_id: &str, state: &AppState) -> Result<(String, String),
 
 async fn register(
     State(state): State<AppState>,
+    client: ClientInfo,
     JsonBody(req): JsonBody<RegisterRequest>,
 ) -> Result<Response, AppError> {
     let system_settings = state.store().get_system_settings()?;
     if !system_settings.registration_enabled {
         return Err(AppError::forbidden("注册功能已关闭"));
     }
-    if system_settings.maintenance_mode {
-        return Err(AppError::forbidden("系统正在维护中"));
-    }
 
     let email = req.email.trim().to_lowercase();
     if !is_valid_email(&email) {
         return Err(AppError::bad_request(
-            "AUTH_INVALID_EMAIL",
+            ErrorCode::AuthInvalidEmail,
             "邮箱格式无效",
         ));
     }
     let username = req.username.trim();
     if let Err(msg) = validate_username(username) {
-        return Err(AppError::bad_request("AUTH_INVALID_USERNAME", msg));
-    }
-    if let Err(msg) = validate_password(&req.password) {
-        return Err(AppError::bad_request("AUTH_WEAK_PASSWORD", msg));
+        return Err(AppError::bad_request(ErrorCode::AuthInvalidUsername, msg));
     }
+    password_breach::enforce(&req.password, &state.config().password_policy).await?;
 
     if state.store().get_user_by_email(&email)?.is_some() {
         return Err(AppError::conflict(
-            "AUTH_EMAIL_EXISTS",
+            ErrorCode::AuthEmailExists,
             "该邮箱已被注册",
         ));
     }
@@ -197,11 +223,15 @@ async fn register(
         updated_at: now,
         failed_login_count: 0,
         locked_until: None,
+        lockout_level: 0,
     };
 
     state.store().create_user(&user)?;
+    state
+        .store()
+        .init_study_config(&system_settings.default_study_config(&user.id))?;
 
-    let (access_token, refresh_token) = issue_token_pair(&user.id, &state)?;
+    let (access_token, refresh_token) = issue_token_pair(&user.id, &client, &state)?;
 
     tracing::info!(
         user_id = %user.id,
@@ -222,12 +252,9 @@ async fn register(
 
 async fn login(
     State(state): State<AppState>,
+    client: ClientInfo,
     JsonBody(req): JsonBody<LoginRequest>,
 ) -> Result<Response, AppError> {
-    if state.store().get_system_settings()?.maintenance_mode {
-        return Err(AppError::forbidden("系统正在维护中"));
-    }
-
     let (user, stored_hash) = match state.store().get_user_by_email(&req.email)? {
         Some(user) => {
             let hash = user.password_hash.clone();
@@ -239,7 +266,9 @@ async fn login(
     let verified = verify_password(&req.password, &stored_hash)?;
     if !verified || user.is_none() {
         if let Some(ref u) = user {
-            let _ = state.store().record_failed_login(&u.id);
+            let _ = state
+                .store()
+                .record_failed_login(&u.id, &state.config().lockout);
         }
         return Err(AppError::unauthorized("邮箱或密码错误"));
     }
@@ -250,15 +279,22 @@ async fn login(
         return Err(AppError::forbidden("用户已被封禁"));
     }
 
-    if state.store().is_account_locked(&user.id)? {
-        return Err(AppError::too_many_requests(
-            "账户因多次登录失败已被临时锁定，请稍后再试",
-        ));
+    if let Some(locked_until) = state.store().account_locked_until(&user.id)? {
+        let remaining_secs = (locked_until - Utc::now()).num_seconds().max(1);
+        return Err(AppError {
+            code: ErrorCode::AuthAccountLocked,
+            ..AppError::forbidden("账户因多次登录失败已被临时锁定，请稍后再试")
+        }
+        .with_details(serde_json::json!({
+            "reason": "too_many_failed_logins",
+            "lockedUntil": locked_until,
+            "remainingSeconds": remaining_secs,
+        })));
     }
 
     let _ = state.store().reset_login_attempts(&user.id);
 
-    let (access_token, refresh_token) = issue_token_pair(&user.id, &state)?;
+    let (access_token, refresh_token) = issue_token_pair(&user.id, &client, &state)?;
 
     tracing::info!(
         user_id = %user.id,
@@ -277,7 +313,11 @@ async fn login(
     Ok(response)
 }
 
-async fn refresh(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+async fn refresh(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     // Extract the refresh token from Authorization header or cookie
     let old_token = extract_refresh_token_from_headers(&headers)?;
 
@@ -289,24 +329,43 @@ async fn refresh(State(state): State<AppState>, headers: HeaderMap) -> Result<Re
         ));
     }
 
-    // Verify the refresh session exists in the store
+    // 轮换：旧令牌作废，新令牌继承同一 family；已轮换的令牌再次出现说明可能被盗，撤销整条链
     let old_hash = hash_token(&old_token);
-    let session = state
-        .store()
-        .get_session(&old_hash)?
-        .ok_or_else(|| AppError::unauthorized("刷新会话不存在或已过期"))?;
+    let (refresh_token, expires_at) = sign_refresh_token(&claims.sub, &state)?;
+    let rotated = match state.store().rotate_refresh_token(
+        &old_hash,
+        &hash_token(&refresh_token),
+        expires_at,
+    )? {
+        RefreshRotation::Rotated(rotated) => rotated,
+        RefreshRotation::Reused { user_id, family_id } => {
+            let revoked = state
+                .store()
+                .revoke_refresh_token_family(&user_id, &family_id)?;
+            // 该链签发的访问令牌一并失效，否则盗用者在过期前仍可继续访问
+            state
+                .store()
+                .delete_family_sessions(&user_id, &family_id)?;
+            tracing::warn!(
+                user_id,
+                family_id,
+                revoked,
+                "检测到刷新令牌重放，已撤销整条令牌链"
+            );
+            return Err(AppError {
+                code: ErrorCode::AuthRefreshTokenReused,
+                ..AppError::unauthorized("刷新令牌已被使用")
+            });
+        }
+        RefreshRotation::Invalid => {
+            return Err(AppError::unauthorized("刷新会话不存在或已过期"));
+        }
+    };
 
-    if session.user_id != claims.sub {
+    if rotated.user_id != claims.sub {
         return Err(AppError::unauthorized("刷新会话不匹配"));
     }
 
-    // 原子性删除旧的 refresh 会话，防止 token 重放攻击
-    let was_deleted = state.store().delete_session_if_exists(&old_hash)?;
-    if !was_deleted {
-        // token 已被使用（可能是重放攻击），拒绝请求
-        return Err(AppError::unauthorized("刷新令牌已被使用"));
-    }
-
     // 在签发新 token 前检查用户状态（封禁检查）
     let user = state
         .store()
@@ -317,8 +376,11 @@ async fn refresh(State(state): State<AppState>, headers: HeaderMap) -> Result<Re
         return Err(AppError::forbidden("用户已被封禁"));
     }
 
-    // Issue a new token pair
-    let (access_token, refresh_token) = issue_token_pair(&claims.sub, &state)?;
+    // 同一设备只保留最新的访问会话
+    state
+        .store()
+        .delete_family_sessions(&claims.sub, &rotated.family_id)?;
+    let access_token = issue_access_token(&claims.sub, &rotated.family_id, &client, &state)?;
 
     let mut response = ok(AuthResponse {
         access_token: access_token.clone(),
@@ -382,9 +444,7 @@ async fn reset_password(
     State(state): State<AppState>,
     JsonBody(req): JsonBody<ResetPasswordRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    if let Err(msg) = validate_password(&req.new_password) {
-        return Err(AppError::bad_request("AUTH_WEAK_PASSWORD", msg));
-    }
+    password_breach::enforce(&req.new_password, &state.config().password_policy).await?;
 
     let token_hash = hash_token(&req.token);
     let key = keys::password_reset_key(&token_hash)?;
@@ -396,14 +456,14 @@ async fn reset_password(
         .password_reset_tokens
         .remove(key.as_bytes())
         .map_err(|e| AppError::internal(&e.to_string()))?
-        .ok_or_else(|| AppError::bad_request("AUTH_INVALID_RESET_TOKEN", "重置令牌无效"))?;
+        .ok_or_else(|| AppError::bad_request(ErrorCode::AuthInvalidResetToken, "重置令牌无效"))?;
 
     let entry: PasswordResetEntry = serde_json::from_slice(&raw)
         .map_err(|e| AppError::internal(&format!("reset token decode error: {e}")))?;
 
     if entry.expires_at <= Utc::now() {
         return Err(AppError::bad_request(
-            "AUTH_EXPIRED_RESET_TOKEN",
+            ErrorCode::AuthExpiredResetToken,
             "重置令牌已过期",
         ));
     }
@@ -411,7 +471,7 @@ async fn reset_password(
     let mut user = state
         .store()
         .get_user_by_id(&entry.user_id)?
-        .ok_or_else(|| AppError::bad_request("AUTH_INVALID_RESET_TOKEN", "重置令牌无效"))?;
+        .ok_or_else(|| AppError::bad_request(ErrorCode::AuthInvalidResetToken, "重置令牌无效"))?;
 
     user.password_hash = hash_password(&req.new_password)?;
     user.updated_at = Utc::now();
@@ -434,14 +494,14 @@ async fn verify_reset_token(
         .password_reset_tokens
         .get(key.as_bytes())
         .map_err(|e| AppError::internal(&e.to_string()))?
-        .ok_or_else(|| AppError::bad_request("AUTH_INVALID_RESET_TOKEN", "重置令牌无效"))?;
+        .ok_or_else(|| AppError::bad_request(ErrorCode::AuthInvalidResetToken, "重置令牌无效"))?;
 
     let entry: PasswordResetEntry = serde_json::from_slice(&raw)
         .map_err(|e| AppError::internal(&format!("reset token decode error: {e}")))?;
 
     if entry.expires_at <= Utc::now() {
         return Err(AppError::bad_request(
-            "AUTH_EXPIRED_RESET_TOKEN",
+            ErrorCode::AuthExpiredResetToken,
             "重置令牌已过期",
         ));
     }
@@ -487,7 +547,7 @@ fn append_set_cookie(
     Ok(())
 }
 
-fn mask_email_for_log(email: &str) -> String {
+pub(crate) fn mask_email_for_log(email: &str) -> String {
     let trimmed = email.trim();
     let Some((local, domain)) = trimmed.split_once('@') else {
         return "***".to_string();
@@ -1,28 +1,69 @@
 use axum::extract::{Path, Query, State};
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::Router;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::auth::{AdminAuthUser, AuthUser};
-use crate::constants::MAX_CONFUSION_PAIRS;
+use crate::constants::{
+    DEFAULT_PAGE_SIZE_RECORDS, ETYMOLOGY_REJECTION_FIELDS, EXAMPLE_PROMPT_VERSION,
+    GENERATED_EXAMPLE_COUNT, MAX_CONFUSION_PAIRS,
+};
 use crate::extractors::JsonBody;
 use serde::{Deserialize, Serialize};
 
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::routes::words::WordPublic;
+use crate::routes::{BULK_BODY_LIMIT, JSON_BODY_LIMIT, UPLOAD_BODY_LIMIT};
+use crate::services::llm_provider::{LlmError, LlmFeature};
+use crate::services::{moderation, semantic_search};
 use crate::state::AppState;
 use crate::store::keys;
+use crate::store::operations::content_cache::ContentKind;
+use crate::store::operations::generated_examples::GeneratedExamples;
+use crate::store::operations::words::Word;
 
 pub fn router() -> Router<AppState> {
     Router::new()
+        .route("/limits", get(get_limits))
         .route("/etymology/:word_id", get(get_etymology))
         .route("/semantic/search", get(semantic_search))
         .route("/word-contexts/:word_id", get(get_word_contexts))
+        .route(
+            "/word-contexts/:word_id/regenerate",
+            post(regenerate_word_contexts),
+        )
         .route(
             "/morphemes/:word_id",
             get(get_morphemes).post(set_morphemes),
         )
         .route("/confusion-pairs/:word_id", get(get_confusion_pairs))
+        .route("/related/:word_id", get(get_related_words))
+        .route("/moderation/rejections", get(list_moderation_rejections))
+        .route("/cache/invalidate", post(invalidate_content_cache))
+        .route("/clusters", get(list_clusters))
+        .route("/clusters/:id", get(get_cluster))
+}
+
+/// 服务端实际执行的分页、批量与请求体上限，客户端据此自行配置，无需登录
+async fn get_limits(State(state): State<AppState>) -> impl axum::response::IntoResponse {
+    let config = state.config();
+    ok(serde_json::json!({
+        "pagination": {
+            "defaultPageSize": config.pagination.default_page_size,
+            "recordsDefaultPageSize": DEFAULT_PAGE_SIZE_RECORDS,
+            "maxPageSize": config.pagination.max_page_size,
+        },
+        "batch": {
+            "maxBatchSize": config.limits.max_batch_size,
+            "maxExcludeWordIds": config.limits.max_exclude_word_ids,
+            "maxImportWords": config.limits.max_import_words,
+        },
+        "body": {
+            "jsonBytes": JSON_BODY_LIMIT,
+            "bulkBytes": BULK_BODY_LIMIT,
+            "uploadBytes": UPLOAD_BODY_LIMIT,
+        },
+    }))
 }
 
 // B52: Etymology (LLM-generated, cached in sled)
@@ -34,6 +75,7 @@ async fn get_etymology(
     let key = keys::etymology_key(&word_id)?;
 
     // Check cache first
+    let mut pending_entry = None;
     if let Some(raw) = state
         .store()
         .etymologies
@@ -57,6 +99,7 @@ async fn get_etymology(
             .etymologies
             .remove(key.as_bytes())
             .map_err(|e| AppError::internal(&e.to_string()))?;
+        pending_entry = Some(cached);
     }
 
     // Look up the word
@@ -105,7 +148,7 @@ async fn get_etymology(
         )
     };
 
-    let etymology = serde_json::json!({
+    let mut etymology = serde_json::json!({
         "wordId": word_id,
         "word": word.text,
         "etymology": etymology_text,
@@ -113,6 +156,12 @@ async fn get_etymology(
         "generated": false,
         "source": "rule_based_fallback",
     });
+    // 保留审核拒绝的退避标记，避免后台任务提前重试
+    for field in ETYMOLOGY_REJECTION_FIELDS {
+        if let Some(value) = pending_entry.as_ref().and_then(|e| e.get(field)) {
+            etymology[field] = value.clone();
+        }
+    }
 
     state
         .store()
@@ -141,11 +190,36 @@ async fn semantic_search(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let query = q.query.trim();
     if query.is_empty() {
-        return Err(AppError::bad_request("INVALID_QUERY", "搜索内容不能为空"));
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidQuery,
+            "搜索内容不能为空",
+        ));
     }
     let limit = q.limit.unwrap_or(10).clamp(1, 50);
 
-    // TODO: 接入向量数据库实现真正的语义搜索，当前 fallback 到文本匹配
+    if let Some(query_vec) = embed_query(&state, query).await {
+        let probe = state.config().limits.semantic_search_probe_clusters;
+        let (matches, index) = semantic_search::search(state.store(), &query_vec, limit, probe)?;
+        if !matches.is_empty() {
+            let results: Vec<ScoredWord> = matches
+                .iter()
+                .map(|(word, score)| ScoredWord {
+                    word: WordPublic::from(word),
+                    score: *score,
+                })
+                .collect();
+            return Ok(ok(serde_json::json!({
+                "query": query,
+                "total": results.len(),
+                "results": results,
+                "method": "vector",
+                "index": index.as_str(),
+                "degraded": false,
+            })));
+        }
+    }
+
+    // provider 不可用或尚无词向量时降级为文本匹配
     let (items, total) = state.store().search_words(query, limit, 0)?;
     let items: Vec<WordPublic> = items.iter().map(WordPublic::from).collect();
 
@@ -158,6 +232,26 @@ async fn semantic_search(
     })))
 }
 
+#[derive(Debug, Serialize)]
+struct ScoredWord {
+    #[serde(flatten)]
+    word: WordPublic,
+    score: f64,
+}
+
+async fn embed_query(state: &AppState, query: &str) -> Option<Vec<f64>> {
+    if !state.runtime().is_llm_enabled() {
+        return None;
+    }
+    match state.llm().embed(&[query.to_string()]).await {
+        Ok(mut vectors) => vectors.pop(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Query embedding failed, falling back to keyword search");
+            None
+        }
+    }
+}
+
 // B54: Word contexts
 async fn get_word_contexts(
     _user: AuthUser,
@@ -169,26 +263,141 @@ async fn get_word_contexts(
         .get_word(&word_id)?
         .ok_or_else(|| AppError::not_found("单词不存在"))?;
 
-    // 从 word.examples 生成基本上下文数据
-    let contexts: Vec<serde_json::Value> = word
+    // 缓存缺失或提示词版本过期时尝试生成；同一单词已在生成、处于失败退避期或 LLM 不可用时
+    // 直接返回已有内容（可能为空）
+    let cached = state.store().get_generated_examples(&word_id)?;
+    let generated = match cached {
+        Some(cached) if cached.prompt_version == EXAMPLE_PROMPT_VERSION => Some(cached),
+        stale if !state.runtime().is_llm_enabled() => stale,
+        stale => match state.example_generation().try_begin(&word_id) {
+            None => stale,
+            Some(permit) => match generate_examples(&state, &word).await {
+                Ok(fresh) => {
+                    permit.succeed();
+                    Some(fresh)
+                }
+                Err(GenerationError::Rejected) => {
+                    permit.reject();
+                    stale
+                }
+                Err(e) => {
+                    tracing::debug!(word_id = %word_id, error = %e, "Example generation skipped");
+                    stale
+                }
+            },
+        },
+    };
+
+    Ok(ok(word_contexts_body(&word, generated.as_ref())))
+}
+
+async fn regenerate_word_contexts(
+    _admin: AdminAuthUser,
+    Path(word_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let word = state
+        .store()
+        .get_word(&word_id)?
+        .ok_or_else(|| AppError::not_found("单词不存在"))?;
+    let generated = generate_examples(&state, &word)
+        .await
+        .map_err(|e| match e {
+            GenerationError::Llm(e) => AppError::service_unavailable(
+                ErrorCode::LlmUnavailable,
+                &format!("例句生成失败：{e}"),
+            ),
+            GenerationError::Rejected => {
+                AppError::service_unavailable(ErrorCode::ContentRejected, "生成的例句未通过审核")
+            }
+        })?;
+    Ok(ok(word_contexts_body(&word, Some(&generated))))
+}
+
+#[derive(Debug, thiserror::Error)]
+enum GenerationError {
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+    #[error("generated content rejected by moderation")]
+    Rejected,
+}
+
+/// 调用 LLM 生成例句，审核通过后覆盖缓存
+async fn generate_examples(
+    state: &AppState,
+    word: &Word,
+) -> Result<GeneratedExamples, GenerationError> {
+    if !state.runtime().is_llm_enabled() {
+        return Err(LlmError::Disabled.into());
+    }
+    let sentences = state
+        .llm()
+        .generate_examples(&word.text, &word.meaning, GENERATED_EXAMPLE_COUNT)
+        .await?;
+    let allowed = moderation::screen(
+        state.store(),
+        &state.config().moderation,
+        state.llm(),
+        "examples",
+        &word.id,
+        &sentences,
+    )
+    .await;
+    if !allowed {
+        return Err(GenerationError::Rejected);
+    }
+    let generated = GeneratedExamples {
+        word_id: word.id.clone(),
+        sentences,
+        model: state.llm().model_tag(LlmFeature::Examples),
+        prompt_version: EXAMPLE_PROMPT_VERSION,
+        generated_at: chrono::Utc::now(),
+    };
+    if let Err(e) = state.store().put_generated_examples(&generated) {
+        tracing::warn!(word_id = %word.id, error = %e, "Failed to cache generated examples");
+    }
+    Ok(generated)
+}
+
+/// 单词自带的例句在前，生成的例句在后，与自带例句重复的生成例句省略
+fn word_contexts_body(word: &Word, generated: Option<&GeneratedExamples>) -> serde_json::Value {
+    let mut contexts: Vec<serde_json::Value> = word
         .examples
         .iter()
         .enumerate()
         .map(|(i, example)| {
             serde_json::json!({
-                "id": format!("{}-ctx-{}", word_id, i),
+                "id": format!("{}-ctx-{}", word.id, i),
                 "sentence": example,
                 "source": "word_examples",
             })
         })
         .collect();
+    if let Some(generated) = generated {
+        for (i, sentence) in generated.sentences.iter().enumerate() {
+            if word.examples.contains(sentence) {
+                continue;
+            }
+            contexts.push(serde_json::json!({
+                "id": format!("{}-gen-{}", word.id, i),
+                "sentence": sentence,
+                "source": "generated",
+                "model": generated.model,
+            }));
+        }
+    }
 
-    Ok(ok(serde_json::json!({
-        "wordId": word_id,
+    serde_json::json!({
+        "wordId": word.id,
         "word": word.text,
         "examples": word.examples,
         "contexts": contexts,
-    })))
+        "generated": generated.map(|g| serde_json::json!({
+            "model": g.model,
+            "promptVersion": g.prompt_version,
+            "generatedAt": g.generated_at,
+        })),
+    })
 }
 
 // B55: Word morphemes
@@ -324,7 +533,15 @@ async fn get_confusion_pairs(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let limit = q.limit.unwrap_or(20).clamp(1, MAX_CONFUSION_PAIRS);
+    let pairs = collect_confusion_pairs(&state, &word_id, limit);
+
+    Ok(ok(serde_json::json!({
+        "wordId": word_id,
+        "confusionPairs": pairs,
+    })))
+}
 
+fn collect_confusion_pairs(state: &AppState, word_id: &str, limit: usize) -> Vec<ConfusionPair> {
     let mut pairs = Vec::new();
     let mut seen = HashSet::new();
 
@@ -337,7 +554,7 @@ async fn get_confusion_pairs(
             Ok(kv) => kv,
             Err(_) => continue,
         };
-        if let Some(val) = decode_confusion_pair(&v, &word_id, &state) {
+        if let Some(val) = decode_confusion_pair(&v, word_id, state) {
             if seen.insert(val.word_id.clone()) {
                 pairs.push(val);
             }
@@ -356,7 +573,7 @@ async fn get_confusion_pairs(
             };
             let key_str = String::from_utf8_lossy(&k);
             if key_str.ends_with(&suffix) {
-                if let Some(val) = decode_confusion_pair(&v, &word_id, &state) {
+                if let Some(val) = decode_confusion_pair(&v, word_id, state) {
                     if seen.insert(val.word_id.clone()) {
                         pairs.push(val);
                     }
@@ -365,8 +582,281 @@ async fn get_confusion_pairs(
         }
     }
 
+    pairs
+}
+
+// 相关单词：合并词向量近邻、共享词素与易混淆词三种信号
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedWordsQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relation {
+    #[serde(rename = "type")]
+    relation_type: &'static str,
+    score: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    shared_morphemes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RelatedWord {
+    #[serde(flatten)]
+    word: WordPublic,
+    score: f64,
+    relations: Vec<Relation>,
+}
+
+/// 多个信号同时命中时分数更高：`1 - Π(1 - s)`
+fn combined_score(relations: &[Relation]) -> f64 {
+    1.0 - relations
+        .iter()
+        .map(|r| 1.0 - r.score.clamp(0.0, 1.0))
+        .product::<f64>()
+}
+
+/// 规范化后的词素文本，忽略大小写与连字符（如 `-tion`）
+fn morpheme_set(morphemes: &[Morpheme]) -> BTreeSet<String> {
+    morphemes
+        .iter()
+        .map(|m| m.text.trim().trim_matches('-').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// 与目标单词共享词素的单词，分数为词素集合的 Jaccard 系数
+fn morpheme_neighbors(
+    state: &AppState,
+    word_id: &str,
+    limit: usize,
+) -> Result<Vec<(String, f64, Vec<String>)>, AppError> {
+    let key = keys::word_morpheme_key(word_id)?;
+    let Some(raw) = state
+        .store()
+        .word_morphemes
+        .get(key.as_bytes())
+        .map_err(|e| AppError::internal(&e.to_string()))?
+    else {
+        return Ok(Vec::new());
+    };
+    let own = serde_json::from_slice::<WordMorphemes>(&raw)
+        .map(|m| morpheme_set(&m.morphemes))
+        .unwrap_or_default();
+    if own.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut neighbors = Vec::new();
+    for item in state.store().word_morphemes.iter() {
+        let (_k, v) = item.map_err(|e| AppError::internal(&e.to_string()))?;
+        let Ok(other) = serde_json::from_slice::<WordMorphemes>(&v) else {
+            continue;
+        };
+        if other.word_id == word_id {
+            continue;
+        }
+        let theirs = morpheme_set(&other.morphemes);
+        let shared: Vec<String> = own.intersection(&theirs).cloned().collect();
+        if shared.is_empty() {
+            continue;
+        }
+        let score = shared.len() as f64 / own.union(&theirs).count() as f64;
+        neighbors.push((other.word_id, score, shared));
+    }
+    neighbors.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    neighbors.truncate(limit);
+    Ok(neighbors)
+}
+
+async fn get_related_words(
+    _user: AuthUser,
+    Path(word_id): Path<String>,
+    Query(q): Query<RelatedWordsQuery>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = q.limit.unwrap_or(10).clamp(1, 50);
+    let word = state
+        .store()
+        .get_word(&word_id)?
+        .ok_or_else(|| AppError::not_found("单词不存在"))?;
+
+    let mut relations: HashMap<String, Vec<Relation>> = HashMap::new();
+
+    // 单词尚无词向量时跳过近邻信号，仅返回词素与易混淆词
+    let embedding = word.embedding.as_deref().filter(|e| !e.is_empty());
+    if let Some(embedding) = embedding {
+        let probe = state.config().limits.semantic_search_probe_clusters;
+        let (matches, _) = semantic_search::search(state.store(), embedding, limit + 1, probe)?;
+        for (other, score) in matches {
+            if other.id != word_id && score > 0.0 {
+                relations.entry(other.id).or_default().push(Relation {
+                    relation_type: "semantic",
+                    score: score.min(1.0),
+                    shared_morphemes: Vec::new(),
+                });
+            }
+        }
+    }
+    for (other_id, score, shared) in morpheme_neighbors(&state, &word_id, limit)? {
+        relations.entry(other_id).or_default().push(Relation {
+            relation_type: "morpheme",
+            score,
+            shared_morphemes: shared,
+        });
+    }
+    for pair in collect_confusion_pairs(&state, &word_id, limit) {
+        relations.entry(pair.word_id).or_default().push(Relation {
+            relation_type: "confusion",
+            score: pair.similarity,
+            shared_morphemes: Vec::new(),
+        });
+    }
+
+    let ids: Vec<String> = relations.keys().cloned().collect();
+    let words = state.store().get_words_by_ids(&ids)?;
+    let mut scored: Vec<(String, f64, Vec<Relation>)> = relations
+        .into_iter()
+        .filter(|(id, _)| words.contains_key(id))
+        .map(|(id, relations)| (id, combined_score(&relations), relations))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored.truncate(limit);
+    let related: Vec<RelatedWord> = scored
+        .into_iter()
+        .map(|(id, score, relations)| RelatedWord {
+            word: WordPublic::from(&words[&id]),
+            score,
+            relations,
+        })
+        .collect();
+
     Ok(ok(serde_json::json!({
         "wordId": word_id,
-        "confusionPairs": pairs,
+        "word": word.text,
+        "total": related.len(),
+        "related": related,
+        "degraded": embedding.is_none(),
+    })))
+}
+
+// B73: Word clusters (produced by word_clustering worker)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusterSummary {
+    id: String,
+    theme: String,
+    size: usize,
+    word_ids: Vec<String>,
+    generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RejectionsQuery {
+    limit: Option<usize>,
+}
+
+async fn list_moderation_rejections(
+    _admin: AdminAuthUser,
+    Query(q): Query<RejectionsQuery>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    Ok(ok(state.store().list_moderation_rejections(limit)?))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InvalidateCacheRequest {
+    word_id: Option<String>,
+    tag: Option<String>,
+    #[serde(default)]
+    all: bool,
+    /// 默认只失效可重新生成的词源与例句；词素为人工录入，需显式指定
+    kinds: Option<Vec<ContentKind>>,
+}
+
+async fn invalidate_content_cache(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<InvalidateCacheRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let kinds = req
+        .kinds
+        .unwrap_or_else(|| vec![ContentKind::Etymology, ContentKind::Examples]);
+    let tag = req.tag.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let store = state.store();
+    let result = match (req.word_id, tag, req.all) {
+        (Some(word_id), None, false) => store.invalidate_word_content(&[word_id], &kinds)?,
+        (None, Some(tag), false) => {
+            let word_ids: Vec<String> = store.word_ids_with_tag(tag)?.into_iter().collect();
+            store.invalidate_word_content(&word_ids, &kinds)?
+        }
+        (None, None, true) => store.invalidate_all_content(&kinds)?,
+        _ => {
+            return Err(AppError::bad_request(
+                ErrorCode::ValidationError,
+                "wordId、tag、all 须且只能指定一个",
+            ))
+        }
+    };
+    tracing::info!(invalidated = result.total(), "Content cache invalidated");
+    Ok(ok(serde_json::json!({
+        "invalidated": result,
+        "total": result.total(),
+    })))
+}
+
+async fn list_clusters(
+    _user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let clusters: Vec<ClusterSummary> = state
+        .store()
+        .list_word_clusters()?
+        .into_iter()
+        .map(|c| ClusterSummary {
+            size: c.word_ids.len(),
+            id: c.id,
+            theme: c.theme,
+            word_ids: c.word_ids,
+            generated_at: c.generated_at,
+        })
+        .collect();
+    Ok(ok(serde_json::json!({
+        "total": clusters.len(),
+        "clusters": clusters,
+    })))
+}
+
+async fn get_cluster(
+    _user: AuthUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let cluster = state
+        .store()
+        .get_word_cluster(&id)?
+        .ok_or_else(|| AppError::not_found("聚类不存在"))?;
+
+    // 聚类生成后被删除的单词直接跳过
+    let word_map = state.store().get_words_by_ids(&cluster.word_ids)?;
+    let words: Vec<WordPublic> = cluster
+        .word_ids
+        .iter()
+        .filter_map(|id| word_map.get(id))
+        .map(WordPublic::from)
+        .collect();
+
+    Ok(ok(serde_json::json!({
+        "id": cluster.id,
+        "theme": cluster.theme,
+        "size": cluster.word_ids.len(),
+        "wordIds": cluster.word_ids,
+        "centroid": cluster.centroid,
+        "generatedAt": cluster.generated_at,
+        "words": words,
     })))
 }
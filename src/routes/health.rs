@@ -42,12 +42,29 @@ pub async fn liveness() -> StatusCode {
     StatusCode::OK
 }
 
-pub async fn readiness(State(state): State<AppState>) -> StatusCode {
-    if state.store().get_user_by_id("__health_check__").is_ok() {
+/// 存储可读、迁移已完成且（leader 节点）调度器已启动才返回 200，否则 503
+pub async fn readiness(State(state): State<AppState>) -> impl axum::response::IntoResponse {
+    let store_ok = state.store().get_user_by_id("__health_check__").is_ok();
+    let migrations_ok = state.store().migrations_applied();
+    let scheduler_ok = state.readiness().scheduler_ready();
+    let ready = store_ok && migrations_ok && scheduler_ok;
+
+    let status = if ready {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
-    }
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "ready": ready,
+            "checks": {
+                "store": store_ok,
+                "migrations": migrations_ok,
+                "scheduler": scheduler_ok,
+            }
+        })),
+    )
 }
 
 pub async fn database_health(
@@ -70,8 +87,10 @@ pub async fn metrics(
     _admin: AdminAuthUser,
     State(state): State<AppState>,
 ) -> impl axum::response::IntoResponse {
-    let snapshot = state.amas().metrics_registry().snapshot();
+    let registry = state.amas().metrics_registry();
     Json(serde_json::json!({
-        "algorithms": snapshot,
+        "algorithms": registry.snapshot(),
+        "userLockWait": registry.lock_wait_snapshot(),
+        "llmCircuit": state.llm().circuit_snapshot(),
     }))
 }
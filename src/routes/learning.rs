@@ -6,21 +6,31 @@ use crate::extractors::JsonBody;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+use crate::amas::intervention;
+use crate::amas::placement::{
+    self, PlacementAnswer, PlacementResult, PLACEMENT_POOL_SIZE, PLACEMENT_QUESTIONS,
+};
+use crate::amas::types::Intervention;
 use crate::amas::word_selector::{self, SessionSelectionContext};
 use crate::auth::AuthUser;
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::routes::words::WordPublic;
 use crate::state::AppState;
 use crate::store::operations::learning_sessions::{LearningSession, SessionStatus, SessionSummary};
+use crate::store::operations::words::Word;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/session", post(create_or_resume_session))
         .route("/study-words", get(get_study_words))
+        .route("/due-only-words", get(get_due_only_words))
         .route("/next-words", post(next_words))
         .route("/adjust-words", post(adjust_words))
         .route("/sync-progress", post(sync_progress))
         .route("/complete-session", post(complete_session))
+        .route("/explain", get(get_latest_explanation))
+        .route("/intervention", get(get_intervention))
+        .route("/placement", get(get_placement).post(submit_placement))
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -230,6 +240,32 @@ async fn get_study_words(
     }))
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DueOnlyWordsResponse {
+    words: Vec<WordPublic>,
+    limit: u32,
+}
+
+/// 只复习已到期单词：严格按到期索引顺序取词，不注入新词，数量受每日单词数限制
+async fn get_due_only_words(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let config = state.store().get_study_config(&auth.user_id)?;
+    let limit = config.daily_word_count;
+    let due = state.store().get_due_words(&auth.user_id, limit as usize)?;
+
+    let due_word_ids: Vec<String> = due.iter().map(|s| s.word_id.clone()).collect();
+    let words_by_id = state.store().get_words_by_ids(&due_word_ids)?;
+    let words = due_word_ids
+        .iter()
+        .filter_map(|wid| words_by_id.get(wid).map(WordPublic::from))
+        .collect();
+
+    Ok(ok(DueOnlyWordsResponse { words, limit }))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct NextWordsRequest {
@@ -254,7 +290,7 @@ async fn next_words(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.exclude_word_ids.len() > state.config().limits.max_exclude_word_ids {
         return Err(AppError::bad_request(
-            "LEARNING_TOO_MANY_EXCLUDES",
+            ErrorCode::LearningTooManyExcludes,
             &format!(
                 "排除单词数量不能超过{}",
                 state.config().limits.max_exclude_word_ids
@@ -391,7 +427,7 @@ async fn adjust_words(
     if let Some(recent_performance) = req.recent_performance {
         if !recent_performance.is_finite() || !(0.0..=1.0).contains(&recent_performance) {
             return Err(AppError::bad_request(
-                "LEARNING_INVALID_RECENT_PERFORMANCE",
+                ErrorCode::LearningInvalidRecentPerformance,
                 "recentPerformance 必须是0到1之间的数值",
             ));
         }
@@ -551,3 +587,183 @@ async fn complete_session(
 
     Ok(ok(session))
 }
+
+async fn get_latest_explanation(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let record = state.amas().get_latest_explanation(&auth.user_id)?;
+    Ok(ok(record))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InterventionResponse {
+    /// 按当前状态评估的建议，不受冷却限制
+    current: Option<Intervention>,
+    /// 最近一次实际触发的干预
+    last_triggered: Option<Intervention>,
+}
+
+async fn get_intervention(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let amas_state = state.amas().get_user_state(&auth.user_id)?;
+    let amas_config = state.amas().get_config().await;
+    let current = intervention::evaluate(&amas_state, &amas_config.intervention, Utc::now());
+    Ok(ok(InterventionResponse {
+        current,
+        last_triggered: amas_state.last_intervention,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlacementRequest {
+    /// 已作答的全部题目，每次提交都带上之前的作答
+    #[serde(default)]
+    answers: Vec<PlacementAnswer>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlacementResponse {
+    completed: bool,
+    answered: usize,
+    total: usize,
+    estimated_elo: f64,
+    next_word: Option<WordPublic>,
+    result: Option<PlacementResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlacementStatus {
+    /// 未完成定级且尚无学习记录时可以参加
+    available: bool,
+    result: Option<PlacementResult>,
+}
+
+async fn get_placement(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let result = state.store().get_placement_result(&auth.user_id)?;
+    let available = result.is_none() && state.store().get_user_elo(&auth.user_id)?.games == 0;
+    Ok(ok(PlacementStatus { available, result }))
+}
+
+async fn submit_placement(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<PlacementRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let store = state.store();
+    if store.get_placement_result(&auth.user_id)?.is_some()
+        || store.get_user_elo(&auth.user_id)?.games > 0
+    {
+        return Err(AppError::conflict(
+            ErrorCode::LearningPlacementCompleted,
+            "已完成定级测试或已有学习记录",
+        ));
+    }
+
+    let answers = req.answers;
+    if answers.len() > PLACEMENT_QUESTIONS {
+        return Err(AppError::bad_request(
+            ErrorCode::LearningInvalidPlacementAnswers,
+            &format!("定级测试最多{PLACEMENT_QUESTIONS}题"),
+        ));
+    }
+
+    let config = state.amas().get_config().await;
+    let pool = store.list_words(PLACEMENT_POOL_SIZE, 0)?;
+    let pool_ids: Vec<String> = pool.iter().map(|w| w.id.clone()).collect();
+    let pool_elos = store.get_word_elos_by_ids(&pool_ids)?;
+    let rated: Vec<(&Word, f64)> = pool
+        .iter()
+        .map(|w| {
+            let rating = placement::word_rating(w.difficulty, &pool_elos[&w.id], &config);
+            (w, rating)
+        })
+        .collect();
+
+    // 出题顺序是确定的：按已答前缀重算每一题，作答必须与当时下发的单词一致
+    let mut replay: Vec<(f64, bool)> = Vec::with_capacity(answers.len());
+    for (i, answer) in answers.iter().enumerate() {
+        let estimate = placement::estimate_elo(&replay, &config);
+        match next_placement_word(&rated, &answers[..i], estimate.rating) {
+            Some((word, rating)) if word.id == answer.word_id => {
+                replay.push((rating, answer.is_correct))
+            }
+            _ => {
+                return Err(AppError::bad_request(
+                    ErrorCode::LearningInvalidPlacementAnswers,
+                    "定级测试作答与下发的题目不一致",
+                ))
+            }
+        }
+    }
+    let estimate = placement::estimate_elo(&replay, &config);
+
+    let next_word = if answers.len() < PLACEMENT_QUESTIONS {
+        next_placement_word(&rated, &answers, estimate.rating).map(|(w, _)| WordPublic::from(w))
+    } else {
+        None
+    };
+    if next_word.is_some() {
+        return Ok(ok(PlacementResponse {
+            completed: false,
+            answered: answers.len(),
+            total: PLACEMENT_QUESTIONS,
+            estimated_elo: estimate.rating,
+            next_word,
+            result: None,
+        }));
+    }
+    if answers.is_empty() {
+        return Err(AppError::not_found("暂无可用于定级测试的单词"));
+    }
+
+    // 题库不足时提前结束
+    let result = PlacementResult {
+        elo: estimate.rating,
+        answered: answers.len(),
+        correct: answers.iter().filter(|a| a.is_correct).count(),
+        cognitive_profile: placement::seed_profile(&answers, &config),
+        completed_at: Utc::now(),
+    };
+    if !state
+        .amas()
+        .complete_placement(&auth.user_id, &result, &estimate)
+        .await?
+    {
+        return Err(AppError::conflict(
+            ErrorCode::LearningPlacementCompleted,
+            "已完成定级测试或已有学习记录",
+        ));
+    }
+
+    Ok(ok(PlacementResponse {
+        completed: true,
+        answered: result.answered,
+        total: PLACEMENT_QUESTIONS,
+        estimated_elo: result.elo,
+        next_word: None,
+        result: Some(result),
+    }))
+}
+
+/// 下一题取难度最接近当前估计的未答单词
+fn next_placement_word<'a>(
+    rated: &[(&'a Word, f64)],
+    answered: &[PlacementAnswer],
+    estimate: f64,
+) -> Option<(&'a Word, f64)> {
+    rated
+        .iter()
+        .filter(|(w, _)| !answered.iter().any(|a| a.word_id == w.id))
+        .min_by(|a, b| (a.1 - estimate).abs().total_cmp(&(b.1 - estimate).abs()))
+        .copied()
+}
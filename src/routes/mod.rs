@@ -15,18 +15,23 @@ pub mod wordbook_center;
 pub mod wordbooks;
 pub mod words;
 
-use axum::extract::DefaultBodyLimit;
+use axum::extract::{DefaultBodyLimit, State};
 use axum::http::{header, HeaderValue, Request};
 use axum::middleware::Next;
 use axum::response::Response;
 use axum::Router;
 use tower_http::services::{ServeDir, ServeFile};
 
-use crate::middleware::{rate_limit, request_id};
+use crate::middleware::compression::compression_layer;
+use crate::middleware::{in_flight, locale, maintenance, rate_limit, request_id};
 use crate::state::AppState;
 
-/// Maximum request body size: 2 MiB.
-const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+/// 默认请求体上限（认证与普通 JSON 接口）：64 KiB
+pub(crate) const JSON_BODY_LIMIT: usize = 64 * 1024;
+/// 批量写入与导入接口的请求体上限：2 MiB
+pub(crate) const BULK_BODY_LIMIT: usize = 2 * 1024 * 1024;
+/// 文件上传接口的请求体上限：8 MiB
+pub(crate) const UPLOAD_BODY_LIMIT: usize = 8 * 1024 * 1024;
 
 pub fn build_router(state: AppState) -> Router {
     // 认证路由组添加专用速率限制
@@ -65,26 +70,48 @@ pub fn build_router(state: AppState) -> Router {
         .nest("/content", content::router())
         .nest("/wordbook-center", wordbook_center::user_router())
         .nest("/v1", v1::router())
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            maintenance::maintenance_middleware,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             rate_limit::rate_limit_middleware,
         ))
-        .layer(DefaultBodyLimit::max(MAX_BODY_SIZE));
+        .layer(DefaultBodyLimit::max(JSON_BODY_LIMIT));
 
     // B29: Static file serving with SPA fallback
-    let spa_fallback =
-        ServeDir::new("static").not_found_service(ServeFile::new("static/index.html"));
+    let static_assets = &state.config().static_assets;
+    // 目录中带有预压缩的 .br/.gz 文件时直接返回，压缩层会跳过已编码的响应
+    let spa_fallback = ServeDir::new(&static_assets.dir)
+        .precompressed_br()
+        .precompressed_gzip()
+        .not_found_service(ServeFile::new(static_assets.index_file()));
 
     Router::new()
         .nest("/api", api_routes)
         .nest("/health", health::router())
         .fallback_service(spa_fallback)
-        .layer(axum::middleware::from_fn(static_cache_headers))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            static_cache_headers,
+        ))
         .layer(axum::middleware::from_fn(request_id::request_id_middleware))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            in_flight::in_flight_middleware,
+        ))
+        .layer(axum::middleware::from_fn(locale::locale_middleware))
+        // 最外层压缩，request_id 改写错误响应体之后再编码
+        .layer(compression_layer(&state.config().compression))
         .with_state(state)
 }
 
-async fn static_cache_headers(req: Request<axum::body::Body>, next: Next) -> Response {
+async fn static_cache_headers(
+    State(state): State<AppState>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
     let path = req.uri().path().to_string();
     let mut response = next.run(req).await;
 
@@ -99,17 +126,13 @@ async fn static_cache_headers(req: Request<axum::body::Body>, next: Next) -> Res
         .and_then(|v| v.to_str().ok())
         .is_some_and(|ct| ct.contains("text/html"));
 
-    let cache_value = if is_html {
-        "no-cache, must-revalidate"
-    } else if path.starts_with("/assets/") {
-        "public, max-age=31536000, immutable"
-    } else {
-        "public, max-age=3600"
-    };
+    let cache_value = state
+        .config()
+        .static_assets
+        .cache_control(is_html, path.starts_with("/assets/"));
 
-    response.headers_mut().insert(
-        header::CACHE_CONTROL,
-        HeaderValue::from_static(cache_value),
-    );
+    if let Ok(value) = HeaderValue::from_str(&cache_value) {
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
     response
 }
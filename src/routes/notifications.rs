@@ -9,9 +9,13 @@ use serde::{Deserialize, Serialize};
 use crate::auth::AuthUser;
 use crate::constants::{DEFAULT_LANGUAGE, DEFAULT_THEME};
 use crate::extractors::JsonBody;
-use crate::response::{ok, AppError};
+use crate::response::{ok, paginated, AppError, ErrorCode};
+use crate::routes::words::validate_import_url;
+use crate::services::webhook;
 use crate::state::AppState;
 use crate::store::keys;
+use crate::store::operations::notifications::QuietHours;
+use crate::store::operations::webhooks::UserWebhook;
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -21,13 +25,20 @@ pub fn router() -> Router<AppState> {
         .route("/read-all", post(mark_all_read))
         .route("/badges", get(list_badges))
         .route("/preferences", get(get_preferences).put(set_preferences))
+        .route(
+            "/webhook",
+            get(get_webhook).put(set_webhook).delete(delete_webhook),
+        )
 }
 
 // B57: Notification CRUD
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct NotificationQuery {
-    limit: Option<usize>,
+    page: Option<u64>,
+    /// 兼容旧参数 `limit`
+    #[serde(alias = "limit")]
+    per_page: Option<u64>,
     unread_only: Option<bool>,
 }
 
@@ -36,14 +47,21 @@ async fn list_notifications(
     Query(q): Query<NotificationQuery>,
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
-    let limit = q.limit.unwrap_or(50).clamp(1, 200);
-    let unread_only = q.unread_only.unwrap_or(false);
+    let page = q.page.unwrap_or(1).max(1);
+    let per_page = q
+        .per_page
+        .unwrap_or(state.config().pagination.default_page_size)
+        .clamp(1, state.config().pagination.max_page_size);
+    let offset = ((page - 1) * per_page) as usize;
 
-    let notifications = state
-        .store()
-        .list_notifications(&auth.user_id, limit, unread_only)?;
+    let (notifications, total) = state.store().list_notifications(
+        &auth.user_id,
+        per_page as usize,
+        offset,
+        q.unread_only.unwrap_or(false),
+    )?;
 
-    Ok(ok(notifications))
+    Ok(paginated(notifications, total, page, per_page))
 }
 
 async fn get_unread_count(
@@ -167,18 +185,38 @@ async fn list_badges(
         },
     ];
 
-    // Persist newly unlocked badges
-    for badge in &badges {
-        if badge.unlocked {
-            let key = keys::badge_key(&auth.user_id, &badge.id)
-                .map_err(|e| AppError::internal(&e.to_string()))?;
-            store
-                .badges
-                .insert(
-                    key.as_bytes(),
-                    serde_json::to_vec(badge).map_err(|e| AppError::internal(&e.to_string()))?,
-                )
-                .map_err(|e| AppError::internal(&e.to_string()))?;
+    // 以 CAS 从未解锁写为已解锁，只有写入成功的请求推送 webhook，并发查询不会重复推送
+    for badge in badges.iter().filter(|b| b.unlocked) {
+        let key = keys::badge_key(&auth.user_id, &badge.id)
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        let current = store
+            .badges
+            .get(key.as_bytes())
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        if current
+            .as_deref()
+            .and_then(|raw| serde_json::from_slice::<Badge>(raw).ok())
+            .is_some_and(|b| b.unlocked)
+        {
+            continue;
+        }
+        let value = serde_json::to_vec(badge).map_err(|e| AppError::internal(&e.to_string()))?;
+        let swapped = store
+            .badges
+            .compare_and_swap(key.as_bytes(), current, Some(value))
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        if swapped.is_ok() {
+            webhook::dispatch(
+                store,
+                &auth.user_id,
+                "badge_unlocked",
+                serde_json::json!({
+                    "badgeId": badge.id,
+                    "name": badge.name,
+                    "description": badge.description,
+                    "unlockedAt": badge.unlocked_at,
+                }),
+            );
         }
     }
 
@@ -218,6 +256,8 @@ struct UserPreferences {
     language: String,
     notification_enabled: bool,
     sound_enabled: bool,
+    #[serde(default)]
+    quiet_hours: Option<QuietHours>,
 }
 
 impl Default for UserPreferences {
@@ -227,6 +267,7 @@ impl Default for UserPreferences {
             language: DEFAULT_LANGUAGE.to_string(),
             notification_enabled: true,
             sound_enabled: true,
+            quiet_hours: None,
         }
     }
 }
@@ -238,6 +279,7 @@ struct UpdateUserPreferences {
     language: Option<String>,
     notification_enabled: Option<bool>,
     sound_enabled: Option<bool>,
+    quiet_hours: Option<QuietHours>,
 }
 
 async fn get_preferences(
@@ -277,7 +319,7 @@ async fn set_preferences(
         const VALID_THEMES: &[&str] = &["light", "dark", "system"];
         if !VALID_THEMES.contains(&v.as_str()) {
             return Err(AppError::bad_request(
-                "INVALID_THEME",
+                ErrorCode::InvalidTheme,
                 "主题必须是以下之一：light、dark、system",
             ));
         }
@@ -287,7 +329,7 @@ async fn set_preferences(
         const VALID_LANGUAGES: &[&str] = &["en", "zh", "ja", "ko", "fr", "de", "es"];
         if !VALID_LANGUAGES.contains(&v.as_str()) {
             return Err(AppError::bad_request(
-                "INVALID_LANGUAGE",
+                ErrorCode::InvalidLanguage,
                 "语言必须是以下之一：en、zh、ja、ko、fr、de、es",
             ));
         }
@@ -299,6 +341,21 @@ async fn set_preferences(
     if let Some(v) = req.sound_enabled {
         prefs.sound_enabled = v;
     }
+    if let Some(v) = req.quiet_hours {
+        if v.start_hour > 23 || v.end_hour > 23 {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidQuietHours,
+                "静默时段的小时必须在 0-23 之间",
+            ));
+        }
+        if v.tz().is_none() {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidTimezone,
+                "时区必须是有效的 IANA 时区名，如 Asia/Shanghai",
+            ));
+        }
+        prefs.quiet_hours = Some(v);
+    }
 
     state
         .store()
@@ -310,3 +367,90 @@ async fn set_preferences(
         .map_err(|e| AppError::internal(&e.to_string()))?;
     Ok(ok(prefs))
 }
+
+// Webhook 推送
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookView {
+    url: String,
+    /// 仅在创建或轮换密钥时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+impl WebhookView {
+    fn from_webhook(webhook: &UserWebhook, reveal_secret: bool) -> Self {
+        Self {
+            url: webhook.url.clone(),
+            secret: reveal_secret.then(|| webhook.secret.clone()),
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetWebhookRequest {
+    url: String,
+    #[serde(default)]
+    rotate_secret: bool,
+}
+
+async fn get_webhook(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let webhook = state
+        .store()
+        .get_user_webhook(&auth.user_id)?
+        .ok_or_else(|| AppError::not_found("未配置 webhook"))?;
+    Ok(ok(WebhookView::from_webhook(&webhook, false)))
+}
+
+async fn set_webhook(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<SetWebhookRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let url = validate_import_url(req.url.trim()).map_err(|e| AppError {
+        code: ErrorCode::WebhookInvalidUrl,
+        ..e
+    })?;
+
+    let now = Utc::now();
+    let existing = state.store().get_user_webhook(&auth.user_id)?;
+    let reveal_secret = existing.is_none() || req.rotate_secret;
+    let webhook = match existing {
+        Some(w) => UserWebhook {
+            url: url.to_string(),
+            secret: if req.rotate_secret {
+                webhook::generate_secret()
+            } else {
+                w.secret
+            },
+            updated_at: now,
+            ..w
+        },
+        None => UserWebhook {
+            user_id: auth.user_id.clone(),
+            url: url.to_string(),
+            secret: webhook::generate_secret(),
+            created_at: now,
+            updated_at: now,
+        },
+    };
+    state.store().set_user_webhook(&webhook)?;
+
+    Ok(ok(WebhookView::from_webhook(&webhook, reveal_secret)))
+}
+
+async fn delete_webhook(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let deleted = state.store().delete_user_webhook(&auth.user_id)?;
+    Ok(ok(serde_json::json!({ "deleted": deleted })))
+}
@@ -2,14 +2,20 @@ use std::convert::Infallible;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+use axum::extract::Query;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::routing::get;
 use axum::{extract::State, Router};
 use futures::Stream;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::auth::AuthUser;
-use crate::response::AppError;
+use crate::response::{ok, AppError};
 use crate::state::AppState;
+use crate::store::operations::visual_fatigue::VISUAL_FATIGUE_HISTORY_CAP;
+
+const DEFAULT_FATIGUE_HISTORY_LIMIT: usize = 50;
 
 static SSE_CONNECTION_COUNT: AtomicUsize = AtomicUsize::new(0);
 
@@ -20,15 +26,8 @@ impl Drop for SseGuard {
     }
 }
 
-pub fn router() -> Router<AppState> {
-    Router::new().route("/events", get(sse_handler))
-}
-
-pub async fn sse_handler(
-    auth: AuthUser,
-    State(state): State<AppState>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
-    let max_sse = state.config().limits.max_sse_connections;
+/// 占用一个 SSE 连接名额，guard 随流一起释放
+fn acquire_sse_slot(max_sse: usize) -> Result<SseGuard, AppError> {
     loop {
         let current = SSE_CONNECTION_COUNT.load(Ordering::SeqCst);
         if current >= max_sse {
@@ -40,16 +39,55 @@ pub async fn sse_handler(
             Ordering::SeqCst,
             Ordering::SeqCst,
         ) {
-            Ok(_) => break,
+            Ok(_) => return Ok(SseGuard),
             Err(_) => continue,
         }
     }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/events", get(sse_handler))
+        .route("/notifications/stream", get(notification_stream_handler))
+        .route("/fatigue/history", get(fatigue_history))
+}
+
+#[derive(Debug, Deserialize)]
+struct FatigueHistoryQuery {
+    limit: Option<usize>,
+}
+
+/// 最近的视觉疲劳上报（时间升序），附当前引擎疲劳度
+async fn fatigue_history(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<FatigueHistoryQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_FATIGUE_HISTORY_LIMIT)
+        .clamp(1, VISUAL_FATIGUE_HISTORY_CAP);
+    let samples = state
+        .store()
+        .list_visual_fatigue_samples(&auth.user_id, limit)?;
+    let user_state = state.amas().get_user_state(&auth.user_id)?;
+    Ok(ok(serde_json::json!({
+        "samples": samples,
+        "currentFatigue": user_state.fatigue,
+    })))
+}
+
+pub async fn sse_handler(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let guard = acquire_sse_slot(state.config().limits.max_sse_connections)?;
 
     let mut shutdown_rx = state.shutdown_rx();
     let user_id = auth.user_id.clone();
 
     let stream = async_stream::stream! {
-        let _guard = SseGuard;
+        let _guard = guard;
         let mut interval = tokio::time::interval(Duration::from_secs(5));
         let mut last_event_count: u64 = 0;
 
@@ -99,3 +137,44 @@ pub async fn sse_handler(
             .text("keepalive"),
     ))
 }
+
+/// 推送当前用户新创建的通知，客户端断开时流被丢弃即取消订阅
+pub async fn notification_stream_handler(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let guard = acquire_sse_slot(state.config().limits.max_sse_connections)?;
+
+    let mut shutdown_rx = state.shutdown_rx();
+    let mut notification_rx = state.store().subscribe_notifications();
+    let user_id = auth.user_id;
+
+    let stream = async_stream::stream! {
+        let _guard = guard;
+        loop {
+            tokio::select! {
+                received = notification_rx.recv() => match received {
+                    Ok(event) if event.user_id == user_id => {
+                        if let Ok(json) = serde_json::to_string(&event.notification) {
+                            yield Ok(Event::default().event("notification").data(json));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Notification stream lagged");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = shutdown_rx.recv() => {
+                    break;
+                }
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keepalive"),
+    ))
+}
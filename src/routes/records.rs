@@ -1,4 +1,5 @@
-use axum::extract::{Query, State};
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::Router;
@@ -9,9 +10,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::amas::types::{MasteryLevel, ProcessResult, RawEvent};
 use crate::auth::AuthUser;
-use crate::constants::{DEFAULT_HALF_LIFE_HOURS, DEFAULT_PAGE_SIZE_RECORDS, MAX_PAGE_SIZE};
-use crate::response::{created, ok, paginated, AppError};
+use crate::constants::{DEFAULT_HALF_LIFE_HOURS, DEFAULT_PAGE_SIZE_RECORDS};
+use crate::response::{created, ok, paginated, AppError, ErrorCode};
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
+use crate::store::operations::idempotency::{IdempotencyReservation, IdempotentResponse};
 use crate::store::operations::learning_sessions::LearningSession;
 use crate::store::operations::records::LearningRecord;
 use crate::store::operations::word_states::{WordLearningState, WordState};
@@ -21,7 +24,11 @@ pub fn router() -> Router<AppState> {
         .route("/", get(list_records).post(create_record))
         .route("/statistics", get(get_statistics))
         .route("/statistics/enhanced", get(get_enhanced_statistics))
-        .route("/batch", post(batch_create_records))
+        .route("/statistics/velocity", get(get_velocity))
+        .route(
+            "/batch",
+            post(batch_create_records).layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,8 +42,8 @@ impl ListRecordsQuery {
     fn page(&self) -> u64 {
         self.page.unwrap_or(1).clamp(1, u64::MAX)
     }
-    fn per_page(&self) -> u64 {
-        self.per_page.unwrap_or(DEFAULT_PAGE_SIZE_RECORDS).clamp(1, MAX_PAGE_SIZE)
+    fn per_page(&self, max_page_size: u64) -> u64 {
+        self.per_page.unwrap_or(DEFAULT_PAGE_SIZE_RECORDS).clamp(1, max_page_size)
     }
 }
 
@@ -46,7 +53,7 @@ async fn list_records(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let page = q.page();
-    let per_page = q.per_page();
+    let per_page = q.per_page(state.config().pagination.max_page_size);
     let limit = per_page as usize;
     let offset = ((page - 1) * per_page) as usize;
     let records = state
@@ -56,7 +63,7 @@ async fn list_records(
     Ok(paginated(records, total, page, per_page))
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct CreateRecordRequest {
     client_record_id: Option<String>,
@@ -212,6 +219,13 @@ fn restore_engine_algo_state(
     }
 }
 
+fn record_mastery_rollup(store: &crate::store::Store, user_id: &str, record: &LearningRecord) {
+    let date = record.created_at.format("%Y-%m-%d").to_string();
+    if let Err(error) = store.increment_user_daily_mastered(user_id, &date, 1) {
+        tracing::warn!(user_id, error = %error, "Failed to update daily mastery rollup");
+    }
+}
+
 async fn process_single_record(
     user_id: &str,
     req: &CreateRecordRequest,
@@ -233,16 +247,6 @@ async fn process_single_record(
         });
     }
 
-    let record = LearningRecord {
-        id: record_id,
-        user_id: user_id.to_string(),
-        word_id: req.word_id.clone(),
-        is_correct: req.is_correct,
-        response_time_ms: req.response_time_ms,
-        session_id: req.session_id.clone(),
-        created_at: Utc::now(),
-    };
-
     let engine_snapshot = capture_engine_state_snapshot(state.store(), user_id, &req.word_id)?;
 
     let amas_result = state
@@ -268,6 +272,18 @@ async fn process_single_record(
         )
         .await?;
 
+    let record = LearningRecord {
+        id: record_id,
+        user_id: user_id.to_string(),
+        word_id: req.word_id.clone(),
+        is_correct: req.is_correct,
+        response_time_ms: req.response_time_ms,
+        session_id: req.session_id.clone(),
+        created_at: Utc::now(),
+        algorithm_id: amas_result.dominant_algorithm(),
+        algorithm_weights: amas_result.algorithm_weights.clone(),
+    };
+
     // 更新 ELO 评分
     {
         let amas_config = state.amas().get_config().await;
@@ -284,6 +300,7 @@ async fn process_single_record(
     }
 
     let mut next_word_state: Option<WordLearningState> = None;
+    let mut newly_mastered = false;
     if let Some(ref wm) = amas_result.word_mastery {
         let new_state = match wm.mastery_level {
             MasteryLevel::New => WordState::New,
@@ -308,6 +325,7 @@ async fn process_single_record(
                 updated_at: Utc::now(),
             });
 
+        newly_mastered = new_state == WordState::Mastered && wls.state != WordState::Mastered;
         wls.state = new_state;
         wls.mastery_level = wm.memory_strength;
         wls.total_attempts += 1;
@@ -350,6 +368,10 @@ async fn process_single_record(
             AppError::internal(&error.to_string())
         })?;
 
+    if newly_mastered {
+        record_mastery_rollup(state.store(), user_id, &record);
+    }
+
     Ok(CreateRecordResponse {
         record,
         amas_result: Some(amas_result),
@@ -357,21 +379,202 @@ async fn process_single_record(
     })
 }
 
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+const IDEMPOTENCY_TTL_HOURS: i64 = 24;
+/// 处理中标记的有效期，进程崩溃后 key 不会被永久占用
+const IDEMPOTENCY_PENDING_TTL_MINUTES: i64 = 5;
+const IDEMPOTENCY_SCOPE_SINGLE: &str = "records";
+const IDEMPOTENCY_SCOPE_BATCH: &str = "records_batch";
+
+/// 读取 Idempotency-Key 请求头并取摘要；未携带时返回 None，回退到 clientRecordId 去重
+fn idempotency_key_hash(headers: &HeaderMap) -> Result<Option<String>, AppError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value.to_str().map(str::trim).unwrap_or_default();
+    if key.is_empty() || key.len() > IDEMPOTENCY_KEY_MAX_LEN {
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidIdempotencyKey,
+            "Idempotency-Key 须为 1-255 个可见字符",
+        ));
+    }
+    Ok(Some(crate::auth::hash_token(key)))
+}
+
+fn respond(status: StatusCode, body: serde_json::Value) -> axum::response::Response {
+    if status == StatusCode::CREATED {
+        created(body).into_response()
+    } else {
+        ok(body).into_response()
+    }
+}
+
+/// 已占用的 Idempotency-Key 及请求体摘要
+struct IdempotencyClaim {
+    key_hash: String,
+    request_hash: String,
+}
+
+enum IdempotencyStart {
+    /// 未携带 Idempotency-Key
+    Untracked,
+    Claimed(IdempotencyClaim),
+    Replay(axum::response::Response),
+}
+
+/// 以 CAS 占用 key：已完成的相同请求直接重放，处理中返回 409，请求体不同返回 422
+fn begin_idempotent<T: Serialize>(
+    state: &AppState,
+    user_id: &str,
+    scope: &str,
+    headers: &HeaderMap,
+    req: &T,
+) -> Result<IdempotencyStart, AppError> {
+    let Some(key_hash) = idempotency_key_hash(headers)? else {
+        return Ok(IdempotencyStart::Untracked);
+    };
+    let body = serde_json::to_string(req).map_err(|e| AppError::internal(&e.to_string()))?;
+    let request_hash = crate::auth::hash_token(&body);
+    let now = Utc::now();
+    let pending = IdempotentResponse {
+        status: 0,
+        body: serde_json::Value::Null,
+        request_hash: Some(request_hash.clone()),
+        pending: true,
+        created_at: now,
+        expires_at: now + chrono::Duration::minutes(IDEMPOTENCY_PENDING_TTL_MINUTES),
+    };
+    let saved = match state
+        .store()
+        .reserve_idempotency_key(user_id, scope, &key_hash, &pending)?
+    {
+        IdempotencyReservation::Reserved => {
+            return Ok(IdempotencyStart::Claimed(IdempotencyClaim {
+                key_hash,
+                request_hash,
+            }))
+        }
+        IdempotencyReservation::Existing(saved) => saved,
+    };
+    if saved
+        .request_hash
+        .as_deref()
+        .is_some_and(|hash| hash != request_hash)
+    {
+        return Err(AppError::unprocessable(
+            ErrorCode::IdempotencyKeyReused,
+            "Idempotency-Key 已用于不同的请求",
+        ));
+    }
+    if saved.pending {
+        return Err(AppError::conflict(
+            ErrorCode::IdempotencyKeyInProgress,
+            "相同 Idempotency-Key 的请求正在处理中",
+        ));
+    }
+    let status = StatusCode::from_u16(saved.status).unwrap_or(StatusCode::OK);
+    let mut response = respond(status, saved.body);
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    Ok(IdempotencyStart::Replay(response))
+}
+
+/// 首次请求失败时释放 key，客户端可用同一 key 重试
+fn release_idempotent(
+    state: &AppState,
+    user_id: &str,
+    scope: &str,
+    claim: Option<&IdempotencyClaim>,
+) {
+    let Some(claim) = claim else {
+        return;
+    };
+    if let Err(error) = state
+        .store()
+        .release_idempotency_key(user_id, scope, &claim.key_hash)
+    {
+        tracing::warn!(user_id, error = %error, "Failed to release idempotency key");
+    }
+}
+
+/// 仅缓存成功响应；写入失败时释放 key，重试时回退到 clientRecordId 去重
+fn finish_idempotent(
+    state: &AppState,
+    user_id: &str,
+    scope: &str,
+    claim: Option<&IdempotencyClaim>,
+    status: StatusCode,
+    body: serde_json::Value,
+) -> axum::response::Response {
+    if let Some(claim) = claim {
+        let now = Utc::now();
+        let saved = IdempotentResponse {
+            status: status.as_u16(),
+            body: body.clone(),
+            request_hash: Some(claim.request_hash.clone()),
+            pending: false,
+            created_at: now,
+            expires_at: now + chrono::Duration::hours(IDEMPOTENCY_TTL_HOURS),
+        };
+        if let Err(error) =
+            state
+                .store()
+                .put_idempotent_response(user_id, scope, &claim.key_hash, &saved)
+        {
+            tracing::warn!(user_id, error = %error, "Failed to store idempotent response");
+            release_idempotent(state, user_id, scope, Some(claim));
+        }
+    }
+    respond(status, body)
+}
+
 async fn create_record(
     auth: AuthUser,
     State(state): State<AppState>,
+    headers: HeaderMap,
     JsonBody(req): JsonBody<CreateRecordRequest>,
 ) -> Result<axum::response::Response, AppError> {
-    let result = process_single_record(&auth.user_id, &req, &state).await?;
-    if result.duplicate {
-        Ok(ok(result).into_response())
-    } else {
-        Ok(created(result).into_response())
-    }
+    let scope = IDEMPOTENCY_SCOPE_SINGLE;
+    let claim = match begin_idempotent(&state, &auth.user_id, scope, &headers, &req)? {
+        IdempotencyStart::Replay(response) => return Ok(response),
+        IdempotencyStart::Claimed(claim) => Some(claim),
+        IdempotencyStart::Untracked => None,
+    };
+
+    let processed = process_single_record(&auth.user_id, &req, &state)
+        .await
+        .and_then(|result| {
+            let status = if result.duplicate {
+                StatusCode::OK
+            } else {
+                StatusCode::CREATED
+            };
+            serde_json::to_value(&result)
+                .map(|body| (status, body))
+                .map_err(|e| AppError::internal(&e.to_string()))
+        });
+    let (status, body) = match processed {
+        Ok(processed) => processed,
+        Err(error) => {
+            release_idempotent(&state, &auth.user_id, scope, claim.as_ref());
+            return Err(error);
+        }
+    };
+    Ok(finish_idempotent(
+        &state,
+        &auth.user_id,
+        scope,
+        claim.as_ref(),
+        status,
+        body,
+    ))
 }
 
 // B33: Batch submit records
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BatchCreateRecordsRequest {
     records: Vec<CreateRecordRequest>,
@@ -380,11 +583,12 @@ struct BatchCreateRecordsRequest {
 async fn batch_create_records(
     auth: AuthUser,
     State(state): State<AppState>,
+    headers: HeaderMap,
     JsonBody(req): JsonBody<BatchCreateRecordsRequest>,
 ) -> Result<axum::response::Response, AppError> {
     if req.records.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量创建记录数量上限为{}",
                 state.config().limits.max_batch_size
@@ -392,8 +596,21 @@ async fn batch_create_records(
         ));
     }
 
+    let scope = IDEMPOTENCY_SCOPE_BATCH;
+    let claim = match begin_idempotent(&state, &auth.user_id, scope, &headers, &req)? {
+        IdempotencyStart::Replay(response) => return Ok(response),
+        IdempotencyStart::Claimed(claim) => Some(claim),
+        IdempotencyStart::Untracked => None,
+    };
+
     // S6: 在批量首条前捕获一次用户级快照
-    let user_snapshot = capture_user_state_snapshot(state.store(), &auth.user_id)?;
+    let user_snapshot = match capture_user_state_snapshot(state.store(), &auth.user_id) {
+        Ok(snapshot) => snapshot,
+        Err(error) => {
+            release_idempotent(&state, &auth.user_id, scope, claim.as_ref());
+            return Err(error);
+        }
+    };
 
     let mut results: Vec<CreateRecordResponse> = Vec::new();
     let mut errors = Vec::new();
@@ -416,6 +633,11 @@ async fn batch_create_records(
         restore_user_state_snapshot(state.store(), &auth.user_id, &user_snapshot);
     }
 
+    let status = if errors.is_empty() {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
     let payload = serde_json::json!({
         "count": results.len(),
         "failed": errors.len(),
@@ -424,11 +646,14 @@ async fn batch_create_records(
         "errors": errors,
     });
 
-    if payload["partial"].as_bool() == Some(true) {
-        Ok(ok(payload).into_response())
-    } else {
-        Ok(created(payload).into_response())
-    }
+    Ok(finish_idempotent(
+        &state,
+        &auth.user_id,
+        scope,
+        claim.as_ref(),
+        status,
+        payload,
+    ))
 }
 
 /// S5: 批量场景下的单条记录处理，只捕获 word 级快照（mastery + word_elo）
@@ -453,16 +678,6 @@ async fn process_batch_record(
         });
     }
 
-    let record = LearningRecord {
-        id: record_id,
-        user_id: user_id.to_string(),
-        word_id: req.word_id.clone(),
-        is_correct: req.is_correct,
-        response_time_ms: req.response_time_ms,
-        session_id: req.session_id.clone(),
-        created_at: Utc::now(),
-    };
-
     // S6: 只捕获 word 级状态
     let mastery_key = format!("mastery:{}", &req.word_id);
     let prev_mastery = state.store().get_engine_algo_state(user_id, &mastery_key)?;
@@ -492,6 +707,18 @@ async fn process_batch_record(
         )
         .await?;
 
+    let record = LearningRecord {
+        id: record_id,
+        user_id: user_id.to_string(),
+        word_id: req.word_id.clone(),
+        is_correct: req.is_correct,
+        response_time_ms: req.response_time_ms,
+        session_id: req.session_id.clone(),
+        created_at: Utc::now(),
+        algorithm_id: amas_result.dominant_algorithm(),
+        algorithm_weights: amas_result.algorithm_weights.clone(),
+    };
+
     {
         let amas_config = state.amas().get_config().await;
         let mut user_elo = state.store().get_user_elo(user_id)?;
@@ -507,6 +734,7 @@ async fn process_batch_record(
     }
 
     let mut next_word_state: Option<WordLearningState> = None;
+    let mut newly_mastered = false;
     if let Some(ref wm) = amas_result.word_mastery {
         let new_state = match wm.mastery_level {
             MasteryLevel::New => WordState::New,
@@ -531,6 +759,7 @@ async fn process_batch_record(
                 updated_at: Utc::now(),
             });
 
+        newly_mastered = new_state == WordState::Mastered && wls.state != WordState::Mastered;
         wls.state = new_state;
         wls.mastery_level = wm.memory_strength;
         wls.total_attempts += 1;
@@ -581,6 +810,10 @@ async fn process_batch_record(
             AppError::internal(&error.to_string())
         })?;
 
+    if newly_mastered {
+        record_mastery_rollup(state.store(), user_id, &record);
+    }
+
     Ok(CreateRecordResponse {
         record,
         amas_result: Some(amas_result),
@@ -668,3 +901,110 @@ async fn get_enhanced_statistics(
         "daily": daily,
     })))
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VelocityQuery {
+    window_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DailyMastered {
+    date: String,
+    mastered: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VelocityResponse {
+    window_days: i64,
+    mastered_in_window: u64,
+    mastered_in_previous_window: u64,
+    words_per_week: f64,
+    previous_words_per_week: f64,
+    acceleration: f64,
+    daily_trend: f64,
+    interpretation: String,
+    daily: Vec<DailyMastered>,
+}
+
+/// 窗口间速度变化低于此阈值（词/周）视为稳定
+const VELOCITY_STABLE_THRESHOLD: f64 = 1.0;
+
+async fn get_velocity(
+    auth: AuthUser,
+    Query(q): Query<VelocityQuery>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let window = q.window_days.unwrap_or(7).clamp(1, 90);
+    let today = Utc::now().date_naive();
+
+    // 按时间正序读取最近两个窗口的每日掌握数
+    let mut counts = Vec::with_capacity((window * 2) as usize);
+    for offset in (0..window * 2).rev() {
+        let date = (today - chrono::Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        let mastered = state
+            .store()
+            .get_user_daily_mastered(&auth.user_id, &date)?;
+        counts.push(DailyMastered { date, mastered });
+    }
+    let (previous, current) = counts.split_at(window as usize);
+
+    let current_total: u64 = current.iter().map(|d| d.mastered).sum();
+    let previous_total: u64 = previous.iter().map(|d| d.mastered).sum();
+    let words_per_week = current_total as f64 * 7.0 / window as f64;
+    let previous_words_per_week = previous_total as f64 * 7.0 / window as f64;
+    let acceleration = words_per_week - previous_words_per_week;
+    let daily_trend = linear_slope(
+        &current
+            .iter()
+            .map(|d| d.mastered as f64)
+            .collect::<Vec<_>>(),
+    );
+
+    let interpretation = if current_total == 0 && previous_total == 0 {
+        "暂无掌握记录，开始学习后即可查看进度"
+    } else if acceleration > VELOCITY_STABLE_THRESHOLD {
+        "掌握速度正在加快，继续保持"
+    } else if acceleration < -VELOCITY_STABLE_THRESHOLD {
+        "掌握速度有所放缓，可适当增加复习频率"
+    } else {
+        "掌握速度保持稳定"
+    };
+
+    Ok(ok(VelocityResponse {
+        window_days: window,
+        mastered_in_window: current_total,
+        mastered_in_previous_window: previous_total,
+        words_per_week,
+        previous_words_per_week,
+        acceleration,
+        daily_trend,
+        interpretation: interpretation.to_string(),
+        daily: counts.split_off(window as usize),
+    }))
+}
+
+/// 最小二乘斜率（x 为等距天序号）
+fn linear_slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        num += dx * (y - mean_y);
+        den += dx * dx;
+    }
+    if den > 0.0 {
+        num / den
+    } else {
+        0.0
+    }
+}
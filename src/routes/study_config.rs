@@ -3,11 +3,11 @@ use axum::routing::get;
 use axum::Router;
 
 use crate::extractors::JsonBody;
-use chrono::Utc;
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::auth::AuthUser;
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
 use crate::state::AppState;
 use crate::store::operations::study_configs::StudyMode;
 
@@ -16,8 +16,23 @@ pub fn router() -> Router<AppState> {
         .route("/", get(get_config).put(update_config))
         .route("/today-words", get(get_today_words))
         .route("/progress", get(get_progress))
+        .route("/recommended-count", get(get_recommended_count))
 }
 
+const RECOMMEND_LOOKBACK_DAYS: i64 = 14;
+const RECOMMEND_MIN_COUNT: u32 = 5;
+const RECOMMEND_MAX_COUNT: u32 = 200;
+
+// 自适应每日单词数
+const ADAPTIVE_LOOKBACK_DAYS: i64 = 7;
+/// 最近连续多少个学习日轻松完成才建议加量
+const ADAPTIVE_EASY_STREAK: usize = 3;
+const ADAPTIVE_EASY_ACCURACY: f64 = 0.85;
+const ADAPTIVE_HARD_ACCURACY: f64 = 0.6;
+const ADAPTIVE_HIGH_FATIGUE: f64 = 0.6;
+const ADAPTIVE_STEP_UP: f64 = 1.2;
+const ADAPTIVE_STEP_DOWN: f64 = 0.8;
+
 async fn get_config(
     auth: AuthUser,
     State(state): State<AppState>,
@@ -33,6 +48,8 @@ struct UpdateStudyConfigRequest {
     daily_word_count: Option<u32>,
     study_mode: Option<StudyMode>,
     daily_mastery_target: Option<u32>,
+    adaptive_daily_count: Option<bool>,
+    cram_until: Option<DateTime<Utc>>,
 }
 
 async fn update_config(
@@ -47,7 +64,7 @@ async fn update_config(
         for id in &ids {
             if state.store().get_wordbook(id)?.is_none() {
                 return Err(AppError::bad_request(
-                    "WORDBOOK_NOT_FOUND",
+                    ErrorCode::WordbookNotFound,
                     &format!("词书 '{}' 不存在", id),
                 ));
             }
@@ -63,6 +80,28 @@ async fn update_config(
     if let Some(target) = req.daily_mastery_target {
         config.daily_mastery_target = target.clamp(1, 100);
     }
+    if let Some(adaptive) = req.adaptive_daily_count {
+        config.adaptive_daily_count = adaptive;
+    }
+    if let Some(until) = req.cram_until {
+        config.cram_until = Some(until);
+    }
+    if config.study_mode == StudyMode::Cram {
+        // cram 必须限时开启，到期后自动回到 normal
+        let max_days = state.amas().get_config().await.cram_mode.max_duration_days;
+        let now = Utc::now();
+        let valid = config
+            .cram_until
+            .is_some_and(|until| until > now && until <= now + chrono::Duration::days(max_days));
+        if !valid {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidCramUntil,
+                &format!("cram 模式需要设置 {max_days} 天内的截止时间 cramUntil"),
+            ));
+        }
+    } else {
+        config.cram_until = None;
+    }
 
     state.store().set_study_config(&config)?;
     Ok(ok(config))
@@ -129,6 +168,41 @@ async fn get_progress(
     let config = state.store().get_study_config(&auth.user_id)?;
     let stats = state.store().get_word_state_stats(&auth.user_id)?;
 
+    let adaptive = if config.adaptive_daily_count {
+        let since = Utc::now() - chrono::Duration::days(ADAPTIVE_LOOKBACK_DAYS);
+        let records = state
+            .store()
+            .get_user_records(&auth.user_id, state.config().limits.max_stats_records)?;
+        // 记录按时间倒序，按天聚合后 days 也是从近到远
+        let mut days: Vec<(chrono::NaiveDate, DayPerformance)> = Vec::new();
+        let mut words_seen = std::collections::HashSet::new();
+        for r in records.iter().take_while(|r| r.created_at >= since) {
+            let date = r.created_at.date_naive();
+            if days.last().map(|(d, _)| *d) != Some(date) {
+                days.push((date, DayPerformance::default()));
+                words_seen.clear();
+            }
+            let day = &mut days.last_mut().expect("pushed above").1;
+            if words_seen.insert(r.word_id.as_str()) {
+                day.words += 1;
+            }
+            day.total += 1;
+            if r.is_correct {
+                day.correct += 1;
+            }
+        }
+        let days: Vec<DayPerformance> = days.into_iter().map(|(_, d)| d).collect();
+        let user_state = state.amas().get_user_state(&auth.user_id)?;
+        Some(suggest_daily_word_count(
+            config.daily_word_count,
+            &days,
+            user_state.fatigue,
+            user_state.motivation,
+        ))
+    } else {
+        None
+    };
+
     Ok(ok(serde_json::json!({
         "studied": stats.mastered + stats.reviewing,
         "target": config.daily_mastery_target,
@@ -136,5 +210,238 @@ async fn get_progress(
         "learning": stats.learning,
         "reviewing": stats.reviewing,
         "mastered": stats.mastered,
+        "dailyWordCount": config.daily_word_count,
+        "adaptive": adaptive,
     })))
 }
+
+#[derive(Debug, Default, Clone, Copy)]
+struct DayPerformance {
+    words: u32,
+    total: u32,
+    correct: u32,
+}
+
+impl DayPerformance {
+    fn accuracy(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.correct as f64 / self.total as f64
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdaptiveSuggestion {
+    suggested_count: u32,
+    /// increase / decrease / keep
+    adjustment: &'static str,
+    accuracy: f64,
+    completion_rate: f64,
+    fatigue: f64,
+    motivation: f64,
+    reasons: Vec<String>,
+}
+
+/// 根据近期每日表现（从近到远）与疲劳/动机信号给出每日单词数建议：
+/// 低正确率或高疲劳时减量，连续多日轻松完成且状态良好时加量
+fn suggest_daily_word_count(
+    current: u32,
+    days: &[DayPerformance],
+    fatigue: f64,
+    motivation: f64,
+) -> AdaptiveSuggestion {
+    let total: u32 = days.iter().map(|d| d.total).sum();
+    let correct: u32 = days.iter().map(|d| d.correct).sum();
+    let accuracy = if total > 0 {
+        correct as f64 / total as f64
+    } else {
+        0.0
+    };
+    let target = current.max(1) as f64;
+    let completion_rate = if days.is_empty() {
+        0.0
+    } else {
+        days.iter()
+            .map(|d| (d.words as f64 / target).min(1.0))
+            .sum::<f64>()
+            / days.len() as f64
+    };
+
+    let mut reasons = Vec::new();
+    let latest_hard = days
+        .first()
+        .is_some_and(|d| d.accuracy() < ADAPTIVE_HARD_ACCURACY);
+    let easy_streak = days.len() >= ADAPTIVE_EASY_STREAK
+        && days[..ADAPTIVE_EASY_STREAK]
+            .iter()
+            .all(|d| d.words >= current && d.accuracy() >= ADAPTIVE_EASY_ACCURACY);
+
+    let (factor, adjustment) = if fatigue > ADAPTIVE_HIGH_FATIGUE || latest_hard {
+        if fatigue > ADAPTIVE_HIGH_FATIGUE {
+            reasons.push(format!("当前疲劳度{:.0}%偏高", fatigue * 100.0));
+        }
+        if latest_hard {
+            reasons.push("最近一次学习正确率偏低".to_string());
+        }
+        (ADAPTIVE_STEP_DOWN, "decrease")
+    } else if easy_streak && motivation >= 0.0 {
+        reasons.push(format!(
+            "最近{}个学习日均轻松完成目标且正确率较高",
+            ADAPTIVE_EASY_STREAK
+        ));
+        (ADAPTIVE_STEP_UP, "increase")
+    } else {
+        if days.is_empty() {
+            reasons.push("近期暂无学习记录".to_string());
+        }
+        (1.0, "keep")
+    };
+
+    let suggested_count = if adjustment == "keep" {
+        current
+    } else {
+        ((target * factor).round() as u32).clamp(RECOMMEND_MIN_COUNT, RECOMMEND_MAX_COUNT)
+    };
+
+    AdaptiveSuggestion {
+        suggested_count,
+        adjustment,
+        accuracy,
+        completion_rate,
+        fatigue,
+        motivation,
+        reasons,
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecommendedCountResponse {
+    recommended_count: u32,
+    current_count: u32,
+    active_days: usize,
+    avg_daily_words: f64,
+    accuracy: f64,
+    fatigue: f64,
+    rationale: Vec<String>,
+}
+
+async fn get_recommended_count(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let config = state.store().get_study_config(&auth.user_id)?;
+    let since = Utc::now() - chrono::Duration::days(RECOMMEND_LOOKBACK_DAYS);
+    let records = state
+        .store()
+        .get_user_records(&auth.user_id, state.config().limits.max_stats_records)?;
+
+    // 记录按时间倒序，遇到窗口外记录即可停止
+    let mut words_by_day: std::collections::HashMap<_, std::collections::HashSet<&str>> =
+        std::collections::HashMap::new();
+    let mut total = 0usize;
+    let mut correct = 0usize;
+    for r in records.iter().take_while(|r| r.created_at >= since) {
+        words_by_day
+            .entry(r.created_at.date_naive())
+            .or_default()
+            .insert(r.word_id.as_str());
+        total += 1;
+        if r.is_correct {
+            correct += 1;
+        }
+    }
+
+    let active_days = words_by_day.len();
+    let avg_daily_words = if active_days > 0 {
+        words_by_day.values().map(|w| w.len()).sum::<usize>() as f64 / active_days as f64
+    } else {
+        0.0
+    };
+    let accuracy = if total > 0 {
+        correct as f64 / total as f64
+    } else {
+        0.0
+    };
+    let fatigue = state.amas().get_user_state(&auth.user_id)?.fatigue;
+
+    let mut rationale = Vec::new();
+    let base = if active_days > 0 {
+        rationale.push(format!(
+            "近{}天有{}天学习，日均{:.1}个单词",
+            RECOMMEND_LOOKBACK_DAYS, active_days, avg_daily_words
+        ));
+        avg_daily_words
+    } else {
+        rationale.push("暂无近期学习记录，沿用当前设置".to_string());
+        config.daily_word_count as f64
+    };
+
+    let accuracy_factor = if total == 0 {
+        1.0
+    } else if accuracy >= 0.85 {
+        rationale.push(format!("正确率{:.0}%较高，可适当增加", accuracy * 100.0));
+        1.2
+    } else if accuracy < 0.6 {
+        rationale.push(format!("正确率{:.0}%偏低，建议减量巩固", accuracy * 100.0));
+        0.8
+    } else {
+        1.0
+    };
+
+    let fatigue_factor = 1.0 - 0.5 * fatigue.clamp(0.0, 1.0);
+    if fatigue > 0.6 {
+        rationale.push("近期疲劳度较高，建议降低每日学习量".to_string());
+    }
+
+    let recommended = ((base * accuracy_factor * fatigue_factor).round() as u32)
+        .clamp(RECOMMEND_MIN_COUNT, RECOMMEND_MAX_COUNT);
+
+    Ok(ok(RecommendedCountResponse {
+        recommended_count: recommended,
+        current_count: config.daily_word_count,
+        active_days,
+        avg_daily_words,
+        accuracy,
+        fatigue,
+        rationale,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(words: u32, total: u32, correct: u32) -> DayPerformance {
+        DayPerformance {
+            words,
+            total,
+            correct,
+        }
+    }
+
+    #[test]
+    fn adaptive_count_follows_recent_performance() {
+        let easy = [day(20, 25, 24), day(22, 25, 23), day(20, 20, 19)];
+        let up = suggest_daily_word_count(20, &easy, 0.1, 0.2);
+        assert_eq!((up.suggested_count, up.adjustment), (24, "increase"));
+
+        let down = suggest_daily_word_count(20, &easy, 0.8, 0.2);
+        assert_eq!((down.suggested_count, down.adjustment), (16, "decrease"));
+
+        let hard_latest = [day(20, 30, 12), day(20, 20, 19), day(20, 20, 19)];
+        let hard = suggest_daily_word_count(20, &hard_latest, 0.1, 0.2);
+        assert_eq!(hard.adjustment, "decrease");
+
+        let unfinished = [day(10, 12, 12), day(20, 20, 19), day(20, 20, 19)];
+        let keep = suggest_daily_word_count(20, &unfinished, 0.1, 0.2);
+        assert_eq!((keep.suggested_count, keep.adjustment), (20, "keep"));
+        assert_eq!(
+            suggest_daily_word_count(20, &[], 0.0, 0.0).adjustment,
+            "keep"
+        );
+    }
+}
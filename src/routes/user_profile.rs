@@ -1,14 +1,16 @@
-use axum::extract::State;
+use axum::extract::{DefaultBodyLimit, State};
 use axum::routing::{get, post};
 use axum::Router;
-use std::path::PathBuf;
 
+use crate::amas::types::{HourlyStats, TemporalPerformance};
 use crate::auth::AuthUser;
 use crate::constants::DEFAULT_PREFERRED_HOURS;
 use crate::extractors::JsonBody;
 use serde::{Deserialize, Serialize};
 
-use crate::response::{ok, AppError};
+use crate::response::{ok, AppError, ErrorCode};
+use crate::routes::UPLOAD_BODY_LIMIT;
+use crate::services::avatar::{remove_avatar_file, resolve_avatar_dir, DEFAULT_AVATAR_URL};
 use crate::state::AppState;
 use crate::store::keys;
 
@@ -21,8 +23,14 @@ pub fn router() -> Router<AppState> {
         .route("/cognitive", get(get_cognitive_profile))
         .route("/learning-style", get(get_learning_style))
         .route("/chronotype", get(get_chronotype))
+        .route("/optimal-times", get(get_optimal_times))
         .route("/habit", get(get_habit_profile).post(set_habit_profile))
-        .route("/avatar", post(upload_avatar))
+        .route(
+            "/avatar",
+            post(upload_avatar)
+                .delete(delete_avatar)
+                .layer(DefaultBodyLimit::max(UPLOAD_BODY_LIMIT)),
+        )
 }
 
 // B46: Reward preference
@@ -61,7 +69,7 @@ async fn set_reward_preference(
     const VALID_REWARD_TYPES: &[&str] = &["standard", "explorer", "achiever", "social"];
     if !VALID_REWARD_TYPES.contains(&req.reward_type.as_str()) {
         return Err(AppError::bad_request(
-            "INVALID_REWARD_TYPE",
+            ErrorCode::InvalidRewardType,
             "奖励类型必须是以下之一：standard、explorer、achiever、social",
         ));
     }
@@ -124,6 +132,69 @@ async fn get_chronotype(
     })))
 }
 
+// 推荐学习时段：按各小时掌握效率排序，数据不足时回退到默认时段
+const OPTIMAL_TIMES_TOP_N: usize = 3;
+/// 单个小时至少需要的会话数，少于此值视为噪声
+const OPTIMAL_TIMES_MIN_HOUR_SESSIONS: u32 = 2;
+/// 会话数达到此值时置信度为 1
+const OPTIMAL_TIMES_FULL_CONFIDENCE_SESSIONS: u32 = 10;
+
+fn rank_optimal_hours(perf: &TemporalPerformance) -> Vec<serde_json::Value> {
+    let mut candidates: Vec<(usize, &HourlyStats)> = perf
+        .hourly_stats
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.session_count >= OPTIMAL_TIMES_MIN_HOUR_SESSIONS)
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.1.mastery_efficiency
+            .total_cmp(&a.1.mastery_efficiency)
+            .then(b.1.session_count.cmp(&a.1.session_count))
+    });
+    candidates
+        .into_iter()
+        .take(OPTIMAL_TIMES_TOP_N)
+        .map(|(hour, h)| {
+            let confidence =
+                (h.session_count as f64 / OPTIMAL_TIMES_FULL_CONFIDENCE_SESSIONS as f64).min(1.0);
+            serde_json::json!({
+                "hour": hour,
+                "masteryEfficiency": h.mastery_efficiency,
+                "avgAccuracy": h.avg_accuracy,
+                "sessionCount": h.session_count,
+                "confidence": confidence,
+            })
+        })
+        .collect()
+}
+
+async fn get_optimal_times(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let user_state = state.amas().get_user_state(&auth.user_id)?;
+    let perf = &user_state.habit_profile.temporal_performance;
+    let ranked = rank_optimal_hours(perf);
+
+    if ranked.is_empty() {
+        let recommendations: Vec<serde_json::Value> = DEFAULT_PREFERRED_HOURS
+            .iter()
+            .map(|hour| serde_json::json!({ "hour": hour, "confidence": 0.0 }))
+            .collect();
+        return Ok(ok(serde_json::json!({
+            "source": "default",
+            "totalSessions": perf.total_sessions,
+            "recommendations": recommendations,
+        })));
+    }
+
+    Ok(ok(serde_json::json!({
+        "source": "personalized",
+        "totalSessions": perf.total_sessions,
+        "recommendations": ranked,
+    })))
+}
+
 // B50: Habit profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -164,7 +235,7 @@ async fn set_habit_profile(
     if let Some(ref hours) = req.preferred_hours {
         if hours.iter().any(|h| *h > 23) {
             return Err(AppError::bad_request(
-                "INVALID_PREFERRED_HOURS",
+                ErrorCode::InvalidPreferredHours,
                 "偏好时段的值必须在0到23之间",
             ));
         }
@@ -174,7 +245,7 @@ async fn set_habit_profile(
     if let Some(spd) = req.sessions_per_day {
         if !(1.0..=20.0).contains(&spd) {
             return Err(AppError::bad_request(
-                "INVALID_SESSIONS_PER_DAY",
+                ErrorCode::InvalidSessionsPerDay,
                 "每日学习次数必须在1到20之间",
             ));
         }
@@ -184,7 +255,7 @@ async fn set_habit_profile(
     if let Some(msl) = req.median_session_length_mins {
         if !(1.0..=480.0).contains(&msl) {
             return Err(AppError::bad_request(
-                "INVALID_SESSION_LENGTH",
+                ErrorCode::InvalidSessionLength,
                 "单次学习时长（分钟）必须在1到480之间",
             ));
         }
@@ -208,15 +279,69 @@ async fn set_habit_profile(
 }
 
 // B51: Avatar upload
-fn resolve_avatar_dir() -> PathBuf {
-    let cwd_static_dir = PathBuf::from("static");
-    if cwd_static_dir.is_dir() {
-        return cwd_static_dir.join("avatars");
+/// 上传原图大小上限
+const MAX_AVATAR_UPLOAD_SIZE: usize = 5 * 1024 * 1024;
+/// 原图解码尺寸上限，防止解压炸弹
+const MAX_AVATAR_SOURCE_DIMENSION: u32 = 4096;
+/// 存储头像的最大边长
+const AVATAR_MAX_DIMENSION: u32 = 256;
+
+struct NormalizedAvatar {
+    png: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// 解码上传的图片，等比缩放到不超过 256×256 并统一编码为 PNG
+fn normalize_avatar(body: &[u8]) -> Result<NormalizedAvatar, AppError> {
+    let invalid_type = || {
+        AppError::bad_request(
+            ErrorCode::AvatarInvalidType,
+            "仅支持 PNG、JPEG、GIF 和 WebP 格式的图片",
+        )
+    };
+    let format = image::guess_format(body).map_err(|_| invalid_type())?;
+    if !matches!(
+        format,
+        image::ImageFormat::Png
+            | image::ImageFormat::Jpeg
+            | image::ImageFormat::Gif
+            | image::ImageFormat::WebP
+    ) {
+        return Err(invalid_type());
     }
 
-    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("static")
-        .join("avatars")
+    let mut reader = image::ImageReader::with_format(std::io::Cursor::new(body), format);
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_AVATAR_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_AVATAR_SOURCE_DIMENSION);
+    reader.limits(limits);
+    let decoded = reader.decode().map_err(|e| match e {
+        image::ImageError::Limits(_) => {
+            AppError::bad_request(ErrorCode::AvatarTooLarge, "头像图片尺寸不能超过4096×4096")
+        }
+        _ => AppError::bad_request(ErrorCode::AvatarInvalidImage, "无法解析图片内容"),
+    })?;
+
+    let resized = if decoded.width().max(decoded.height()) > AVATAR_MAX_DIMENSION {
+        decoded.resize(
+            AVATAR_MAX_DIMENSION,
+            AVATAR_MAX_DIMENSION,
+            image::imageops::FilterType::Lanczos3,
+        )
+    } else {
+        decoded
+    };
+
+    let mut png = Vec::new();
+    resized
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| AppError::internal(&format!("Failed to encode avatar: {e}")))?;
+    Ok(NormalizedAvatar {
+        png,
+        width: resized.width(),
+        height: resized.height(),
+    })
 }
 
 async fn upload_avatar(
@@ -225,33 +350,19 @@ async fn upload_avatar(
     body: axum::body::Bytes,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if body.is_empty() {
-        return Err(AppError::bad_request("AVATAR_EMPTY", "未上传文件"));
+        return Err(AppError::bad_request(ErrorCode::AvatarEmpty, "未上传文件"));
     }
-
-    // 限制头像大小为 512KB
-    const MAX_AVATAR_SIZE: usize = 512 * 1024;
-    if body.len() > MAX_AVATAR_SIZE {
+    if body.len() > MAX_AVATAR_UPLOAD_SIZE {
         return Err(AppError::bad_request(
-            "AVATAR_TOO_LARGE",
-            "头像文件大小不能超过512KB",
+            ErrorCode::AvatarTooLarge,
+            "头像文件大小不能超过5MB",
         ));
     }
 
-    // 验证文件类型（通过 magic bytes）
-    let extension = match body.get(..4) {
-        Some(b"\x89PNG") => "png",
-        Some(b"\xFF\xD8\xFF\xE0") | Some(b"\xFF\xD8\xFF\xE1") | Some(b"\xFF\xD8\xFF\xDB") => "jpg",
-        Some(bytes) if bytes.starts_with(b"GIF8") => "gif",
-        Some(bytes) if bytes.starts_with(b"RIFF") && body.len() > 12 && &body[8..12] == b"WEBP" => {
-            "webp"
-        }
-        _ => {
-            return Err(AppError::bad_request(
-                "AVATAR_INVALID_TYPE",
-                "仅支持 PNG、JPEG、GIF 和 WebP 格式的图片",
-            ))
-        }
-    };
+    let avatar = tokio::task::spawn_blocking(move || normalize_avatar(&body))
+        .await
+        .map_err(|e| AppError::internal(&format!("Avatar processing task failed: {e}")))??;
+    let extension = "png";
 
     let avatar_dir = resolve_avatar_dir();
     tokio::fs::create_dir_all(&avatar_dir)
@@ -262,11 +373,9 @@ async fn upload_avatar(
     let filename = format!("{}.{}", safe_id, extension);
     let path = avatar_dir.join(&filename);
 
-    tokio::fs::write(&path, &body)
-        .await
-        .map_err(|e| {
-            AppError::internal(&format!("Failed to save avatar to {}: {e}", path.display()))
-        })?;
+    tokio::fs::write(&path, &avatar.png).await.map_err(|e| {
+        AppError::internal(&format!("Failed to save avatar to {}: {e}", path.display()))
+    })?;
 
     let avatar_url = format!("/avatars/{}", filename);
     let avatar_key = keys::user_avatar_key(&auth.user_id)?;
@@ -274,7 +383,10 @@ async fn upload_avatar(
         "avatarUrl": avatar_url,
         "filename": filename,
         "extension": extension,
-        "sizeBytes": body.len(),
+        "contentType": "image/png",
+        "sizeBytes": avatar.png.len(),
+        "width": avatar.width,
+        "height": avatar.height,
     });
     state
         .store()
@@ -287,5 +399,80 @@ async fn upload_avatar(
 
     Ok(ok(serde_json::json!({
         "avatarUrl": avatar_metadata["avatarUrl"],
+        "contentType": avatar_metadata["contentType"],
+        "sizeBytes": avatar_metadata["sizeBytes"],
+        "width": avatar.width,
+        "height": avatar.height,
+    })))
+}
+
+/// 删除头像并恢复默认头像，返回默认头像地址；未上传过时同样返回成功
+async fn delete_avatar(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let removed = state.store().delete_user_avatar(&auth.user_id)?;
+    if let Some(filename) = &removed {
+        remove_avatar_file(filename)
+            .map_err(|e| AppError::internal(&format!("Failed to remove avatar: {e}")))?;
+    }
+
+    Ok(ok(serde_json::json!({
+        "avatarUrl": DEFAULT_AVATAR_URL,
+        "deleted": removed.is_some(),
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimal_hours_rank_by_efficiency_and_skip_sparse_hours() {
+        let mut perf = TemporalPerformance::default();
+        for (hour, sessions, efficiency) in [(8, 10, 0.6), (14, 1, 0.9), (21, 5, 0.8)] {
+            let h = &mut perf.hourly_stats[hour];
+            h.session_count = sessions;
+            h.mastery_efficiency = efficiency;
+        }
+
+        let ranked = rank_optimal_hours(&perf);
+        let hours: Vec<u64> = ranked.iter().map(|r| r["hour"].as_u64().unwrap()).collect();
+        assert_eq!(hours, vec![21, 8]);
+        assert_eq!(ranked[0]["confidence"], 0.5);
+        assert_eq!(ranked[1]["confidence"], 1.0);
+        assert!(rank_optimal_hours(&TemporalPerformance::default()).is_empty());
+    }
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn normalize_avatar_downscales_preserving_aspect_ratio() {
+        let avatar = normalize_avatar(&encode_png(600, 300)).unwrap();
+        assert_eq!((avatar.width, avatar.height), (256, 128));
+        assert_eq!(
+            image::guess_format(&avatar.png).unwrap(),
+            image::ImageFormat::Png
+        );
+
+        let small = normalize_avatar(&encode_png(32, 48)).unwrap();
+        assert_eq!((small.width, small.height), (32, 48));
+    }
+
+    #[test]
+    fn normalize_avatar_rejects_non_images() {
+        let err = normalize_avatar(b"not an image at all").err().unwrap();
+        assert_eq!(err.code, ErrorCode::AvatarInvalidType);
+
+        let mut truncated = encode_png(16, 16);
+        truncated.truncate(40);
+        let err = normalize_avatar(&truncated).err().unwrap();
+        assert_eq!(err.code, ErrorCode::AvatarInvalidImage);
+    }
+}
@@ -1,25 +1,47 @@
 use std::collections::BTreeSet;
 
-use axum::extract::State;
-use axum::routing::{get, put};
+use std::io::{self, BufWriter, Write};
+
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderValue};
+use axum::response::Response;
+use axum::routing::{delete, get, post, put};
 use axum::Router;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 
 use crate::extractors::JsonBody;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::auth::{hash_password, verify_password, AuthUser};
-use crate::response::{ok, AppError};
-use crate::routes::auth::UserProfile;
+use crate::auth::{hash_password, hash_token, verify_password, AuthUser};
+use crate::response::{ok, AppError, ErrorCode};
+use crate::routes::auth::{mask_email_for_log, UserProfile};
+use crate::services::password_breach;
 use crate::state::AppState;
 use crate::store::operations::records::LearningRecord;
-use crate::validation::{validate_password, validate_username};
+use crate::store::operations::sessions::Session;
+use crate::store::operations::users::EmailChangeEntry;
+use crate::store::operations::weekly_reports::{iso_week_label, parse_iso_week};
+use crate::store::StoreError;
+use crate::validation::{is_valid_email, validate_username};
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/me", get(get_profile).put(update_profile))
         .route("/me/password", put(change_password))
+        .route("/me/email", post(request_email_change))
+        .route("/me/email/verify", post(verify_email_change))
         .route("/me/stats", get(get_stats))
+        .route(
+            "/me/sessions",
+            get(list_sessions).delete(revoke_other_sessions),
+        )
+        .route("/me/sessions/:token_hash", delete(revoke_session))
+        .route("/me/reports", get(list_weekly_reports))
+        .route("/me/reports/weekly", get(get_weekly_report))
+        .route("/me/data-export", get(export_my_data))
 }
 
 async fn get_profile(
@@ -52,7 +74,7 @@ async fn update_profile(
     if let Some(username) = req.username {
         let trimmed = username.trim();
         if let Err(msg) = validate_username(trimmed) {
-            return Err(AppError::bad_request("USER_INVALID_USERNAME", msg));
+            return Err(AppError::bad_request(ErrorCode::UserInvalidUsername, msg));
         }
         user.username = trimmed.to_string();
     }
@@ -75,9 +97,7 @@ async fn change_password(
     State(state): State<AppState>,
     JsonBody(req): JsonBody<ChangePasswordRequest>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
-    if let Err(msg) = validate_password(&req.new_password) {
-        return Err(AppError::bad_request("AUTH_WEAK_PASSWORD", msg));
-    }
+    password_breach::enforce(&req.new_password, &state.config().password_policy).await?;
 
     let mut user = state
         .store()
@@ -96,6 +116,107 @@ async fn change_password(
     Ok(ok(serde_json::json!({"passwordChanged": true})))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmailChangeRequest {
+    new_email: String,
+    current_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyEmailChangeRequest {
+    token: String,
+}
+
+/// 向新邮箱发送验证令牌；验证通过前账户邮箱保持不变
+async fn request_email_change(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<EmailChangeRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let new_email = req.new_email.trim().to_lowercase();
+    if !is_valid_email(&new_email) {
+        return Err(AppError::bad_request(
+            ErrorCode::UserInvalidEmail,
+            "邮箱格式无效",
+        ));
+    }
+
+    let user = state
+        .store()
+        .get_user_by_id(&auth.user_id)?
+        .ok_or_else(|| AppError::not_found("用户不存在"))?;
+    if !verify_password(&req.current_password, &user.password_hash)? {
+        return Err(AppError::unauthorized("当前密码不正确"));
+    }
+    if user.email.eq_ignore_ascii_case(&new_email) {
+        return Err(AppError::bad_request(
+            ErrorCode::UserEmailUnchanged,
+            "新邮箱与当前邮箱相同",
+        ));
+    }
+    if state.store().get_user_by_email(&new_email)?.is_some() {
+        return Err(AppError::conflict(
+            ErrorCode::AuthEmailExists,
+            "该邮箱已被注册",
+        ));
+    }
+
+    let raw_token = uuid::Uuid::new_v4().simple().to_string();
+    state.store().create_email_change_token(
+        &hash_token(&raw_token),
+        &EmailChangeEntry {
+            user_id: user.id.clone(),
+            new_email: new_email.clone(),
+            expires_at: Utc::now() + Duration::hours(1),
+        },
+    )?;
+
+    tracing::info!(
+        user_id = %user.id,
+        email = %mask_email_for_log(&new_email),
+        "Email change requested; email delivery disabled in trimmed build"
+    );
+
+    Ok(ok(serde_json::json!({ "verificationSent": true })))
+}
+
+async fn verify_email_change(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<VerifyEmailChangeRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let entry = state
+        .store()
+        .take_email_change_token(&hash_token(&req.token), &auth.user_id)?
+        .ok_or_else(|| {
+            AppError::bad_request(ErrorCode::UserInvalidEmailToken, "邮箱验证令牌无效")
+        })?;
+    if entry.expires_at <= Utc::now() {
+        return Err(AppError::bad_request(
+            ErrorCode::UserExpiredEmailToken,
+            "邮箱验证令牌已过期",
+        ));
+    }
+
+    let mut user = state
+        .store()
+        .get_user_by_id(&auth.user_id)?
+        .ok_or_else(|| AppError::not_found("用户不存在"))?;
+    user.email = entry.new_email;
+    user.updated_at = Utc::now();
+    // update_user 在事务内维护邮箱索引，令牌签发后邮箱被他人占用时返回 Conflict
+    state.store().update_user(&user).map_err(|e| match e {
+        StoreError::Conflict { .. } => {
+            AppError::conflict(ErrorCode::AuthEmailExists, "该邮箱已被注册")
+        }
+        other => AppError::from(other),
+    })?;
+
+    Ok(ok(UserProfile::from(&user)))
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct UserStats {
@@ -193,3 +314,228 @@ pub fn compute_streak_from_dates(dates: &BTreeSet<chrono::NaiveDate>) -> u32 {
 
     streak
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionView {
+    token_hash: String,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    last_seen_at: Option<DateTime<Utc>>,
+    device: String,
+    user_agent: Option<String>,
+    current: bool,
+}
+
+impl SessionView {
+    fn new(session: Session, current_hash: &str) -> Self {
+        Self {
+            current: session.token_hash == current_hash,
+            token_hash: session.token_hash,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            last_seen_at: session.last_seen_at,
+            device: device_label(session.user_agent.as_deref()),
+            user_agent: session.user_agent,
+        }
+    }
+}
+
+/// 从 User-Agent 粗略识别浏览器与系统，如 "Chrome (Windows)"
+fn device_label(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent else {
+        return "未知设备".to_string();
+    };
+    // 顺序敏感：Edge/Opera 的 UA 同时包含 Chrome，Chrome 的 UA 同时包含 Safari
+    const BROWSERS: &[(&str, &str)] = &[
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+    ];
+    const SYSTEMS: &[(&str, &str)] = &[
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("Linux", "Linux"),
+    ];
+    let find = |table: &[(&str, &'static str)]| {
+        table
+            .iter()
+            .find(|(needle, _)| ua.contains(needle))
+            .map(|(_, name)| *name)
+    };
+    match (find(BROWSERS), find(SYSTEMS)) {
+        (Some(browser), Some(os)) => format!("{browser} ({os})"),
+        (Some(name), None) | (None, Some(name)) => name.to_string(),
+        (None, None) => "未知设备".to_string(),
+    }
+}
+
+async fn list_sessions(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let sessions: Vec<SessionView> = state
+        .store()
+        .list_user_sessions(&auth.user_id)?
+        .into_iter()
+        .map(|s| SessionView::new(s, &auth.session_hash))
+        .collect();
+    Ok(ok(sessions))
+}
+
+async fn revoke_session(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Path(token_hash): Path<String>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if !state
+        .store()
+        .revoke_user_session(&auth.user_id, &token_hash)?
+    {
+        return Err(AppError::not_found("会话不存在"));
+    }
+    Ok(ok(serde_json::json!({"revoked": true})))
+}
+
+/// 撤销当前会话以外的全部会话
+async fn revoke_other_sessions(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let revoked = state
+        .store()
+        .revoke_other_user_sessions(&auth.user_id, &auth.session_hash)?;
+    Ok(ok(serde_json::json!({"revoked": revoked})))
+}
+
+#[derive(Debug, Deserialize)]
+struct WeeklyReportQuery {
+    week: Option<String>,
+}
+
+/// 获取指定 ISO 周（`?week=YYYY-Www`）的周报，未指定时返回最近一期
+async fn get_weekly_report(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Query(q): Query<WeeklyReportQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let report = match q.week {
+        Some(week) => {
+            let week_start = parse_iso_week(&week).ok_or_else(|| {
+                AppError::bad_request(
+                    ErrorCode::InvalidWeek,
+                    "week 格式应为 YYYY-Www，如 2026-W07",
+                )
+            })?;
+            state
+                .store()
+                .get_weekly_report(&auth.user_id, &iso_week_label(week_start))?
+        }
+        None => state
+            .store()
+            .list_weekly_reports(&auth.user_id, 1)?
+            .into_iter()
+            .next(),
+    };
+    let report = report.ok_or_else(|| AppError::not_found("周报不存在"))?;
+    Ok(ok(report))
+}
+
+#[derive(Debug, Deserialize)]
+struct WeeklyReportListQuery {
+    limit: Option<usize>,
+}
+
+async fn list_weekly_reports(
+    auth: AuthUser,
+    State(state): State<AppState>,
+    Query(q): Query<WeeklyReportListQuery>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let limit = q.limit.unwrap_or(12).clamp(1, 52);
+    let reports = state.store().list_weekly_reports(&auth.user_id, limit)?;
+    Ok(ok(reports))
+}
+
+/// 导出时每个响应分块的大小
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+/// 导出通道中最多缓冲的分块数，客户端读取慢时阻塞导出线程
+const EXPORT_CHANNEL_CHUNKS: usize = 4;
+
+/// 把写入转发到响应流；客户端断开后返回 BrokenPipe 以中止导出
+struct ChunkSender(mpsc::Sender<io::Result<Vec<u8>>>);
+
+impl Write for ChunkSender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(buf.to_vec()))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 导出当前用户的全部数据（JSON 附件）。数据在阻塞线程中逐块写出并流式返回，
+/// 中途出错时响应被截断，客户端会看到不完整的 JSON
+async fn export_my_data(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    if state.store().get_user_by_id(&auth.user_id)?.is_none() {
+        return Err(AppError::not_found("用户不存在"));
+    }
+
+    let (tx, rx) = mpsc::channel(EXPORT_CHANNEL_CHUNKS);
+    let user_id = auth.user_id.clone();
+    tokio::task::spawn_blocking(move || {
+        let err_tx = tx.clone();
+        let mut out = BufWriter::with_capacity(EXPORT_CHUNK_BYTES, ChunkSender(tx));
+        if let Err(e) = state.store().write_user_export(&user_id, &mut out) {
+            tracing::warn!(user_id, error = %e, "用户数据导出失败");
+            let _ = err_tx.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+
+    let filename = format!(
+        "attachment; filename=\"wordforge-export-{}.json\"",
+        Utc::now().format("%Y%m%d")
+    );
+    let mut response = Response::new(Body::from_stream(ReceiverStream::new(rx)));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    if let Ok(value) = HeaderValue::from_str(&filename) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_label_recognizes_common_agents() {
+        let chrome_win = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+            (KHTML, like Gecko) Chrome/126.0 Safari/537.36";
+        let safari_ios = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) \
+            AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+        let edge = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) Chrome/126.0 Edg/126.0";
+        assert_eq!(device_label(Some(chrome_win)), "Chrome (Windows)");
+        assert_eq!(device_label(Some(safari_ios)), "Safari (iOS)");
+        assert_eq!(device_label(Some(edge)), "Edge (macOS)");
+        assert_eq!(device_label(Some("curl/8.4.0")), "curl");
+        assert_eq!(device_label(None), "未知设备");
+    }
+}
@@ -115,6 +115,8 @@ async fn create_record(
         response_time_ms: req.response_time_ms,
         session_id: None,
         created_at: now,
+        algorithm_id: None,
+        algorithm_weights: Default::default(),
     };
     state.store().create_record(&record)?;
     Ok(ok(record))
@@ -1,4 +1,4 @@
-use axum::extract::{Path, Query, State};
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::routing::{get, post};
 use axum::Router;
 
@@ -7,8 +7,9 @@ use chrono::Utc;
 use serde::Deserialize;
 
 use crate::auth::AuthUser;
-use crate::constants::DEFAULT_HALF_LIFE_HOURS;
-use crate::response::{ok, AppError};
+use crate::constants::{DEFAULT_HALF_LIFE_HOURS, DEFAULT_PAGE_SIZE_RECORDS};
+use crate::response::{ok, paginated, AppError, ErrorCode};
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
 use crate::store::operations::word_states::{WordLearningState, WordState};
 
@@ -17,8 +18,13 @@ pub fn router() -> Router<AppState> {
         .route("/batch", post(batch_query))
         .route("/due/list", get(due_list))
         .route("/stats/overview", get(stats_overview))
-        .route("/batch-update", post(batch_update))
+        .route("/never-correct", get(never_correct))
+        .route(
+            "/batch-update",
+            post(batch_update).layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
         .route("/:word_id", get(get_word_state))
+        .route("/:word_id/history", get(word_history))
         .route("/:word_id/mark-mastered", post(mark_mastered))
         .route("/:word_id/reset", post(reset_word))
 }
@@ -38,6 +44,32 @@ async fn get_word_state(
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WordHistoryQuery {
+    page: Option<u64>,
+    per_page: Option<u64>,
+}
+
+async fn word_history(
+    auth: AuthUser,
+    Path(word_id): Path<String>,
+    Query(q): Query<WordHistoryQuery>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let page = q.page.unwrap_or(1).max(1);
+    let per_page = q
+        .per_page
+        .unwrap_or(DEFAULT_PAGE_SIZE_RECORDS)
+        .clamp(1, state.config().pagination.max_page_size);
+    let offset = ((page - 1) * per_page) as usize;
+    let (records, total) =
+        state
+            .store()
+            .get_user_word_history(&auth.user_id, &word_id, per_page as usize, offset)?;
+    Ok(paginated(records, total as u64, page, per_page))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BatchQueryRequest {
@@ -51,7 +83,7 @@ async fn batch_query(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.word_ids.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量查询单词数量上限为{}",
                 state.config().limits.max_batch_size
@@ -88,6 +120,14 @@ async fn stats_overview(
     Ok(ok(stats))
 }
 
+async fn never_correct(
+    auth: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let states = state.store().get_never_correct_word_states(&auth.user_id)?;
+    Ok(ok(states))
+}
+
 async fn mark_mastered(
     auth: AuthUser,
     Path(word_id): Path<String>,
@@ -166,7 +206,7 @@ async fn batch_update(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.updates.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量更新数量上限为{}",
                 state.config().limits.max_batch_size
@@ -182,7 +222,7 @@ async fn batch_update(
         .collect();
     if !missing.is_empty() {
         return Err(AppError::bad_request(
-            "WORD_NOT_FOUND",
+            ErrorCode::WordNotFound,
             &format!("以下单词不存在：{}", missing.join(", ")),
         ));
     }
@@ -7,9 +7,8 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 
 use crate::auth::{AdminAuthUser, AuthUser};
-use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
 use crate::extractors::JsonBody;
-use crate::response::{created, ok, AppError};
+use crate::response::{created, ok, AppError, ErrorCode, PaginatedResponse};
 use crate::routes::words::{resolve_import_url_addrs, validate_import_url};
 use crate::state::AppState;
 use crate::store::operations::wb_center::WordbookCenterImport;
@@ -165,14 +164,14 @@ async fn fetch_remote_json<T: serde::de::DeserializeOwned>(
 
     let response = client.get(url_parsed).send().await.map_err(|e| {
         AppError::bad_request(
-            "WB_CENTER_FETCH_FAILED",
+            ErrorCode::WbCenterFetchFailed,
             &format!("获取远程数据失败：{e}"),
         )
     })?;
 
     if !response.status().is_success() {
         return Err(AppError::bad_request(
-            "WB_CENTER_FETCH_FAILED",
+            ErrorCode::WbCenterFetchFailed,
             &format!("远程服务返回状态码 {}", response.status()),
         ));
     }
@@ -181,7 +180,7 @@ async fn fetch_remote_json<T: serde::de::DeserializeOwned>(
     if let Some(len) = response.content_length() {
         if len > MAX_SIZE as u64 {
             return Err(AppError::bad_request(
-                "WB_CENTER_TOO_LARGE",
+                ErrorCode::WbCenterTooLarge,
                 "响应内容过大（上限50MB）",
             ));
         }
@@ -192,15 +191,12 @@ async fn fetch_remote_json<T: serde::de::DeserializeOwned>(
     use futures::StreamExt;
     while let Some(chunk_result) = stream.next().await {
         let chunk = chunk_result.map_err(|e| {
-            AppError::bad_request(
-                "WB_CENTER_READ_FAILED",
-                &format!("读取内容失败：{e}"),
-            )
+            AppError::bad_request(ErrorCode::WbCenterReadFailed, &format!("读取内容失败：{e}"))
         })?;
         body_bytes.extend_from_slice(&chunk);
         if body_bytes.len() > MAX_SIZE {
             return Err(AppError::bad_request(
-                "WB_CENTER_TOO_LARGE",
+                ErrorCode::WbCenterTooLarge,
                 "响应内容过大（上限50MB）",
             ));
         }
@@ -208,7 +204,7 @@ async fn fetch_remote_json<T: serde::de::DeserializeOwned>(
 
     serde_json::from_slice(&body_bytes).map_err(|e| {
         AppError::bad_request(
-            "WB_CENTER_PARSE_FAILED",
+            ErrorCode::WbCenterParseFailed,
             &format!("解析远程数据失败：{e}"),
         )
     })
@@ -297,7 +293,7 @@ async fn do_import(
         .is_some()
     {
         return Err(AppError::conflict(
-            "WB_CENTER_ALREADY_IMPORTED",
+            ErrorCode::WbCenterAlreadyImported,
             "该词书已被导入",
         ));
     }
@@ -446,24 +442,19 @@ fn paginated_words(
     total: u64,
     page: u64,
     per_page: u64,
-) -> serde_json::Value {
-    let total_pages = if per_page > 0 {
-        total.div_ceil(per_page)
-    } else {
-        0
-    };
-    serde_json::json!({
-        "data": words.iter().map(|w| serde_json::json!({
-            "spelling": w.spelling,
-            "phonetic": w.phonetic,
-            "meanings": w.meanings,
-            "examples": w.examples,
-        })).collect::<Vec<_>>(),
-        "total": total,
-        "page": page,
-        "perPage": per_page,
-        "totalPages": total_pages,
-    })
+) -> PaginatedResponse<serde_json::Value> {
+    let data = words
+        .iter()
+        .map(|w| {
+            serde_json::json!({
+                "spelling": w.spelling,
+                "phonetic": w.phonetic,
+                "meanings": w.meanings,
+                "examples": w.examples,
+            })
+        })
+        .collect();
+    PaginatedResponse::new(data, total, page, per_page)
 }
 
 // ════════════════════ Admin endpoints ════════════════════
@@ -474,10 +465,7 @@ async fn admin_browse(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let settings = state.store().get_system_settings()?;
     let base_url = settings.wordbook_center_url.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "词书中心URL未配置")
     })?;
 
     let catalog: RemoteCatalog = fetch_remote_json(&base_url, "index.json").await?;
@@ -494,10 +482,7 @@ async fn admin_preview(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let settings = state.store().get_system_settings()?;
     let base_url = settings.wordbook_center_url.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "词书中心URL未配置")
     })?;
 
     let remote: RemoteWordbook =
@@ -506,8 +491,8 @@ async fn admin_preview(
     let page = q.page.unwrap_or(1).max(1);
     let per_page = q
         .per_page
-        .unwrap_or(DEFAULT_PAGE_SIZE)
-        .clamp(1, MAX_PAGE_SIZE);
+        .unwrap_or(state.config().pagination.default_page_size)
+        .clamp(1, state.config().pagination.max_page_size);
     let total = remote.words.len() as u64;
     let offset = ((page - 1) * per_page) as usize;
     let words: Vec<&RemoteWord> = remote.words.iter().skip(offset).take(per_page as usize).collect();
@@ -533,10 +518,7 @@ async fn admin_import(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let settings = state.store().get_system_settings()?;
     let base_url = settings.wordbook_center_url.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "词书中心URL未配置")
     })?;
 
     let result = do_import(&state, &base_url, &id, WordbookType::System, None).await?;
@@ -590,10 +572,7 @@ async fn admin_sync(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let settings = state.store().get_system_settings()?;
     let base_url = settings.wordbook_center_url.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "词书中心URL未配置")
     })?;
 
     let import_record = state
@@ -724,10 +703,7 @@ async fn user_preview(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let base_url = get_user_wb_center_url(&state, &auth.user_id)?.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "个人词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "个人词书中心URL未配置")
     })?;
 
     let remote: RemoteWordbook =
@@ -736,8 +712,8 @@ async fn user_preview(
     let page = q.page.unwrap_or(1).max(1);
     let per_page = q
         .per_page
-        .unwrap_or(DEFAULT_PAGE_SIZE)
-        .clamp(1, MAX_PAGE_SIZE);
+        .unwrap_or(state.config().pagination.default_page_size)
+        .clamp(1, state.config().pagination.max_page_size);
     let total = remote.words.len() as u64;
     let offset = ((page - 1) * per_page) as usize;
     let words: Vec<&RemoteWord> = remote.words.iter().skip(offset).take(per_page as usize).collect();
@@ -762,10 +738,7 @@ async fn user_import(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let base_url = get_user_wb_center_url(&state, &auth.user_id)?.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "个人词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "个人词书中心URL未配置")
     })?;
 
     let result = do_import(
@@ -809,7 +782,7 @@ async fn user_import_url(
         .is_some()
     {
         return Err(AppError::conflict(
-            "WB_CENTER_ALREADY_IMPORTED",
+            ErrorCode::WbCenterAlreadyImported,
             "该词书已被导入",
         ));
     }
@@ -900,10 +873,7 @@ async fn user_sync(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let base_url = get_user_wb_center_url(&state, &auth.user_id)?.ok_or_else(|| {
-        AppError::bad_request(
-            "WB_CENTER_NOT_CONFIGURED",
-            "个人词书中心URL未配置",
-        )
+        AppError::bad_request(ErrorCode::WbCenterNotConfigured, "个人词书中心URL未配置")
     })?;
 
     let import_record = state
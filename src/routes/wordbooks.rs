@@ -1,4 +1,4 @@
-use axum::extract::{Path, Query, State};
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::routing::{delete, get, post};
 use axum::Router;
 
@@ -7,34 +7,80 @@ use chrono::Utc;
 use serde::Deserialize;
 
 use crate::auth::AuthUser;
-use crate::response::{created, ok, paginated, AppError};
+use crate::config::WordUniqueness;
+use crate::response::{created, ok, paginated, AppError, ErrorCode};
 use crate::routes::words::WordPublic;
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
 use crate::store::operations::wordbooks::{Wordbook, WordbookType};
+use crate::store::StoreError;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/system", get(list_system_wordbooks))
         .route("/user", get(list_user_wordbooks))
         .route("/", post(create_wordbook))
-        .route("/:id/words", get(list_wordbook_words).post(add_words))
+        .route(
+            "/:id/words",
+            get(list_wordbook_words)
+                .post(add_words)
+                .layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
         .route("/:id/words/:word_id", delete(remove_word))
+        .route("/:id/progress", get(get_wordbook_progress))
+        .route("/:id/reset-progress", post(reset_wordbook_progress))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageQuery {
+    page: Option<u64>,
+    per_page: Option<u64>,
+}
+
+impl PageQuery {
+    fn resolve(&self, state: &AppState) -> (u64, u64) {
+        let page = self.page.unwrap_or(1).max(1);
+        let per_page = self
+            .per_page
+            .unwrap_or(state.config().pagination.default_page_size)
+            .clamp(1, state.config().pagination.max_page_size);
+        (page, per_page)
+    }
+}
+
+/// 词书数量有限，整体读出后在内存中分页
+fn paginate_wordbooks(
+    books: Vec<Wordbook>,
+    q: &PageQuery,
+    state: &AppState,
+) -> impl axum::response::IntoResponse {
+    let (page, per_page) = q.resolve(state);
+    let total = books.len() as u64;
+    let items = books
+        .into_iter()
+        .skip(((page - 1) * per_page) as usize)
+        .take(per_page as usize)
+        .collect();
+    paginated(items, total, page, per_page)
 }
 
 async fn list_system_wordbooks(
     _user: AuthUser,
+    Query(q): Query<PageQuery>,
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let books = state.store().list_system_wordbooks()?;
-    Ok(ok(books))
+    Ok(paginate_wordbooks(books, &q, &state))
 }
 
 async fn list_user_wordbooks(
     auth: AuthUser,
+    Query(q): Query<PageQuery>,
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let books = state.store().list_user_wordbooks(&auth.user_id)?;
-    Ok(ok(books))
+    Ok(paginate_wordbooks(books, &q, &state))
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,7 +97,7 @@ async fn create_wordbook(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.name.trim().is_empty() {
         return Err(AppError::bad_request(
-            "WORDBOOK_INVALID_NAME",
+            ErrorCode::WordbookInvalidName,
             "名称不能为空",
         ));
     }
@@ -70,17 +116,10 @@ async fn create_wordbook(
     Ok(created(book))
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ListWordbookWordsQuery {
-    page: Option<u64>,
-    per_page: Option<u64>,
-}
-
 async fn list_wordbook_words(
     auth: AuthUser,
     Path(id): Path<String>,
-    Query(q): Query<ListWordbookWordsQuery>,
+    Query(q): Query<PageQuery>,
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let book = state
@@ -93,11 +132,7 @@ async fn list_wordbook_words(
         return Err(AppError::forbidden("您没有该词书的操作权限"));
     }
 
-    let page = q.page.unwrap_or(1).clamp(1, u64::MAX);
-    let per_page = q
-        .per_page
-        .unwrap_or(state.config().pagination.default_page_size)
-        .clamp(1, state.config().pagination.max_page_size);
+    let (page, per_page) = q.resolve(&state);
     let limit = per_page as usize;
     let offset = ((page - 1) * per_page) as usize;
     let total = state.store().count_wordbook_words(&id)?;
@@ -112,6 +147,80 @@ async fn list_wordbook_words(
     Ok(paginated(items, total, page, per_page))
 }
 
+/// 当前用户在该词书内各学习状态的单词数，用于词书进度条
+async fn get_wordbook_progress(
+    auth: AuthUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let book = state
+        .store()
+        .get_wordbook(&id)?
+        .ok_or_else(|| AppError::not_found("词书不存在"))?;
+
+    if book.user_id.is_some() && book.user_id.as_deref() != Some(&auth.user_id) {
+        return Err(AppError::forbidden("您没有该词书的操作权限"));
+    }
+
+    let (total, stats) = state
+        .store()
+        .get_wordbook_word_state_stats(&auth.user_id, &id)?;
+    let mastered_percent = if total > 0 {
+        stats.mastered as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(ok(serde_json::json!({
+        "wordbookId": id,
+        "total": total,
+        "new": stats.new_count,
+        "learning": stats.learning,
+        "reviewing": stats.reviewing,
+        "mastered": stats.mastered,
+        "forgotten": stats.forgotten,
+        "masteredPercent": mastered_percent,
+    })))
+}
+
+/// 重置当前用户在该词书内全部单词的学习进度，逐词事务执行并返回汇总
+async fn reset_wordbook_progress(
+    auth: AuthUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let book = state
+        .store()
+        .get_wordbook(&id)?
+        .ok_or_else(|| AppError::not_found("词书不存在"))?;
+
+    if book.user_id.is_some() && book.user_id.as_deref() != Some(&auth.user_id) {
+        return Err(AppError::forbidden("您没有该词书的操作权限"));
+    }
+
+    let total = state.store().count_wordbook_words(&id)? as usize;
+    let word_ids = state.store().list_wordbook_words(&id, total, 0)?;
+    let mut reset = 0u64;
+    let mut failed = 0u64;
+    for word_id in &word_ids {
+        match state.store().reset_word_progress(&auth.user_id, word_id) {
+            Ok(true) => reset += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(user_id = %auth.user_id, word_id, error = %e, "重置单词进度失败");
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(ok(serde_json::json!({
+        "wordbookId": id,
+        "total": word_ids.len(),
+        "reset": reset,
+        "failed": failed,
+    })))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AddWordsRequest {
@@ -139,7 +248,7 @@ async fn add_words(
 
     if req.word_ids.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "WORDBOOK_TOO_MANY_WORDS",
+            ErrorCode::WordbookTooManyWords,
             &format!(
                 "单次添加单词数量不能超过{}",
                 state.config().limits.max_batch_size
@@ -147,14 +256,27 @@ async fn add_words(
         ));
     }
 
+    let unique_text = state.config().word_uniqueness == WordUniqueness::Wordbook;
     let mut added = 0usize;
+    let mut conflicts = Vec::new();
     for word_id in &req.word_ids {
-        if state.store().add_word_to_wordbook(&id, word_id)? {
-            added += 1;
+        let result = if unique_text {
+            state.store().add_unique_word_to_wordbook(&id, word_id)
+        } else {
+            state.store().add_word_to_wordbook(&id, word_id)
+        };
+        match result {
+            Ok(true) => added += 1,
+            Ok(false) => {}
+            Err(StoreError::Conflict { .. }) => conflicts.push(word_id.clone()),
+            Err(e) => return Err(e.into()),
         }
     }
 
-    Ok(ok(serde_json::json!({"added": added})))
+    Ok(ok(serde_json::json!({
+        "added": added,
+        "conflicts": conflicts,
+    })))
 }
 
 async fn remove_word(
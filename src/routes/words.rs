@@ -1,17 +1,19 @@
-use axum::extract::{Path, Query, State};
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
 use axum::routing::{get, post};
 use axum::Router;
 
 use crate::auth::{AdminAuthUser, AuthUser};
-use crate::constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::config::{PaginationConfig, WordUniqueness};
 use crate::extractors::JsonBody;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, SocketAddr};
 
-use crate::response::{created, ok, paginated, AppError};
+use crate::response::{created, ok, paginated, AppError, ErrorCode};
+use crate::routes::BULK_BODY_LIMIT;
 use crate::state::AppState;
-use crate::store::operations::words::Word;
+use crate::store::operations::words::{Word, WordFilter};
+use crate::store::StoreError;
 
 /// 对外 API 使用的 Word 视图，排除 embedding 等内部字段
 #[derive(Debug, Serialize)]
@@ -48,7 +50,14 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_words).post(create_word))
         .route("/count", get(count_words))
-        .route("/batch", post(batch_create_words))
+        .route("/tags", get(list_tags))
+        .route("/tags/bulk", post(bulk_update_tags))
+        .route("/duplicates", get(list_duplicates))
+        .route("/duplicates/merge", post(merge_duplicates))
+        .route(
+            "/batch",
+            post(batch_create_words).layer(DefaultBodyLimit::max(BULK_BODY_LIMIT)),
+        )
         .route("/batch-get", post(batch_get_words))
         .route("/import-url", post(import_from_url))
         .route("/:id", get(get_word).put(update_word).delete(delete_word))
@@ -60,6 +69,18 @@ struct ListWordsQuery {
     page: Option<u64>,
     per_page: Option<u64>,
     search: Option<String>,
+    /// 逗号分隔的多个标签
+    tag: Option<String>,
+    tag_match: Option<TagMatch>,
+    pos: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TagMatch {
+    #[default]
+    All,
+    Any,
 }
 
 impl ListWordsQuery {
@@ -67,8 +88,32 @@ impl ListWordsQuery {
         self.page.unwrap_or(1).clamp(1, u64::MAX)
     }
 
-    fn per_page(&self) -> u64 {
-        self.per_page.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    fn per_page(&self, pagination: &PaginationConfig) -> u64 {
+        self.per_page
+            .unwrap_or(pagination.default_page_size)
+            .clamp(1, pagination.max_page_size)
+    }
+
+    fn filter(&self) -> WordFilter {
+        let non_empty = |v: &Option<String>| {
+            v.as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        WordFilter {
+            tags: self
+                .tag
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            match_all_tags: matches!(self.tag_match.unwrap_or_default(), TagMatch::All),
+            search: non_empty(&self.search),
+            part_of_speech: non_empty(&self.pos),
+        }
     }
 }
 
@@ -78,17 +123,16 @@ async fn list_words(
     State(state): State<AppState>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     let page = query.page();
-    let per_page = query.per_page();
+    let per_page = query.per_page(&state.config().pagination);
     let offset = ((page - 1) * per_page) as usize;
     let limit = per_page as usize;
 
-    // B15: search support
-    if let Some(ref search) = query.search {
-        if !search.trim().is_empty() {
-            let (items, total) = state.store().search_words(search, limit, offset)?;
-            let items: Vec<WordPublic> = items.iter().map(WordPublic::from).collect();
-            return Ok(paginated(items, total, page, per_page));
-        }
+    // B15: search support，标签/词性筛选可与关键词叠加
+    let filter = query.filter();
+    if !filter.is_empty() {
+        let (items, total) = state.store().filter_words(&filter, limit, offset)?;
+        let items: Vec<WordPublic> = items.iter().map(WordPublic::from).collect();
+        return Ok(paginated(items, total, page, per_page));
     }
 
     let total = state.store().count_words()?;
@@ -106,6 +150,139 @@ async fn count_words(
     Ok(ok(serde_json::json!({"total": total})))
 }
 
+async fn list_tags(
+    _user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let tags: Vec<serde_json::Value> = state
+        .store()
+        .list_word_tags()?
+        .into_iter()
+        .map(|(tag, count)| serde_json::json!({"tag": tag, "count": count}))
+        .collect();
+    Ok(ok(tags))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BulkTagsRequest {
+    word_ids: Vec<String>,
+    #[serde(default)]
+    add_tags: Vec<String>,
+    #[serde(default)]
+    remove_tags: Vec<String>,
+}
+
+/// 批量增删标签：逐词在事务内更新，单个单词失败不影响其余单词
+async fn bulk_update_tags(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<BulkTagsRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if req.word_ids.len() > state.config().limits.max_batch_size {
+        return Err(AppError::bad_request(
+            ErrorCode::BatchTooLarge,
+            &format!(
+                "批量修改标签的单词数量上限为{}",
+                state.config().limits.max_batch_size
+            ),
+        ));
+    }
+    let has_tags = |tags: &[String]| tags.iter().any(|t| !t.trim().is_empty());
+    if !has_tags(&req.add_tags) && !has_tags(&req.remove_tags) {
+        return Err(AppError::bad_request(
+            ErrorCode::WordsInvalidPayload,
+            "addTags 与 removeTags 不能同时为空",
+        ));
+    }
+
+    let mut results = Vec::with_capacity(req.word_ids.len());
+    let mut updated = 0;
+    for word_id in &req.word_ids {
+        let error = match state
+            .store()
+            .update_word_tags(word_id, &req.add_tags, &req.remove_tags)
+        {
+            Ok(Some(word)) => {
+                updated += 1;
+                results.push(serde_json::json!({
+                    "wordId": word_id,
+                    "success": true,
+                    "tags": word.tags,
+                }));
+                continue;
+            }
+            Ok(None) => "单词不存在".to_string(),
+            Err(StoreError::Validation(message)) => message,
+            Err(e) => return Err(e.into()),
+        };
+        results.push(serde_json::json!({
+            "wordId": word_id,
+            "success": false,
+            "error": error,
+        }));
+    }
+
+    Ok(ok(serde_json::json!({
+        "updated": updated,
+        "failed": results.len() - updated,
+        "results": results,
+    })))
+}
+
+/// 按规范化拼写分组的重复单词，附各单词被引用的条目数，供合并前预览
+async fn list_duplicates(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    Ok(ok(state.store().find_duplicate_words()?))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MergeDuplicatesRequest {
+    canonical_id: String,
+    duplicate_ids: Vec<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+async fn merge_duplicates(
+    _admin: AdminAuthUser,
+    State(state): State<AppState>,
+    JsonBody(req): JsonBody<MergeDuplicatesRequest>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    if req.duplicate_ids.is_empty() {
+        return Err(AppError::bad_request(
+            ErrorCode::WordsInvalidPayload,
+            "duplicateIds 不能为空",
+        ));
+    }
+    if req.duplicate_ids.len() > state.config().limits.max_batch_size {
+        return Err(AppError::bad_request(
+            ErrorCode::BatchTooLarge,
+            &format!(
+                "单次合并的单词数量上限为{}",
+                state.config().limits.max_batch_size
+            ),
+        ));
+    }
+    // 合并会扫描并改写所有引用条目，放到阻塞线程池执行
+    let summary = tokio::task::spawn_blocking(move || {
+        state
+            .store()
+            .merge_words(&req.canonical_id, &req.duplicate_ids, req.dry_run)
+    })
+    .await
+    .map_err(|e| AppError::internal(&format!("Merge task failed: {e}")))?
+    .map_err(|e| match e {
+        StoreError::NotFound { key, .. } => AppError::not_found(&format!("单词 {key} 不存在")),
+        StoreError::Validation(msg) => AppError::bad_request(ErrorCode::WordsInvalidPayload, &msg),
+        other => other.into(),
+    })?;
+    Ok(ok(summary))
+}
+
 // Batch get words by IDs
 #[derive(Debug, Deserialize)]
 struct BatchGetRequest {
@@ -119,7 +296,7 @@ async fn batch_get_words(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.ids.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量获取单词数量上限为{}",
                 state.config().limits.max_batch_size
@@ -181,7 +358,7 @@ async fn create_word(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.text.trim().is_empty() || req.meaning.trim().is_empty() {
         return Err(AppError::bad_request(
-            "WORDS_INVALID_PAYLOAD",
+            ErrorCode::WordsInvalidPayload,
             "单词和释义不能为空",
         ));
     }
@@ -199,7 +376,7 @@ async fn create_word(
         created_at: Utc::now(),
     };
 
-    state.store().upsert_word(&word)?;
+    save_word(&state, &word).map_err(|e| word_save_error(e, &word))?;
     Ok(created(WordPublic::from(&word)))
 }
 
@@ -238,10 +415,37 @@ async fn update_word(
         created_at: existing.created_at,
     };
 
-    state.store().upsert_word(&word)?;
+    save_word(&state, &word).map_err(|e| word_save_error(e, &word))?;
     Ok(ok(WordPublic::from(&word)))
 }
 
+/// 按 `WORD_UNIQUENESS` 写入单词，拼写冲突时返回 `StoreError::Conflict`
+fn save_word(state: &AppState, word: &Word) -> Result<(), StoreError> {
+    match state.config().word_uniqueness {
+        WordUniqueness::Off => state.store().upsert_word(word),
+        WordUniqueness::Global => state.store().upsert_unique_word(word),
+        WordUniqueness::Wordbook => {
+            if state.store().has_wordbook_text_conflict(word)? {
+                return Err(StoreError::Conflict {
+                    entity: "wordbook_word_text".to_string(),
+                    key: word.text.clone(),
+                });
+            }
+            state.store().upsert_word(word)
+        }
+    }
+}
+
+fn word_save_error(err: StoreError, word: &Word) -> AppError {
+    match err {
+        StoreError::Conflict { .. } => AppError::conflict(
+            ErrorCode::WordTextExists,
+            &format!("单词“{}”已存在", word.text),
+        ),
+        other => AppError::from(other),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BatchCreateWordsRequest {
@@ -255,7 +459,7 @@ async fn batch_create_words(
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     if req.words.len() > state.config().limits.max_batch_size {
         return Err(AppError::bad_request(
-            "BATCH_TOO_LARGE",
+            ErrorCode::BatchTooLarge,
             &format!(
                 "批量创建单词数量上限为{}",
                 state.config().limits.max_batch_size
@@ -282,8 +486,11 @@ async fn batch_create_words(
             embedding: None,
             created_at: Utc::now(),
         };
-        state.store().upsert_word(&word)?;
-        created_words.push(WordPublic::from(&word));
+        match save_word(&state, &word) {
+            Ok(()) => created_words.push(WordPublic::from(&word)),
+            Err(StoreError::Conflict { .. }) => skipped_indices.push(i),
+            Err(e) => return Err(e.into()),
+        }
     }
 
     Ok(created(serde_json::json!({
@@ -330,14 +537,14 @@ async fn import_from_url(
         .map_err(|e| AppError::internal(&format!("HTTP client error: {e}")))?;
 
     let response = client.get(url_parsed.clone()).send().await.map_err(|e| {
-        AppError::bad_request("IMPORT_FETCH_FAILED", &format!("获取URL失败：{e}"))
+        AppError::bad_request(ErrorCode::ImportFetchFailed, &format!("获取URL失败：{e}"))
     })?;
 
     // 检查 Content-Length（如果服务端提供了）
     if let Some(len) = response.content_length() {
         if len > MAX_RESPONSE_SIZE as u64 {
             return Err(AppError::bad_request(
-                "IMPORT_TOO_LARGE",
+                ErrorCode::ImportTooLarge,
                 "响应内容过大（上限10MB）",
             ));
         }
@@ -349,15 +556,12 @@ async fn import_from_url(
     use futures::StreamExt;
     while let Some(chunk_result) = stream.next().await {
         let chunk = chunk_result.map_err(|e| {
-            AppError::bad_request(
-                "IMPORT_READ_FAILED",
-                &format!("读取内容失败：{e}"),
-            )
+            AppError::bad_request(ErrorCode::ImportReadFailed, &format!("读取内容失败：{e}"))
         })?;
         body_bytes.extend_from_slice(&chunk);
         if body_bytes.len() > MAX_RESPONSE_SIZE {
             return Err(AppError::bad_request(
-                "IMPORT_TOO_LARGE",
+                ErrorCode::ImportTooLarge,
                 "响应内容过大（上限10MB）",
             ));
         }
@@ -411,8 +615,11 @@ async fn import_from_url(
             embedding: None,
             created_at: Utc::now(),
         };
-        state.store().upsert_word(&word)?;
-        imported.push(WordPublic::from(&word));
+        match save_word(&state, &word) {
+            Ok(()) => imported.push(WordPublic::from(&word)),
+            Err(StoreError::Conflict { .. }) => continue,
+            Err(e) => return Err(e.into()),
+        }
     }
 
     Ok(created(serde_json::json!({
@@ -422,24 +629,25 @@ async fn import_from_url(
 }
 
 pub(crate) fn validate_import_url(raw_url: &str) -> Result<reqwest::Url, AppError> {
-    let parsed = reqwest::Url::parse(raw_url)
-        .map_err(|e| AppError::bad_request("IMPORT_INVALID_URL", &format!("URL无效：{e}")))?;
+    let parsed = reqwest::Url::parse(raw_url).map_err(|e| {
+        AppError::bad_request(ErrorCode::ImportInvalidUrl, &format!("URL无效：{e}"))
+    })?;
 
     if parsed.scheme() != "http" && parsed.scheme() != "https" {
         return Err(AppError::bad_request(
-            "IMPORT_INVALID_URL",
+            ErrorCode::ImportInvalidUrl,
             "仅允许 http 和 https 协议的URL",
         ));
     }
 
     let host = parsed
         .host_str()
-        .ok_or_else(|| AppError::bad_request("IMPORT_INVALID_URL", "URL必须包含主机名"))?;
+        .ok_or_else(|| AppError::bad_request(ErrorCode::ImportInvalidUrl, "URL必须包含主机名"))?;
 
     if let Ok(ip) = host.parse::<IpAddr>() {
         if is_private_ip(ip) {
             return Err(AppError::bad_request(
-                "IMPORT_BLOCKED_URL",
+                ErrorCode::ImportBlockedUrl,
                 "不允许访问内网地址",
             ));
         }
@@ -451,7 +659,7 @@ pub(crate) fn validate_import_url(raw_url: &str) -> Result<reqwest::Url, AppErro
         || lower_host.ends_with(".internal")
     {
         return Err(AppError::bad_request(
-            "IMPORT_BLOCKED_URL",
+            ErrorCode::ImportBlockedUrl,
             "不允许访问本地地址",
         ));
     }
@@ -464,7 +672,7 @@ pub(crate) async fn resolve_import_url_addrs(
 ) -> Result<(String, Vec<SocketAddr>), AppError> {
     let host = url
         .host_str()
-        .ok_or_else(|| AppError::bad_request("IMPORT_INVALID_URL", "URL必须包含主机名"))?
+        .ok_or_else(|| AppError::bad_request(ErrorCode::ImportInvalidUrl, "URL必须包含主机名"))?
         .to_string();
     let port = url.port_or_known_default().unwrap_or(443);
 
@@ -473,7 +681,7 @@ pub(crate) async fn resolve_import_url_addrs(
     } else {
         tokio::net::lookup_host((host.as_str(), port))
             .await
-            .map_err(|_| AppError::bad_request("IMPORT_DNS_FAILED", "无法解析主机名"))?
+            .map_err(|_| AppError::bad_request(ErrorCode::ImportDnsFailed, "无法解析主机名"))?
             .collect::<Vec<SocketAddr>>()
     };
 
@@ -484,7 +692,7 @@ pub(crate) async fn resolve_import_url_addrs(
 fn ensure_public_import_addrs(addrs: Vec<SocketAddr>) -> Result<Vec<SocketAddr>, AppError> {
     if addrs.is_empty() {
         return Err(AppError::bad_request(
-            "IMPORT_DNS_FAILED",
+            ErrorCode::ImportDnsFailed,
             "无法解析主机名",
         ));
     }
@@ -492,7 +700,7 @@ fn ensure_public_import_addrs(addrs: Vec<SocketAddr>) -> Result<Vec<SocketAddr>,
     for socket_addr in &addrs {
         if is_private_ip(socket_addr.ip()) {
             return Err(AppError::bad_request(
-                "IMPORT_BLOCKED_URL",
+                ErrorCode::ImportBlockedUrl,
                 "URL指向内网IP地址",
             ));
         }
@@ -538,13 +746,13 @@ mod tests {
     #[test]
     fn validate_import_url_rejects_non_http_scheme() {
         let err = validate_import_url("ftp://example.com/words.txt").unwrap_err();
-        assert_eq!(err.code, "IMPORT_INVALID_URL");
+        assert_eq!(err.code, ErrorCode::ImportInvalidUrl);
     }
 
     #[test]
     fn validate_import_url_rejects_private_host() {
         let err = validate_import_url("http://127.0.0.1/words.txt").unwrap_err();
-        assert_eq!(err.code, "IMPORT_BLOCKED_URL");
+        assert_eq!(err.code, ErrorCode::ImportBlockedUrl);
     }
 
     #[test]
@@ -560,7 +768,7 @@ mod tests {
             443,
         )])
         .unwrap_err();
-        assert_eq!(err.code, "IMPORT_BLOCKED_URL");
+        assert_eq!(err.code, ErrorCode::ImportBlockedUrl);
     }
 
     #[test]
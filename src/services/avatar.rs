@@ -0,0 +1,29 @@
+//! 头像文件存储位置与清理
+
+use std::path::PathBuf;
+
+/// 未上传或删除头像后使用的默认头像，随静态资源一起发布
+pub const DEFAULT_AVATAR_URL: &str = "/avatars/default.svg";
+
+pub fn resolve_avatar_dir() -> PathBuf {
+    let cwd_static_dir = PathBuf::from("static");
+    if cwd_static_dir.is_dir() {
+        return cwd_static_dir.join("avatars");
+    }
+
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("static")
+        .join("avatars")
+}
+
+/// 删除头像文件；文件不存在视为已删除
+pub fn remove_avatar_file(filename: &str) -> std::io::Result<()> {
+    // 只接受纯文件名，避免元数据被篡改后删除目录外的文件
+    if filename.is_empty() || filename.contains(['/', '\\']) || filename.starts_with('.') {
+        return Ok(());
+    }
+    match std::fs::remove_file(resolve_avatar_dir().join(filename)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,170 @@
+//! 外部服务调用的熔断器：连续失败达到阈值后打开，冷却期内直接拒绝调用；
+//! 冷却结束后半开，只放行一次探测调用，成功则关闭，失败则重新打开。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// 累计打开次数
+    pub opened_total: u64,
+    /// 累计被短路拒绝的调用数
+    pub rejected_total: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// 半开状态下探测调用的开始时间；探测被取消（如 worker 超时）时，再过一个冷却期允许新的探测
+    probe_started: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: &'static str,
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+    opened_total: AtomicU64,
+    rejected_total: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &'static str, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner::default()),
+            opened_total: AtomicU64::new(0),
+            rejected_total: AtomicU64::new(0),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn state_of(&self, inner: &Inner) -> CircuitState {
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if opened.elapsed() < self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state_of(&self.lock())
+    }
+
+    /// 是否允许本次调用；允许时调用方须在结束后调用 `record_success` 或 `record_failure`
+    pub fn try_acquire(&self) -> bool {
+        let mut inner = self.lock();
+        let allowed = match self.state_of(&inner) {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                let probing = inner
+                    .probe_started
+                    .is_some_and(|started| started.elapsed() < self.cooldown);
+                if !probing {
+                    inner.probe_started = Some(Instant::now());
+                }
+                !probing
+            }
+        };
+        if !allowed {
+            self.rejected_total.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.opened_at.is_some() {
+            tracing::info!(
+                circuit = self.name,
+                "Circuit breaker closed, provider recovered"
+            );
+        }
+        *inner = Inner::default();
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let probe_failed = inner.probe_started.take().is_some();
+        if probe_failed
+            || (inner.opened_at.is_none() && inner.consecutive_failures >= self.failure_threshold)
+        {
+            inner.opened_at = Some(Instant::now());
+            self.opened_total.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                circuit = self.name,
+                consecutive_failures = inner.consecutive_failures,
+                cooldown_secs = self.cooldown.as_secs(),
+                "Circuit breaker opened"
+            );
+        }
+    }
+
+    pub fn snapshot(&self) -> CircuitSnapshot {
+        let inner = self.lock();
+        CircuitSnapshot {
+            state: self.state_of(&inner),
+            consecutive_failures: inner.consecutive_failures,
+            opened_total: self.opened_total.load(Ordering::Relaxed),
+            rejected_total: self.rejected_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_recovers_through_half_open_probe() {
+        let breaker = CircuitBreaker::new("test", 2, Duration::from_millis(30));
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire());
+
+        // 冷却后半开：只放行一次探测，探测失败重新打开
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire());
+        assert!(!breaker.try_acquire());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(breaker.try_acquire());
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.opened_total, 2);
+        assert_eq!(snapshot.rejected_total, 2);
+        assert_eq!(snapshot.consecutive_failures, 0);
+    }
+}
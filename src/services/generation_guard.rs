@@ -0,0 +1,128 @@
+//! 按需生成内容的单飞与失败退避：同一单词同时只发起一次 LLM 生成，
+//! 其余请求直接返回已缓存内容；生成失败后按指数退避暂停该单词的重试，审核拒绝后暂停更久。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+const BASE_FAILURE_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(3600);
+/// 被审核拒绝的单词重新生成大概率仍被拒，一天内不再重试
+const REJECTED_BACKOFF: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug, Default)]
+struct Inner {
+    in_flight: HashSet<String>,
+    /// 单词 -> (连续失败次数, 允许再次生成的时间)
+    backoff: HashMap<String, (u32, Instant)>,
+}
+
+#[derive(Debug, Default)]
+pub struct GenerationGuard {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Failed,
+    Succeeded,
+    Rejected,
+}
+
+/// 生成许可，drop 时释放单飞占用；未标记结果的许可按失败处理
+pub struct GenerationPermit {
+    guard: Arc<GenerationGuard>,
+    key: String,
+    outcome: Outcome,
+}
+
+impl GenerationGuard {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 已有同一单词的生成在进行或仍在退避期内时返回 None
+    pub fn try_begin(self: &Arc<Self>, key: &str) -> Option<GenerationPermit> {
+        let mut inner = self.lock();
+        if inner
+            .backoff
+            .get(key)
+            .is_some_and(|(_, until)| Instant::now() < *until)
+        {
+            return None;
+        }
+        if !inner.in_flight.insert(key.to_string()) {
+            return None;
+        }
+        Some(GenerationPermit {
+            guard: Arc::clone(self),
+            key: key.to_string(),
+            outcome: Outcome::Failed,
+        })
+    }
+}
+
+impl GenerationPermit {
+    pub fn succeed(mut self) {
+        self.outcome = Outcome::Succeeded;
+        self.guard.lock().backoff.remove(&self.key);
+    }
+
+    pub fn reject(mut self) {
+        self.outcome = Outcome::Rejected;
+    }
+}
+
+impl Drop for GenerationPermit {
+    fn drop(&mut self) {
+        let mut inner = self.guard.lock();
+        inner.in_flight.remove(&self.key);
+        let failures = inner.backoff.get(&self.key).map_or(0, |(n, _)| *n) + 1;
+        let delay = match self.outcome {
+            Outcome::Succeeded => return,
+            Outcome::Rejected => REJECTED_BACKOFF,
+            Outcome::Failed => BASE_FAILURE_BACKOFF
+                .saturating_mul(1 << (failures - 1).min(16))
+                .min(MAX_FAILURE_BACKOFF),
+        };
+        inner
+            .backoff
+            .insert(self.key.clone(), (failures, Instant::now() + delay));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_flight_and_backoff_after_failure() {
+        let guard = Arc::new(GenerationGuard::default());
+        let permit = guard.try_begin("w1").unwrap();
+        assert!(guard.try_begin("w1").is_none());
+        assert!(guard.try_begin("w2").is_some());
+
+        drop(permit);
+        assert!(guard.try_begin("w1").is_none());
+        let (failures, until) = guard.lock().backoff["w1"];
+        assert_eq!(failures, 1);
+        assert!(until > Instant::now() + BASE_FAILURE_BACKOFF / 2);
+
+        guard
+            .lock()
+            .backoff
+            .insert("w1".to_string(), (1, Instant::now()));
+        guard.try_begin("w1").unwrap().succeed();
+        assert!(!guard.lock().backoff.contains_key("w1"));
+        assert!(guard.try_begin("w1").is_some());
+    }
+
+    #[test]
+    fn rejection_backs_off_for_a_day() {
+        let guard = Arc::new(GenerationGuard::default());
+        guard.try_begin("w1").unwrap().reject();
+        let (_, until) = guard.lock().backoff["w1"];
+        assert!(until > Instant::now() + MAX_FAILURE_BACKOFF);
+        assert!(guard.try_begin("w1").is_none());
+    }
+}
@@ -1,51 +1,470 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
-use crate::config::LLMConfig;
+use crate::config::{LLMConfig, LlmBackend};
+use crate::services::circuit_breaker::{CircuitBreaker, CircuitSnapshot, CircuitState};
+use crate::store::operations::llm_advisories::AdvisorOutput;
+use crate::store::operations::llm_usage::LlmUsageCounts;
+use crate::store::Store;
+
+/// mock 模式下伪嵌入向量的维度
+pub const MOCK_EMBEDDING_DIM: usize = 64;
+
+/// 调用 LLM 的功能，各自可配置不同模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmFeature {
+    Etymology,
+    Examples,
+    Moderation,
+    Advisor,
+    Embedding,
+}
+
+impl LlmFeature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Etymology => "etymology",
+            Self::Examples => "examples",
+            Self::Moderation => "moderation",
+            Self::Advisor => "advisor",
+            Self::Embedding => "embedding",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct LlmProvider {
     config: LLMConfig,
-    #[allow(dead_code)]
     client: reqwest::Client,
+    usage_store: Option<Arc<Store>>,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl LlmProvider {
     pub fn new(config: &LLMConfig) -> Self {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .timeout(Duration::from_secs(config.timeout_secs))
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
         Self {
             config: config.clone(),
             client,
+            usage_store: None,
+            breaker: Arc::new(CircuitBreaker::new(
+                "llm",
+                config.circuit.failure_threshold,
+                Duration::from_secs(config.circuit.cooldown_secs),
+            )),
         }
     }
 
+    /// 熔断器打开（冷却中）时 worker 应跳过本次运行
+    pub fn is_circuit_open(&self) -> bool {
+        self.breaker.state() == CircuitState::Open
+    }
+
+    pub fn circuit_snapshot(&self) -> CircuitSnapshot {
+        self.breaker.snapshot()
+    }
+
+    /// 将每次调用的 token 用量累加到 `llm_usage_daily`，并据此判断每日预算
+    pub fn with_usage_tracking(mut self, store: Arc<Store>) -> Self {
+        self.usage_store = Some(store);
+        self
+    }
+
     /// Validate LLM configuration at startup.
-    /// Panics if `enabled=true` and `mock=false` since real LLM mode is not yet implemented.
+    /// Panics on malformed model names, or when the OpenAI backend is enabled without an API key.
     pub fn validate_config(config: &LLMConfig) {
-        if config.enabled && !config.mock {
+        let models = &config.models;
+        let configured = [
+            ("LLM_MODEL", Some(&models.default)),
+            ("LLM_MODEL_EMBEDDING", Some(&models.embedding)),
+            ("LLM_MODEL_ETYMOLOGY", models.etymology.as_ref()),
+            ("LLM_MODEL_EXAMPLES", models.examples.as_ref()),
+            ("LLM_MODEL_MODERATION", models.moderation.as_ref()),
+            ("LLM_MODEL_ADVISOR", models.advisor.as_ref()),
+        ];
+        for (key, model) in configured {
+            if let Some(Err(e)) = model.map(|m| validate_model_name(m)) {
+                panic!("Invalid LLM configuration: {key}: {e}");
+            }
+        }
+        if config.enabled && config.provider == LlmBackend::OpenAi && config.api_key.is_empty() {
             panic!(
-                "Invalid LLM configuration: enabled=true and mock=false, \
-                 but real LLM API integration is not yet implemented. \
-                 Set LLM_MOCK=true or LLM_ENABLED=false."
+                "Invalid LLM configuration: LLM_PROVIDER=openai requires LLM_API_KEY. \
+                 Set LLM_PROVIDER=mock or LLM_ENABLED=false."
             );
         }
     }
 
-    pub async fn chat(&self, _messages: Vec<ChatMessage>) -> Result<String, LlmError> {
+    /// 功能使用的模型，未单独配置时使用默认模型
+    pub fn model_for(&self, feature: LlmFeature) -> &str {
+        let models = &self.config.models;
+        let configured = match feature {
+            LlmFeature::Embedding => return &models.embedding,
+            LlmFeature::Etymology => &models.etymology,
+            LlmFeature::Examples => &models.examples,
+            LlmFeature::Moderation => &models.moderation,
+            LlmFeature::Advisor => &models.advisor,
+        };
+        configured.as_deref().unwrap_or(&models.default)
+    }
+
+    /// 生成内容所用模型的标识，随缓存一同保存
+    pub fn model_tag(&self, feature: LlmFeature) -> String {
+        match self.config.provider {
+            LlmBackend::Mock => "mock".to_string(),
+            _ => self.model_for(feature).to_string(),
+        }
+    }
+
+    /// 使用指定模型对话（不计入用量）；各功能方法按 `model_for` 选择模型并记录用量
+    pub async fn chat(&self, model: &str, messages: Vec<ChatMessage>) -> Result<String, LlmError> {
         if !self.config.enabled {
             return Err(LlmError::Disabled);
         }
-        if self.config.mock {
+        if self.config.provider == LlmBackend::Mock {
             return Ok("Mock LLM response".to_string());
         }
+        self.chat_with_usage(model, &messages)
+            .await
+            .map(|(reply, _)| reply)
+    }
+
+    async fn chat_with_usage(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+    ) -> Result<(String, Option<TokenUsage>), LlmError> {
+        let body = serde_json::json!({ "model": model, "messages": messages });
+        let reply = self.post_json("chat/completions", &body).await?;
+        let text = reply["choices"][0]["message"]["content"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| {
+                LlmError::InvalidResponse("missing choices[0].message.content".into())
+            })?;
+        Ok((text, TokenUsage::from_response(&reply)))
+    }
+
+    /// 单轮对话并按功能记录用量；mock 后端返回 `mock_reply` 并按字数估算用量
+    async fn complete(
+        &self,
+        feature: LlmFeature,
+        prompt: String,
+        mock_reply: impl FnOnce() -> String,
+    ) -> Result<String, LlmError> {
+        if !self.config.enabled {
+            return Err(LlmError::Disabled);
+        }
+        let (reply, usage) = if self.config.provider == LlmBackend::Mock {
+            self.ensure_budget()?;
+            (mock_reply(), None)
+        } else {
+            let messages = [ChatMessage {
+                role: "user".to_string(),
+                content: prompt.clone(),
+            }];
+            self.chat_with_usage(self.model_for(feature), &messages)
+                .await?
+        };
+        let usage = usage.unwrap_or_else(|| TokenUsage::estimate(&prompt, &reply));
+        self.record_usage(feature, usage);
+        Ok(reply)
+    }
+
+    /// 为单词生成 `count` 条例句
+    pub async fn generate_examples(
+        &self,
+        text: &str,
+        meaning: &str,
+        count: usize,
+    ) -> Result<Vec<String>, LlmError> {
+        let prompt = format!(
+            "Write {count} short English example sentences using the word \"{text}\" ({meaning}). \
+             Return one sentence per line without numbering."
+        );
+        let reply = self
+            .complete(LlmFeature::Examples, prompt, || {
+                (1..=count)
+                    .map(|i| format!("Example {i}: \"{text}\" means {meaning}."))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .await?;
+        Ok(reply
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(count)
+            .map(String::from)
+            .collect())
+    }
 
-        Err(LlmError::ApiError {
-            status: 501,
-            message: "Real LLM API integration is not implemented yet".to_string(),
+    /// 生成单词的词源说明
+    pub async fn generate_etymology(&self, text: &str, meaning: &str) -> Result<String, LlmError> {
+        let prompt = format!(
+            "Explain the etymology of the English word \"{text}\" ({meaning}) in two or three sentences."
+        );
+        self.complete(LlmFeature::Etymology, prompt, || {
+            format!("Mock etymology for '{text}' ({meaning}).")
         })
+        .await
     }
+
+    /// 审核一段生成内容，违规时返回原因
+    pub async fn moderate(&self, text: &str) -> Result<Option<String>, LlmError> {
+        let prompt = format!(
+            "Is the following text appropriate for a vocabulary learning app? \
+             Reply \"OK\" if it is, otherwise reply with a one-line reason.\n\n{text}"
+        );
+        let reply = self
+            .complete(LlmFeature::Moderation, prompt, || "OK".to_string())
+            .await?;
+        let reply = reply.trim();
+        Ok((!reply.eq_ignore_ascii_case("ok")).then(|| reply.to_string()))
+    }
+
+    /// 根据监控汇总与当前 AMAS 配置（`context` 为 JSON）给出配置调整建议与异常标记
+    pub async fn advise(&self, context: &str) -> Result<AdvisorOutput, LlmError> {
+        let prompt = format!(
+            "You are tuning the adaptive learning engine (AMAS) of a vocabulary app. \
+             Given the monitoring summaries and current config below, reply with JSON only: \
+             {{\"summary\": string, \
+             \"suggestions\": [{{\"path\": dotted camelCase config path, \"proposed\": value, \"rationale\": string}}], \
+             \"anomalies\": [{{\"metric\": string, \"severity\": \"info\"|\"warning\"|\"critical\", \"detail\": string, \"date\": \"YYYY-MM-DD\"|null}}]}}. \
+             Only suggest changes that the data supports.\n\n{context}"
+        );
+        let reply = self
+            .complete(LlmFeature::Advisor, prompt, || {
+                r#"{"summary": "Mock advisory: no changes suggested.", "suggestions": [], "anomalies": []}"#
+                    .to_string()
+            })
+            .await?;
+        // 模型常把 JSON 包在代码块里，取最外层花括号之间的内容
+        let json = match (reply.find('{'), reply.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &reply[start..=end],
+            _ => reply.as_str(),
+        };
+        serde_json::from_str(json).map_err(|e| LlmError::InvalidResponse(e.to_string()))
+    }
+
+    /// 为每条文本生成一个嵌入向量，返回顺序与输入一致
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, LlmError> {
+        if !self.config.enabled {
+            return Err(LlmError::Disabled);
+        }
+        let input = texts.concat();
+        if self.config.provider == LlmBackend::Mock {
+            self.ensure_budget()?;
+            self.record_usage(LlmFeature::Embedding, TokenUsage::estimate(&input, ""));
+            return Ok(texts.iter().map(|t| mock_embedding(t)).collect());
+        }
+
+        let body = serde_json::json!({
+            "model": self.model_for(LlmFeature::Embedding),
+            "input": texts,
+        });
+        let reply = self.post_json("embeddings", &body).await?;
+        let usage =
+            TokenUsage::from_response(&reply).unwrap_or_else(|| TokenUsage::estimate(&input, ""));
+        self.record_usage(LlmFeature::Embedding, usage);
+        let mut data: Vec<EmbeddingItem> = serde_json::from_value(reply["data"].clone())
+            .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+        // 按 index 还原为输入顺序
+        data.sort_by_key(|item| item.index);
+        Ok(data.into_iter().map(|item| item.embedding).collect())
+    }
+
+    /// 累加到当日用量；本次调用使用量越过每日上限时记录日志
+    fn record_usage(&self, feature: LlmFeature, usage: TokenUsage) {
+        let Some(store) = &self.usage_store else {
+            return;
+        };
+        let budget = self.config.budget;
+        let counts = LlmUsageCounts {
+            calls: 1,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            estimated_cost: usage.prompt_tokens as f64 / 1000.0 * budget.input_price_per_1k
+                + usage.completion_tokens as f64 / 1000.0 * budget.output_price_per_1k,
+        };
+        let daily = match store.record_llm_usage(Utc::now().date_naive(), feature.as_str(), &counts)
+        {
+            Ok(daily) => daily,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to record LLM usage");
+                return;
+            }
+        };
+        let limit = budget.daily_token_limit;
+        let after = daily.total.total_tokens();
+        if limit > 0 && after >= limit && after - counts.total_tokens() < limit {
+            tracing::warn!(
+                limit,
+                used = after,
+                "LLM daily token budget reached, LLM calls paused until next UTC day"
+            );
+        }
+    }
+
+    /// 当日用量已达每日上限；未配置上限或未开启用量记录时恒为 false
+    pub fn budget_exhausted(&self) -> bool {
+        let limit = self.config.budget.daily_token_limit;
+        let Some(store) = self.usage_store.as_ref().filter(|_| limit > 0) else {
+            return false;
+        };
+        match store.get_llm_usage_daily(Utc::now().date_naive()) {
+            Ok(daily) => daily.is_some_and(|d| d.total.total_tokens() >= limit),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read LLM usage");
+                false
+            }
+        }
+    }
+
+    fn ensure_budget(&self) -> Result<(), LlmError> {
+        if self.budget_exhausted() {
+            return Err(LlmError::BudgetExhausted);
+        }
+        Ok(())
+    }
+
+    /// 当日预算用尽时直接拒绝；其余请求经熔断器发送，任何错误都计为一次失败
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value, LlmError> {
+        self.ensure_budget()?;
+        if !self.breaker.try_acquire() {
+            return Err(LlmError::CircuitOpen);
+        }
+        let result = self.send_json(path, body).await;
+        match &result {
+            Ok(_) => self.breaker.record_success(),
+            Err(_) => self.breaker.record_failure(),
+        }
+        result
+    }
+
+    async fn send_json(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value, LlmError> {
+        let base = match self.config.api_url.as_str() {
+            "" => self.config.provider.default_api_url(),
+            url => url,
+        };
+        let mut request = self
+            .client
+            .post(format!("{}/{path}", base.trim_end_matches('/')))
+            .json(body);
+        if !self.config.api_key.is_empty() {
+            request = request.bearer_auth(&self.config.api_key);
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                LlmError::Timeout
+            } else {
+                LlmError::Network(e.to_string())
+            }
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(LlmError::ApiError {
+                status: status.as_u16(),
+                message: message.chars().take(500).collect(),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| LlmError::InvalidResponse(e.to_string()))
+    }
+}
+
+/// 单次调用的 token 用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    /// 读取 OpenAI 兼容响应中的 `usage`
+    fn from_response(reply: &serde_json::Value) -> Option<Self> {
+        let usage = reply.get("usage")?;
+        let prompt_tokens = usage.get("prompt_tokens")?.as_u64()?;
+        let completion_tokens = usage
+            .get("completion_tokens")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        Some(Self {
+            prompt_tokens,
+            completion_tokens,
+        })
+    }
+
+    /// 接口未返回用量时按约 4 个字符一个 token 估算
+    fn estimate(prompt: &str, completion: &str) -> Self {
+        let tokens = |text: &str| (text.chars().count() as u64).div_ceil(4);
+        Self {
+            prompt_tokens: tokens(prompt),
+            completion_tokens: tokens(completion),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingItem {
+    index: usize,
+    embedding: Vec<f64>,
+}
+
+/// 模型名须非空、无空白，仅含字母数字与 `-._:/@`
+pub fn validate_model_name(model: &str) -> Result<(), String> {
+    if model.is_empty() || model.len() > 128 {
+        return Err(format!("model name must be 1-128 characters: {model:?}"));
+    }
+    match model
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "-._:/@".contains(*c)))
+    {
+        Some(c) => Err(format!(
+            "model name contains invalid character {c:?}: {model:?}"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 确定性伪嵌入：字符三元组特征哈希后 L2 归一化，拼写相近的单词向量也相近
+fn mock_embedding(text: &str) -> Vec<f64> {
+    let mut vector = vec![0.0; MOCK_EMBEDDING_DIM];
+    let chars: Vec<char> = format!(" {} ", text.trim().to_lowercase())
+        .chars()
+        .collect();
+    for gram in chars.windows(3) {
+        // FNV-1a，保证跨进程、跨版本稳定
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in gram.iter().collect::<String>().bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % MOCK_EMBEDDING_DIM as u64) as usize] += sign;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,23 +483,33 @@ pub enum LlmError {
     Network(String),
     #[error("llm api error: status={status}, message={message}")]
     ApiError { status: u16, message: String },
+    #[error("llm invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("llm circuit breaker is open")]
+    CircuitOpen,
+    #[error("llm daily token budget exhausted")]
+    BudgetExhausted,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::LlmBudget;
 
     #[tokio::test]
     async fn disabled_mode_returns_error() {
         let cfg = LLMConfig {
             enabled: false,
-            mock: true,
+            provider: LlmBackend::Mock,
             api_url: String::new(),
             api_key: String::new(),
             timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
         };
         let provider = LlmProvider::new(&cfg);
-        let result = provider.chat(vec![]).await;
+        let result = provider.chat("gpt-4o-mini", vec![]).await;
         assert!(matches!(result, Err(LlmError::Disabled)));
     }
 
@@ -88,13 +517,143 @@ mod tests {
     async fn mock_mode_returns_text() {
         let cfg = LLMConfig {
             enabled: true,
-            mock: true,
+            provider: LlmBackend::Mock,
             api_url: String::new(),
             api_key: String::new(),
             timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
         };
         let provider = LlmProvider::new(&cfg);
-        let result = provider.chat(vec![]).await.unwrap();
+        let result = provider.chat("gpt-4o-mini", vec![]).await.unwrap();
         assert_eq!(result, "Mock LLM response");
     }
+
+    #[tokio::test]
+    async fn mock_embeddings_are_deterministic() {
+        let cfg = LLMConfig {
+            enabled: true,
+            provider: LlmBackend::Mock,
+            api_url: String::new(),
+            api_key: String::new(),
+            timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
+        };
+        let provider = LlmProvider::new(&cfg);
+        let texts = vec![
+            "apple".to_string(),
+            "apples".to_string(),
+            "zebra".to_string(),
+        ];
+        let first = provider.embed(&texts).await.unwrap();
+        let second = provider.embed(&texts).await.unwrap();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|v| v.len() == MOCK_EMBEDDING_DIM));
+
+        let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+        assert!(dot(&first[0], &first[1]) > dot(&first[0], &first[2]));
+    }
+
+    #[test]
+    fn features_fall_back_to_default_model() {
+        let mut cfg = LLMConfig {
+            enabled: true,
+            provider: LlmBackend::OpenAi,
+            api_url: String::new(),
+            api_key: "key".to_string(),
+            timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
+        };
+        cfg.models.default = "chat-model".to_string();
+        cfg.models.etymology = Some("etymology-model".to_string());
+        let provider = LlmProvider::new(&cfg);
+        assert_eq!(provider.model_for(LlmFeature::Etymology), "etymology-model");
+        assert_eq!(provider.model_for(LlmFeature::Examples), "chat-model");
+        assert_eq!(
+            provider.model_for(LlmFeature::Embedding),
+            "text-embedding-3-small"
+        );
+        assert_eq!(provider.model_tag(LlmFeature::Etymology), "etymology-model");
+    }
+
+    #[test]
+    fn validates_model_names() {
+        assert!(validate_model_name("gpt-4o-mini").is_ok());
+        assert!(validate_model_name("qwen2.5:7b").is_ok());
+        assert!(validate_model_name("org/model@v1").is_ok());
+        assert!(validate_model_name("").is_err());
+        assert!(validate_model_name("gpt 4").is_err());
+        assert!(validate_model_name(&"m".repeat(129)).is_err());
+    }
+
+    #[tokio::test]
+    async fn usage_is_recorded_and_budget_pauses_at_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(Store::open(dir.path().join("llm").to_str().unwrap()).unwrap());
+        let cfg = LLMConfig {
+            enabled: true,
+            provider: LlmBackend::Mock,
+            api_url: String::new(),
+            api_key: String::new(),
+            timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: LlmBudget {
+                daily_token_limit: 20,
+                input_price_per_1k: 1.0,
+                output_price_per_1k: 2.0,
+            },
+        };
+        let provider = LlmProvider::new(&cfg).with_usage_tracking(store.clone());
+        assert!(!provider.budget_exhausted());
+
+        provider.generate_etymology("apple", "苹果").await.unwrap();
+        let daily = store
+            .get_llm_usage_daily(Utc::now().date_naive())
+            .unwrap()
+            .unwrap();
+        assert_eq!(daily.by_feature["etymology"].calls, 1);
+        assert!(daily.total.prompt_tokens > 0 && daily.total.completion_tokens > 0);
+        assert!(daily.total.estimated_cost > 0.0);
+        assert!(provider.budget_exhausted());
+        assert!(matches!(
+            provider.generate_examples("apple", "苹果", 3).await,
+            Err(LlmError::BudgetExhausted)
+        ));
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_consecutive_failures() {
+        let cfg = LLMConfig {
+            enabled: true,
+            provider: LlmBackend::Local,
+            // 端口 9 (discard) 通常未监听，连接立即被拒绝
+            api_url: "http://127.0.0.1:9/v1".to_string(),
+            api_key: String::new(),
+            timeout_secs: 2,
+            models: Default::default(),
+            circuit: crate::config::LlmCircuitConfig {
+                failure_threshold: 2,
+                cooldown_secs: 60,
+            },
+            budget: Default::default(),
+        };
+        let provider = LlmProvider::new(&cfg);
+        for _ in 0..2 {
+            let err = provider
+                .generate_etymology("apple", "苹果")
+                .await
+                .unwrap_err();
+            assert!(!matches!(err, LlmError::CircuitOpen));
+        }
+        assert!(provider.is_circuit_open());
+        let err = provider.embed(&["apple".to_string()]).await.unwrap_err();
+        assert!(matches!(err, LlmError::CircuitOpen));
+        assert_eq!(provider.circuit_snapshot().rejected_total, 1);
+    }
 }
@@ -1 +1,9 @@
+pub mod avatar;
+pub mod circuit_breaker;
+pub mod generation_guard;
 pub mod llm_provider;
+pub mod moderation;
+pub mod password_breach;
+pub mod semantic_search;
+pub mod totp;
+pub mod webhook;
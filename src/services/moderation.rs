@@ -0,0 +1,161 @@
+//! LLM 生成内容的审核：写入缓存前先按屏蔽词表检查，`Provider` 模式下再交给 LLM 审核。
+//! 审核不可用时按拒绝处理（fail-closed），被拒内容记入 `moderation_rejections` 供管理员复核。
+
+use crate::config::{ModerationConfig, ModerationMode};
+use crate::services::llm_provider::LlmProvider;
+use crate::store::operations::moderation::ModerationRejection;
+use crate::store::Store;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    Rejected(String),
+}
+
+/// 命中的第一个屏蔽词：单词按整词匹配，含空格的短语按子串匹配，均不区分大小写
+pub fn blocked_term<'a>(terms: &'a [String], text: &str) -> Option<&'a str> {
+    let lower = text.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|t| !t.is_empty())
+        .collect();
+    terms
+        .iter()
+        .find(|term| {
+            let term = term.trim().to_lowercase();
+            !term.is_empty()
+                && if term.contains(' ') {
+                    lower.contains(&term)
+                } else {
+                    tokens.contains(&term.as_str())
+                }
+        })
+        .map(String::as_str)
+}
+
+pub async fn moderate(config: &ModerationConfig, llm: &LlmProvider, texts: &[String]) -> Verdict {
+    if config.mode == ModerationMode::Off {
+        return Verdict::Allowed;
+    }
+    for text in texts {
+        if let Some(term) = blocked_term(&config.blocked_terms, text) {
+            return Verdict::Rejected(format!("blocked term: {term}"));
+        }
+    }
+    if config.mode == ModerationMode::Provider {
+        for text in texts {
+            match llm.moderate(text).await {
+                Ok(None) => {}
+                Ok(Some(reason)) => return Verdict::Rejected(reason),
+                Err(e) => return Verdict::Rejected(format!("moderation unavailable: {e}")),
+            }
+        }
+    }
+    Verdict::Allowed
+}
+
+/// 审核通过返回 true；被拒时记录日志与拒绝记录后返回 false
+pub async fn screen(
+    store: &Store,
+    config: &ModerationConfig,
+    llm: &LlmProvider,
+    kind: &str,
+    word_id: &str,
+    texts: &[String],
+) -> bool {
+    let Verdict::Rejected(reason) = moderate(config, llm, texts).await else {
+        return true;
+    };
+    tracing::warn!(kind, word_id, reason = %reason, "Generated content rejected by moderation");
+    let rejection = ModerationRejection {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        word_id: word_id.to_string(),
+        content: texts.to_vec(),
+        reason,
+        created_at: chrono::Utc::now(),
+    };
+    if let Err(e) = store.record_moderation_rejection(&rejection) {
+        tracing::warn!(word_id, error = %e, "Failed to record moderation rejection");
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{LLMConfig, LlmBackend};
+
+    fn provider(enabled: bool) -> LlmProvider {
+        LlmProvider::new(&LLMConfig {
+            enabled,
+            provider: LlmBackend::Mock,
+            api_url: String::new(),
+            api_key: String::new(),
+            timeout_secs: 1,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
+        })
+    }
+
+    fn config(mode: ModerationMode, terms: &[&str]) -> ModerationConfig {
+        ModerationConfig {
+            mode,
+            blocked_terms: terms.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn blocked_terms_match_whole_words_and_phrases() {
+        let terms = vec!["ass".to_string(), "kill yourself".to_string()];
+        assert_eq!(blocked_term(&terms, "A class of its own"), None);
+        assert_eq!(blocked_term(&terms, "Don't be an ASS."), Some("ass"));
+        assert_eq!(
+            blocked_term(&terms, "He said: kill yourself!"),
+            Some("kill yourself")
+        );
+    }
+
+    #[tokio::test]
+    async fn provider_mode_fails_closed_when_llm_unavailable() {
+        let texts = vec!["A harmless sentence.".to_string()];
+        assert_eq!(
+            moderate(
+                &config(ModerationMode::Provider, &[]),
+                &provider(true),
+                &texts
+            )
+            .await,
+            Verdict::Allowed
+        );
+        assert!(matches!(
+            moderate(
+                &config(ModerationMode::Provider, &[]),
+                &provider(false),
+                &texts
+            )
+            .await,
+            Verdict::Rejected(_)
+        ));
+        // 仅词表模式不依赖 provider
+        assert_eq!(
+            moderate(
+                &config(ModerationMode::Wordlist, &[]),
+                &provider(false),
+                &texts
+            )
+            .await,
+            Verdict::Allowed
+        );
+        assert!(matches!(
+            moderate(
+                &config(ModerationMode::Wordlist, &["harmless"]),
+                &provider(false),
+                &texts
+            )
+            .await,
+            Verdict::Rejected(_)
+        ));
+    }
+}
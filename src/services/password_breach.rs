@@ -0,0 +1,95 @@
+//! HaveIBeenPwned range API 泄露密码检查（k-匿名：只发送 SHA-1 前 5 位）
+
+use once_cell::sync::Lazy;
+use sha1::{Digest, Sha1};
+
+use crate::config::PasswordPolicy;
+use crate::validation::{validate_password, PasswordViolation};
+
+const BREACH_CHECK_TIMEOUT_SECS: u64 = 3;
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(BREACH_CHECK_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+/// 按策略完整校验密码；泄露检查服务不可用时放行，避免外部故障阻塞注册
+pub async fn enforce(password: &str, policy: &PasswordPolicy) -> Result<(), PasswordViolation> {
+    validate_password(password, policy)?;
+    if !policy.check_breached {
+        return Ok(());
+    }
+    match is_breached(&policy.breach_api_url, password).await {
+        Ok(true) => Err(PasswordViolation::Breached),
+        Ok(false) => Ok(()),
+        Err(e) => {
+            tracing::warn!(error = %e, "Password breach check failed, skipping");
+            Ok(())
+        }
+    }
+}
+
+async fn is_breached(api_url: &str, password: &str) -> Result<bool, reqwest::Error> {
+    let (prefix, suffix) = hash_parts(password);
+    let body = HTTP_CLIENT
+        .get(format!("{api_url}{prefix}"))
+        .header("Add-Padding", "true")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(range_contains(&body, &suffix))
+}
+
+/// SHA-1 大写十六进制拆分为 (前 5 位, 其余 35 位)
+fn hash_parts(password: &str) -> (String, String) {
+    let digest = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = digest.split_at(5);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// 响应每行为 `SUFFIX:COUNT`，填充行的 COUNT 为 0
+fn range_contains(body: &str, suffix: &str) -> bool {
+    body.lines().any(|line| {
+        line.trim()
+            .split_once(':')
+            .is_some_and(|(s, count)| s.eq_ignore_ascii_case(suffix) && count.trim() != "0")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_parts_splits_sha1() {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let (prefix, suffix) = hash_parts("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+    }
+
+    #[test]
+    fn range_contains_ignores_padding_rows() {
+        let body =
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n1E4C9B93F3F0682250B6CF8331B7EE68FD8:0\r\n";
+        assert!(!range_contains(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"));
+        assert!(range_contains(body, "0018a45c4d1def81644b54ab7f969b88d65"));
+    }
+
+    #[tokio::test]
+    async fn enforce_skips_breach_check_when_disabled() {
+        let policy = PasswordPolicy {
+            breach_api_url: "http://127.0.0.1:1/".to_string(),
+            ..PasswordPolicy::default()
+        };
+        assert!(enforce("Abc12345", &policy).await.is_ok());
+        assert_eq!(
+            enforce("short", &policy).await,
+            Err(PasswordViolation::TooShort(8))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! 基于词向量的语义检索。
+//! 存在 word_clusters 时先按质心挑选近邻聚类（IVF 式近似索引），只对其成员打分；否则全量暴力检索。
+
+use std::collections::HashSet;
+
+use crate::store::operations::words::Word;
+use crate::store::{Store, StoreError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchIndex {
+    Clustered,
+    BruteForce,
+}
+
+impl SearchIndex {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Clustered => "clustered",
+            Self::BruteForce => "brute_force",
+        }
+    }
+}
+
+pub fn cosine_similarity(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a * norm_b))
+}
+
+/// 返回按相似度降序的 top-k；没有可比较的词向量时返回空列表
+pub fn search(
+    store: &Store,
+    query: &[f64],
+    limit: usize,
+    probe_clusters: usize,
+) -> Result<(Vec<(Word, f64)>, SearchIndex), StoreError> {
+    if probe_clusters > 0 {
+        let mut clusters: Vec<(f64, Vec<String>)> = store
+            .list_word_clusters()?
+            .into_iter()
+            .filter_map(|c| cosine_similarity(query, &c.centroid).map(|s| (s, c.word_ids)))
+            .collect();
+        if !clusters.is_empty() {
+            clusters.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let candidate_ids: Vec<String> = clusters
+                .into_iter()
+                .take(probe_clusters)
+                .flat_map(|(_, ids)| ids)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let candidates = store.get_words_by_ids(&candidate_ids)?.into_values();
+            let results = top_k(candidates, query, limit);
+            if !results.is_empty() {
+                return Ok((results, SearchIndex::Clustered));
+            }
+        }
+    }
+
+    let mut words = Vec::new();
+    for item in store.words.iter() {
+        let (_, raw) = item?;
+        let word: Word = serde_json::from_slice(&raw)?;
+        if word.embedding.is_some() {
+            words.push(word);
+        }
+    }
+    Ok((top_k(words, query, limit), SearchIndex::BruteForce))
+}
+
+fn top_k(words: impl IntoIterator<Item = Word>, query: &[f64], limit: usize) -> Vec<(Word, f64)> {
+    let mut scored: Vec<(Word, f64)> = words
+        .into_iter()
+        .filter_map(|w| {
+            let score = cosine_similarity(query, w.embedding.as_deref()?)?;
+            Some((w, score))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cosine_similarity_handles_mismatch_and_zero() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]), Some(1.0));
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).unwrap().abs() < 1e-12);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), None);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), None);
+    }
+}
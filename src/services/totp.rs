@@ -0,0 +1,213 @@
+//! RFC 6238 TOTP（SHA-1 / 6 位 / 30 秒）及密钥的静态加密（AES-256-GCM）
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+type HmacSha1 = Hmac<Sha1>;
+
+const STEP_SECS: u64 = 30;
+const DIGITS: u32 = 6;
+const SECRET_LEN: usize = 20;
+/// 绑定到密文的附加数据，避免密文被挪作他用
+const SECRET_AAD: &[u8] = b"admin_totp_secret";
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_LEN];
+    rand::thread_rng().fill_bytes(&mut secret);
+    secret
+}
+
+/// RFC 4648 base32，不带填充（验证器 App 通用格式）
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECS}",
+        percent_encode(account),
+        base32_encode(secret),
+    )
+}
+
+fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~@".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(secret).expect("HMAC can accept any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(DIGITS)
+}
+
+pub fn current_step(unix_secs: u64) -> u64 {
+    unix_secs / STEP_SECS
+}
+
+pub fn generate_code(secret: &[u8], unix_secs: u64) -> String {
+    format!(
+        "{:0width$}",
+        hotp(secret, current_step(unix_secs)),
+        width = DIGITS as usize
+    )
+}
+
+/// 校验验证码，允许前后各一个时间步的时钟偏差；返回匹配的时间步用于防重放
+pub fn verify_code(secret: &[u8], code: &str, unix_secs: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let expected: u32 = code.parse().ok()?;
+    let step = current_step(unix_secs);
+    [step.saturating_sub(1), step, step + 1]
+        .into_iter()
+        .find(|&s| hotp(secret, s) == expected)
+}
+
+/// 生成形如 `a1b2-c3d4-e5f6-a7b8` 的一次性备用码（64 位随机数）
+pub fn generate_backup_codes(count: usize) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .map(|_| {
+            let raw = hex::encode(rng.next_u64().to_be_bytes());
+            format!(
+                "{}-{}-{}-{}",
+                &raw[..4],
+                &raw[4..8],
+                &raw[8..12],
+                &raw[12..]
+            )
+        })
+        .collect()
+}
+
+/// 统一备用码格式（忽略大小写、连字符与空白）后再做哈希比较
+pub fn normalize_backup_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// 任意长度的加密密钥经 SHA-256 得到 AES-256 密钥
+fn cipher(key: &str) -> LessSafeKey {
+    let digest = Sha256::digest(key.as_bytes());
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &digest).expect("AES-256 key is 32 bytes"))
+}
+
+/// AES-256-GCM 加密，输出 hex(nonce || 密文 || 标签)
+pub fn encrypt_secret(key: &str, secret: &[u8]) -> String {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let mut sealed = secret.to_vec();
+    cipher(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(SECRET_AAD),
+            &mut sealed,
+        )
+        .expect("AES-GCM seal cannot fail for short inputs");
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&sealed);
+    hex::encode(out)
+}
+
+/// 认证失败（密文被篡改或加密密钥已更换）时返回 None
+pub fn decrypt_secret(key: &str, sealed: &str) -> Option<Vec<u8>> {
+    let raw = hex::decode(sealed).ok()?;
+    if raw.len() < NONCE_LEN + AES_256_GCM.tag_len() {
+        return None;
+    }
+    let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    let secret = cipher(key)
+        .open_in_place(nonce, Aad::from(SECRET_AAD), &mut in_out)
+        .ok()?;
+    Some(secret.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rfc6238_sha1_vectors() {
+        let secret = b"12345678901234567890";
+        assert_eq!(hotp(secret, current_step(59)) % 1_000_000, 287082);
+        assert_eq!(generate_code(secret, 1_111_111_109), "081804");
+        assert_eq!(verify_code(secret, "287082", 59), Some(1));
+        assert_eq!(verify_code(secret, "287082", 59 + 30 * 3), None);
+        assert_eq!(verify_code(secret, "28708", 59), None);
+    }
+
+    #[test]
+    fn base32_encodes_without_padding() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(
+            base32_encode(b"12345678901234567890"),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+        );
+    }
+
+    #[test]
+    fn encrypted_secret_roundtrips_and_detects_tampering() {
+        let secret = generate_secret();
+        let sealed = encrypt_secret("master-key", &secret);
+        assert_eq!(decrypt_secret("master-key", &sealed), Some(secret));
+        assert_eq!(decrypt_secret("other-key", &sealed), None);
+
+        let mut tampered = sealed.into_bytes();
+        tampered[40] = if tampered[40] == b'0' { b'1' } else { b'0' };
+        assert_eq!(
+            decrypt_secret("master-key", &String::from_utf8(tampered).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn backup_codes_carry_64_random_bits() {
+        let codes = generate_backup_codes(10);
+        assert!(codes
+            .iter()
+            .all(|c| normalize_backup_code(c).len() == 16 && c.len() == 19));
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+    }
+}
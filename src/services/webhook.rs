@@ -0,0 +1,139 @@
+//! 用户 webhook 推送：HMAC-SHA256 签名，失败按指数退避重试
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::routes::words::resolve_import_url_addrs;
+use crate::store::operations::webhooks::UserWebhook;
+use crate::store::Store;
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+const WEBHOOK_MAX_ATTEMPTS: u32 = 4;
+const WEBHOOK_BACKOFF_BASE_MS: u64 = 1_000;
+
+pub const SIGNATURE_HEADER: &str = "X-WordForge-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-WordForge-Timestamp";
+pub const EVENT_HEADER: &str = "X-WordForge-Event";
+
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// 签名内容为 `{timestamp}.{body}`，接收方可据此校验来源并拒绝过旧的重放
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 若用户配置了 webhook，则在后台投递事件；不阻塞调用方
+pub fn dispatch(store: &Store, user_id: &str, event: &str, data: serde_json::Value) {
+    let webhook = match store.get_user_webhook(user_id) {
+        Ok(Some(w)) => w,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, user_id, "Failed to load user webhook");
+            return;
+        }
+    };
+    let payload = serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "event": event,
+        "userId": user_id,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+        "data": data,
+    });
+    let event = event.to_string();
+    tokio::spawn(async move {
+        deliver(&webhook, &event, &payload).await;
+    });
+}
+
+async fn deliver(webhook: &UserWebhook, event: &str, payload: &serde_json::Value) {
+    let body = match serde_json::to_vec(payload) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to serialize webhook payload");
+            return;
+        }
+    };
+
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        match send_once(webhook, event, &body).await {
+            Ok(()) => return,
+            Err(e) if attempt == WEBHOOK_MAX_ATTEMPTS => {
+                tracing::warn!(
+                    user_id = %webhook.user_id,
+                    event,
+                    attempts = attempt,
+                    error = %e,
+                    "Webhook delivery failed, giving up"
+                );
+            }
+            Err(e) => {
+                tracing::debug!(
+                    user_id = %webhook.user_id,
+                    event,
+                    attempt,
+                    error = %e,
+                    "Webhook delivery failed, retrying"
+                );
+                let delay = WEBHOOK_BACKOFF_BASE_MS << (attempt - 1);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+        }
+    }
+}
+
+async fn send_once(webhook: &UserWebhook, event: &str, body: &[u8]) -> Result<(), String> {
+    let url = reqwest::Url::parse(&webhook.url).map_err(|e| e.to_string())?;
+    // 每次投递都重新解析并校验公网地址，防止配置后 DNS 被指向内网
+    let (host, addrs) = resolve_import_url_addrs(&url)
+        .await
+        .map_err(|e| e.message)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(&host, &addrs)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let timestamp = chrono::Utc::now().timestamp();
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, body))
+        .body(body.to_vec())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        const BODY: &[u8] = br#"{"event":"badge_unlocked"}"#;
+        let sig = sign("whsec_test", 1_700_000_000, BODY);
+        assert!(sig.starts_with("sha256="));
+        assert_eq!(sig.len(), "sha256=".len() + 64);
+        assert_eq!(sig, sign("whsec_test", 1_700_000_000, BODY));
+        assert_ne!(sig, sign("whsec_test", 1_700_000_001, BODY));
+        assert_ne!(sig, sign("whsec_other", 1_700_000_000, BODY));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -5,8 +6,13 @@ use std::time::Instant;
 use tokio::sync::{broadcast, RwLock};
 
 use crate::amas::engine::AMASEngine;
-use crate::config::Config;
+use crate::config::{Config, LlmBackend};
+use crate::middleware::in_flight::InFlightRequests;
 use crate::middleware::rate_limit::{AuthRateLimitState, RateLimitState};
+use crate::services::generation_guard::GenerationGuard;
+use crate::services::llm_provider::LlmProvider;
+use crate::store::operations::retention::RetentionReport;
+use crate::store::operations::word_difficulty::WordDifficultyReport;
 use crate::store::Store;
 
 #[derive(Clone)]
@@ -14,12 +20,43 @@ pub struct AppState {
     store: Arc<Store>,
     amas_engine: Arc<AMASEngine>,
     runtime: Arc<RuntimeConfig>,
+    llm: Arc<LlmProvider>,
     rate_limit: Arc<RateLimitState>,
     auth_rate_limit: Arc<AuthRateLimitState>,
     config: Arc<Config>,
     shutdown_tx: broadcast::Sender<()>,
     started_at: Instant,
     update_cache: Arc<RwLock<Option<(Instant, serde_json::Value)>>>,
+    /// 留存矩阵计算代价高，按回看周数缓存
+    retention_cache: Arc<RwLock<HashMap<usize, (Instant, RetentionReport)>>>,
+    word_difficulty_cache: Arc<RwLock<Option<(Instant, WordDifficultyReport)>>>,
+    readiness: Arc<Readiness>,
+    in_flight: Arc<InFlightRequests>,
+    /// 例句按需生成的单飞与失败退避
+    example_generation: Arc<GenerationGuard>,
+}
+
+/// 就绪标记：leader 节点需等待 worker 调度器启动后才报告就绪
+pub struct Readiness {
+    scheduler_required: bool,
+    scheduler_started: AtomicBool,
+}
+
+impl Readiness {
+    pub fn new(scheduler_required: bool) -> Self {
+        Self {
+            scheduler_required,
+            scheduler_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn mark_scheduler_started(&self) {
+        self.scheduler_started.store(true, Ordering::Release);
+    }
+
+    pub fn scheduler_ready(&self) -> bool {
+        !self.scheduler_required || self.scheduler_started.load(Ordering::Acquire)
+    }
 }
 
 pub struct RuntimeConfig {
@@ -43,17 +80,24 @@ impl AppState {
             config.auth_rate_limit.window_secs,
             config.auth_rate_limit.max_requests,
         ));
+        let llm = Arc::new(LlmProvider::new(&config.llm).with_usage_tracking(store.clone()));
 
         Self {
             store,
             amas_engine,
             runtime,
+            llm,
             rate_limit,
             auth_rate_limit,
             config: Arc::new(config.clone()),
             shutdown_tx,
             started_at: Instant::now(),
             update_cache: Arc::new(RwLock::new(None)),
+            retention_cache: Arc::new(RwLock::new(HashMap::new())),
+            word_difficulty_cache: Arc::new(RwLock::new(None)),
+            readiness: Arc::new(Readiness::new(config.worker.is_leader)),
+            in_flight: Arc::new(InFlightRequests::default()),
+            example_generation: Arc::new(GenerationGuard::default()),
         }
     }
 
@@ -69,6 +113,10 @@ impl AppState {
         &self.runtime
     }
 
+    pub fn llm(&self) -> &Arc<LlmProvider> {
+        &self.llm
+    }
+
     pub fn rate_limit(&self) -> &Arc<RateLimitState> {
         &self.rate_limit
     }
@@ -96,13 +144,33 @@ impl AppState {
     pub fn update_cache(&self) -> &RwLock<Option<(Instant, serde_json::Value)>> {
         &self.update_cache
     }
+
+    pub fn retention_cache(&self) -> &RwLock<HashMap<usize, (Instant, RetentionReport)>> {
+        &self.retention_cache
+    }
+
+    pub fn word_difficulty_cache(&self) -> &RwLock<Option<(Instant, WordDifficultyReport)>> {
+        &self.word_difficulty_cache
+    }
+
+    pub fn readiness(&self) -> &Arc<Readiness> {
+        &self.readiness
+    }
+
+    pub fn in_flight(&self) -> &Arc<InFlightRequests> {
+        &self.in_flight
+    }
+
+    pub fn example_generation(&self) -> &Arc<GenerationGuard> {
+        &self.example_generation
+    }
 }
 
 impl RuntimeConfig {
     pub fn from_config(config: &Config) -> Self {
         Self {
             llm_enabled: AtomicBool::new(config.llm.enabled),
-            llm_mock: AtomicBool::new(config.llm.mock),
+            llm_mock: AtomicBool::new(config.llm.provider == LlmBackend::Mock),
         }
     }
 
@@ -0,0 +1,369 @@
+//! 存储备份与恢复：按树逐条导出为长度前缀的二进制文件，恢复时按同一格式重建
+//!
+//! 文件格式：`MAGIC` + 版本号(u32)，随后依次为
+//! `TAG_TREE name` 与若干 `TAG_ENTRY key value`，以 `TAG_END` 结尾；
+//! 所有长度均为大端 u32。
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::store::{Store, StoreError};
+
+const MAGIC: &[u8; 8] = b"WFBACKUP";
+const FORMAT_VERSION: u32 = 1;
+const TAG_END: u8 = 0;
+const TAG_TREE: u8 = 1;
+const TAG_ENTRY: u8 = 2;
+
+/// 恢复时每批写入的条目数
+const RESTORE_BATCH_SIZE: usize = 1000;
+
+const FILE_PREFIX: &str = "backup-";
+const FILE_EXTENSION: &str = "wfbak";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupReport {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub trees: usize,
+    pub entries: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub trees: usize,
+    pub entries: u64,
+}
+
+impl Store {
+    /// 先 flush 再逐树导出到 `dir` 下的新文件；写入临时文件后原子改名，避免留下半截备份。
+    /// sled 没有跨树快照，导出期间的并发写入可能只部分包含在备份中。
+    pub fn write_backup(&self, dir: &Path) -> Result<BackupReport, StoreError> {
+        self.db.flush()?;
+        fs::create_dir_all(dir)?;
+
+        let created_at = Utc::now();
+        let filename = format!(
+            "{FILE_PREFIX}{}.{FILE_EXTENSION}",
+            created_at.format("%Y%m%dT%H%M%S%3fZ")
+        );
+        let path = dir.join(&filename);
+        let tmp_path = dir.join(format!("{filename}.tmp"));
+
+        let mut out = BufWriter::new(File::create(&tmp_path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_be_bytes())?;
+
+        let mut trees = 0;
+        let mut entries = 0u64;
+        for name in self.db.tree_names() {
+            let tree = self.db.open_tree(&name)?;
+            out.write_all(&[TAG_TREE])?;
+            write_chunk(&mut out, &name)?;
+            for item in tree.iter() {
+                let (key, value) = item?;
+                out.write_all(&[TAG_ENTRY])?;
+                write_chunk(&mut out, &key)?;
+                write_chunk(&mut out, &value)?;
+                entries += 1;
+            }
+            trees += 1;
+        }
+        out.write_all(&[TAG_END])?;
+
+        let file = out.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, &path)?;
+
+        Ok(BackupReport {
+            size_bytes: fs::metadata(&path)?.len(),
+            path,
+            trees,
+            entries,
+            created_at,
+        })
+    }
+
+    /// 从备份文件重建所有树。数据库非空时拒绝执行，除非 `force`（清空现有数据）。
+    /// 先完整校验一遍文件，确认结构完好后才清空并写入，损坏或截断的备份不会破坏现有数据。
+    /// 调用方需在恢复后执行迁移。
+    pub fn restore_backup(&self, path: &Path, force: bool) -> Result<RestoreReport, StoreError> {
+        let report = BackupReader::open(path)?.replay(|_| Ok(()))?;
+
+        let existing = self.db.tree_names();
+        if !force {
+            for name in &existing {
+                if !self.db.open_tree(name)?.is_empty() {
+                    return Err(StoreError::Validation(
+                        "database is not empty; use --force to overwrite".to_string(),
+                    ));
+                }
+            }
+        }
+        for name in &existing {
+            self.db.open_tree(name)?.clear()?;
+        }
+
+        let mut current: Option<(sled::Tree, sled::Batch, usize)> = None;
+        BackupReader::open(path)?.replay(|item| {
+            match item {
+                BackupItem::Tree(name) => {
+                    if let Some((tree, batch, _)) = current.take() {
+                        tree.apply_batch(batch)?;
+                    }
+                    current = Some((self.db.open_tree(name)?, sled::Batch::default(), 0));
+                }
+                BackupItem::Entry(key, value) => {
+                    // 校验阶段已保证条目前有树头
+                    if let Some((tree, batch, pending)) = current.as_mut() {
+                        batch.insert(key, value);
+                        *pending += 1;
+                        if *pending >= RESTORE_BATCH_SIZE {
+                            tree.apply_batch(std::mem::take(batch))?;
+                            *pending = 0;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        if let Some((tree, batch, _)) = current.take() {
+            tree.apply_batch(batch)?;
+        }
+        self.db.flush()?;
+
+        Ok(report)
+    }
+}
+
+enum BackupItem {
+    Tree(Vec<u8>),
+    Entry(Vec<u8>, Vec<u8>),
+}
+
+/// 顺序读取备份文件，所有长度都与文件剩余字节数比较，防止伪造的长度触发超大分配
+struct BackupReader {
+    input: BufReader<File>,
+    remaining: u64,
+}
+
+impl BackupReader {
+    fn open(path: &Path) -> Result<Self, StoreError> {
+        let file = File::open(path)?;
+        let remaining = file.metadata()?.len();
+        let mut reader = Self {
+            input: BufReader::new(file),
+            remaining,
+        };
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(StoreError::Validation("not a backup file".to_string()));
+        }
+        let version = reader.read_u32()?;
+        if version != FORMAT_VERSION {
+            return Err(StoreError::Validation(format!(
+                "unsupported backup format version {version}"
+            )));
+        }
+        Ok(reader)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), StoreError> {
+        if buf.len() as u64 > self.remaining {
+            return Err(StoreError::Validation("truncated backup".to_string()));
+        }
+        self.input.read_exact(buf)?;
+        self.remaining -= buf.len() as u64;
+        Ok(())
+    }
+
+    fn read_u32(&mut self) -> Result<u32, StoreError> {
+        let mut buf = [0u8; 4];
+        self.read_exact(&mut buf)?;
+        Ok(u32::from_be_bytes(buf))
+    }
+
+    fn read_chunk(&mut self) -> Result<Vec<u8>, StoreError> {
+        let len = u64::from(self.read_u32()?);
+        if len > self.remaining {
+            return Err(StoreError::Validation("truncated backup".to_string()));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// 依次回调树头与条目；文件必须以 `TAG_END` 恰好结束
+    fn replay(
+        mut self,
+        mut on_item: impl FnMut(BackupItem) -> Result<(), StoreError>,
+    ) -> Result<RestoreReport, StoreError> {
+        let mut trees = 0;
+        let mut entries = 0u64;
+        let mut in_tree = false;
+        loop {
+            let mut tag = [0u8; 1];
+            self.read_exact(&mut tag)?;
+            match tag[0] {
+                TAG_TREE => {
+                    on_item(BackupItem::Tree(self.read_chunk()?))?;
+                    in_tree = true;
+                    trees += 1;
+                }
+                TAG_ENTRY => {
+                    if !in_tree {
+                        return Err(StoreError::Validation(
+                            "backup entry before tree header".to_string(),
+                        ));
+                    }
+                    let key = self.read_chunk()?;
+                    let value = self.read_chunk()?;
+                    on_item(BackupItem::Entry(key, value))?;
+                    entries += 1;
+                }
+                TAG_END => break,
+                other => {
+                    return Err(StoreError::Validation(format!(
+                        "corrupt backup: unknown tag {other}"
+                    )))
+                }
+            }
+        }
+        if self.remaining != 0 {
+            return Err(StoreError::Validation(
+                "corrupt backup: trailing data".to_string(),
+            ));
+        }
+        Ok(RestoreReport { trees, entries })
+    }
+}
+
+fn write_chunk(out: &mut impl Write, bytes: &[u8]) -> Result<(), StoreError> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| StoreError::Validation("backup chunk exceeds 4 GiB".to_string()))?;
+    out.write_all(&len.to_be_bytes())?;
+    out.write_all(bytes)?;
+    Ok(())
+}
+
+/// 只保留最新的 `keep` 份备份（文件名按时间排序），返回删除的数量
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<usize, StoreError> {
+    let mut backups: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| is_backup_file(path))
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep);
+    for path in &backups[..excess] {
+        fs::remove_file(path)?;
+    }
+    Ok(excess)
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name.starts_with(FILE_PREFIX)
+        && path.extension().and_then(|e| e.to_str()) == Some(FILE_EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn backup_writes_all_trees_and_prunes_oldest() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("db").to_str().unwrap()).unwrap();
+        store.words.insert(b"w1", b"{}".as_slice()).unwrap();
+        store.users.insert(b"u1", b"{}".as_slice()).unwrap();
+
+        let backup_dir = dir.path().join("backups");
+        let first = store.write_backup(&backup_dir).unwrap();
+        assert!(first.path.exists());
+        assert!(first.size_bytes > 0);
+        assert_eq!(first.entries, 2);
+        assert!(first.trees > 2);
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = store.write_backup(&backup_dir).unwrap();
+        assert_eq!(prune_backups(&backup_dir, 1).unwrap(), 1);
+        assert!(!first.path.exists());
+        assert!(second.path.exists());
+    }
+
+    #[test]
+    fn restore_requires_force_on_non_empty_database() {
+        let dir = tempdir().unwrap();
+        let source = Store::open(dir.path().join("source").to_str().unwrap()).unwrap();
+        source.words.insert(b"w1", b"{\"a\":1}".as_slice()).unwrap();
+        source.users.insert(b"u1", b"{}".as_slice()).unwrap();
+        let backup = source.write_backup(&dir.path().join("backups")).unwrap();
+
+        let target = Store::open(dir.path().join("target").to_str().unwrap()).unwrap();
+        let report = target.restore_backup(&backup.path, false).unwrap();
+        assert_eq!(report.entries, 2);
+        assert_eq!(
+            target.words.get(b"w1").unwrap().as_deref(),
+            Some(b"{\"a\":1}".as_slice())
+        );
+
+        target.users.insert(b"stale", b"{}".as_slice()).unwrap();
+        assert!(matches!(
+            target.restore_backup(&backup.path, false),
+            Err(StoreError::Validation(_))
+        ));
+        target.restore_backup(&backup.path, true).unwrap();
+        assert!(target.users.get(b"stale").unwrap().is_none());
+        assert!(target.users.get(b"u1").unwrap().is_some());
+    }
+
+    #[test]
+    fn corrupt_backup_leaves_database_untouched() {
+        let dir = tempdir().unwrap();
+        let source = Store::open(dir.path().join("source").to_str().unwrap()).unwrap();
+        source.words.insert(b"w1", b"{}".as_slice()).unwrap();
+        let backup = source.write_backup(&dir.path().join("backups")).unwrap();
+        let bytes = fs::read(&backup.path).unwrap();
+
+        let target = Store::open(dir.path().join("target").to_str().unwrap()).unwrap();
+        target.users.insert(b"live", b"{}".as_slice()).unwrap();
+
+        let truncated = dir.path().join("truncated.wfbak");
+        fs::write(&truncated, &bytes[..bytes.len() - 3]).unwrap();
+        assert!(matches!(
+            target.restore_backup(&truncated, true),
+            Err(StoreError::Validation(_))
+        ));
+
+        // 声明 4 GiB 长度的树名，不应按该长度分配内存
+        let mut oversized = bytes[..12].to_vec();
+        oversized.push(TAG_TREE);
+        oversized.extend_from_slice(&u32::MAX.to_be_bytes());
+        let oversized_path = dir.path().join("oversized.wfbak");
+        fs::write(&oversized_path, oversized).unwrap();
+        assert!(matches!(
+            target.restore_backup(&oversized_path, true),
+            Err(StoreError::Validation(_))
+        ));
+
+        assert!(target.users.get(b"live").unwrap().is_some());
+    }
+}
@@ -91,12 +91,29 @@ pub fn engine_algo_state_key(user_id: &str, algorithm_id: &str) -> Result<String
     Ok(format!("{}:{}", validate_id(user_id)?, algorithm_id))
 }
 
+pub fn engine_algo_state_prefix(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:", validate_id(user_id)?))
+}
+
 pub fn monitoring_event_key(timestamp_ms: i64, event_id: &str) -> Result<String, StoreError> {
     let ts = timestamp_ms.max(0) as u64;
     let reverse_ts = u64::MAX - ts;
     Ok(format!("{:020}:{}", reverse_ts, validate_id(event_id)?))
 }
 
+/// 时间戳不晚于 `timestamp_ms` 的监控事件都排在该键之后（含同毫秒事件）
+pub fn monitoring_event_cutoff_key(timestamp_ms: i64) -> String {
+    let ts = timestamp_ms.max(0) as u64;
+    format!("{:020}:", u64::MAX - ts)
+}
+
+/// algo_state_repairs: `{reverse_ts:020}:{repair_id}`，最新的在前
+pub fn algo_state_repair_key(timestamp_ms: i64, repair_id: &str) -> Result<String, StoreError> {
+    let ts = timestamp_ms.max(0) as u64;
+    let reverse_ts = u64::MAX - ts;
+    Ok(format!("{:020}:{}", reverse_ts, validate_id(repair_id)?))
+}
+
 pub fn metrics_daily_key(date: &str, algorithm_id: &str) -> Result<String, StoreError> {
     Ok(format!(
         "{}:{}",
@@ -105,10 +122,19 @@ pub fn metrics_daily_key(date: &str, algorithm_id: &str) -> Result<String, Store
     ))
 }
 
+/// 用户级每日掌握数 rollup 在 algorithm_metrics_daily 中的指标名
+pub fn user_mastery_rollup_id(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("mastered_{}", validate_id(user_id)?))
+}
+
 pub fn password_reset_key(token_hash: &str) -> Result<String, StoreError> {
     Ok(validate_id(token_hash)?.to_string())
 }
 
+pub fn email_change_key(token_hash: &str) -> Result<String, StoreError> {
+    Ok(validate_id(token_hash)?.to_string())
+}
+
 pub fn config_version_key(config_type: &str, version: u32) -> Result<String, StoreError> {
     Ok(format!("{}:{:010}", validate_id(config_type)?, version))
 }
@@ -244,6 +270,42 @@ pub fn user_preferences_key(user_id: &str) -> Result<String, StoreError> {
     Ok(validate_id(user_id)?.to_string())
 }
 
+pub fn user_webhook_key(user_id: &str) -> Result<String, StoreError> {
+    Ok(validate_id(user_id)?.to_string())
+}
+
+/// 周报键: `{user_id}:{iso_week}`，iso_week 形如 `2026-W07`，字典序即时间序
+pub fn weekly_report_key(user_id: &str, iso_week: &str) -> Result<String, StoreError> {
+    Ok(format!(
+        "{}:{}",
+        validate_id(user_id)?,
+        validate_id(iso_week)?
+    ))
+}
+
+pub fn weekly_report_prefix(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:", validate_id(user_id)?))
+}
+
+/// 视觉疲劳样本键: `{user_id}:{timestamp_ms:020}:{seq:020}`，字典序即时间序，
+/// 同一毫秒内由单调递增的 seq 保证写入顺序
+pub fn visual_fatigue_key(
+    user_id: &str,
+    timestamp_ms: i64,
+    seq: u64,
+) -> Result<String, StoreError> {
+    Ok(format!(
+        "{}:{:020}:{:020}",
+        validate_id(user_id)?,
+        timestamp_ms.max(0),
+        seq
+    ))
+}
+
+pub fn visual_fatigue_prefix(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:", validate_id(user_id)?))
+}
+
 pub fn etymology_key(word_id: &str) -> Result<String, StoreError> {
     Ok(validate_id(word_id)?.to_string())
 }
@@ -274,6 +336,14 @@ pub fn word_elo_key(word_id: &str) -> Result<String, StoreError> {
     Ok(format!("word_elo:{}", validate_id(word_id)?))
 }
 
+pub fn placement_key(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("placement:{}", validate_id(user_id)?))
+}
+
+pub fn feature_flag_overrides_key(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("feature_flags:{}", validate_id(user_id)?))
+}
+
 pub fn confusion_pair_key(word_id_a: &str, word_id_b: &str) -> Result<String, StoreError> {
     let a = validate_id(word_id_a)?;
     let b = validate_id(word_id_b)?;
@@ -284,6 +354,43 @@ pub fn confusion_pair_key(word_id_a: &str, word_id_b: &str) -> Result<String, St
     }
 }
 
+pub fn word_cluster_key(cluster_id: &str) -> Result<String, StoreError> {
+    Ok(validate_id(cluster_id)?.to_string())
+}
+
+pub fn generated_examples_key(word_id: &str) -> Result<String, StoreError> {
+    Ok(validate_id(word_id)?.to_string())
+}
+
+/// moderation_rejections: `{timestamp_020}:{id}`，按时间排序
+pub fn moderation_rejection_key(created_at_ms: i64, id: &str) -> Result<String, StoreError> {
+    Ok(format!("{:020}:{}", created_at_ms.max(0), validate_id(id)?))
+}
+
+/// 早于 `created_at_ms` 的拒绝记录都排在该键之前
+pub fn moderation_rejection_cutoff_key(created_at_ms: i64) -> String {
+    format!("{:020}:", created_at_ms.max(0))
+}
+
+/// llm_advisories: `{timestamp_020}:{id}`，按时间排序
+pub fn llm_advisory_key(created_at_ms: i64, id: &str) -> Result<String, StoreError> {
+    Ok(format!("{:020}:{}", created_at_ms.max(0), validate_id(id)?))
+}
+
+/// scheduled_broadcasts: `{scheduled_at_020}:{id}`，按计划时间排序
+pub fn scheduled_broadcast_key(scheduled_at_ms: i64, id: &str) -> Result<String, StoreError> {
+    Ok(format!("{:020}:{}", scheduled_at_ms.max(0), validate_id(id)?))
+}
+
+/// broadcast_deliveries: `{broadcast_id}:{user_id}`
+pub fn broadcast_delivery_key(broadcast_id: &str, user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:{}", validate_id(broadcast_id)?, validate_id(user_id)?))
+}
+
+pub fn broadcast_delivery_prefix(broadcast_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:", validate_id(broadcast_id)?))
+}
+
 // Secondary index keys
 
 /// users_by_created_at: `{timestamp_be_20}:{user_id}`
@@ -300,6 +407,27 @@ pub fn words_by_created_at_key(created_at_ms: i64, word_id: &str) -> Result<Stri
     Ok(format!("{:020}:{}", reverse_ts, validate_id(word_id)?))
 }
 
+/// word_tag_index: `{tag}:{word_id}`；标签本身可含冒号，解析时从右侧切分
+pub fn word_tag_key(tag: &str, word_id: &str) -> Result<String, StoreError> {
+    Ok(format!(
+        "{}{}",
+        word_tag_prefix(tag)?,
+        validate_id(word_id)?
+    ))
+}
+
+pub fn word_tag_prefix(tag: &str) -> Result<String, StoreError> {
+    if tag.is_empty() {
+        return Err(StoreError::Validation("标签不能为空".to_string()));
+    }
+    Ok(format!("{}:", tag))
+}
+
+/// 解析 word_tag_index 的 key，返回 `(tag, word_id)`
+pub fn parse_word_tag_key(key: &[u8]) -> Option<(&str, &str)> {
+    std::str::from_utf8(key).ok()?.rsplit_once(':')
+}
+
 /// records_by_time: `{timestamp_be_20}:{record_id}`
 /// Uses forward timestamp (big-endian) so range scan `start..` works for "since" queries.
 pub fn records_by_time_key(created_at_ms: i64, record_id: &str) -> Result<String, StoreError> {
@@ -327,6 +455,15 @@ pub fn word_ref_prefix(word_id: &str) -> Result<String, StoreError> {
     Ok(format!("{}:", validate_id(word_id)?))
 }
 
+/// word_references 中某用户某单词的作答记录前缀；hex 编码保持字节序，扫描结果与 records 同序
+pub fn word_user_records_ref_prefix(word_id: &str, user_id: &str) -> Result<String, StoreError> {
+    Ok(format!(
+        "{}:records:{}",
+        validate_id(word_id)?,
+        hex::encode(record_prefix(user_id)?)
+    ))
+}
+
 /// user_stats key
 pub fn user_stats_key(user_id: &str) -> Result<String, StoreError> {
     Ok(validate_id(user_id)?.to_string())
@@ -348,6 +485,54 @@ pub fn alert_dedup_key(user_id: &str, word_id: &str) -> Result<String, StoreErro
     ))
 }
 
+/// 幂等键: `{user_id}:{scope}:{key_hash}`，key_hash 为客户端 Idempotency-Key 的摘要
+pub fn idempotency_key(user_id: &str, scope: &str, key_hash: &str) -> Result<String, StoreError> {
+    Ok(format!(
+        "{}:{}:{}",
+        validate_id(user_id)?,
+        validate_id(scope)?,
+        validate_id(key_hash)?
+    ))
+}
+
+pub fn idempotency_prefix(user_id: &str) -> Result<String, StoreError> {
+    Ok(format!("{}:", validate_id(user_id)?))
+}
+
+/// deferred_notifications 索引: `{release_ts_ms:020}:{user_id}:{notification_id}`，按释放时间升序
+pub fn deferred_notification_key(
+    release_ts_ms: i64,
+    user_id: &str,
+    notification_id: &str,
+) -> Result<String, StoreError> {
+    Ok(format!(
+        "{:020}:{}:{}",
+        release_ts_ms.max(0),
+        validate_id(user_id)?,
+        validate_id(notification_id)?
+    ))
+}
+
+/// 解析 deferred_notifications 索引键，返回 (release_ts_ms, user_id, notification_id)
+pub fn parse_deferred_notification_key(key: &[u8]) -> Option<(i64, String, String)> {
+    let key_text = std::str::from_utf8(key).ok()?;
+    let mut parts = key_text.splitn(3, ':');
+    let ts = parts.next()?.parse::<i64>().ok()?;
+    let user_id = parts.next()?.to_string();
+    let notification_id = parts.next()?.to_string();
+    Some((ts, user_id, notification_id))
+}
+
+/// monitoring_daily: `{YYYY-MM-DD}`，按日期升序
+pub fn monitoring_daily_key(date: chrono::NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// llm_usage_daily: `{YYYY-MM-DD}`，按日期升序
+pub fn llm_usage_daily_key(date: chrono::NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
 pub fn monitoring_ts_key(timestamp_ms: i64, period_id: &str) -> Result<String, StoreError> {
     let ts = timestamp_ms.max(0) as u64;
     let reverse_ts = u64::MAX - ts;
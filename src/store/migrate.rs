@@ -1,3 +1,4 @@
+use crate::store::operations::counters::Counter;
 use crate::store::operations::records::{LearningRecord, UserStatsAgg};
 use crate::store::operations::users::User;
 use crate::store::operations::words::Word;
@@ -14,6 +15,9 @@ fn migrations() -> Vec<(&'static str, MigrationFn)> {
         ("002_word_due_index", m002_word_due_index),
         ("003_secondary_indexes", m003_secondary_indexes),
         ("004_wordbook_type_index", m004_wordbook_type_index),
+        ("005_counters", m005_counters),
+        ("006_word_tag_index", m006_word_tag_index),
+        ("007_word_text_index", m007_word_text_index),
     ]
 }
 
@@ -216,6 +220,36 @@ fn m004_wordbook_type_index(store: &Store) -> Result<(), StoreError> {
     Ok(())
 }
 
+/// 以一次全量扫描初始化计数器，之后随增删增量维护
+fn m005_counters(store: &Store) -> Result<(), StoreError> {
+    for counter in Counter::ALL {
+        store.rebuild_counter(counter)?;
+    }
+    Ok(())
+}
+
+/// Build word_tag_index for existing words.
+fn m006_word_tag_index(store: &Store) -> Result<(), StoreError> {
+    for item in store.words.iter() {
+        let (_, value) = item?;
+        if let Ok(word) = Store::deserialize::<Word>(&value) {
+            store.sync_word_tags(&word.id, &[], &word.tags)?;
+        }
+    }
+    Ok(())
+}
+
+/// Build word_text_index for existing words.
+fn m007_word_text_index(store: &Store) -> Result<(), StoreError> {
+    for item in store.words.iter() {
+        let (_, value) = item?;
+        if let Ok(word) = Store::deserialize::<Word>(&value) {
+            store.index_word_text(&word)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
@@ -233,8 +267,8 @@ mod tests {
         run(&store).unwrap();
         let second = get_current_version(&store).unwrap();
 
-        assert_eq!(first, 4);
-        assert_eq!(second, 4);
+        assert_eq!(first, 7);
+        assert_eq!(second, 7);
     }
 
     #[test]
@@ -1,19 +1,28 @@
+pub mod backup;
 pub mod keys;
 pub mod migrate;
 pub mod operations;
 pub mod trees;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sled::Db;
 use thiserror::Error;
 
+use crate::config::StoreConfig;
+use crate::constants::MAX_CAS_RETRIES;
+
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 1024;
+
 #[derive(Debug)]
 pub struct Store {
     db: Db,
     pub users: sled::Tree,
     pub sessions: sled::Tree,
     pub admin_sessions: sled::Tree,
+    pub refresh_tokens: sled::Tree,
     pub words: sled::Tree,
     pub records: sled::Tree,
     pub learning_sessions: sled::Tree,
@@ -22,6 +31,7 @@ pub struct Store {
     pub engine_monitoring_events: sled::Tree,
     pub algorithm_metrics_daily: sled::Tree,
     pub password_reset_tokens: sled::Tree,
+    pub email_change_tokens: sled::Tree,
     pub config_versions: sled::Tree,
     // P0 new trees
     pub admins: sled::Tree,
@@ -36,20 +46,40 @@ pub struct Store {
     pub notifications: sled::Tree,
     pub badges: sled::Tree,
     pub user_preferences: sled::Tree,
+    pub user_webhooks: sled::Tree,
+    pub user_weekly_reports: sled::Tree,
+    pub visual_fatigue_history: sled::Tree,
     pub etymologies: sled::Tree,
     pub word_morphemes: sled::Tree,
     pub confusion_pairs: sled::Tree,
+    pub word_clusters: sled::Tree,
+    pub generated_examples: sled::Tree,
+    pub moderation_rejections: sled::Tree,
     pub wb_center_imports: sled::Tree,
     pub wordbook_type_index: sled::Tree,
     // Secondary index trees
     pub users_by_created_at: sled::Tree,
     pub words_by_created_at: sled::Tree,
+    pub word_tag_index: sled::Tree,
+    pub word_text_index: sled::Tree,
     pub records_by_time: sled::Tree,
     pub word_references: sled::Tree,
     pub user_stats: sled::Tree,
     pub record_id_index: sled::Tree,
     pub alert_dedup: sled::Tree,
     pub monitoring_timeseries: sled::Tree,
+    pub algo_state_repairs: sled::Tree,
+    pub deferred_notifications: sled::Tree,
+    pub idempotency_keys: sled::Tree,
+    pub monitoring_daily: sled::Tree,
+    pub llm_usage_daily: sled::Tree,
+    pub llm_advisories: sled::Tree,
+    pub scheduled_broadcasts: sled::Tree,
+    pub broadcast_deliveries: sled::Tree,
+    pub counters: sled::Tree,
+    /// 新通知写入后的广播，供 SSE 推送按 user_id 过滤
+    notification_tx: tokio::sync::broadcast::Sender<operations::notifications::NotificationEvent>,
+    migrated: AtomicBool,
 }
 
 #[derive(Debug, Error)]
@@ -72,18 +102,25 @@ pub enum StoreError {
     Validation(String),
     #[error("migration error at version {version}: {message}")]
     Migration { version: u32, message: String },
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 impl Store {
     pub fn open(sled_path: &str) -> Result<Self, StoreError> {
+        Self::open_with_config(sled_path, &StoreConfig::default())
+    }
+
+    pub fn open_with_config(sled_path: &str, config: &StoreConfig) -> Result<Self, StoreError> {
         let db = sled::Config::new()
             .path(sled_path)
-            .cache_capacity(512_000_000)
-            .flush_every_ms(Some(1000))
+            .cache_capacity(config.cache_capacity_bytes)
+            .flush_every_ms((config.flush_every_ms > 0).then_some(config.flush_every_ms))
             .open()?;
         let users = db.open_tree(trees::USERS)?;
         let sessions = db.open_tree(trees::SESSIONS)?;
         let admin_sessions = db.open_tree(trees::ADMIN_SESSIONS)?;
+        let refresh_tokens = db.open_tree(trees::REFRESH_TOKENS)?;
         let words = db.open_tree(trees::WORDS)?;
         let records = db.open_tree(trees::RECORDS)?;
         let learning_sessions = db.open_tree(trees::LEARNING_SESSIONS)?;
@@ -92,6 +129,7 @@ impl Store {
         let engine_monitoring_events = db.open_tree(trees::ENGINE_MONITORING_EVENTS)?;
         let algorithm_metrics_daily = db.open_tree(trees::ALGORITHM_METRICS_DAILY)?;
         let password_reset_tokens = db.open_tree(trees::PASSWORD_RESET_TOKENS)?;
+        let email_change_tokens = db.open_tree(trees::EMAIL_CHANGE_TOKENS)?;
         let config_versions = db.open_tree(trees::CONFIG_VERSIONS)?;
         // P0 new trees
         let admins = db.open_tree(trees::ADMINS)?;
@@ -106,26 +144,44 @@ impl Store {
         let notifications = db.open_tree(trees::NOTIFICATIONS)?;
         let badges = db.open_tree(trees::BADGES)?;
         let user_preferences = db.open_tree(trees::USER_PREFERENCES)?;
+        let user_webhooks = db.open_tree(trees::USER_WEBHOOKS)?;
+        let user_weekly_reports = db.open_tree(trees::USER_WEEKLY_REPORTS)?;
+        let visual_fatigue_history = db.open_tree(trees::VISUAL_FATIGUE_HISTORY)?;
         let etymologies = db.open_tree(trees::ETYMOLOGIES)?;
         let word_morphemes = db.open_tree(trees::WORD_MORPHEMES)?;
         let confusion_pairs = db.open_tree(trees::CONFUSION_PAIRS)?;
+        let word_clusters = db.open_tree(trees::WORD_CLUSTERS)?;
+        let generated_examples = db.open_tree(trees::GENERATED_EXAMPLES)?;
+        let moderation_rejections = db.open_tree(trees::MODERATION_REJECTIONS)?;
         let wb_center_imports = db.open_tree(trees::WB_CENTER_IMPORTS)?;
         let wordbook_type_index = db.open_tree(trees::WORDBOOK_TYPE_INDEX)?;
         // Secondary index trees
         let users_by_created_at = db.open_tree(trees::USERS_BY_CREATED_AT)?;
         let words_by_created_at = db.open_tree(trees::WORDS_BY_CREATED_AT)?;
+        let word_tag_index = db.open_tree(trees::WORD_TAG_INDEX)?;
+        let word_text_index = db.open_tree(trees::WORD_TEXT_INDEX)?;
         let records_by_time = db.open_tree(trees::RECORDS_BY_TIME)?;
         let word_references = db.open_tree(trees::WORD_REFERENCES)?;
         let user_stats = db.open_tree(trees::USER_STATS)?;
         let record_id_index = db.open_tree(trees::RECORD_ID_INDEX)?;
         let alert_dedup = db.open_tree(trees::ALERT_DEDUP)?;
         let monitoring_timeseries = db.open_tree(trees::MONITORING_TIMESERIES)?;
+        let algo_state_repairs = db.open_tree(trees::ALGO_STATE_REPAIRS)?;
+        let deferred_notifications = db.open_tree(trees::DEFERRED_NOTIFICATIONS)?;
+        let idempotency_keys = db.open_tree(trees::IDEMPOTENCY_KEYS)?;
+        let monitoring_daily = db.open_tree(trees::MONITORING_DAILY)?;
+        let llm_usage_daily = db.open_tree(trees::LLM_USAGE_DAILY)?;
+        let llm_advisories = db.open_tree(trees::LLM_ADVISORIES)?;
+        let scheduled_broadcasts = db.open_tree(trees::SCHEDULED_BROADCASTS)?;
+        let broadcast_deliveries = db.open_tree(trees::BROADCAST_DELIVERIES)?;
+        let counters = db.open_tree(trees::COUNTERS)?;
 
         Ok(Self {
             db,
             users,
             sessions,
             admin_sessions,
+            refresh_tokens,
             words,
             records,
             learning_sessions,
@@ -134,6 +190,7 @@ impl Store {
             engine_monitoring_events,
             algorithm_metrics_daily,
             password_reset_tokens,
+            email_change_tokens,
             config_versions,
             admins,
             wordbooks,
@@ -146,24 +203,50 @@ impl Store {
             notifications,
             badges,
             user_preferences,
+            user_webhooks,
+            user_weekly_reports,
+            visual_fatigue_history,
             etymologies,
             word_morphemes,
             confusion_pairs,
+            word_clusters,
+            generated_examples,
+            moderation_rejections,
             wb_center_imports,
             wordbook_type_index,
             users_by_created_at,
             words_by_created_at,
+            word_tag_index,
+            word_text_index,
             records_by_time,
             word_references,
             user_stats,
             record_id_index,
             alert_dedup,
             monitoring_timeseries,
+            algo_state_repairs,
+            deferred_notifications,
+            idempotency_keys,
+            monitoring_daily,
+            llm_usage_daily,
+            llm_advisories,
+            scheduled_broadcasts,
+            broadcast_deliveries,
+            counters,
+            notification_tx: tokio::sync::broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0,
+            migrated: AtomicBool::new(false),
         })
     }
 
     pub fn run_migrations(&self) -> Result<(), StoreError> {
-        migrate::run(self)
+        migrate::run(self)?;
+        self.migrated.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// 本进程内迁移是否已执行完成，供就绪探针使用
+    pub fn migrations_applied(&self) -> bool {
+        self.migrated.load(Ordering::Acquire)
     }
 
     pub fn flush(&self) -> Result<(), StoreError> {
@@ -171,6 +254,10 @@ impl Store {
         Ok(())
     }
 
+    pub fn size_on_disk(&self) -> Result<u64, StoreError> {
+        Ok(self.db.size_on_disk()?)
+    }
+
     pub fn raw_db(&self) -> &Db {
         &self.db
     }
@@ -182,4 +269,59 @@ impl Store {
     pub(crate) fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, StoreError> {
         Ok(serde_json::from_slice(bytes)?)
     }
+
+    pub(crate) fn get_typed<T: DeserializeOwned>(
+        tree: &sled::Tree,
+        key: &str,
+    ) -> Result<Option<T>, StoreError> {
+        match tree.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn put_typed<T: Serialize>(
+        tree: &sled::Tree,
+        key: &str,
+        value: &T,
+    ) -> Result<(), StoreError> {
+        tree.insert(key.as_bytes(), Self::serialize(value)?)?;
+        Ok(())
+    }
+
+    /// 读取-修改-compare_and_swap 写回，被并发修改时以最新值重新调用 `apply`，
+    /// 至多重试 `MAX_CAS_RETRIES` 次。`apply` 返回 None 表示无需写入；
+    /// 记录不存在时返回以 `entity` 标注的 NotFound。
+    pub(crate) fn cas_update<T, R>(
+        tree: &sled::Tree,
+        entity: &str,
+        key: &str,
+        mut apply: impl FnMut(&mut T) -> Option<R>,
+    ) -> Result<Option<R>, StoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        for _ in 0..MAX_CAS_RETRIES {
+            let old_raw = tree
+                .get(key.as_bytes())?
+                .ok_or_else(|| StoreError::NotFound {
+                    entity: entity.to_string(),
+                    key: key.to_string(),
+                })?;
+            let mut value: T = Self::deserialize(&old_raw)?;
+            let Some(result) = apply(&mut value) else {
+                return Ok(None);
+            };
+            let new_raw = Self::serialize(&value)?;
+            match tree.compare_and_swap(key.as_bytes(), Some(old_raw), Some(new_raw))? {
+                Ok(()) => return Ok(Some(result)),
+                Err(_) => continue, // 数据已被其他操作修改，重试
+            }
+        }
+        Err(StoreError::CasRetryExhausted {
+            entity: entity.to_string(),
+            key: key.to_string(),
+            attempts: MAX_CAS_RETRIES,
+        })
+    }
 }
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{LOCKOUT_DURATION_MINUTES, MAX_CAS_RETRIES, MAX_FAILED_LOGIN_ATTEMPTS};
+use crate::constants::{LOCKOUT_DURATION_MINUTES, MAX_FAILED_LOGIN_ATTEMPTS};
 use crate::store::keys;
 use crate::store::{Store, StoreError};
 
@@ -18,6 +18,17 @@ pub struct Admin {
     pub failed_login_count: u32,
     #[serde(default)]
     pub locked_until: Option<DateTime<Utc>>,
+    /// 加密存储的 TOTP 密钥，登记后未验证前 `totp_enabled` 为 false
+    #[serde(default)]
+    pub totp_secret: Option<String>,
+    #[serde(default)]
+    pub totp_enabled: bool,
+    /// 备用码的 SHA-256 哈希，使用后移除
+    #[serde(default)]
+    pub totp_backup_codes: Vec<String>,
+    /// 最近一次成功使用的 TOTP 时间步，拒绝重放
+    #[serde(default)]
+    pub totp_last_step: Option<u64>,
 }
 
 impl Store {
@@ -86,11 +97,7 @@ impl Store {
     }
 
     pub fn get_admin_by_id(&self, admin_id: &str) -> Result<Option<Admin>, StoreError> {
-        let key = keys::admin_key(admin_id)?;
-        match self.admins.get(key.as_bytes())? {
-            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
-            None => Ok(None),
-        }
+        Self::get_typed(&self.admins, &keys::admin_key(admin_id)?)
     }
 
     pub fn get_admin_by_email(&self, email: &str) -> Result<Option<Admin>, StoreError> {
@@ -125,72 +132,28 @@ impl Store {
 
     /// 记录一次管理员登录失败，返回账户是否因此被锁定
     pub fn record_admin_failed_login(&self, admin_id: &str) -> Result<bool, StoreError> {
-        let admin_key = keys::admin_key(admin_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.admins
-                    .get(admin_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "admin".to_string(),
-                        key: admin_id.to_string(),
-                    })?;
-            let mut admin: Admin = Self::deserialize(&old_raw)?;
+        let locked = self.update_admin(admin_id, |admin| {
             admin.failed_login_count += 1;
             let locked = admin.failed_login_count >= MAX_FAILED_LOGIN_ATTEMPTS;
             if locked {
                 admin.locked_until = Some(Utc::now() + Duration::minutes(LOCKOUT_DURATION_MINUTES));
             }
-            admin.updated_at = Utc::now();
-            let new_raw = Self::serialize(&admin)?;
-            match self.admins.compare_and_swap(
-                admin_key.as_bytes(),
-                Some(old_raw),
-                Some(new_raw),
-            )? {
-                Ok(()) => return Ok(locked),
-                Err(_) => continue,
-            }
-        }
-        Err(StoreError::CasRetryExhausted {
-            entity: "admin".to_string(),
-            key: admin_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+            Some(locked)
+        })?;
+        Ok(locked.unwrap_or(false))
     }
 
     /// 重置管理员登录失败计数（登录成功时调用）
     pub fn reset_admin_login_attempts(&self, admin_id: &str) -> Result<(), StoreError> {
-        let admin_key = keys::admin_key(admin_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.admins
-                    .get(admin_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "admin".to_string(),
-                        key: admin_id.to_string(),
-                    })?;
-            let mut admin: Admin = Self::deserialize(&old_raw)?;
+        self.update_admin(admin_id, |admin| {
             if admin.failed_login_count == 0 && admin.locked_until.is_none() {
-                return Ok(());
+                return None;
             }
             admin.failed_login_count = 0;
             admin.locked_until = None;
-            admin.updated_at = Utc::now();
-            let new_raw = Self::serialize(&admin)?;
-            match self.admins.compare_and_swap(
-                admin_key.as_bytes(),
-                Some(old_raw),
-                Some(new_raw),
-            )? {
-                Ok(()) => return Ok(()),
-                Err(_) => continue,
-            }
-        }
-        Err(StoreError::CasRetryExhausted {
-            entity: "admin".to_string(),
-            key: admin_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+            Some(())
+        })?;
+        Ok(())
     }
 
     /// 检查管理员账户是否处于锁定状态
@@ -208,4 +171,112 @@ impl Store {
         }
         Ok(false)
     }
+
+    /// 以 CAS 方式修改管理员记录并刷新 updated_at；闭包返回 None 表示无需写入
+    fn update_admin<T>(
+        &self,
+        admin_id: &str,
+        mut apply: impl FnMut(&mut Admin) -> Option<T>,
+    ) -> Result<Option<T>, StoreError> {
+        let admin_key = keys::admin_key(admin_id)?;
+        Self::cas_update(&self.admins, "admin", &admin_key, |admin: &mut Admin| {
+            let result = apply(admin)?;
+            admin.updated_at = Utc::now();
+            Some(result)
+        })
+    }
+
+    /// 登记待验证的 TOTP 密钥；已启用时返回 false，不覆盖现有密钥
+    pub fn set_admin_pending_totp(
+        &self,
+        admin_id: &str,
+        encrypted_secret: &str,
+    ) -> Result<bool, StoreError> {
+        let updated = self.update_admin(admin_id, |admin| {
+            if admin.totp_enabled {
+                return None;
+            }
+            admin.totp_secret = Some(encrypted_secret.to_string());
+            admin.totp_backup_codes.clear();
+            admin.totp_last_step = None;
+            Some(())
+        })?;
+        Ok(updated.is_some())
+    }
+
+    /// 启用 TOTP 并写入备用码哈希，同时记录本次验证使用的时间步
+    pub fn enable_admin_totp(
+        &self,
+        admin_id: &str,
+        backup_code_hashes: Vec<String>,
+        step: u64,
+    ) -> Result<bool, StoreError> {
+        let updated = self.update_admin(admin_id, |admin| {
+            if admin.totp_enabled || admin.totp_secret.is_none() {
+                return None;
+            }
+            admin.totp_enabled = true;
+            admin.totp_backup_codes = backup_code_hashes.clone();
+            admin.totp_last_step = Some(step);
+            Some(())
+        })?;
+        Ok(updated.is_some())
+    }
+
+    /// 关闭两步验证并清除密钥、备用码与时间步记录
+    pub fn disable_admin_totp(&self, admin_id: &str) -> Result<(), StoreError> {
+        self.update_admin(admin_id, |admin| {
+            admin.totp_enabled = false;
+            admin.totp_secret = None;
+            admin.totp_backup_codes.clear();
+            admin.totp_last_step = None;
+            Some(())
+        })?;
+        Ok(())
+    }
+
+    /// 用新的备用码哈希替换全部旧备用码；未启用两步验证时返回 false
+    pub fn replace_admin_backup_codes(
+        &self,
+        admin_id: &str,
+        backup_code_hashes: Vec<String>,
+    ) -> Result<bool, StoreError> {
+        let updated = self.update_admin(admin_id, |admin| {
+            if !admin.totp_enabled {
+                return None;
+            }
+            admin.totp_backup_codes = backup_code_hashes.clone();
+            Some(())
+        })?;
+        Ok(updated.is_some())
+    }
+
+    /// 消费一个 TOTP 时间步，已使用过的（或更早的）时间步返回 false
+    pub fn consume_admin_totp_step(&self, admin_id: &str, step: u64) -> Result<bool, StoreError> {
+        let updated = self.update_admin(admin_id, |admin| {
+            if admin.totp_last_step.is_some_and(|last| step <= last) {
+                return None;
+            }
+            admin.totp_last_step = Some(step);
+            Some(())
+        })?;
+        Ok(updated.is_some())
+    }
+
+    /// 消费一个备用码（按哈希匹配），每个备用码只能使用一次
+    pub fn consume_admin_backup_code(
+        &self,
+        admin_id: &str,
+        code_hash: &str,
+    ) -> Result<bool, StoreError> {
+        let updated = self.update_admin(admin_id, |admin| {
+            let idx = admin
+                .totp_backup_codes
+                .iter()
+                .position(|h| h == code_hash)?;
+            admin.totp_backup_codes.remove(idx);
+            Some(())
+        })?;
+        Ok(updated.is_some())
+    }
 }
@@ -0,0 +1,474 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::store::keys;
+use crate::store::operations::notifications::{Notification, NotificationType};
+use crate::store::{Store, StoreError};
+
+/// 分批加载用户，避免一次性加载所有用户导致内存溢出
+const USER_BATCH_SIZE: usize = 100;
+
+/// 广播的接收人群
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BroadcastTarget {
+    /// 全部用户（含已封禁）
+    #[default]
+    All,
+    ExcludeBanned,
+    /// 最近 `days` 天内活跃的未封禁用户
+    ActiveWithin {
+        days: u32,
+    },
+    /// 已选择该词书学习的未封禁用户
+    WordbookLearners {
+        #[serde(rename = "wordbookId")]
+        wordbook_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BroadcastStatus {
+    Pending,
+    Sent,
+    Cancelled,
+}
+
+/// 定时广播，到期后由 scheduled_broadcasts worker 按当时的人群投递
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledBroadcast {
+    pub id: String,
+    pub title: String,
+    pub message: String,
+    pub target: BroadcastTarget,
+    pub scheduled_at: DateTime<Utc>,
+    pub status: BroadcastStatus,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// 创建时解析出的接收人数
+    pub recipients: usize,
+    #[serde(default)]
+    pub sent_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sent: usize,
+    #[serde(default)]
+    pub skipped_duplicates: usize,
+}
+
+/// 去重用的内容指纹（标题+内容）
+pub fn broadcast_content_hash(title: &str, message: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(title.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(message.as_bytes());
+    format!("broadcast_{}", hex::encode(hasher.finalize()))
+}
+
+fn broadcast_notification_id(broadcast_id: &str, user_id: &str) -> String {
+    format!("{broadcast_id}_{user_id}")
+}
+
+/// 广播的送达与已读统计；用户删除的通知无法判断是否已读，单独计数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastStats {
+    pub broadcast_id: String,
+    pub delivered: usize,
+    pub read: usize,
+    pub unread: usize,
+    pub dismissed: usize,
+    /// 已读数 / 送达数
+    pub read_rate: f64,
+    pub first_delivered_at: Option<DateTime<Utc>>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastOutcome {
+    pub sent: usize,
+    pub skipped_duplicates: usize,
+}
+
+impl Store {
+    /// 解析接收人群，返回用户 ID
+    pub fn resolve_broadcast_recipients(
+        &self,
+        target: &BroadcastTarget,
+    ) -> Result<Vec<String>, StoreError> {
+        let now = Utc::now();
+        let mut recipients = Vec::new();
+        let mut offset = 0;
+        loop {
+            let users = self.list_users(USER_BATCH_SIZE, offset)?;
+            if users.is_empty() {
+                break;
+            }
+            offset += users.len();
+            for user in users {
+                let included = match target {
+                    BroadcastTarget::All => true,
+                    _ if user.is_banned => false,
+                    BroadcastTarget::ExcludeBanned => true,
+                    BroadcastTarget::ActiveWithin { days } => {
+                        self.user_last_active_at(&user)? >= now - Duration::days(i64::from(*days))
+                    }
+                    BroadcastTarget::WordbookLearners { wordbook_id } => self
+                        .get_study_config(&user.id)?
+                        .selected_wordbook_ids
+                        .contains(wordbook_id),
+                };
+                if included {
+                    recipients.push(user.id);
+                }
+            }
+        }
+        Ok(recipients)
+    }
+
+    /// 向接收人投递广播通知；`dedup_window_ms` 内已收到过相同内容（`content_hash`）的用户跳过
+    pub fn send_broadcast(
+        &self,
+        broadcast_id: &str,
+        title: &str,
+        message: &str,
+        content_hash: &str,
+        recipients: &[String],
+        dedup_window_ms: i64,
+    ) -> Result<BroadcastOutcome, StoreError> {
+        let now = Utc::now();
+        let now_ms = now.timestamp_millis();
+        let mut outcome = BroadcastOutcome::default();
+        for chunk in recipients.chunks(USER_BATCH_SIZE) {
+            let mut user_ids = Vec::with_capacity(chunk.len());
+            for user_id in chunk {
+                if dedup_window_ms > 0 {
+                    let last_sent_ms = self.get_broadcast_sent_at(user_id, content_hash)?;
+                    if last_sent_ms.is_some_and(|ts| now_ms - ts < dedup_window_ms) {
+                        outcome.skipped_duplicates += 1;
+                        continue;
+                    }
+                }
+                user_ids.push(user_id.as_str());
+            }
+
+            let notifications: Vec<Notification> = user_ids
+                .iter()
+                .map(|user_id| Notification {
+                    id: broadcast_notification_id(broadcast_id, user_id),
+                    user_id: user_id.to_string(),
+                    notification_type: NotificationType::Broadcast,
+                    title: title.to_string(),
+                    message: message.to_string(),
+                    read: false,
+                    created_at: now,
+                    group_key: None,
+                    count: 1,
+                    items: Vec::new(),
+                    updated_at: None,
+                    deferred_until: None,
+                    broadcast_id: Some(broadcast_id.to_string()),
+                })
+                .collect();
+            self.batch_create_notifications(notifications)?;
+            self.record_broadcast_deliveries(broadcast_id, &user_ids, now)?;
+            self.mark_broadcast_sent(&user_ids, content_hash, now_ms)?;
+            outcome.sent += user_ids.len();
+        }
+        Ok(outcome)
+    }
+
+    fn record_broadcast_deliveries(
+        &self,
+        broadcast_id: &str,
+        user_ids: &[&str],
+        delivered_at: DateTime<Utc>,
+    ) -> Result<(), StoreError> {
+        let value = Self::serialize(&delivered_at)?;
+        let mut batch = sled::Batch::default();
+        for user_id in user_ids {
+            let key = keys::broadcast_delivery_key(broadcast_id, user_id)?;
+            batch.insert(key.as_bytes(), value.as_slice());
+        }
+        self.broadcast_deliveries.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// 删除用户的全部送达记录；key 以广播 ID 开头，需要全表扫描
+    pub fn delete_user_broadcast_deliveries(&self, user_id: &str) -> Result<(), StoreError> {
+        let suffix = format!(":{}", keys::validate_id(user_id)?);
+        for item in self.broadcast_deliveries.iter() {
+            let (key, _) = item?;
+            if key.ends_with(suffix.as_bytes()) {
+                self.broadcast_deliveries.remove(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按送达记录逐个查看对应通知的已读状态
+    pub fn broadcast_stats(&self, broadcast_id: &str) -> Result<BroadcastStats, StoreError> {
+        let prefix = keys::broadcast_delivery_prefix(broadcast_id)?;
+        let mut stats = BroadcastStats {
+            broadcast_id: broadcast_id.to_string(),
+            delivered: 0,
+            read: 0,
+            unread: 0,
+            dismissed: 0,
+            read_rate: 0.0,
+            first_delivered_at: None,
+            last_delivered_at: None,
+        };
+        for item in self.broadcast_deliveries.scan_prefix(prefix.as_bytes()) {
+            let (key, raw) = item?;
+            let delivered_at: DateTime<Utc> = Self::deserialize(&raw)?;
+            let user_id = String::from_utf8_lossy(&key[prefix.len()..]).into_owned();
+            stats.delivered += 1;
+            stats.first_delivered_at = Some(
+                stats
+                    .first_delivered_at
+                    .map_or(delivered_at, |at| at.min(delivered_at)),
+            );
+            stats.last_delivered_at = Some(
+                stats
+                    .last_delivered_at
+                    .map_or(delivered_at, |at| at.max(delivered_at)),
+            );
+
+            let notification_key = keys::notification_key(
+                &user_id,
+                &broadcast_notification_id(broadcast_id, &user_id),
+            )?;
+            match self.notifications.get(notification_key.as_bytes())? {
+                Some(raw) if Self::deserialize::<Notification>(&raw)?.read => stats.read += 1,
+                Some(_) => stats.unread += 1,
+                None => stats.dismissed += 1,
+            }
+        }
+        if stats.delivered > 0 {
+            stats.read_rate = stats.read as f64 / stats.delivered as f64;
+        }
+        Ok(stats)
+    }
+
+    pub fn save_scheduled_broadcast(
+        &self,
+        broadcast: &ScheduledBroadcast,
+    ) -> Result<(), StoreError> {
+        let key = keys::scheduled_broadcast_key(
+            broadcast.scheduled_at.timestamp_millis(),
+            &broadcast.id,
+        )?;
+        self.scheduled_broadcasts
+            .insert(key.as_bytes(), Self::serialize(broadcast)?)?;
+        Ok(())
+    }
+
+    /// 待发送的广播转为 `status`（投递认领或取消）；已不是待发送时返回 None，避免投递与取消互相覆盖
+    pub fn transition_scheduled_broadcast(
+        &self,
+        broadcast: &ScheduledBroadcast,
+        status: BroadcastStatus,
+    ) -> Result<Option<ScheduledBroadcast>, StoreError> {
+        let key = keys::scheduled_broadcast_key(
+            broadcast.scheduled_at.timestamp_millis(),
+            &broadcast.id,
+        )?;
+        Self::cas_update(
+            &self.scheduled_broadcasts,
+            "scheduled_broadcast",
+            &key,
+            |b: &mut ScheduledBroadcast| {
+                if b.status != BroadcastStatus::Pending {
+                    return None;
+                }
+                b.status = status;
+                if status == BroadcastStatus::Sent {
+                    b.sent_at = Some(Utc::now());
+                }
+                Some(b.clone())
+            },
+        )
+    }
+
+    /// 按计划时间倒序
+    pub fn list_scheduled_broadcasts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<ScheduledBroadcast>, StoreError> {
+        let mut items = Vec::new();
+        for item in self.scheduled_broadcasts.iter().rev().take(limit) {
+            let (_, raw) = item?;
+            items.push(Self::deserialize(&raw)?);
+        }
+        Ok(items)
+    }
+
+    pub fn get_scheduled_broadcast(
+        &self,
+        id: &str,
+    ) -> Result<Option<ScheduledBroadcast>, StoreError> {
+        let suffix = format!(":{}", keys::validate_id(id)?);
+        for item in self.scheduled_broadcasts.iter().rev() {
+            let (key, raw) = item?;
+            if key.ends_with(suffix.as_bytes()) {
+                return Ok(Some(Self::deserialize(&raw)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 计划时间不晚于 `now` 且仍待发送的广播，按计划时间升序
+    pub fn due_scheduled_broadcasts(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ScheduledBroadcast>, StoreError> {
+        let end = format!("{:020};", now.timestamp_millis().max(0));
+        let mut due = Vec::new();
+        for item in self.scheduled_broadcasts.range(..end.as_bytes()) {
+            let (_, raw) = item?;
+            let broadcast: ScheduledBroadcast = Self::deserialize(&raw)?;
+            if broadcast.status == BroadcastStatus::Pending {
+                due.push(broadcast);
+            }
+        }
+        Ok(due)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::operations::test_fixtures::sample_user;
+    use crate::store::operations::users::User;
+
+    #[test]
+    fn resolves_targets_and_finds_due_broadcasts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("broadcasts").to_str().unwrap()).unwrap();
+        let created_at = Utc::now() - Duration::days(30);
+        store.create_user(&sample_user("u1", created_at)).unwrap();
+        store
+            .create_user(&User {
+                is_banned: true,
+                ..sample_user("u2", created_at)
+            })
+            .unwrap();
+        let mut config = store.get_study_config("u1").unwrap();
+        config.selected_wordbook_ids = vec!["wb1".to_string()];
+        store.set_study_config(&config).unwrap();
+
+        let resolve = |target: BroadcastTarget| {
+            let mut ids = store.resolve_broadcast_recipients(&target).unwrap();
+            ids.sort();
+            ids
+        };
+        assert_eq!(resolve(BroadcastTarget::All), ["u1", "u2"]);
+        assert_eq!(resolve(BroadcastTarget::ExcludeBanned), ["u1"]);
+        assert!(resolve(BroadcastTarget::ActiveWithin { days: 7 }).is_empty());
+        assert_eq!(resolve(BroadcastTarget::ActiveWithin { days: 60 }), ["u1"]);
+        assert_eq!(
+            resolve(BroadcastTarget::WordbookLearners {
+                wordbook_id: "wb1".to_string()
+            }),
+            ["u1"]
+        );
+
+        let now = Utc::now();
+        let scheduled = |id: &str, at: DateTime<Utc>, status: BroadcastStatus| ScheduledBroadcast {
+            id: id.to_string(),
+            title: "t".to_string(),
+            message: "m".to_string(),
+            target: BroadcastTarget::All,
+            scheduled_at: at,
+            status,
+            created_by: "admin".to_string(),
+            created_at: now,
+            recipients: 2,
+            sent_at: None,
+            sent: 0,
+            skipped_duplicates: 0,
+        };
+        for broadcast in [
+            scheduled("due", now - Duration::minutes(1), BroadcastStatus::Pending),
+            scheduled("done", now - Duration::minutes(2), BroadcastStatus::Sent),
+            scheduled("later", now + Duration::hours(1), BroadcastStatus::Pending),
+        ] {
+            store.save_scheduled_broadcast(&broadcast).unwrap();
+        }
+        let due: Vec<String> = store
+            .due_scheduled_broadcasts(now)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.id)
+            .collect();
+        assert_eq!(due, ["due"]);
+        assert_eq!(store.list_scheduled_broadcasts(10).unwrap()[0].id, "later");
+        assert!(store.get_scheduled_broadcast("done").unwrap().is_some());
+    }
+
+    #[test]
+    fn stats_track_delivery_and_read_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("broadcast-stats").to_str().unwrap()).unwrap();
+        let recipients: Vec<String> = ["u1", "u2", "u3"].map(String::from).to_vec();
+        let outcome = store
+            .send_broadcast("b1", "t", "m", "hash", &recipients, 0)
+            .unwrap();
+        assert_eq!(outcome.sent, 3);
+
+        store.mark_notification_read("u1", "b1_u1").unwrap();
+        store.delete_notification("u3", "b1_u3").unwrap();
+        let (notifications, _) = store.list_notifications("u2", 10, 0, false).unwrap();
+        assert_eq!(notifications[0].broadcast_id.as_deref(), Some("b1"));
+
+        let stats = store.broadcast_stats("b1").unwrap();
+        assert_eq!(
+            (stats.delivered, stats.read, stats.unread, stats.dismissed),
+            (3, 1, 1, 1)
+        );
+        assert!((stats.read_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!(stats.first_delivered_at.is_some());
+        assert_eq!(store.broadcast_stats("missing").unwrap().delivered, 0);
+
+        // 已删除的用户不再计入送达统计
+        store.create_user(&sample_user("u3", Utc::now())).unwrap();
+        store.delete_user("u3").unwrap();
+        let stats = store.broadcast_stats("b1").unwrap();
+        assert_eq!((stats.delivered, stats.dismissed), (2, 0));
+    }
+
+    #[test]
+    fn broadcast_is_deferred_during_quiet_hours() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("broadcast-quiet").to_str().unwrap()).unwrap();
+        // 静默时段只排除前一小时，测试跨整点时仍处于静默时段内
+        let hour = chrono::Timelike::hour(&Utc::now());
+        let prefs = serde_json::json!({
+            "quietHours": {
+                "enabled": true,
+                "startHour": hour,
+                "endHour": (hour + 23) % 24,
+                "timezone": "UTC",
+            }
+        });
+        store
+            .user_preferences
+            .insert("u2", serde_json::to_vec(&prefs).unwrap())
+            .unwrap();
+
+        let mut events = store.subscribe_notifications();
+        let recipients: Vec<String> = ["u1", "u2"].map(String::from).to_vec();
+        store
+            .send_broadcast("b1", "t", "m", "hash", &recipients, 0)
+            .unwrap();
+
+        assert_eq!(events.try_recv().unwrap().user_id, "u1");
+        assert!(events.try_recv().is_err());
+        assert_eq!(store.list_notifications("u1", 10, 0, false).unwrap().1, 1);
+        assert_eq!(store.list_notifications("u2", 10, 0, false).unwrap().1, 0);
+        assert_eq!(store.broadcast_stats("b1").unwrap().delivered, 2);
+    }
+}
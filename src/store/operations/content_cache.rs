@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 按单词缓存的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Etymology,
+    Examples,
+    Morphemes,
+}
+
+/// 各类型实际删除的缓存条目数
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentInvalidation {
+    pub etymology: u64,
+    pub examples: u64,
+    pub morphemes: u64,
+}
+
+impl ContentInvalidation {
+    fn count(&mut self, kind: ContentKind, n: u64) {
+        match kind {
+            ContentKind::Etymology => self.etymology += n,
+            ContentKind::Examples => self.examples += n,
+            ContentKind::Morphemes => self.morphemes += n,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.etymology + self.examples + self.morphemes
+    }
+}
+
+/// 词源缓存中只有生成的条目可以失效，人工录入的词源保留
+fn is_generated_etymology(raw: &[u8]) -> bool {
+    let Ok(entry) = serde_json::from_slice::<serde_json::Value>(raw) else {
+        return false;
+    };
+    matches!(
+        entry.get("source").and_then(|v| v.as_str()),
+        Some("llm" | "rule_based_fallback")
+    ) || entry.get("generated").and_then(|v| v.as_bool()) == Some(true)
+}
+
+impl Store {
+    fn content_tree(&self, kind: ContentKind) -> &sled::Tree {
+        match kind {
+            ContentKind::Etymology => &self.etymologies,
+            ContentKind::Examples => &self.generated_examples,
+            ContentKind::Morphemes => &self.word_morphemes,
+        }
+    }
+
+    /// 删除一条缓存，内容已被并发改写或为人工录入的词源时跳过
+    fn remove_content_entry(
+        &self,
+        kind: ContentKind,
+        key: &[u8],
+        raw: sled::IVec,
+    ) -> Result<bool, StoreError> {
+        if kind == ContentKind::Etymology && !is_generated_etymology(&raw) {
+            return Ok(false);
+        }
+        Ok(self
+            .content_tree(kind)
+            .compare_and_swap(key, Some(raw), None as Option<&[u8]>)?
+            .is_ok())
+    }
+
+    /// 删除指定单词的内容缓存，下次访问或 worker 运行时重新生成
+    pub fn invalidate_word_content(
+        &self,
+        word_ids: &[String],
+        kinds: &[ContentKind],
+    ) -> Result<ContentInvalidation, StoreError> {
+        let mut result = ContentInvalidation::default();
+        for word_id in word_ids {
+            for &kind in kinds {
+                let key = match kind {
+                    ContentKind::Etymology => keys::etymology_key(word_id)?,
+                    ContentKind::Examples => keys::generated_examples_key(word_id)?,
+                    ContentKind::Morphemes => keys::word_morpheme_key(word_id)?,
+                };
+                let Some(raw) = self.content_tree(kind).get(key.as_bytes())? else {
+                    continue;
+                };
+                if self.remove_content_entry(kind, key.as_bytes(), raw)? {
+                    result.count(kind, 1);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// 清空指定类型的全部内容缓存（人工录入的词源除外），返回实际删除数
+    pub fn invalidate_all_content(
+        &self,
+        kinds: &[ContentKind],
+    ) -> Result<ContentInvalidation, StoreError> {
+        let mut result = ContentInvalidation::default();
+        for &kind in kinds {
+            for item in self.content_tree(kind).iter() {
+                let (key, raw) = item?;
+                if self.remove_content_entry(kind, &key, raw)? {
+                    result.count(kind, 1);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::operations::generated_examples::GeneratedExamples;
+
+    #[test]
+    fn invalidation_counts_only_existing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("content-cache").to_str().unwrap()).unwrap();
+        for id in ["w1", "w2"] {
+            store
+                .etymologies
+                .insert(id, br#"{"source":"llm"}"#.to_vec())
+                .unwrap();
+        }
+        store
+            .etymologies
+            .insert(
+                "w4",
+                br#"{"etymology":"curated","generated":false}"#.to_vec(),
+            )
+            .unwrap();
+        store
+            .put_generated_examples(&GeneratedExamples {
+                word_id: "w1".to_string(),
+                sentences: vec!["A sentence.".to_string()],
+                model: "mock".to_string(),
+                prompt_version: 1,
+                generated_at: chrono::Utc::now(),
+            })
+            .unwrap();
+
+        let ids = vec!["w1".to_string(), "w3".to_string()];
+        let kinds = [ContentKind::Etymology, ContentKind::Examples];
+        let result = store.invalidate_word_content(&ids, &kinds).unwrap();
+        assert_eq!((result.etymology, result.examples), (1, 1));
+        assert!(store.get_generated_examples("w1").unwrap().is_none());
+
+        let result = store.invalidate_all_content(&kinds).unwrap();
+        assert_eq!(result.total(), 1);
+        assert_eq!(store.etymologies.len(), 1);
+        assert!(store.etymologies.contains_key("w4").unwrap());
+
+        let ids = vec!["w4".to_string()];
+        let result = store.invalidate_word_content(&ids, &kinds).unwrap();
+        assert_eq!(result.total(), 0);
+    }
+}
@@ -0,0 +1,151 @@
+//! users/words/records 的条目计数，供统计接口 O(1) 读取。
+//! 计数随增删在同一事务中更新；缺失、为负或与树是否为空相矛盾时回退全量扫描并重写。
+
+use sled::transaction::{TransactionalTree, UnabortableTransactionError};
+
+use crate::store::{Store, StoreError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    Users,
+    Words,
+    Records,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 3] = [Counter::Users, Counter::Words, Counter::Records];
+
+    fn key(self) -> &'static [u8] {
+        match self {
+            Counter::Users => b"users",
+            Counter::Words => b"words",
+            Counter::Records => b"records",
+        }
+    }
+}
+
+fn decode(raw: &[u8]) -> Option<i64> {
+    raw.try_into().ok().map(i64::from_be_bytes)
+}
+
+/// 在事务内调整计数。计数尚未初始化时保持缺失，留给读取时的全量扫描补建。
+pub(crate) fn adjust_in_tx(
+    tx: &TransactionalTree,
+    counter: Counter,
+    delta: i64,
+) -> Result<(), UnabortableTransactionError> {
+    if delta == 0 {
+        return Ok(());
+    }
+    if let Some(current) = tx.get(counter.key())?.as_deref().and_then(decode) {
+        tx.insert(counter.key(), &(current + delta).to_be_bytes())?;
+    }
+    Ok(())
+}
+
+impl Store {
+    /// 非事务写入路径上的计数调整，语义同 `adjust_in_tx`
+    pub(crate) fn adjust_counter(&self, counter: Counter, delta: i64) -> Result<(), StoreError> {
+        if delta == 0 {
+            return Ok(());
+        }
+        self.counters.update_and_fetch(counter.key(), |old| {
+            old.and_then(decode)
+                .map(|current| (current + delta).to_be_bytes().to_vec())
+        })?;
+        Ok(())
+    }
+
+    pub fn read_counter(&self, counter: Counter) -> Result<u64, StoreError> {
+        if let Some(value) = self
+            .counters
+            .get(counter.key())?
+            .as_deref()
+            .and_then(decode)
+        {
+            if value >= 0 && (value == 0) == self.counted_tree(counter).is_empty() {
+                return Ok(value as u64);
+            }
+            tracing::warn!(
+                ?counter,
+                value,
+                "Counter inconsistent, rebuilding from scan"
+            );
+        }
+        self.rebuild_counter(counter)
+    }
+
+    /// 全量扫描并重写计数。扫描期间的并发写入可能带来少量偏差。
+    pub fn rebuild_counter(&self, counter: Counter) -> Result<u64, StoreError> {
+        let mut count = 0u64;
+        for item in self.counted_tree(counter).iter() {
+            let (key, _) = item?;
+            // users 树同时存放 email 索引
+            if counter == Counter::Users && key.starts_with(b"email:") {
+                continue;
+            }
+            count += 1;
+        }
+        self.counters
+            .insert(counter.key(), &(count as i64).to_be_bytes())?;
+        Ok(count)
+    }
+
+    fn counted_tree(&self, counter: Counter) -> &sled::Tree {
+        match counter {
+            Counter::Users => &self.users,
+            Counter::Words => &self.words,
+            Counter::Records => &self.records,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::store::operations::test_fixtures::{sample_record, sample_user, sample_word};
+
+    #[test]
+    fn counters_track_writes_and_self_heal() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("counters-db").to_str().unwrap()).unwrap();
+        store.run_migrations().unwrap();
+
+        store.create_user(&sample_user("u1", Utc::now())).unwrap();
+        store.create_user(&sample_user("u2", Utc::now())).unwrap();
+        store.upsert_word(&sample_word("w1", Utc::now())).unwrap();
+        store.upsert_word(&sample_word("w1", Utc::now())).unwrap();
+        store.upsert_word(&sample_word("w2", Utc::now())).unwrap();
+        store
+            .create_record(&sample_record("r1", "u1", "w1", Utc::now()))
+            .unwrap();
+        store
+            .create_record_with_updates(&sample_record("r2", "u2", "w1", Utc::now()), None, None)
+            .unwrap();
+        store
+            .create_record(&sample_record("r3", "u2", "w2", Utc::now()))
+            .unwrap();
+        assert_eq!(store.count_users().unwrap(), 2);
+        assert_eq!(store.count_words().unwrap(), 2);
+        assert_eq!(store.count_all_records().unwrap(), 3);
+
+        store.delete_word("w1").unwrap();
+        store.delete_user("u2").unwrap();
+        assert_eq!(store.count_users().unwrap(), 1);
+        assert_eq!(store.count_words().unwrap(), 1);
+        assert_eq!(store.count_all_records().unwrap(), 0);
+
+        store
+            .counters
+            .insert(Counter::Users.key(), &(-3i64).to_be_bytes())
+            .unwrap();
+        store.counters.remove(Counter::Words.key()).unwrap();
+        assert_eq!(store.count_users().unwrap(), 1);
+        assert_eq!(store.count_words().unwrap(), 1);
+        store.upsert_word(&sample_word("w3", Utc::now())).unwrap();
+        assert_eq!(store.count_words().unwrap(), 2);
+    }
+}
@@ -0,0 +1,260 @@
+//! 用户数据导出：覆盖 `delete_user` 清理的各棵树，按节依次写出一个 JSON 对象。
+//! 存储中的值本身就是 JSON，逐条原样写出，内存占用只与单条记录大小相关。
+//! 登录会话、幂等键等安全凭据不导出；webhook 只导出地址，不含签名密钥。
+
+use std::io::Write;
+
+use chrono::Utc;
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+struct ExportWriter<'a, W: Write> {
+    out: &'a mut W,
+    first_field: bool,
+}
+
+impl<'a, W: Write> ExportWriter<'a, W> {
+    fn new(out: &'a mut W) -> Result<Self, StoreError> {
+        out.write_all(b"{")?;
+        Ok(Self {
+            out,
+            first_field: true,
+        })
+    }
+
+    fn field_name(&mut self, name: &str) -> Result<(), StoreError> {
+        if !self.first_field {
+            self.out.write_all(b",")?;
+        }
+        self.first_field = false;
+        serde_json::to_writer(&mut *self.out, name)?;
+        self.out.write_all(b":")?;
+        Ok(())
+    }
+
+    fn value<T: serde::Serialize>(&mut self, name: &str, value: &T) -> Result<(), StoreError> {
+        self.field_name(name)?;
+        serde_json::to_writer(&mut *self.out, value)?;
+        Ok(())
+    }
+
+    /// 单条存储值，缺失时写 null
+    fn raw(&mut self, name: &str, raw: Option<sled::IVec>) -> Result<(), StoreError> {
+        self.field_name(name)?;
+        self.out.write_all(raw.as_deref().unwrap_or(b"null"))?;
+        Ok(())
+    }
+
+    /// 前缀下的全部值写成数组
+    fn scan(&mut self, name: &str, tree: &sled::Tree, prefix: &str) -> Result<(), StoreError> {
+        self.field_name(name)?;
+        self.out.write_all(b"[")?;
+        for (i, item) in tree.scan_prefix(prefix.as_bytes()).enumerate() {
+            let (_, value) = item?;
+            if i > 0 {
+                self.out.write_all(b",")?;
+            }
+            self.out.write_all(&value)?;
+        }
+        self.out.write_all(b"]")?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), StoreError> {
+        self.out.write_all(b"}")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl Store {
+    /// 将用户的全部数据写为一个 JSON 对象；用户不存在时返回 NotFound 且不写出任何内容
+    pub fn write_user_export(&self, user_id: &str, out: &mut impl Write) -> Result<(), StoreError> {
+        let user = self
+            .get_user_by_id(user_id)?
+            .ok_or_else(|| StoreError::NotFound {
+                entity: "user".to_string(),
+                key: user_id.to_string(),
+            })?;
+        let mut user = serde_json::to_value(&user)?;
+        if let Some(fields) = user.as_object_mut() {
+            fields.remove("passwordHash");
+        }
+
+        let mut w = ExportWriter::new(out)?;
+        w.value("exportedAt", &Utc::now())?;
+        w.value("user", &user)?;
+        w.raw(
+            "stats",
+            self.user_stats
+                .get(keys::user_stats_key(user_id)?.as_bytes())?,
+        )?;
+        w.raw(
+            "studyConfig",
+            self.study_configs
+                .get(keys::study_config_key(user_id)?.as_bytes())?,
+        )?;
+        w.raw(
+            "profile",
+            self.user_profiles
+                .get(keys::user_profile_key(user_id)?.as_bytes())?,
+        )?;
+        w.raw(
+            "avatar",
+            self.user_profiles
+                .get(keys::user_avatar_key(user_id)?.as_bytes())?,
+        )?;
+        w.raw(
+            "habitProfile",
+            self.habit_profiles
+                .get(keys::habit_profile_key(user_id)?.as_bytes())?,
+        )?;
+        w.raw(
+            "preferences",
+            self.user_preferences
+                .get(keys::user_preferences_key(user_id)?.as_bytes())?,
+        )?;
+        let webhook = self.get_user_webhook(user_id)?.map(|hook| {
+            serde_json::json!({
+                "url": hook.url,
+                "createdAt": hook.created_at,
+                "updatedAt": hook.updated_at,
+            })
+        });
+        w.value("webhook", &webhook)?;
+
+        w.scan("records", &self.records, &keys::record_prefix(user_id)?)?;
+        w.scan(
+            "wordLearningStates",
+            &self.word_learning_states,
+            &keys::word_learning_state_prefix(user_id)?,
+        )?;
+        w.value("wordbooks", &self.export_user_wordbooks(user_id)?)?;
+        w.value(
+            "learningSessions",
+            &self.export_user_learning_sessions(user_id)?,
+        )?;
+        w.scan(
+            "notifications",
+            &self.notifications,
+            &keys::notification_prefix(user_id)?,
+        )?;
+        w.scan("badges", &self.badges, &keys::badge_prefix(user_id)?)?;
+        w.scan(
+            "weeklyReports",
+            &self.user_weekly_reports,
+            &keys::weekly_report_prefix(user_id)?,
+        )?;
+        w.scan(
+            "visualFatigue",
+            &self.visual_fatigue_history,
+            &keys::visual_fatigue_prefix(user_id)?,
+        )?;
+
+        w.raw(
+            "amasUserState",
+            self.engine_user_states
+                .get(keys::engine_user_state_key(user_id)?.as_bytes())?,
+        )?;
+        w.value(
+            "amasAlgorithmStates",
+            &self
+                .list_engine_algo_states(user_id)?
+                .into_iter()
+                .collect::<serde_json::Map<_, _>>(),
+        )?;
+        w.raw(
+            "elo",
+            self.engine_algorithm_states
+                .get(keys::user_elo_key(user_id)?.as_bytes())?,
+        )?;
+        w.finish()
+    }
+
+    /// 用户自建词书及其单词 ID；词书数量有限，整体构建
+    fn export_user_wordbooks(&self, user_id: &str) -> Result<Vec<serde_json::Value>, StoreError> {
+        let mut books = Vec::new();
+        for book in self.list_user_wordbooks(user_id)? {
+            let prefix = keys::wordbook_words_prefix(&book.id)?;
+            let mut word_ids = Vec::new();
+            for item in self.wordbook_words.scan_prefix(prefix.as_bytes()) {
+                let (_, raw) = item?;
+                let entry: super::wordbooks::WordbookWordEntry = Self::deserialize(&raw)?;
+                word_ids.push(entry.word_id);
+            }
+            let mut value = serde_json::to_value(&book)?;
+            value["wordIds"] = serde_json::json!(word_ids);
+            books.push(value);
+        }
+        Ok(books)
+    }
+
+    fn export_user_learning_sessions(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<serde_json::Value>, StoreError> {
+        let prefix = keys::learning_session_user_index_prefix(user_id)?;
+        let mut sessions = Vec::new();
+        for item in self.learning_sessions.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = item?;
+            let Some(session_id) = std::str::from_utf8(&key[prefix.len()..]).ok() else {
+                continue;
+            };
+            let session_key = keys::learning_session_key(session_id)?;
+            if let Some(raw) = self.learning_sessions.get(session_key.as_bytes())? {
+                sessions.push(Self::deserialize(&raw)?);
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::store::operations::test_fixtures::{sample_record, sample_user};
+    use crate::store::operations::users::User;
+
+    #[test]
+    fn export_includes_user_data_without_secrets() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("export-db").to_str().unwrap()).unwrap();
+        let now = Utc::now();
+        for id in ["u1", "u2"] {
+            store
+                .create_user(&User {
+                    password_hash: "secret-hash".to_string(),
+                    ..sample_user(id, now)
+                })
+                .unwrap();
+        }
+        for (id, user_id) in [("r1", "u1"), ("r2", "u1"), ("r3", "u2")] {
+            store
+                .create_record(&sample_record(id, user_id, "w1", now))
+                .unwrap();
+        }
+        store
+            .set_engine_algo_state("u1", "mastery:w1", &serde_json::json!({"p": 0.4}))
+            .unwrap();
+
+        let mut buf = Vec::new();
+        store.write_user_export("u1", &mut buf).unwrap();
+        let export: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(export["user"]["email"], "u1@example.com");
+        assert!(export["user"].get("passwordHash").is_none());
+        assert_eq!(export["records"].as_array().unwrap().len(), 2);
+        assert_eq!(export["amasAlgorithmStates"]["mastery:w1"]["p"], 0.4);
+        assert!(export["studyConfig"].is_null());
+        assert!(!String::from_utf8(buf).unwrap().contains("secret-hash"));
+
+        let mut buf = Vec::new();
+        assert!(matches!(
+            store.write_user_export("missing", &mut buf),
+            Err(StoreError::NotFound { .. })
+        ));
+        assert!(buf.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::amas::elo::EloRating;
 use crate::amas::memory::mdm::MdmState;
+use crate::amas::placement::PlacementResult;
 use crate::store::keys;
 use crate::store::{Store, StoreError};
 use std::collections::HashMap;
@@ -77,6 +78,34 @@ impl Store {
         Ok(())
     }
 
+    pub fn get_placement_result(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<PlacementResult>, StoreError> {
+        let key = keys::placement_key(user_id)?;
+        match self.engine_algorithm_states.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 记录定级测试结果，已存在时不覆盖并返回 false
+    pub fn record_placement_result(
+        &self,
+        user_id: &str,
+        result: &PlacementResult,
+    ) -> Result<bool, StoreError> {
+        let key = keys::placement_key(user_id)?;
+        Ok(self
+            .engine_algorithm_states
+            .compare_and_swap(
+                key.as_bytes(),
+                None as Option<&[u8]>,
+                Some(Self::serialize(result)?),
+            )?
+            .is_ok())
+    }
+
     /// 批量读取 mastery 状态
     #[deprecated(note = "Use batch_get_engine_mastery_mdm_states for typed mastery states")]
     pub fn batch_get_engine_mastery_states(
@@ -126,6 +155,8 @@ mod tests {
     use tempfile::tempdir;
 
     use crate::amas::elo::EloRating;
+    use crate::amas::placement::PlacementResult;
+    use crate::amas::types::CognitiveProfile;
     use crate::store::Store;
 
     #[test]
@@ -153,6 +184,31 @@ mod tests {
         assert_eq!(got.games, 10);
     }
 
+    #[test]
+    fn placement_result_is_recorded_once() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("db").to_str().unwrap()).unwrap();
+
+        let result = PlacementResult {
+            elo: 1300.0,
+            answered: 8,
+            correct: 6,
+            cognitive_profile: CognitiveProfile::default(),
+            completed_at: chrono::Utc::now(),
+        };
+        assert!(store.get_placement_result("u1").unwrap().is_none());
+        assert!(store.record_placement_result("u1", &result).unwrap());
+        let again = PlacementResult {
+            elo: 900.0,
+            ..result
+        };
+        assert!(!store.record_placement_result("u1", &again).unwrap());
+        assert_eq!(
+            store.get_placement_result("u1").unwrap().unwrap().elo,
+            1300.0
+        );
+    }
+
     #[test]
     fn word_elo_round_trip() {
         let dir = tempdir().unwrap();
@@ -1,8 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sled::Transactional;
 
+use crate::amas::config::FeatureFlagOverrides;
 use crate::store::keys;
 use crate::store::{Store, StoreError};
 
+/// 算法状态损坏后被重置为默认值的记录，保留原始数据以便排查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoStateRepair {
+    pub id: String,
+    pub user_id: String,
+    pub algo_id: String,
+    pub error: String,
+    pub raw_state: serde_json::Value,
+    pub detected_at: DateTime<Utc>,
+}
+
 impl Store {
     pub fn get_engine_user_state(
         &self,
@@ -62,6 +77,48 @@ impl Store {
         Ok(())
     }
 
+    /// 列出用户全部算法状态，返回 (algo_id, state)
+    pub fn list_engine_algo_states(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<(String, serde_json::Value)>, StoreError> {
+        let prefix = keys::engine_algo_state_prefix(user_id)?;
+        let mut states = Vec::new();
+        for item in self.engine_algorithm_states.scan_prefix(prefix.as_bytes()) {
+            let (k, v) = item?;
+            let algo_id = String::from_utf8_lossy(&k[prefix.len()..]).to_string();
+            states.push((algo_id, Self::deserialize(&v)?));
+        }
+        Ok(states)
+    }
+
+    pub fn get_feature_flag_overrides(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<FeatureFlagOverrides>, StoreError> {
+        let key = keys::feature_flag_overrides_key(user_id)?;
+        match self.engine_algorithm_states.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 保存用户的算法开关覆盖，全部为空时删除
+    pub fn set_feature_flag_overrides(
+        &self,
+        user_id: &str,
+        overrides: &FeatureFlagOverrides,
+    ) -> Result<(), StoreError> {
+        let key = keys::feature_flag_overrides_key(user_id)?;
+        if overrides.is_empty() {
+            self.engine_algorithm_states.remove(key.as_bytes())?;
+        } else {
+            self.engine_algorithm_states
+                .insert(key.as_bytes(), Self::serialize(overrides)?)?;
+        }
+        Ok(())
+    }
+
     pub fn insert_monitoring_event(&self, event: &serde_json::Value) -> Result<(), StoreError> {
         let id = match event.get("id").and_then(|v| v.as_str()) {
             Some(id) => id.to_string(),
@@ -132,6 +189,132 @@ impl Store {
         }
     }
 
+    /// 累加用户当日新掌握单词数（写入 daily rollup），并发累加不丢失
+    pub fn increment_user_daily_mastered(
+        &self,
+        user_id: &str,
+        date: &str,
+        delta: u64,
+    ) -> Result<(), StoreError> {
+        let metric_id = keys::user_mastery_rollup_id(user_id)?;
+        let key = keys::metrics_daily_key(date, &metric_id)?;
+        self.algorithm_metrics_daily
+            .update_and_fetch(key.as_bytes(), |old| {
+                let current = old
+                    .and_then(|raw| serde_json::from_slice::<serde_json::Value>(raw).ok())
+                    .and_then(|v| v.get("mastered").and_then(|m| m.as_u64()))
+                    .unwrap_or(0);
+                serde_json::to_vec(&serde_json::json!({ "mastered": current + delta })).ok()
+            })?;
+        Ok(())
+    }
+
+    /// 删除用户所有日期的掌握数 rollup
+    pub fn delete_user_daily_mastered(&self, user_id: &str) -> Result<(), StoreError> {
+        let suffix = format!(":{}", keys::user_mastery_rollup_id(user_id)?);
+        for item in self.algorithm_metrics_daily.iter() {
+            let (key, _) = item?;
+            if key.ends_with(suffix.as_bytes()) {
+                self.algorithm_metrics_daily.remove(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_user_daily_mastered(&self, user_id: &str, date: &str) -> Result<u64, StoreError> {
+        let metric_id = keys::user_mastery_rollup_id(user_id)?;
+        Ok(self
+            .get_metrics_daily(date, &metric_id)?
+            .and_then(|v| v.get("mastered").and_then(|m| m.as_u64()))
+            .unwrap_or(0))
+    }
+
+    /// 以给定状态整体替换用户引擎状态（清除未包含的旧算法状态）
+    pub fn replace_engine_state_atomic(
+        &self,
+        user_id: &str,
+        user_state: &serde_json::Value,
+        algo_states: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        let user_key = keys::engine_user_state_key(user_id)?;
+        let user_bytes = Self::serialize(user_state)?;
+
+        let algo_entries: Vec<(String, Vec<u8>)> = algo_states
+            .iter()
+            .map(|(algo_id, value)| {
+                let key = keys::engine_algo_state_key(user_id, algo_id)?;
+                let bytes = Self::serialize(value)?;
+                Ok((key, bytes))
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+
+        let prefix = keys::engine_algo_state_prefix(user_id)?;
+        let stale_keys: Vec<sled::IVec> = self
+            .engine_algorithm_states
+            .scan_prefix(prefix.as_bytes())
+            .keys()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        (&self.engine_user_states, &self.engine_algorithm_states)
+            .transaction(|(tx_user, tx_algo)| {
+                tx_user.insert(user_key.as_bytes(), user_bytes.as_slice())?;
+                for key in &stale_keys {
+                    tx_algo.remove(key)?;
+                }
+                for (key, bytes) in &algo_entries {
+                    tx_algo.insert(key.as_bytes(), bytes.as_slice())?;
+                }
+                Ok(())
+            })
+            .map_err(|e: sled::transaction::TransactionError<()>| match e {
+                sled::transaction::TransactionError::Abort(()) => {
+                    StoreError::Sled(sled::Error::Unsupported("transaction aborted".into()))
+                }
+                sled::transaction::TransactionError::Storage(se) => StoreError::Sled(se),
+            })?;
+
+        Ok(())
+    }
+
+    pub fn record_algo_state_repair(&self, repair: &AlgoStateRepair) -> Result<(), StoreError> {
+        let key = keys::algo_state_repair_key(repair.detected_at.timestamp_millis(), &repair.id)?;
+        self.algo_state_repairs
+            .insert(key.as_bytes(), Self::serialize(repair)?)?;
+        Ok(())
+    }
+
+    /// 删除用户的修复日志；日志按时间排序，需要全表扫描
+    pub fn delete_user_algo_state_repairs(&self, user_id: &str) -> Result<(), StoreError> {
+        for item in self.algo_state_repairs.iter() {
+            let (key, raw) = item?;
+            if Self::deserialize::<AlgoStateRepair>(&raw).is_ok_and(|r| r.user_id == user_id) {
+                self.algo_state_repairs.remove(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按时间倒序列出修复日志，可按用户过滤
+    pub fn list_algo_state_repairs(
+        &self,
+        user_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AlgoStateRepair>, StoreError> {
+        let mut repairs = Vec::new();
+        for item in self.algo_state_repairs.iter() {
+            let (_, raw) = item?;
+            let repair: AlgoStateRepair = Self::deserialize(&raw)?;
+            if user_id.is_some_and(|id| id != repair.user_id) {
+                continue;
+            }
+            repairs.push(repair);
+            if repairs.len() >= limit {
+                break;
+            }
+        }
+        Ok(repairs)
+    }
+
     pub fn persist_engine_state_atomic(
         &self,
         user_id: &str,
@@ -186,4 +369,46 @@ mod tests {
         let got = store.get_engine_user_state("u1").unwrap().unwrap();
         assert_eq!(got["attention"], 0.7);
     }
+
+    #[test]
+    fn daily_mastered_counts_concurrent_increments_and_is_deleted() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("mastered-db").to_str().unwrap()).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..25 {
+                        store
+                            .increment_user_daily_mastered("u1", "2024-01-01", 1)
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        store
+            .increment_user_daily_mastered("u1", "2024-01-02", 2)
+            .unwrap();
+        store
+            .increment_user_daily_mastered("u2", "2024-01-01", 3)
+            .unwrap();
+        assert_eq!(
+            store.get_user_daily_mastered("u1", "2024-01-01").unwrap(),
+            100
+        );
+
+        store.delete_user_daily_mastered("u1").unwrap();
+        assert_eq!(
+            store.get_user_daily_mastered("u1", "2024-01-01").unwrap(),
+            0
+        );
+        assert_eq!(
+            store.get_user_daily_mastered("u1", "2024-01-02").unwrap(),
+            0
+        );
+        assert_eq!(
+            store.get_user_daily_mastered("u2", "2024-01-01").unwrap(),
+            3
+        );
+    }
 }
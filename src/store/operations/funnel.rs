@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 视为养成习惯的连续学习天数
+pub const FUNNEL_STREAK_DAYS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FunnelStep {
+    Registered,
+    /// 提交过至少一条学习记录
+    FirstWord,
+    FirstSession,
+    /// 曾连续 `FUNNEL_STREAK_DAYS` 天有学习记录
+    Streak,
+}
+
+impl FunnelStep {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Registered => "registered",
+            Self::FirstWord => "firstWord",
+            Self::FirstSession => "firstSession",
+            Self::Streak => "streak",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunnelStage {
+    pub step: FunnelStep,
+    pub users: usize,
+    /// 相对上一步的转化率，首步为 1
+    pub conversion_rate: f64,
+    /// 相对注册人数的转化率
+    pub overall_rate: f64,
+}
+
+fn rate(users: usize, base: usize) -> f64 {
+    if base > 0 {
+        users as f64 / base as f64
+    } else {
+        0.0
+    }
+}
+
+fn longest_streak(dates: &BTreeSet<NaiveDate>) -> u32 {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    for &date in dates {
+        run = if prev.and_then(|p| p.succ_opt()) == Some(date) {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+        prev = Some(date);
+    }
+    longest
+}
+
+impl Store {
+    /// 在 `[from, to)` 内注册的用户的引导漏斗。各步按顺序嵌套：只有完成前一步的用户才计入下一步；
+    /// 后续步骤不限时间，统计的是该批用户至今的转化情况
+    pub fn onboarding_funnel(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<FunnelStage>, StoreError> {
+        let mut counts = [0usize; 4];
+        for item in self.users_by_created_at.iter() {
+            let (_, value) = item?;
+            let user_id = String::from_utf8(value.to_vec()).unwrap_or_default();
+            let Some(user) = self.get_user_by_id(&user_id)? else {
+                continue;
+            };
+            // 索引按注册时间倒序
+            if user.created_at < from {
+                break;
+            }
+            if user.created_at >= to {
+                continue;
+            }
+            counts[0] += 1;
+
+            let prefix = keys::record_prefix(&user.id)?;
+            let mut dates = BTreeSet::new();
+            for item in self.records.scan_prefix(prefix.as_bytes()) {
+                let (key, _) = item?;
+                let reverse_ts = std::str::from_utf8(&key[prefix.len()..])
+                    .ok()
+                    .and_then(|rest| rest.split(':').next())
+                    .and_then(|ts| ts.parse::<u64>().ok());
+                if let Some(at) = reverse_ts
+                    .and_then(|r| i64::try_from(u64::MAX - r).ok())
+                    .and_then(DateTime::<Utc>::from_timestamp_millis)
+                {
+                    dates.insert(at.date_naive());
+                }
+            }
+            if dates.is_empty() {
+                continue;
+            }
+            counts[1] += 1;
+
+            let session_prefix = keys::learning_session_user_index_prefix(&user.id)?;
+            if self
+                .learning_sessions
+                .scan_prefix(session_prefix.as_bytes())
+                .next()
+                .transpose()?
+                .is_none()
+            {
+                continue;
+            }
+            counts[2] += 1;
+
+            if longest_streak(&dates) >= FUNNEL_STREAK_DAYS {
+                counts[3] += 1;
+            }
+        }
+
+        let steps = [
+            FunnelStep::Registered,
+            FunnelStep::FirstWord,
+            FunnelStep::FirstSession,
+            FunnelStep::Streak,
+        ];
+        Ok(steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, step)| FunnelStage {
+                step,
+                users: counts[i],
+                conversion_rate: if i == 0 {
+                    1.0
+                } else {
+                    rate(counts[i], counts[i - 1])
+                },
+                overall_rate: rate(counts[i], counts[0]),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::operations::learning_sessions::{LearningSession, SessionStatus};
+    use crate::store::operations::test_fixtures::{sample_record, sample_user};
+    use chrono::Duration;
+
+    fn session(id: &str, user_id: &str, created_at: DateTime<Utc>) -> LearningSession {
+        LearningSession {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            status: SessionStatus::Completed,
+            target_mastery_count: 10,
+            total_questions: 0,
+            actual_mastery_count: 0,
+            context_shifts: 0,
+            created_at,
+            updated_at: created_at,
+            summary: None,
+            correct_count: 0,
+            total_count: 0,
+        }
+    }
+
+    #[test]
+    fn counts_nested_onboarding_steps_in_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("funnel").to_str().unwrap()).unwrap();
+        let start = Utc::now() - Duration::days(20);
+        let day = |n: i64| start + Duration::days(n);
+        for id in ["idle", "one-day", "two-days", "streak"] {
+            store.create_user(&sample_user(id, day(0))).unwrap();
+        }
+        store.create_user(&sample_user("outside", day(15))).unwrap();
+        store
+            .create_record(&sample_record("r1", "one-day", "w1", day(1)))
+            .unwrap();
+        for (i, n) in [1, 3].into_iter().enumerate() {
+            store
+                .create_record(&sample_record(&format!("t{i}"), "two-days", "w1", day(n)))
+                .unwrap();
+        }
+        for n in 1..=3 {
+            store
+                .create_record(&sample_record(&format!("s{n}"), "streak", "w1", day(n)))
+                .unwrap();
+        }
+        store
+            .create_record(&sample_record("o1", "outside", "w1", day(15)))
+            .unwrap();
+        for user_id in ["two-days", "streak", "outside"] {
+            store
+                .create_learning_session(&session(&format!("ls-{user_id}"), user_id, day(1)))
+                .unwrap();
+        }
+
+        let funnel = store.onboarding_funnel(day(-1), day(10)).unwrap();
+        let users: Vec<usize> = funnel.iter().map(|s| s.users).collect();
+        assert_eq!(users, [4, 3, 2, 1]);
+        assert_eq!(funnel[1].conversion_rate, 0.75);
+        assert_eq!(funnel[3].conversion_rate, 0.5);
+        assert_eq!(funnel[3].overall_rate, 0.25);
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// LLM 为单词生成的例句，每个单词缓存一份，重新生成时整体替换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedExamples {
+    pub word_id: String,
+    pub sentences: Vec<String>,
+    /// 生成所用模型
+    pub model: String,
+    /// 生成所用提示词版本，与当前版本不一致的缓存视为过期
+    pub prompt_version: u32,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl Store {
+    pub fn get_generated_examples(
+        &self,
+        word_id: &str,
+    ) -> Result<Option<GeneratedExamples>, StoreError> {
+        let key = keys::generated_examples_key(word_id)?;
+        match self.generated_examples.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_generated_examples(&self, examples: &GeneratedExamples) -> Result<(), StoreError> {
+        let key = keys::generated_examples_key(&examples.word_id)?;
+        self.generated_examples
+            .insert(key.as_bytes(), Self::serialize(examples)?)?;
+        Ok(())
+    }
+
+    pub fn delete_generated_examples(&self, word_id: &str) -> Result<(), StoreError> {
+        let key = keys::generated_examples_key(word_id)?;
+        self.generated_examples.remove(key.as_bytes())?;
+        Ok(())
+    }
+}
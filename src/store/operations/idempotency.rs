@@ -0,0 +1,218 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::MAX_CAS_RETRIES;
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 以 Idempotency-Key 缓存的首次响应，重试时原样返回
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdempotentResponse {
+    pub status: u16,
+    pub body: serde_json::Value,
+    /// 首次请求体的摘要，同一 key 携带不同请求体时拒绝重放
+    #[serde(default)]
+    pub request_hash: Option<String>,
+    /// 首次请求仍在处理中，尚无可重放的响应
+    #[serde(default)]
+    pub pending: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 占用 Idempotency-Key 的结果
+#[derive(Debug)]
+pub enum IdempotencyReservation {
+    /// 本次请求获得处理权
+    Reserved,
+    /// key 已被占用（处理中或已完成）
+    Existing(IdempotentResponse),
+}
+
+impl Store {
+    /// 已过期的记录视为不存在
+    pub fn get_idempotent_response(
+        &self,
+        user_id: &str,
+        scope: &str,
+        key_hash: &str,
+    ) -> Result<Option<IdempotentResponse>, StoreError> {
+        let key = keys::idempotency_key(user_id, scope, key_hash)?;
+        let Some(raw) = self.idempotency_keys.get(key.as_bytes())? else {
+            return Ok(None);
+        };
+        let response: IdempotentResponse = Self::deserialize(&raw)?;
+        if response.expires_at <= Utc::now() {
+            return Ok(None);
+        }
+        Ok(Some(response))
+    }
+
+    pub fn put_idempotent_response(
+        &self,
+        user_id: &str,
+        scope: &str,
+        key_hash: &str,
+        response: &IdempotentResponse,
+    ) -> Result<(), StoreError> {
+        let key = keys::idempotency_key(user_id, scope, key_hash)?;
+        self.idempotency_keys
+            .insert(key.as_bytes(), Self::serialize(response)?)?;
+        Ok(())
+    }
+
+    /// 以 CAS 写入处理中标记：key 不存在或已过期时占用成功，否则返回现有记录。
+    /// 并发的相同请求只有一个能拿到处理权
+    pub fn reserve_idempotency_key(
+        &self,
+        user_id: &str,
+        scope: &str,
+        key_hash: &str,
+        pending: &IdempotentResponse,
+    ) -> Result<IdempotencyReservation, StoreError> {
+        let key = keys::idempotency_key(user_id, scope, key_hash)?;
+        let new_raw = Self::serialize(pending)?;
+        for _ in 0..MAX_CAS_RETRIES {
+            let current = self.idempotency_keys.get(key.as_bytes())?;
+            if let Some(raw) = &current {
+                let existing: IdempotentResponse = Self::deserialize(raw)?;
+                if existing.expires_at > Utc::now() {
+                    return Ok(IdempotencyReservation::Existing(existing));
+                }
+            }
+            if self
+                .idempotency_keys
+                .compare_and_swap(key.as_bytes(), current, Some(new_raw.clone()))?
+                .is_ok()
+            {
+                return Ok(IdempotencyReservation::Reserved);
+            }
+        }
+        Err(StoreError::CasRetryExhausted {
+            entity: "IdempotencyKey".to_string(),
+            key,
+            attempts: MAX_CAS_RETRIES,
+        })
+    }
+
+    /// 首次请求失败时释放处理中标记，允许客户端重试
+    pub fn release_idempotency_key(
+        &self,
+        user_id: &str,
+        scope: &str,
+        key_hash: &str,
+    ) -> Result<(), StoreError> {
+        let key = keys::idempotency_key(user_id, scope, key_hash)?;
+        self.idempotency_keys.remove(key.as_bytes())?;
+        Ok(())
+    }
+
+    /// 清理已过期的幂等记录，每批最多 1000 条
+    pub fn cleanup_expired_idempotency_keys(&self) -> Result<u32, StoreError> {
+        const MAX_BATCH_SIZE: usize = 1000;
+
+        let now = Utc::now();
+        let mut batch = sled::Batch::default();
+        let mut count = 0;
+        for item in self.idempotency_keys.iter() {
+            let (k, v) = item?;
+            let response: IdempotentResponse = Self::deserialize(&v)?;
+            if response.expires_at > now {
+                continue;
+            }
+            batch.remove(k);
+            count += 1;
+            if count >= MAX_BATCH_SIZE {
+                break;
+            }
+        }
+        self.idempotency_keys.apply_batch(batch)?;
+        Ok(count as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn expired_responses_are_ignored_and_cleaned() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("idem-db").to_str().unwrap()).unwrap();
+        let response = |expires_at| IdempotentResponse {
+            status: 201,
+            body: serde_json::json!({ "id": "r1" }),
+            request_hash: None,
+            pending: false,
+            created_at: Utc::now(),
+            expires_at,
+        };
+        store
+            .put_idempotent_response(
+                "u1",
+                "records",
+                "live",
+                &response(Utc::now() + Duration::hours(1)),
+            )
+            .unwrap();
+        store
+            .put_idempotent_response(
+                "u1",
+                "records",
+                "stale",
+                &response(Utc::now() - Duration::seconds(1)),
+            )
+            .unwrap();
+
+        let live = store
+            .get_idempotent_response("u1", "records", "live")
+            .unwrap();
+        assert_eq!(live.unwrap().body["id"], "r1");
+        assert!(store
+            .get_idempotent_response("u1", "records", "stale")
+            .unwrap()
+            .is_none());
+        assert!(store
+            .get_idempotent_response("u1", "records_batch", "live")
+            .unwrap()
+            .is_none());
+
+        assert_eq!(store.cleanup_expired_idempotency_keys().unwrap(), 1);
+        assert_eq!(store.idempotency_keys.len(), 1);
+    }
+
+    #[test]
+    fn reservation_is_exclusive_until_released() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("idem-db2").to_str().unwrap()).unwrap();
+        let pending = IdempotentResponse {
+            status: 0,
+            body: serde_json::Value::Null,
+            request_hash: Some("h1".to_string()),
+            pending: true,
+            created_at: Utc::now(),
+            expires_at: Utc::now() + Duration::minutes(5),
+        };
+
+        let first = store
+            .reserve_idempotency_key("u1", "records", "k1", &pending)
+            .unwrap();
+        assert!(matches!(first, IdempotencyReservation::Reserved));
+        let second = store
+            .reserve_idempotency_key("u1", "records", "k1", &pending)
+            .unwrap();
+        assert!(matches!(second, IdempotencyReservation::Existing(r) if r.pending));
+
+        store
+            .release_idempotency_key("u1", "records", "k1")
+            .unwrap();
+        let retried = store
+            .reserve_idempotency_key("u1", "records", "k1", &pending)
+            .unwrap();
+        assert!(matches!(retried, IdempotencyReservation::Reserved));
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 对 AMAS 配置中单个字段的调整建议，须经管理员确认后才会应用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSuggestion {
+    /// AMAS 配置 JSON 中的字段路径，点号分隔，如 `ensemble.warmupSamples`
+    pub path: String,
+    /// 生成建议时的配置值
+    #[serde(default)]
+    pub current: serde_json::Value,
+    pub proposed: serde_json::Value,
+    pub rationale: String,
+    #[serde(default)]
+    pub applied_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub applied_by: Option<String>,
+}
+
+impl ConfigSuggestion {
+    /// 对应的 JSON Pointer，如 `/ensemble/warmupSamples`
+    pub fn json_pointer(&self) -> String {
+        format!("/{}", self.path.replace('.', "/"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalySeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// 监控数据中值得关注的异常
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedAnomaly {
+    pub metric: String,
+    pub severity: AnomalySeverity,
+    pub detail: String,
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+}
+
+/// LLM 顾问的输出：一段总结、配置调整建议与异常标记
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdvisorOutput {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub suggestions: Vec<ConfigSuggestion>,
+    #[serde(default)]
+    pub anomalies: Vec<FlaggedAnomaly>,
+}
+
+/// llm_advisor worker 单次运行的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmAdvisory {
+    pub id: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub output: AdvisorOutput,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Store {
+    pub fn save_llm_advisory(&self, advisory: &LlmAdvisory) -> Result<(), StoreError> {
+        let key = keys::llm_advisory_key(advisory.created_at.timestamp_millis(), &advisory.id)?;
+        self.llm_advisories
+            .insert(key.as_bytes(), Self::serialize(advisory)?)?;
+        Ok(())
+    }
+
+    /// 最近的建议，按时间倒序
+    pub fn list_llm_advisories(&self, limit: usize) -> Result<Vec<LlmAdvisory>, StoreError> {
+        let mut items = Vec::new();
+        for item in self.llm_advisories.iter().rev().take(limit) {
+            let (_, raw) = item?;
+            items.push(Self::deserialize(&raw)?);
+        }
+        Ok(items)
+    }
+
+    /// 按 id 查找；建议数量少（每次 worker 运行一条），直接倒序扫描
+    pub fn get_llm_advisory(&self, id: &str) -> Result<Option<LlmAdvisory>, StoreError> {
+        let suffix = format!(":{}", keys::validate_id(id)?);
+        for item in self.llm_advisories.iter().rev() {
+            let (key, raw) = item?;
+            if key.ends_with(suffix.as_bytes()) {
+                return Ok(Some(Self::deserialize(&raw)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 标记第 `index` 条建议已由管理员应用，返回更新后的记录
+    pub fn mark_llm_advisory_suggestion_applied(
+        &self,
+        id: &str,
+        index: usize,
+        admin_id: &str,
+    ) -> Result<LlmAdvisory, StoreError> {
+        let mut advisory = self
+            .get_llm_advisory(id)?
+            .ok_or_else(|| StoreError::NotFound {
+                entity: "llm_advisory".to_string(),
+                key: id.to_string(),
+            })?;
+        let suggestion = advisory
+            .output
+            .suggestions
+            .get_mut(index)
+            .ok_or_else(|| StoreError::Validation("建议序号超出范围".to_string()))?;
+        suggestion.applied_at = Some(Utc::now());
+        suggestion.applied_by = Some(admin_id.to_string());
+        self.save_llm_advisory(&advisory)?;
+        Ok(advisory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(id: &str, created_at: DateTime<Utc>) -> LlmAdvisory {
+        LlmAdvisory {
+            id: id.to_string(),
+            model: "mock".to_string(),
+            output: AdvisorOutput {
+                summary: "ok".to_string(),
+                suggestions: vec![ConfigSuggestion {
+                    path: "ensemble.warmupSamples".to_string(),
+                    current: serde_json::json!(20),
+                    proposed: serde_json::json!(30),
+                    rationale: "more warmup".to_string(),
+                    applied_at: None,
+                    applied_by: None,
+                }],
+                anomalies: Vec::new(),
+            },
+            created_at,
+        }
+    }
+
+    #[test]
+    fn advisories_list_newest_first_and_track_applied_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("llm-advisories").to_str().unwrap()).unwrap();
+        let now = Utc::now();
+        store
+            .save_llm_advisory(&advisory("a1", now - chrono::Duration::hours(1)))
+            .unwrap();
+        store.save_llm_advisory(&advisory("a2", now)).unwrap();
+
+        let ids: Vec<String> = store
+            .list_llm_advisories(10)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, ["a2", "a1"]);
+
+        let updated = store
+            .mark_llm_advisory_suggestion_applied("a1", 0, "admin-1")
+            .unwrap();
+        assert_eq!(
+            updated.output.suggestions[0].applied_by.as_deref(),
+            Some("admin-1")
+        );
+        let stored = store.get_llm_advisory("a1").unwrap().unwrap();
+        assert!(stored.output.suggestions[0].applied_at.is_some());
+        assert_eq!(store.list_llm_advisories(10).unwrap().len(), 2);
+        assert!(store
+            .mark_llm_advisory_suggestion_applied("a1", 5, "admin-1")
+            .is_err());
+        assert!(store.get_llm_advisory("missing").unwrap().is_none());
+        assert_eq!(
+            updated.output.suggestions[0].json_pointer(),
+            "/ensemble/warmupSamples"
+        );
+    }
+}
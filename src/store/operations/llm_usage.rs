@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmUsageCounts {
+    pub calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// 按配置单价估算，单位美元
+    pub estimated_cost: f64,
+}
+
+impl LlmUsageCounts {
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    fn add(&mut self, other: &LlmUsageCounts) {
+        self.calls += other.calls;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.estimated_cost += other.estimated_cost;
+    }
+}
+
+/// 单日 LLM 用量，每次调用后累加
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmUsageDaily {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub total: LlmUsageCounts,
+    /// 按功能（etymology/examples/embedding/...）拆分
+    pub by_feature: BTreeMap<String, LlmUsageCounts>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LlmUsageDaily {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            total: LlmUsageCounts::default(),
+            by_feature: BTreeMap::new(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+impl Store {
+    /// 累加一次调用的用量，返回累加后的当日汇总
+    pub fn record_llm_usage(
+        &self,
+        date: NaiveDate,
+        feature: &str,
+        usage: &LlmUsageCounts,
+    ) -> Result<LlmUsageDaily, StoreError> {
+        let key = keys::llm_usage_daily_key(date);
+        let mut updated = None;
+        self.llm_usage_daily
+            .update_and_fetch(key.as_bytes(), |old| {
+                let mut daily = old
+                    .and_then(|raw| serde_json::from_slice::<LlmUsageDaily>(raw).ok())
+                    .unwrap_or_else(|| LlmUsageDaily::new(date));
+                daily.total.add(usage);
+                daily
+                    .by_feature
+                    .entry(feature.to_string())
+                    .or_default()
+                    .add(usage);
+                daily.updated_at = Utc::now();
+                let bytes = serde_json::to_vec(&daily).ok();
+                updated = Some(daily);
+                bytes
+            })?;
+        updated.ok_or_else(|| StoreError::Validation("failed to encode llm usage".to_string()))
+    }
+
+    pub fn get_llm_usage_daily(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<LlmUsageDaily>, StoreError> {
+        let key = keys::llm_usage_daily_key(date);
+        match self.llm_usage_daily.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 最近 `days` 天有调用的用量，按日期降序
+    pub fn list_llm_usage_daily(&self, days: usize) -> Result<Vec<LlmUsageDaily>, StoreError> {
+        let mut items = Vec::new();
+        for item in self.llm_usage_daily.iter().rev().take(days) {
+            let (_, raw) = item?;
+            items.push(Self::deserialize(&raw)?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_accumulates_per_day_and_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("llm-usage").to_str().unwrap()).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let call = LlmUsageCounts {
+            calls: 1,
+            prompt_tokens: 100,
+            completion_tokens: 20,
+            estimated_cost: 0.5,
+        };
+        store.record_llm_usage(date, "etymology", &call).unwrap();
+        store.record_llm_usage(date, "etymology", &call).unwrap();
+        let daily = store.record_llm_usage(date, "embedding", &call).unwrap();
+
+        assert_eq!(daily.total.calls, 3);
+        assert_eq!(daily.total.total_tokens(), 360);
+        assert_eq!(daily.by_feature["etymology"].calls, 2);
+        assert_eq!(daily.by_feature["embedding"].prompt_tokens, 100);
+        assert_eq!(
+            store.get_llm_usage_daily(date).unwrap().unwrap().total,
+            daily.total
+        );
+    }
+}
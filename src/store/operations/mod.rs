@@ -1,14 +1,35 @@
 pub mod admins;
+pub mod broadcasts;
+pub mod content_cache;
+pub mod counters;
+pub mod data_export;
 pub mod elo;
 pub mod engine;
+pub mod funnel;
+pub mod generated_examples;
+pub mod idempotency;
 pub mod learning_sessions;
+pub mod llm_advisories;
+pub mod llm_usage;
+pub mod moderation;
+pub mod monitoring;
 pub mod notifications;
 pub mod records;
+pub mod refresh_tokens;
+pub mod retention;
 pub mod sessions;
 pub mod study_configs;
 pub mod system_settings;
+#[cfg(test)]
+pub mod test_fixtures;
 pub mod users;
+pub mod visual_fatigue;
+pub mod webhooks;
+pub mod weekly_reports;
 pub mod wb_center;
+pub mod word_clusters;
+pub mod word_difficulty;
+pub mod word_merge;
 pub mod word_states;
 pub mod wordbooks;
 pub mod words;
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 未通过审核的 LLM 生成内容，不缓存也不对外提供，仅供管理员复核
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationRejection {
+    pub id: String,
+    /// 内容类型：`examples` 或 `etymology`
+    pub kind: String,
+    pub word_id: String,
+    pub content: Vec<String>,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Store {
+    pub fn record_moderation_rejection(
+        &self,
+        rejection: &ModerationRejection,
+    ) -> Result<(), StoreError> {
+        let key =
+            keys::moderation_rejection_key(rejection.created_at.timestamp_millis(), &rejection.id)?;
+        self.moderation_rejections
+            .insert(key.as_bytes(), Self::serialize(rejection)?)?;
+        Ok(())
+    }
+
+    /// 删除早于 `cutoff_ms` 的拒绝记录，最多删除 `limit` 条，返回删除数
+    pub fn purge_moderation_rejections_before(
+        &self,
+        cutoff_ms: i64,
+        limit: usize,
+    ) -> Result<usize, StoreError> {
+        let end = keys::moderation_rejection_cutoff_key(cutoff_ms);
+        let mut removed = 0;
+        for item in self
+            .moderation_rejections
+            .range(..end.as_bytes())
+            .take(limit)
+        {
+            let (key, _) = item?;
+            if self.moderation_rejections.remove(&key)?.is_some() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 最近的审核拒绝记录，按时间倒序
+    pub fn list_moderation_rejections(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<ModerationRejection>, StoreError> {
+        let mut items = Vec::new();
+        for item in self.moderation_rejections.iter().rev().take(limit) {
+            let (_, raw) = item?;
+            items.push(Self::deserialize(&raw)?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn purge_removes_only_rejections_before_cutoff() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("moderation-db").to_str().unwrap()).unwrap();
+        let now = Utc::now();
+        for (id, age_days) in [("old", 100), ("recent", 1)] {
+            store
+                .record_moderation_rejection(&ModerationRejection {
+                    id: id.to_string(),
+                    kind: "examples".to_string(),
+                    word_id: "w1".to_string(),
+                    content: vec![],
+                    reason: "blocked term: x".to_string(),
+                    created_at: now - Duration::days(age_days),
+                })
+                .unwrap();
+        }
+
+        let cutoff = (now - Duration::days(90)).timestamp_millis();
+        assert_eq!(
+            store
+                .purge_moderation_rejections_before(cutoff, 10)
+                .unwrap(),
+            1
+        );
+        let remaining = store.list_moderation_rejections(10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "recent");
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sled::Transactional;
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 延迟直方图各桶上界（毫秒），超过最大上界的事件计入额外的溢出桶
+pub const LATENCY_BUCKET_BOUNDS_MS: [i64; 9] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500];
+
+const AGGREGATE_CURSOR_CONFIG: &str = "monitoring_aggregate_cursor";
+
+/// 按天汇总的监控事件，由 monitoring_aggregate worker 增量累加
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringDailySummary {
+    pub date: NaiveDate,
+    pub total_events: u64,
+    pub anomaly_count: u64,
+    /// 与 `LATENCY_BUCKET_BOUNDS_MS` 一一对应，末尾多一个溢出桶
+    pub latency_histogram: Vec<u64>,
+    /// 按难度档位（low/medium/high）统计的策略分布
+    pub difficulty_distribution: BTreeMap<String, u64>,
+    pub review_mode_count: u64,
+    /// 约束层改写了原始策略的次数
+    pub constraint_trigger_count: u64,
+    /// 按字段统计的不变量违例次数
+    pub violation_counts: BTreeMap<String, u64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MonitoringDailySummary {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            total_events: 0,
+            anomaly_count: 0,
+            latency_histogram: vec![0; LATENCY_BUCKET_BOUNDS_MS.len() + 1],
+            difficulty_distribution: BTreeMap::new(),
+            review_mode_count: 0,
+            constraint_trigger_count: 0,
+            violation_counts: BTreeMap::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn record_latency(&mut self, latency_ms: i64) {
+        let bucket = LATENCY_BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKET_BOUNDS_MS.len());
+        if let Some(count) = self.latency_histogram.get_mut(bucket) {
+            *count += 1;
+        }
+    }
+
+    /// 由直方图估算分位数，返回所在桶的上界；溢出桶按最大上界计
+    pub fn latency_percentile(&self, pct: f64) -> i64 {
+        let total: u64 = self.latency_histogram.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+        let rank = ((pct / 100.0) * total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, count) in self.latency_histogram.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKET_BOUNDS_MS[idx.min(LATENCY_BUCKET_BOUNDS_MS.len() - 1)];
+            }
+        }
+        LATENCY_BUCKET_BOUNDS_MS[LATENCY_BUCKET_BOUNDS_MS.len() - 1]
+    }
+}
+
+impl Store {
+    pub fn get_monitoring_daily_summary(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<MonitoringDailySummary>, StoreError> {
+        let key = keys::monitoring_daily_key(date);
+        match self.monitoring_daily.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 最近 `days` 天的汇总，按日期降序
+    pub fn list_monitoring_daily_summaries(
+        &self,
+        days: usize,
+    ) -> Result<Vec<MonitoringDailySummary>, StoreError> {
+        let mut summaries = Vec::new();
+        for item in self.monitoring_daily.iter().rev().take(days) {
+            let (_, raw) = item?;
+            summaries.push(Self::deserialize(&raw)?);
+        }
+        Ok(summaries)
+    }
+
+    /// 已聚合到的最后一个事件键；旧版本保存的是毫秒时间戳，换算为该毫秒的截止键
+    pub fn get_monitoring_aggregate_cursor(&self) -> Result<Option<String>, StoreError> {
+        let key = keys::config_latest_key(AGGREGATE_CURSOR_CONFIG)?;
+        let Some(raw) = self.config_versions.get(key.as_bytes())? else {
+            return Ok(None);
+        };
+        Ok(match Self::deserialize::<serde_json::Value>(&raw)? {
+            serde_json::Value::String(event_key) => Some(event_key),
+            serde_json::Value::Number(ms) => ms.as_i64().map(keys::monitoring_event_cutoff_key),
+            _ => None,
+        })
+    }
+
+    /// 汇总与游标在同一事务中写入，避免中途失败导致重复累加
+    pub fn save_monitoring_daily_summaries(
+        &self,
+        summaries: &[MonitoringDailySummary],
+        cursor: &str,
+    ) -> Result<(), StoreError> {
+        let cursor_key = keys::config_latest_key(AGGREGATE_CURSOR_CONFIG)?;
+        let cursor_bytes = Self::serialize(&cursor)?;
+        let entries = summaries
+            .iter()
+            .map(|s| Ok((keys::monitoring_daily_key(s.date), Self::serialize(s)?)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+
+        (&self.monitoring_daily, &self.config_versions)
+            .transaction(|(tx_daily, tx_config)| {
+                for (key, bytes) in &entries {
+                    tx_daily.insert(key.as_bytes(), bytes.as_slice())?;
+                }
+                tx_config.insert(cursor_key.as_bytes(), cursor_bytes.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: sled::transaction::TransactionError<()>| match e {
+                sled::transaction::TransactionError::Abort(()) => {
+                    StoreError::Sled(sled::Error::Unsupported("transaction aborted".into()))
+                }
+                sled::transaction::TransactionError::Storage(se) => StoreError::Sled(se),
+            })?;
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
 use crate::store::keys;
 use crate::store::{Store, StoreError};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NotificationType {
     System,
@@ -12,6 +14,8 @@ pub enum NotificationType {
     Reminder,
     Info,
     Broadcast,
+    #[serde(rename = "forgetting_alert")]
+    ForgettingAlert,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,30 +29,325 @@ pub struct Notification {
     pub message: String,
     pub read: bool,
     pub created_at: DateTime<Utc>,
+    /// 分组目标；相同类型与目标的未读通知在窗口内合并为一条，缺省时按类型分组
+    #[serde(default)]
+    pub group_key: Option<String>,
+    /// 合并进该通知的事件数
+    #[serde(default = "default_notification_count")]
+    pub count: u32,
+    /// 合并进该通知的事件明细（如单词 ID），合并时追加，只保留最近的 [`MAX_GROUPED_ITEMS`] 条
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// 创建于用户静默时段时推迟到该时刻才展示和推送
+    #[serde(default)]
+    pub deferred_until: Option<DateTime<Utc>>,
+    /// 来自管理员广播时为广播 ID，用于统计送达与已读
+    #[serde(default)]
+    pub broadcast_id: Option<String>,
+}
+
+pub const MAX_GROUPED_ITEMS: usize = 100;
+
+fn default_notification_count() -> u32 {
+    1
+}
+
+impl Notification {
+    /// 最近一次创建或合并的时间，用于排序和分组窗口判断
+    pub fn last_activity_at(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or(self.created_at)
+    }
+
+    pub fn is_deferred(&self, now: DateTime<Utc>) -> bool {
+        self.deferred_until.is_some_and(|until| until > now)
+    }
+}
+
+/// 用户偏好中的静默时段，按用户本地时区的整点计算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// 本地时间 [start_hour, end_hour)，start_hour > end_hour 表示跨午夜
+    pub start_hour: u32,
+    pub end_hour: u32,
+    /// IANA 时区名，如 `Asia/Shanghai`
+    pub timezone: String,
+}
+
+impl QuietHours {
+    pub fn tz(&self) -> Option<Tz> {
+        self.timezone.parse().ok()
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let Some(tz) = self.tz() else {
+            return false;
+        };
+        if !self.enabled || self.start_hour == self.end_hour {
+            return false;
+        }
+        let hour = at.with_timezone(&tz).hour();
+        if self.start_hour < self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+
+    /// 静默时段结束的时刻；不在静默时段内时返回 `at`
+    pub fn next_allowed_at(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let Some(tz) = self.tz().filter(|_| self.contains(at)) else {
+            return at;
+        };
+        let local = at.with_timezone(&tz);
+        let mut date = local.date_naive();
+        if local.hour() >= self.end_hour {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        date.and_hms_opt(self.end_hour, 0, 0)
+            .and_then(|naive| tz.from_local_datetime(&naive).earliest())
+            .map(|end| end.with_timezone(&Utc))
+            // 结束整点恰好落在夏令时跳变的空档里
+            .unwrap_or(at + chrono::Duration::hours(1))
+    }
+}
+
+/// 新创建的通知（原始 JSON），通过 [`Store::subscribe_notifications`] 订阅
+#[derive(Debug, Clone)]
+pub struct NotificationEvent {
+    pub user_id: String,
+    pub notification: serde_json::Value,
 }
 
 impl Store {
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<NotificationEvent> {
+        self.notification_tx.subscribe()
+    }
+
+    fn publish_notification(&self, user_id: &str, notification: &serde_json::Value) {
+        // 没有订阅者时 send 返回错误，可忽略
+        let _ = self.notification_tx.send(NotificationEvent {
+            user_id: user_id.to_string(),
+            notification: notification.clone(),
+        });
+    }
+
+    pub fn create_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        let key = keys::notification_key(user_id, notification_id)?;
+        self.notifications
+            .insert(key.as_bytes(), Self::serialize(value)?)?;
+        self.publish_notification(user_id, value);
+        Ok(())
+    }
+
+    pub fn get_user_quiet_hours(&self, user_id: &str) -> Result<Option<QuietHours>, StoreError> {
+        let key = keys::user_preferences_key(user_id)?;
+        let Some(raw) = self.user_preferences.get(key.as_bytes())? else {
+            return Ok(None);
+        };
+        let prefs: serde_json::Value = Self::deserialize(&raw)?;
+        Ok(prefs
+            .get("quietHours")
+            .and_then(|v| serde_json::from_value::<QuietHours>(v.clone()).ok())
+            .filter(|q| q.enabled))
+    }
+
+    /// 释放到期的延迟通知并推送给在线订阅者，返回释放数量
+    pub fn release_deferred_notifications(&self) -> Result<u32, StoreError> {
+        let now_ms = Utc::now().timestamp_millis();
+        let mut released = 0u32;
+        for item in self.deferred_notifications.iter() {
+            let (index_key, _) = item?;
+            let Some((release_ms, user_id, notification_id)) =
+                keys::parse_deferred_notification_key(&index_key)
+            else {
+                self.deferred_notifications.remove(&index_key)?;
+                continue;
+            };
+            if release_ms > now_ms {
+                break;
+            }
+
+            let key = keys::notification_key(&user_id, &notification_id)?;
+            if let Some(raw) = self.notifications.get(key.as_bytes())? {
+                let mut notification: Notification = Self::deserialize(&raw)?;
+                notification.deferred_until = None;
+                let value = serde_json::to_value(&notification)?;
+                self.notifications
+                    .insert(key.as_bytes(), Self::serialize(&value)?)?;
+                self.publish_notification(&user_id, &value);
+                released += 1;
+            }
+            self.deferred_notifications.remove(&index_key)?;
+        }
+        Ok(released)
+    }
+
+    /// 若窗口内已有相同类型与 `group_key` 的未读通知，则累加其 `count`、追加 `items`，
+    /// 并用 `render_message` 按合并后的总数重写内容；否则插入新通知。
+    /// 用户处于静默时段时新通知标记为延迟，且不做实时推送。返回最终写入的通知
+    pub fn create_grouped_notification(
+        &self,
+        notification: &Notification,
+        window: chrono::Duration,
+        render_message: impl Fn(u32) -> String,
+    ) -> Result<Notification, StoreError> {
+        let now = Utc::now();
+        let existing = if window > chrono::Duration::zero() {
+            self.find_groupable_notification(notification, now - window)?
+        } else {
+            None
+        };
+
+        let is_new = existing.is_none();
+        let mut merged = match existing {
+            Some(mut current) => {
+                current.count = current.count.saturating_add(notification.count);
+                current.items.extend(notification.items.iter().cloned());
+                let overflow = current.items.len().saturating_sub(MAX_GROUPED_ITEMS);
+                current.items.drain(..overflow);
+                current.title = notification.title.clone();
+                current.message = render_message(current.count);
+                current.updated_at = Some(now);
+                current
+            }
+            None => Notification {
+                message: render_message(notification.count),
+                ..notification.clone()
+            },
+        };
+
+        let quiet_hours = self
+            .get_user_quiet_hours(&merged.user_id)?
+            .filter(|q| q.contains(now));
+        if let (true, Some(quiet_hours)) = (is_new, &quiet_hours) {
+            self.defer_notification(&mut merged, quiet_hours.next_allowed_at(now))?;
+        }
+
+        let value = serde_json::to_value(&merged)?;
+        let key = keys::notification_key(&merged.user_id, &merged.id)?;
+        self.notifications
+            .insert(key.as_bytes(), Self::serialize(&value)?)?;
+        if quiet_hours.is_none() && merged.deferred_until.is_none() {
+            self.publish_notification(&merged.user_id, &value);
+        }
+        Ok(merged)
+    }
+
+    /// 推迟到 `release_at` 才展示，由 [`Store::release_deferred_notifications`] 到期释放并推送
+    fn defer_notification(
+        &self,
+        notification: &mut Notification,
+        release_at: DateTime<Utc>,
+    ) -> Result<(), StoreError> {
+        notification.deferred_until = Some(release_at);
+        let index_key = keys::deferred_notification_key(
+            release_at.timestamp_millis(),
+            &notification.user_id,
+            &notification.id,
+        )?;
+        self.deferred_notifications
+            .insert(index_key.as_bytes(), &[] as &[u8])?;
+        Ok(())
+    }
+
+    fn find_groupable_notification(
+        &self,
+        notification: &Notification,
+        since: DateTime<Utc>,
+    ) -> Result<Option<Notification>, StoreError> {
+        let prefix = keys::notification_prefix(&notification.user_id)?;
+        for item in self.notifications.scan_prefix(prefix.as_bytes()) {
+            let (_, raw) = item?;
+            let Ok(candidate) = Self::deserialize::<Notification>(&raw) else {
+                continue;
+            };
+            if !candidate.read
+                && candidate.notification_type == notification.notification_type
+                && candidate.group_key == notification.group_key
+                && candidate.last_activity_at() >= since
+            {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 批量写入通知；处于静默时段的用户与单条通知一样推迟展示，且不做实时推送
     pub fn batch_create_notifications(
         &self,
-        entries: &[(String, String, serde_json::Value)],
+        notifications: Vec<Notification>,
     ) -> Result<(), StoreError> {
+        let now = Utc::now();
         let mut batch = sled::Batch::default();
-        for (user_id, notification_id, value) in entries {
-            let key = keys::notification_key(user_id, notification_id)?;
-            let bytes = serde_json::to_vec(value)?;
-            batch.insert(key.as_bytes(), bytes);
+        let mut published = Vec::with_capacity(notifications.len());
+        for mut notification in notifications {
+            if let Some(quiet_hours) = self
+                .get_user_quiet_hours(&notification.user_id)?
+                .filter(|q| q.contains(now))
+            {
+                self.defer_notification(&mut notification, quiet_hours.next_allowed_at(now))?;
+            }
+            let value = serde_json::to_value(&notification)?;
+            let key = keys::notification_key(&notification.user_id, &notification.id)?;
+            batch.insert(key.as_bytes(), Self::serialize(&value)?);
+            if notification.deferred_until.is_none() {
+                published.push((notification.user_id, value));
+            }
         }
         self.notifications.apply_batch(batch)?;
+        for (user_id, value) in &published {
+            self.publish_notification(user_id, value);
+        }
+        Ok(())
+    }
+
+    /// 广播去重复用 alert_dedup tree，值为最近投递时间戳（毫秒）
+    pub fn get_broadcast_sent_at(
+        &self,
+        user_id: &str,
+        content_hash: &str,
+    ) -> Result<Option<i64>, StoreError> {
+        let key = keys::alert_dedup_key(user_id, content_hash)?;
+        Ok(self
+            .alert_dedup
+            .get(key.as_bytes())?
+            .and_then(|v| std::str::from_utf8(&v).ok()?.parse::<i64>().ok()))
+    }
+
+    pub fn mark_broadcast_sent(
+        &self,
+        user_ids: &[&str],
+        content_hash: &str,
+        sent_at_ms: i64,
+    ) -> Result<(), StoreError> {
+        let mut batch = sled::Batch::default();
+        for user_id in user_ids {
+            let key = keys::alert_dedup_key(user_id, content_hash)?;
+            batch.insert(key.as_bytes(), sent_at_ms.to_string().as_bytes());
+        }
+        self.alert_dedup.apply_batch(batch)?;
         Ok(())
     }
 
+    /// 按最近活动时间倒序分页，返回当前页与符合条件的总数
     pub fn list_notifications(
         &self,
         user_id: &str,
         limit: usize,
+        offset: usize,
         unread_only: bool,
-    ) -> Result<Vec<Notification>, StoreError> {
+    ) -> Result<(Vec<Notification>, u64), StoreError> {
         let prefix = keys::notification_prefix(user_id)?;
+        let now = Utc::now();
         let mut notifications = Vec::new();
 
         for item in self.notifications.scan_prefix(prefix.as_bytes()) {
@@ -57,16 +356,17 @@ impl Store {
                 Err(_) => continue,
             };
             if let Ok(notification) = Self::deserialize::<Notification>(&raw) {
-                if unread_only && notification.read {
+                if (unread_only && notification.read) || notification.is_deferred(now) {
                     continue;
                 }
                 notifications.push(notification);
             }
         }
 
-        notifications.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        notifications.truncate(limit);
-        Ok(notifications)
+        notifications.sort_by_key(|n| std::cmp::Reverse(n.last_activity_at()));
+        let total = notifications.len() as u64;
+        let page = notifications.into_iter().skip(offset).take(limit).collect();
+        Ok((page, total))
     }
 
     pub fn mark_notification_read(
@@ -136,6 +436,7 @@ impl Store {
 
     pub fn count_unread_notifications(&self, user_id: &str) -> Result<u64, StoreError> {
         let prefix = keys::notification_prefix(user_id)?;
+        let now = Utc::now();
         let mut unread_count = 0u64;
 
         for item in self.notifications.scan_prefix(prefix.as_bytes()) {
@@ -144,7 +445,7 @@ impl Store {
                 Err(_) => continue,
             };
             if let Ok(notification) = Self::deserialize::<Notification>(&raw) {
-                if !notification.read {
+                if !notification.read && !notification.is_deferred(now) {
                     unread_count += 1;
                 }
             }
@@ -153,3 +454,112 @@ impl Store {
         Ok(unread_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Timelike};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn quiet_hours_follow_user_timezone_across_midnight() {
+        let quiet = QuietHours {
+            enabled: true,
+            start_hour: 22,
+            end_hour: 7,
+            timezone: "Asia/Shanghai".to_string(),
+        };
+        // 16:00 UTC = 次日 00:00 北京时间
+        let midnight_local = Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap();
+        assert!(quiet.contains(midnight_local));
+        assert_eq!(
+            quiet.next_allowed_at(midnight_local),
+            Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap()
+        );
+
+        let afternoon_local = Utc.with_ymd_and_hms(2024, 1, 1, 5, 0, 0).unwrap();
+        assert!(!quiet.contains(afternoon_local));
+        assert_eq!(quiet.next_allowed_at(afternoon_local), afternoon_local);
+    }
+
+    #[test]
+    fn grouped_notifications_merge_count_and_items() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("notif-db").to_str().unwrap()).unwrap();
+        let alert = |id: &str, word_id: &str| Notification {
+            id: id.to_string(),
+            user_id: "u1".to_string(),
+            notification_type: NotificationType::ForgettingAlert,
+            title: "复习提醒".to_string(),
+            message: String::new(),
+            read: false,
+            created_at: Utc::now(),
+            group_key: None,
+            count: 1,
+            items: vec![serde_json::json!({ "wordId": word_id })],
+            updated_at: None,
+            deferred_until: None,
+            broadcast_id: None,
+        };
+
+        store
+            .create_grouped_notification(&alert("n1", "w1"), Duration::hours(1), |n| n.to_string())
+            .unwrap();
+        let merged = store
+            .create_grouped_notification(&alert("n2", "w2"), Duration::hours(1), |n| n.to_string())
+            .unwrap();
+
+        assert_eq!(merged.id, "n1");
+        assert_eq!((merged.count, merged.message.as_str()), (2, "2"));
+        let word_ids: Vec<_> = merged.items.iter().map(|i| &i["wordId"]).collect();
+        assert_eq!(word_ids, ["w1", "w2"]);
+        assert_eq!(store.list_notifications("u1", 10, 0, false).unwrap().1, 1);
+    }
+
+    #[test]
+    fn notification_created_in_quiet_hours_is_deferred() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("notif-db").to_str().unwrap()).unwrap();
+        // 静默时段只排除前一小时，测试跨整点时仍处于静默时段内
+        let hour = Utc::now().hour();
+        let prefs = serde_json::json!({
+            "quietHours": {
+                "enabled": true,
+                "startHour": hour,
+                "endHour": (hour + 23) % 24,
+                "timezone": "UTC",
+            }
+        });
+        store
+            .user_preferences
+            .insert("u1", serde_json::to_vec(&prefs).unwrap())
+            .unwrap();
+
+        let notification = Notification {
+            id: "n1".to_string(),
+            user_id: "u1".to_string(),
+            notification_type: NotificationType::Reminder,
+            title: "复习提醒".to_string(),
+            message: String::new(),
+            read: false,
+            created_at: Utc::now(),
+            group_key: None,
+            count: 1,
+            items: Vec::new(),
+            updated_at: None,
+            deferred_until: None,
+            broadcast_id: None,
+        };
+        let mut events = store.subscribe_notifications();
+        let created = store
+            .create_grouped_notification(&notification, Duration::hours(1), |n| n.to_string())
+            .unwrap();
+
+        assert!(created.deferred_until.is_some_and(|t| t > Utc::now()));
+        assert!(events.try_recv().is_err());
+        assert_eq!(store.list_notifications("u1", 10, 0, false).unwrap().1, 0);
+        assert_eq!(store.count_unread_notifications("u1").unwrap(), 0);
+        assert_eq!(store.release_deferred_notifications().unwrap(), 0);
+    }
+}
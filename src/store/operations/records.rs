@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sled::Transactional;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use crate::amas::types::AlgorithmId;
 use crate::store::keys;
+use crate::store::operations::counters::{self, Counter};
 use crate::store::{Store, StoreError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +18,12 @@ pub struct LearningRecord {
     pub response_time_ms: i64,
     pub session_id: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// 处理本次作答后生成下一步策略的主导算法；旧记录及未经 AMAS 处理的记录为空
+    #[serde(default)]
+    pub algorithm_id: Option<AlgorithmId>,
+    /// 生成该策略时各算法的权重
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub algorithm_weights: HashMap<AlgorithmId, f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -69,8 +77,13 @@ impl Store {
     pub fn create_record(&self, record: &LearningRecord) -> Result<(), StoreError> {
         let ts = record.created_at.timestamp_millis();
         let key = keys::record_key(&record.user_id, ts, &record.id)?;
-        self.records
-            .insert(key.as_bytes(), Self::serialize(record)?)?;
+        if self
+            .records
+            .insert(key.as_bytes(), Self::serialize(record)?)?
+            .is_none()
+        {
+            self.adjust_counter(Counter::Records, 1)?;
+        }
         let idx_key = keys::record_id_index_key(&record.user_id, &record.id)?;
         self.record_id_index
             .insert(idx_key.as_bytes(), key.as_bytes())?;
@@ -132,9 +145,15 @@ impl Store {
             &self.word_learning_states,
             &self.word_due_index,
             &self.learning_sessions,
+            &self.counters,
         )
-            .transaction(|(tx_records, tx_word_states, tx_due_index, tx_sessions)| {
-                tx_records.insert(record_key.as_bytes(), record_bytes.as_slice())?;
+            .transaction(|(tx_records, tx_word_states, tx_due_index, tx_sessions, tx_counters)| {
+                if tx_records
+                    .insert(record_key.as_bytes(), record_bytes.as_slice())?
+                    .is_none()
+                {
+                    counters::adjust_in_tx(tx_counters, Counter::Records, 1)?;
+                }
 
                 if let Some((key, bytes, due_index_key)) = &word_state_payload {
                     if let Some(old_raw) = tx_word_states.get(key.as_bytes())? {
@@ -249,6 +268,64 @@ impl Store {
         Ok(records)
     }
 
+    /// 用户最早的 `limit` 条记录，按时间升序
+    pub fn get_user_records_oldest_first(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<LearningRecord>, StoreError> {
+        let prefix = keys::record_prefix(user_id)?;
+        let mut records = Vec::new();
+        for item in self.records.scan_prefix(prefix.as_bytes()).rev() {
+            let (_, value) = item?;
+            records.push(Self::deserialize::<LearningRecord>(&value)?);
+            if records.len() >= limit {
+                break;
+            }
+        }
+        Ok(records)
+    }
+
+    /// 用户自 `since` 起的全部记录，按时间升序
+    pub fn get_user_records_since(
+        &self,
+        user_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<LearningRecord>, StoreError> {
+        let prefix = keys::record_prefix(user_id)?;
+        let mut records = Vec::new();
+        // 键内为逆序时间戳，前缀扫描从新到旧
+        for item in self.records.scan_prefix(prefix.as_bytes()) {
+            let (_, value) = item?;
+            let record: LearningRecord = Self::deserialize(&value)?;
+            if record.created_at < since {
+                break;
+            }
+            records.push(record);
+        }
+        records.reverse();
+        Ok(records)
+    }
+
+    /// `[from, to)` 内有学习记录的用户 ID（使用 records_by_time 索引）
+    pub fn list_active_user_ids_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashSet<String>, StoreError> {
+        let start_key = keys::records_by_time_since_key(from.timestamp_millis());
+        let end_key = keys::records_by_time_since_key(to.timestamp_millis());
+        let mut user_ids = HashSet::new();
+        for item in self
+            .records_by_time
+            .range(start_key.as_bytes()..end_key.as_bytes())
+        {
+            let (_, value) = item?;
+            user_ids.insert(String::from_utf8_lossy(&value).into_owned());
+        }
+        Ok(user_ids)
+    }
+
     pub fn get_user_records_with_offset(
         &self,
         user_id: &str,
@@ -299,12 +376,7 @@ impl Store {
     }
 
     pub fn count_all_records(&self) -> Result<usize, StoreError> {
-        let mut count = 0usize;
-        for item in self.records.iter() {
-            let _ = item?;
-            count += 1;
-        }
-        Ok(count)
+        Ok(self.read_counter(Counter::Records)? as usize)
     }
 
     /// 统计所有 is_correct=true 的记录数，避免逐用户统计
@@ -340,6 +412,38 @@ impl Store {
         }
         Ok(records)
     }
+
+    /// 按时间正序返回用户某个单词的作答记录分页，以及该单词的记录总数
+    pub fn get_user_word_history(
+        &self,
+        user_id: &str,
+        word_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<LearningRecord>, usize), StoreError> {
+        // 经 word_references 索引只访问该单词的记录，索引按时间倒序，反向扫描即为时间正序
+        let prefix = keys::word_user_records_ref_prefix(word_id, user_id)?;
+        let mut records = Vec::new();
+        let mut total = 0usize;
+        for item in self.word_references.scan_prefix(prefix.as_bytes()).rev() {
+            let (ref_key, _) = item?;
+            let Some(assoc_hex) = ref_key.rsplit(|b| *b == b':').next() else {
+                continue;
+            };
+            let Ok(record_key) = hex::decode(assoc_hex) else {
+                continue;
+            };
+            // 记录已删除但索引残留时跳过
+            let Some(value) = self.records.get(&record_key)? else {
+                continue;
+            };
+            if total >= offset && records.len() < limit {
+                records.push(Self::deserialize(&value)?);
+            }
+            total += 1;
+        }
+        Ok((records, total))
+    }
 }
 
 #[cfg(test)]
@@ -363,6 +467,8 @@ mod tests {
             response_time_ms: 1000,
             session_id: Some("s1".to_string()),
             created_at,
+            algorithm_id: None,
+            algorithm_weights: Default::default(),
         }
     }
 
@@ -384,4 +490,29 @@ mod tests {
         assert_eq!(list[0].id, "r2");
         assert_eq!(list[1].id, "r1");
     }
+
+    #[test]
+    fn algorithm_tag_roundtrips_and_defaults_for_legacy_records() {
+        let legacy: LearningRecord = serde_json::from_value(serde_json::json!({
+            "id": "r1",
+            "userId": "u1",
+            "wordId": "w1",
+            "isCorrect": true,
+            "responseTimeMs": 1000,
+            "sessionId": null,
+            "createdAt": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        assert!(legacy.algorithm_id.is_none());
+        assert!(legacy.algorithm_weights.is_empty());
+
+        let mut record = sample_record("r2", "u1", "w1", Utc::now());
+        record.algorithm_id = Some(AlgorithmId::Ige);
+        record.algorithm_weights =
+            HashMap::from([(AlgorithmId::Ige, 0.7), (AlgorithmId::Swd, 0.3)]);
+        let restored: LearningRecord =
+            Store::deserialize(&Store::serialize(&record).unwrap()).unwrap();
+        assert_eq!(restored.algorithm_id, Some(AlgorithmId::Ige));
+        assert_eq!(restored.algorithm_weights, record.algorithm_weights);
+    }
 }
@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 刷新令牌记录（仅存哈希）。同一次登录派生出的令牌共享 `family_id`，
+/// 轮换后旧令牌保留 `replaced_by` 直到过期，用于识别重放。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshToken {
+    pub token_hash: String,
+    pub user_id: String,
+    pub family_id: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+#[derive(Debug)]
+pub enum RefreshRotation {
+    Rotated(RefreshToken),
+    /// 已轮换过的令牌被再次使用，视为令牌被盗
+    Reused {
+        user_id: String,
+        family_id: String,
+    },
+    Invalid,
+}
+
+impl Store {
+    pub fn create_refresh_token(&self, token: &RefreshToken) -> Result<(), StoreError> {
+        let key = keys::session_key(&token.token_hash)?;
+        let index_key = keys::session_user_index_key(&token.user_id, &token.token_hash)?;
+        let mut batch = sled::Batch::default();
+        batch.insert(key.as_bytes(), Self::serialize(token)?);
+        batch.insert(index_key.as_bytes(), &[] as &[u8]);
+        self.refresh_tokens.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// 原子地以新令牌替换旧令牌：旧令牌标记为已轮换，新令牌继承其 family
+    pub fn rotate_refresh_token(
+        &self,
+        old_hash: &str,
+        new_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<RefreshRotation, StoreError> {
+        let old_key = keys::session_key(old_hash)?;
+        let new_key = keys::session_key(new_hash)?;
+
+        self.refresh_tokens
+            .transaction(|tx| {
+                let Some(raw) = tx.get(old_key.as_bytes())? else {
+                    return Ok(RefreshRotation::Invalid);
+                };
+                let mut old: RefreshToken = serde_json::from_slice(&raw)
+                    .map_err(|e| ConflictableTransactionError::Abort(StoreError::from(e)))?;
+                let now = Utc::now();
+                if old.revoked || old.expires_at <= now {
+                    return Ok(RefreshRotation::Invalid);
+                }
+                if old.replaced_by.is_some() {
+                    return Ok(RefreshRotation::Reused {
+                        user_id: old.user_id,
+                        family_id: old.family_id,
+                    });
+                }
+
+                let rotated = RefreshToken {
+                    token_hash: new_hash.to_string(),
+                    user_id: old.user_id.clone(),
+                    family_id: old.family_id.clone(),
+                    created_at: now,
+                    expires_at,
+                    replaced_by: None,
+                    revoked: false,
+                };
+                let index_key = keys::session_user_index_key(&rotated.user_id, new_hash)
+                    .map_err(ConflictableTransactionError::Abort)?;
+                old.replaced_by = Some(new_hash.to_string());
+
+                let old_bytes =
+                    Self::serialize(&old).map_err(ConflictableTransactionError::Abort)?;
+                let new_bytes =
+                    Self::serialize(&rotated).map_err(ConflictableTransactionError::Abort)?;
+                tx.insert(old_key.as_bytes(), old_bytes)?;
+                tx.insert(new_key.as_bytes(), new_bytes)?;
+                tx.insert(index_key.as_bytes(), &[] as &[u8])?;
+                Ok(RefreshRotation::Rotated(rotated))
+            })
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })
+    }
+
+    fn user_refresh_token_hashes(&self, user_id: &str) -> Result<Vec<String>, StoreError> {
+        let prefix = keys::session_user_index_prefix(user_id)?;
+        let mut hashes = Vec::new();
+        for item in self.refresh_tokens.scan_prefix(prefix.as_bytes()) {
+            let (k, _) = item?;
+            hashes.push(String::from_utf8_lossy(&k[prefix.len()..]).to_string());
+        }
+        Ok(hashes)
+    }
+
+    fn revoke_refresh_tokens_where(
+        &self,
+        user_id: &str,
+        should_revoke: impl Fn(&RefreshToken) -> bool,
+    ) -> Result<usize, StoreError> {
+        let mut revoked = 0;
+        for hash in self.user_refresh_token_hashes(user_id)? {
+            let key = keys::session_key(&hash)?;
+            let Some(raw) = self.refresh_tokens.get(key.as_bytes())? else {
+                continue;
+            };
+            let mut token: RefreshToken = Self::deserialize(&raw)?;
+            if token.revoked || !should_revoke(&token) {
+                continue;
+            }
+            token.revoked = true;
+            self.refresh_tokens
+                .insert(key.as_bytes(), Self::serialize(&token)?)?;
+            revoked += 1;
+        }
+        Ok(revoked)
+    }
+
+    /// 撤销同一 family 下的全部刷新令牌，返回撤销数量
+    pub fn revoke_refresh_token_family(
+        &self,
+        user_id: &str,
+        family_id: &str,
+    ) -> Result<usize, StoreError> {
+        self.revoke_refresh_tokens_where(user_id, |t| t.family_id == family_id)
+    }
+
+    /// 撤销用户除 `keep_family` 外的全部刷新令牌
+    pub fn revoke_refresh_tokens_except_family(
+        &self,
+        user_id: &str,
+        keep_family: Option<&str>,
+    ) -> Result<usize, StoreError> {
+        self.revoke_refresh_tokens_where(user_id, |t| Some(t.family_id.as_str()) != keep_family)
+    }
+
+    /// 删除用户全部刷新令牌（登出、封禁、改密时调用），返回删除数量
+    pub fn delete_user_refresh_tokens(&self, user_id: &str) -> Result<u32, StoreError> {
+        let hashes = self.user_refresh_token_hashes(user_id)?;
+        let mut batch = sled::Batch::default();
+        for hash in &hashes {
+            batch.remove(keys::session_key(hash)?.as_bytes());
+            batch.remove(keys::session_user_index_key(user_id, hash)?.as_bytes());
+        }
+        self.refresh_tokens.apply_batch(batch)?;
+        Ok(hashes.len() as u32)
+    }
+
+    /// 清理已过期的刷新令牌（含已轮换的旧令牌），每批最多 1000 条
+    pub fn cleanup_expired_refresh_tokens(&self) -> Result<u32, StoreError> {
+        const MAX_BATCH_SIZE: usize = 1000;
+
+        let now = Utc::now();
+        let mut batch = sled::Batch::default();
+        let mut count = 0;
+        for item in self.refresh_tokens.iter() {
+            let (k, v) = item?;
+            if k.starts_with(b"user:") {
+                continue;
+            }
+            let token: RefreshToken = Self::deserialize(&v)?;
+            if token.expires_at > now {
+                continue;
+            }
+            batch.remove(k);
+            batch.remove(
+                keys::session_user_index_key(&token.user_id, &token.token_hash)?.as_bytes(),
+            );
+            count += 1;
+            if count >= MAX_BATCH_SIZE {
+                break;
+            }
+        }
+        self.refresh_tokens.apply_batch(batch)?;
+        Ok(count as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn reuse_of_rotated_token_is_detected() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("refresh-db").to_str().unwrap()).unwrap();
+        let expires_at = Utc::now() + Duration::hours(1);
+        store
+            .create_refresh_token(&RefreshToken {
+                token_hash: "h1".to_string(),
+                user_id: "u1".to_string(),
+                family_id: "f1".to_string(),
+                created_at: Utc::now(),
+                expires_at,
+                replaced_by: None,
+                revoked: false,
+            })
+            .unwrap();
+
+        let rotated = store.rotate_refresh_token("h1", "h2", expires_at).unwrap();
+        assert!(matches!(rotated, RefreshRotation::Rotated(ref t) if t.family_id == "f1"));
+        let reused = store.rotate_refresh_token("h1", "h3", expires_at).unwrap();
+        assert!(
+            matches!(reused, RefreshRotation::Reused { ref family_id, .. } if family_id == "f1")
+        );
+
+        assert_eq!(store.revoke_refresh_token_family("u1", "f1").unwrap(), 2);
+        let after_revoke = store.rotate_refresh_token("h2", "h4", expires_at).unwrap();
+        assert!(matches!(after_revoke, RefreshRotation::Invalid));
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 按注册周分组的一个用户群
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCohort {
+    /// 注册周的周一（UTC）
+    pub week_start: NaiveDate,
+    pub size: usize,
+    /// 第 k 项为注册后第 k 周内提交过学习记录的人数，第 0 周即注册当周
+    pub active: Vec<usize>,
+    /// `active / size`，与 `active` 一一对应
+    pub retention: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    pub weeks: usize,
+    pub generated_at: DateTime<Utc>,
+    /// 按注册周升序；较新的群只包含已经过去（含当前未满）的周
+    pub cohorts: Vec<RetentionCohort>,
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+impl Store {
+    /// 最近 `weeks` 个注册周的留存矩阵。注册用户按 `users_by_created_at` 倒序扫描到窗口起点为止，
+    /// 活跃度来自窗口内的 `records_by_time` 索引，不逐用户读取记录
+    pub fn cohort_retention(
+        &self,
+        weeks: usize,
+        now: DateTime<Utc>,
+    ) -> Result<RetentionReport, StoreError> {
+        let weeks = weeks.max(1);
+        let first_week = week_start(now.date_naive()) - Duration::weeks(weeks as i64 - 1);
+        let window_start = first_week.and_time(NaiveTime::MIN).and_utc();
+        let week_index = |at: DateTime<Utc>| ((at - window_start).num_days() / 7) as usize;
+
+        let mut cohort_of: HashMap<String, usize> = HashMap::new();
+        let mut sizes = vec![0usize; weeks];
+        for item in self.users_by_created_at.iter() {
+            let (_, value) = item?;
+            let user_id = String::from_utf8(value.to_vec()).unwrap_or_default();
+            let Some(user) = self.get_user_by_id(&user_id)? else {
+                continue;
+            };
+            if user.created_at < window_start {
+                break;
+            }
+            let cohort = week_index(user.created_at).min(weeks - 1);
+            sizes[cohort] += 1;
+            cohort_of.insert(user.id, cohort);
+        }
+
+        let mut active: Vec<Vec<HashSet<String>>> = (0..weeks)
+            .map(|cohort| vec![HashSet::new(); weeks - cohort])
+            .collect();
+        let start_key = keys::records_by_time_since_key(window_start.timestamp_millis());
+        for item in self.records_by_time.range(start_key.as_bytes()..) {
+            let (key, value) = item?;
+            let user_id = String::from_utf8_lossy(&value);
+            let Some(&cohort) = cohort_of.get(user_id.as_ref()) else {
+                continue;
+            };
+            let Some(ts) = std::str::from_utf8(&key[..key.len().min(20)])
+                .ok()
+                .and_then(|s| s.parse::<i64>().ok())
+                .and_then(DateTime::<Utc>::from_timestamp_millis)
+            else {
+                continue;
+            };
+            let week = week_index(ts);
+            if let Some(users) = week
+                .checked_sub(cohort)
+                .and_then(|offset| active[cohort].get_mut(offset))
+            {
+                users.insert(user_id.into_owned());
+            }
+        }
+
+        let cohorts = active
+            .into_iter()
+            .enumerate()
+            .map(|(cohort, users)| {
+                let size = sizes[cohort];
+                let active: Vec<usize> = users.iter().map(HashSet::len).collect();
+                RetentionCohort {
+                    week_start: first_week + Duration::weeks(cohort as i64),
+                    size,
+                    retention: active
+                        .iter()
+                        .map(|&n| {
+                            if size > 0 {
+                                n as f64 / size as f64
+                            } else {
+                                0.0
+                            }
+                        })
+                        .collect(),
+                    active,
+                }
+            })
+            .collect();
+        Ok(RetentionReport {
+            weeks,
+            generated_at: now,
+            cohorts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::operations::test_fixtures::{sample_record, sample_user};
+
+    fn at(date: &str) -> DateTime<Utc> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn builds_weekly_retention_matrix() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("retention").to_str().unwrap()).unwrap();
+        // 2026-03-18 为周三，三周窗口从 03-02 所在周开始
+        let now = at("2026-03-18");
+        store
+            .create_user(&sample_user("old", at("2026-02-10")))
+            .unwrap();
+        store
+            .create_user(&sample_user("a", at("2026-03-03")))
+            .unwrap();
+        store
+            .create_user(&sample_user("b", at("2026-03-05")))
+            .unwrap();
+        store
+            .create_user(&sample_user("c", at("2026-03-17")))
+            .unwrap();
+        for (id, user_id, date) in [
+            ("r1", "a", "2026-03-03"),
+            ("r2", "a", "2026-03-10"),
+            ("r3", "a", "2026-03-11"),
+            ("r4", "b", "2026-03-16"),
+            ("r5", "old", "2026-03-16"),
+        ] {
+            store
+                .create_record(&sample_record(id, user_id, "w1", at(date)))
+                .unwrap();
+        }
+
+        let report = store.cohort_retention(3, now).unwrap();
+        let first = &report.cohorts[0];
+        assert_eq!(
+            first.week_start,
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+        );
+        assert_eq!(first.size, 2);
+        assert_eq!(first.active, [1, 1, 1]);
+        assert_eq!(first.retention, [0.5, 0.5, 0.5]);
+        assert_eq!(report.cohorts[1].size, 0);
+        assert_eq!(report.cohorts[1].retention, [0.0, 0.0]);
+        assert_eq!(report.cohorts[2].size, 1);
+        assert_eq!(report.cohorts[2].active, [0]);
+    }
+}
@@ -13,6 +13,16 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub revoked: bool,
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// 签发此访问会话的刷新令牌链，撤销会话时一并撤销
+    #[serde(default)]
+    pub family_id: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// 客户端 IP 的 HMAC 摘要，不保存明文 IP
+    #[serde(default)]
+    pub ip_hash: Option<String>,
 }
 
 impl Store {
@@ -141,6 +151,93 @@ impl Store {
         for hash in hashes {
             self.delete_session(&hash)?;
         }
+        Ok(count + self.delete_user_refresh_tokens(user_id)?)
+    }
+
+    /// 列出用户有效的访问会话，按创建时间倒序
+    pub fn list_user_sessions(&self, user_id: &str) -> Result<Vec<Session>, StoreError> {
+        let prefix = keys::session_user_index_prefix(user_id)?;
+        let mut sessions = Vec::new();
+        for item in self.sessions.scan_prefix(prefix.as_bytes()) {
+            let (k, _) = item?;
+            let hash = String::from_utf8_lossy(&k[prefix.len()..]).to_string();
+            if let Some(session) = self.get_session(&hash)? {
+                if session.token_type == "user" {
+                    sessions.push(session);
+                }
+            }
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        Ok(sessions)
+    }
+
+    /// 更新会话最近活跃时间。用 CAS 写回，期间被登出或吊销的会话不会被重新写入
+    pub fn touch_session(&self, token_hash: &str, at: DateTime<Utc>) -> Result<(), StoreError> {
+        let key = keys::session_key(token_hash)?;
+        match Self::cas_update(&self.sessions, "Session", &key, |session: &mut Session| {
+            session.last_seen_at = Some(at);
+            Some(())
+        }) {
+            Ok(_) | Err(StoreError::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 删除同一刷新令牌链下的访问会话（令牌轮换后旧访问令牌随之失效）
+    pub fn delete_family_sessions(
+        &self,
+        user_id: &str,
+        family_id: &str,
+    ) -> Result<u32, StoreError> {
+        let mut count = 0;
+        for session in self.list_user_sessions(user_id)? {
+            if session.family_id.as_deref() == Some(family_id) {
+                self.delete_session(&session.token_hash)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 撤销用户的单个会话及其刷新令牌链；会话不存在或不属于该用户时返回 false
+    pub fn revoke_user_session(&self, user_id: &str, token_hash: &str) -> Result<bool, StoreError> {
+        let Some(session) = self.get_session(token_hash)? else {
+            return Ok(false);
+        };
+        if session.user_id != user_id {
+            return Ok(false);
+        }
+        self.delete_session(token_hash)?;
+        if let Some(family_id) = session.family_id {
+            self.revoke_refresh_token_family(user_id, &family_id)?;
+            self.delete_family_sessions(user_id, &family_id)?;
+        }
+        Ok(true)
+    }
+
+    /// 撤销除 `keep_hash` 所在设备外的全部会话及刷新令牌，返回撤销的会话数
+    pub fn revoke_other_user_sessions(
+        &self,
+        user_id: &str,
+        keep_hash: &str,
+    ) -> Result<u32, StoreError> {
+        let sessions = self.list_user_sessions(user_id)?;
+        let keep_family = sessions
+            .iter()
+            .find(|s| s.token_hash == keep_hash)
+            .and_then(|s| s.family_id.clone());
+
+        let mut count = 0;
+        for session in sessions {
+            if session.token_hash == keep_hash
+                || (keep_family.is_some() && session.family_id == keep_family)
+            {
+                continue;
+            }
+            self.delete_session(&session.token_hash)?;
+            count += 1;
+        }
+        self.revoke_refresh_tokens_except_family(user_id, keep_family.as_deref())?;
         Ok(count)
     }
 
@@ -310,6 +407,10 @@ mod tests {
             created_at: Utc::now(),
             expires_at: Utc::now() + Duration::hours(expires_in_hours),
             revoked: false,
+            last_seen_at: None,
+            family_id: None,
+            user_agent: None,
+            ip_hash: None,
         }
     }
 
@@ -344,4 +445,24 @@ mod tests {
         assert!(store.get_session("h_expired").unwrap().is_none());
         assert!(store.get_session("h_alive").unwrap().is_some());
     }
+
+    #[test]
+    fn touch_does_not_recreate_deleted_session() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("sessions-db3").to_str().unwrap()).unwrap();
+
+        store
+            .create_session(&sample_session("h1", "u1", 1))
+            .unwrap();
+        let now = Utc::now();
+        store.touch_session("h1", now).unwrap();
+        assert_eq!(
+            store.get_session("h1").unwrap().unwrap().last_seen_at,
+            Some(now)
+        );
+
+        store.delete_session("h1").unwrap();
+        store.touch_session("h1", now).unwrap();
+        assert!(store.get_session("h1").unwrap().is_none());
+    }
 }
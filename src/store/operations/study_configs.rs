@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::constants::{DEFAULT_DAILY_MASTERY_TARGET, DEFAULT_DAILY_WORDS};
@@ -12,15 +13,24 @@ pub struct UserStudyConfig {
     pub daily_word_count: u32,
     pub study_mode: StudyMode,
     pub daily_mastery_target: u32,
+    /// 开启后 `/progress` 额外返回基于近期表现的每日单词数建议，不改动用户设置
+    #[serde(default)]
+    pub adaptive_daily_count: bool,
+    /// cram 模式截止时间，过期后自动回到 normal
+    #[serde(default)]
+    pub cram_until: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum StudyMode {
+    #[default]
     Normal,
     Intensive,
     Review,
     Casual,
+    /// 考前突击：前置新词并缩短复习间隔，牺牲长期保持换取覆盖面，必须设置截止时间
+    Cram,
 }
 
 impl Default for UserStudyConfig {
@@ -31,15 +41,31 @@ impl Default for UserStudyConfig {
             daily_word_count: DEFAULT_DAILY_WORDS,
             study_mode: StudyMode::Normal,
             daily_mastery_target: DEFAULT_DAILY_MASTERY_TARGET,
+            adaptive_daily_count: false,
+            cram_until: None,
         }
     }
 }
 
+impl UserStudyConfig {
+    pub fn is_cram_active(&self, now: DateTime<Utc>) -> bool {
+        self.study_mode == StudyMode::Cram && self.cram_until.is_some_and(|until| until > now)
+    }
+}
+
 impl Store {
     pub fn get_study_config(&self, user_id: &str) -> Result<UserStudyConfig, StoreError> {
         let key = keys::study_config_key(user_id)?;
         match self.study_configs.get(key.as_bytes())? {
-            Some(raw) => Ok(Self::deserialize(&raw)?),
+            Some(raw) => {
+                let mut config: UserStudyConfig = Self::deserialize(&raw)?;
+                // cram 到期后读取时即视为 normal，避免长期扭曲记忆模型
+                if config.study_mode == StudyMode::Cram && !config.is_cram_active(Utc::now()) {
+                    config.study_mode = StudyMode::Normal;
+                    config.cram_until = None;
+                }
+                Ok(config)
+            }
             None => {
                 Ok(UserStudyConfig {
                     user_id: user_id.to_string(),
@@ -49,6 +75,19 @@ impl Store {
         }
     }
 
+    /// 仅在用户尚无学习设置时写入，返回是否写入；用于注册时应用默认设置，不覆盖已有设置
+    pub fn init_study_config(&self, config: &UserStudyConfig) -> Result<bool, StoreError> {
+        let key = keys::study_config_key(&config.user_id)?;
+        Ok(self
+            .study_configs
+            .compare_and_swap(
+                key.as_bytes(),
+                None as Option<&[u8]>,
+                Some(Self::serialize(config)?),
+            )?
+            .is_ok())
+    }
+
     pub fn set_study_config(&self, config: &UserStudyConfig) -> Result<(), StoreError> {
         let key = keys::study_config_key(&config.user_id)?;
         self.study_configs
@@ -56,3 +95,24 @@ impl Store {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_study_config_keeps_existing_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("study-configs").to_str().unwrap()).unwrap();
+        let mut config = UserStudyConfig {
+            user_id: "u1".to_string(),
+            daily_word_count: 40,
+            ..Default::default()
+        };
+        assert!(store.init_study_config(&config).unwrap());
+
+        config.daily_word_count = 5;
+        assert!(!store.init_study_config(&config).unwrap());
+        assert_eq!(store.get_study_config("u1").unwrap().daily_word_count, 40);
+    }
+}
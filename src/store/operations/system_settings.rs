@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{DEFAULT_DAILY_WORDS, DEFAULT_MAX_USERS};
+use crate::constants::{DEFAULT_DAILY_MASTERY_TARGET, DEFAULT_DAILY_WORDS, DEFAULT_MAX_USERS};
 use crate::store::keys;
+use crate::store::operations::study_configs::{StudyMode, UserStudyConfig};
 use crate::store::{Store, StoreError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,10 +12,20 @@ pub struct SystemSettings {
     pub registration_enabled: bool,
     pub maintenance_mode: bool,
     pub default_daily_words: u32,
+    /// 新用户注册时的每日掌握目标
+    #[serde(default = "default_mastery_target")]
+    pub default_mastery_target: u32,
+    /// 新用户注册时的学习模式，不能为 cram
+    #[serde(default)]
+    pub default_study_mode: StudyMode,
     #[serde(default)]
     pub wordbook_center_url: Option<String>,
 }
 
+fn default_mastery_target() -> u32 {
+    DEFAULT_DAILY_MASTERY_TARGET
+}
+
 fn default_wordbook_center_url() -> Option<String> {
     Some("https://cdn.jsdelivr.net/gh/Heartcoolman/wordbook-center@main".to_string())
 }
@@ -26,11 +37,26 @@ impl Default for SystemSettings {
             registration_enabled: true,
             maintenance_mode: false,
             default_daily_words: DEFAULT_DAILY_WORDS,
+            default_mastery_target: DEFAULT_DAILY_MASTERY_TARGET,
+            default_study_mode: StudyMode::Normal,
             wordbook_center_url: Some("https://cdn.jsdelivr.net/gh/Heartcoolman/wordbook-center@main".to_string()),
         }
     }
 }
 
+impl SystemSettings {
+    /// 注册时为新用户写入的学习设置
+    pub fn default_study_config(&self, user_id: &str) -> UserStudyConfig {
+        UserStudyConfig {
+            user_id: user_id.to_string(),
+            daily_word_count: self.default_daily_words,
+            daily_mastery_target: self.default_mastery_target,
+            study_mode: self.default_study_mode.clone(),
+            ..Default::default()
+        }
+    }
+}
+
 impl Store {
     pub fn get_system_settings(&self) -> Result<SystemSettings, StoreError> {
         let key = keys::config_latest_key("system_settings")?;
@@ -55,8 +81,6 @@ impl Store {
 
     pub fn save_system_settings(&self, settings: &SystemSettings) -> Result<(), StoreError> {
         let key = keys::config_latest_key("system_settings")?;
-        self.config_versions
-            .insert(key.as_bytes(), Self::serialize(settings)?)?;
-        Ok(())
+        Self::put_typed(&self.config_versions, &key, settings)
     }
 }
@@ -0,0 +1,59 @@
+//! 单元测试共用的用户、单词与作答记录构造
+
+use chrono::{DateTime, Utc};
+
+use crate::store::operations::records::LearningRecord;
+use crate::store::operations::users::User;
+use crate::store::operations::words::Word;
+
+/// 邮箱为 `{id}@example.com`，用户名同 ID
+pub fn sample_user(id: &str, created_at: DateTime<Utc>) -> User {
+    User {
+        id: id.to_string(),
+        email: format!("{id}@example.com"),
+        username: id.to_string(),
+        password_hash: "hash".to_string(),
+        is_banned: false,
+        created_at,
+        updated_at: created_at,
+        failed_login_count: 0,
+        locked_until: None,
+        lockout_level: 0,
+    }
+}
+
+/// 文本同 ID、难度 0.5、无例句与标签
+pub fn sample_word(id: &str, created_at: DateTime<Utc>) -> Word {
+    Word {
+        id: id.to_string(),
+        text: id.to_string(),
+        meaning: "meaning".to_string(),
+        pronunciation: None,
+        part_of_speech: None,
+        difficulty: 0.5,
+        examples: vec![],
+        tags: vec![],
+        embedding: None,
+        created_at,
+    }
+}
+
+/// 答对、耗时 1 秒、不属于任何会话的作答记录
+pub fn sample_record(
+    id: &str,
+    user_id: &str,
+    word_id: &str,
+    created_at: DateTime<Utc>,
+) -> LearningRecord {
+    LearningRecord {
+        id: id.to_string(),
+        user_id: user_id.to_string(),
+        word_id: word_id.to_string(),
+        is_correct: true,
+        response_time_ms: 1000,
+        session_id: None,
+        created_at,
+        algorithm_id: None,
+        algorithm_weights: Default::default(),
+    }
+}
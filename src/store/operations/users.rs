@@ -1,10 +1,16 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use sled::Transactional;
 
-use crate::constants::{LOCKOUT_DURATION_MINUTES, MAX_CAS_RETRIES, MAX_FAILED_LOGIN_ATTEMPTS};
+use crate::config::LockoutPolicy;
+use crate::constants::MAX_CAS_RETRIES;
 use crate::store::keys;
+use crate::store::operations::counters::{self, Counter};
 use crate::store::{Store, StoreError};
 
+/// 匿名化账户使用的邮箱后缀（`.invalid` 保留域名，不会与真实邮箱冲突）
+pub const ANONYMIZED_EMAIL_SUFFIX: &str = "@anonymized.invalid";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
@@ -21,22 +27,30 @@ pub struct User {
     pub failed_login_count: u32,
     #[serde(default)]
     pub locked_until: Option<DateTime<Utc>>,
+    /// 连续锁定周期数，用于递增锁定时长；登录成功或长期未再锁定后清零
+    #[serde(default)]
+    pub lockout_level: u32,
+}
+
+/// 待验证的邮箱变更请求，按令牌哈希存储
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailChangeEntry {
+    pub user_id: String,
+    pub new_email: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn is_anonymized(&self) -> bool {
+        self.email.ends_with(ANONYMIZED_EMAIL_SUFFIX)
+    }
 }
 
 impl Store {
-    /// 统计用户数量。
-    /// 利用 users tree 的 len() 和 email 索引前缀扫描来高效计算：
-    /// 用户数 = 总条目数 - email 索引条目数。
-    /// 这避免了全表反序列化，但仍需遍历 email 前缀来计数索引条目。
-    /// TODO: 如果性能仍不够，可维护单独的原子计数器。
+    /// 统计用户数量，读取随增删维护的计数器
     pub fn count_users(&self) -> Result<usize, StoreError> {
-        let total = self.users.len();
-        let mut email_index_count = 0usize;
-        for item in self.users.scan_prefix(b"email:") {
-            let _ = item?;
-            email_index_count += 1;
-        }
-        Ok(total - email_index_count)
+        Ok(self.read_counter(Counter::Users)? as usize)
     }
 
     pub fn create_user(&self, user: &User) -> Result<(), StoreError> {
@@ -45,14 +59,16 @@ impl Store {
         let uid_bytes = user.id.as_bytes().to_vec();
         let user_bytes = Self::serialize(user)?;
 
-        self.users
-            .transaction(move |tx| {
+        (&self.users, &self.counters)
+            .transaction(move |(tx, tx_counters)| {
                 // Check email uniqueness inside the transaction
                 if tx.get(email_key.as_bytes())?.is_some() {
                     return sled::transaction::abort(());
                 }
                 tx.insert(email_key.as_bytes(), uid_bytes.as_slice())?;
-                tx.insert(user_key.as_bytes(), user_bytes.as_slice())?;
+                if tx.insert(user_key.as_bytes(), user_bytes.as_slice())?.is_none() {
+                    counters::adjust_in_tx(tx_counters, Counter::Users, 1)?;
+                }
                 Ok(())
             })
             .map_err(|e: sled::transaction::TransactionError<()>| match e {
@@ -75,11 +91,7 @@ impl Store {
     }
 
     pub fn get_user_by_id(&self, user_id: &str) -> Result<Option<User>, StoreError> {
-        let key = keys::user_key(user_id)?;
-        match self.users.get(key.as_bytes())? {
-            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
-            None => Ok(None),
-        }
+        Self::get_typed(&self.users, &keys::user_key(user_id)?)
     }
 
     pub fn get_user_by_email(&self, email: &str) -> Result<Option<User>, StoreError> {
@@ -169,67 +181,29 @@ impl Store {
     /// 原子性地封禁用户，使用 compare_and_swap 避免竞态条件
     pub fn ban_user(&self, user_id: &str) -> Result<(), StoreError> {
         let user_key = keys::user_key(user_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.users
-                    .get(user_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "user".to_string(),
-                        key: user_id.to_string(),
-                    })?;
-            let mut user: User = Self::deserialize(&old_raw)?;
+        Self::cas_update(&self.users, "user", &user_key, |user: &mut User| {
             if user.is_banned {
-                return Ok(()); // 已封禁，幂等返回
+                return None; // 已封禁，幂等返回
             }
             user.is_banned = true;
             user.updated_at = Utc::now();
-            let new_raw = Self::serialize(&user)?;
-            match self
-                .users
-                .compare_and_swap(user_key.as_bytes(), Some(old_raw), Some(new_raw))?
-            {
-                Ok(()) => return Ok(()),
-                Err(_) => continue, // 数据已被其他操作修改，重试
-            }
-        }
-        Err(StoreError::CasRetryExhausted {
-            entity: "user".to_string(),
-            key: user_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+            Some(())
+        })?;
+        Ok(())
     }
 
     /// 原子性地解封用户，使用 compare_and_swap 避免竞态条件
     pub fn unban_user(&self, user_id: &str) -> Result<(), StoreError> {
         let user_key = keys::user_key(user_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.users
-                    .get(user_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "user".to_string(),
-                        key: user_id.to_string(),
-                    })?;
-            let mut user: User = Self::deserialize(&old_raw)?;
+        Self::cas_update(&self.users, "user", &user_key, |user: &mut User| {
             if !user.is_banned {
-                return Ok(()); // 未封禁，幂等返回
+                return None; // 未封禁，幂等返回
             }
             user.is_banned = false;
             user.updated_at = Utc::now();
-            let new_raw = Self::serialize(&user)?;
-            match self
-                .users
-                .compare_and_swap(user_key.as_bytes(), Some(old_raw), Some(new_raw))?
-            {
-                Ok(()) => return Ok(()),
-                Err(_) => continue, // 数据已被其他操作修改，重试
-            }
-        }
-        Err(StoreError::CasRetryExhausted {
-            entity: "user".to_string(),
-            key: user_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+            Some(())
+        })?;
+        Ok(())
     }
 
     /// 仅列出用户 ID，跳过 email 索引条目，避免不必要的用户对象反序列化和排序。
@@ -289,87 +263,93 @@ impl Store {
     }
 
     /// 记录一次登录失败，返回账户是否因此被锁定
-    pub fn record_failed_login(&self, user_id: &str) -> Result<bool, StoreError> {
+    pub fn record_failed_login(
+        &self,
+        user_id: &str,
+        policy: &LockoutPolicy,
+    ) -> Result<bool, StoreError> {
         let user_key = keys::user_key(user_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.users
-                    .get(user_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "user".to_string(),
-                        key: user_id.to_string(),
-                    })?;
-            let mut user: User = Self::deserialize(&old_raw)?;
+        let locked = Self::cas_update(&self.users, "user", &user_key, |user: &mut User| {
+            let now = Utc::now();
             user.failed_login_count += 1;
-            let locked = user.failed_login_count >= MAX_FAILED_LOGIN_ATTEMPTS;
-            if locked {
-                user.locked_until = Some(Utc::now() + Duration::minutes(LOCKOUT_DURATION_MINUTES));
-            }
-            user.updated_at = Utc::now();
-            let new_raw = Self::serialize(&user)?;
-            match self
-                .users
-                .compare_and_swap(user_key.as_bytes(), Some(old_raw), Some(new_raw))?
-            {
-                Ok(()) => return Ok(locked),
-                Err(_) => continue,
+            let locked = user.failed_login_count >= policy.max_failed_attempts;
+            // 递增模式下锁定期内的失败不再叠加级别，避免被恶意尝试迅速推到上限
+            let already_locked = user.locked_until.is_some_and(|until| until > now);
+            if locked && !(policy.escalation_enabled && already_locked) {
+                let clean_since_last_lock = user.locked_until.map_or(true, |until| {
+                    now - until >= Duration::hours(policy.reset_after_hours)
+                });
+                user.lockout_level = if clean_since_last_lock {
+                    1
+                } else {
+                    user.lockout_level.saturating_add(1)
+                };
+                user.locked_until = Some(now + policy.lock_duration(user.lockout_level));
             }
-        }
-        Err(StoreError::CasRetryExhausted {
-            entity: "user".to_string(),
-            key: user_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+            user.updated_at = now;
+            Some(locked)
+        })?;
+        Ok(locked.unwrap_or(false))
     }
 
     /// 重置登录失败计数（登录成功时调用）
     pub fn reset_login_attempts(&self, user_id: &str) -> Result<(), StoreError> {
         let user_key = keys::user_key(user_id)?;
-        for _ in 0..MAX_CAS_RETRIES {
-            let old_raw =
-                self.users
-                    .get(user_key.as_bytes())?
-                    .ok_or_else(|| StoreError::NotFound {
-                        entity: "user".to_string(),
-                        key: user_id.to_string(),
-                    })?;
-            let mut user: User = Self::deserialize(&old_raw)?;
-            if user.failed_login_count == 0 && user.locked_until.is_none() {
-                return Ok(()); // 无需更新
+        Self::cas_update(&self.users, "user", &user_key, |user: &mut User| {
+            if user.failed_login_count == 0
+                && user.locked_until.is_none()
+                && user.lockout_level == 0
+            {
+                return None; // 无需更新
             }
             user.failed_login_count = 0;
             user.locked_until = None;
+            user.lockout_level = 0;
             user.updated_at = Utc::now();
-            let new_raw = Self::serialize(&user)?;
-            match self
-                .users
-                .compare_and_swap(user_key.as_bytes(), Some(old_raw), Some(new_raw))?
-            {
-                Ok(()) => return Ok(()),
-                Err(_) => continue,
-            }
+            Some(())
+        })?;
+        Ok(())
+    }
+
+    /// 最近活跃时间：最新学习记录、会话最近使用时间与注册时间中的最大者
+    pub fn user_last_active_at(&self, user: &User) -> Result<DateTime<Utc>, StoreError> {
+        let mut last = user.created_at;
+        if let Some(record) = self.get_user_records(&user.id, 1)?.first() {
+            last = last.max(record.created_at);
         }
-        Err(StoreError::CasRetryExhausted {
-            entity: "user".to_string(),
-            key: user_id.to_string(),
-            attempts: MAX_CAS_RETRIES,
-        })
+        for session in self.list_user_sessions(&user.id)? {
+            last = last.max(session.last_seen_at.unwrap_or(session.created_at));
+        }
+        Ok(last)
     }
 
-    /// 检查账户是否处于锁定状态
-    pub fn is_account_locked(&self, user_id: &str) -> Result<bool, StoreError> {
+    /// 抹去邮箱、用户名与密码并封禁账户，同时删除会话、画像、头像、通知、偏好等个人数据；
+    /// 学习记录保留用于匿名统计
+    pub fn anonymize_user(&self, user_id: &str) -> Result<(), StoreError> {
+        let mut user = self
+            .get_user_by_id(user_id)?
+            .ok_or_else(|| StoreError::NotFound {
+                entity: "user".to_string(),
+                key: user_id.to_string(),
+            })?;
+        user.email = format!("{user_id}{ANONYMIZED_EMAIL_SUFFIX}");
+        user.username = "已注销用户".to_string();
+        user.password_hash = String::new();
+        user.is_banned = true;
+        user.updated_at = Utc::now();
+        self.update_user(&user)?;
+        self.delete_personal_data(user_id)
+    }
+
+    /// 账户仍处于锁定期时返回锁定截止时间
+    pub fn account_locked_until(&self, user_id: &str) -> Result<Option<DateTime<Utc>>, StoreError> {
         let user = self
             .get_user_by_id(user_id)?
             .ok_or_else(|| StoreError::NotFound {
                 entity: "user".to_string(),
                 key: user_id.to_string(),
             })?;
-        if let Some(locked_until) = user.locked_until {
-            if locked_until > Utc::now() {
-                return Ok(true);
-            }
-        }
-        Ok(false)
+        Ok(user.locked_until.filter(|until| *until > Utc::now()))
     }
 
     /// 删除用户及其所有关联数据。
@@ -391,9 +371,11 @@ impl Store {
         let email_key = keys::user_email_index_key(&user.email)?;
         let uk = user_key.clone();
         let ek = email_key.clone();
-        self.users
-            .transaction(move |tx| {
-                tx.remove(uk.as_bytes())?;
+        (&self.users, &self.counters)
+            .transaction(move |(tx, tx_counters)| {
+                if tx.remove(uk.as_bytes())?.is_some() {
+                    counters::adjust_in_tx(tx_counters, Counter::Users, -1)?;
+                }
                 tx.remove(ek.as_bytes())?;
                 Ok(())
             })
@@ -417,15 +399,19 @@ impl Store {
             let _ = self.user_stats.remove(stats_key.as_bytes());
         }
 
-        // 2. 删除用户会话
-        if let Err(e) = self.delete_user_sessions(user_id) {
-            tracing::warn!(user_id, error = %e, "删除用户会话失败");
-        }
+        // 2. 删除会话、画像、通知、偏好等个人数据
+        self.delete_personal_data(user_id)?;
 
         // 3. 删除学习记录
         let record_prefix = keys::record_prefix(user_id)?;
+        let mut removed_records = 0i64;
         for (key, _) in self.records.scan_prefix(record_prefix.as_bytes()).flatten() {
-            let _ = self.records.remove(&key);
+            if let Ok(Some(_)) = self.records.remove(&key) {
+                removed_records += 1;
+            }
+        }
+        if let Err(e) = self.adjust_counter(Counter::Records, -removed_records) {
+            tracing::warn!(user_id, error = %e, "更新记录计数失败");
         }
 
         // 4. 删除单词学习状态及到期索引
@@ -449,56 +435,175 @@ impl Store {
             }
         }
 
-        // 5. 删除学习配置
+        // 5. 删除每日掌握数
+        if let Err(e) = self.delete_user_daily_mastered(user_id) {
+            tracing::warn!(user_id, error = %e, "删除每日掌握数失败");
+        }
+
+        // 6. 删除定级测试结果
+        if let Ok(placement_key) = keys::placement_key(user_id) {
+            let _ = self
+                .engine_algorithm_states
+                .remove(placement_key.as_bytes());
+        }
+
+        // 7. 删除徽章
+        let badge_prefix = keys::badge_prefix(user_id)?;
+        for (key, _) in self.badges.scan_prefix(badge_prefix.as_bytes()).flatten() {
+            let _ = self.badges.remove(&key);
+        }
+
+        // 8. 删除广播送达记录，避免被统计为已删除通知
+        if let Err(e) = self.delete_user_broadcast_deliveries(user_id) {
+            tracing::warn!(user_id, error = %e, "删除广播送达记录失败");
+        }
+
+        // 9. 删除学习会话索引
+        let ls_prefix = keys::learning_session_user_index_prefix(user_id)?;
+        for (key, _) in self.learning_sessions.scan_prefix(ls_prefix.as_bytes()).flatten() {
+            let key_str = String::from_utf8(key.to_vec()).unwrap_or_default();
+            if let Some(session_id) = key_str.rsplit(':').next() {
+                if let Ok(sk) = keys::learning_session_key(session_id) {
+                    let _ = self.learning_sessions.remove(sk.as_bytes());
+                }
+            }
+            let _ = self.learning_sessions.remove(&key);
+        }
+
+        tracing::info!(user_id, "用户及关联数据已删除");
+        Ok(())
+    }
+
+    /// 删除会话、学习配置、引擎状态（含认知与时段画像）、算法开关覆盖与修复日志、头像、通知、偏好、webhook、
+    /// 周报与待确认的邮箱变更等个人数据；学习记录、单词状态与徽章等不在此列
+    fn delete_personal_data(&self, user_id: &str) -> Result<(), StoreError> {
+        if let Err(e) = self.delete_user_sessions(user_id) {
+            tracing::warn!(user_id, error = %e, "删除用户会话失败");
+        }
+
         if let Ok(config_key) = keys::study_config_key(user_id) {
             let _ = self.study_configs.remove(config_key.as_bytes());
         }
 
-        // 6. 删除引擎用户状态
         if let Err(e) = self.delete_engine_user_state(user_id) {
             tracing::warn!(user_id, error = %e, "删除引擎用户状态失败");
         }
+        if let Ok(flags_key) = keys::feature_flag_overrides_key(user_id) {
+            let _ = self.engine_algorithm_states.remove(flags_key.as_bytes());
+        }
+        if let Err(e) = self.delete_user_algo_state_repairs(user_id) {
+            tracing::warn!(user_id, error = %e, "删除算法状态修复日志失败");
+        }
 
-        // 7. 删除用户画像
         if let Ok(profile_key) = keys::user_profile_key(user_id) {
             let _ = self.user_profiles.remove(profile_key.as_bytes());
         }
+        if let Ok(Some(filename)) = self.delete_user_avatar(user_id) {
+            if let Err(e) = crate::services::avatar::remove_avatar_file(&filename) {
+                tracing::warn!(user_id, error = %e, "删除头像文件失败");
+            }
+        }
         if let Ok(habit_key) = keys::habit_profile_key(user_id) {
             let _ = self.habit_profiles.remove(habit_key.as_bytes());
         }
 
-        // 8. 删除通知
         let notif_prefix = keys::notification_prefix(user_id)?;
         for (key, _) in self.notifications.scan_prefix(notif_prefix.as_bytes()).flatten() {
             let _ = self.notifications.remove(&key);
         }
 
-        // 9. 删除徽章
-        let badge_prefix = keys::badge_prefix(user_id)?;
-        for (key, _) in self.badges.scan_prefix(badge_prefix.as_bytes()).flatten() {
-            let _ = self.badges.remove(&key);
-        }
-
-        // 10. 删除用户偏好设置
         if let Ok(pref_key) = keys::user_preferences_key(user_id) {
             let _ = self.user_preferences.remove(pref_key.as_bytes());
         }
-
-        // 11. 删除学习会话索引
-        let ls_prefix = keys::learning_session_user_index_prefix(user_id)?;
-        for (key, _) in self.learning_sessions.scan_prefix(ls_prefix.as_bytes()).flatten() {
-            let key_str = String::from_utf8(key.to_vec()).unwrap_or_default();
-            if let Some(session_id) = key_str.rsplit(':').next() {
-                if let Ok(sk) = keys::learning_session_key(session_id) {
-                    let _ = self.learning_sessions.remove(sk.as_bytes());
-                }
-            }
-            let _ = self.learning_sessions.remove(&key);
+        if let Ok(webhook_key) = keys::user_webhook_key(user_id) {
+            let _ = self.user_webhooks.remove(webhook_key.as_bytes());
+        }
+        let report_prefix = keys::weekly_report_prefix(user_id)?;
+        for (key, _) in self
+            .user_weekly_reports
+            .scan_prefix(report_prefix.as_bytes())
+            .flatten()
+        {
+            let _ = self.user_weekly_reports.remove(&key);
+        }
+        let fatigue_prefix = keys::visual_fatigue_prefix(user_id)?;
+        for (key, _) in self
+            .visual_fatigue_history
+            .scan_prefix(fatigue_prefix.as_bytes())
+            .flatten()
+        {
+            let _ = self.visual_fatigue_history.remove(&key);
         }
+        let idempotency_prefix = keys::idempotency_prefix(user_id)?;
+        for (key, _) in self
+            .idempotency_keys
+            .scan_prefix(idempotency_prefix.as_bytes())
+            .flatten()
+        {
+            let _ = self.idempotency_keys.remove(&key);
+        }
+        let token_ids: Vec<_> = self
+            .email_change_tokens
+            .iter()
+            .flatten()
+            .filter(|(_, raw)| {
+                Self::deserialize::<EmailChangeEntry>(raw).is_ok_and(|e| e.user_id == user_id)
+            })
+            .map(|(key, _)| key)
+            .collect();
+        for key in token_ids {
+            let _ = self.email_change_tokens.remove(key);
+        }
+        Ok(())
+    }
 
-        tracing::info!(user_id, "用户及关联数据已删除");
+    /// 删除头像元数据，返回原头像文件名
+    pub fn delete_user_avatar(&self, user_id: &str) -> Result<Option<String>, StoreError> {
+        let key = keys::user_avatar_key(user_id)?;
+        let Some(raw) = self.user_profiles.remove(key.as_bytes())? else {
+            return Ok(None);
+        };
+        let metadata: serde_json::Value = Self::deserialize(&raw)?;
+        Ok(metadata
+            .get("filename")
+            .and_then(|v| v.as_str())
+            .map(str::to_string))
+    }
+
+    pub fn create_email_change_token(
+        &self,
+        token_hash: &str,
+        entry: &EmailChangeEntry,
+    ) -> Result<(), StoreError> {
+        let key = keys::email_change_key(token_hash)?;
+        self.email_change_tokens
+            .insert(key.as_bytes(), Self::serialize(entry)?)?;
         Ok(())
     }
+
+    /// 原子地取出属于该用户的邮箱变更令牌，保证同一令牌只能使用一次
+    pub fn take_email_change_token(
+        &self,
+        token_hash: &str,
+        user_id: &str,
+    ) -> Result<Option<EmailChangeEntry>, StoreError> {
+        let key = keys::email_change_key(token_hash)?;
+        let Some(raw) = self.email_change_tokens.get(key.as_bytes())? else {
+            return Ok(None);
+        };
+        let entry: EmailChangeEntry = Self::deserialize(&raw)?;
+        if entry.user_id != user_id {
+            return Ok(None);
+        }
+        match self.email_change_tokens.compare_and_swap(
+            key.as_bytes(),
+            Some(raw),
+            None as Option<&[u8]>,
+        )? {
+            Ok(()) => Ok(Some(entry)),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -507,18 +612,23 @@ mod tests {
     use tempfile::tempdir;
 
     use super::*;
+    use crate::store::operations::test_fixtures;
 
     fn sample_user(id: &str, email: &str) -> User {
         User {
-            id: id.to_string(),
             email: email.to_string(),
-            username: "demo".to_string(),
-            password_hash: "hash".to_string(),
-            is_banned: false,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            failed_login_count: 0,
-            locked_until: None,
+            ..test_fixtures::sample_user(id, Utc::now())
+        }
+    }
+
+    fn sample_repair(user_id: &str) -> crate::store::operations::engine::AlgoStateRepair {
+        crate::store::operations::engine::AlgoStateRepair {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            algo_id: "ige".to_string(),
+            error: "invalid type".to_string(),
+            raw_state: serde_json::json!({}),
+            detected_at: Utc::now(),
         }
     }
 
@@ -534,6 +644,27 @@ mod tests {
         assert_eq!(got.email, "u1@test.com");
     }
 
+    #[test]
+    fn ban_is_idempotent_and_missing_user_is_not_found() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("users-ban-db").to_str().unwrap()).unwrap();
+        store.create_user(&sample_user("u1", "ban@test.com")).unwrap();
+
+        store.ban_user("u1").unwrap();
+        let banned_at = store.get_user_by_id("u1").unwrap().unwrap().updated_at;
+        store.ban_user("u1").unwrap();
+        let again = store.get_user_by_id("u1").unwrap().unwrap();
+        assert!(again.is_banned);
+        assert_eq!(again.updated_at, banned_at);
+
+        store.unban_user("u1").unwrap();
+        assert!(!store.get_user_by_id("u1").unwrap().unwrap().is_banned);
+        assert!(matches!(
+            store.ban_user("missing"),
+            Err(StoreError::NotFound { .. })
+        ));
+    }
+
     #[test]
     fn duplicate_email_conflicts() {
         let dir = tempdir().unwrap();
@@ -565,4 +696,141 @@ mod tests {
 
         assert_eq!(ids, vec!["u1".to_string(), "u2".to_string()]);
     }
+
+    #[test]
+    fn delete_user_removes_placement_result_and_repair_log() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("users-del-db").to_str().unwrap()).unwrap();
+        store
+            .create_user(&sample_user("u1", "del@test.com"))
+            .unwrap();
+        let result = crate::amas::placement::PlacementResult {
+            elo: 1300.0,
+            answered: 8,
+            correct: 6,
+            cognitive_profile: crate::amas::types::CognitiveProfile::default(),
+            completed_at: Utc::now(),
+        };
+        assert!(store.record_placement_result("u1", &result).unwrap());
+        store
+            .record_algo_state_repair(&sample_repair("u1"))
+            .unwrap();
+
+        store.delete_user("u1").unwrap();
+
+        assert!(store.get_placement_result("u1").unwrap().is_none());
+        assert!(store.list_algo_state_repairs(None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn anonymize_removes_personal_data() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("users-anon-db").to_str().unwrap()).unwrap();
+        store
+            .create_user(&sample_user("u1", "anon@test.com"))
+            .unwrap();
+        let now = Utc::now();
+        store
+            .create_session(&crate::store::operations::sessions::Session {
+                token_hash: "t1".to_string(),
+                user_id: "u1".to_string(),
+                token_type: "user".to_string(),
+                created_at: now,
+                expires_at: now + chrono::Duration::hours(1),
+                revoked: false,
+                last_seen_at: None,
+                family_id: None,
+                user_agent: None,
+                ip_hash: None,
+            })
+            .unwrap();
+        store
+            .set_engine_user_state("u1", &serde_json::json!({}))
+            .unwrap();
+        let overrides = crate::amas::config::FeatureFlagOverrides {
+            ige_enabled: Some(false),
+            ..Default::default()
+        };
+        store.set_feature_flag_overrides("u1", &overrides).unwrap();
+        store
+            .record_algo_state_repair(&sample_repair("u1"))
+            .unwrap();
+        store
+            .record_algo_state_repair(&sample_repair("u2"))
+            .unwrap();
+        let raw = |v: serde_json::Value| serde_json::to_vec(&v).unwrap();
+        let profile_key = keys::user_profile_key("u1").unwrap();
+        let avatar_key = keys::user_avatar_key("u1").unwrap();
+        let pref_key = keys::user_preferences_key("u1").unwrap();
+        store
+            .user_profiles
+            .insert(profile_key.as_bytes(), raw(serde_json::json!({})))
+            .unwrap();
+        store
+            .user_profiles
+            .insert(
+                avatar_key.as_bytes(),
+                raw(serde_json::json!({"filename": "none.png"})),
+            )
+            .unwrap();
+        store
+            .user_preferences
+            .insert(
+                pref_key.as_bytes(),
+                raw(serde_json::json!({"theme": "dark"})),
+            )
+            .unwrap();
+        store
+            .create_notification("u1", "n1", &serde_json::json!({"id": "n1"}))
+            .unwrap();
+        store
+            .set_user_webhook(&crate::store::operations::webhooks::UserWebhook {
+                user_id: "u1".to_string(),
+                url: "https://example.com/hook".to_string(),
+                secret: "s".to_string(),
+                created_at: now,
+                updated_at: now,
+            })
+            .unwrap();
+        let change = EmailChangeEntry {
+            user_id: "u1".to_string(),
+            new_email: "new@test.com".to_string(),
+            expires_at: now + chrono::Duration::hours(1),
+        };
+        store.create_email_change_token("c1", &change).unwrap();
+
+        store.anonymize_user("u1").unwrap();
+
+        assert!(store.get_user_by_id("u1").unwrap().unwrap().is_anonymized());
+        assert!(store.list_user_sessions("u1").unwrap().is_empty());
+        assert!(store.get_engine_user_state("u1").unwrap().is_none());
+        assert!(store.get_feature_flag_overrides("u1").unwrap().is_none());
+        assert!(store
+            .list_algo_state_repairs(Some("u1"), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.list_algo_state_repairs(None, 10).unwrap().len(), 1);
+        assert!(!store
+            .user_profiles
+            .contains_key(profile_key.as_bytes())
+            .unwrap());
+        assert!(!store
+            .user_profiles
+            .contains_key(avatar_key.as_bytes())
+            .unwrap());
+        assert!(!store
+            .user_preferences
+            .contains_key(pref_key.as_bytes())
+            .unwrap());
+        let notif_prefix = keys::notification_prefix("u1").unwrap();
+        assert_eq!(
+            store
+                .notifications
+                .scan_prefix(notif_prefix.as_bytes())
+                .count(),
+            0
+        );
+        assert!(store.get_user_webhook("u1").unwrap().is_none());
+        assert!(store.email_change_tokens.is_empty());
+    }
 }
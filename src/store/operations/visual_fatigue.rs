@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 每个用户保留的视觉疲劳样本上限，超出后淘汰最旧的样本
+pub const VISUAL_FATIGUE_HISTORY_CAP: usize = 200;
+
+/// 一次视觉疲劳上报：原始分数与混合后的引擎疲劳度并存，便于对比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualFatigueSample {
+    /// 客户端上报的原始分数（0-100）
+    pub visual_score: f64,
+    /// 混合视觉输入后的引擎疲劳度（0-1）
+    pub blended_fatigue: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl Store {
+    pub fn append_visual_fatigue_sample(
+        &self,
+        user_id: &str,
+        sample: &VisualFatigueSample,
+    ) -> Result<(), StoreError> {
+        let key = keys::visual_fatigue_key(
+            user_id,
+            sample.recorded_at.timestamp_millis(),
+            self.db.generate_id()?,
+        )?;
+        self.visual_fatigue_history
+            .insert(key.as_bytes(), Self::serialize(sample)?)?;
+
+        let prefix = keys::visual_fatigue_prefix(user_id)?;
+        let stale: Vec<sled::IVec> = self
+            .visual_fatigue_history
+            .scan_prefix(prefix.as_bytes())
+            .keys()
+            .rev()
+            .skip(VISUAL_FATIGUE_HISTORY_CAP)
+            .collect::<Result<_, _>>()?;
+        if !stale.is_empty() {
+            let mut batch = sled::Batch::default();
+            for key in stale {
+                batch.remove(key);
+            }
+            self.visual_fatigue_history.apply_batch(batch)?;
+        }
+        Ok(())
+    }
+
+    /// 最近 `limit` 条样本，按时间升序
+    pub fn list_visual_fatigue_samples(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<VisualFatigueSample>, StoreError> {
+        let prefix = keys::visual_fatigue_prefix(user_id)?;
+        let mut samples = Vec::new();
+        for item in self
+            .visual_fatigue_history
+            .scan_prefix(prefix.as_bytes())
+            .rev()
+            .take(limit)
+        {
+            let (_, raw) = item?;
+            samples.push(Self::deserialize(&raw)?);
+        }
+        samples.reverse();
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn history_is_bounded_and_chronological() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("fatigue-db").to_str().unwrap()).unwrap();
+        let start = Utc::now() - Duration::hours(1);
+        for i in 0..VISUAL_FATIGUE_HISTORY_CAP + 5 {
+            store
+                .append_visual_fatigue_sample(
+                    "u1",
+                    &VisualFatigueSample {
+                        visual_score: i as f64,
+                        blended_fatigue: 0.1,
+                        recorded_at: start + Duration::seconds(i as i64),
+                    },
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            store.visual_fatigue_history.len(),
+            VISUAL_FATIGUE_HISTORY_CAP
+        );
+        let recent = store.list_visual_fatigue_samples("u1", 3).unwrap();
+        let scores: Vec<f64> = recent.iter().map(|s| s.visual_score).collect();
+        let last = (VISUAL_FATIGUE_HISTORY_CAP + 4) as f64;
+        assert_eq!(scores, vec![last - 2.0, last - 1.0, last]);
+    }
+}
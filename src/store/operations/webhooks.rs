@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 用户配置的 webhook 推送地址；`secret` 用于对推送内容做 HMAC 签名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserWebhook {
+    pub user_id: String,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Store {
+    pub fn get_user_webhook(&self, user_id: &str) -> Result<Option<UserWebhook>, StoreError> {
+        let key = keys::user_webhook_key(user_id)?;
+        match self.user_webhooks.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_user_webhook(&self, webhook: &UserWebhook) -> Result<(), StoreError> {
+        let key = keys::user_webhook_key(&webhook.user_id)?;
+        self.user_webhooks
+            .insert(key.as_bytes(), Self::serialize(webhook)?)?;
+        Ok(())
+    }
+
+    pub fn delete_user_webhook(&self, user_id: &str) -> Result<bool, StoreError> {
+        let key = keys::user_webhook_key(user_id)?;
+        Ok(self.user_webhooks.remove(key.as_bytes())?.is_some())
+    }
+}
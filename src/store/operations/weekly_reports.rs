@@ -0,0 +1,108 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// 用户周报，由 weekly_report worker 按 ISO 周生成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyReport {
+    pub user_id: String,
+    /// ISO 周，形如 `2026-W07`
+    pub week: String,
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    /// 本周学习过的不同单词数
+    pub words_learned: u64,
+    pub total_answers: u64,
+    pub accuracy: f64,
+    /// 按天的正确率，周一到周日共 7 项
+    pub accuracy_trend: Vec<DailyAccuracy>,
+    /// 截至周末的连续学习天数
+    pub streak_days: u32,
+    pub top_confusions: Vec<ConfusionSummary>,
+    /// 累计作答耗时（毫秒）
+    pub time_studied_ms: i64,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyAccuracy {
+    pub date: NaiveDate,
+    pub total: u64,
+    pub correct: u64,
+    pub accuracy: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusionSummary {
+    pub word_id: String,
+    pub word: Option<String>,
+    pub confused_with_id: String,
+    pub confused_with: Option<String>,
+    pub count: u32,
+}
+
+pub fn iso_week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// ISO 周标识，形如 `2026-W07`
+pub fn iso_week_label(week_start: NaiveDate) -> String {
+    week_start.format("%G-W%V").to_string()
+}
+
+/// 解析 `YYYY-Www`，返回该周周一
+pub fn parse_iso_week(label: &str) -> Option<NaiveDate> {
+    let (year, week) = label.split_once("-W")?;
+    if week.len() != 2 {
+        return None;
+    }
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+}
+
+impl Store {
+    pub fn upsert_weekly_report(&self, report: &WeeklyReport) -> Result<(), StoreError> {
+        let key = keys::weekly_report_key(&report.user_id, &report.week)?;
+        self.user_weekly_reports
+            .insert(key.as_bytes(), Self::serialize(report)?)?;
+        Ok(())
+    }
+
+    pub fn get_weekly_report(
+        &self,
+        user_id: &str,
+        iso_week: &str,
+    ) -> Result<Option<WeeklyReport>, StoreError> {
+        let key = keys::weekly_report_key(user_id, iso_week)?;
+        match self.user_weekly_reports.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 按周倒序列出用户周报
+    pub fn list_weekly_reports(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<WeeklyReport>, StoreError> {
+        let prefix = keys::weekly_report_prefix(user_id)?;
+        let mut reports = Vec::new();
+        for item in self
+            .user_weekly_reports
+            .scan_prefix(prefix.as_bytes())
+            .rev()
+        {
+            let (_, raw) = item?;
+            reports.push(Self::deserialize(&raw)?);
+            if reports.len() >= limit {
+                break;
+            }
+        }
+        Ok(reports)
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::keys;
+use crate::store::{Store, StoreError};
+
+/// word_clustering worker 的聚类输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordCluster {
+    pub id: String,
+    pub theme: String,
+    pub word_ids: Vec<String>,
+    pub centroid: Vec<f64>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl Store {
+    /// 以新一轮聚类结果整体替换旧结果
+    pub fn replace_word_clusters(&self, clusters: &[WordCluster]) -> Result<(), StoreError> {
+        let mut batch = sled::Batch::default();
+        for key in self.word_clusters.iter().keys() {
+            batch.remove(key?);
+        }
+        for cluster in clusters {
+            let key = keys::word_cluster_key(&cluster.id)?;
+            batch.insert(key.as_bytes(), Self::serialize(cluster)?);
+        }
+        self.word_clusters.apply_batch(batch)?;
+        Ok(())
+    }
+
+    pub fn list_word_clusters(&self) -> Result<Vec<WordCluster>, StoreError> {
+        let mut clusters = Vec::new();
+        for item in self.word_clusters.iter() {
+            let (_, raw) = item?;
+            clusters.push(Self::deserialize(&raw)?);
+        }
+        Ok(clusters)
+    }
+
+    pub fn get_word_cluster(&self, cluster_id: &str) -> Result<Option<WordCluster>, StoreError> {
+        let key = keys::word_cluster_key(cluster_id)?;
+        match self.word_clusters.get(key.as_bytes())? {
+            Some(raw) => Ok(Some(Self::deserialize(&raw)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::store::keys;
+use crate::store::operations::records::LearningRecord;
+use crate::store::{Store, StoreError};
+
+/// 95% 置信区间对应的 z 值
+const WILSON_Z: f64 = 1.96;
+
+/// 单个单词在全体用户中的实测难度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordDifficulty {
+    pub word_id: String,
+    pub text: String,
+    /// 人工标注的难度（0-1）
+    pub labeled_difficulty: f64,
+    pub attempts: u64,
+    pub correct: u64,
+    /// 错误率，即实测难度
+    pub error_rate: f64,
+    /// 错误率的 Wilson 95% 置信区间，样本越少区间越宽
+    pub confidence_low: f64,
+    pub confidence_high: f64,
+    /// 实测难度与标注难度之差，绝对值大的词可能标注有误
+    pub difficulty_gap: f64,
+    pub elo_rating: f64,
+    pub elo_games: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordDifficultyReport {
+    pub generated_at: DateTime<Utc>,
+    /// 参与统计的学习记录数（最近的记录优先）
+    pub sampled_records: usize,
+    /// 按 `confidence_low` 降序，即在置信意义上最难的词排在前面
+    pub words: Vec<WordDifficulty>,
+}
+
+fn wilson_interval(successes: u64, trials: u64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = WILSON_Z * WILSON_Z;
+    let center = p + z2 / (2.0 * n);
+    let margin = WILSON_Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    let denom = 1.0 + z2 / n;
+    (
+        ((center - margin) / denom).max(0.0),
+        ((center + margin) / denom).min(1.0),
+    )
+}
+
+impl Store {
+    /// 汇总最近 `max_records` 条学习记录的逐词正确率，并附上单词 ELO。
+    /// 通过 `records_by_time` 倒序取最近的记录，避免全量扫描
+    pub fn word_difficulty_report(
+        &self,
+        max_records: usize,
+    ) -> Result<WordDifficultyReport, StoreError> {
+        let mut tallies: HashMap<String, (u64, u64)> = HashMap::new();
+        let mut sampled_records = 0;
+        for item in self.records_by_time.iter().rev().take(max_records) {
+            let (key, value) = item?;
+            let key = String::from_utf8_lossy(&key);
+            let Some((ts, record_id)) = key.split_once(':') else {
+                continue;
+            };
+            let Ok(ts) = ts.parse::<i64>() else {
+                continue;
+            };
+            let user_id = String::from_utf8_lossy(&value);
+            let record_key = keys::record_key(&user_id, ts, record_id)?;
+            let Some(raw) = self.records.get(record_key.as_bytes())? else {
+                continue;
+            };
+            let record: LearningRecord = Self::deserialize(&raw)?;
+            let tally = tallies.entry(record.word_id).or_default();
+            tally.0 += 1;
+            if record.is_correct {
+                tally.1 += 1;
+            }
+            sampled_records += 1;
+        }
+
+        let mut words = Vec::with_capacity(tallies.len());
+        for (word_id, (attempts, correct)) in tallies {
+            // 已删除的单词不再展示
+            let Some(word) = self.get_word(&word_id)? else {
+                continue;
+            };
+            let elo = self.get_word_elo(&word_id)?;
+            let errors = attempts - correct;
+            let error_rate = errors as f64 / attempts as f64;
+            let (confidence_low, confidence_high) = wilson_interval(errors, attempts);
+            words.push(WordDifficulty {
+                word_id,
+                text: word.text,
+                labeled_difficulty: word.difficulty,
+                attempts,
+                correct,
+                error_rate,
+                confidence_low,
+                confidence_high,
+                difficulty_gap: error_rate - word.difficulty,
+                elo_rating: elo.rating,
+                elo_games: elo.games,
+            });
+        }
+        words.sort_by(|a, b| {
+            b.confidence_low
+                .total_cmp(&a.confidence_low)
+                .then(b.attempts.cmp(&a.attempts))
+        });
+
+        Ok(WordDifficultyReport {
+            generated_at: Utc::now(),
+            sampled_records,
+            words,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::operations::test_fixtures::{sample_record, sample_word};
+    use crate::store::operations::words::Word;
+
+    #[test]
+    fn ranks_words_by_error_rate_confidence() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("word-difficulty").to_str().unwrap()).unwrap();
+        let now = Utc::now();
+        store
+            .upsert_word(&Word {
+                difficulty: 0.2,
+                ..sample_word("hard", now)
+            })
+            .unwrap();
+        store.upsert_word(&sample_word("easy", now)).unwrap();
+        store.upsert_word(&sample_word("rare", now)).unwrap();
+        for i in 0..10 {
+            let created_at = now - chrono::Duration::seconds(i + 1);
+            store
+                .create_record(&LearningRecord {
+                    is_correct: i < 2,
+                    ..sample_record(&format!("h{i}"), "u1", "hard", created_at)
+                })
+                .unwrap();
+            store
+                .create_record(&sample_record(&format!("e{i}"), "u1", "easy", created_at))
+                .unwrap();
+        }
+        for (id, word_id, age_secs) in [("r0", "rare", 100), ("gone", "deleted-word", 1)] {
+            store
+                .create_record(&LearningRecord {
+                    is_correct: false,
+                    ..sample_record(id, "u1", word_id, now - chrono::Duration::seconds(age_secs))
+                })
+                .unwrap();
+        }
+
+        let report = store.word_difficulty_report(1000).unwrap();
+        assert_eq!(report.sampled_records, 22);
+        let ids: Vec<&str> = report.words.iter().map(|w| w.word_id.as_str()).collect();
+        // 单条错误记录的词置信下界低，排在样本充足的难词之后
+        assert_eq!(ids, ["hard", "rare", "easy"]);
+        let hard = &report.words[0];
+        assert_eq!(hard.attempts, 10);
+        assert_eq!(hard.correct, 2);
+        assert!((hard.difficulty_gap - 0.6).abs() < 1e-9);
+        assert!(hard.confidence_low < 0.8 && hard.confidence_high > 0.8);
+
+        // 只统计最近的记录
+        let capped = store.word_difficulty_report(5).unwrap();
+        assert_eq!(capped.sampled_records, 5);
+        assert!(capped.words.iter().all(|w| w.word_id != "rare"));
+    }
+}
@@ -0,0 +1,575 @@
+//! 重复单词检测与合并：按规范化拼写（去首尾空白、小写、折叠连续空白）分组。
+//! 合并时将重复单词的答题记录、学习状态与词书条目改指向保留的单词，再删除重复单词；
+//! 受影响的条目通过 word_references 定位，条目内容在写入的同一事务内读取。
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sled::transaction::{
+    ConflictableTransactionError, ConflictableTransactionResult, TransactionError,
+    TransactionalTree,
+};
+use sled::Transactional;
+
+use crate::store::keys;
+use crate::store::operations::counters::{self, Counter};
+use crate::store::operations::records::{LearningRecord, UserStatsAgg};
+use crate::store::operations::word_states::WordLearningState;
+use crate::store::operations::wordbooks::{Wordbook, WordbookWordEntry};
+use crate::store::operations::words::Word;
+use crate::store::{Store, StoreError};
+
+pub fn normalize_word_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WordReferenceCounts {
+    pub records: u64,
+    pub learning_states: u64,
+    pub wordbook_entries: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateWord {
+    pub id: String,
+    pub text: String,
+    pub meaning: String,
+    pub created_at: DateTime<Utc>,
+    pub references: WordReferenceCounts,
+}
+
+/// 一组拼写相同的单词，`words` 中第一个为建议保留的单词（最早创建）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub normalized_text: String,
+    pub canonical_id: String,
+    pub words: Vec<DuplicateWord>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSummary {
+    pub canonical_id: String,
+    pub merged_ids: Vec<String>,
+    pub records_moved: u64,
+    pub learning_states_moved: u64,
+    /// 用户同时学过两个单词时只保留作答次数更多的学习状态
+    pub learning_states_dropped: u64,
+    pub wordbook_entries_moved: u64,
+    /// 词书已包含保留单词时，重复单词的条目直接移除
+    pub wordbook_entries_dropped: u64,
+    pub applied: bool,
+}
+
+impl MergeSummary {
+    fn absorb(&mut self, other: &MergeSummary) {
+        self.records_moved += other.records_moved;
+        self.learning_states_moved += other.learning_states_moved;
+        self.learning_states_dropped += other.learning_states_dropped;
+        self.wordbook_entries_moved += other.wordbook_entries_moved;
+        self.wordbook_entries_dropped += other.wordbook_entries_dropped;
+    }
+}
+
+/// 单个重复单词的合并计划，在同一事务内读出并写入，避免覆盖并发写入
+#[derive(Default)]
+struct MergePlan {
+    canonical_id: String,
+    counts: MergeSummary,
+    records: Vec<(Vec<u8>, Vec<u8>)>,
+    user_stats: Vec<(Vec<u8>, Vec<u8>)>,
+    state_removals: Vec<Vec<u8>>,
+    state_inserts: Vec<(Vec<u8>, Vec<u8>)>,
+    due_removals: Vec<Vec<u8>>,
+    due_inserts: Vec<Vec<u8>>,
+    wordbook_word_removals: Vec<Vec<u8>>,
+    wordbook_word_inserts: Vec<(Vec<u8>, Vec<u8>)>,
+    wordbooks: Vec<(Vec<u8>, Vec<u8>)>,
+    /// 合并后需为保留单词补建的 word_references：`(tree_name, assoc_key)`
+    new_refs: Vec<(&'static str, Vec<u8>)>,
+}
+
+/// 规划合并时需要读取的事务树
+struct MergeTx<'a> {
+    records: &'a TransactionalTree,
+    user_stats: &'a TransactionalTree,
+    word_learning_states: &'a TransactionalTree,
+    wordbook_words: &'a TransactionalTree,
+    wordbooks: &'a TransactionalTree,
+}
+
+fn due_key(state: &WordLearningState) -> Result<Option<Vec<u8>>, StoreError> {
+    state
+        .next_review_date
+        .map(|due| {
+            keys::word_due_index_key(&state.user_id, due.timestamp_millis(), &state.word_id)
+                .map(String::into_bytes)
+        })
+        .transpose()
+}
+
+impl Store {
+    /// 仍然存在的引用条目 `(tree_name, assoc_key)`；word_references 为尽力维护，可能残留失效条目
+    pub(crate) fn live_word_references(&self, word_id: &str) -> Result<Vec<(String, Vec<u8>)>, StoreError> {
+        let prefix = keys::word_ref_prefix(word_id)?;
+        let mut refs = Vec::new();
+        for item in self.word_references.scan_prefix(prefix.as_bytes()) {
+            let (ref_key, _) = item?;
+            let ref_key = String::from_utf8_lossy(&ref_key[prefix.len()..]).to_string();
+            let Some((tree_name, assoc_hex)) = ref_key.split_once(':') else {
+                continue;
+            };
+            let Ok(assoc_key) = hex::decode(assoc_hex) else {
+                continue;
+            };
+            let tree = match tree_name {
+                "records" => &self.records,
+                "word_learning_states" => &self.word_learning_states,
+                "wordbook_words" => &self.wordbook_words,
+                _ => continue,
+            };
+            if tree.contains_key(&assoc_key)? {
+                refs.push((tree_name.to_string(), assoc_key));
+            }
+        }
+        Ok(refs)
+    }
+
+    fn word_reference_counts(&self, word_id: &str) -> Result<WordReferenceCounts, StoreError> {
+        let mut counts = WordReferenceCounts::default();
+        for (tree_name, _) in self.live_word_references(word_id)? {
+            match tree_name.as_str() {
+                "records" => counts.records += 1,
+                "word_learning_states" => counts.learning_states += 1,
+                _ => counts.wordbook_entries += 1,
+            }
+        }
+        Ok(counts)
+    }
+
+    pub fn find_duplicate_words(&self) -> Result<Vec<DuplicateGroup>, StoreError> {
+        let mut groups: BTreeMap<String, Vec<Word>> = BTreeMap::new();
+        for item in self.words.iter() {
+            let (_, value) = item?;
+            let word: Word = Self::deserialize(&value)?;
+            groups
+                .entry(normalize_word_text(&word.text))
+                .or_default()
+                .push(word);
+        }
+
+        let mut duplicates = Vec::new();
+        for (normalized_text, mut words) in groups {
+            if words.len() < 2 {
+                continue;
+            }
+            words.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+            let mut entries = Vec::with_capacity(words.len());
+            for word in words {
+                entries.push(DuplicateWord {
+                    references: self.word_reference_counts(&word.id)?,
+                    id: word.id,
+                    text: word.text,
+                    meaning: word.meaning,
+                    created_at: word.created_at,
+                });
+            }
+            duplicates.push(DuplicateGroup {
+                normalized_text,
+                canonical_id: entries[0].id.clone(),
+                words: entries,
+            });
+        }
+        Ok(duplicates)
+    }
+
+    /// 将 `duplicate_ids` 合并到 `canonical_id`；`dry_run` 时只统计不写入。
+    /// 每个重复单词在单独的事务内完成改指向与删除。
+    pub fn merge_words(
+        &self,
+        canonical_id: &str,
+        duplicate_ids: &[String],
+        dry_run: bool,
+    ) -> Result<MergeSummary, StoreError> {
+        let canonical = self
+            .get_word(canonical_id)?
+            .ok_or_else(|| StoreError::NotFound {
+                entity: "word".to_string(),
+                key: canonical_id.to_string(),
+            })?;
+        let normalized = normalize_word_text(&canonical.text);
+
+        let mut duplicates = Vec::with_capacity(duplicate_ids.len());
+        for id in duplicate_ids {
+            if id == canonical_id || duplicates.iter().any(|w: &Word| &w.id == id) {
+                continue;
+            }
+            let word = self.get_word(id)?.ok_or_else(|| StoreError::NotFound {
+                entity: "word".to_string(),
+                key: id.to_string(),
+            })?;
+            if normalize_word_text(&word.text) != normalized {
+                return Err(StoreError::Validation(format!(
+                    "单词 {} 与保留单词拼写不同，不能合并",
+                    word.id
+                )));
+            }
+            duplicates.push(word);
+        }
+
+        let mut summary = MergeSummary {
+            canonical_id: canonical_id.to_string(),
+            applied: !dry_run,
+            ..Default::default()
+        };
+        for duplicate in &duplicates {
+            let plan = self.merge_word(canonical_id, duplicate, dry_run)?;
+            summary.absorb(&plan.counts);
+            summary.merged_ids.push(duplicate.id.clone());
+        }
+        Ok(summary)
+    }
+
+    fn merge_word(
+        &self,
+        canonical_id: &str,
+        duplicate: &Word,
+        dry_run: bool,
+    ) -> Result<MergePlan, StoreError> {
+        let refs = self.live_word_references(&duplicate.id)?;
+        let word_key = keys::word_key(&duplicate.id)?;
+        let plan = (
+            &self.words,
+            &self.records,
+            &self.user_stats,
+            &self.word_learning_states,
+            &self.word_due_index,
+            &self.wordbook_words,
+            &self.wordbooks,
+            &self.counters,
+        )
+            .transaction(
+                |(tx_words, tx_rec, tx_stats, tx_wls, tx_due, tx_ww, tx_wb, tx_cnt)| {
+                    let tx = MergeTx {
+                        records: tx_rec,
+                        user_stats: tx_stats,
+                        word_learning_states: tx_wls,
+                        wordbook_words: tx_ww,
+                        wordbooks: tx_wb,
+                    };
+                    let plan = plan_word_merge(&tx, canonical_id, &duplicate.id, &refs)?;
+                    if dry_run {
+                        return Ok(plan);
+                    }
+                    for (k, v) in &plan.records {
+                        tx_rec.insert(k.as_slice(), v.as_slice())?;
+                    }
+                    for (k, v) in &plan.user_stats {
+                        tx_stats.insert(k.as_slice(), v.as_slice())?;
+                    }
+                    for k in &plan.state_removals {
+                        tx_wls.remove(k.as_slice())?;
+                    }
+                    for (k, v) in &plan.state_inserts {
+                        tx_wls.insert(k.as_slice(), v.as_slice())?;
+                    }
+                    for k in &plan.due_removals {
+                        tx_due.remove(k.as_slice())?;
+                    }
+                    for k in &plan.due_inserts {
+                        tx_due.insert(k.as_slice(), &[] as &[u8])?;
+                    }
+                    for k in &plan.wordbook_word_removals {
+                        tx_ww.remove(k.as_slice())?;
+                    }
+                    for (k, v) in &plan.wordbook_word_inserts {
+                        tx_ww.insert(k.as_slice(), v.as_slice())?;
+                    }
+                    for (k, v) in &plan.wordbooks {
+                        tx_wb.insert(k.as_slice(), v.as_slice())?;
+                    }
+                    if tx_words.remove(word_key.as_bytes())?.is_some() {
+                        counters::adjust_in_tx(tx_cnt, Counter::Words, -1)?;
+                    }
+                    Ok(plan)
+                },
+            )
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })?;
+        if dry_run {
+            return Ok(plan);
+        }
+
+        // 索引在事务外尽力维护，与单词增删一致
+        for (tree_name, assoc_key) in &plan.new_refs {
+            let ref_key = keys::word_ref_key(&plan.canonical_id, tree_name, assoc_key)?;
+            self.word_references.insert(ref_key.as_bytes(), &[])?;
+        }
+        let ref_prefix = keys::word_ref_prefix(&duplicate.id)?;
+        for (k, _) in self
+            .word_references
+            .scan_prefix(ref_prefix.as_bytes())
+            .flatten()
+        {
+            self.word_references.remove(&k)?;
+        }
+        let created_key =
+            keys::words_by_created_at_key(duplicate.created_at.timestamp_millis(), &duplicate.id)?;
+        self.words_by_created_at.remove(created_key.as_bytes())?;
+        self.sync_word_tags(&duplicate.id, &duplicate.tags, &[])?;
+        self.unindex_word_text(duplicate)?;
+        Ok(plan)
+    }
+}
+
+/// 在事务内读取重复单词的引用条目并生成改写计划
+fn plan_word_merge(
+    tx: &MergeTx<'_>,
+    canonical_id: &str,
+    duplicate_id: &str,
+    refs: &[(String, Vec<u8>)],
+) -> ConflictableTransactionResult<MergePlan, StoreError> {
+    let abort = ConflictableTransactionError::Abort;
+    let mut plan = MergePlan {
+        canonical_id: canonical_id.to_string(),
+        ..Default::default()
+    };
+    let mut stats_users: HashMap<String, UserStatsAgg> = HashMap::new();
+    let mut wordbook_decrements: HashMap<String, u64> = HashMap::new();
+
+    for (tree_name, assoc_key) in refs {
+        let assoc_key = assoc_key.clone();
+        match tree_name.as_str() {
+            "records" => {
+                let Some(raw) = tx.records.get(&assoc_key)? else {
+                    continue;
+                };
+                let mut record: LearningRecord = Store::deserialize(&raw).map_err(abort)?;
+                record.word_id = canonical_id.to_string();
+                if !stats_users.contains_key(&record.user_id) {
+                    let key = keys::user_stats_key(&record.user_id).map_err(abort)?;
+                    let agg = match tx.user_stats.get(key.as_bytes())? {
+                        Some(raw) => Store::deserialize(&raw).map_err(abort)?,
+                        None => UserStatsAgg::default(),
+                    };
+                    stats_users.insert(record.user_id.clone(), agg);
+                }
+                plan.records
+                    .push((assoc_key.clone(), Store::serialize(&record).map_err(abort)?));
+                plan.new_refs.push(("records", assoc_key));
+                plan.counts.records_moved += 1;
+            }
+            "word_learning_states" => {
+                let Some(raw) = tx.word_learning_states.get(&assoc_key)? else {
+                    continue;
+                };
+                let mut state: WordLearningState = Store::deserialize(&raw).map_err(abort)?;
+                plan.state_removals.push(assoc_key);
+                plan.due_removals.extend(due_key(&state).map_err(abort)?);
+
+                let new_key = keys::word_learning_state_key(&state.user_id, canonical_id)
+                    .map_err(abort)?
+                    .into_bytes();
+                if let Some(raw) = tx.word_learning_states.get(&new_key)? {
+                    let existing: WordLearningState = Store::deserialize(&raw).map_err(abort)?;
+                    if existing.total_attempts >= state.total_attempts {
+                        plan.counts.learning_states_dropped += 1;
+                        continue;
+                    }
+                    // 重复单词上的进度更多，替换保留单词的状态
+                    plan.due_removals.extend(due_key(&existing).map_err(abort)?);
+                    plan.counts.learning_states_dropped += 1;
+                }
+                state.word_id = canonical_id.to_string();
+                if let Some(new_due) = due_key(&state).map_err(abort)? {
+                    plan.due_inserts.push(new_due.clone());
+                    plan.new_refs.push(("word_due_index", new_due));
+                }
+                plan.state_inserts
+                    .push((new_key.clone(), Store::serialize(&state).map_err(abort)?));
+                plan.new_refs.push(("word_learning_states", new_key));
+                plan.counts.learning_states_moved += 1;
+            }
+            _ => {
+                let Some(raw) = tx.wordbook_words.get(&assoc_key)? else {
+                    continue;
+                };
+                let mut entry: WordbookWordEntry = Store::deserialize(&raw).map_err(abort)?;
+                plan.wordbook_word_removals.push(assoc_key);
+                let new_key = keys::wordbook_words_key(&entry.wordbook_id, canonical_id)
+                    .map_err(abort)?
+                    .into_bytes();
+                if tx.wordbook_words.get(&new_key)?.is_some() {
+                    *wordbook_decrements
+                        .entry(entry.wordbook_id.clone())
+                        .or_default() += 1;
+                    plan.counts.wordbook_entries_dropped += 1;
+                    continue;
+                }
+                entry.word_id = canonical_id.to_string();
+                plan.wordbook_word_inserts
+                    .push((new_key.clone(), Store::serialize(&entry).map_err(abort)?));
+                plan.new_refs.push(("wordbook_words", new_key));
+                plan.counts.wordbook_entries_moved += 1;
+            }
+        }
+    }
+
+    for (user_id, mut agg) in stats_users {
+        if agg.word_ids.remove(duplicate_id) {
+            agg.word_ids.insert(canonical_id.to_string());
+        }
+        let key = keys::user_stats_key(&user_id).map_err(abort)?.into_bytes();
+        plan.user_stats
+            .push((key, Store::serialize(&agg).map_err(abort)?));
+    }
+    for (wordbook_id, removed) in wordbook_decrements {
+        let key = keys::wordbook_key(&wordbook_id).map_err(abort)?;
+        if let Some(raw) = tx.wordbooks.get(key.as_bytes())? {
+            let mut book: Wordbook = Store::deserialize(&raw).map_err(abort)?;
+            book.word_count = book.word_count.saturating_sub(removed);
+            plan.wordbooks
+                .push((key.into_bytes(), Store::serialize(&book).map_err(abort)?));
+        }
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::store::operations::test_fixtures::sample_word;
+    use crate::store::operations::word_states::WordState;
+    use crate::store::operations::wordbooks::WordbookType;
+
+    fn learning_state(user_id: &str, word_id: &str, total_attempts: u32) -> WordLearningState {
+        WordLearningState {
+            user_id: user_id.to_string(),
+            word_id: word_id.to_string(),
+            state: WordState::Learning,
+            mastery_level: 0.3,
+            next_review_date: Some(Utc::now()),
+            half_life: 24.0,
+            correct_streak: 1,
+            total_attempts,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn merge_repoints_references_and_removes_duplicates() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("merge-db").to_str().unwrap()).unwrap();
+        for (id, text, age_secs) in [
+            ("w1", "Apple", 20),
+            ("w2", " apple ", 10),
+            ("w3", "banana", 5),
+        ] {
+            let created_at = Utc::now() - chrono::Duration::seconds(age_secs);
+            store
+                .upsert_word(&Word {
+                    text: text.to_string(),
+                    ..sample_word(id, created_at)
+                })
+                .unwrap();
+        }
+
+        store
+            .create_record(&LearningRecord {
+                id: "r1".to_string(),
+                user_id: "u1".to_string(),
+                word_id: "w2".to_string(),
+                is_correct: true,
+                response_time_ms: 1000,
+                session_id: None,
+                created_at: Utc::now(),
+                algorithm_id: None,
+                algorithm_weights: Default::default(),
+            })
+            .unwrap();
+        // u1 在重复单词上进度更多，u2 只学过保留单词
+        store
+            .set_word_learning_state(&learning_state("u1", "w1", 1))
+            .unwrap();
+        store
+            .set_word_learning_state(&learning_state("u1", "w2", 5))
+            .unwrap();
+        store
+            .set_word_learning_state(&learning_state("u2", "w1", 2))
+            .unwrap();
+        store
+            .upsert_wordbook(&Wordbook {
+                id: "b1".to_string(),
+                name: "book".to_string(),
+                description: String::new(),
+                book_type: WordbookType::System,
+                user_id: None,
+                word_count: 0,
+                created_at: Utc::now(),
+            })
+            .unwrap();
+        store.add_word_to_wordbook("b1", "w1").unwrap();
+        store.add_word_to_wordbook("b1", "w2").unwrap();
+
+        let groups = store.find_duplicate_words().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].canonical_id, "w1");
+        assert_eq!(
+            groups[0].words[1].references,
+            WordReferenceCounts {
+                records: 1,
+                learning_states: 1,
+                wordbook_entries: 1,
+            }
+        );
+
+        let preview = store.merge_words("w1", &["w2".to_string()], true).unwrap();
+        assert!(!preview.applied);
+        assert!(store.get_word("w2").unwrap().is_some());
+
+        let summary = store.merge_words("w1", &["w2".to_string()], false).unwrap();
+        assert_eq!(
+            summary,
+            MergeSummary {
+                applied: true,
+                ..preview
+            }
+        );
+        assert_eq!(
+            (
+                summary.records_moved,
+                summary.learning_states_moved,
+                summary.learning_states_dropped,
+                summary.wordbook_entries_dropped,
+            ),
+            (1, 1, 1, 1)
+        );
+
+        assert!(store.get_word("w2").unwrap().is_none());
+        assert_eq!(store.count_words().unwrap(), 2);
+        assert_eq!(store.get_user_word_history("u1", "w1", 10, 0).unwrap().1, 1);
+        let kept = store.get_word_learning_state("u1", "w1").unwrap().unwrap();
+        assert_eq!(kept.total_attempts, 5);
+        assert!(store.get_word_learning_state("u1", "w2").unwrap().is_none());
+        assert_eq!(store.get_wordbook("b1").unwrap().unwrap().word_count, 1);
+        assert_eq!(store.word_reference_counts("w1").unwrap().records, 1);
+        assert!(store.find_duplicate_words().unwrap().is_empty());
+
+        let err = store
+            .merge_words("w1", &["w3".to_string()], false)
+            .unwrap_err();
+        assert!(matches!(err, StoreError::Validation(_)));
+    }
+}
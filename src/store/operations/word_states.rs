@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sled::Transactional;
 use std::collections::{HashMap, HashSet};
 
+use crate::constants::DEFAULT_HALF_LIFE_HOURS;
 use crate::store::keys;
 use crate::store::{Store, StoreError};
 
@@ -40,6 +41,18 @@ pub struct WordStateStats {
     pub forgotten: u64,
 }
 
+impl WordStateStats {
+    fn add(&mut self, state: &WordState) {
+        match state {
+            WordState::New => self.new_count += 1,
+            WordState::Learning => self.learning += 1,
+            WordState::Reviewing => self.reviewing += 1,
+            WordState::Mastered => self.mastered += 1,
+            WordState::Forgotten => self.forgotten += 1,
+        }
+    }
+}
+
 fn due_index_key_for_state(wls: &WordLearningState) -> Result<Option<String>, StoreError> {
     match wls.next_review_date {
         Some(next_review_date) => Ok(Some(keys::word_due_index_key(
@@ -196,17 +209,105 @@ impl Store {
         for item in self.word_learning_states.scan_prefix(prefix.as_bytes()) {
             let (_, v) = item?;
             let wls: WordLearningState = Self::deserialize(&v)?;
-            match wls.state {
-                WordState::New => stats.new_count += 1,
-                WordState::Learning => stats.learning += 1,
-                WordState::Reviewing => stats.reviewing += 1,
-                WordState::Mastered => stats.mastered += 1,
-                WordState::Forgotten => stats.forgotten += 1,
+            stats.add(&wls.state);
+        }
+        Ok(stats)
+    }
+
+    /// 统计用户在某词书内各学习状态的单词数，返回 (词书单词总数, 状态统计)，
+    /// 没有学习状态的单词计入 new。
+    /// 从较小的一侧做关联：用户学过的单词少于词书规模时遍历用户学习状态，
+    /// 否则遍历词书单词逐个查询，避免大词书逐词反序列化
+    pub fn get_wordbook_word_state_stats(
+        &self,
+        user_id: &str,
+        wordbook_id: &str,
+    ) -> Result<(u64, WordStateStats), StoreError> {
+        let total = self.count_wordbook_words(wordbook_id)?;
+        let studied = self.get_user_stats_agg(user_id)?.word_ids.len() as u64;
+        let mut stats = if studied < total {
+            self.wordbook_state_stats_by_user_states(user_id, wordbook_id)?
+        } else {
+            self.wordbook_state_stats_by_book_words(user_id, wordbook_id)?
+        };
+        let started = stats.learning + stats.reviewing + stats.mastered + stats.forgotten;
+        stats.new_count = total.saturating_sub(started);
+        Ok((total, stats))
+    }
+
+    fn wordbook_state_stats_by_user_states(
+        &self,
+        user_id: &str,
+        wordbook_id: &str,
+    ) -> Result<WordStateStats, StoreError> {
+        let prefix = keys::word_learning_state_prefix(user_id)?;
+        let mut stats = WordStateStats::default();
+        for item in self.word_learning_states.scan_prefix(prefix.as_bytes()) {
+            let (_, v) = item?;
+            let wls: WordLearningState = Self::deserialize(&v)?;
+            let book_key = keys::wordbook_words_key(wordbook_id, &wls.word_id)?;
+            if self.wordbook_words.contains_key(book_key.as_bytes())? {
+                stats.add(&wls.state);
+            }
+        }
+        Ok(stats)
+    }
+
+    fn wordbook_state_stats_by_book_words(
+        &self,
+        user_id: &str,
+        wordbook_id: &str,
+    ) -> Result<WordStateStats, StoreError> {
+        let prefix = keys::wordbook_words_prefix(wordbook_id)?;
+        let mut stats = WordStateStats::default();
+        for key in self.wordbook_words.scan_prefix(prefix.as_bytes()).keys() {
+            let key = key?;
+            let word_id = String::from_utf8_lossy(&key[prefix.len()..]);
+            if let Some(wls) = self.get_word_learning_state(user_id, &word_id)? {
+                stats.add(&wls.state);
             }
         }
         Ok(stats)
     }
 
+    /// 有作答但从未答对的单词，按作答次数降序
+    pub fn get_never_correct_word_states(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<WordLearningState>, StoreError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RecordOutcome {
+            word_id: String,
+            is_correct: bool,
+        }
+
+        let record_prefix = keys::record_prefix(user_id)?;
+        let mut ever_correct = HashSet::new();
+        for item in self.records.scan_prefix(record_prefix.as_bytes()) {
+            let (_, v) = item?;
+            let outcome: RecordOutcome = Self::deserialize(&v)?;
+            if outcome.is_correct {
+                ever_correct.insert(outcome.word_id);
+            }
+        }
+
+        let prefix = keys::word_learning_state_prefix(user_id)?;
+        let mut states = Vec::new();
+        for item in self.word_learning_states.scan_prefix(prefix.as_bytes()) {
+            let (_, v) = item?;
+            let wls: WordLearningState = Self::deserialize(&v)?;
+            if wls.total_attempts > 0
+                && wls.state != WordState::Mastered
+                && !ever_correct.contains(&wls.word_id)
+            {
+                states.push(wls);
+            }
+        }
+        states.sort_by_key(|s| std::cmp::Reverse(s.total_attempts));
+        Ok(states)
+    }
+
     pub fn delete_word_learning_state(
         &self,
         user_id: &str,
@@ -246,6 +347,57 @@ impl Store {
         Ok(())
     }
 
+    /// 在同一事务内将单词学习状态重置为 New，并清除到期索引与 mastery 算法状态。
+    /// 单词从未学习过时不写入任何数据，返回 false
+    pub fn reset_word_progress(&self, user_id: &str, word_id: &str) -> Result<bool, StoreError> {
+        let key = keys::word_learning_state_key(user_id, word_id)?;
+        let mastery_key = keys::engine_algo_state_key(user_id, &format!("mastery:{word_id}"))?;
+        let fresh = Self::serialize(&WordLearningState {
+            user_id: user_id.to_string(),
+            word_id: word_id.to_string(),
+            state: WordState::New,
+            mastery_level: 0.0,
+            next_review_date: None,
+            half_life: DEFAULT_HALF_LIFE_HOURS,
+            correct_streak: 0,
+            total_attempts: 0,
+            updated_at: Utc::now(),
+        })?;
+
+        (
+            &self.word_learning_states,
+            &self.word_due_index,
+            &self.engine_algorithm_states,
+        )
+            .transaction(|(tx_states, tx_due_index, tx_algo)| {
+                let had_mastery = tx_algo.remove(mastery_key.as_bytes())?.is_some();
+                let Some(old_raw) = tx_states.get(key.as_bytes())? else {
+                    return Ok(had_mastery);
+                };
+                let old_state: WordLearningState =
+                    serde_json::from_slice(&old_raw).map_err(|error| {
+                        sled::transaction::ConflictableTransactionError::Abort(
+                            StoreError::Serialization(error),
+                        )
+                    })?;
+                if let Some(old_due_index_key) = due_index_key_for_state(&old_state)
+                    .map_err(sled::transaction::ConflictableTransactionError::Abort)?
+                {
+                    tx_due_index.remove(old_due_index_key.as_bytes())?;
+                }
+                tx_states.insert(key.as_bytes(), fresh.as_slice())?;
+                Ok(true)
+            })
+            .map_err(
+                |error: sled::transaction::TransactionError<StoreError>| match error {
+                    sled::transaction::TransactionError::Abort(store_error) => store_error,
+                    sled::transaction::TransactionError::Storage(storage_error) => {
+                        StoreError::Sled(storage_error)
+                    }
+                },
+            )
+    }
+
     pub fn list_user_word_states(
         &self,
         user_id: &str,
@@ -318,6 +470,81 @@ mod tests {
         assert_eq!(results[2].total_attempts, 7);
     }
 
+    #[test]
+    fn wordbook_state_stats_agree_for_both_join_directions() {
+        use crate::store::operations::wordbooks::{Wordbook, WordbookType};
+
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("db-book-stats").to_str().unwrap()).unwrap();
+        store
+            .upsert_wordbook(&Wordbook {
+                id: "b1".to_string(),
+                name: "book".to_string(),
+                description: String::new(),
+                book_type: WordbookType::System,
+                user_id: None,
+                word_count: 0,
+                created_at: Utc::now(),
+            })
+            .unwrap();
+        for i in 1..=4 {
+            store.add_word_to_wordbook("b1", &format!("w{i}")).unwrap();
+        }
+
+        let mut mastered = mock_word_learning_state("u1", "w1", 5);
+        mastered.state = WordState::Mastered;
+        store.set_word_learning_state(&mastered).unwrap();
+        store
+            .set_word_learning_state(&mock_word_learning_state("u1", "w2", 2))
+            .unwrap();
+        // 不在词书中的单词不计入
+        store
+            .set_word_learning_state(&mock_word_learning_state("u1", "other", 1))
+            .unwrap();
+
+        let by_user = store
+            .wordbook_state_stats_by_user_states("u1", "b1")
+            .unwrap();
+        let by_book = store
+            .wordbook_state_stats_by_book_words("u1", "b1")
+            .unwrap();
+        assert_eq!((by_user.mastered, by_user.learning), (1, 1));
+        assert_eq!((by_book.mastered, by_book.learning), (1, 1));
+
+        let (total, stats) = store.get_wordbook_word_state_stats("u1", "b1").unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(stats.new_count, 2);
+    }
+
+    #[test]
+    fn reset_word_progress_clears_due_index_and_mastery_state() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("db-reset").to_str().unwrap()).unwrap();
+
+        let mut state = mock_word_learning_state("u1", "w1", 4);
+        state.next_review_date = Some(Utc::now() - Duration::minutes(1));
+        store.set_word_learning_state(&state).unwrap();
+        store
+            .set_engine_algo_state("u1", "mastery:w1", &serde_json::json!({"halfLife": 48.0}))
+            .unwrap();
+
+        assert!(store.reset_word_progress("u1", "w1").unwrap());
+        let reset = store.get_word_learning_state("u1", "w1").unwrap().unwrap();
+        assert_eq!(reset.state, WordState::New);
+        assert_eq!(reset.total_attempts, 0);
+        assert!(store.get_due_words("u1", 10).unwrap().is_empty());
+        assert!(store
+            .get_engine_algo_state("u1", "mastery:w1")
+            .unwrap()
+            .is_none());
+
+        assert!(!store.reset_word_progress("u1", "untouched").unwrap());
+        assert!(store
+            .get_word_learning_state("u1", "untouched")
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn get_due_words_returns_asc_order_and_respects_limit() {
         let dir = tempdir().unwrap();
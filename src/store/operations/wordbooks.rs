@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sled::Transactional;
 
 use crate::store::keys;
+use crate::store::operations::words::Word;
 use crate::store::{Store, StoreError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,8 +101,38 @@ impl Store {
         &self,
         wordbook_id: &str,
         word_id: &str,
+    ) -> Result<bool, StoreError> {
+        self.insert_wordbook_word(wordbook_id, word_id, false)
+    }
+
+    /// 与 `add_word_to_wordbook` 相同，但词书中已有规范化拼写相同的其他单词时返回 `Conflict`
+    pub fn add_unique_word_to_wordbook(
+        &self,
+        wordbook_id: &str,
+        word_id: &str,
+    ) -> Result<bool, StoreError> {
+        self.insert_wordbook_word(wordbook_id, word_id, true)
+    }
+
+    fn insert_wordbook_word(
+        &self,
+        wordbook_id: &str,
+        word_id: &str,
+        unique_text: bool,
     ) -> Result<bool, StoreError> {
         let ww_key = keys::wordbook_words_key(wordbook_id, word_id)?;
+        let mut homograph_keys = Vec::new();
+        let mut word_text = String::new();
+        if unique_text {
+            if let Some(word) = self.get_word(word_id)? {
+                for other in self.word_ids_by_text(&word.text)? {
+                    if other != word_id {
+                        homograph_keys.push(keys::wordbook_words_key(wordbook_id, &other)?);
+                    }
+                }
+                word_text = word.text;
+            }
+        }
         let wordbook_id_owned = wordbook_id.to_string();
         let entry = WordbookWordEntry {
             wordbook_id: wordbook_id.to_string(),
@@ -125,6 +156,17 @@ impl Store {
                     )
                 })?;
 
+                for key in &homograph_keys {
+                    if tx_ww.get(key.as_bytes())?.is_some() {
+                        return Err(sled::transaction::ConflictableTransactionError::Abort(
+                            StoreError::Conflict {
+                                entity: "wordbook_word_text".to_string(),
+                                key: word_text.clone(),
+                            },
+                        ));
+                    }
+                }
+
                 let inserted_new = tx_ww
                     .insert(ww_key.as_bytes(), entry_bytes.as_slice())?
                     .is_none();
@@ -160,6 +202,34 @@ impl Store {
         Ok(inserted)
     }
 
+    /// 包含该单词的词书中是否已有规范化拼写相同的其他单词，用于改名前检查
+    pub fn has_wordbook_text_conflict(&self, word: &Word) -> Result<bool, StoreError> {
+        let homographs: Vec<String> = self
+            .word_ids_by_text(&word.text)?
+            .into_iter()
+            .filter(|id| id != &word.id)
+            .collect();
+        if homographs.is_empty() {
+            return Ok(false);
+        }
+        for (tree_name, assoc_key) in self.live_word_references(&word.id)? {
+            if tree_name != "wordbook_words" {
+                continue;
+            }
+            let assoc_key = String::from_utf8_lossy(&assoc_key);
+            let Some((wordbook_id, _)) = assoc_key.split_once(':') else {
+                continue;
+            };
+            for other in &homographs {
+                let key = keys::wordbook_words_key(wordbook_id, other)?;
+                if self.wordbook_words.contains_key(key.as_bytes())? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
     pub fn remove_word_from_wordbook(
         &self,
         wordbook_id: &str,
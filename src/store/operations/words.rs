@@ -1,9 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sled::transaction::{ConflictableTransactionError, TransactionError, TransactionalTree};
 use sled::Transactional;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crate::store::keys;
+use crate::store::operations::counters::{self, Counter};
+use crate::store::operations::word_merge::normalize_word_text;
 use crate::store::{Store, StoreError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,10 +24,168 @@ pub struct Word {
     pub created_at: DateTime<Utc>,
 }
 
+impl Word {
+    fn matches_query(&self, query_lower: &str) -> bool {
+        self.text.to_lowercase().contains(query_lower)
+            || self.meaning.to_lowercase().contains(query_lower)
+            || self
+                .pronunciation
+                .as_deref()
+                .is_some_and(|p| p.to_lowercase().contains(query_lower))
+    }
+}
+
+/// 单词列表的筛选条件，各条件之间为“且”
+#[derive(Debug, Clone, Default)]
+pub struct WordFilter {
+    /// 为空时不按标签筛选
+    pub tags: Vec<String>,
+    /// 为真时须包含全部标签，否则包含任一即可
+    pub match_all_tags: bool,
+    /// 匹配拼写、释义与音标，不区分大小写
+    pub search: Option<String>,
+    /// 词性，不区分大小写；未标注词性的单词不会命中
+    pub part_of_speech: Option<String>,
+}
+
+impl WordFilter {
+    pub fn is_empty(&self) -> bool {
+        normalized_tags(&self.tags).is_empty()
+            && self.search.is_none()
+            && self.part_of_speech.is_none()
+    }
+
+    fn matches(&self, word: &Word, query_lower: Option<&str>) -> bool {
+        let pos_ok = self.part_of_speech.as_deref().map_or(true, |pos| {
+            word.part_of_speech
+                .as_deref()
+                .is_some_and(|p| p.trim().eq_ignore_ascii_case(pos.trim()))
+        });
+        pos_ok && query_lower.map_or(true, |q| word.matches_query(q))
+    }
+}
+
+/// 去除首尾空白后的非空标签
+fn normalized_tags(tags: &[String]) -> BTreeSet<&str> {
+    tags.iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn tag_index_batch(
+    word_id: &str,
+    old_tags: &[String],
+    new_tags: &[String],
+) -> Result<sled::Batch, StoreError> {
+    let (old, new) = (normalized_tags(old_tags), normalized_tags(new_tags));
+    let mut batch = sled::Batch::default();
+    for tag in old.difference(&new) {
+        batch.remove(keys::word_tag_key(tag, word_id)?.as_bytes());
+    }
+    for tag in &new {
+        batch.insert(keys::word_tag_key(tag, word_id)?.as_bytes(), &[]);
+    }
+    Ok(batch)
+}
+
+type TextTxResult<T> = Result<T, ConflictableTransactionError<StoreError>>;
+
+fn read_text_ids(tx_text: &TransactionalTree, text_key: &str) -> TextTxResult<Vec<String>> {
+    match tx_text.get(text_key.as_bytes())? {
+        Some(raw) => serde_json::from_slice(&raw)
+            .map_err(|e| ConflictableTransactionError::Abort(StoreError::from(e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn write_text_ids(tx_text: &TransactionalTree, text_key: &str, ids: &[String]) -> TextTxResult<()> {
+    if ids.is_empty() {
+        tx_text.remove(text_key.as_bytes())?;
+    } else {
+        let bytes = serde_json::to_vec(ids)
+            .map_err(|e| ConflictableTransactionError::Abort(StoreError::from(e)))?;
+        tx_text.insert(text_key.as_bytes(), bytes)?;
+    }
+    Ok(())
+}
+
+fn remove_text_entry(
+    tx_text: &TransactionalTree,
+    text_key: &str,
+    word_id: &str,
+) -> TextTxResult<()> {
+    let mut ids = read_text_ids(tx_text, text_key)?;
+    let before = ids.len();
+    ids.retain(|id| id != word_id);
+    if ids.len() != before {
+        write_text_ids(tx_text, text_key, &ids)?;
+    }
+    Ok(())
+}
+
 impl Store {
     pub fn upsert_word(&self, word: &Word) -> Result<(), StoreError> {
+        self.write_word(word, false)
+    }
+
+    /// 与 `upsert_word` 相同，但规范化拼写已被其他单词占用时返回 `Conflict`
+    pub fn upsert_unique_word(&self, word: &Word) -> Result<(), StoreError> {
+        self.write_word(word, true)
+    }
+
+    fn write_word(&self, word: &Word, unique_text: bool) -> Result<(), StoreError> {
         let key = keys::word_key(&word.id)?;
-        self.words.insert(key.as_bytes(), Self::serialize(word)?)?;
+        let bytes = Self::serialize(word)?;
+        let text_key = normalize_word_text(&word.text);
+        (
+            &self.words,
+            &self.counters,
+            &self.word_text_index,
+            &self.word_tag_index,
+        )
+            .transaction(|(tx_words, tx_counters, tx_text, tx_tags)| {
+                let previous = tx_words.insert(key.as_bytes(), bytes.as_slice())?;
+                let previous_tags = match &previous {
+                    Some(raw) => {
+                        let old: Word = serde_json::from_slice(raw).map_err(|e| {
+                            ConflictableTransactionError::Abort(StoreError::from(e))
+                        })?;
+                        let old_text = normalize_word_text(&old.text);
+                        if old_text != text_key && !old_text.is_empty() {
+                            remove_text_entry(tx_text, &old_text, &word.id)?;
+                        }
+                        old.tags
+                    }
+                    None => {
+                        counters::adjust_in_tx(tx_counters, Counter::Words, 1)?;
+                        Vec::new()
+                    }
+                };
+                let batch = tag_index_batch(&word.id, &previous_tags, &word.tags)
+                    .map_err(ConflictableTransactionError::Abort)?;
+                tx_tags.apply_batch(&batch)?;
+
+                if text_key.is_empty() {
+                    return Ok(());
+                }
+                let mut ids = read_text_ids(tx_text, &text_key)?;
+                if unique_text && ids.iter().any(|id| id != &word.id) {
+                    return Err(ConflictableTransactionError::Abort(StoreError::Conflict {
+                        entity: "word_text".to_string(),
+                        key: word.text.clone(),
+                    }));
+                }
+                if !ids.contains(&word.id) {
+                    ids.push(word.id.clone());
+                    write_text_ids(tx_text, &text_key, &ids)?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })?;
         // Maintain words_by_created_at index
         let idx_key = keys::words_by_created_at_key(
             word.created_at.timestamp_millis(),
@@ -35,6 +196,104 @@ impl Store {
         Ok(())
     }
 
+    /// 规范化拼写相同的全部单词 ID
+    pub fn word_ids_by_text(&self, text: &str) -> Result<Vec<String>, StoreError> {
+        let normalized = normalize_word_text(text);
+        if normalized.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self.word_text_index.get(normalized.as_bytes())? {
+            Some(raw) => Self::deserialize(&raw),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 从 word_text_index 中移除单词
+    pub(crate) fn unindex_word_text(&self, word: &Word) -> Result<(), StoreError> {
+        let normalized = normalize_word_text(&word.text);
+        if normalized.is_empty() {
+            return Ok(());
+        }
+        self.word_text_index
+            .transaction(|tx_text| remove_text_entry(tx_text, &normalized, &word.id))
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })
+    }
+
+    /// 将单词补记到 word_text_index，已存在时不变
+    pub(crate) fn index_word_text(&self, word: &Word) -> Result<(), StoreError> {
+        let normalized = normalize_word_text(&word.text);
+        if normalized.is_empty() {
+            return Ok(());
+        }
+        self.word_text_index
+            .transaction(|tx_text| {
+                let mut ids = read_text_ids(tx_text, &normalized)?;
+                if !ids.contains(&word.id) {
+                    ids.push(word.id.clone());
+                    write_text_ids(tx_text, &normalized, &ids)?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })
+    }
+
+    /// 按新旧标签差异维护 word_tag_index
+    pub(crate) fn sync_word_tags(
+        &self,
+        word_id: &str,
+        old_tags: &[String],
+        new_tags: &[String],
+    ) -> Result<(), StoreError> {
+        let batch = tag_index_batch(word_id, old_tags, new_tags)?;
+        self.word_tag_index.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// 在同一事务内增删单词标签并维护 word_tag_index，同一标签同时出现在增删两侧时以新增为准；
+    /// 单词不存在时返回 `None`
+    pub fn update_word_tags(
+        &self,
+        word_id: &str,
+        add_tags: &[String],
+        remove_tags: &[String],
+    ) -> Result<Option<Word>, StoreError> {
+        let key = keys::word_key(word_id)?;
+        let (add, remove) = (normalized_tags(add_tags), normalized_tags(remove_tags));
+
+        (&self.words, &self.word_tag_index)
+            .transaction(|(tx_words, tx_tags)| {
+                let Some(raw) = tx_words.get(key.as_bytes())? else {
+                    return Ok(None);
+                };
+                let mut word: Word = serde_json::from_slice(&raw)
+                    .map_err(|e| ConflictableTransactionError::Abort(StoreError::from(e)))?;
+                let old_tags = word.tags.clone();
+                word.tags.retain(|t| !remove.contains(t.trim()));
+                for tag in &add {
+                    if !word.tags.iter().any(|t| t.trim() == *tag) {
+                        word.tags.push(tag.to_string());
+                    }
+                }
+
+                let batch = tag_index_batch(word_id, &old_tags, &word.tags)
+                    .map_err(ConflictableTransactionError::Abort)?;
+                let bytes = Self::serialize(&word).map_err(ConflictableTransactionError::Abort)?;
+                tx_words.insert(key.as_bytes(), bytes)?;
+                tx_tags.apply_batch(&batch)?;
+                Ok(Some(word))
+            })
+            .map_err(|e: TransactionError<StoreError>| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(se) => StoreError::Sled(se),
+            })
+    }
+
     pub fn get_word(&self, word_id: &str) -> Result<Option<Word>, StoreError> {
         let key = keys::word_key(word_id)?;
         match self.words.get(key.as_bytes())? {
@@ -201,9 +460,12 @@ impl Store {
             &self.word_due_index,
             &self.records,
             &self.wordbooks,
+            &self.counters,
         )
-            .transaction(|(tx_words, tx_ww, tx_wls, tx_due, tx_rec, tx_wb)| {
-                tx_words.remove(word_key.as_bytes())?;
+            .transaction(|(tx_words, tx_ww, tx_wls, tx_due, tx_rec, tx_wb, tx_cnt)| {
+                if tx_words.remove(word_key.as_bytes())?.is_some() {
+                    counters::adjust_in_tx(tx_cnt, Counter::Words, -1)?;
+                }
 
                 for k in &ww_keys_to_remove {
                     tx_ww.remove(k.as_slice())?;
@@ -214,9 +476,13 @@ impl Store {
                 for k in &due_index_keys_to_remove {
                     tx_due.remove(k.as_slice())?;
                 }
+                let mut removed_records = 0;
                 for k in &rec_keys_to_remove {
-                    tx_rec.remove(k.as_slice())?;
+                    if tx_rec.remove(k.as_slice())?.is_some() {
+                        removed_records += 1;
+                    }
                 }
+                counters::adjust_in_tx(tx_cnt, Counter::Records, -removed_records)?;
                 for (wb_key, wb_bytes) in &wordbook_updates {
                     tx_wb.insert(wb_key.as_slice(), wb_bytes.as_slice())?;
                 }
@@ -237,7 +503,10 @@ impl Store {
             if let Ok(idx_key) = keys::words_by_created_at_key(word.created_at.timestamp_millis(), word_id) {
                 let _ = self.words_by_created_at.remove(idx_key.as_bytes());
             }
+            let _ = self.sync_word_tags(word_id, &word.tags, &[]);
+            let _ = self.unindex_word_text(&word);
         }
+        let _ = self.delete_generated_examples(word_id);
 
         // Clean up records_by_time and record_id_index for deleted records
         for rec_key in &rec_keys_to_remove {
@@ -267,7 +536,7 @@ impl Store {
     }
 
     pub fn count_words(&self) -> Result<u64, StoreError> {
-        Ok(self.words.len() as u64)
+        self.read_counter(Counter::Words)
     }
 
     pub fn search_words(
@@ -276,17 +545,42 @@ impl Store {
         limit: usize,
         offset: usize,
     ) -> Result<(Vec<Word>, u64), StoreError> {
-        // 注意：文本搜索需要遍历所有单词来匹配，无法使用前缀扫描优化。
-        // TODO: 引入全文搜索索引（如倒排索引）来避免全表扫描。
-        let query_lower = query.to_lowercase();
+        let filter = WordFilter {
+            search: Some(query.to_string()),
+            ..Default::default()
+        };
+        self.filter_words(&filter, limit, offset)
+    }
+
+    /// 按条件筛选单词，结果按拼写排序。指定标签时先经 word_tag_index 缩小候选集，
+    /// 否则需遍历所有单词。
+    // TODO: 引入全文搜索索引（如倒排索引）来避免关键词搜索的全表扫描。
+    pub fn filter_words(
+        &self,
+        filter: &WordFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<Word>, u64), StoreError> {
+        let query_lower = filter.search.as_deref().map(str::to_lowercase);
         let mut matching = Vec::new();
-        for item in self.words.iter() {
-            let (_, v) = item?;
-            let word: Word = Self::deserialize(&v)?;
-            if word.text.to_lowercase().contains(&query_lower)
-                || word.meaning.to_lowercase().contains(&query_lower)
-            {
-                matching.push(word);
+        match self.word_ids_matching_tags(filter)? {
+            Some(word_ids) => {
+                for word_id in word_ids {
+                    if let Some(word) = self.get_word(&word_id)? {
+                        if filter.matches(&word, query_lower.as_deref()) {
+                            matching.push(word);
+                        }
+                    }
+                }
+            }
+            None => {
+                for item in self.words.iter() {
+                    let (_, v) = item?;
+                    let word: Word = Self::deserialize(&v)?;
+                    if filter.matches(&word, query_lower.as_deref()) {
+                        matching.push(word);
+                    }
+                }
             }
         }
         matching.sort_by(|a, b| a.text.cmp(&b.text));
@@ -295,6 +589,55 @@ impl Store {
         Ok((items, total))
     }
 
+    /// 未指定标签时返回 `None`
+    fn word_ids_matching_tags(
+        &self,
+        filter: &WordFilter,
+    ) -> Result<Option<HashSet<String>>, StoreError> {
+        let mut matched: Option<HashSet<String>> = None;
+        for tag in normalized_tags(&filter.tags) {
+            let ids = self.word_ids_with_tag(tag)?;
+            matched = Some(match matched {
+                None => ids,
+                Some(acc) if filter.match_all_tags => acc.intersection(&ids).cloned().collect(),
+                Some(mut acc) => {
+                    acc.extend(ids);
+                    acc
+                }
+            });
+        }
+        Ok(matched)
+    }
+
+    pub(crate) fn word_ids_with_tag(&self, tag: &str) -> Result<HashSet<String>, StoreError> {
+        let prefix = keys::word_tag_prefix(tag)?;
+        let mut ids = HashSet::new();
+        for item in self.word_tag_index.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = item?;
+            // 前缀 `a:` 也会扫到标签 `a:b` 的条目，需按完整标签过滤
+            if let Some((t, word_id)) = keys::parse_word_tag_key(&key) {
+                if t == tag {
+                    ids.insert(word_id.to_string());
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// 所有标签及其单词数，按数量降序、同数量按标签名排序
+    pub fn list_word_tags(&self) -> Result<Vec<(String, u64)>, StoreError> {
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        for item in self.word_tag_index.iter() {
+            let (key, _) = item?;
+            if let Some((tag, _)) = keys::parse_word_tag_key(&key) {
+                *counts.entry(tag.to_string()).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<(String, u64)> = counts.into_iter().collect();
+        tags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        Ok(tags)
+    }
+
     pub fn get_words_without_embedding(&self, limit: usize) -> Result<Vec<Word>, StoreError> {
         let mut words = Vec::new();
         for item in self.words.iter() {
@@ -370,4 +713,122 @@ mod tests {
         assert!(words.contains_key("w1"));
         assert!(words.contains_key("w2"));
     }
+
+    #[test]
+    fn tag_index_follows_upserts_and_deletes() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("words-db-tags").to_str().unwrap()).unwrap();
+
+        let mut apple = sample_word("w1", "apple");
+        apple.tags = vec!["basic".to_string(), "TOEFL".to_string()];
+        let mut banana = sample_word("w2", "banana");
+        banana.tags = vec!["basic".to_string(), "a:b".to_string()];
+        store.upsert_word(&apple).unwrap();
+        store.upsert_word(&banana).unwrap();
+
+        let filter = |tags: &[&str], match_all_tags: bool, search: Option<&str>| WordFilter {
+            tags: tags.iter().map(|s| s.to_string()).collect(),
+            match_all_tags,
+            search: search.map(str::to_string),
+            ..Default::default()
+        };
+        let texts = |f: WordFilter| {
+            let (words, _) = store.filter_words(&f, 10, 0).unwrap();
+            words.into_iter().map(|w| w.text).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(filter(&["basic", "TOEFL"], true, None)),
+            vec!["apple"]
+        );
+        assert_eq!(
+            texts(filter(&["TOEFL", "a:b"], false, None)),
+            vec!["apple", "banana"]
+        );
+        assert_eq!(texts(filter(&["basic"], true, Some("BAN"))), vec!["banana"]);
+        // 标签 `a` 不应匹配 `a:b`
+        assert!(texts(filter(&["a"], true, None)).is_empty());
+
+        apple.tags = vec!["basic".to_string()];
+        store.upsert_word(&apple).unwrap();
+        store.delete_word("w2").unwrap();
+        assert_eq!(
+            store.list_word_tags().unwrap(),
+            vec![("basic".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn update_word_tags_adds_and_removes_in_one_step() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("words-db-bulk-tags").to_str().unwrap()).unwrap();
+        store.upsert_word(&sample_word("w1", "apple")).unwrap();
+
+        let updated = store
+            .update_word_tags(
+                "w1",
+                &[" TOEFL ".to_string(), "tag".to_string()],
+                &["tag".to_string(), "basic".to_string()],
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.tags, vec!["TOEFL", "tag"]);
+        assert_eq!(store.get_word("w1").unwrap().unwrap().tags, updated.tags);
+
+        store
+            .update_word_tags("w1", &[], &["tag".to_string()])
+            .unwrap();
+        assert_eq!(
+            store.list_word_tags().unwrap(),
+            vec![("TOEFL".to_string(), 1)]
+        );
+        assert!(store
+            .update_word_tags("missing", &[], &[])
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn filter_matches_part_of_speech_and_pronunciation() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("words-db-pos").to_str().unwrap()).unwrap();
+
+        let mut run = sample_word("w1", "run");
+        run.part_of_speech = Some("Verb".to_string());
+        run.pronunciation = Some("/rʌn/".to_string());
+        let mut apple = sample_word("w2", "apple");
+        apple.part_of_speech = Some("noun".to_string());
+        store.upsert_word(&run).unwrap();
+        store.upsert_word(&apple).unwrap();
+        store.upsert_word(&sample_word("w3", "unknown")).unwrap();
+
+        let verbs = WordFilter {
+            part_of_speech: Some("verb".to_string()),
+            ..Default::default()
+        };
+        let (words, total) = store.filter_words(&verbs, 10, 0).unwrap();
+        assert_eq!((words[0].id.as_str(), total), ("w1", 1));
+        let (words, _) = store.search_words("rʌn", 10, 0).unwrap();
+        assert_eq!(words[0].id, "w1");
+    }
+
+    #[test]
+    fn unique_upsert_rejects_normalized_homographs() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("words-db-unique").to_str().unwrap()).unwrap();
+
+        store.upsert_unique_word(&sample_word("w1", "Apple")).unwrap();
+        // 非强制写入允许同形词
+        store.upsert_word(&sample_word("w2", " apple ")).unwrap();
+        assert_eq!(store.word_ids_by_text("APPLE").unwrap(), vec!["w1", "w2"]);
+
+        let err = store.upsert_unique_word(&sample_word("w3", "apple")).unwrap_err();
+        assert!(matches!(err, StoreError::Conflict { .. }));
+        assert!(store.get_word("w3").unwrap().is_none());
+        assert_eq!(store.count_words().unwrap(), 2);
+
+        store.delete_word("w2").unwrap();
+        store.upsert_unique_word(&sample_word("w1", "pear")).unwrap();
+        assert!(store.word_ids_by_text("apple").unwrap().is_empty());
+        assert_eq!(store.word_ids_by_text("Pear").unwrap(), vec!["w1"]);
+    }
 }
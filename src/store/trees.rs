@@ -10,6 +10,7 @@
 pub const USERS: &str = "users";
 pub const SESSIONS: &str = "sessions";
 pub const ADMIN_SESSIONS: &str = "admin_sessions";
+pub const REFRESH_TOKENS: &str = "refresh_tokens";
 pub const WORDS: &str = "words";
 pub const RECORDS: &str = "records";
 pub const LEARNING_SESSIONS: &str = "learning_sessions";
@@ -21,6 +22,7 @@ pub const ENGINE_MONITORING_EVENTS: &str = "engine_monitoring";
 /// 常量名 ALGORITHM_METRICS_DAILY，tree 名缩写为 algo_metrics_daily
 pub const ALGORITHM_METRICS_DAILY: &str = "algo_metrics_daily";
 pub const PASSWORD_RESET_TOKENS: &str = "password_reset_tokens";
+pub const EMAIL_CHANGE_TOKENS: &str = "email_change_tokens";
 pub const CONFIG_VERSIONS: &str = "config_versions";
 
 // P0 new trees
@@ -37,9 +39,16 @@ pub const HABIT_PROFILES: &str = "habit_profiles";
 pub const NOTIFICATIONS: &str = "notifications";
 pub const BADGES: &str = "badges";
 pub const USER_PREFERENCES: &str = "user_preferences";
+pub const USER_WEBHOOKS: &str = "user_webhooks";
+pub const USER_WEEKLY_REPORTS: &str = "user_weekly_reports";
+pub const VISUAL_FATIGUE_HISTORY: &str = "visual_fatigue_history";
 pub const ETYMOLOGIES: &str = "etymologies";
 pub const WORD_MORPHEMES: &str = "word_morphemes";
 pub const CONFUSION_PAIRS: &str = "confusion_pairs";
+pub const WORD_CLUSTERS: &str = "word_clusters";
+pub const GENERATED_EXAMPLES: &str = "generated_examples";
+/// 未通过审核的 LLM 生成内容，供管理员复核
+pub const MODERATION_REJECTIONS: &str = "moderation_rejections";
 pub const WB_CENTER_IMPORTS: &str = "wb_center_imports";
 
 pub const WORDBOOK_TYPE_INDEX: &str = "idx_wordbook_type";
@@ -47,9 +56,26 @@ pub const WORDBOOK_TYPE_INDEX: &str = "idx_wordbook_type";
 // Secondary index trees (performance optimization)
 pub const USERS_BY_CREATED_AT: &str = "idx_users_by_created";
 pub const WORDS_BY_CREATED_AT: &str = "idx_words_by_created";
+pub const WORD_TAG_INDEX: &str = "idx_word_tags";
+/// 规范化拼写 -> 该拼写下全部单词 ID（JSON 数组）
+pub const WORD_TEXT_INDEX: &str = "idx_word_text";
 pub const RECORDS_BY_TIME: &str = "idx_records_by_time";
 pub const WORD_REFERENCES: &str = "idx_word_refs";
 pub const USER_STATS: &str = "idx_user_stats";
 pub const RECORD_ID_INDEX: &str = "idx_record_id";
 pub const ALERT_DEDUP: &str = "idx_alert_dedup";
 pub const MONITORING_TIMESERIES: &str = "monitoring_ts";
+pub const ALGO_STATE_REPAIRS: &str = "algo_state_repairs";
+pub const DEFERRED_NOTIFICATIONS: &str = "idx_deferred_notifications";
+pub const IDEMPOTENCY_KEYS: &str = "idempotency_keys";
+pub const MONITORING_DAILY: &str = "monitoring_daily";
+/// LLM 调用的 token 用量与估算费用，按天汇总
+pub const LLM_USAGE_DAILY: &str = "llm_usage_daily";
+/// 定时广播，按计划时间排序
+pub const SCHEDULED_BROADCASTS: &str = "scheduled_broadcasts";
+/// 广播逐个接收人的送达记录，按广播 ID 分组
+pub const BROADCAST_DELIVERIES: &str = "broadcast_deliveries";
+/// llm_advisor worker 每次运行产出的配置建议与异常标记
+pub const LLM_ADVISORIES: &str = "llm_advisories";
+/// users/words/records 的条目计数，随增删在同一事务中维护
+pub const COUNTERS: &str = "counters";
@@ -1,18 +1,71 @@
 /// 公共验证函数模块
 /// 提供密码、邮箱、用户名等输入验证，供认证和用户相关路由共用。
-/// 验证密码强度：至少 8 字符、最多 256 字符，需包含大小写字母和数字
-pub fn validate_password(password: &str) -> Result<(), &'static str> {
-    if password.len() < 8 {
-        return Err("密码长度不能少于8个字符");
+use crate::config::PasswordPolicy;
+use crate::response::{AppError, ErrorCode};
+
+/// 密码未满足的具体规则，每条规则对应独立错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordViolation {
+    TooShort(usize),
+    TooLong(usize),
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol,
+    Breached,
+}
+
+impl PasswordViolation {
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Self::TooShort(_) => ErrorCode::PasswordTooShort,
+            Self::TooLong(_) => ErrorCode::PasswordTooLong,
+            Self::MissingUppercase => ErrorCode::PasswordMissingUppercase,
+            Self::MissingLowercase => ErrorCode::PasswordMissingLowercase,
+            Self::MissingDigit => ErrorCode::PasswordMissingDigit,
+            Self::MissingSymbol => ErrorCode::PasswordMissingSymbol,
+            Self::Breached => ErrorCode::PasswordBreached,
+        }
     }
-    if password.len() > 256 {
-        return Err("密码长度不能超过256个字符");
+
+    pub fn message(self) -> String {
+        match self {
+            Self::TooShort(min) => format!("密码长度不能少于{min}个字符"),
+            Self::TooLong(max) => format!("密码长度不能超过{max}个字符"),
+            Self::MissingUppercase => "密码必须包含至少一个大写字母".to_string(),
+            Self::MissingLowercase => "密码必须包含至少一个小写字母".to_string(),
+            Self::MissingDigit => "密码必须包含至少一个数字".to_string(),
+            Self::MissingSymbol => "密码必须包含至少一个特殊字符".to_string(),
+            Self::Breached => "该密码已出现在公开泄露数据中，请更换".to_string(),
+        }
     }
-    let has_upper = password.chars().any(|c| c.is_ascii_uppercase());
-    let has_lower = password.chars().any(|c| c.is_ascii_lowercase());
-    let has_digit = password.chars().any(|c| c.is_ascii_digit());
-    if !has_upper || !has_lower || !has_digit {
-        return Err("密码必须包含至少一个大写字母、一个小写字母和一个数字");
+}
+
+impl From<PasswordViolation> for AppError {
+    fn from(v: PasswordViolation) -> Self {
+        AppError::bad_request(v.code(), &v.message())
+    }
+}
+
+/// 按密码策略验证长度与字符类别（泄露检查见 `services::password_breach`）
+pub fn validate_password(password: &str, policy: &PasswordPolicy) -> Result<(), PasswordViolation> {
+    if password.len() < policy.min_length {
+        return Err(PasswordViolation::TooShort(policy.min_length));
+    }
+    if password.len() > policy.max_length {
+        return Err(PasswordViolation::TooLong(policy.max_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err(PasswordViolation::MissingUppercase);
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_ascii_lowercase()) {
+        return Err(PasswordViolation::MissingLowercase);
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(PasswordViolation::MissingDigit);
+    }
+    if policy.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+        return Err(PasswordViolation::MissingSymbol);
     }
     Ok(())
 }
@@ -78,22 +131,54 @@ mod tests {
 
     #[test]
     fn valid_password_accepted() {
-        assert!(validate_password("Abc12345").is_ok());
+        assert!(validate_password("Abc12345", &PasswordPolicy::default()).is_ok());
     }
 
     #[test]
     fn short_password_rejected() {
-        assert!(validate_password("Ab1").is_err());
+        assert_eq!(
+            validate_password("Ab1", &PasswordPolicy::default()),
+            Err(PasswordViolation::TooShort(8))
+        );
     }
 
     #[test]
     fn no_uppercase_rejected() {
-        assert!(validate_password("abcdefg1").is_err());
+        assert_eq!(
+            validate_password("abcdefg1", &PasswordPolicy::default()),
+            Err(PasswordViolation::MissingUppercase)
+        );
     }
 
     #[test]
     fn no_digit_rejected() {
-        assert!(validate_password("Abcdefgh").is_err());
+        assert_eq!(
+            validate_password("Abcdefgh", &PasswordPolicy::default()),
+            Err(PasswordViolation::MissingDigit)
+        );
+    }
+
+    #[test]
+    fn custom_policy_applies_length_and_symbol_rules() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_uppercase: false,
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        };
+        assert_eq!(
+            validate_password("abc12345", &policy),
+            Err(PasswordViolation::TooShort(12))
+        );
+        assert_eq!(
+            validate_password("abcdefgh1234", &policy),
+            Err(PasswordViolation::MissingSymbol)
+        );
+        assert!(validate_password("abcdefgh123!", &policy).is_ok());
+        assert_eq!(
+            PasswordViolation::MissingSymbol.code(),
+            ErrorCode::PasswordMissingSymbol
+        );
     }
 
     #[test]
@@ -0,0 +1,41 @@
+//! 定时备份：导出 sled 数据库快照并只保留最新的若干份
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::store::backup::prune_backups;
+use crate::store::Store;
+
+pub async fn run(store: Arc<Store>, dir: &Path, retention: usize) {
+    tracing::debug!("backup: start");
+    // 快照需要遍历整个数据库并写文件，放到阻塞线程池执行
+    let snapshot_dir = dir.to_path_buf();
+    let report = match tokio::task::spawn_blocking(move || store.write_backup(&snapshot_dir)).await
+    {
+        Ok(Ok(report)) => report,
+        Ok(Err(e)) => {
+            tracing::error!(error = %e, "backup: failed to write snapshot");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "backup: snapshot task failed");
+            return;
+        }
+    };
+
+    let removed = match prune_backups(dir, retention) {
+        Ok(removed) => removed,
+        Err(e) => {
+            tracing::warn!(error = %e, "backup: failed to prune old backups");
+            0
+        }
+    };
+
+    tracing::info!(
+        path = %report.path.display(),
+        size_bytes = report.size_bytes,
+        entries = report.entries,
+        removed,
+        "backup: done"
+    );
+}
@@ -1,6 +1,8 @@
 //! B68: AMAS cache cleanup (every 10 minutes)
-//! 边扫描边删除，限制单次最多删除 10000 条
+//! 按保留期清理监控事件与审核拒绝记录，限制单次最多删除 10000 条
 
+use crate::constants::MODERATION_REJECTION_RETENTION_DAYS;
+use crate::store::keys;
 use crate::store::Store;
 
 use super::parse_monitoring_event_timestamp_ms;
@@ -8,13 +10,15 @@ use super::parse_monitoring_event_timestamp_ms;
 /// 单次清理最多删除的条目数
 const MAX_REMOVALS_PER_RUN: u32 = 10_000;
 
-pub async fn run(store: &Store) {
+pub async fn run(store: &Store, retention: chrono::Duration) {
     tracing::debug!("AMAS cache cleanup worker tick");
 
-    let cutoff_ms = (chrono::Utc::now() - chrono::Duration::days(7)).timestamp_millis();
+    let cutoff_ms = (chrono::Utc::now() - retention).timestamp_millis();
     let mut removed = 0u32;
 
-    for item in store.engine_monitoring_events.iter() {
+    // 键为逆序时间戳，过期事件集中在尾部，从截止键开始范围扫描即可
+    let start = keys::monitoring_event_cutoff_key(cutoff_ms.saturating_sub(1));
+    for item in store.engine_monitoring_events.range(start.as_bytes()..) {
         if removed >= MAX_REMOVALS_PER_RUN {
             tracing::info!(
                 removed,
@@ -32,14 +36,25 @@ pub async fn run(store: &Store) {
             continue;
         };
 
-        if event_ts < cutoff_ms {
-            if store.engine_monitoring_events.remove(k.as_ref()).is_ok() {
-                removed += 1;
-            }
+        if event_ts < cutoff_ms && store.engine_monitoring_events.remove(k.as_ref()).is_ok() {
+            removed += 1;
         }
     }
 
     if removed > 0 {
         tracing::info!(removed, "Cache cleanup: removed old monitoring events");
     }
+
+    let rejection_cutoff_ms = (chrono::Utc::now()
+        - chrono::Duration::days(MODERATION_REJECTION_RETENTION_DAYS))
+    .timestamp_millis();
+    match store
+        .purge_moderation_rejections_before(rejection_cutoff_ms, MAX_REMOVALS_PER_RUN as usize)
+    {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!(removed, "Cache cleanup: removed old moderation rejections"),
+        Err(e) => {
+            tracing::warn!(error = %e, "Cache cleanup: failed to purge moderation rejections")
+        }
+    }
 }
@@ -0,0 +1,13 @@
+use crate::store::Store;
+
+/// 释放静默时段结束的延迟通知
+pub async fn run(store: &Store) {
+    match store.release_deferred_notifications() {
+        Ok(released) => {
+            if released > 0 {
+                tracing::info!(released, "deferred_notifications: released");
+            }
+        }
+        Err(e) => tracing::error!(error = %e, "deferred_notifications failed"),
+    }
+}
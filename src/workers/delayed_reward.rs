@@ -92,26 +92,12 @@ pub fn count_overdue_words(store: &Store, now_ms: i64) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::count_overdue_words;
-    use crate::store::operations::users::User;
+    use crate::store::operations::test_fixtures::sample_user;
     use crate::store::operations::word_states::{WordLearningState, WordState};
     use crate::store::Store;
     use chrono::{Duration, Utc};
     use tempfile::tempdir;
 
-    fn sample_user(id: &str, email: &str) -> User {
-        User {
-            id: id.to_string(),
-            email: email.to_string(),
-            username: format!("user-{id}"),
-            password_hash: "hash".to_string(),
-            is_banned: false,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            failed_login_count: 0,
-            locked_until: None,
-        }
-    }
-
     fn sample_state(
         user_id: &str,
         word_id: &str,
@@ -136,7 +122,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let store = Store::open(dir.path().join("db-delayed-reward").to_str().unwrap()).unwrap();
 
-        let user = sample_user("u1", "u1@example.com");
+        let user = sample_user("u1", Utc::now());
         store.create_user(&user).unwrap();
 
         let now = Utc::now();
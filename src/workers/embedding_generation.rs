@@ -1,24 +1,72 @@
-// TODO: 实现词向量嵌入生成 worker。需要集成外部 embedding 服务（如 OpenAI embeddings），
-// 为缺少嵌入的单词生成向量表示，存储到 Word.embedding 字段，用于语义搜索。
+//! 为缺少嵌入的单词生成向量（每 5 分钟），写入 Word.embedding 供语义搜索使用
+
+use crate::services::llm_provider::{LlmError, LlmProvider};
 use crate::store::Store;
 
-pub async fn run(store: &Store) {
+/// 单批请求的单词数
+const EMBEDDING_BATCH_SIZE: usize = 20;
+/// 单次运行最多处理的批次数，剩余单词留给下次运行
+const MAX_BATCHES_PER_RUN: usize = 10;
+
+pub async fn run(store: &Store, llm: &LlmProvider) {
     tracing::debug!("Embedding generation worker tick");
+    if llm.is_circuit_open() {
+        tracing::info!("Embedding generation skipped: LLM circuit open");
+        return;
+    }
 
-    let words = match store.get_words_without_embedding(20) {
-        Ok(w) => w,
-        Err(e) => {
-            tracing::warn!(error = %e, "Failed to get words without embeddings");
-            return;
+    let mut embedded = 0usize;
+    for _ in 0..MAX_BATCHES_PER_RUN {
+        let words = match store.get_words_without_embedding(EMBEDDING_BATCH_SIZE) {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to get words without embeddings");
+                break;
+            }
+        };
+        if words.is_empty() {
+            break;
         }
-    };
 
-    if words.is_empty() {
-        return;
+        let texts: Vec<String> = words.iter().map(|w| w.text.clone()).collect();
+        let vectors = match llm.embed(&texts).await {
+            Ok(v) if v.len() == words.len() => v,
+            Ok(v) => {
+                tracing::warn!(
+                    expected = words.len(),
+                    got = v.len(),
+                    "Embedding provider returned mismatched batch"
+                );
+                break;
+            }
+            Err(LlmError::CircuitOpen) => {
+                tracing::info!(embedded, "Embedding generation stopped: LLM circuit open");
+                break;
+            }
+            Err(LlmError::BudgetExhausted) => {
+                tracing::warn!(
+                    embedded,
+                    "Embedding generation paused: LLM daily token budget exhausted"
+                );
+                break;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Embedding provider failed");
+                break;
+            }
+        };
+
+        for (mut word, vector) in words.into_iter().zip(vectors) {
+            word.embedding = Some(vector);
+            if let Err(e) = store.upsert_word(&word) {
+                tracing::warn!(error = %e, word_id = %word.id, "Failed to persist embedding");
+                continue;
+            }
+            embedded += 1;
+        }
     }
 
-    tracing::info!(
-        count = words.len(),
-        "Found words without embeddings (embedding service integration pending)"
-    );
+    if embedded > 0 {
+        tracing::info!(embedded, "Embedding generation complete");
+    }
 }
@@ -1,64 +1,195 @@
-//! 实验性功能：词源自动生成
-//! 当前使用占位符文本，需要外部 LLM API 才能生成真实词源。
-//! 在 workers/mod.rs 的 planned_jobs() 中默认禁用（enabled: false），
-//! 启用前请确保已配置并测试 LLM provider。
+//! 为缺少词源的单词预生成词源（每日），写入 etymologies 缓存，使 `/api/content/etymology/:id` 无需在请求路径上生成。
+//! 仅在配置了 LLM provider 时启用。缺失、规则兜底或提示词版本过期的条目会被（重新）生成，
+//! 人工录入的词源不会被覆盖。生成结果须通过内容审核才写入缓存，被拒绝的单词在条目中记录退避标记，
+//! 退避期内不再选中。
 
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::config::ModerationConfig;
+use crate::constants::{ETYMOLOGY_PROMPT_VERSION, ETYMOLOGY_REJECTION_FIELDS};
+use crate::services::llm_provider::{LlmError, LlmFeature, LlmProvider};
+use crate::services::moderation;
+use crate::store::keys;
+use crate::store::operations::words::Word;
 use crate::store::Store;
 
-pub async fn run(store: &Store) {
-    tracing::info!("Etymology generation worker running");
+/// 单次运行最多生成的单词数，剩余单词留给下次运行
+const MAX_WORDS_PER_RUN: usize = 50;
+/// 单次运行的时间预算，留出余量在 worker 超时前主动结束
+const RUN_BUDGET: Duration = Duration::from_secs(240);
+/// 审核拒绝后的重试间隔，按连续拒绝次数翻倍
+const REJECTION_BACKOFF_BASE_DAYS: i64 = 1;
+const REJECTION_BACKOFF_MAX_DAYS: i64 = 30;
+
+/// 缓存条目是否需要（重新）生成
+fn needs_generation(cached: Option<&serde_json::Value>, now: DateTime<Utc>) -> bool {
+    let Some(cached) = cached else {
+        return true;
+    };
+    let field = |name: &str| cached.get(name).and_then(|v| v.as_str());
+    let [_, retry_after_field] = ETYMOLOGY_REJECTION_FIELDS;
+    if field(retry_after_field)
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .is_some_and(|retry_after| retry_after > now)
+    {
+        return false;
+    }
+    match field("source") {
+        Some("llm") => {
+            cached.get("promptVersion").and_then(|v| v.as_u64())
+                != Some(u64::from(ETYMOLOGY_PROMPT_VERSION))
+        }
+        Some("rule_based_fallback") => true,
+        // 旧版占位数据带 `generated: true`，人工录入的词源不带
+        _ => {
+            field("status") == Some("pending_llm")
+                || cached.get("generated").and_then(|v| v.as_bool()) == Some(true)
+        }
+    }
+}
 
-    let mut words_to_process = Vec::new();
+/// 在词源条目中记录一次审核拒绝；条目不存在时写入待生成占位
+fn mark_rejected(store: &Store, word: &Word, now: DateTime<Utc>) -> Result<(), sled::Error> {
+    let Ok(key) = keys::etymology_key(&word.id) else {
+        return Ok(());
+    };
+    let [rejections_field, retry_after_field] = ETYMOLOGY_REJECTION_FIELDS;
+    store.etymologies.update_and_fetch(key.as_bytes(), |old| {
+        let mut entry = old
+            .and_then(|raw| serde_json::from_slice::<serde_json::Value>(raw).ok())
+            .filter(|v| v.is_object())
+            .unwrap_or_else(|| {
+                serde_json::json!({
+                    "wordId": word.id,
+                    "word": word.text,
+                    "status": "pending_llm",
+                })
+            });
+        let rejections = entry
+            .get(rejections_field)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            + 1;
+        let backoff_days = (REJECTION_BACKOFF_BASE_DAYS << (rejections - 1).min(8))
+            .min(REJECTION_BACKOFF_MAX_DAYS);
+        entry[rejections_field] = rejections.into();
+        entry[retry_after_field] = (now + chrono::Duration::days(backoff_days))
+            .to_rfc3339()
+            .into();
+        serde_json::to_vec(&entry).ok()
+    })?;
+    Ok(())
+}
 
+fn morpheme_roots(store: &Store, word_id: &str) -> Vec<String> {
+    let Ok(key) = keys::word_morpheme_key(word_id) else {
+        return Vec::new();
+    };
+    store
+        .word_morphemes
+        .get(key.as_bytes())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok())
+        .and_then(|data| {
+            data.get("morphemes").and_then(|m| m.as_array()).map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.get("text").and_then(|t| t.as_str()))
+                    .filter(|t| !t.trim().is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+pub async fn run(store: &Store, llm: &LlmProvider, moderation: &ModerationConfig) {
+    tracing::debug!("Etymology generation worker tick");
+    if llm.is_circuit_open() {
+        tracing::info!("Etymology generation skipped: LLM circuit open");
+        return;
+    }
+    let started = Instant::now();
+    let now = Utc::now();
+
+    let mut words_to_process: Vec<Word> = Vec::new();
     for item in store.words.iter() {
-        let (_, v) = match item {
-            Ok(kv) => kv,
-            Err(_) => continue,
+        let Ok((_, v)) = item else {
+            continue;
         };
-
-        let word: crate::store::operations::words::Word = match serde_json::from_slice(&v) {
-            Ok(w) => w,
-            Err(_) => continue,
+        let Ok(word) = serde_json::from_slice::<Word>(&v) else {
+            continue;
         };
-
-        let key = match crate::store::keys::etymology_key(&word.id) {
-            Ok(k) => k,
-            Err(_) => continue,
+        let Ok(key) = keys::etymology_key(&word.id) else {
+            continue;
         };
-        if store
+        let cached = store
             .etymologies
             .get(key.as_bytes())
             .ok()
             .flatten()
-            .is_none()
-        {
+            .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok());
+        if needs_generation(cached.as_ref(), now) {
             words_to_process.push(word);
         }
-
-        if words_to_process.len() >= 50 {
+        if words_to_process.len() >= MAX_WORDS_PER_RUN {
             break;
         }
     }
 
+    let mut generated = 0usize;
     for word in &words_to_process {
-        let etymology = serde_json::json!({
+        if started.elapsed() >= RUN_BUDGET {
+            tracing::info!(generated, "Etymology generation stopped at run budget");
+            break;
+        }
+        let etymology = match llm.generate_etymology(&word.text, &word.meaning).await {
+            Ok(text) => text,
+            Err(LlmError::CircuitOpen) => {
+                tracing::info!(generated, "Etymology generation stopped: LLM circuit open");
+                break;
+            }
+            Err(LlmError::BudgetExhausted) => {
+                tracing::warn!(
+                    generated,
+                    "Etymology generation paused: LLM daily token budget exhausted"
+                );
+                break;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Etymology provider failed");
+                break;
+            }
+        };
+        let texts = [etymology.clone()];
+        if !moderation::screen(store, moderation, llm, "etymology", &word.id, &texts).await {
+            if let Err(e) = mark_rejected(store, word, Utc::now()) {
+                tracing::warn!(word_id = %word.id, error = %e, "Failed to mark rejected etymology");
+            }
+            continue;
+        }
+        let entry = serde_json::json!({
             "wordId": word.id,
             "word": word.text,
-            // TODO: 接入 LLM API 生成真实词源，当前为占位文本
-            "etymology": format!("Auto-generated etymology for '{}'", word.text),
-            "roots": [],
+            "etymology": etymology,
+            "roots": morpheme_roots(store, &word.id),
             "generated": true,
-            "generatedAt": chrono::Utc::now().to_rfc3339(),
+            "source": "llm",
+            "model": llm.model_tag(LlmFeature::Etymology),
+            "promptVersion": ETYMOLOGY_PROMPT_VERSION,
+            "generatedAt": Utc::now().to_rfc3339(),
         });
 
-        let key = match crate::store::keys::etymology_key(&word.id) {
+        let key = match keys::etymology_key(&word.id) {
             Ok(k) => k,
             Err(e) => {
                 tracing::warn!(word_id = %word.id, error = %e, "Failed to build etymology key");
                 continue;
             }
         };
-        let bytes = match serde_json::to_vec(&etymology) {
+        let bytes = match serde_json::to_vec(&entry) {
             Ok(b) => b,
             Err(e) => {
                 tracing::warn!(word_id = %word.id, error = %e, "Failed to serialize etymology");
@@ -67,11 +198,83 @@ pub async fn run(store: &Store) {
         };
         if let Err(e) = store.etymologies.insert(key.as_bytes(), bytes) {
             tracing::warn!(word_id = %word.id, error = %e, "Failed to store etymology");
+            continue;
         }
+        generated += 1;
+    }
+
+    if generated > 0 {
+        tracing::info!(generated, "Etymology generation complete");
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    tracing::info!(
-        processed = words_to_process.len(),
-        "Etymology generation complete"
-    );
+    #[test]
+    fn regenerates_fallback_and_stale_entries_only() {
+        let now = Utc::now();
+        assert!(needs_generation(None, now));
+        assert!(needs_generation(
+            Some(&serde_json::json!({"source": "rule_based_fallback"})),
+            now
+        ));
+        assert!(needs_generation(
+            Some(&serde_json::json!({"status": "pending_llm"})),
+            now
+        ));
+        assert!(needs_generation(
+            Some(&serde_json::json!({"source": "llm", "promptVersion": 0})),
+            now
+        ));
+        assert!(!needs_generation(
+            Some(&serde_json::json!({
+                "source": "llm",
+                "promptVersion": ETYMOLOGY_PROMPT_VERSION,
+            })),
+            now
+        ));
+        // 人工录入
+        assert!(!needs_generation(
+            Some(&serde_json::json!({"etymology": "curated", "generated": false})),
+            now
+        ));
+    }
+
+    #[test]
+    fn rejected_words_back_off_with_growing_delay() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("db").to_str().unwrap()).unwrap();
+        let word = Word {
+            id: "w1".to_string(),
+            text: "apple".to_string(),
+            meaning: "苹果".to_string(),
+            pronunciation: None,
+            part_of_speech: None,
+            difficulty: 0.5,
+            examples: vec![],
+            tags: vec![],
+            embedding: None,
+            created_at: Utc::now(),
+        };
+        let read = || -> serde_json::Value {
+            let raw = store.etymologies.get("w1").unwrap().unwrap();
+            serde_json::from_slice(&raw).unwrap()
+        };
+
+        let now = Utc::now();
+        let after_first_backoff = now + chrono::Duration::days(REJECTION_BACKOFF_BASE_DAYS);
+        mark_rejected(&store, &word, now).unwrap();
+        let entry = read();
+        assert_eq!(entry["status"], "pending_llm");
+        assert_eq!(entry["llmRejections"], 1);
+        assert!(!needs_generation(Some(&entry), now));
+        assert!(needs_generation(Some(&entry), after_first_backoff));
+
+        mark_rejected(&store, &word, now).unwrap();
+        let entry = read();
+        assert_eq!(entry["llmRejections"], 2);
+        assert!(!needs_generation(Some(&entry), after_first_backoff));
+    }
 }
@@ -1,14 +1,20 @@
 //! B44: Forgetting alert worker
 //! Daily scan for words at high forgetting risk, generate notifications.
 //! 使用 alert_dedup tree 进行 O(1) 去重检查
+//! 每个用户每轮只生成一条提醒，并与分组窗口内未读的提醒合并计数
+//! 配置了 webhook 的用户每轮汇总推送一次，静默时段内不推送
 
 use crate::constants::MILLIS_PER_HOUR;
+use crate::store::operations::notifications::{Notification, NotificationType};
 use crate::store::Store;
 
 /// 通知去重窗口
 const DEDUP_WINDOW_HOURS: i64 = 48;
 
-pub async fn run(store: &Store) {
+/// 遗忘提醒的分组目标：同一用户的到期复习提醒合并为一条
+const FORGETTING_ALERT_GROUP_KEY: &str = "due_reviews";
+
+pub async fn run(store: &Store, group_window: chrono::Duration) {
     tracing::info!("Forgetting alert worker running");
 
     let now = chrono::Utc::now();
@@ -32,6 +38,8 @@ pub async fn run(store: &Store) {
             Ok(p) => p,
             Err(_) => continue,
         };
+        let mut user_alerts = Vec::new();
+        let mut dedup_keys = Vec::new();
 
         for item in store.word_due_index.scan_prefix(prefix.as_bytes()) {
             let (key, _) = match item {
@@ -87,47 +95,53 @@ pub async fn run(store: &Store) {
             }
 
             let overdue_hours = now_ms.saturating_sub(due_ts_ms) / MILLIS_PER_HOUR;
-            let notification = serde_json::json!({
-                "id": uuid::Uuid::new_v4().to_string(),
-                "userId": user_id,
-                "type": "forgetting_alert",
+            dedup_keys.push(dedup_key);
+            user_alerts.push(serde_json::json!({
                 "wordId": word_id,
                 "overdueHours": overdue_hours,
-                "createdAt": now.to_rfc3339(),
-                "read": false,
-            });
-
-            let notif_key = match crate::store::keys::notification_key(
-                user_id,
-                notification["id"].as_str().unwrap_or("unknown"),
-            ) {
-                Ok(k) => k,
-                Err(e) => {
-                    tracing::warn!(error = %e, "Failed to build notification key");
-                    continue;
-                }
-            };
+            }));
+        }
 
-            let notification_bytes = match serde_json::to_vec(&notification) {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    tracing::warn!(error = %e, "Failed to serialize forgetting alert notification");
-                    continue;
-                }
+        if !user_alerts.is_empty() {
+            let notification = Notification {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user_id.clone(),
+                notification_type: NotificationType::ForgettingAlert,
+                title: "复习提醒".to_string(),
+                message: String::new(),
+                read: false,
+                created_at: now,
+                group_key: Some(FORGETTING_ALERT_GROUP_KEY.to_string()),
+                count: user_alerts.len() as u32,
+                items: user_alerts.clone(),
+                updated_at: None,
+                deferred_until: None,
+                broadcast_id: None,
             };
-
-            if let Err(e) = store
-                .notifications
-                .insert(notif_key.as_bytes(), notification_bytes)
-            {
+            if let Err(e) = store.create_grouped_notification(&notification, group_window, |n| {
+                format!("{n} 个单词即将遗忘，建议尽快复习")
+            }) {
                 tracing::warn!(error = %e, "Failed to insert forgetting alert notification");
                 continue;
             }
 
-            let _ = store
-                .alert_dedup
-                .insert(dedup_key.as_bytes(), now_ms.to_string().as_bytes());
-            at_risk += 1;
+            for dedup_key in &dedup_keys {
+                let _ = store
+                    .alert_dedup
+                    .insert(dedup_key.as_bytes(), now_ms.to_string().as_bytes());
+            }
+            at_risk += user_alerts.len() as u32;
+
+            let in_quiet_hours =
+                matches!(store.get_user_quiet_hours(user_id), Ok(Some(q)) if q.contains(now));
+            if !in_quiet_hours {
+                crate::services::webhook::dispatch(
+                    store,
+                    user_id,
+                    "forgetting_alert",
+                    serde_json::json!({ "count": user_alerts.len(), "words": user_alerts }),
+                );
+            }
         }
     }
 
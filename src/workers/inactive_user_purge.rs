@@ -0,0 +1,170 @@
+//! 数据保留：超过保留期未活跃的用户按配置提醒、匿名化或删除。
+//! 默认 dry-run，只输出候选报告，不修改任何数据。
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::InactiveUserAction;
+use crate::store::operations::notifications::{Notification, NotificationType};
+use crate::store::{Store, StoreError};
+
+/// 同一账户的保留期提醒合并为一条
+const INACTIVE_NOTICE_GROUP_KEY: &str = "inactive_account";
+
+/// 报告中列出的候选用户 ID 上限，避免日志过大
+const MAX_REPORTED_IDS: usize = 50;
+
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    pub scanned: usize,
+    pub candidates: Vec<String>,
+    pub processed: usize,
+    pub failed: usize,
+}
+
+pub async fn run(store: &Store, action: InactiveUserAction, retention_days: u64, dry_run: bool) {
+    tracing::debug!("inactive_user_purge: start");
+    let report = match purge(store, action, retention_days, dry_run, Utc::now()) {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::warn!(error = %e, "inactive_user_purge: failed to scan users");
+            return;
+        }
+    };
+
+    let listed = &report.candidates[..report.candidates.len().min(MAX_REPORTED_IDS)];
+    tracing::info!(
+        action = action.as_str(),
+        dry_run,
+        retention_days,
+        scanned = report.scanned,
+        candidates = report.candidates.len(),
+        processed = report.processed,
+        failed = report.failed,
+        user_ids = ?listed,
+        "inactive_user_purge: done"
+    );
+}
+
+pub fn purge(
+    store: &Store,
+    action: InactiveUserAction,
+    retention_days: u64,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> Result<PurgeReport, StoreError> {
+    let retention = Duration::days(retention_days as i64);
+    let cutoff = now - retention;
+    let mut report = PurgeReport::default();
+
+    for user_id in store.list_user_ids()? {
+        let Some(user) = store.get_user_by_id(&user_id)? else {
+            continue;
+        };
+        report.scanned += 1;
+        if user.is_anonymized() || store.user_last_active_at(&user)? > cutoff {
+            continue;
+        }
+        report.candidates.push(user_id.clone());
+        if dry_run {
+            continue;
+        }
+
+        let result = match action {
+            InactiveUserAction::Notify => notify(store, &user_id, retention_days, retention, now),
+            InactiveUserAction::Anonymize => store.anonymize_user(&user_id),
+            InactiveUserAction::Delete => store.delete_user(&user_id),
+        };
+        match result {
+            Ok(()) => report.processed += 1,
+            Err(e) => {
+                report.failed += 1;
+                tracing::warn!(
+                    user_id,
+                    error = %e,
+                    action = action.as_str(),
+                    "inactive_user_purge: action failed"
+                );
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn notify(
+    store: &Store,
+    user_id: &str,
+    retention_days: u64,
+    group_window: Duration,
+    now: DateTime<Utc>,
+) -> Result<(), StoreError> {
+    let notification = Notification {
+        id: uuid::Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        notification_type: NotificationType::System,
+        title: "账户长期未使用".to_string(),
+        message: String::new(),
+        read: false,
+        created_at: now,
+        group_key: Some(INACTIVE_NOTICE_GROUP_KEY.to_string()),
+        count: 1,
+        items: Vec::new(),
+        updated_at: None,
+        deferred_until: None,
+        broadcast_id: None,
+    };
+    store.create_grouped_notification(&notification, group_window, |_| {
+        format!("您的账户已超过 {retention_days} 天未使用，按数据保留政策可能被清理，登录学习即可保留数据")
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::store::operations::test_fixtures::{sample_record, sample_user};
+
+    #[test]
+    fn dry_run_reports_without_changes_then_actions_apply() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("purge-db").to_str().unwrap()).unwrap();
+        let now = Utc::now();
+        let long_ago = now - Duration::days(400);
+        store.create_user(&sample_user("stale", long_ago)).unwrap();
+        store.create_user(&sample_user("gone", long_ago)).unwrap();
+        store.create_user(&sample_user("active", long_ago)).unwrap();
+        store
+            .create_record(&sample_record(
+                "r1",
+                "active",
+                "w1",
+                now - Duration::days(3),
+            ))
+            .unwrap();
+
+        let report = purge(&store, InactiveUserAction::Delete, 365, true, now).unwrap();
+        assert_eq!(report.scanned, 3);
+        let mut candidates = report.candidates.clone();
+        candidates.sort();
+        assert_eq!(candidates, vec!["gone", "stale"]);
+        assert_eq!(report.processed, 0);
+        assert!(store.get_user_by_id("gone").unwrap().is_some());
+
+        let report = purge(&store, InactiveUserAction::Anonymize, 365, false, now).unwrap();
+        assert_eq!(report.processed, 2);
+        let anonymized = store.get_user_by_id("stale").unwrap().unwrap();
+        assert!(anonymized.is_anonymized() && anonymized.is_banned);
+        assert!(store
+            .get_user_by_email("stale@example.com")
+            .unwrap()
+            .is_none());
+
+        // 已匿名化的账户不再作为候选
+        store.create_user(&sample_user("old", long_ago)).unwrap();
+        let report = purge(&store, InactiveUserAction::Delete, 365, false, now).unwrap();
+        assert_eq!(report.candidates, vec!["old"]);
+        assert!(store.get_user_by_id("old").unwrap().is_none());
+        assert!(store.get_user_by_id("active").unwrap().is_some());
+    }
+}
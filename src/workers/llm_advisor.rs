@@ -1,8 +1,150 @@
-// TODO: 实现 LLM 学习建议 worker。需要调用外部 LLM API，基于用户学习数据
-// 生成个性化学习建议和策略调整推荐，存储到通知系统供用户查看。
+//! LLM 顾问：汇总近几日监控数据与当前 AMAS 配置交给 LLM，
+//! 产出配置调整建议与异常标记并存入 `llm_advisories`，由管理员复核后决定是否应用。
+use crate::amas::engine::AMASEngine;
+use crate::services::llm_provider::{LlmError, LlmFeature, LlmProvider};
+use crate::store::operations::llm_advisories::{AnomalySeverity, FlaggedAnomaly, LlmAdvisory};
+use crate::store::operations::monitoring::MonitoringDailySummary;
 use crate::store::Store;
 
-pub async fn run(_store: &Store) {
-    tracing::debug!("llm_advisor: start");
-    tracing::debug!("llm_advisor: done (stub)");
+/// 提交给 LLM 的监控汇总天数
+const LOOKBACK_DAYS: usize = 7;
+const ANOMALY_RATE_WARN: f64 = 0.05;
+const P95_LATENCY_WARN_MS: i64 = 1000;
+
+/// 按固定阈值从每日汇总中标记异常，随上下文交给 LLM 并与其标记合并保存
+pub fn detect_anomalies(summaries: &[MonitoringDailySummary]) -> Vec<FlaggedAnomaly> {
+    let mut anomalies = Vec::new();
+    for summary in summaries.iter().filter(|s| s.total_events > 0) {
+        let rate = summary.anomaly_count as f64 / summary.total_events as f64;
+        if rate > ANOMALY_RATE_WARN {
+            anomalies.push(FlaggedAnomaly {
+                metric: "anomalyRate".to_string(),
+                severity: AnomalySeverity::Warning,
+                detail: format!(
+                    "{:.1}% of {} events flagged as anomalies",
+                    rate * 100.0,
+                    summary.total_events
+                ),
+                date: Some(summary.date),
+            });
+        }
+        let p95 = summary.latency_percentile(95.0);
+        if p95 > P95_LATENCY_WARN_MS {
+            anomalies.push(FlaggedAnomaly {
+                metric: "latencyP95".to_string(),
+                severity: AnomalySeverity::Warning,
+                detail: format!("p95 latency {p95}ms exceeds {P95_LATENCY_WARN_MS}ms"),
+                date: Some(summary.date),
+            });
+        }
+        let violations: u64 = summary.violation_counts.values().sum();
+        if violations > 0 {
+            anomalies.push(FlaggedAnomaly {
+                metric: "invariantViolations".to_string(),
+                severity: AnomalySeverity::Critical,
+                detail: format!("{violations} invariant violations"),
+                date: Some(summary.date),
+            });
+        }
+    }
+    anomalies
+}
+
+pub async fn run(store: &Store, engine: &AMASEngine, llm: Option<&LlmProvider>) {
+    let Some(llm) = llm else {
+        tracing::debug!("llm_advisor: skipped, LLM not configured");
+        return;
+    };
+    if llm.is_circuit_open() {
+        tracing::info!("llm_advisor: skipped, LLM circuit open");
+        return;
+    }
+
+    let summaries = match store.list_monitoring_daily_summaries(LOOKBACK_DAYS) {
+        Ok(summaries) => summaries,
+        Err(e) => {
+            tracing::warn!(error = %e, "llm_advisor: failed to load monitoring summaries");
+            return;
+        }
+    };
+    let detected = detect_anomalies(&summaries);
+    let config = match serde_json::to_value(engine.get_config().await) {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::warn!(error = %e, "llm_advisor: failed to serialize AMAS config");
+            return;
+        }
+    };
+    let context = serde_json::json!({
+        "monitoring": summaries,
+        "detectedAnomalies": detected,
+        "amasConfig": config,
+    });
+
+    let mut output = match llm.advise(&context.to_string()).await {
+        Ok(output) => output,
+        Err(LlmError::BudgetExhausted) => {
+            tracing::warn!("llm_advisor: paused, LLM daily token budget exhausted");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "llm_advisor: LLM call failed");
+            return;
+        }
+    };
+    // 只保留指向现有配置字段的建议，并记下生成时的取值
+    output.suggestions.retain_mut(|s| {
+        s.applied_at = None;
+        s.applied_by = None;
+        match config.pointer(&s.json_pointer()) {
+            Some(current) => {
+                s.current = current.clone();
+                true
+            }
+            None => {
+                tracing::warn!(path = %s.path, "llm_advisor: dropped suggestion for unknown config path");
+                false
+            }
+        }
+    });
+    output.anomalies.splice(0..0, detected);
+
+    let advisory = LlmAdvisory {
+        id: uuid::Uuid::new_v4().to_string(),
+        model: llm.model_tag(LlmFeature::Advisor),
+        output,
+        created_at: chrono::Utc::now(),
+    };
+    match store.save_llm_advisory(&advisory) {
+        Ok(()) => tracing::info!(
+            advisory_id = %advisory.id,
+            suggestions = advisory.output.suggestions.len(),
+            anomalies = advisory.output.anomalies.len(),
+            "llm_advisor: advisory saved"
+        ),
+        Err(e) => tracing::warn!(error = %e, "llm_advisor: failed to save advisory"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_high_anomaly_rate_and_violations() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        let mut summary = MonitoringDailySummary::new(date);
+        summary.total_events = 100;
+        summary.anomaly_count = 2;
+        summary.record_latency(10);
+        assert!(detect_anomalies(&[summary.clone()]).is_empty());
+
+        summary.anomaly_count = 10;
+        summary.violation_counts.insert("attention".to_string(), 3);
+        let metrics: Vec<String> = detect_anomalies(&[summary])
+            .into_iter()
+            .map(|a| a.metric)
+            .collect();
+        assert_eq!(metrics, ["anomalyRate", "invariantViolations"]);
+    }
 }
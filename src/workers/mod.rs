@@ -1,18 +1,23 @@
 pub mod algorithm_optimization;
+pub mod backup;
 pub mod cache_cleanup;
 pub mod confusion_pair_cache;
 pub mod daily_aggregation;
+pub mod deferred_notifications;
 pub mod delayed_reward;
 pub mod embedding_generation;
 pub mod etymology_generation;
 pub mod forgetting_alert;
 pub mod health_analysis;
+pub mod inactive_user_purge;
 pub mod llm_advisor;
 pub mod log_export;
 pub mod metrics_flush;
 pub mod monitoring_aggregate;
 pub mod password_reset_cleanup;
+pub mod scheduled_broadcasts;
 pub mod session_cleanup;
+pub mod store_compaction;
 pub mod weekly_report;
 pub mod word_clustering;
 
@@ -44,7 +49,9 @@ use tokio::sync::broadcast;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::amas::engine::AMASEngine;
-use crate::config::WorkerConfig;
+use crate::config::{ModerationConfig, WorkerConfig};
+use crate::services::llm_provider::LlmProvider;
+use crate::state::Readiness;
 use crate::store::Store;
 
 /// Timeout for individual worker invocations (5 minutes).
@@ -66,6 +73,8 @@ pub enum WorkerName {
     LlmAdvisor,
     DelayedReward,
     ForgettingAlert,
+    DeferredNotifications,
+    ScheduledBroadcasts,
     AlgorithmOptimization,
     CacheCleanup,
     DailyAggregation,
@@ -76,6 +85,9 @@ pub enum WorkerName {
     ConfusionPairCache,
     WeeklyReport,
     LogExport,
+    StoreCompaction,
+    Backup,
+    InactiveUserPurge,
 }
 
 impl WorkerName {
@@ -88,6 +100,8 @@ impl WorkerName {
             Self::LlmAdvisor => "llm_advisor",
             Self::DelayedReward => "delayed_reward",
             Self::ForgettingAlert => "forgetting_alert",
+            Self::DeferredNotifications => "deferred_notifications",
+            Self::ScheduledBroadcasts => "scheduled_broadcasts",
             Self::AlgorithmOptimization => "algorithm_optimization",
             Self::CacheCleanup => "cache_cleanup",
             Self::DailyAggregation => "daily_aggregation",
@@ -98,6 +112,9 @@ impl WorkerName {
             Self::ConfusionPairCache => "confusion_pair_cache",
             Self::WeeklyReport => "weekly_report",
             Self::LogExport => "log_export",
+            Self::StoreCompaction => "store_compaction",
+            Self::Backup => "backup",
+            Self::InactiveUserPurge => "inactive_user_purge",
         }
     }
 }
@@ -105,7 +122,7 @@ impl WorkerName {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JobSpec {
     pub name: WorkerName,
-    pub cron: &'static str,
+    pub cron: String,
     pub enabled: bool,
 }
 
@@ -114,6 +131,10 @@ pub struct WorkerManager {
     amas_engine: Arc<AMASEngine>,
     shutdown_rx: broadcast::Receiver<()>,
     config: WorkerConfig,
+    llm: Option<Arc<LlmProvider>>,
+    moderation: ModerationConfig,
+    broadcast_dedup_window_secs: u64,
+    readiness: Option<Arc<Readiness>>,
 }
 
 impl WorkerManager {
@@ -128,9 +149,37 @@ impl WorkerManager {
             amas_engine,
             shutdown_rx,
             config: config.clone(),
+            llm: None,
+            moderation: ModerationConfig::default(),
+            broadcast_dedup_window_secs: 0,
+            readiness: None,
         }
     }
 
+    /// 注入 LLM provider，依赖它的 worker（如 embedding 生成）才会启用
+    pub fn with_llm_provider(mut self, llm: Arc<LlmProvider>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// 生成内容写入缓存前使用的审核配置
+    pub fn with_moderation(mut self, moderation: ModerationConfig) -> Self {
+        self.moderation = moderation;
+        self
+    }
+
+    /// 定时广播投递时的重复内容抑制窗口，与即时广播一致
+    pub fn with_broadcast_dedup_window(mut self, secs: u64) -> Self {
+        self.broadcast_dedup_window_secs = secs;
+        self
+    }
+
+    /// 调度器启动后标记就绪，供 `/health/ready` 读取
+    pub fn with_readiness(mut self, readiness: Arc<Readiness>) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
     /// Single source of truth for all planned jobs and their cron schedules.
     pub fn planned_jobs(&self) -> Vec<JobSpec> {
         if !self.config.is_leader {
@@ -141,92 +190,113 @@ impl WorkerManager {
             // 核心 worker —— 始终启用
             JobSpec {
                 name: WorkerName::SessionCleanup,
-                cron: "0 0 * * * *",
+                cron: "0 0 * * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::PasswordResetCleanup,
-                cron: "0 30 * * * *",
+                cron: "0 30 * * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::DelayedReward,
-                cron: "0 */5 * * * *", // 降频: 每分钟 -> 每5分钟
+                cron: "0 */5 * * * *".into(), // 降频: 每分钟 -> 每5分钟
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::ForgettingAlert,
-                cron: "0 30 6 * * *",
+                cron: "0 30 6 * * *".into(),
+                enabled: true,
+            },
+            JobSpec {
+                name: WorkerName::DeferredNotifications,
+                cron: "0 */5 * * * *".into(),
+                enabled: true,
+            },
+            JobSpec {
+                name: WorkerName::ScheduledBroadcasts,
+                cron: "0 * * * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::AlgorithmOptimization,
-                cron: "0 0 0 * * *",
+                cron: "0 0 0 * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::CacheCleanup,
-                cron: "0 */10 * * * *",
+                cron: "0 */10 * * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::DailyAggregation,
-                cron: "0 0 1 * * *",
+                cron: "0 0 1 * * *".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::HealthAnalysis,
-                cron: "0 0 5 * * 1",
+                cron: "0 0 5 * * 1".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::ConfusionPairCache,
-                cron: "0 0 5 * * 0",
+                cron: "0 0 5 * * 0".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::WeeklyReport,
-                cron: "0 30 6 * * 1",
+                cron: "0 30 6 * * 1".into(),
                 enabled: true,
             },
             JobSpec {
                 name: WorkerName::LogExport,
-                cron: "0 0 * * * *",
+                cron: "0 0 * * * *".into(),
+                enabled: true,
+            },
+            JobSpec {
+                name: WorkerName::StoreCompaction,
+                cron: self.config.store_compaction_cron.clone(),
                 enabled: true,
             },
             // 条件启用 worker
+            JobSpec {
+                name: WorkerName::Backup,
+                cron: self.config.backup_cron.clone(),
+                enabled: self.config.enable_backup,
+            },
+            JobSpec {
+                name: WorkerName::InactiveUserPurge,
+                cron: self.config.inactive_user_purge_cron.clone(),
+                enabled: self.config.enable_inactive_user_purge,
+            },
             JobSpec {
                 name: WorkerName::MetricsFlush,
-                cron: "0 */5 * * * *",
+                cron: "0 */5 * * * *".into(),
                 enabled: self.config.enable_monitoring,
             },
             JobSpec {
                 name: WorkerName::MonitoringAggregate,
-                cron: "0 */15 * * * *",
-                // WIP: 待监控聚合实现完成后启用
-                enabled: false,
+                cron: "0 */15 * * * *".into(),
+                enabled: self.config.enable_monitoring,
             },
             JobSpec {
                 name: WorkerName::LlmAdvisor,
-                cron: "0 */20 * * * *",
+                cron: "0 */20 * * * *".into(),
                 enabled: self.config.enable_llm_advisor,
             },
-            // Stub workers —— 默认禁用
             JobSpec {
                 name: WorkerName::EtymologyGeneration,
-                cron: "0 30 3 * * *",
-                // WIP: 待 LLM provider 就绪后启用
-                enabled: false,
+                cron: "0 30 3 * * *".into(),
+                enabled: self.llm.is_some(),
             },
             JobSpec {
                 name: WorkerName::EmbeddingGeneration,
-                cron: "0 */5 * * * *",
-                // WIP: 待 LLM provider 就绪后启用
-                enabled: false,
+                cron: "0 */5 * * * *".into(),
+                enabled: self.llm.is_some(),
             },
             JobSpec {
                 name: WorkerName::WordClustering,
-                cron: "0 0 4 * * 0",
+                cron: "0 0 4 * * 0".into(),
                 // WIP: 待 LLM provider 就绪后启用
                 enabled: false,
             },
@@ -245,6 +315,9 @@ impl WorkerManager {
         self.register_jobs(&scheduler).await;
 
         scheduler.start().await?;
+        if let Some(readiness) = &self.readiness {
+            readiness.mark_scheduler_started();
+        }
 
         tracing::info!("Worker manager started");
         let _ = self.shutdown_rx.recv().await;
@@ -275,7 +348,7 @@ impl WorkerManager {
             match spec.name {
                 WorkerName::MetricsFlush => {
                     let registry = engine.metrics_registry().clone();
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         let registry = registry.clone();
                         async move {
@@ -285,7 +358,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::SessionCleanup => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             session_cleanup::run(&store).await;
@@ -294,7 +367,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::PasswordResetCleanup => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             password_reset_cleanup::run(&store).await;
@@ -303,7 +376,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::MonitoringAggregate => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             monitoring_aggregate::run(&store).await;
@@ -312,16 +385,19 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::LlmAdvisor => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    let llm = self.llm.clone();
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
+                        let engine = engine.clone();
+                        let llm = llm.clone();
                         async move {
-                            llm_advisor::run(&store).await;
+                            llm_advisor::run(&store, &engine, llm.as_deref()).await;
                         }
                     })
                     .await;
                 }
                 WorkerName::DelayedReward => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             delayed_reward::run(&store).await;
@@ -330,16 +406,38 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::ForgettingAlert => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    let group_window = chrono::Duration::seconds(
+                        self.config.notification_group_window_secs as i64,
+                    );
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
-                            forgetting_alert::run(&store).await;
+                            forgetting_alert::run(&store, group_window).await;
+                        }
+                    })
+                    .await;
+                }
+                WorkerName::DeferredNotifications => {
+                    add_job(scheduler, &spec.cron, name_str, move || {
+                        let store = store.clone();
+                        async move {
+                            deferred_notifications::run(&store).await;
+                        }
+                    })
+                    .await;
+                }
+                WorkerName::ScheduledBroadcasts => {
+                    let dedup_window_ms = self.broadcast_dedup_window_secs as i64 * 1000;
+                    add_job(scheduler, &spec.cron, name_str, move || {
+                        let store = store.clone();
+                        async move {
+                            scheduled_broadcasts::run(&store, dedup_window_ms).await;
                         }
                     })
                     .await;
                 }
                 WorkerName::AlgorithmOptimization => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         let engine = engine.clone();
                         async move {
@@ -349,16 +447,18 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::CacheCleanup => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    let retention =
+                        chrono::Duration::days(self.config.monitoring_retention_days as i64);
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
-                            cache_cleanup::run(&store).await;
+                            cache_cleanup::run(&store, retention).await;
                         }
                     })
                     .await;
                 }
                 WorkerName::DailyAggregation => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             daily_aggregation::run(&store).await;
@@ -367,7 +467,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::HealthAnalysis => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             health_analysis::run(&store).await;
@@ -376,25 +476,35 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::EtymologyGeneration => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    let Some(llm) = self.llm.clone() else {
+                        continue;
+                    };
+                    let moderation = self.moderation.clone();
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
+                        let llm = llm.clone();
+                        let moderation = moderation.clone();
                         async move {
-                            etymology_generation::run(&store).await;
+                            etymology_generation::run(&store, &llm, &moderation).await;
                         }
                     })
                     .await;
                 }
                 WorkerName::EmbeddingGeneration => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    let Some(llm) = self.llm.clone() else {
+                        continue;
+                    };
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
+                        let llm = llm.clone();
                         async move {
-                            embedding_generation::run(&store).await;
+                            embedding_generation::run(&store, &llm).await;
                         }
                     })
                     .await;
                 }
                 WorkerName::WordClustering => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             word_clustering::run(&store).await;
@@ -403,7 +513,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::ConfusionPairCache => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             confusion_pair_cache::run(&store).await;
@@ -412,7 +522,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::WeeklyReport => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             weekly_report::run(&store).await;
@@ -421,7 +531,7 @@ impl WorkerManager {
                     .await;
                 }
                 WorkerName::LogExport => {
-                    add_job(scheduler, spec.cron, name_str, move || {
+                    add_job(scheduler, &spec.cron, name_str, move || {
                         let store = store.clone();
                         async move {
                             log_export::run(&store).await;
@@ -429,8 +539,42 @@ impl WorkerManager {
                     })
                     .await;
                 }
+                WorkerName::StoreCompaction => {
+                    add_job(scheduler, &spec.cron, name_str, move || {
+                        let store = store.clone();
+                        async move {
+                            store_compaction::run(&store).await;
+                        }
+                    })
+                    .await;
+                }
+                WorkerName::Backup => {
+                    let dir = std::path::PathBuf::from(&self.config.backup_dir);
+                    let retention = self.config.backup_retention;
+                    add_job(scheduler, &spec.cron, name_str, move || {
+                        let store = store.clone();
+                        let dir = dir.clone();
+                        async move {
+                            backup::run(store, &dir, retention).await;
+                        }
+                    })
+                    .await;
+                }
+                WorkerName::InactiveUserPurge => {
+                    let action = self.config.inactive_user_action;
+                    let retention_days = self.config.inactive_user_retention_days;
+                    let dry_run = self.config.inactive_user_purge_dry_run;
+                    add_job(scheduler, &spec.cron, name_str, move || {
+                        let store = store.clone();
+                        async move {
+                            inactive_user_purge::run(&store, action, retention_days, dry_run)
+                                .await;
+                        }
+                    })
+                    .await;
+                }
             }
-            tracing::info!(name = name_str, cron = spec.cron, "Registered worker");
+            tracing::info!(name = name_str, cron = %spec.cron, "Registered worker");
         }
     }
 }
@@ -581,6 +725,8 @@ mod tests {
             WorkerName::LlmAdvisor,
             WorkerName::DelayedReward,
             WorkerName::ForgettingAlert,
+            WorkerName::DeferredNotifications,
+            WorkerName::ScheduledBroadcasts,
             WorkerName::AlgorithmOptimization,
             WorkerName::CacheCleanup,
             WorkerName::DailyAggregation,
@@ -591,6 +737,9 @@ mod tests {
             WorkerName::ConfusionPairCache,
             WorkerName::WeeklyReport,
             WorkerName::LogExport,
+            WorkerName::StoreCompaction,
+            WorkerName::Backup,
+            WorkerName::InactiveUserPurge,
         ];
 
         for name in &names {
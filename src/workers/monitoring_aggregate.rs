@@ -1,113 +1,240 @@
 //! Monitoring aggregation worker.
-//! 定期聚合系统指标（请求延迟、错误率、活跃用户数），写入时序数据。
+//! 从上次游标（最后处理的事件键）处增量扫描采样的监控事件，按天汇总延迟分布、策略分布与约束触发次数。
 
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::amas::monitoring::MonitoringEvent;
+use crate::store::operations::monitoring::MonitoringDailySummary;
 use crate::store::Store;
 
+use super::parse_monitoring_event_timestamp_ms;
+
+/// 单次最多处理的事件数，剩余部分下次继续
+const MAX_EVENTS_PER_RUN: usize = 20_000;
+/// 最近这段时间内的事件暂不汇总，留给并发写入的较早事件落地，避免被游标越过
+const LATE_EVENT_GRACE_MS: i64 = 60_000;
+
 pub async fn run(store: &Store) {
-    tracing::debug!("monitoring_aggregate: start");
+    let settled_before_ms = chrono::Utc::now().timestamp_millis() - LATE_EVENT_GRACE_MS;
+    aggregate(store, MAX_EVENTS_PER_RUN, settled_before_ms);
+}
 
-    let now = chrono::Utc::now();
-    let period_start = now - chrono::Duration::minutes(5);
+fn aggregate(store: &Store, max_events: usize, settled_before_ms: i64) {
+    tracing::debug!("monitoring_aggregate: start");
 
-    let events = match store.get_recent_monitoring_events(1000) {
-        Ok(e) => e,
+    let cursor = match store.get_monitoring_aggregate_cursor() {
+        Ok(c) => c,
         Err(e) => {
-            tracing::warn!(error = %e, "monitoring_aggregate: failed to read events");
+            tracing::warn!(error = %e, "monitoring_aggregate: failed to read cursor");
             return;
         }
     };
 
-    let mut latencies: Vec<i64> = Vec::new();
-    let mut error_count = 0u64;
-    let mut total_count = 0u64;
-    let mut users = std::collections::HashSet::new();
-
-    for event in &events {
-        let ts = event
-            .get("timestamp")
-            .and_then(|v| v.as_str())
-            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
-        let Some(ts) = ts else { continue };
-        if ts.with_timezone(&chrono::Utc) < period_start {
-            continue;
-        }
-
-        total_count += 1;
+    // 键为逆序时间戳：游标之前的都是尚未处理的事件，反向遍历即从旧到新（同毫秒按 id 逆序）
+    let events = match &cursor {
+        Some(cursor) => store.engine_monitoring_events.range(..cursor.as_bytes()),
+        None => store.engine_monitoring_events.iter(),
+    };
+    let mut summaries: BTreeMap<NaiveDate, MonitoringDailySummary> = BTreeMap::new();
+    let mut last_key = None;
+    let mut processed = 0usize;
 
-        if let Some(lat) = event.get("latencyMs").and_then(|v| v.as_i64()) {
-            latencies.push(lat);
+    for item in events.rev().take(max_events) {
+        let (k, raw) = match item {
+            Ok(kv) => kv,
+            Err(e) => {
+                tracing::warn!(error = %e, "monitoring_aggregate: failed to scan events");
+                break;
+            }
+        };
+        if parse_monitoring_event_timestamp_ms(&k).is_some_and(|ts| ts >= settled_before_ms) {
+            break;
         }
+        last_key = Some(k);
 
-        if event.get("isAnomaly").and_then(|v| v.as_bool()) == Some(true) {
-            error_count += 1;
-        }
+        let Ok(event) = serde_json::from_slice::<MonitoringEvent>(&raw) else {
+            continue;
+        };
+        let date = event.timestamp.date_naive();
+        let summary = match summaries.entry(date) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match store.get_monitoring_daily_summary(date) {
+                Ok(existing) => {
+                    entry.insert(existing.unwrap_or_else(|| MonitoringDailySummary::new(date)))
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, %date, "monitoring_aggregate: failed to load summary");
+                    return;
+                }
+            },
+        };
+        absorb(summary, &event);
+        processed += 1;
+    }
 
-        if let Some(uid) = event.get("userId").and_then(|v| v.as_str()) {
-            users.insert(uid.to_string());
-        }
+    let Some(last_key) = last_key else {
+        return;
+    };
+
+    let now = chrono::Utc::now();
+    let summaries: Vec<MonitoringDailySummary> = summaries
+        .into_values()
+        .map(|mut s| {
+            s.updated_at = now;
+            s
+        })
+        .collect();
+    let cursor = String::from_utf8_lossy(&last_key);
+    if let Err(e) = store.save_monitoring_daily_summaries(&summaries, &cursor) {
+        tracing::warn!(error = %e, "monitoring_aggregate: failed to store summaries");
+        return;
     }
 
-    latencies.sort_unstable();
+    tracing::debug!(
+        processed,
+        days = summaries.len(),
+        "monitoring_aggregate: done"
+    );
+}
 
-    let (p50, p95, p99) = if latencies.is_empty() {
-        (0.0, 0.0, 0.0)
+fn absorb(summary: &mut MonitoringDailySummary, event: &MonitoringEvent) {
+    summary.total_events += 1;
+    if event.is_anomaly {
+        summary.anomaly_count += 1;
+    }
+    summary.record_latency(event.latency_ms);
+
+    let difficulty = event
+        .strategy
+        .get("difficulty")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.5);
+    let band = if difficulty < 0.4 {
+        "low"
+    } else if difficulty < 0.7 {
+        "medium"
     } else {
-        let p = |pct: f64| -> f64 {
-            let idx = ((pct / 100.0) * (latencies.len() as f64 - 1.0)).round() as usize;
-            latencies[idx.min(latencies.len() - 1)] as f64
-        };
-        (p(50.0), p(95.0), p(99.0))
+        "high"
     };
+    *summary
+        .difficulty_distribution
+        .entry(band.to_string())
+        .or_default() += 1;
+    if event.strategy.get("reviewMode").and_then(|v| v.as_bool()) == Some(true) {
+        summary.review_mode_count += 1;
+    }
 
-    let error_rate = if total_count > 0 {
-        error_count as f64 / total_count as f64
-    } else {
-        0.0
-    };
+    if !event.selection_constraints_met {
+        summary.constraint_trigger_count += 1;
+    }
+    for violation in &event.invariant_violations {
+        *summary
+            .violation_counts
+            .entry(violation.field.clone())
+            .or_default() += 1;
+    }
+}
 
-    let active_users = match store.count_active_users_since(period_start) {
-        Ok(n) => n,
-        Err(_) => users.len(),
-    };
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn event(id: &str, ts: chrono::DateTime<Utc>, latency_ms: i64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "userId": "u1",
+            "sessionId": "s1",
+            "eventType": "process_event",
+            "timestamp": ts.to_rfc3339(),
+            "latencyMs": latency_ms,
+            "isAnomaly": false,
+            "invariantViolations": [],
+            "userState": {},
+            "strategy": { "difficulty": 0.8, "reviewMode": false },
+            "reward": {},
+            "coldStartPhase": null,
+            "selectionConstraintsMet": false,
+            "rewardValue": 0.0,
+        })
+    }
 
-    let period_id = now.format("%Y%m%d%H%M").to_string();
-    let aggregate = serde_json::json!({
-        "periodStart": period_start.to_rfc3339(),
-        "periodEnd": now.to_rfc3339(),
-        "totalEvents": total_count,
-        "errorCount": error_count,
-        "errorRate": error_rate,
-        "latencyP50": p50,
-        "latencyP95": p95,
-        "latencyP99": p99,
-        "activeUsers": active_users,
-    });
-
-    let ts_key = match crate::store::keys::monitoring_ts_key(now.timestamp_millis(), &period_id) {
-        Ok(k) => k,
-        Err(e) => {
-            tracing::warn!(error = %e, "monitoring_aggregate: failed to build key");
-            return;
+    #[tokio::test]
+    async fn aggregation_resumes_from_cursor() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("agg-db").to_str().unwrap()).unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        store
+            .insert_monitoring_event(&event("e1", base, 8))
+            .unwrap();
+        store
+            .insert_monitoring_event(&event("e2", base + Duration::seconds(1), 40))
+            .unwrap();
+
+        run(&store).await;
+        run(&store).await;
+        let summary = store
+            .get_monitoring_daily_summary(base.date_naive())
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.total_events, 2);
+        assert_eq!(summary.constraint_trigger_count, 2);
+        assert_eq!(summary.difficulty_distribution["high"], 2);
+        assert_eq!(summary.latency_percentile(50.0), 10);
+        assert_eq!(summary.latency_percentile(99.0), 50);
+
+        store
+            .insert_monitoring_event(&event("e3", base + Duration::seconds(2), 3000))
+            .unwrap();
+        run(&store).await;
+        let summary = store
+            .get_monitoring_daily_summary(base.date_naive())
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.total_events, 3);
+        assert_eq!(summary.latency_histogram.last(), Some(&1));
+    }
+
+    #[test]
+    fn cursor_resumes_within_same_millisecond_and_waits_for_late_events() {
+        let dir = tempdir().unwrap();
+        let store = Store::open(dir.path().join("agg-db").to_str().unwrap()).unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        for id in ["e1", "e2", "e3"] {
+            store.insert_monitoring_event(&event(id, base, 8)).unwrap();
         }
-    };
+        let total = || {
+            store
+                .get_monitoring_daily_summary(base.date_naive())
+                .unwrap()
+                .map_or(0, |s| s.total_events)
+        };
 
-    if let Err(e) = store.monitoring_timeseries.insert(
-        ts_key.as_bytes(),
-        match serde_json::to_vec(&aggregate) {
-            Ok(b) => b,
-            Err(e) => {
-                tracing::warn!(error = %e, "monitoring_aggregate: failed to serialize");
-                return;
-            }
-        },
-    ) {
-        tracing::warn!(error = %e, "monitoring_aggregate: failed to store");
+        let settled = (base + Duration::seconds(10)).timestamp_millis();
+        for _ in 0..3 {
+            aggregate(&store, 2, settled);
+        }
+        assert_eq!(total(), 3);
+
+        // 尚在宽限期内的事件不推进游标，稍早的事件随后写入也不会漏计
+        store
+            .insert_monitoring_event(&event("e5", base + Duration::seconds(20), 8))
+            .unwrap();
+        aggregate(&store, 10, settled);
+        store
+            .insert_monitoring_event(&event("e4", base + Duration::seconds(15), 8))
+            .unwrap();
+        assert_eq!(total(), 3);
+        aggregate(
+            &store,
+            10,
+            (base + Duration::seconds(30)).timestamp_millis(),
+        );
+        assert_eq!(total(), 5);
     }
-
-    tracing::debug!(
-        total_count,
-        error_count,
-        active_users,
-        "monitoring_aggregate: done"
-    );
 }
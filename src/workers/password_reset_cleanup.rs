@@ -1,9 +1,12 @@
 use crate::store::Store;
 
-/// 清理过期的密码重置 token
+/// 清理过期的密码重置与邮箱变更 token
 pub async fn run(store: &Store) {
     tracing::debug!("password_reset_cleanup: start");
-    match cleanup_expired_tokens(store) {
+    let result = cleanup_expired_tokens(&store.password_reset_tokens).and_then(|reset| {
+        cleanup_expired_tokens(&store.email_change_tokens).map(|email| reset + email)
+    });
+    match result {
         Ok(count) => {
             if count > 0 {
                 tracing::info!(cleaned = count, "password_reset_cleanup: done");
@@ -13,11 +16,11 @@ pub async fn run(store: &Store) {
     }
 }
 
-fn cleanup_expired_tokens(store: &Store) -> Result<u32, crate::store::StoreError> {
+fn cleanup_expired_tokens(tree: &sled::Tree) -> Result<u32, crate::store::StoreError> {
     let now = chrono::Utc::now();
     let mut expired_keys = Vec::new();
 
-    for item in store.password_reset_tokens.iter() {
+    for item in tree.iter() {
         let (k, v) = item.map_err(crate::store::StoreError::from)?;
         if let Ok(entry) = serde_json::from_slice::<PasswordResetEntry>(&v) {
             if entry.expires_at <= now {
@@ -28,12 +31,13 @@ fn cleanup_expired_tokens(store: &Store) -> Result<u32, crate::store::StoreError
 
     let count = expired_keys.len() as u32;
     for key in expired_keys {
-        let _ = store.password_reset_tokens.remove(key);
+        let _ = tree.remove(key);
     }
 
     Ok(count)
 }
 
+/// 两类 token 记录都带 `expiresAt`，这里只解析清理所需的字段
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PasswordResetEntry {
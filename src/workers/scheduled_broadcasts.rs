@@ -0,0 +1,55 @@
+use chrono::Utc;
+
+use crate::store::operations::broadcasts::{
+    broadcast_content_hash, BroadcastStatus, ScheduledBroadcast,
+};
+use crate::store::{Store, StoreError};
+
+/// 投递到期的定时广播，接收人群按投递时解析
+pub async fn run(store: &Store, dedup_window_ms: i64) {
+    let due = match store.due_scheduled_broadcasts(Utc::now()) {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!(error = %e, "scheduled_broadcasts: failed to load due broadcasts");
+            return;
+        }
+    };
+    for broadcast in due {
+        if let Err(e) = dispatch(store, &broadcast, dedup_window_ms) {
+            tracing::error!(broadcast_id = %broadcast.id, error = %e, "scheduled_broadcasts: dispatch failed");
+        }
+    }
+}
+
+fn dispatch(
+    store: &Store,
+    broadcast: &ScheduledBroadcast,
+    dedup_window_ms: i64,
+) -> Result<(), StoreError> {
+    // 先认领为已发送再投递：已被取消或其他运行认领时跳过，中途失败时宁可漏发也不重复发送
+    let Some(mut broadcast) =
+        store.transition_scheduled_broadcast(broadcast, BroadcastStatus::Sent)?
+    else {
+        return Ok(());
+    };
+
+    let recipients = store.resolve_broadcast_recipients(&broadcast.target)?;
+    let outcome = store.send_broadcast(
+        &broadcast.id,
+        &broadcast.title,
+        &broadcast.message,
+        &broadcast_content_hash(&broadcast.title, &broadcast.message),
+        &recipients,
+        dedup_window_ms,
+    )?;
+    broadcast.sent = outcome.sent;
+    broadcast.skipped_duplicates = outcome.skipped_duplicates;
+    store.save_scheduled_broadcast(&broadcast)?;
+    tracing::info!(
+        broadcast_id = %broadcast.id,
+        sent = outcome.sent,
+        skipped_duplicates = outcome.skipped_duplicates,
+        "scheduled_broadcasts: broadcast sent"
+    );
+    Ok(())
+}
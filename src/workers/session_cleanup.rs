@@ -6,4 +6,12 @@ pub async fn run(store: &Store) {
         Ok(count) => tracing::info!(cleaned = count, "session_cleanup: done"),
         Err(e) => tracing::error!(error=%e, "session_cleanup failed"),
     }
+    match store.cleanup_expired_refresh_tokens() {
+        Ok(count) => tracing::info!(cleaned = count, "session_cleanup: refresh tokens done"),
+        Err(e) => tracing::error!(error=%e, "session_cleanup: refresh token cleanup failed"),
+    }
+    match store.cleanup_expired_idempotency_keys() {
+        Ok(count) => tracing::info!(cleaned = count, "session_cleanup: idempotency keys done"),
+        Err(e) => tracing::error!(error=%e, "session_cleanup: idempotency key cleanup failed"),
+    }
 }
@@ -1,17 +1,30 @@
 //! B75: Weekly report (Monday 6:30)
 
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::store::operations::records::LearningRecord;
+use crate::store::operations::weekly_reports::{
+    iso_week_label, iso_week_start, ConfusionSummary, DailyAccuracy, WeeklyReport,
+};
 use crate::store::Store;
 
 /// 每批获取用户数
 const USER_BATCH_SIZE: usize = 500;
 /// 每个用户读取的最大记录数
 const MAX_RECORDS_PER_USER: usize = 10_000;
+/// 周报中保留的易混淆词对数量
+const TOP_CONFUSIONS: usize = 5;
 
 pub async fn run(store: &Store) {
     tracing::info!("Weekly report worker running");
 
     let now = chrono::Utc::now();
     let week_ago = now - chrono::Duration::days(7);
+    // 周一运行时汇总上一个完整的 ISO 周
+    let report_week_start = iso_week_start(week_ago.date_naive());
+    let mut user_reports = 0u32;
 
     let mut weekly_active = 0u32;
     let mut total_records_week = 0u64;
@@ -52,6 +65,17 @@ pub async fn run(store: &Store) {
             if has_weekly {
                 weekly_active += 1;
             }
+
+            if let Some(report) =
+                build_user_report(store, &user.id, report_week_start, &records, now)
+            {
+                match store.upsert_weekly_report(&report) {
+                    Ok(()) => user_reports += 1,
+                    Err(e) => {
+                        tracing::warn!(user_id = %user.id, error = %e, "Failed to store user weekly report")
+                    }
+                }
+            }
         }
 
         offset += batch_len;
@@ -79,6 +103,120 @@ pub async fn run(store: &Store) {
     tracing::info!(
         active = weekly_active,
         records = total_records_week,
+        user_reports,
         "Weekly report generated"
     );
 }
+
+/// 根据用户记录（按时间倒序）生成指定周的周报；该周无记录时返回 None
+pub fn build_user_report(
+    store: &Store,
+    user_id: &str,
+    week_start: NaiveDate,
+    records: &[LearningRecord],
+    now: DateTime<Utc>,
+) -> Option<WeeklyReport> {
+    let week_end = week_start + Duration::days(6);
+    let in_week: Vec<&LearningRecord> = records
+        .iter()
+        .filter(|r| {
+            let d = r.created_at.date_naive();
+            d >= week_start && d <= week_end
+        })
+        .collect();
+    if in_week.is_empty() {
+        return None;
+    }
+
+    let mut daily: Vec<DailyAccuracy> = (0..7)
+        .map(|i| DailyAccuracy {
+            date: week_start + Duration::days(i),
+            total: 0,
+            correct: 0,
+            accuracy: 0.0,
+        })
+        .collect();
+    let mut words = BTreeSet::new();
+    let mut correct = 0u64;
+    let mut time_studied_ms = 0i64;
+    let mut pair_counts: HashMap<(String, String), u32> = HashMap::new();
+    let mut prev_incorrect: Option<&str> = None;
+
+    for r in &in_week {
+        let day = &mut daily[(r.created_at.date_naive() - week_start).num_days() as usize];
+        day.total += 1;
+        if r.is_correct {
+            day.correct += 1;
+            correct += 1;
+        }
+        words.insert(r.word_id.as_str());
+        time_studied_ms += r.response_time_ms.max(0);
+
+        // 与 confusion_pair_cache 一致：连续答错的两个不同单词视为一次混淆
+        if r.is_correct {
+            prev_incorrect = None;
+            continue;
+        }
+        if let Some(prev) = prev_incorrect.filter(|p| *p != r.word_id) {
+            let pair = if prev < r.word_id.as_str() {
+                (prev.to_string(), r.word_id.clone())
+            } else {
+                (r.word_id.clone(), prev.to_string())
+            };
+            *pair_counts.entry(pair).or_default() += 1;
+        }
+        prev_incorrect = Some(&r.word_id);
+    }
+    for day in &mut daily {
+        if day.total > 0 {
+            day.accuracy = day.correct as f64 / day.total as f64;
+        }
+    }
+
+    let mut pairs: Vec<((String, String), u32)> = pair_counts.into_iter().collect();
+    pairs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let word_text = |id: &str| store.get_word(id).ok().flatten().map(|w| w.text);
+    let top_confusions = pairs
+        .into_iter()
+        .take(TOP_CONFUSIONS)
+        .map(|((a, b), count)| ConfusionSummary {
+            word: word_text(&a),
+            confused_with: word_text(&b),
+            word_id: a,
+            confused_with_id: b,
+            count,
+        })
+        .collect();
+
+    // 连续天数以周日为终点，周日未学习时允许从周六起算
+    let dates: BTreeSet<NaiveDate> = records
+        .iter()
+        .map(|r| r.created_at.date_naive())
+        .filter(|d| *d <= week_end)
+        .collect();
+    let mut cursor = week_end;
+    if !dates.contains(&cursor) {
+        cursor -= Duration::days(1);
+    }
+    let mut streak_days = 0u32;
+    while dates.contains(&cursor) {
+        streak_days += 1;
+        cursor -= Duration::days(1);
+    }
+
+    let total_answers = in_week.len() as u64;
+    Some(WeeklyReport {
+        user_id: user_id.to_string(),
+        week: iso_week_label(week_start),
+        week_start,
+        week_end,
+        words_learned: words.len() as u64,
+        total_answers,
+        accuracy: correct as f64 / total_answers as f64,
+        accuracy_trend: daily,
+        streak_days,
+        top_confusions,
+        time_studied_ms,
+        generated_at: now,
+    })
+}
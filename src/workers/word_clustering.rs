@@ -1,16 +1,33 @@
 //! B73: Word clustering (weekly Sunday 4:00)
+//! 基于 embedding 的 k-means 聚类，结果写入 word_clusters tree；无 embedding 的单词跳过
 
+use std::collections::BTreeMap;
+
+use crate::store::operations::word_clusters::WordCluster;
 use crate::store::Store;
 
 const WORD_PAGE_SIZE: usize = 5000;
 const DIFFICULTY_EASY_THRESHOLD: f64 = 0.33;
 const DIFFICULTY_MEDIUM_THRESHOLD: f64 = 0.66;
+const MAX_CLUSTERS: usize = 50;
+const KMEANS_MAX_ITERATIONS: usize = 25;
 
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct WordMinimal {
+    id: String,
+    text: String,
     difficulty: f64,
     tags: Vec<String>,
+    #[serde(default)]
+    embedding: Option<Vec<f64>>,
+}
+
+struct EmbeddedWord {
+    id: String,
+    text: String,
+    tags: Vec<String>,
+    embedding: Vec<f64>,
 }
 
 pub async fn run(store: &Store) {
@@ -21,6 +38,8 @@ pub async fn run(store: &Store) {
     let mut hard = 0u32;
     let mut total_count = 0usize;
     let mut tag_counts: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
+    let mut embedded: Vec<EmbeddedWord> = Vec::new();
+    let mut skipped_without_embedding = 0usize;
 
     let mut scanned = 0usize;
     for item in store.words.iter() {
@@ -51,6 +70,24 @@ pub async fn run(store: &Store) {
             *tag_counts.entry(tag.clone()).or_insert(0) += 1;
         }
 
+        // 维度以首个 embedding 为准，维度不一致的视同缺失
+        match word.embedding {
+            Some(embedding)
+                if !embedding.is_empty()
+                    && embedded
+                        .first()
+                        .map_or(true, |e| e.embedding.len() == embedding.len()) =>
+            {
+                embedded.push(EmbeddedWord {
+                    id: word.id,
+                    text: word.text,
+                    tags: word.tags,
+                    embedding,
+                });
+            }
+            _ => skipped_without_embedding += 1,
+        }
+
         scanned += 1;
         if scanned % WORD_PAGE_SIZE == 0 {
             tokio::task::yield_now().await;
@@ -73,11 +110,174 @@ pub async fn run(store: &Store) {
         tracing::warn!(error = %e, "Failed to store clustering report");
     }
 
+    // k-means 为纯 CPU 计算，放到阻塞线程池避免占用 worker 所在的异步线程
+    let clusters = match tokio::task::spawn_blocking(move || build_clusters(&embedded)).await {
+        Ok(clusters) => clusters,
+        Err(e) => {
+            tracing::warn!(error = %e, "Word clustering task failed");
+            return;
+        }
+    };
+    if let Err(e) = store.replace_word_clusters(&clusters) {
+        tracing::warn!(error = %e, "Failed to store word clusters");
+    }
+
     tracing::info!(
         total = total_count,
         easy,
         medium,
         hard,
+        clusters = clusters.len(),
+        skipped_without_embedding,
         "Word clustering complete"
     );
 }
+
+fn build_clusters(words: &[EmbeddedWord]) -> Vec<WordCluster> {
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let points: Vec<&[f64]> = words.iter().map(|w| w.embedding.as_slice()).collect();
+    // 经验值 k ≈ sqrt(n/2)
+    let k = ((words.len() as f64 / 2.0).sqrt().ceil() as usize).clamp(1, MAX_CLUSTERS);
+    let (assignments, centroids) = kmeans(&points, k);
+
+    let generated_at = chrono::Utc::now();
+    let mut clusters = Vec::new();
+    for (idx, centroid) in centroids.into_iter().enumerate() {
+        let members: Vec<&EmbeddedWord> = words
+            .iter()
+            .zip(&assignments)
+            .filter(|(_, a)| **a == idx)
+            .map(|(w, _)| w)
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        clusters.push(WordCluster {
+            id: format!("cluster-{:03}", clusters.len()),
+            theme: cluster_theme(&members, &centroid),
+            word_ids: members.iter().map(|w| w.id.clone()).collect(),
+            centroid,
+            generated_at,
+        });
+    }
+    clusters
+}
+
+/// 主题取成员中最常见的标签，无标签时取最接近质心的单词
+fn cluster_theme(members: &[&EmbeddedWord], centroid: &[f64]) -> String {
+    let mut tag_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in members.iter().flat_map(|w| &w.tags) {
+        *tag_counts.entry(tag.as_str()).or_default() += 1;
+    }
+    if let Some((tag, _)) = tag_counts.into_iter().max_by_key(|(_, count)| *count) {
+        return tag.to_string();
+    }
+    members
+        .iter()
+        .min_by(|a, b| {
+            squared_distance(&a.embedding, centroid)
+                .total_cmp(&squared_distance(&b.embedding, centroid))
+        })
+        .map(|w| w.text.clone())
+        .unwrap_or_default()
+}
+
+/// 确定性 k-means：最远点初始化，迭代至分配不再变化
+fn kmeans(points: &[&[f64]], k: usize) -> (Vec<usize>, Vec<Vec<f64>>) {
+    let mut centroids: Vec<Vec<f64>> = vec![points[0].to_vec()];
+    while centroids.len() < k {
+        let (farthest, dist) = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, nearest_centroid(p, &centroids).1))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        if dist <= 0.0 {
+            break;
+        }
+        centroids.push(points[farthest].to_vec());
+    }
+
+    let dim = points[0].len();
+    let mut assignments = vec![usize::MAX; points.len()];
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let nearest = nearest_centroid(p, &centroids).0;
+            if assignments[i] != nearest {
+                assignments[i] = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0; dim]; centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for (p, &a) in points.iter().zip(&assignments) {
+            counts[a] += 1;
+            for (s, v) in sums[a].iter_mut().zip(p.iter()) {
+                *s += v;
+            }
+        }
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            // 空簇保留原质心
+            if count > 0 {
+                *centroid = sum.into_iter().map(|s| s / count as f64).collect();
+            }
+        }
+    }
+    (assignments, centroids)
+}
+
+fn nearest_centroid(point: &[f64], centroids: &[Vec<f64>]) -> (usize, f64) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, squared_distance(point, c)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0))
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(id: &str, embedding: Vec<f64>, tags: &[&str]) -> EmbeddedWord {
+        EmbeddedWord {
+            id: id.to_string(),
+            text: id.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            embedding,
+        }
+    }
+
+    #[test]
+    fn separates_distinct_groups() {
+        let words = vec![
+            word("a1", vec![0.0, 0.1], &["animal"]),
+            word("a2", vec![0.1, 0.0], &["animal"]),
+            word("a3", vec![0.05, 0.05], &[]),
+            word("f1", vec![5.0, 5.1], &["food"]),
+            word("f2", vec![5.1, 5.0], &["food"]),
+            word("f3", vec![4.9, 5.0], &[]),
+        ];
+
+        let clusters = build_clusters(&words);
+        assert_eq!(clusters.len(), 2);
+        for cluster in &clusters {
+            assert_eq!(cluster.word_ids.len(), 3);
+            let prefix = &cluster.word_ids[0][..1];
+            assert!(cluster.word_ids.iter().all(|id| id.starts_with(prefix)));
+            let expected_theme = if prefix == "a" { "animal" } else { "food" };
+            assert_eq!(cluster.theme, expected_theme);
+        }
+    }
+}
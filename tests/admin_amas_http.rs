@@ -4,8 +4,9 @@ use axum::http::{Method, StatusCode};
 use chrono::Utc;
 
 use common::app::spawn_test_server;
-use common::auth::{auth_header, login_and_get_token};
-use common::http::{request, response_json};
+use common::auth::{auth_header, current_user_id, login_and_get_token, setup_admin_and_get_token};
+use common::http::{request, response_json, response_text};
+use learning_backend::services::totp;
 
 async fn create_word(app: &axum::Router, token: &str, text: &str) -> String {
     let response = request(
@@ -245,6 +246,7 @@ async fn it_admin_auth_and_management_routes() {
         "/api/admin/stats",
         "/api/admin/analytics/engagement",
         "/api/admin/analytics/learning",
+        "/api/admin/analytics/monitoring",
         "/api/admin/monitoring/health",
         "/api/admin/monitoring/database",
     ] {
@@ -315,7 +317,7 @@ async fn it_admin_auth_and_management_routes() {
     let (notifications_status, _, notifications_body) = response_json(notifications).await;
     assert_eq!(notifications_status, StatusCode::OK);
     assert!(
-        !notifications_body["data"]
+        !notifications_body["data"]["data"]
             .as_array()
             .unwrap_or(&Vec::new())
             .is_empty()
@@ -563,3 +565,1402 @@ async fn it_amas_user_and_admin_endpoints() {
     let (config_as_user_status, _, _) = response_json(config_as_user).await;
     assert_eq!(config_as_user_status, StatusCode::UNAUTHORIZED);
 }
+
+#[tokio::test]
+async fn it_admin_amas_export_import_roundtrip() {
+    let source = spawn_test_server().await;
+    let source_token = login_and_get_token(&source.app).await;
+    let source_admin = setup_admin_and_get_token(&source.app).await;
+    let source_user = current_user_id(&source.app, &source_token).await;
+
+    for idx in 0..3 {
+        let response = request(
+            &source.app,
+            Method::POST,
+            "/api/amas/process-event",
+            Some(serde_json::json!({
+                "wordId": format!("word-{idx}"),
+                "isCorrect": idx % 2 == 0,
+                "responseTime": 1500,
+                "sessionId": "export-session"
+            })),
+            &[("authorization", auth_header(&source_token))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = request(
+        &source.app,
+        Method::GET,
+        &format!("/api/admin/users/{source_user}/amas-export"),
+        None,
+        &[("authorization", auth_header(&source_admin))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let exported = body["data"].clone();
+    assert_eq!(exported["masteryStates"].as_object().unwrap().len(), 3);
+
+    let target = spawn_test_server().await;
+    let target_token = login_and_get_token(&target.app).await;
+    let target_admin = setup_admin_and_get_token(&target.app).await;
+    let target_user = current_user_id(&target.app, &target_token).await;
+
+    let response = request(
+        &target.app,
+        Method::POST,
+        &format!("/api/admin/users/{target_user}/amas-import"),
+        Some(exported.clone()),
+        &[("authorization", auth_header(&target_admin))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK, "import failed: {body}");
+
+    let response = request(
+        &target.app,
+        Method::GET,
+        &format!("/api/admin/users/{target_user}/amas-export"),
+        None,
+        &[("authorization", auth_header(&target_admin))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let reexported = &body["data"];
+    for field in ["userState", "algoStates", "masteryStates", "elo"] {
+        assert_eq!(reexported[field], exported[field], "{field} mismatch");
+    }
+}
+
+#[tokio::test]
+async fn it_admin_broadcast_suppresses_duplicates_within_window() {
+    let app = spawn_test_server().await;
+    let user_token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+
+    let payload = serde_json::json!({
+        "title": "Duplicate Notice",
+        "message": "Sent twice by accident"
+    });
+    let mut sent = Vec::new();
+    for _ in 0..2 {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/admin/broadcast",
+            Some(payload.clone()),
+            &[("authorization", auth_header(&admin_token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        sent.push(body["data"].clone());
+    }
+    assert_eq!(sent[0]["sent"], 1);
+    assert_eq!(sent[1]["sent"], 0);
+    assert_eq!(sent[1]["skippedDuplicates"], 1);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/notifications?limit=50",
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let broadcasts = body["data"]["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|n| n["title"] == "Duplicate Notice")
+        .count();
+    assert_eq!(broadcasts, 1);
+}
+
+#[tokio::test]
+async fn it_admin_amas_state_repairs_record_corrupt_algo_state() {
+    let app = spawn_test_server().await;
+    let user_token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &user_token).await;
+
+    app.state
+        .store()
+        .set_engine_algo_state(&user_id, "swd", &serde_json::json!("not-a-swd-state"))
+        .expect("write corrupt state");
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/amas/process-event",
+        Some(serde_json::json!({
+            "wordId": "word-1",
+            "isCorrect": true,
+            "responseTime": 1000,
+            "sessionId": "repair-session"
+        })),
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    let (status, _, _) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let repaired = app
+        .state
+        .store()
+        .get_engine_algo_state(&user_id, "swd")
+        .expect("read swd state")
+        .expect("swd state persisted");
+    assert!(repaired.is_object());
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/admin/amas/state-repairs?userId={user_id}"),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let repairs = body["data"].as_array().unwrap();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(repairs[0]["userId"], user_id);
+    assert_eq!(repairs[0]["algoId"], "swd");
+    assert_eq!(repairs[0]["rawState"], "not-a-swd-state");
+    assert!(!repairs[0]["error"].as_str().unwrap().is_empty());
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/amas/state-repairs?userId=someone-else",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    assert!(body["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn it_admin_totp_enrollment_enforced_at_login() {
+    // 登录与两步验证接口共用认证限流，本用例请求较多
+    let app =
+        common::app::spawn_test_server_with_config(|c| c.auth_rate_limit.max_requests = 100).await;
+    let email = "totp-admin@test.com";
+    let password = "AdminPassw0rd!";
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/auth/setup",
+        Some(serde_json::json!({ "email": email, "password": password })),
+        &[],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/auth/totp/enroll",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = body["data"]["otpauthUri"].as_str().unwrap();
+    assert!(uri.starts_with("otpauth://totp/WordForge:totp-admin@test.com?secret="));
+
+    let admin = app
+        .state
+        .store()
+        .get_admin_by_email(email)
+        .unwrap()
+        .unwrap();
+    let sealed = admin.totp_secret.expect("pending secret stored");
+    assert!(!sealed.contains(body["data"]["secret"].as_str().unwrap()));
+    let secret =
+        totp::decrypt_secret(&app.state.config().admin_totp_encryption_key, &sealed).unwrap();
+    let now = Utc::now().timestamp() as u64;
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/auth/totp/enable",
+        Some(serde_json::json!({ "code": totp::generate_code(&secret, now) })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let backup_codes: Vec<String> =
+        serde_json::from_value(body["data"]["backupCodes"].clone()).unwrap();
+    assert_eq!(backup_codes.len(), 10);
+
+    let login = |code: Option<String>| {
+        let app = app.app.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::POST,
+                "/api/admin/auth/login",
+                Some(serde_json::json!({ "email": email, "password": password, "totpCode": code })),
+                &[],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, body) = login(None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "ADMIN_TOTP_REQUIRED");
+
+    // 启用时已使用的时间步不能重放
+    let (status, _, body) = login(Some(totp::generate_code(&secret, now))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "ADMIN_TOTP_INVALID");
+
+    let (status, _, body) = login(Some(totp::generate_code(&secret, now + 30))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, _, _) = login(Some(backup_codes[0].to_uppercase())).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = login(Some(backup_codes[0].clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let totp_action = |path: &'static str, code: String| {
+        let (app, token) = (app.app.clone(), token.clone());
+        async move {
+            let response = request(
+                &app,
+                Method::POST,
+                path,
+                Some(serde_json::json!({ "code": code })),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    // 重新生成后旧备用码全部作废
+    let (status, _, body) =
+        totp_action("/api/admin/auth/totp/backup-codes", "0000-0000".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "ADMIN_TOTP_INVALID");
+    let (status, _, body) =
+        totp_action("/api/admin/auth/totp/backup-codes", backup_codes[1].clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let new_codes: Vec<String> =
+        serde_json::from_value(body["data"]["backupCodes"].clone()).unwrap();
+    assert_eq!(new_codes.len(), 10);
+    let (status, _, _) = login(Some(backup_codes[2].clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = login(Some(new_codes[0].clone())).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, body) = totp_action("/api/admin/auth/totp/disable", new_codes[1].clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["enabled"], false);
+    let (status, _, _) = login(None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, body) = totp_action("/api/admin/auth/totp/disable", new_codes[2].clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "ADMIN_TOTP_NOT_ENROLLED");
+}
+
+#[tokio::test]
+async fn it_admin_amas_replay_is_dry_run_in_record_order() {
+    let app = spawn_test_server().await;
+    let user_token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &user_token).await;
+
+    for idx in 0..3 {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/records",
+            Some(serde_json::json!({
+                "wordId": format!("replay-word-{}", idx % 2),
+                "isCorrect": idx != 1,
+                "responseTimeMs": 1200,
+                "sessionId": "replay-session"
+            })),
+            &[("authorization", auth_header(&user_token))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        // 保证记录时间戳严格递增
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let state_before = app
+        .state
+        .store()
+        .get_engine_user_state(&user_id)
+        .unwrap()
+        .expect("engine state persisted");
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/admin/amas/replay/{user_id}"),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK, "replay failed: {body}");
+    let data = &body["data"];
+    assert_eq!(data["replayedCount"], 3);
+    let words: Vec<&str> = data["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["wordId"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, ["replay-word-0", "replay-word-1", "replay-word-0"]);
+    assert!(data["storedStrategy"].is_object());
+    assert!(data["replayedStrategy"].is_object());
+    let mastery = data["mastery"].as_array().unwrap();
+    assert_eq!(mastery.len(), 2);
+    assert!(mastery.iter().all(|m| !m["stored"].is_null()));
+    assert_eq!(data["hasMore"], false);
+    assert!(data["steps"][0]["storedAlgorithm"].is_string());
+    assert!(data["steps"][0]["algorithmChanged"].is_boolean());
+
+    // 分页时仍从第一条记录开始重放，只返回指定区间
+    let response = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/admin/amas/replay/{user_id}?offset=1&limit=1"),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = &body["data"];
+    assert_eq!(page["replayedCount"], 2);
+    assert_eq!(page["hasMore"], true);
+    assert_eq!(page["steps"].as_array().unwrap().len(), 1);
+    assert_eq!(page["steps"][0]["wordId"], "replay-word-1");
+    assert_eq!(page["steps"][0]["strategy"], data["steps"][1]["strategy"]);
+    assert!(page["storedStrategy"].is_null());
+
+    let state_after = app
+        .state
+        .store()
+        .get_engine_user_state(&user_id)
+        .unwrap()
+        .expect("engine state persisted");
+    assert_eq!(state_before, state_after);
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/amas/replay/missing-user",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn it_admin_backup_writes_snapshot_file() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+
+    let forbidden = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/backup",
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    assert!(forbidden.status().is_client_error());
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/backup",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let path = std::path::PathBuf::from(body["data"]["path"].as_str().unwrap());
+    assert!(path.starts_with(&app.config.worker.backup_dir));
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().len(),
+        body["data"]["sizeBytes"].as_u64().unwrap()
+    );
+    assert!(body["data"]["entries"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn it_maintenance_mode_blocks_writes_but_not_reads_or_admin() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+
+    let admin_headers = [("authorization", auth_header(&admin_token))];
+    let user_headers = [("authorization", auth_header(&user_token))];
+    let set_maintenance = |enabled: bool| {
+        request(
+            &app.app,
+            Method::PUT,
+            "/api/admin/settings",
+            Some(serde_json::json!({ "maintenanceMode": enabled })),
+            &admin_headers,
+        )
+    };
+    assert_eq!(set_maintenance(true).await.status(), StatusCode::OK);
+
+    let update_profile = || {
+        request(
+            &app.app,
+            Method::PUT,
+            "/api/users/me",
+            Some(serde_json::json!({ "username": "maint_user" })),
+            &user_headers,
+        )
+    };
+    let blocked = update_profile().await;
+    let (status, headers, body) = response_json(blocked).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "MAINTENANCE_MODE");
+    assert!(headers.contains_key("retry-after"));
+
+    let read = request(&app.app, Method::GET, "/api/users/me", None, &user_headers).await;
+    assert_eq!(read.status(), StatusCode::OK);
+
+    assert_eq!(set_maintenance(false).await.status(), StatusCode::OK);
+    assert_eq!(update_profile().await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn it_admin_algorithm_evaluation_groups_tagged_records() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+
+    for (word_id, correct) in [("eval-w1", true), ("eval-w2", false), ("eval-w1", true)] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/records",
+            Some(serde_json::json!({
+                "wordId": word_id,
+                "isCorrect": correct,
+                "responseTimeMs": 1500,
+            })),
+            &[("authorization", auth_header(&user_token))],
+        )
+        .await;
+        let (status, _, _) = response_json(response).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let admin = [("authorization", auth_header(&admin_token))];
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/algorithm-evaluation",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let algorithms = body["data"]["algorithms"].as_array().unwrap();
+    let samples: u64 = algorithms
+        .iter()
+        .map(|a| a["samples"].as_u64().unwrap())
+        .sum();
+    let downstream: u64 = algorithms
+        .iter()
+        .map(|a| a["downstreamSamples"].as_u64().unwrap())
+        .sum();
+    assert_eq!(samples, 3);
+    assert_eq!(downstream, 2);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/algorithm-evaluation?from=2026-03-10&to=2026-03-01",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_DATE_RANGE");
+}
+
+#[tokio::test]
+async fn it_admin_reset_trust_keeps_other_amas_state() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &user_token).await;
+
+    for correct in [true, false, true] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/records",
+            Some(serde_json::json!({
+                "wordId": "trust-w1",
+                "isCorrect": correct,
+                "responseTimeMs": 1500,
+            })),
+            &[("authorization", auth_header(&user_token))],
+        )
+        .await;
+        let (status, _, _) = response_json(response).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let admin = [("authorization", auth_header(&admin_token))];
+    let response = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/admin/amas/{user_id}/reset-trust"),
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["previous"]["decayed_at"].is_string());
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/admin/users/{user_id}/amas-export"),
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["algoStates"].get("trust").is_none());
+    assert!(body["data"]["algoStates"].get("ige").is_some());
+    assert_eq!(body["data"]["userState"]["totalEventCount"], 3);
+    assert!(body["data"]["masteryStates"]["trust-w1"].is_object());
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/amas/missing-user/reset-trust",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, _) = response_json(response).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn it_admin_sets_cold_start_phase() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &user_token).await;
+    let admin = [("authorization", auth_header(&admin_token))];
+
+    let set_phase = |phase: &'static str| {
+        let (router, admin, user_id) = (&app.app, &admin, &user_id);
+        async move {
+            let response = request(
+                router,
+                Method::POST,
+                &format!("/api/admin/amas/{user_id}/cold-start-phase"),
+                Some(serde_json::json!({ "phase": phase })),
+                admin,
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+    let user_phase = || async {
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/amas/phase",
+            None,
+            &[("authorization", auth_header(&user_token))],
+        )
+        .await;
+        let (_, _, body) = response_json(response).await;
+        body["data"]["phase"].clone()
+    };
+
+    let (status, _, body) = set_phase("Explore").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["previousPhase"], "Classify");
+    assert_eq!(body["data"]["totalEventCount"], 20);
+    assert_eq!(user_phase().await, "Explore");
+
+    let (status, _, body) = set_phase("Exploit").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["previousEventCount"], 20);
+    assert!(user_phase().await.is_null());
+
+    let (status, _, _) = set_phase("Classify").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user_phase().await, "Classify");
+
+    // 阈值为 0 时分类阶段不可达
+    let mut cfg = app.state.amas().get_config().await;
+    cfg.cold_start.classify_to_explore_events = 0;
+    app.state.amas().reload_config(cfg).await.unwrap();
+    let (status, _, body) = set_phase("Classify").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "AMAS_INVALID_COLD_START_PHASE");
+    assert_eq!(user_phase().await, "Explore");
+}
+
+#[tokio::test]
+async fn it_admin_feature_flag_overrides_apply_to_single_user() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let canary_token = login_and_get_token(&app.app).await;
+    let canary_id = current_user_id(&app.app, &canary_token).await;
+    let other_token = login_and_get_token(&app.app).await;
+    let admin = [("authorization", auth_header(&admin_token))];
+    let path = format!("/api/admin/amas/{canary_id}/feature-flags");
+
+    let response = request(
+        &app.app,
+        Method::PUT,
+        &path,
+        Some(serde_json::json!({ "igeEnabled": false, "mtpEnabled": true })),
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["overrides"]["igeEnabled"], false);
+    assert!(body["data"]["overrides"]["swdEnabled"].is_null());
+    assert_eq!(body["data"]["effective"]["igeEnabled"], false);
+    assert_eq!(body["data"]["effective"]["swdEnabled"], true);
+    assert_eq!(body["data"]["effective"]["mtpEnabled"], true);
+
+    let weights = |token: String| {
+        let router = &app.app;
+        async move {
+            let response = request(
+                router,
+                Method::POST,
+                "/api/records",
+                Some(serde_json::json!({
+                    "wordId": "flag-w1",
+                    "isCorrect": true,
+                    "responseTimeMs": 1500,
+                })),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            let (status, _, body) = response_json(response).await;
+            assert_eq!(status, StatusCode::CREATED);
+            body["data"]["record"]["algorithmWeights"].clone()
+        }
+    };
+    assert!(weights(canary_token.clone()).await.get("Ige").is_none());
+    assert!(weights(other_token).await.get("Ige").is_some());
+
+    // 全部为 null 即清除覆盖
+    let response = request(
+        &app.app,
+        Method::PUT,
+        &path,
+        Some(serde_json::json!({})),
+        &admin,
+    )
+    .await;
+    let (status, _, _) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let response = request(&app.app, Method::GET, &path, None, &admin).await;
+    let (_, _, body) = response_json(response).await;
+    assert!(body["data"]["overrides"]["igeEnabled"].is_null());
+    assert_eq!(body["data"]["effective"]["igeEnabled"], true);
+    assert!(weights(canary_token).await.get("Ige").is_some());
+}
+
+#[tokio::test]
+async fn it_admin_llm_usage_tracks_tokens_against_budget() {
+    let app = common::app::spawn_test_server_with_config(|c| {
+        c.llm.enabled = true;
+        c.llm.budget.daily_token_limit = 1_000_000;
+        c.llm.budget.input_price_per_1k = 0.5;
+    })
+    .await;
+    let user_token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let word_id = create_word(&app.app, &admin_token, "budget").await;
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/content/word-contexts/{word_id}"),
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/llm-usage",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let today = &body["data"]["days"][0];
+    assert_eq!(today["date"], Utc::now().date_naive().to_string());
+    assert_eq!(today["byFeature"]["examples"]["calls"], 1);
+    assert!(today["estimatedCost"].as_f64().unwrap() > 0.0);
+    let used = body["data"]["budget"]["usedToday"].as_u64().unwrap();
+    assert!(used > 0);
+    assert_eq!(
+        body["data"]["budget"]["remainingToday"].as_u64().unwrap(),
+        1_000_000 - used
+    );
+    assert_eq!(body["data"]["budget"]["exhausted"], false);
+}
+
+#[tokio::test]
+async fn it_admin_llm_advisories_require_confirmation_before_applying() {
+    use learning_backend::store::operations::llm_advisories::{
+        AdvisorOutput, ConfigSuggestion, LlmAdvisory,
+    };
+
+    let app = common::app::spawn_test_server_with_mock_llm().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+
+    learning_backend::workers::llm_advisor::run(
+        app.state.store(),
+        app.state.amas(),
+        Some(app.state.llm().as_ref()),
+    )
+    .await;
+
+    let suggestion = |path: &str, proposed: serde_json::Value| ConfigSuggestion {
+        path: path.to_string(),
+        current: serde_json::Value::Null,
+        proposed,
+        rationale: "test".to_string(),
+        applied_at: None,
+        applied_by: None,
+    };
+    app.state
+        .store()
+        .save_llm_advisory(&LlmAdvisory {
+            id: "advisory-1".to_string(),
+            model: "mock".to_string(),
+            output: AdvisorOutput {
+                summary: "seeded".to_string(),
+                suggestions: vec![
+                    suggestion("ensemble.warmupSamples", serde_json::json!(42)),
+                    suggestion("monitoring.sampleRate", serde_json::json!(5.0)),
+                ],
+                anomalies: Vec::new(),
+            },
+            created_at: Utc::now() + chrono::Duration::seconds(1),
+        })
+        .unwrap();
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/amas/advisories?limit=10",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let advisories = body["data"].as_array().unwrap();
+    assert_eq!(advisories.len(), 2);
+    assert_eq!(advisories[0]["id"], "advisory-1");
+    assert_eq!(advisories[1]["model"], "mock");
+
+    let apply = |index: usize, confirm: bool| {
+        let app = app.app.clone();
+        let admin_token = admin_token.clone();
+        async move {
+            request(
+                &app,
+                Method::POST,
+                &format!("/api/admin/amas/advisories/advisory-1/suggestions/{index}/apply"),
+                Some(serde_json::json!({ "confirm": confirm })),
+                &[("authorization", auth_header(&admin_token))],
+            )
+            .await
+        }
+    };
+    let previous = app.state.amas().get_config().await.ensemble.warmup_samples;
+
+    let (status, _, body) = response_json(apply(0, false).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "CONFIRMATION_REQUIRED");
+    assert_eq!(
+        app.state.amas().get_config().await.ensemble.warmup_samples,
+        previous
+    );
+
+    let (status, _, body) = response_json(apply(0, true).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["previous"], previous);
+    assert!(body["data"]["advisory"]["suggestions"][0]["appliedAt"].is_string());
+    assert_eq!(
+        app.state.amas().get_config().await.ensemble.warmup_samples,
+        42
+    );
+
+    let (status, _, body) = response_json(apply(0, true).await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "ADVISORY_SUGGESTION_APPLIED");
+
+    // 未通过配置校验的建议不会生效
+    let (status, _, body) = response_json(apply(1, true).await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "AMAS_INVALID_CONFIG");
+    assert!(app.state.amas().get_config().await.monitoring.sample_rate <= 1.0);
+
+    let (status, _, _) = response_json(apply(9, true).await).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn it_admin_broadcast_schedules_targeted_sends() {
+    use learning_backend::store::operations::broadcasts::{BroadcastStatus, BroadcastTarget};
+
+    let app = spawn_test_server().await;
+    let user_token = login_and_get_token(&app.app).await;
+    let banned_token = login_and_get_token(&app.app).await;
+    let banned_id = current_user_id(&app.app, &banned_token).await;
+    app.state.store().ban_user(&banned_id).unwrap();
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+
+    let schedule = |payload: serde_json::Value| {
+        let app = app.app.clone();
+        let admin_token = admin_token.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::POST,
+                "/api/admin/broadcast",
+                Some(payload),
+                &[("authorization", auth_header(&admin_token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, body) = schedule(serde_json::json!({
+        "title": "Past",
+        "message": "Too late",
+        "scheduledAt": (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339(),
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_SCHEDULED_AT");
+
+    let (status, _, body) = schedule(serde_json::json!({
+        "title": "Wordbook",
+        "message": "Missing wordbook",
+        "target": { "type": "wordbookLearners", "wordbookId": "missing" },
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "WORDBOOK_NOT_FOUND");
+
+    let (status, _, body) = schedule(serde_json::json!({
+        "title": "Maintenance",
+        "message": "Tonight at 2am",
+        "target": { "type": "excludeBanned" },
+        "scheduledAt": (Utc::now() + chrono::Duration::hours(1)).to_rfc3339(),
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "pending");
+    assert_eq!(body["data"]["recipients"], 1);
+    let broadcast_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let cancel_path = format!("/api/admin/broadcast/scheduled/{broadcast_id}");
+    for expected in [StatusCode::OK, StatusCode::CONFLICT] {
+        let response = request(
+            &app.app,
+            Method::DELETE,
+            &cancel_path,
+            None,
+            &[("authorization", auth_header(&admin_token))],
+        )
+        .await;
+        assert_eq!(response.status(), expected);
+    }
+
+    // 到期后由 worker 投递，仅未封禁用户收到
+    let mut due = app
+        .state
+        .store()
+        .get_scheduled_broadcast(&broadcast_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(due.target, BroadcastTarget::ExcludeBanned);
+    due.id = "due-broadcast".to_string();
+    due.status = BroadcastStatus::Pending;
+    due.scheduled_at = Utc::now() - chrono::Duration::seconds(1);
+    app.state.store().save_scheduled_broadcast(&due).unwrap();
+    learning_backend::workers::scheduled_broadcasts::run(app.state.store(), 0).await;
+
+    let sent = app
+        .state
+        .store()
+        .get_scheduled_broadcast("due-broadcast")
+        .unwrap()
+        .unwrap();
+    assert_eq!(sent.status, BroadcastStatus::Sent);
+    assert_eq!(sent.sent, 1);
+    for (token, expected) in [(&user_token, 1), (&banned_token, 0)] {
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/notifications?limit=50",
+            None,
+            &[("authorization", auth_header(token))],
+        )
+        .await;
+        let (_, _, body) = response_json(response).await;
+        let received = body["data"]["data"].as_array().map_or(0, |items| {
+            items.iter().filter(|n| n["title"] == "Maintenance").count()
+        });
+        assert_eq!(received, expected);
+    }
+
+    let (status, _, body) = schedule(serde_json::json!({
+        "title": "Later",
+        "message": "Everyone",
+        "scheduledAt": (Utc::now() + chrono::Duration::days(1)).to_rfc3339(),
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["recipients"], 2);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/broadcast/scheduled",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let statuses: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["pending", "cancelled", "sent"]);
+}
+
+#[tokio::test]
+async fn it_admin_broadcast_stats_report_read_rate() {
+    let app = spawn_test_server().await;
+    let user_token = login_and_get_token(&app.app).await;
+    login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/admin/broadcast",
+        Some(serde_json::json!({ "title": "Survey", "message": "Tell us more" })),
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let broadcast_id = body["data"]["broadcastId"].as_str().unwrap().to_string();
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/notifications?limit=50",
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    let notification = body["data"]["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["broadcastId"] == broadcast_id.as_str())
+        .expect("broadcast notification")
+        .clone();
+    let response = request(
+        &app.app,
+        Method::PUT,
+        &format!(
+            "/api/notifications/{}/read",
+            notification["id"].as_str().unwrap()
+        ),
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/admin/broadcast/{broadcast_id}/stats"),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["delivered"], 2);
+    assert_eq!(body["data"]["read"], 1);
+    assert_eq!(body["data"]["unread"], 1);
+    assert_eq!(body["data"]["readRate"], 0.5);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/broadcast/unknown/stats",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn it_admin_retention_reports_signup_week_cohorts() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/records",
+        Some(serde_json::json!({
+            "wordId": "retention-w1",
+            "isCorrect": true,
+            "responseTimeMs": 1200,
+        })),
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let admin = [("authorization", auth_header(&admin_token))];
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/retention?weeks=4",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let cohorts = body["data"]["cohorts"].as_array().unwrap();
+    assert_eq!(cohorts.len(), 4);
+    let current = &cohorts[3];
+    assert_eq!(current["size"], 1);
+    assert_eq!(current["active"], serde_json::json!([1]));
+    assert_eq!(current["retention"], serde_json::json!([1.0]));
+    let generated_at = body["data"]["generatedAt"].clone();
+
+    // 缓存期内新注册的用户不会出现在结果中
+    login_and_get_token(&app.app).await;
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/retention?weeks=4",
+        None,
+        &admin,
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    assert_eq!(body["data"]["generatedAt"], generated_at);
+    assert_eq!(body["data"]["cohorts"][3]["size"], 1);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/retention?weeks=1000",
+        None,
+        &admin,
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    assert_eq!(body["data"]["weeks"], 26);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/retention?weeks=4&format=csv",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, csv) = response_text(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "cohortWeek,cohortSize,weekOffset,active,retention"
+    );
+    // 4 + 3 + 2 + 1 个（用户群, 周偏移）组合
+    assert_eq!(lines.len(), 11);
+    assert!(lines[10].ends_with(",1,0,1,1"));
+}
+
+#[tokio::test]
+async fn it_admin_funnel_counts_onboarding_steps() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    login_and_get_token(&app.app).await;
+    let learner_token = login_and_get_token(&app.app).await;
+    let learner = [("authorization", auth_header(&learner_token))];
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/records",
+        Some(serde_json::json!({
+            "wordId": "funnel-w1",
+            "isCorrect": true,
+            "responseTimeMs": 1200,
+        })),
+        &learner,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/learning/session",
+        None,
+        &learner,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let admin = [("authorization", auth_header(&admin_token))];
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/funnel",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let steps = body["data"]["steps"].as_array().unwrap();
+    let users: Vec<u64> = steps.iter().map(|s| s["users"].as_u64().unwrap()).collect();
+    assert_eq!(users, [2, 1, 1, 0]);
+    assert_eq!(steps[1]["step"], "firstWord");
+    assert_eq!(steps[1]["conversionRate"], 0.5);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/funnel?format=csv",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, headers, csv) = response_text(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["content-type"], "text/csv; charset=utf-8");
+    assert!(headers["content-disposition"]
+        .to_str()
+        .unwrap()
+        .starts_with("attachment; filename=\"wordforge-funnel-"));
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "step,users,conversionRate,overallRate");
+    assert_eq!(lines[2], "firstWord,1,0.5,0.5");
+    assert_eq!(lines.len(), 5);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/funnel?from=2026-03-10&to=2026-03-01",
+        None,
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_DATE_RANGE");
+}
+
+#[tokio::test]
+async fn it_admin_word_difficulty_ranks_words_by_error_rate() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let user_token = login_and_get_token(&app.app).await;
+    let hard = create_word(&app.app, &admin_token, "arduous").await;
+    let easy = create_word(&app.app, &admin_token, "cat").await;
+
+    for (word_id, correct) in [
+        (&hard, false),
+        (&hard, false),
+        (&hard, true),
+        (&easy, true),
+        (&easy, true),
+        (&easy, true),
+    ] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/records",
+            Some(serde_json::json!({
+                "wordId": word_id,
+                "isCorrect": correct,
+                "responseTimeMs": 1500,
+            })),
+            &[("authorization", auth_header(&user_token))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/word-difficulty?minSamples=3",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["sampledRecords"], 6);
+    let words = body["data"]["words"].as_array().unwrap();
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[0]["wordId"], hard.as_str());
+    assert_eq!(words[0]["attempts"], 3);
+    assert_eq!(words[0]["correct"], 1);
+    assert!(words[0]["eloGames"].as_u64().unwrap() > 0);
+    assert_eq!(words[1]["wordId"], easy.as_str());
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/word-difficulty?minSamples=4",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    assert!(body["data"]["words"].as_array().unwrap().is_empty());
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/admin/analytics/word-difficulty?format=csv&minSamples=1",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, csv) = response_text(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("wordId,text,labeledDifficulty,attempts,correct"));
+    assert!(lines[1].starts_with(&format!("{hard},arduous,")));
+    assert_eq!(lines.len(), 3);
+}
+
+#[tokio::test]
+async fn it_registration_applies_default_study_config() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let admin = [("authorization", auth_header(&admin_token))];
+
+    let response = request(
+        &app.app,
+        Method::PUT,
+        "/api/admin/settings",
+        Some(serde_json::json!({ "defaultStudyMode": "cram" })),
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_STUDY_MODE");
+
+    let response = request(
+        &app.app,
+        Method::PUT,
+        "/api/admin/settings",
+        Some(serde_json::json!({
+            "defaultDailyWords": 35,
+            "defaultMasteryTarget": 15,
+            "defaultStudyMode": "intensive",
+        })),
+        &admin,
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["defaultMasteryTarget"], 15);
+
+    let user_token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &user_token).await;
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/study-config",
+        None,
+        &[("authorization", auth_header(&user_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["dailyWordCount"], 35);
+    assert_eq!(body["data"]["dailyMasteryTarget"], 15);
+    assert_eq!(body["data"]["studyMode"], "intensive");
+
+    // 已有设置不会被默认值覆盖
+    let settings = app.state.store().get_system_settings().unwrap();
+    let mut custom = settings.default_study_config(&user_id);
+    custom.daily_word_count = 5;
+    assert!(!app.state.store().init_study_config(&custom).unwrap());
+    assert_eq!(
+        app.state
+            .store()
+            .get_study_config(&user_id)
+            .unwrap()
+            .daily_word_count,
+        35
+    );
+}
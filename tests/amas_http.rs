@@ -64,3 +64,103 @@ async fn it_amas_high_fatigue_applies_constraints() {
         .unwrap();
     assert!(difficulty <= 0.55);
 }
+
+#[tokio::test]
+async fn it_learning_explain_returns_latest_explanation() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/learning/explain",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_null());
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/amas/process-event",
+        Some(serde_json::json!({
+            "wordId": "word-1",
+            "isCorrect": true,
+            "responseTime": 1000,
+            "sessionId": "explain-session"
+        })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, event_body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/learning/explain",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["sessionId"], "explain-session");
+    assert!(body["data"]["generatedAt"].is_string());
+    assert_eq!(
+        body["data"]["explanation"]["primaryReason"],
+        event_body["data"]["explanation"]["primaryReason"]
+    );
+    assert_ne!(
+        body["data"]["explanation"]["primaryReason"],
+        "Strategy generated by AMAS"
+    );
+    assert!(body["data"]["explanation"]["reasonKey"]
+        .as_str()
+        .unwrap()
+        .starts_with("amas.reason."));
+    for factor in body["data"]["explanation"]["factors"].as_array().unwrap() {
+        assert!(!factor["description"].as_str().unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn it_visual_fatigue_smoothing_suppresses_oscillation() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+
+    let mut cfg = app.state.amas().get_config().await;
+    cfg.modeling.visual_fatigue_max_delta = 0.05;
+    app.state.amas().reload_config(cfg).await.unwrap();
+
+    let mut fatigues = Vec::new();
+    for idx in 0..10 {
+        let score = if idx % 2 == 0 { 100.0 } else { 0.0 };
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/amas/visual-fatigue",
+            Some(serde_json::json!({ "score": score })),
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        fatigues.push(body["data"]["fatigue"].as_f64().unwrap());
+    }
+
+    let mut prev = 0.0;
+    for f in &fatigues {
+        assert!(
+            (f - prev).abs() <= 0.05 + 1e-9,
+            "step too large: {prev} -> {f}"
+        );
+        prev = *f;
+    }
+    let max = fatigues.iter().cloned().fold(f64::MIN, f64::max);
+    let min = fatigues.iter().cloned().fold(f64::MAX, f64::min);
+    assert!(max - min <= 0.1, "fatigue oscillated: {fatigues:?}");
+}
@@ -3,7 +3,7 @@ mod common;
 use axum::http::{Method, StatusCode};
 
 use common::app::spawn_test_server;
-use common::auth::{auth_header, login_and_get_token, login_and_get_tokens};
+use common::auth::{auth_header, extract_cookie_value, login_and_get_token, login_and_get_tokens};
 use common::http::{assert_json_error, request, response_json};
 use learning_backend::store::operations::system_settings::SystemSettings;
 
@@ -146,6 +146,53 @@ async fn it_auth_refresh_rotates_token() {
     assert_eq!(old_refresh_status, StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+async fn it_auth_refresh_reuse_revokes_token_chain() {
+    let app = spawn_test_server().await;
+    let (_, first_refresh) = login_and_get_tokens(&app.app).await;
+
+    let refresh = |token: String| {
+        let app = app.app.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::POST,
+                "/api/auth/refresh",
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, headers, body) = refresh(first_refresh.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let second_refresh = extract_cookie_value(&headers, "refresh_token").unwrap();
+    assert_ne!(second_refresh, first_refresh);
+    let stolen_access = body["data"]["accessToken"].as_str().unwrap().to_string();
+
+    // 旧令牌重放：拒绝并撤销整条链，连同最新签发的令牌
+    let (status, _, body) = refresh(first_refresh).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_json_error(&body, "AUTH_REFRESH_TOKEN_REUSED");
+
+    let (status, _, _) = refresh(second_refresh).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // 该链签发的访问令牌也已失效
+    let me = request(
+        &app.app,
+        Method::GET,
+        "/api/users/me",
+        None,
+        &[("authorization", auth_header(&stolen_access))],
+    )
+    .await;
+    let (me_status, _, _) = response_json(me).await;
+    assert_eq!(me_status, StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn it_auth_logout_revokes_session() {
     let app = spawn_test_server().await;
@@ -243,9 +290,10 @@ async fn it_auth_login_blocked_in_maintenance_mode() {
     )
     .await;
 
-    let (status, _, body) = response_json(response).await;
-    assert_eq!(status, StatusCode::FORBIDDEN);
-    assert_eq!(body["code"], "FORBIDDEN");
+    let (status, headers, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "MAINTENANCE_MODE");
+    assert!(headers.contains_key("retry-after"));
 }
 
 #[tokio::test]
@@ -292,3 +340,176 @@ async fn it_auth_register_respects_max_users_limit() {
     assert_eq!(second_status, StatusCode::FORBIDDEN);
     assert_eq!(second_body["code"], "FORBIDDEN");
 }
+
+#[tokio::test]
+async fn it_auth_register_reports_specific_password_rule() {
+    let app = spawn_test_server().await;
+
+    for (idx, (password, code)) in [
+        ("Pw0!", "PASSWORD_TOO_SHORT"),
+        ("password1", "PASSWORD_MISSING_UPPERCASE"),
+        ("PASSWORD1", "PASSWORD_MISSING_LOWERCASE"),
+        ("Passwords", "PASSWORD_MISSING_DIGIT"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/auth/register",
+            Some(serde_json::json!({
+                "email": format!("policy-{idx}@test.com"),
+                "username": format!("policy{idx}"),
+                "password": password
+            })),
+            &[],
+        )
+        .await;
+
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{password}");
+        assert_json_error(&body, code);
+    }
+}
+
+#[tokio::test]
+async fn it_error_message_follows_accept_language() {
+    let app = spawn_test_server().await;
+    let payload = serde_json::json!({
+        "email": "i18n@test.com",
+        "username": "i18n",
+        "password": "Pw0!"
+    });
+
+    for (accept_language, message) in [
+        ("en-US,en;q=0.9", "Password is too short"),
+        ("zh-CN,zh;q=0.9,en;q=0.8", "密码长度不能少于8个字符"),
+        ("fr-FR", "密码长度不能少于8个字符"),
+    ] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/auth/register",
+            Some(payload.clone()),
+            &[("accept-language", accept_language.to_string())],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "PASSWORD_TOO_SHORT", "{accept_language}");
+        assert_eq!(body["message"], message, "{accept_language}");
+    }
+}
+
+#[tokio::test]
+async fn it_error_response_echoes_request_id() {
+    let app = spawn_test_server().await;
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/users/me",
+        None,
+        &[("x-request-id", "support-case-42".to_string())],
+    )
+    .await;
+    let (status, headers, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(headers["x-request-id"], "support-case-42");
+    assert_eq!(body["traceId"], "support-case-42");
+    assert_eq!(body["code"], "AUTH_UNAUTHORIZED");
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/auth/login",
+        Some(serde_json::json!({"email": "nobody@test.com", "password": "Passw0rd!"})),
+        &[],
+    )
+    .await;
+    let (status, headers, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let generated = headers["x-request-id"].to_str().unwrap();
+    assert!(!generated.is_empty());
+    assert_eq!(body["traceId"], generated);
+}
+
+#[tokio::test]
+async fn it_auth_locked_account_reports_remaining_time() {
+    let app = spawn_test_server().await;
+
+    let _ = request(
+        &app.app,
+        Method::POST,
+        "/api/auth/register",
+        Some(serde_json::json!({
+            "email": "lockout@test.com",
+            "username": "lockout_user",
+            "password": "Passw0rd!"
+        })),
+        &[],
+    )
+    .await;
+
+    for _ in 0..5 {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/auth/login",
+            Some(serde_json::json!({ "email": "lockout@test.com", "password": "Wrong0rd!" })),
+            &[],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/auth/login",
+        Some(serde_json::json!({ "email": "lockout@test.com", "password": "Passw0rd!" })),
+        &[],
+    )
+    .await;
+
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_json_error(&body, "AUTH_ACCOUNT_LOCKED");
+    assert_eq!(body["details"]["reason"], "too_many_failed_logins");
+    assert!(body["details"]["lockedUntil"].is_string());
+    assert!(body["details"]["remainingSeconds"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn it_body_limits_differ_by_route() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let padding = "x".repeat(80 * 1024);
+
+    let login = request(
+        &app.app,
+        Method::POST,
+        "/api/auth/login",
+        Some(serde_json::json!({
+            "email": "oversized@test.com",
+            "password": padding,
+        })),
+        &[],
+    )
+    .await;
+    let (status, _, body) = response_json(login).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_json_error(&body, "PAYLOAD_TOO_LARGE");
+
+    // 批量接口允许更大的请求体
+    let batch = request(
+        &app.app,
+        Method::POST,
+        "/api/records/batch",
+        Some(serde_json::json!({ "records": [], "padding": padding })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert_ne!(batch.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -18,7 +18,11 @@ pub struct TestApp {
     _temp_dir: TempDir,
 }
 
-async fn spawn_with_limits(api_limit: u64) -> TestApp {
+async fn spawn_with_limits(api_limit: u64, llm_enabled: bool) -> TestApp {
+    spawn_with(api_limit, llm_enabled, |_| {}).await
+}
+
+async fn spawn_with(api_limit: u64, llm_enabled: bool, configure: impl FnOnce(&mut Config)) -> TestApp {
     let temp_dir = tempfile::tempdir().expect("tempdir");
     let sled_path = temp_dir.path().join("learning-test.sled");
 
@@ -27,21 +31,24 @@ async fn spawn_with_limits(api_limit: u64) -> TestApp {
     let test_admin_secret = format!("integration-test-admin-secret-{}", uuid::Uuid::new_v4());
     let test_refresh_secret = format!("integration-test-refresh-secret-{}", uuid::Uuid::new_v4());
 
-    let config = Config {
+    let mut config = Config {
         host: std::net::IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1)),
         port: 3000,
         log_level: "info".to_string(),
         enable_file_logs: false,
         log_dir: "./logs".to_string(),
         sled_path: sled_path.to_string_lossy().to_string(),
+        store: Default::default(),
         jwt_secret: test_secret,
         refresh_jwt_secret: test_refresh_secret,
         jwt_expires_in_hours: 24,
         refresh_token_expires_in_hours: 168,
         admin_jwt_secret: test_admin_secret,
         admin_jwt_expires_in_hours: 2,
+        admin_totp_encryption_key: format!("integration-test-totp-key-{}", uuid::Uuid::new_v4()),
         cors_origin: "http://localhost:5173".to_string(),
         trust_proxy: false,
+        http_drain_timeout_secs: 30,
         rate_limit: learning_backend::config::RateLimitConfig {
             window_secs: 60,
             max_requests: api_limit,
@@ -51,23 +58,46 @@ async fn spawn_with_limits(api_limit: u64) -> TestApp {
             is_leader: false,
             enable_llm_advisor: false,
             enable_monitoring: false,
+            store_compaction_cron: "0 45 * * * *".to_string(),
+            notification_group_window_secs: 86_400,
+            monitoring_retention_days: 7,
+            enable_backup: false,
+            backup_cron: "0 15 3 * * *".to_string(),
+            backup_dir: temp_dir.path().join("backups").to_string_lossy().to_string(),
+            backup_retention: 7,
+            enable_inactive_user_purge: false,
+            inactive_user_purge_cron: "0 0 4 * * *".to_string(),
+            inactive_user_retention_days: 730,
+            inactive_user_action: learning_backend::config::InactiveUserAction::Notify,
+            inactive_user_purge_dry_run: true,
         },
         amas: learning_backend::config::AMASEnvConfig {
             ensemble_enabled: true,
             monitor_sample_rate: 0.05,
         },
         llm: learning_backend::config::LLMConfig {
-            enabled: false,
-            mock: true,
+            enabled: llm_enabled,
+            provider: learning_backend::config::LlmBackend::Mock,
             api_url: String::new(),
             api_key: String::new(),
             timeout_secs: 30,
+            models: Default::default(),
+            circuit: Default::default(),
+            budget: Default::default(),
         },
         pagination: Default::default(),
         limits: Default::default(),
+        password_policy: Default::default(),
+        lockout: Default::default(),
+        csp: Default::default(),
+        static_assets: Default::default(),
+        compression: Default::default(),
+        word_uniqueness: Default::default(),
+        moderation: Default::default(),
     };
+    configure(&mut config);
 
-    let store = Arc::new(Store::open(&config.sled_path).expect("open store"));
+    let store = Arc::new(Store::open_with_config(&config.sled_path, &config.store).expect("open store"));
     store.run_migrations().expect("run migrations");
 
     let amas_engine = Arc::new(AMASEngine::new(
@@ -89,13 +119,23 @@ async fn spawn_with_limits(api_limit: u64) -> TestApp {
 }
 
 pub async fn spawn_test_app() -> TestApp {
-    spawn_with_limits(100).await
+    spawn_with_limits(100, false).await
+}
+
+/// 启用 mock LLM provider（确定性伪嵌入），用于覆盖依赖 LLM 的路径
+pub async fn spawn_test_server_with_mock_llm() -> TestApp {
+    spawn_with_limits(100, true).await
 }
 
 pub async fn spawn_test_server() -> TestApp {
     spawn_test_app().await
 }
 
+/// 在默认测试配置上调整个别字段
+pub async fn spawn_test_server_with_config(configure: impl FnOnce(&mut Config)) -> TestApp {
+    spawn_with(100, false, configure).await
+}
+
 pub async fn spawn_test_server_with_limits(api_limit: u64, _auth_limit: u64) -> TestApp {
-    spawn_with_limits(api_limit).await
+    spawn_with_limits(api_limit, false).await
 }
@@ -9,7 +9,7 @@ pub async fn login_and_get_token(app: &Router) -> String {
 }
 
 /// 从 Set-Cookie header 中提取指定 cookie 的值
-fn extract_cookie_value(headers: &HeaderMap, cookie_name: &str) -> Option<String> {
+pub fn extract_cookie_value(headers: &HeaderMap, cookie_name: &str) -> Option<String> {
     for value in headers.get_all("set-cookie") {
         if let Ok(s) = value.to_str() {
             // cookie 格式: "name=value; Path=/; ..."
@@ -86,3 +86,17 @@ pub async fn setup_admin_and_get_token(app: &Router) -> String {
         .expect("admin token in setup response")
         .to_string()
 }
+
+pub async fn current_user_id(app: &Router, token: &str) -> String {
+    let response = request(
+        app,
+        Method::GET,
+        "/api/users/me",
+        None,
+        &[("authorization", auth_header(token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert!(status.is_success(), "get current user failed: {body}");
+    body["data"]["id"].as_str().expect("user id").to_string()
+}
@@ -18,6 +18,7 @@ pub fn seed_user(store: &Store, email: &str, username: &str, password: &str) ->
         updated_at: now,
         failed_login_count: 0,
         locked_until: None,
+        lockout_level: 0,
     };
     store.create_user(&user).expect("create seed user");
     user
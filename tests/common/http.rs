@@ -46,6 +46,16 @@ pub async fn response_json(resp: Response) -> (StatusCode, HeaderMap, Value) {
     (status, headers, json)
 }
 
+pub async fn response_text(resp: Response) -> (StatusCode, HeaderMap, String) {
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let bytes = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("read body bytes");
+    let text = String::from_utf8(bytes.to_vec()).expect("utf-8 body");
+    (status, headers, text)
+}
+
 pub fn assert_json_error(body: &Value, code: &str) {
     assert_eq!(body["success"], false);
     assert_eq!(body["code"], code);
@@ -101,11 +101,13 @@ async fn it_learning_wordbooks_word_states_and_study_config_flow() {
     let (list_user_status, _, list_user_body) = response_json(list_user_books).await;
     assert_eq!(list_user_status, StatusCode::OK);
     assert!(
-        !list_user_body["data"]
+        !list_user_body["data"]["data"]
             .as_array()
             .unwrap_or(&Vec::new())
             .is_empty()
     );
+    assert_eq!(list_user_body["data"]["page"], 1);
+    assert_eq!(list_user_body["data"]["hasNext"], false);
 
     let add_words = request(
         &app.app,
@@ -146,6 +148,20 @@ async fn it_learning_wordbooks_word_states_and_study_config_flow() {
     let (forbidden_status, _, _) = response_json(forbidden_list).await;
     assert_eq!(forbidden_status, StatusCode::FORBIDDEN);
 
+    let progress = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/wordbooks/{wordbook_id}/progress"),
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (progress_status, _, progress_body) = response_json(progress).await;
+    assert_eq!(progress_status, StatusCode::OK);
+    assert_eq!(progress_body["data"]["total"], 3);
+    assert_eq!(progress_body["data"]["new"], 3);
+    assert_eq!(progress_body["data"]["masteredPercent"], 0.0);
+
     let update_study_config = request(
         &app.app,
         Method::PUT,
@@ -432,6 +448,41 @@ async fn it_learning_wordbooks_word_states_and_study_config_flow() {
     assert_eq!(remove_status, StatusCode::OK);
     assert_eq!(remove_body["data"]["removed"], true);
 
+    let forbidden_reset = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/wordbooks/{wordbook_id}/reset-progress"),
+        None,
+        &[("authorization", auth_header(&another_token))],
+    )
+    .await;
+    assert_eq!(forbidden_reset.status(), StatusCode::FORBIDDEN);
+
+    let reset_progress = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/wordbooks/{wordbook_id}/reset-progress"),
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (reset_status, _, reset_body) = response_json(reset_progress).await;
+    assert_eq!(reset_status, StatusCode::OK);
+    assert_eq!(reset_body["data"]["total"], 2);
+    assert_eq!(reset_body["data"]["reset"], 2);
+    assert_eq!(reset_body["data"]["failed"], 0);
+
+    let reset_state = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/word-states/{word_id_2}"),
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (_, _, reset_state_body) = response_json(reset_state).await;
+    assert_eq!(reset_state_body["data"]["state"], "NEW");
+
     let system_books = request(
         &app.app,
         Method::GET,
@@ -491,6 +542,7 @@ async fn it_user_profile_notifications_content_and_v1_flow() {
         "/api/user-profile/cognitive",
         "/api/user-profile/learning-style",
         "/api/user-profile/chronotype",
+        "/api/user-profile/optimal-times",
         "/api/user-profile/habit",
     ] {
         let response = request(
@@ -505,6 +557,18 @@ async fn it_user_profile_notifications_content_and_v1_flow() {
         assert_eq!(status, StatusCode::OK, "path: {path}");
     }
 
+    let optimal = request(
+        &app.app,
+        Method::GET,
+        "/api/user-profile/optimal-times",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (_, _, optimal_body) = response_json(optimal).await;
+    assert_eq!(optimal_body["data"]["source"], "default");
+    assert_eq!(optimal_body["data"]["recommendations"][0]["hour"], 9);
+
     let invalid_hours = request(
         &app.app,
         Method::POST,
@@ -588,6 +652,24 @@ async fn it_user_profile_notifications_content_and_v1_flow() {
     let (avatar_ok_status, _, avatar_ok_body) = response_json(avatar_ok).await;
     assert_eq!(avatar_ok_status, StatusCode::OK);
     assert!(avatar_ok_body["data"]["avatarUrl"].is_string());
+    assert_eq!(avatar_ok_body["data"]["contentType"], "image/png");
+    assert_eq!(avatar_ok_body["data"]["width"], 1);
+    assert_eq!(avatar_ok_body["data"]["height"], 1);
+
+    for expected_deleted in [true, false] {
+        let avatar_deleted = request(
+            &app.app,
+            Method::DELETE,
+            "/api/user-profile/avatar",
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(avatar_deleted).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["avatarUrl"], "/avatars/default.svg");
+        assert_eq!(body["data"]["deleted"], expected_deleted);
+    }
 
     let notification_1 = serde_json::json!({
         "id": "n-1",
@@ -658,7 +740,23 @@ async fn it_user_profile_notifications_content_and_v1_flow() {
     let (list_notifications_status, _, list_notifications_body) =
         response_json(list_notifications).await;
     assert_eq!(list_notifications_status, StatusCode::OK);
-    assert!(list_notifications_body["data"].is_array());
+    assert!(list_notifications_body["data"]["data"].is_array());
+    assert_eq!(list_notifications_body["data"]["perPage"], 20);
+
+    let first_page = request(
+        &app.app,
+        Method::GET,
+        "/api/notifications?perPage=1",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (_, _, first_page_body) = response_json(first_page).await;
+    // n-2 的类型无法解析，不计入
+    assert_eq!(first_page_body["data"]["total"], 1);
+    assert_eq!(first_page_body["data"]["totalPages"], 1);
+    assert_eq!(first_page_body["data"]["hasNext"], false);
+    assert_eq!(first_page_body["data"]["data"][0]["id"], "n-1");
 
     let mark_read = request(
         &app.app,
@@ -1344,8 +1442,7 @@ async fn it_words_users_records_auth_and_extractor_edges() {
     .await;
     let (missing_ct_status, _, missing_ct_body) = response_json(missing_content_type).await;
     assert_eq!(missing_ct_status, StatusCode::BAD_REQUEST);
-    // Error code can be MISSING_CONTENT_TYPE or INVALID_REQUEST_BODY depending on middleware
-    assert!(missing_ct_body["code"].is_string());
+    assert_eq!(missing_ct_body["code"], "MISSING_CONTENT_TYPE");
 
     let invalid_json = request_raw(
         &app.app,
@@ -1360,6 +1457,92 @@ async fn it_words_users_records_auth_and_extractor_edges() {
     .await;
     let (invalid_json_status, _, invalid_json_body) = response_json(invalid_json).await;
     assert_eq!(invalid_json_status, StatusCode::BAD_REQUEST);
-    // Error code can be INVALID_JSON_SYNTAX or INVALID_REQUEST_BODY depending on middleware
-    assert!(invalid_json_body["code"].is_string());
+    assert_eq!(invalid_json_body["code"], "INVALID_JSON_SYNTAX");
+}
+
+#[tokio::test]
+async fn it_notifications_webhook_config_flow() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let auth = [("authorization", auth_header(&token))];
+
+    let missing = request(
+        &app.app,
+        Method::GET,
+        "/api/notifications/webhook",
+        None,
+        &auth,
+    )
+    .await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "ftp://example.com",
+    ] {
+        let blocked = request(
+            &app.app,
+            Method::PUT,
+            "/api/notifications/webhook",
+            Some(serde_json::json!({ "url": url })),
+            &auth,
+        )
+        .await;
+        let (status, _, body) = response_json(blocked).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{url}");
+        assert_eq!(body["code"], "WEBHOOK_INVALID_URL");
+    }
+
+    let created = request(
+        &app.app,
+        Method::PUT,
+        "/api/notifications/webhook",
+        Some(serde_json::json!({ "url": "https://hooks.example.com/wordforge" })),
+        &auth,
+    )
+    .await;
+    let (status, _, body) = response_json(created).await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = body["data"]["secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("whsec_"));
+
+    let fetched = request(
+        &app.app,
+        Method::GET,
+        "/api/notifications/webhook",
+        None,
+        &auth,
+    )
+    .await;
+    let (status, _, body) = response_json(fetched).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["url"], "https://hooks.example.com/wordforge");
+    assert!(body["data"].get("secret").is_none());
+
+    let rotated = request(
+        &app.app,
+        Method::PUT,
+        "/api/notifications/webhook",
+        Some(serde_json::json!({
+            "url": "https://hooks.example.com/wordforge",
+            "rotateSecret": true
+        })),
+        &auth,
+    )
+    .await;
+    let (_, _, body) = response_json(rotated).await;
+    assert_ne!(body["data"]["secret"].as_str().unwrap(), secret);
+
+    let deleted = request(
+        &app.app,
+        Method::DELETE,
+        "/api/notifications/webhook",
+        None,
+        &auth,
+    )
+    .await;
+    let (status, _, body) = response_json(deleted).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["deleted"], true);
 }
@@ -1,6 +1,15 @@
 mod common;
 
+use std::sync::Arc;
+
 use axum::http::{Method, StatusCode};
+use tokio::sync::broadcast;
+
+use learning_backend::amas::config::AMASConfig;
+use learning_backend::amas::engine::AMASEngine;
+use learning_backend::routes::build_router;
+use learning_backend::state::AppState;
+use learning_backend::store::Store;
 
 use common::app::spawn_test_server;
 use common::http::{request, response_json};
@@ -30,3 +39,37 @@ async fn it_health_database_is_ok() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(body["healthy"], true);
 }
+
+#[tokio::test]
+async fn it_health_ready_waits_for_migrations_and_scheduler() {
+    let app = spawn_test_server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(Store::open(dir.path().join("ready-db").to_str().unwrap()).unwrap());
+    let mut config = app.config.clone();
+    config.worker.is_leader = true;
+    let engine = Arc::new(AMASEngine::new(
+        AMASConfig::from_env(&config.amas),
+        store.clone(),
+    ));
+    let (shutdown_tx, _) = broadcast::channel::<()>(1);
+    let state = AppState::new(store.clone(), engine, &config, shutdown_tx);
+    let router = build_router(state.clone());
+
+    let live = request(&router, Method::GET, "/health/live", None, &[]).await;
+    assert_eq!(live.status(), StatusCode::OK);
+
+    let ready = request(&router, Method::GET, "/health/ready", None, &[]).await;
+    let (status, _, body) = response_json(ready).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["migrations"], false);
+
+    store.run_migrations().unwrap();
+    let ready = request(&router, Method::GET, "/health/ready", None, &[]).await;
+    let (status, _, body) = response_json(ready).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["scheduler"], false);
+
+    state.readiness().mark_scheduler_started();
+    let ready = request(&router, Method::GET, "/health/ready", None, &[]).await;
+    assert_eq!(ready.status(), StatusCode::OK);
+}
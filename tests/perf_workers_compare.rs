@@ -21,6 +21,7 @@ fn sample_user(id: &str, email: &str) -> User {
         updated_at: Utc::now(),
         failed_login_count: 0,
         locked_until: None,
+        lockout_level: 0,
     }
 }
 
@@ -4,7 +4,7 @@ use axum::http::{Method, StatusCode};
 
 use common::app::spawn_test_server;
 use common::auth::{auth_header, login_and_get_token};
-use common::http::request;
+use common::http::{request, response_json};
 
 #[tokio::test]
 async fn it_sse_endpoint_is_reachable() {
@@ -28,3 +28,96 @@ async fn it_sse_endpoint_is_reachable() {
         .unwrap_or("");
     assert!(content_type.contains("text/event-stream"));
 }
+
+#[tokio::test]
+async fn it_notification_stream_pushes_only_own_notifications() {
+    use futures::StreamExt;
+
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+
+    let me = request(
+        &app.app,
+        Method::GET,
+        "/api/users/me",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (_, _, me_body) = response_json(me).await;
+    let user_id = me_body["data"]["id"].as_str().unwrap().to_string();
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/realtime/notifications/stream",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+
+    let store = app.state.store();
+    store
+        .create_notification(
+            "someone-else",
+            "n-other",
+            &serde_json::json!({ "id": "n-other", "title": "not mine" }),
+        )
+        .unwrap();
+    store
+        .create_notification(
+            &user_id,
+            "n-mine",
+            &serde_json::json!({ "id": "n-mine", "title": "hello" }),
+        )
+        .unwrap();
+
+    let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+        .await
+        .expect("notification event")
+        .unwrap()
+        .unwrap();
+    let text = String::from_utf8_lossy(&chunk);
+    assert!(text.contains("event: notification"), "{text}");
+    assert!(text.contains("n-mine"), "{text}");
+    assert!(!text.contains("n-other"), "{text}");
+}
+
+#[tokio::test]
+async fn it_fatigue_history_keeps_raw_and_blended_scores() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+
+    for score in [20.0, 80.0, 60.0] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/amas/visual-fatigue",
+            Some(serde_json::json!({ "score": score })),
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/realtime/fatigue/history?limit=2",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let samples = body["data"]["samples"].as_array().unwrap();
+    let scores: Vec<f64> = samples
+        .iter()
+        .map(|s| s["visualScore"].as_f64().unwrap())
+        .collect();
+    assert_eq!(scores, vec![80.0, 60.0]);
+    assert_eq!(samples[1]["blendedFatigue"], body["data"]["currentFatigue"]);
+    assert!(samples[0]["recordedAt"].is_string());
+}
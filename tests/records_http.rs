@@ -1,9 +1,13 @@
 mod common;
 
 use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use learning_backend::store::operations::idempotency::IdempotentResponse;
+use learning_backend::store::operations::records::LearningRecord;
 
 use common::app::spawn_test_server;
-use common::auth::{auth_header, login_and_get_token};
+use common::auth::{auth_header, current_user_id, login_and_get_token};
+use common::fixtures::{seed_engine_state, seed_words};
 use common::http::{request, response_json};
 
 #[tokio::test]
@@ -29,6 +33,8 @@ async fn it_record_create_and_query() {
     assert_eq!(status, StatusCode::CREATED);
     assert!(body["data"]["record"]["id"].is_string());
     assert!(body["data"]["amasResult"]["strategy"].is_object());
+    let algorithm = body["data"]["record"]["algorithmId"].as_str().unwrap();
+    assert!(body["data"]["record"]["algorithmWeights"][algorithm].is_number());
 
     let list = request(
         &app.app,
@@ -44,3 +50,624 @@ async fn it_record_create_and_query() {
     assert!(list_body["data"]["data"].is_array());
     assert!(!list_body["data"]["data"].as_array().unwrap().is_empty());
 }
+
+#[tokio::test]
+async fn it_record_velocity_reflects_increasing_mastery() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let today = chrono::Utc::now().date_naive();
+    for days_ago in 0..7i64 {
+        let date = (today - chrono::Duration::days(days_ago))
+            .format("%Y-%m-%d")
+            .to_string();
+        app.state
+            .store()
+            .increment_user_daily_mastered(&user_id, &date, (7 - days_ago) as u64)
+            .expect("seed mastery rollup");
+    }
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/records/statistics/velocity",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let data = &body["data"];
+    assert_eq!(data["masteredInWindow"], 28);
+    assert!(data["wordsPerWeek"].as_f64().unwrap() > 0.0);
+    assert!(data["acceleration"].as_f64().unwrap() > 0.0);
+    assert!(data["dailyTrend"].as_f64().unwrap() > 0.0);
+    assert_eq!(data["daily"].as_array().unwrap().len(), 7);
+}
+
+#[tokio::test]
+async fn it_word_states_never_correct_lists_only_failed_words() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let attempts = [
+        ("w-failed", false),
+        ("w-failed", false),
+        ("w-failed", false),
+        ("w-mastered", false),
+        ("w-mastered", true),
+    ];
+    for (idx, (word_id, is_correct)) in attempts.iter().enumerate() {
+        let response = request(
+            &app.app,
+            Method::POST,
+            "/api/records",
+            Some(serde_json::json!({
+                "wordId": word_id,
+                "isCorrect": is_correct,
+                "responseTimeMs": 1500,
+                "sessionId": format!("s-{idx}")
+            })),
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let mut mastered = app
+        .state
+        .store()
+        .get_word_learning_state(&user_id, "w-mastered")
+        .unwrap()
+        .unwrap();
+    mastered.state = learning_backend::store::operations::word_states::WordState::Mastered;
+    app.state
+        .store()
+        .set_word_learning_state(&mastered)
+        .unwrap();
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/word-states/never-correct",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let words = body["data"].as_array().unwrap();
+    assert_eq!(words.len(), 1);
+    assert_eq!(words[0]["wordId"], "w-failed");
+    assert_eq!(words[0]["totalAttempts"], 3);
+}
+
+#[tokio::test]
+async fn it_study_config_recommended_count_penalizes_fatigue() {
+    let app = spawn_test_server().await;
+
+    let mut recommendations = Vec::new();
+    for fatigue in [0.0, 0.95] {
+        let token = login_and_get_token(&app.app).await;
+        let user_id = current_user_id(&app.app, &token).await;
+
+        for idx in 0..20 {
+            let response = request(
+                &app.app,
+                Method::POST,
+                "/api/records",
+                Some(serde_json::json!({
+                    "wordId": format!("w-rec-{idx}"),
+                    "isCorrect": true,
+                    "responseTimeMs": 1200,
+                    "sessionId": "s-rec"
+                })),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        seed_engine_state(app.state.store(), &user_id, fatigue);
+
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/study-config/recommended-count",
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["activeDays"], 1);
+        assert!(!body["data"]["rationale"].as_array().unwrap().is_empty());
+        recommendations.push(body["data"]["recommendedCount"].as_u64().unwrap());
+    }
+
+    assert!(
+        recommendations[0] > recommendations[1],
+        "fresh user should get more words: {recommendations:?}"
+    );
+}
+
+#[tokio::test]
+async fn it_word_state_history_is_filtered_and_chronological() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let base = Utc::now() - Duration::hours(1);
+    // 插入顺序打乱，验证按时间而非写入顺序返回
+    let seeds = [
+        ("w-a", 30),
+        ("w-b", 10),
+        ("w-a", 5),
+        ("w-a", 50),
+        ("w-b", 40),
+    ];
+    for (idx, (word_id, minutes)) in seeds.into_iter().enumerate() {
+        app.state
+            .store()
+            .create_record(&LearningRecord {
+                id: format!("r-{idx}"),
+                user_id: user_id.clone(),
+                word_id: word_id.to_string(),
+                is_correct: idx % 2 == 0,
+                response_time_ms: 1000 + idx as i64,
+                session_id: None,
+                created_at: base + Duration::minutes(minutes),
+                algorithm_id: None,
+                algorithm_weights: Default::default(),
+            })
+            .unwrap();
+    }
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/word-states/w-a/history?perPage=2",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 3);
+    let first_page: Vec<&str> = body["data"]["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(first_page, vec!["r-2", "r-0"]);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/word-states/w-a/history?page=2&perPage=2",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let second_page = body["data"]["data"].as_array().unwrap();
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0]["id"], "r-3");
+    assert_eq!(second_page[0]["wordId"], "w-a");
+}
+
+#[tokio::test]
+async fn it_study_config_progress_exposes_opt_in_adaptive_count() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let progress = || async {
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/study-config/progress",
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        body["data"].clone()
+    };
+    assert!(progress().await["adaptive"].is_null());
+
+    let response = request(
+        &app.app,
+        Method::PUT,
+        "/api/study-config",
+        Some(serde_json::json!({ "dailyWordCount": 10, "adaptiveDailyCount": true })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 最近 3 天每天都完成 10 个单词且全部答对
+    for day in 0..3 {
+        for idx in 0..10 {
+            app.state
+                .store()
+                .create_record(&LearningRecord {
+                    id: format!("r-{day}-{idx}"),
+                    user_id: user_id.clone(),
+                    word_id: format!("w-{idx}"),
+                    is_correct: true,
+                    response_time_ms: 1000,
+                    session_id: None,
+                    created_at: (Utc::now().date_naive() - Duration::days(day))
+                        .and_hms_opt(0, idx, 0)
+                        .unwrap()
+                        .and_utc(),
+                    algorithm_id: None,
+                    algorithm_weights: Default::default(),
+                })
+                .unwrap();
+        }
+    }
+    seed_engine_state(app.state.store(), &user_id, 0.0);
+
+    let data = progress().await;
+    assert_eq!(data["dailyWordCount"], 10);
+    assert_eq!(data["adaptive"]["adjustment"], "increase");
+    assert_eq!(data["adaptive"]["suggestedCount"], 12);
+
+    seed_engine_state(app.state.store(), &user_id, 0.9);
+    let data = progress().await;
+    assert_eq!(data["adaptive"]["adjustment"], "decrease");
+    assert_eq!(data["adaptive"]["suggestedCount"], 8);
+}
+
+#[tokio::test]
+async fn it_study_config_cram_mode_requires_expiry_and_auto_expires() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let put = |body: serde_json::Value| {
+        let app = app.app.clone();
+        let token = token.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::PUT,
+                "/api/study-config",
+                Some(body),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, body) = put(serde_json::json!({ "studyMode": "cram" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_CRAM_UNTIL");
+
+    let too_far = Utc::now() + Duration::days(365);
+    let (status, _, _) =
+        put(serde_json::json!({ "studyMode": "cram", "cramUntil": too_far })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let until = Utc::now() + Duration::days(3);
+    let (status, _, body) =
+        put(serde_json::json!({ "studyMode": "cram", "cramUntil": until })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["studyMode"], "cram");
+    assert!(body["data"]["cramUntil"].is_string());
+
+    // 到期后读取即回到 normal
+    let mut config = app.state.store().get_study_config(&user_id).unwrap();
+    config.cram_until = Some(Utc::now() - Duration::minutes(1));
+    app.state.store().set_study_config(&config).unwrap();
+    let config = app.state.store().get_study_config(&user_id).unwrap();
+    assert_eq!(
+        config.study_mode,
+        learning_backend::store::operations::study_configs::StudyMode::Normal
+    );
+    assert!(config.cram_until.is_none());
+}
+
+#[tokio::test]
+async fn it_learning_due_only_words_follow_due_order_and_daily_cap() {
+    use learning_backend::store::operations::word_states::{WordLearningState, WordState};
+    use learning_backend::store::operations::words::Word;
+
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let due_words = || async {
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/learning/due-only-words",
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        body["data"]["words"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert!(due_words().await.is_empty());
+
+    let response = request(
+        &app.app,
+        Method::PUT,
+        "/api/study-config",
+        Some(serde_json::json!({ "dailyWordCount": 2 })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 三个已到期、一个未到期
+    for (word_id, due_in_minutes) in [
+        ("w-due-b", -10),
+        ("w-due-a", -30),
+        ("w-due-c", -5),
+        ("w-later", 60),
+    ] {
+        app.state
+            .store()
+            .upsert_word(&Word {
+                id: word_id.to_string(),
+                text: word_id.to_string(),
+                meaning: "释义".to_string(),
+                pronunciation: None,
+                part_of_speech: None,
+                difficulty: 0.5,
+                examples: Vec::new(),
+                tags: Vec::new(),
+                embedding: None,
+                created_at: Utc::now(),
+            })
+            .unwrap();
+        app.state
+            .store()
+            .set_word_learning_state(&WordLearningState {
+                user_id: user_id.clone(),
+                word_id: word_id.to_string(),
+                state: WordState::Reviewing,
+                mastery_level: 0.5,
+                next_review_date: Some(Utc::now() + Duration::minutes(due_in_minutes)),
+                half_life: 24.0,
+                correct_streak: 1,
+                total_attempts: 2,
+                updated_at: Utc::now(),
+            })
+            .unwrap();
+    }
+
+    assert_eq!(due_words().await, vec!["w-due-a", "w-due-b"]);
+}
+
+#[tokio::test]
+async fn it_record_create_replays_response_for_idempotency_key() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+    let payload = serde_json::json!({
+        "wordId": "w-idem",
+        "isCorrect": true,
+        "responseTimeMs": 900
+    });
+
+    let (router, token) = (&app.app, &token);
+    let submit = |path: &'static str, body: serde_json::Value, key: &'static str| async move {
+        request(
+            router,
+            Method::POST,
+            path,
+            Some(body),
+            &[
+                ("authorization", auth_header(token)),
+                ("idempotency-key", key.to_string()),
+            ],
+        )
+        .await
+    };
+
+    let (status, headers, first) =
+        response_json(submit("/api/records", payload.clone(), "retry-1").await).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(headers.get("idempotent-replayed").is_none());
+
+    let (status, headers, replay) =
+        response_json(submit("/api/records", payload.clone(), "retry-1").await).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(headers.get("idempotent-replayed").unwrap(), "true");
+    assert_eq!(replay, first);
+
+    let changed = serde_json::json!({
+        "wordId": "w-idem",
+        "isCorrect": false,
+        "responseTimeMs": 900
+    });
+    let (status, _, body) = response_json(submit("/api/records", changed, "retry-1").await).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "IDEMPOTENCY_KEY_REUSED");
+
+    // 模拟首次请求仍在处理中
+    let now = Utc::now();
+    let pending = IdempotentResponse {
+        status: 0,
+        body: serde_json::Value::Null,
+        request_hash: None,
+        pending: true,
+        created_at: now,
+        expires_at: now + Duration::minutes(5),
+    };
+    app.state
+        .store()
+        .reserve_idempotency_key(
+            &user_id,
+            "records",
+            &learning_backend::auth::hash_token("in-flight"),
+            &pending,
+        )
+        .unwrap();
+    let (status, _, body) =
+        response_json(submit("/api/records", payload.clone(), "in-flight").await).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "IDEMPOTENCY_KEY_IN_PROGRESS");
+
+    let batch = serde_json::json!({ "records": [payload.clone()] });
+    let (status, _, batch_first) =
+        response_json(submit("/api/records/batch", batch.clone(), "retry-1").await).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_ne!(
+        batch_first["data"]["items"][0]["record"]["id"],
+        first["data"]["record"]["id"]
+    );
+    let (_, headers, batch_replay) =
+        response_json(submit("/api/records/batch", batch, "retry-1").await).await;
+    assert_eq!(headers.get("idempotent-replayed").unwrap(), "true");
+    assert_eq!(batch_replay, batch_first);
+
+    let (status, _, body) = response_json(submit("/api/records", payload, "   ").await).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_IDEMPOTENCY_KEY");
+
+    let list = request(
+        &app.app,
+        Method::GET,
+        "/api/records?limit=50",
+        None,
+        &[("authorization", auth_header(token))],
+    )
+    .await;
+    let (_, _, list_body) = response_json(list).await;
+    assert_eq!(list_body["data"]["data"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn it_learning_intervention_reports_break_for_severe_fatigue() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+
+    let intervention = || async {
+        let response = request(
+            &app.app,
+            Method::GET,
+            "/api/learning/intervention",
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert_eq!(status, StatusCode::OK);
+        body["data"].clone()
+    };
+
+    seed_engine_state(app.state.store(), &user_id, 0.0);
+    assert!(intervention().await["current"].is_null());
+
+    seed_engine_state(app.state.store(), &user_id, 0.95);
+    let data = intervention().await;
+    assert_eq!(data["current"]["kind"], "take_break");
+    assert_eq!(data["current"]["severity"], "critical");
+    assert!(data["lastTriggered"].is_null());
+}
+
+#[tokio::test]
+async fn it_learning_placement_seeds_elo_and_profile_once() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let user_id = current_user_id(&app.app, &token).await;
+    let words = seed_words(app.state.store(), 12);
+
+    let submit = |answers: Vec<serde_json::Value>| {
+        let (router, token) = (&app.app, &token);
+        async move {
+            let response = request(
+                router,
+                Method::POST,
+                "/api/learning/placement",
+                Some(serde_json::json!({ "answers": answers })),
+                &[("authorization", auth_header(token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let mut answers = Vec::new();
+    let data = loop {
+        let (status, _, body) = submit(answers.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        let data = body["data"].clone();
+        if data["completed"] == true {
+            break data;
+        }
+        assert_eq!(data["answered"], answers.len());
+        let word_id = data["nextWord"]["id"].as_str().unwrap().to_string();
+        if answers.len() == 1 {
+            let mut repeated = answers.clone();
+            repeated.push(repeated[0].clone());
+            let (status, _, body) = submit(repeated).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["code"], "LEARNING_INVALID_PLACEMENT_ANSWERS");
+
+            // 未下发的单词不能作为作答提交
+            let unserved = words.iter().find(|w| w.id != word_id).unwrap();
+            let mut forged = answers.clone();
+            forged.push(serde_json::json!({
+                "wordId": unserved.id,
+                "isCorrect": true,
+                "responseTimeMs": 2000
+            }));
+            let (status, _, body) = submit(forged).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["code"], "LEARNING_INVALID_PLACEMENT_ANSWERS");
+        }
+        answers.push(serde_json::json!({
+            "wordId": word_id,
+            "isCorrect": true,
+            "responseTimeMs": 2000
+        }));
+    };
+    assert_eq!(data["answered"], 8);
+    assert_eq!(data["result"]["correct"], 8);
+    assert_eq!(data["result"]["cognitiveProfile"]["memoryCapacity"], 1.0);
+    let elo = data["result"]["elo"].as_f64().unwrap();
+    assert!(elo > 1200.0);
+
+    let user_elo = app.state.store().get_user_elo(&user_id).unwrap();
+    assert_eq!(user_elo.rating, elo);
+    assert_eq!(user_elo.games, 8);
+    let amas_state = app.state.amas().get_user_state(&user_id).unwrap();
+    assert_eq!(amas_state.total_event_count, 8);
+    assert_eq!(amas_state.cognitive_profile.memory_capacity, 1.0);
+
+    let (status, _, body) = submit(vec![]).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "LEARNING_PLACEMENT_COMPLETED");
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/learning/placement",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (_, _, body) = response_json(response).await;
+    assert_eq!(body["data"]["available"], false);
+    assert_eq!(body["data"]["result"]["elo"], elo);
+}
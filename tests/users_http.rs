@@ -1,10 +1,13 @@
 mod common;
 
 use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
 
 use common::app::spawn_test_server;
 use common::auth::{auth_header, login_and_get_token};
 use common::http::{assert_status_ok_json, request, response_json};
+use learning_backend::auth::hash_token;
+use learning_backend::store::operations::users::EmailChangeEntry;
 
 #[tokio::test]
 async fn it_user_get_me_success() {
@@ -33,3 +36,340 @@ async fn it_user_me_requires_auth() {
     let (status, _, _) = response_json(response).await;
     assert_eq!(status, StatusCode::UNAUTHORIZED);
 }
+
+#[tokio::test]
+async fn it_user_sessions_list_and_revoke() {
+    let app = spawn_test_server().await;
+    let email = format!("sessions-{}@test.com", uuid::Uuid::new_v4());
+    let password = "Passw0rd!";
+    let mut tokens = Vec::new();
+    for path in ["/api/auth/register", "/api/auth/login", "/api/auth/login"] {
+        let response = request(
+            &app.app,
+            Method::POST,
+            path,
+            Some(serde_json::json!({
+                "email": email,
+                "username": format!("u{}", uuid::Uuid::new_v4().simple()),
+                "password": password,
+            })),
+            &[],
+        )
+        .await;
+        let (status, _, body) = response_json(response).await;
+        assert!(status.is_success(), "{path} failed: {body}");
+        tokens.push(body["data"]["accessToken"].as_str().unwrap().to_string());
+    }
+
+    let list = |token: String| {
+        let app = app.app.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::GET,
+                "/api/users/me/sessions",
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, body) = list(tokens[0].clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let sessions = body["data"].as_array().unwrap();
+    assert_eq!(sessions.len(), 3);
+    assert_eq!(sessions.iter().filter(|s| s["current"] == true).count(), 1);
+    assert!(sessions.iter().all(|s| s["lastSeenAt"].is_string()));
+
+    // 撤销指定会话
+    let (_, _, body) = list(tokens[1].clone()).await;
+    let target = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["current"] == false)
+        .unwrap()["tokenHash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let response = request(
+        &app.app,
+        Method::DELETE,
+        &format!("/api/users/me/sessions/{target}"),
+        None,
+        &[("authorization", auth_header(&tokens[1]))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request(
+        &app.app,
+        Method::DELETE,
+        "/api/users/me/sessions/unknown-hash",
+        None,
+        &[("authorization", auth_header(&tokens[1]))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // 撤销其余全部会话，只保留当前会话
+    let response = request(
+        &app.app,
+        Method::DELETE,
+        "/api/users/me/sessions",
+        None,
+        &[("authorization", auth_header(&tokens[1]))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, _, body) = list(tokens[1].clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    for token in [&tokens[0], &tokens[2]] {
+        let (status, _, _) = list(token.clone()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn it_user_sessions_capture_device_info() {
+    let app = spawn_test_server().await;
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/auth/register",
+        Some(serde_json::json!({
+            "email": format!("device-{}@test.com", uuid::Uuid::new_v4()),
+            "username": format!("u{}", uuid::Uuid::new_v4().simple()),
+            "password": "Passw0rd!",
+        })),
+        &[
+            (
+                "user-agent",
+                "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0"
+                    .to_string(),
+            ),
+            ("x-forwarded-for", "203.0.113.7".to_string()),
+        ],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert!(status.is_success(), "{body}");
+    let token = body["data"]["accessToken"].as_str().unwrap().to_string();
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/users/me/sessions",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let session = &body["data"][0];
+    assert_eq!(session["device"], "Firefox (Linux)");
+    assert!(session["userAgent"].as_str().unwrap().contains("Firefox"));
+
+    let stored = app
+        .state
+        .store()
+        .get_session(session["tokenHash"].as_str().unwrap())
+        .unwrap()
+        .unwrap();
+    let ip_hash = stored.ip_hash.expect("ip hash stored");
+    assert!(!ip_hash.contains("203.0.113.7"));
+    assert_eq!(ip_hash.len(), 64);
+}
+
+#[tokio::test]
+async fn it_user_email_change_requires_verification() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let other_token = login_and_get_token(&app.app).await;
+    let me = |token: String| {
+        let app = app.app.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::GET,
+                "/api/users/me",
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await.2["data"].clone()
+        }
+    };
+    let original = me(token.clone()).await;
+    let other = me(other_token.clone()).await;
+
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/users/me/email",
+        Some(serde_json::json!({ "newEmail": other["email"], "currentPassword": "Passw0rd!" })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "AUTH_EMAIL_EXISTS");
+
+    let new_email = format!("changed-{}@test.com", uuid::Uuid::new_v4());
+    let response = request(
+        &app.app,
+        Method::POST,
+        "/api/users/me/email",
+        Some(serde_json::json!({ "newEmail": new_email, "currentPassword": "Passw0rd!" })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(me(token.clone()).await["email"], original["email"]);
+
+    // 邮件发送在精简构建中关闭，这里直接写入已知令牌模拟邮件中的链接
+    app.state
+        .store()
+        .create_email_change_token(
+            &hash_token("known-token"),
+            &EmailChangeEntry {
+                user_id: original["id"].as_str().unwrap().to_string(),
+                new_email: new_email.clone(),
+                expires_at: Utc::now() + Duration::hours(1),
+            },
+        )
+        .unwrap();
+
+    let verify = |token: String| {
+        let app = app.app.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::POST,
+                "/api/users/me/email/verify",
+                Some(serde_json::json!({ "token": "known-token" })),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, _) = verify(other_token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _, body) = verify(token.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["email"], new_email.as_str());
+    assert!(app
+        .state
+        .store()
+        .get_user_by_email(original["email"].as_str().unwrap())
+        .unwrap()
+        .is_none());
+
+    let (status, _, body) = verify(token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "USER_INVALID_EMAIL_TOKEN");
+}
+
+#[tokio::test]
+async fn it_user_weekly_reports_latest_by_default() {
+    use learning_backend::store::operations::weekly_reports::{
+        iso_week_label, parse_iso_week, WeeklyReport,
+    };
+
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let get = |path: &'static str| {
+        let app = app.app.clone();
+        let token = token.clone();
+        async move {
+            let response = request(
+                &app,
+                Method::GET,
+                path,
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(response).await
+        }
+    };
+
+    let (status, _, _) = get("/api/users/me/reports/weekly").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, _, me) = get("/api/users/me").await;
+    let user_id = me["data"]["id"].as_str().unwrap().to_string();
+    for week in ["2026-W09", "2026-W10"] {
+        let week_start = parse_iso_week(week).unwrap();
+        app.state
+            .store()
+            .upsert_weekly_report(&WeeklyReport {
+                user_id: user_id.clone(),
+                week: iso_week_label(week_start),
+                week_start,
+                week_end: week_start + Duration::days(6),
+                words_learned: 3,
+                total_answers: 5,
+                accuracy: 0.6,
+                accuracy_trend: Vec::new(),
+                streak_days: 2,
+                top_confusions: Vec::new(),
+                time_studied_ms: 4_000,
+                generated_at: Utc::now(),
+            })
+            .unwrap();
+    }
+
+    let (status, _, body) = get("/api/users/me/reports/weekly").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["week"], "2026-W10");
+
+    let (_, _, body) = get("/api/users/me/reports/weekly?week=2026-W09").await;
+    assert_eq!(body["data"]["weekStart"], "2026-02-23");
+
+    let (status, _, body) = get("/api/users/me/reports/weekly?week=2026-9").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_WEEK");
+
+    let (_, _, body) = get("/api/users/me/reports").await;
+    let weeks: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["week"].as_str().unwrap())
+        .collect();
+    assert_eq!(weeks, vec!["2026-W10", "2026-W09"]);
+}
+
+#[tokio::test]
+async fn it_user_data_export_streams_json_attachment() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/users/me/data-export",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, headers, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let disposition = headers["content-disposition"].to_str().unwrap();
+    assert!(disposition.starts_with("attachment;"));
+    assert!(body["user"]["email"].is_string());
+    assert!(body["user"].get("passwordHash").is_none());
+    assert!(body["records"].is_array());
+
+    let response = request(&app.app, Method::GET, "/api/users/me/data-export", None, &[]).await;
+    let (status, _, _) = response_json(response).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -2,8 +2,14 @@ mod common;
 
 use axum::http::{Method, StatusCode};
 
-use common::app::spawn_test_server;
+use learning_backend::config::{ModerationConfig, ModerationMode, WordUniqueness};
+use learning_backend::store::keys;
+use learning_backend::store::operations::words::Word;
+use learning_backend::workers;
+
+use common::app::{spawn_test_server, spawn_test_server_with_config, spawn_test_server_with_mock_llm};
 use common::auth::{auth_header, login_and_get_token, setup_admin_and_get_token};
+use common::fixtures::seed_words;
 use common::http::{request, response_json};
 
 #[tokio::test]
@@ -64,3 +70,817 @@ async fn it_word_list_large_per_page_is_clamped() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(body["data"]["perPage"].as_u64().unwrap(), 100);
 }
+
+#[tokio::test]
+async fn it_word_batch_get_keeps_order_and_skips_missing() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let words = seed_words(app.state.store(), 3);
+
+    let ids = [words[2].id.as_str(), "missing", words[0].id.as_str()];
+    let res = request(
+        &app.app,
+        Method::POST,
+        "/api/words/batch-get",
+        Some(serde_json::json!({ "ids": ids })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    let got: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(got, vec![words[2].id.as_str(), words[0].id.as_str()]);
+
+    let too_many: Vec<String> = (0..501).map(|i| format!("w-{i}")).collect();
+    let res = request(
+        &app.app,
+        Method::POST,
+        "/api/words/batch-get",
+        Some(serde_json::json!({ "ids": too_many })),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "BATCH_TOO_LARGE");
+}
+
+#[tokio::test]
+async fn it_words_filter_by_tags_pos_and_list_tag_counts() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let mut words = seed_words(app.state.store(), 3);
+    words[0].tags = vec!["TOEFL".to_string(), "basic".to_string()];
+    words[1].tags = vec!["basic".to_string()];
+    words[1].part_of_speech = Some("verb".to_string());
+    for word in &words[..2] {
+        app.state.store().upsert_word(word).unwrap();
+    }
+
+    let list = |query: &'static str| {
+        let app = app.app.clone();
+        let token = token.clone();
+        async move {
+            let res = request(
+                &app,
+                Method::GET,
+                &format!("/api/words?{query}"),
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await.2["data"].clone()
+        }
+    };
+    assert_eq!(list("tag=basic").await["total"], 2);
+    assert_eq!(list("tag=basic,TOEFL").await["total"], 1);
+    assert_eq!(list("tag=TOEFL,seed&tagMatch=any").await["total"], 2);
+    assert_eq!(list("tag=basic&search=word-1").await["data"][0]["id"], words[1].id);
+    // 未标注词性的单词不参与词性筛选
+    assert_eq!(list("pos=Verb").await["total"], 1);
+    assert_eq!(list("pos=noun").await["total"], 0);
+
+    let res = request(
+        &app.app,
+        Method::GET,
+        "/api/words/tags",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["data"],
+        serde_json::json!([
+            {"tag": "basic", "count": 2},
+            {"tag": "TOEFL", "count": 1},
+            {"tag": "seed", "count": 1},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn it_words_bulk_tags_report_per_word_results() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let words = seed_words(app.state.store(), 2);
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        "/api/words/tags/bulk",
+        Some(serde_json::json!({
+            "wordIds": [words[0].id, "missing", words[1].id],
+            "addTags": ["TOEFL"],
+            "removeTags": ["seed"],
+        })),
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["updated"], 2);
+    assert_eq!(body["data"]["failed"], 1);
+    assert_eq!(body["data"]["results"][0]["tags"], serde_json::json!(["TOEFL"]));
+    assert_eq!(body["data"]["results"][1]["success"], false);
+    assert_eq!(
+        app.state.store().list_word_tags().unwrap(),
+        vec![("TOEFL".to_string(), 2)]
+    );
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        "/api/words/tags/bulk",
+        Some(serde_json::json!({ "wordIds": [words[0].id] })),
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "WORDS_INVALID_PAYLOAD");
+}
+
+#[tokio::test]
+async fn it_words_duplicates_preview_and_merge() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let mut words = seed_words(app.state.store(), 3);
+    words[1].text = "WORD-0 ".to_string();
+    app.state.store().upsert_word(&words[1]).unwrap();
+
+    let res = request(
+        &app.app,
+        Method::GET,
+        "/api/words/duplicates",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["normalizedText"], "word-0");
+    let canonical = body["data"][0]["canonicalId"].as_str().unwrap().to_string();
+    let duplicate = body["data"][0]["words"][1]["id"].as_str().unwrap().to_string();
+
+    let merge = |payload: serde_json::Value| {
+        let app = app.app.clone();
+        let token = admin_token.clone();
+        async move {
+            let res = request(
+                &app,
+                Method::POST,
+                "/api/words/duplicates/merge",
+                Some(payload),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await
+        }
+    };
+    let (status, _, body) = merge(serde_json::json!({
+        "canonicalId": canonical,
+        "duplicateIds": [words[2].id],
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "WORDS_INVALID_PAYLOAD");
+
+    let (status, _, body) = merge(serde_json::json!({
+        "canonicalId": canonical,
+        "duplicateIds": [duplicate],
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["mergedIds"], serde_json::json!([duplicate]));
+    assert!(app.state.store().get_word(&duplicate).unwrap().is_none());
+
+    let (status, _, _) = merge(serde_json::json!({
+        "canonicalId": canonical,
+        "duplicateIds": [duplicate],
+    }))
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn it_words_global_uniqueness_rejects_homographs() {
+    let app = spawn_test_server_with_config(|c| c.word_uniqueness = WordUniqueness::Global).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let send = |method: Method, uri: &'static str, payload: serde_json::Value| {
+        let app = app.app.clone();
+        let token = admin_token.clone();
+        async move {
+            let res = request(
+                &app,
+                method,
+                uri,
+                Some(payload),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await
+        }
+    };
+
+    let (status, _, _) = send(
+        Method::POST,
+        "/api/words",
+        serde_json::json!({"text": "Apple", "meaning": "苹果"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _, body) = send(
+        Method::POST,
+        "/api/words",
+        serde_json::json!({"text": " apple ", "meaning": "苹果"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "WORD_TEXT_EXISTS");
+
+    let (status, _, body) = send(
+        Method::POST,
+        "/api/words/batch",
+        serde_json::json!({"words": [
+            {"text": "APPLE", "meaning": "苹果"},
+            {"text": "pear", "meaning": "梨"},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["data"]["skipped"], serde_json::json!([0]));
+    let pear_id = body["data"]["items"][0]["id"].as_str().unwrap().to_string();
+
+    let res = request(
+        &app.app,
+        Method::PUT,
+        &format!("/api/words/{pear_id}"),
+        Some(serde_json::json!({"text": "apple", "meaning": ""})),
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "WORD_TEXT_EXISTS");
+    assert_eq!(app.state.store().get_word(&pear_id).unwrap().unwrap().text, "pear");
+}
+
+#[tokio::test]
+async fn it_wordbook_uniqueness_skips_homographs_in_same_book() {
+    let app = spawn_test_server_with_config(|c| c.word_uniqueness = WordUniqueness::Wordbook).await;
+    let token = login_and_get_token(&app.app).await;
+    let mut words = seed_words(app.state.store(), 2);
+    words[1].text = "Word-0".to_string();
+    app.state.store().upsert_word(&words[1]).unwrap();
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        "/api/wordbooks",
+        Some(serde_json::json!({"name": "mine"})),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let book_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/wordbooks/{book_id}/words"),
+        Some(serde_json::json!({"wordIds": [words[0].id, words[1].id]})),
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["added"], 1);
+    assert_eq!(body["data"]["conflicts"], serde_json::json!([words[1].id]));
+}
+
+#[tokio::test]
+async fn it_content_related_words_merges_signals() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let store = app.state.store();
+    let mut words = seed_words(store, 4);
+    for (word, embedding) in words.iter_mut().zip([[1.0, 0.0], [0.9, 0.1], [0.0, 1.0]]) {
+        word.embedding = Some(embedding.to_vec());
+        store.upsert_word(word).unwrap();
+    }
+    for (word, morphemes) in [(&words[0], ["in", "spect"]), (&words[2], ["re", "spect"])] {
+        let value = serde_json::json!({
+            "wordId": word.id,
+            "morphemes": morphemes
+                .iter()
+                .map(|m| serde_json::json!({"text": m, "type": "root", "meaning": ""}))
+                .collect::<Vec<_>>(),
+        });
+        store
+            .word_morphemes
+            .insert(
+                keys::word_morpheme_key(&word.id).unwrap().as_bytes(),
+                serde_json::to_vec(&value).unwrap(),
+            )
+            .unwrap();
+    }
+    store
+        .confusion_pairs
+        .insert(
+            keys::confusion_pair_key(&words[0].id, &words[1].id)
+                .unwrap()
+                .as_bytes(),
+            serde_json::to_vec(&serde_json::json!({
+                "wordA": words[0].id,
+                "wordB": words[1].id,
+                "score": 0.5,
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+    let related = |word_id: String| {
+        let app = app.app.clone();
+        let token = token.clone();
+        async move {
+            let res = request(
+                &app,
+                Method::GET,
+                &format!("/api/content/related/{word_id}"),
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await
+        }
+    };
+
+    let (status, _, body) = related(words[0].id.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["degraded"], false);
+    let items = body["data"]["related"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["id"], words[1].id.as_str());
+    let types: Vec<&str> = items[0]["relations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["semantic", "confusion"]);
+    assert_eq!(items[1]["id"], words[2].id.as_str());
+    assert_eq!(items[1]["relations"][0]["type"], "morpheme");
+    assert_eq!(
+        items[1]["relations"][0]["sharedMorphemes"],
+        serde_json::json!(["spect"])
+    );
+
+    // 无词向量时降级为其余信号
+    let (status, _, body) = related(words[3].id.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["degraded"], true);
+    assert_eq!(body["data"]["total"], 0);
+}
+
+#[tokio::test]
+async fn it_word_contexts_cache_generated_examples() {
+    let app = spawn_test_server_with_mock_llm().await;
+    let token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let mut words = seed_words(app.state.store(), 1);
+    words[0].examples = vec!["Own sentence.".to_string()];
+    app.state.store().upsert_word(&words[0]).unwrap();
+    let word_id = words[0].id.clone();
+
+    let contexts = |method: Method, uri: String, token: String| {
+        let app = app.app.clone();
+        async move {
+            let res = request(
+                &app,
+                method,
+                &uri,
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await
+        }
+    };
+    let uri = format!("/api/content/word-contexts/{word_id}");
+
+    let (status, _, first) = contexts(Method::GET, uri.clone(), token.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = first["data"]["contexts"].as_array().unwrap();
+    assert_eq!(items[0]["source"], "word_examples");
+    assert_eq!(items.len(), 4);
+    assert!(items[1..].iter().all(|c| c["source"] == "generated"));
+    assert_eq!(first["data"]["generated"]["model"], "mock");
+
+    // 再次请求读取缓存，不会重新生成
+    let (_, _, second) = contexts(Method::GET, uri.clone(), token.clone()).await;
+    assert_eq!(
+        second["data"]["generated"]["generatedAt"],
+        first["data"]["generated"]["generatedAt"]
+    );
+
+    let regenerate = format!("{uri}/regenerate");
+    let (status, _, _) = contexts(Method::POST, regenerate.clone(), token.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, body) = contexts(Method::POST, regenerate, admin_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(
+        body["data"]["generated"]["generatedAt"],
+        first["data"]["generated"]["generatedAt"]
+    );
+
+    app.state.store().delete_word(&word_id).unwrap();
+    assert!(app
+        .state
+        .store()
+        .get_generated_examples(&word_id)
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn it_word_contexts_regenerate_requires_llm() {
+    let app = spawn_test_server().await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let words = seed_words(app.state.store(), 1);
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/content/word-contexts/{}/regenerate", words[0].id),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "LLM_UNAVAILABLE");
+}
+
+#[tokio::test]
+async fn it_word_contexts_reject_blocked_generated_examples() {
+    let app = spawn_test_server_with_config(|c| {
+        c.llm.enabled = true;
+        // mock 例句形如 `Example 1: "w" means ...`
+        c.moderation = ModerationConfig {
+            mode: ModerationMode::Wordlist,
+            blocked_terms: vec!["means".to_string()],
+        };
+    })
+    .await;
+    let token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let words = seed_words(app.state.store(), 1);
+    let word_id = words[0].id.clone();
+
+    // 第二次请求处于拒绝退避期，不再生成，也不会新增拒绝记录
+    for _ in 0..2 {
+        let res = request(
+            &app.app,
+            Method::GET,
+            &format!("/api/content/word-contexts/{word_id}"),
+            None,
+            &[("authorization", auth_header(&token))],
+        )
+        .await;
+        let (status, _, body) = response_json(res).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"]["generated"].is_null());
+    }
+    assert!(app
+        .state
+        .store()
+        .get_generated_examples(&word_id)
+        .unwrap()
+        .is_none());
+
+    let res = request(
+        &app.app,
+        Method::POST,
+        &format!("/api/content/word-contexts/{word_id}/regenerate"),
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "CONTENT_REJECTED");
+
+    let res = request(
+        &app.app,
+        Method::GET,
+        "/api/content/moderation/rejections",
+        None,
+        &[("authorization", auth_header(&admin_token))],
+    )
+    .await;
+    let (status, _, body) = response_json(res).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = body["data"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["kind"], "examples");
+    assert_eq!(items[0]["wordId"], word_id.as_str());
+    assert_eq!(items[0]["reason"], "blocked term: means");
+}
+
+#[tokio::test]
+async fn it_content_cache_invalidate_by_word_tag_and_all() {
+    let app = spawn_test_server_with_mock_llm().await;
+    let token = login_and_get_token(&app.app).await;
+    let admin_token = setup_admin_and_get_token(&app.app).await;
+    let mut words = seed_words(app.state.store(), 3);
+    words[2].tags = vec!["other".to_string()];
+    app.state.store().upsert_word(&words[2]).unwrap();
+
+    // 访问一次，缓存词源与例句
+    for word in &words {
+        for path in ["etymology", "word-contexts"] {
+            let res = request(
+                &app.app,
+                Method::GET,
+                &format!("/api/content/{path}/{}", word.id),
+                None,
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let invalidate = |body: serde_json::Value, token: String| {
+        let app = app.app.clone();
+        async move {
+            let res = request(
+                &app,
+                Method::POST,
+                "/api/content/cache/invalidate",
+                Some(body),
+                &[("authorization", auth_header(&token))],
+            )
+            .await;
+            response_json(res).await
+        }
+    };
+
+    let (status, _, _) = invalidate(serde_json::json!({ "all": true }), token.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = invalidate(
+        serde_json::json!({ "wordId": words[0].id, "all": true }),
+        admin_token.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _, body) = invalidate(
+        serde_json::json!({ "wordId": words[0].id, "kinds": ["examples"] }),
+        admin_token.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["invalidated"]["examples"], 1);
+    assert_eq!(body["data"]["invalidated"]["etymology"], 0);
+    assert!(app
+        .state
+        .store()
+        .get_generated_examples(&words[0].id)
+        .unwrap()
+        .is_none());
+
+    let (_, _, body) = invalidate(serde_json::json!({ "tag": "seed" }), admin_token.clone()).await;
+    assert_eq!(body["data"]["invalidated"]["etymology"], 2);
+    assert_eq!(body["data"]["invalidated"]["examples"], 1);
+    assert_eq!(body["data"]["total"], 3);
+
+    let (_, _, body) = invalidate(serde_json::json!({ "all": true }), admin_token).await;
+    assert_eq!(body["data"]["total"], 2);
+    assert!(app.state.store().etymologies.is_empty());
+}
+
+#[tokio::test]
+async fn it_content_limits_match_enforced_caps() {
+    let app = spawn_test_server().await;
+
+    let limits = request(&app.app, Method::GET, "/api/content/limits", None, &[]).await;
+    let (status, _, body) = response_json(limits).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["pagination"]["defaultPageSize"], 20);
+    assert_eq!(body["data"]["pagination"]["maxPageSize"], 100);
+    assert_eq!(body["data"]["batch"]["maxBatchSize"], 500);
+    assert_eq!(body["data"]["body"]["jsonBytes"], 64 * 1024);
+    assert_eq!(body["data"]["body"]["bulkBytes"], 2 * 1024 * 1024);
+}
+
+#[tokio::test]
+async fn it_large_responses_are_compressed_when_accepted() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    seed_words(app.state.store(), 50);
+
+    let gzip = request(
+        &app.app,
+        Method::GET,
+        "/api/words?page=1&perPage=50",
+        None,
+        &[
+            ("authorization", auth_header(&token)),
+            ("accept-encoding", "gzip".to_string()),
+        ],
+    )
+    .await;
+    assert_eq!(gzip.status(), StatusCode::OK);
+    assert_eq!(gzip.headers()["content-encoding"], "gzip");
+
+    let plain = request(
+        &app.app,
+        Method::GET,
+        "/api/words?page=1&perPage=50",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    assert!(plain.headers().get("content-encoding").is_none());
+
+    // 前端入口页同样压缩；低于阈值的小文件保持原样
+    let index = request(
+        &app.app,
+        Method::GET,
+        "/",
+        None,
+        &[("accept-encoding", "br".to_string())],
+    )
+    .await;
+    assert_eq!(index.status(), StatusCode::OK);
+    assert_eq!(index.headers()["content-encoding"], "br");
+    let small = request(
+        &app.app,
+        Method::GET,
+        "/theme-init.js",
+        None,
+        &[("accept-encoding", "gzip".to_string())],
+    )
+    .await;
+    assert_eq!(small.status(), StatusCode::OK);
+    assert!(small.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn it_content_clusters_group_words_by_embedding() {
+    let app = spawn_test_server().await;
+    let token = login_and_get_token(&app.app).await;
+    let store = app.state.store();
+
+    let seeds: [(&str, Option<Vec<f64>>); 5] = [
+        ("a1", Some(vec![0.0, 0.1])),
+        ("a2", Some(vec![0.1, 0.0])),
+        ("b1", Some(vec![5.0, 5.1])),
+        ("b2", Some(vec![5.1, 5.0])),
+        ("plain", None),
+    ];
+    for (id, embedding) in seeds {
+        store
+            .upsert_word(&Word {
+                id: id.to_string(),
+                text: format!("text-{id}"),
+                meaning: "m".to_string(),
+                pronunciation: None,
+                part_of_speech: None,
+                difficulty: 0.5,
+                examples: vec![],
+                tags: vec![],
+                embedding,
+                created_at: chrono::Utc::now(),
+            })
+            .unwrap();
+    }
+
+    workers::word_clustering::run(store).await;
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/content/clusters",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    let clusters = body["data"]["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 2);
+    for cluster in clusters {
+        let ids: Vec<&str> = cluster["wordIds"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[0][..1], ids[1][..1]);
+        assert!(!ids.contains(&"plain"));
+    }
+
+    let cluster_id = clusters[0]["id"].as_str().unwrap();
+    let response = request(
+        &app.app,
+        Method::GET,
+        &format!("/api/content/clusters/{cluster_id}"),
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["words"].as_array().unwrap().len(), 2);
+    assert_eq!(body["data"]["centroid"].as_array().unwrap().len(), 2);
+
+    let response = request(
+        &app.app,
+        Method::GET,
+        "/api/content/clusters/missing",
+        None,
+        &[("authorization", auth_header(&token))],
+    )
+    .await;
+    let (status, _, _) = response_json(response).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+async fn semantic_search(app: &axum::Router, token: &str) -> serde_json::Value {
+    let response = request(
+        app,
+        Method::GET,
+        "/api/content/semantic/search?query=apples&limit=2",
+        None,
+        &[("authorization", auth_header(token))],
+    )
+    .await;
+    let (status, _, body) = response_json(response).await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+#[tokio::test]
+async fn it_semantic_search_ranks_by_embedding_similarity() {
+    let app = spawn_test_server_with_mock_llm().await;
+    let token = login_and_get_token(&app.app).await;
+    let store = app.state.store();
+
+    for (idx, text) in ["apple", "applesauce", "zebra", "mountain", "river"]
+        .into_iter()
+        .enumerate()
+    {
+        store
+            .upsert_word(&Word {
+                id: format!("sem-{idx}"),
+                text: text.to_string(),
+                meaning: "m".to_string(),
+                pronunciation: None,
+                part_of_speech: None,
+                difficulty: 0.5,
+                examples: vec![],
+                tags: vec![],
+                embedding: None,
+                created_at: chrono::Utc::now(),
+            })
+            .unwrap();
+    }
+
+    // 尚无词向量时保持关键词降级
+    let body = semantic_search(&app.app, &token).await;
+    assert_eq!(body["data"]["method"], "keyword_fallback");
+
+    workers::embedding_generation::run(store, app.state.llm()).await;
+
+    let body = semantic_search(&app.app, &token).await;
+    assert_eq!(body["data"]["method"], "vector");
+    assert_eq!(body["data"]["index"], "brute_force");
+    assert_eq!(body["data"]["degraded"], false);
+    let results = body["data"]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    let texts: Vec<&str> = results
+        .iter()
+        .map(|r| r["text"].as_str().unwrap())
+        .collect();
+    assert!(texts.contains(&"apple") && texts.contains(&"applesauce"));
+    let first = results[0]["score"].as_f64().unwrap();
+    let second = results[1]["score"].as_f64().unwrap();
+    assert!(first >= second && second > 0.0);
+
+    workers::word_clustering::run(store).await;
+    let body = semantic_search(&app.app, &token).await;
+    assert_eq!(body["data"]["index"], "clustered");
+    assert!(!body["data"]["results"].as_array().unwrap().is_empty());
+}
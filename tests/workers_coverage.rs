@@ -9,7 +9,8 @@ use learning_backend::amas::engine::AMASEngine;
 use learning_backend::amas::memory::{evm, iad, mtp};
 use learning_backend::amas::metrics::MetricsRegistry;
 use learning_backend::amas::types::AlgorithmId;
-use learning_backend::config::Config;
+use learning_backend::config::{Config, LLMConfig, LlmBackend};
+use learning_backend::services::llm_provider::LlmProvider;
 use learning_backend::store::keys;
 use learning_backend::store::operations::records::LearningRecord;
 use learning_backend::store::operations::sessions::Session;
@@ -26,6 +27,19 @@ fn setup_store(db_name: &str) -> (tempfile::TempDir, Arc<Store>) {
     (temp_dir, store)
 }
 
+fn mock_llm() -> LlmProvider {
+    LlmProvider::new(&LLMConfig {
+        enabled: true,
+        provider: LlmBackend::Mock,
+        api_url: String::new(),
+        api_key: String::new(),
+        timeout_secs: 1,
+        models: Default::default(),
+        circuit: Default::default(),
+        budget: Default::default(),
+    })
+}
+
 fn sample_user(id: &str, email: &str) -> User {
     User {
         id: id.to_string(),
@@ -37,6 +51,7 @@ fn sample_user(id: &str, email: &str) -> User {
         updated_at: Utc::now(),
         failed_login_count: 0,
         locked_until: None,
+        lockout_level: 0,
     }
 }
 
@@ -76,6 +91,8 @@ fn sample_record(
         response_time_ms: 900,
         session_id: Some("session-1".to_string()),
         created_at,
+        algorithm_id: None,
+        algorithm_weights: Default::default(),
     }
 }
 
@@ -111,6 +128,10 @@ fn sample_session(
         created_at: Utc::now(),
         expires_at: Utc::now() + Duration::hours(expires_in_hours),
         revoked,
+        last_seen_at: None,
+        family_id: None,
+        user_agent: None,
+        ip_hash: None,
     }
 }
 
@@ -137,10 +158,9 @@ async fn it_worker_manager_registers_jobs_and_shutdowns() {
     assert!(jobs
         .iter()
         .any(|j| j.name == workers::WorkerName::MetricsFlush && j.enabled));
-    // MonitoringAggregate is stub-disabled by default
     assert!(jobs
         .iter()
-        .any(|j| j.name == workers::WorkerName::MonitoringAggregate && !j.enabled));
+        .any(|j| j.name == workers::WorkerName::MonitoringAggregate && j.enabled));
     // LlmAdvisor is configurable and enabled in this test
     assert!(jobs
         .iter()
@@ -179,7 +199,7 @@ async fn it_runs_worker_tasks_and_persists_side_effects() {
     let (_tmp, store) = setup_store("workers-side-effects.sled");
     let engine = Arc::new(AMASEngine::new(AMASConfig::default(), store.clone()));
 
-    workers::embedding_generation::run(store.as_ref()).await;
+    workers::embedding_generation::run(store.as_ref(), &mock_llm()).await;
 
     let user_1 = sample_user("u1", "u1@test.com");
     let user_2 = sample_user("u2", "u2@test.com");
@@ -311,19 +331,20 @@ async fn it_runs_worker_tasks_and_persists_side_effects() {
 
     workers::session_cleanup::run(store.as_ref()).await;
     workers::monitoring_aggregate::run(store.as_ref()).await;
-    workers::llm_advisor::run(store.as_ref()).await;
+    workers::llm_advisor::run(store.as_ref(), &engine, Some(&mock_llm())).await;
+    assert_eq!(store.list_llm_advisories(10).unwrap().len(), 1);
     workers::delayed_reward::run(store.as_ref()).await;
-    workers::forgetting_alert::run(store.as_ref()).await;
+    workers::forgetting_alert::run(store.as_ref(), Duration::hours(24)).await;
     workers::algorithm_optimization::run(store.as_ref(), &engine).await;
     workers::daily_aggregation::run(store.as_ref()).await;
     workers::health_analysis::run(store.as_ref()).await;
-    workers::etymology_generation::run(store.as_ref()).await;
-    workers::embedding_generation::run(store.as_ref()).await;
+    workers::etymology_generation::run(store.as_ref(), &mock_llm(), &Default::default()).await;
+    workers::embedding_generation::run(store.as_ref(), &mock_llm()).await;
     workers::word_clustering::run(store.as_ref()).await;
     workers::confusion_pair_cache::run(store.as_ref()).await;
     workers::weekly_report::run(store.as_ref()).await;
     workers::log_export::run(store.as_ref()).await;
-    workers::cache_cleanup::run(store.as_ref()).await;
+    workers::cache_cleanup::run(store.as_ref(), Duration::days(7)).await;
 
     assert!(store.get_session("expired").expect("get expired").is_none());
     assert!(store.get_session("revoked").expect("get revoked").is_none());
@@ -343,11 +364,26 @@ async fn it_runs_worker_tasks_and_persists_side_effects() {
         "forgetting alert should create notifications"
     );
 
-    assert!(store
-        .etymologies
-        .get(keys::etymology_key("w1").unwrap().as_bytes())
-        .expect("get etymology w1")
-        .is_some());
+    let etymology: serde_json::Value = serde_json::from_slice(
+        &store
+            .etymologies
+            .get(keys::etymology_key("w1").unwrap().as_bytes())
+            .expect("get etymology w1")
+            .expect("etymology w1 generated"),
+    )
+    .expect("etymology w1 json");
+    assert_eq!(etymology["source"], "llm");
+    assert_eq!(etymology["model"], "mock");
+    // 人工录入的词源不被覆盖
+    let curated: serde_json::Value = serde_json::from_slice(
+        &store
+            .etymologies
+            .get(keys::etymology_key("w2").unwrap().as_bytes())
+            .expect("get etymology w2")
+            .expect("etymology w2 kept"),
+    )
+    .expect("etymology w2 json");
+    assert_eq!(curated["etymology"], "pre-seeded");
 
     let confusion_key = keys::confusion_pair_key(&word_easy.id, &word_mid.id).unwrap();
     assert!(store
@@ -409,24 +445,33 @@ async fn forgetting_alert_is_deduplicated_across_consecutive_runs() {
         ))
         .expect("set overdue state");
 
-    workers::forgetting_alert::run(store.as_ref()).await;
-    workers::forgetting_alert::run(store.as_ref()).await;
-
-    let prefix = keys::notification_prefix(&user.id).expect("notification prefix");
-    let mut forgetting_alert_count = 0usize;
-
-    for item in store.notifications.scan_prefix(prefix.as_bytes()) {
-        let (_, value) = item.expect("scan notification");
-        let notif: serde_json::Value = serde_json::from_slice(&value).expect("parse notification");
-        let is_forgetting_alert =
-            notif.get("type").and_then(|t| t.as_str()) == Some("forgetting_alert");
-        let same_word = notif.get("wordId").and_then(|w| w.as_str()) == Some("word-overdue");
-        if is_forgetting_alert && same_word {
-            forgetting_alert_count += 1;
-        }
-    }
+    workers::forgetting_alert::run(store.as_ref(), Duration::hours(24)).await;
+    workers::forgetting_alert::run(store.as_ref(), Duration::hours(24)).await;
+
+    let (alerts, _) = store
+        .list_notifications(&user.id, 50, 0, false)
+        .expect("list notifications");
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].count, 1);
 
-    assert_eq!(forgetting_alert_count, 1);
+    // 新的到期单词合并进同一条未读提醒
+    store
+        .set_word_learning_state(&sample_word_state(
+            &user.id,
+            "word-overdue-2",
+            WordState::Learning,
+            Some(due_time),
+        ))
+        .expect("set second overdue state");
+    workers::forgetting_alert::run(store.as_ref(), Duration::hours(24)).await;
+
+    let (alerts, _) = store
+        .list_notifications(&user.id, 50, 0, false)
+        .expect("list notifications");
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].count, 2);
+    assert!(alerts[0].message.starts_with('2'));
+    assert!(alerts[0].updated_at.is_some());
 }
 
 #[test]
@@ -552,3 +597,111 @@ fn it_exercises_memory_models_and_metrics_registry() {
         .expect("swd metrics after manual reset");
     assert_eq!(swd_after_manual_reset.call_count, 0);
 }
+
+#[tokio::test]
+async fn it_store_compaction_flushes_populated_store() {
+    let (_tmp, store) = setup_store("store-compaction.sled");
+
+    for idx in 0..200 {
+        let word = sample_word(&format!("w{idx}"), &format!("word{idx}"), 0.5, None, vec![]);
+        store.upsert_word(&word).expect("upsert word");
+    }
+
+    let report = workers::store_compaction::compact(store.as_ref()).expect("compact store");
+    assert!(report.size_after > 0);
+
+    workers::store_compaction::run(store.as_ref()).await;
+    assert_eq!(store.count_words().expect("count words"), 200);
+}
+
+#[tokio::test]
+async fn embedding_generation_fills_missing_embeddings_with_mock_provider() {
+    let (_tmp, store) = setup_store("embedding-generation.sled");
+
+    for idx in 0..45 {
+        let word = sample_word(&format!("w{idx}"), &format!("word{idx}"), 0.5, None, vec![]);
+        store.upsert_word(&word).expect("upsert word");
+    }
+    let preset = sample_word("preset", "preset", 0.5, Some(vec![1.0, 0.0]), vec![]);
+    store.upsert_word(&preset).expect("upsert preset");
+
+    workers::embedding_generation::run(store.as_ref(), &mock_llm()).await;
+
+    assert!(store
+        .get_words_without_embedding(100)
+        .expect("words without embedding")
+        .is_empty());
+    let first = store.get_word("w0").expect("get w0").expect("w0 exists");
+    assert_eq!(first.embedding.as_ref().map(Vec::len), Some(64));
+    let preset = store
+        .get_word("preset")
+        .expect("get preset")
+        .expect("preset exists");
+    assert_eq!(preset.embedding, Some(vec![1.0, 0.0]));
+
+    // 相同文本生成相同向量
+    let rerun = sample_word("w0-copy", "word0", 0.5, None, vec![]);
+    store.upsert_word(&rerun).expect("upsert copy");
+    workers::embedding_generation::run(store.as_ref(), &mock_llm()).await;
+    let copy = store
+        .get_word("w0-copy")
+        .expect("get copy")
+        .expect("copy exists");
+    assert_eq!(copy.embedding, first.embedding);
+}
+
+#[tokio::test]
+async fn weekly_report_persists_previous_iso_week_per_user() {
+    use learning_backend::store::operations::weekly_reports::{iso_week_label, iso_week_start};
+
+    let (_tmp, store) = setup_store("weekly-report.sled");
+    let user = sample_user("u-weekly", "weekly@test.com");
+    store.create_user(&user).expect("create user");
+    store
+        .upsert_word(&sample_word("w1", "apple", 0.3, None, vec![]))
+        .expect("upsert w1");
+    store
+        .upsert_word(&sample_word("w2", "apply", 0.3, None, vec![]))
+        .expect("upsert w2");
+
+    let last_week = iso_week_start((Utc::now() - Duration::days(7)).date_naive());
+    let at = |day: i64, hour: u32| {
+        (last_week + Duration::days(day))
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+    };
+    for (id, word_id, is_correct, created_at) in [
+        ("r1", "w1", false, at(0, 9)),
+        ("r2", "w2", false, at(0, 10)),
+        ("r3", "w1", true, at(6, 9)),
+        ("r4", "w1", true, Utc::now()),
+    ] {
+        store
+            .create_record(&sample_record(
+                id, &user.id, word_id, is_correct, created_at,
+            ))
+            .expect("create record");
+    }
+
+    workers::weekly_report::run(store.as_ref()).await;
+
+    let report = store
+        .get_weekly_report(&user.id, &iso_week_label(last_week))
+        .expect("get weekly report")
+        .expect("weekly report exists");
+    assert_eq!(report.week_start, last_week);
+    assert_eq!(report.total_answers, 3);
+    assert_eq!(report.words_learned, 2);
+    assert_eq!(report.accuracy_trend.len(), 7);
+    assert_eq!(report.accuracy_trend[0].total, 2);
+    assert_eq!(report.accuracy_trend[6].accuracy, 1.0);
+    assert_eq!(report.streak_days, 1);
+    assert_eq!(report.time_studied_ms, 2700);
+    assert_eq!(report.top_confusions.len(), 1);
+    assert_eq!(report.top_confusions[0].word.as_deref(), Some("apple"));
+    assert_eq!(
+        report.top_confusions[0].confused_with.as_deref(),
+        Some("apply")
+    );
+}